@@ -1,6 +1,6 @@
 use clap::Args;
 
-use super::modes::{QDumpInspectionData, QWorkerMode};
+use super::modes::{QDumpInspectionData, QReportFormat, QWorkerMode};
 
 #[derive(Clone, Args)]
 pub struct RPCServerArgs {
@@ -47,6 +47,20 @@ pub struct OrchestratorArgs {
     pub db_path: String,
     #[clap(short, long, default_value = "dogeregtest", env)]
     pub network: String,
+    /// circuit fingerprint manifest generated by `gen-fingerprint-manifest`
+    #[clap(long, default_value = "fingerprints.json", env)]
+    pub fingerprint_manifest: String,
+}
+
+#[derive(Clone, Args)]
+pub struct GenFingerprintManifestArgs {
+    #[clap(short, long, default_value = "dogeregtest", env)]
+    pub network: String,
+    #[clap(short, long, default_value = "fingerprints.json")]
+    pub output: String,
+    /// must match the --fee-recipient-user-id of the workers
+    #[clap(long, default_value = "1", env)]
+    pub fee_recipient_user_id: u64,
 }
 
 #[derive(Clone, Args)]
@@ -61,11 +75,28 @@ pub struct L2WorkerArgs {
     #[clap(short, long, default_value = "dogeregtest", env)]
     pub network: String,
     
-    #[clap(long, short, default_value_t = QWorkerMode::All)]
+    /// all, no-groth16, only-groth16 or a comma separated list of capabilities (standard-proof, groth16-proof, signature-proof)
+    #[clap(long, short, default_value = "all")]
     pub worker_mode: QWorkerMode,
     
     #[clap(long, short, default_value = "0")]
     pub debug_mode: u32,
+
+    /// user credited with the sequencer fees, it is built into the transfer and withdrawal circuits
+    #[clap(long, default_value = "1", env)]
+    pub fee_recipient_user_id: u64,
+
+    /// defaults to <hostname>-<pid>
+    #[clap(long, env)]
+    pub worker_id: Option<String>,
+
+    /// how often the worker reports to the fleet registry (ms)
+    #[clap(long, default_value = "5000", env)]
+    pub heartbeat_interval: u64,
+
+    /// directory used to cache built circuits between restarts, keyed by network and circuit fingerprints
+    #[clap(long, env)]
+    pub circuit_cache_dir: Option<String>,
 }
 
 
@@ -101,6 +132,56 @@ pub struct InspectL2DumpArgs {
 }
 
 
+#[derive(Clone, Args)]
+pub struct WatchBlockArgs {
+    #[clap(long, default_value = "http://127.0.0.1:7777", env)]
+    pub api_server_address: String,
+
+    #[clap(long, short)]
+    pub checkpoint_id: u64,
+
+    /// refresh interval in milliseconds
+    #[clap(long, short, default_value = "2000")]
+    pub interval: u64,
+}
+
+#[derive(Clone, Args)]
+pub struct BenchReportArgs {
+    #[clap(
+        env,
+        long,
+        default_value = "redis://localhost:6379/0",
+        env
+    )]
+    pub redis_uri: String,
+
+    /// qbench output files to read the job benchmarks from instead of redis
+    #[clap(short, long, value_parser, num_args = 1.., value_delimiter = ' ')]
+    pub input: Vec<String>,
+
+    /// first and last checkpoint to report on, both are required when reading from redis
+    #[clap(long)]
+    pub checkpoint_start: Option<u64>,
+
+    #[clap(long)]
+    pub checkpoint_end: Option<u64>,
+
+    #[clap(short, long, default_value = "csv")]
+    pub format: QReportFormat,
+
+    /// defaults to stdout
+    #[clap(short, long)]
+    pub output: Option<String>,
+
+    /// a json report from a previous run to compare against
+    #[clap(short, long)]
+    pub baseline: Option<String>,
+
+    /// p50/p95 slowdown (in percent) reported as a regression when comparing against a baseline
+    #[clap(long, default_value = "10")]
+    pub regression_threshold: f64,
+}
+
 #[derive(Clone, Args)]
 pub struct QBenchArgs {
     #[clap(short, long, value_parser, num_args = 1.., value_delimiter = ' ')]
@@ -114,6 +195,10 @@ pub struct QBenchArgs {
     
     #[clap(long, short, default_value = "1")]
     pub num_iterations: u32,
+
+    /// Replays the measured job durations with this many workers to compare FIFO and critical path dispatch
+    #[clap(long)]
+    pub simulate_workers: Option<usize>,
 }
 
 
@@ -172,3 +257,39 @@ pub struct APIServerArgs {
     #[clap(short, env, long, default_value = "db", env)]
     pub db_path: String,
 }
+
+#[derive(Clone, Args)]
+pub struct SnapshotExportArgs {
+    #[clap(short, env, long, default_value = "db", env)]
+    pub db_path: String,
+
+    #[clap(long, short)]
+    pub checkpoint_id: u64,
+
+    #[clap(long, short)]
+    pub output: String,
+}
+
+#[derive(Clone, Args)]
+pub struct SnapshotImportArgs {
+    #[clap(long, short)]
+    pub input: String,
+
+    /// path of the new node's database, must not exist yet
+    #[clap(short, env, long, default_value = "db", env)]
+    pub db_path: String,
+
+    /// api server of a synced node, its getCityRoot is checked against the imported state
+    #[clap(long, default_value = "http://127.0.0.1:7777", env)]
+    pub api_server_address: String,
+}
+
+#[derive(Clone, Args)]
+pub struct MigrateArgs {
+    #[clap(short, env, long, default_value = "db", env)]
+    pub db_path: String,
+
+    /// run and verify the migrations without committing them
+    #[clap(long)]
+    pub dry_run: bool,
+}
@@ -8,9 +8,35 @@ pub const NETWORK_MAGIC_DOGE_REGTEST: u64 = 0x1337CF514544FF69u64;
 pub struct PrintCircuitInfoArgs {
     #[clap(short, long, default_value = "dogeregtest", env)]
     pub network: String,
+
+    /// load and store built circuits in this directory
+    #[clap(long, env)]
+    pub circuit_cache_dir: Option<String>,
 }
 #[derive(Clone, Args)]
 pub struct TreeProveTestArgs {
     #[clap(short, long, default_value = "dogeregtest", env)]
     pub network: String,
 }
+#[derive(Clone, Args)]
+pub struct GenSighashWhitelistArgs {
+    #[clap(
+        short,
+        long,
+        default_value = "city_rollup_common/src/config/sighash_wrapper_config.rs"
+    )]
+    pub output: String,
+    #[clap(long, default_value = "4")]
+    pub max_deposits: usize,
+    #[clap(long, default_value = "4")]
+    pub max_withdrawals: usize,
+    // sighash circuits spending forced withdrawal requests are only generated if this is non-zero
+    #[clap(long, default_value = "0")]
+    pub max_forced_withdrawal_requests: usize,
+    // number of threads used to build the sighash circuits, defaults to the available parallelism
+    #[clap(short, long)]
+    pub threads: Option<usize>,
+    // fail instead of writing if the committed constants differ from the generated whitelist
+    #[clap(long)]
+    pub check: bool,
+}
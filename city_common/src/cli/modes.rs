@@ -1,4 +1,7 @@
+use std::{fmt::Display, str::FromStr};
+
 use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 
 #[derive(
@@ -15,47 +18,138 @@ use serde_repr::{Deserialize_repr, Serialize_repr};
     ValueEnum,
 )]
 #[repr(u32)]
-pub enum QWorkerMode {
-    All = 0,
-    NoGroth16 = 1,
-    OnlyGroth16 = 2,
+pub enum QWorkerCapability {
+    StandardProof = 0,
+    Groth16Proof = 1,
+    SignatureProof = 2,
 }
-impl QWorkerMode {
+impl QWorkerCapability {
+    pub const ALL: [QWorkerCapability; 3] = [
+        QWorkerCapability::StandardProof,
+        QWorkerCapability::Groth16Proof,
+        QWorkerCapability::SignatureProof,
+    ];
     pub fn to_u32(&self) -> u32 {
         *self as u32
     }
-    pub fn is_groth16_enabled(&self) -> bool {
-        match self {
-            QWorkerMode::All => true,
-            QWorkerMode::NoGroth16 => false,
-            QWorkerMode::OnlyGroth16 => true,
+    pub fn to_flag(&self) -> u32 {
+        1u32 << self.to_u32()
+    }
+}
+impl From<QWorkerCapability> for u32 {
+    fn from(value: QWorkerCapability) -> u32 {
+        value as u32
+    }
+}
+impl TryFrom<u32> for QWorkerCapability {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(QWorkerCapability::StandardProof),
+            1 => Ok(QWorkerCapability::Groth16Proof),
+            2 => Ok(QWorkerCapability::SignatureProof),
+            _ => Err(anyhow::format_err!(
+                "Invalid QWorkerCapability value: {}",
+                value
+            )),
+        }
+    }
+}
+impl ToString for QWorkerCapability {
+    fn to_string(&self) -> String {
+        match *self {
+            QWorkerCapability::StandardProof => "standard-proof".to_string(),
+            QWorkerCapability::Groth16Proof => "groth16-proof".to_string(),
+            QWorkerCapability::SignatureProof => "signature-proof".to_string(),
         }
     }
 }
+
+// a set of QWorkerCapability flags
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy, Eq, Hash, PartialOrd, Ord)]
+#[serde(transparent)]
+pub struct QWorkerMode(u32);
+
+impl QWorkerMode {
+    pub const ALL: QWorkerMode = QWorkerMode(0b111);
+    pub const NO_GROTH16: QWorkerMode = QWorkerMode(0b101);
+    pub const ONLY_GROTH16: QWorkerMode = QWorkerMode(0b010);
+
+    pub fn new(capabilities: &[QWorkerCapability]) -> Self {
+        Self(capabilities.iter().fold(0, |acc, x| acc | x.to_flag()))
+    }
+    pub fn to_u32(&self) -> u32 {
+        self.0
+    }
+    pub fn has_capability(&self, capability: QWorkerCapability) -> bool {
+        self.0 & capability.to_flag() != 0
+    }
+    pub fn get_capabilities(&self) -> Vec<QWorkerCapability> {
+        QWorkerCapability::ALL
+            .into_iter()
+            .filter(|x| self.has_capability(*x))
+            .collect()
+    }
+    pub fn is_groth16_enabled(&self) -> bool {
+        self.has_capability(QWorkerCapability::Groth16Proof)
+    }
+}
 impl From<QWorkerMode> for u32 {
     fn from(value: QWorkerMode) -> u32 {
-        value as u32
+        value.0
     }
 }
 impl TryFrom<u32> for QWorkerMode {
     type Error = anyhow::Error;
 
     fn try_from(value: u32) -> Result<Self, Self::Error> {
-        match value {
-            0 => Ok(QWorkerMode::All),
-            1 => Ok(QWorkerMode::NoGroth16),
-            2 => Ok(QWorkerMode::OnlyGroth16),
-            _ => Err(anyhow::format_err!("Invalid QWorkerMode value: {}", value)),
+        if value == 0 || (value & !QWorkerMode::ALL.0) != 0 {
+            Err(anyhow::format_err!("Invalid QWorkerMode value: {}", value))
+        } else {
+            Ok(QWorkerMode(value))
         }
     }
 }
 
-impl ToString for QWorkerMode {
-    fn to_string(&self) -> String {
+impl FromStr for QWorkerMode {
+    type Err = anyhow::Error;
+
+    // accepts one of the presets (all, no-groth16, only-groth16) or a comma separated list of capabilities
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "all" => Ok(QWorkerMode::ALL),
+            "no-groth16" => Ok(QWorkerMode::NO_GROTH16),
+            "only-groth16" => Ok(QWorkerMode::ONLY_GROTH16),
+            _ => {
+                let capabilities = s
+                    .split(',')
+                    .map(|x| {
+                        <QWorkerCapability as ValueEnum>::from_str(x.trim(), true)
+                            .map_err(|err| anyhow::format_err!("Invalid QWorkerMode: {}", err))
+                    })
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+                QWorkerMode::new(&capabilities).to_u32().try_into()
+            }
+        }
+    }
+}
+
+impl Display for QWorkerMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match *self {
-            QWorkerMode::All => "all".to_string(),
-            QWorkerMode::NoGroth16 => "no-groth16".to_string(),
-            QWorkerMode::OnlyGroth16 => "only-groth16".to_string(),
+            QWorkerMode::ALL => write!(f, "all"),
+            QWorkerMode::NO_GROTH16 => write!(f, "no-groth16"),
+            QWorkerMode::ONLY_GROTH16 => write!(f, "only-groth16"),
+            _ => write!(
+                f,
+                "{}",
+                self.get_capabilities()
+                    .iter()
+                    .map(|x| x.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
         }
     }
 }
@@ -123,3 +217,72 @@ impl ToString for QDumpInspectionData {
         }
     }
 }
+
+#[derive(
+    Serialize_repr,
+    Deserialize_repr,
+    PartialEq,
+    Debug,
+    Clone,
+    Copy,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    ValueEnum,
+)]
+#[repr(u32)]
+pub enum QReportFormat {
+    Csv = 0,
+    Json = 1,
+}
+impl QReportFormat {
+    pub fn to_u32(&self) -> u32 {
+        *self as u32
+    }
+}
+impl TryFrom<u32> for QReportFormat {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(QReportFormat::Csv),
+            1 => Ok(QReportFormat::Json),
+            _ => Err(anyhow::format_err!("Invalid QReportFormat value: {}", value)),
+        }
+    }
+}
+impl ToString for QReportFormat {
+    fn to_string(&self) -> String {
+        match *self {
+            QReportFormat::Csv => "csv".to_string(),
+            QReportFormat::Json => "json".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::{QWorkerCapability, QWorkerMode};
+
+    #[test]
+    fn test_parse_worker_mode() {
+        assert_eq!(QWorkerMode::from_str("all").unwrap(), QWorkerMode::ALL);
+        assert_eq!(
+            QWorkerMode::from_str("standard-proof,signature-proof").unwrap(),
+            QWorkerMode::NO_GROTH16
+        );
+        let signature_only = QWorkerMode::from_str("signature-proof").unwrap();
+        assert!(signature_only.has_capability(QWorkerCapability::SignatureProof));
+        assert!(!signature_only.is_groth16_enabled());
+        assert_eq!(signature_only.to_string(), "signature-proof");
+        assert_eq!(
+            QWorkerMode::from_str(&QWorkerMode::ONLY_GROTH16.to_string()).unwrap(),
+            QWorkerMode::ONLY_GROTH16
+        );
+        assert!(QWorkerMode::from_str("").is_err());
+        assert!(QWorkerMode::try_from(0).is_err());
+    }
+}
@@ -1,8 +1,24 @@
 pub const GLOBAL_USER_TREE_HEIGHT: u8 = 32;
 pub const L1_DEPOSIT_TREE_HEIGHT: u8 = 32;
 pub const L1_WITHDRAWAL_TREE_HEIGHT: u8 = 32;
+pub const USER_PUBLIC_KEY_TREE_HEIGHT: u8 = 32;
+// token ids are the user ids of their issuers (< 2^31), the registry tree is indexed by token id
+pub const TOKEN_REGISTRY_TREE_HEIGHT: u8 = 31;
+// the balance tree is indexed by (user_id << TOKEN_REGISTRY_TREE_HEIGHT) + token_id
+pub const TOKEN_BALANCE_TREE_HEIGHT: u8 = 62;
+// the left leaf of this user holds hash(token registry root, token balance root), so the token state
+// is committed in the user tree root. it is written at genesis and the user can never be registered
+pub const TOKEN_STATE_USER_ID: u64 = 0;
+pub const FIRST_USER_ID: u64 = 1;
 pub const BALANCE_BIT_SIZE: usize = 64; //56;
 pub const NONCE_BIT_SIZE: usize = 64; //56;
 pub const WITHDRAWAL_FEE_AMOUNT: u64 = 100000;
 pub const BLOCK_SCRIPT_SPEND_BASE_FEE_AMOUNT: u64 = 80000000;
 pub const DEPOSIT_FEE_AMOUNT: u64 = 100000;
+// transfer and withdrawal fees are credited to the fee recipient in the same block (must be a registered
+// user). the recipient is a circuit parameter, this is the default used when none is configured
+pub const DEFAULT_SEQUENCER_FEE_RECIPIENT_USER_ID: u64 = FIRST_USER_ID;
+// maximum number of recipients in a single batched L2 transfer (unused slots are padded with zero-value transfers)
+pub const L2_BATCH_TRANSFER_MAX_RECIPIENTS: usize = 4;
+// minimum value of the deposit address output of a forced withdrawal request (credited back as a deposit to the requester)
+pub const FORCED_WITHDRAWAL_REQUEST_MIN_VALUE: u64 = 1000000;
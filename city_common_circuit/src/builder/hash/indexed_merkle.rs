@@ -0,0 +1,414 @@
+use city_crypto::hash::merkle::indexed::{
+    IndexedMerkleLeaf, IndexedMerkleMembershipProof, IndexedMerkleNonMembershipProof,
+};
+use plonky2::{
+    field::{extension::Extendable, types::Field},
+    hash::hash_types::{HashOut, HashOutTarget, RichField},
+    iop::{
+        target::{BoolTarget, Target},
+        witness::{PartialWitness, WitnessWrite},
+    },
+    plonk::{circuit_builder::CircuitBuilder, config::AlgebraicHasher},
+};
+
+use crate::{
+    builder::select::CircuitBuilderSelectHelpers,
+    hash::merkle::gadgets::merkle_proof::{MerkleProofGadget, OptionalMerkleProofGadget},
+    u32::gadgets::multiple_comparison::list_lte_circuit,
+};
+
+pub trait CircuitBuilderIndexedMerkle<F: RichField + Extendable<D>, const D: usize> {
+    // the canonical u32 limbs of the key, least significant first
+    fn indexed_merkle_key_to_u32_limbs(&mut self, key: HashOutTarget) -> Vec<Target>;
+    fn is_indexed_merkle_key_lte(&mut self, a: HashOutTarget, b: HashOutTarget) -> BoolTarget;
+    fn is_indexed_merkle_key_lt(&mut self, a: HashOutTarget, b: HashOutTarget) -> BoolTarget;
+    fn is_indexed_merkle_low_leaf(
+        &mut self,
+        low_leaf: &IndexedMerkleLeafTarget,
+        key: HashOutTarget,
+    ) -> BoolTarget;
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilderIndexedMerkle<F, D>
+    for CircuitBuilder<F, D>
+{
+    fn indexed_merkle_key_to_u32_limbs(&mut self, key: HashOutTarget) -> Vec<Target> {
+        let max_high = self.constant(F::from_canonical_u32(u32::MAX));
+        let mut limbs = Vec::with_capacity(8);
+        for element in key.elements {
+            let (low, high) = self.split_low_high(element, 32, 64);
+            // x + p also fits in 64 bits if x < 2^32 - 1, its high limb is 2^32 - 1 and its low
+            // limb is non zero, which no canonical value has
+            let high_is_max = self.is_equal(high, max_high);
+            let non_canonical = self.mul(high_is_max.target, low);
+            self.assert_zero(non_canonical);
+            limbs.push(low);
+            limbs.push(high);
+        }
+        limbs
+    }
+
+    fn is_indexed_merkle_key_lte(&mut self, a: HashOutTarget, b: HashOutTarget) -> BoolTarget {
+        let a_limbs = self.indexed_merkle_key_to_u32_limbs(a);
+        let b_limbs = self.indexed_merkle_key_to_u32_limbs(b);
+        list_lte_circuit(self, a_limbs, b_limbs, 32)
+    }
+
+    fn is_indexed_merkle_key_lt(&mut self, a: HashOutTarget, b: HashOutTarget) -> BoolTarget {
+        let lte = self.is_indexed_merkle_key_lte(a, b);
+        let equal = self.is_equal_hash(a, b);
+        let not_equal = self.not(equal);
+        self.and(lte, not_equal)
+    }
+
+    fn is_indexed_merkle_low_leaf(
+        &mut self,
+        low_leaf: &IndexedMerkleLeafTarget,
+        key: HashOutTarget,
+    ) -> BoolTarget {
+        let zero_hash = self.constant_hash(HashOut::ZERO);
+        let key_is_zero = self.is_equal_hash(key, zero_hash);
+        let key_is_non_zero = self.not(key_is_zero);
+
+        let above_low_key = self.is_indexed_merkle_key_lt(low_leaf.key, key);
+        let below_next_key = self.is_indexed_merkle_key_lt(key, low_leaf.next_key);
+        let is_last = self.is_equal_hash(low_leaf.next_key, zero_hash);
+        let below_upper_bound = self.or(is_last, below_next_key);
+
+        let in_range = self.and(above_low_key, below_upper_bound);
+        self.and(key_is_non_zero, in_range)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct IndexedMerkleLeafTarget {
+    pub key: HashOutTarget,
+    pub next_key: HashOutTarget,
+    pub next_index: Target,
+}
+
+impl IndexedMerkleLeafTarget {
+    pub fn add_virtual_to<F: RichField + Extendable<D>, const D: usize>(
+        builder: &mut CircuitBuilder<F, D>,
+    ) -> Self {
+        Self {
+            key: builder.add_virtual_hash(),
+            next_key: builder.add_virtual_hash(),
+            next_index: builder.add_virtual_target(),
+        }
+    }
+    pub fn hash<H: AlgebraicHasher<F>, F: RichField + Extendable<D>, const D: usize>(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+    ) -> HashOutTarget {
+        builder.hash_n_to_hash_no_pad::<H>(vec![
+            self.key.elements[0],
+            self.key.elements[1],
+            self.key.elements[2],
+            self.key.elements[3],
+            self.next_key.elements[0],
+            self.next_key.elements[1],
+            self.next_key.elements[2],
+            self.next_key.elements[3],
+            self.next_index,
+        ])
+    }
+    pub fn set_witness<F: RichField>(
+        &self,
+        witness: &mut PartialWitness<F>,
+        leaf: &IndexedMerkleLeaf<F>,
+    ) {
+        witness.set_hash_target(self.key, leaf.key.0);
+        witness.set_hash_target(self.next_key, leaf.next_key.0);
+        witness.set_target(self.next_index, F::from_canonical_u64(leaf.next_index));
+    }
+}
+
+fn add_virtual_leaf_proof<H: AlgebraicHasher<F>, F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    height: usize,
+) -> (IndexedMerkleLeafTarget, MerkleProofGadget) {
+    let leaf = IndexedMerkleLeafTarget::add_virtual_to(builder);
+    let leaf_hash = leaf.hash::<H, F, D>(builder);
+    let proof = MerkleProofGadget::add_virtual_to_with_options::<H, F, D>(
+        builder,
+        height,
+        OptionalMerkleProofGadget {
+            root: None,
+            value: Some(leaf_hash),
+            index: None,
+            siblings: None,
+        },
+    );
+    (leaf, proof)
+}
+
+#[derive(Debug, Clone)]
+pub struct IndexedMerkleMembershipProofGadget {
+    pub leaf: IndexedMerkleLeafTarget,
+    pub proof: MerkleProofGadget,
+}
+
+impl IndexedMerkleMembershipProofGadget {
+    pub fn add_virtual_to<H: AlgebraicHasher<F>, F: RichField + Extendable<D>, const D: usize>(
+        builder: &mut CircuitBuilder<F, D>,
+        height: usize,
+    ) -> Self {
+        let (leaf, proof) = add_virtual_leaf_proof::<H, F, D>(builder, height);
+        // the sentinel leaf does not hold a key
+        let zero_hash = builder.constant_hash(HashOut::ZERO);
+        let key_is_zero = builder.is_equal_hash(leaf.key, zero_hash);
+        builder.assert_zero(key_is_zero.target);
+        Self { leaf, proof }
+    }
+    pub fn key(&self) -> HashOutTarget {
+        self.leaf.key
+    }
+    pub fn root(&self) -> HashOutTarget {
+        self.proof.root
+    }
+    pub fn set_witness<F: RichField>(
+        &self,
+        witness: &mut PartialWitness<F>,
+        input: &IndexedMerkleMembershipProof<F>,
+    ) {
+        self.leaf.set_witness(witness, &input.leaf);
+        self.proof.set_witness(
+            witness,
+            F::from_canonical_u64(input.proof.index),
+            input.proof.value,
+            &input.proof.siblings,
+        );
+    }
+}
+
+/// Proves that `key` is not in the indexed merkle tree with root `root()`.
+#[derive(Debug, Clone)]
+pub struct IndexedMerkleNonMembershipProofGadget {
+    pub key: HashOutTarget,
+    pub low_leaf: IndexedMerkleLeafTarget,
+    pub low_leaf_proof: MerkleProofGadget,
+    // false if the key is computed by the circuit and not set as part of the witness
+    pub key_is_virtual: bool,
+}
+
+impl IndexedMerkleNonMembershipProofGadget {
+    pub fn add_virtual_to<H: AlgebraicHasher<F>, F: RichField + Extendable<D>, const D: usize>(
+        builder: &mut CircuitBuilder<F, D>,
+        height: usize,
+    ) -> Self {
+        let key = builder.add_virtual_hash();
+        Self {
+            key_is_virtual: true,
+            ..Self::add_virtual_to_with_key::<H, F, D>(builder, height, key)
+        }
+    }
+    pub fn add_virtual_to_with_key<
+        H: AlgebraicHasher<F>,
+        F: RichField + Extendable<D>,
+        const D: usize,
+    >(
+        builder: &mut CircuitBuilder<F, D>,
+        height: usize,
+        key: HashOutTarget,
+    ) -> Self {
+        let (low_leaf, low_leaf_proof) = add_virtual_leaf_proof::<H, F, D>(builder, height);
+        let is_low_leaf = builder.is_indexed_merkle_low_leaf(&low_leaf, key);
+        builder.assert_one(is_low_leaf.target);
+        Self {
+            key,
+            low_leaf,
+            low_leaf_proof,
+            key_is_virtual: false,
+        }
+    }
+    pub fn root(&self) -> HashOutTarget {
+        self.low_leaf_proof.root
+    }
+    pub fn set_witness<F: RichField>(
+        &self,
+        witness: &mut PartialWitness<F>,
+        input: &IndexedMerkleNonMembershipProof<F>,
+    ) {
+        if self.key_is_virtual {
+            witness.set_hash_target(self.key, input.key.0);
+        }
+        self.low_leaf.set_witness(witness, &input.low_leaf);
+        self.low_leaf_proof.set_witness(
+            witness,
+            F::from_canonical_u64(input.low_leaf_proof.index),
+            input.low_leaf_proof.value,
+            &input.low_leaf_proof.siblings,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use city_crypto::hash::{
+        merkle::indexed::{compare_indexed_merkle_keys, IndexedMerkleTree},
+        qhashout::QHashOut,
+    };
+    use plonky2::{
+        field::types::PrimeField64,
+        hash::poseidon::PoseidonHash,
+        plonk::{
+            circuit_data::CircuitConfig,
+            config::{GenericConfig, PoseidonGoldilocksConfig},
+        },
+    };
+
+    use super::*;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type H = PoseidonHash;
+
+    const HEIGHT: u8 = 4;
+
+    fn key(a: u64, b: u64) -> QHashOut<F> {
+        QHashOut::from_values(a, 0, 0, b)
+    }
+
+    fn build_tree() -> IndexedMerkleTree<F, H> {
+        let mut tree = IndexedMerkleTree::<F, H>::new(HEIGHT);
+        for k in [
+            key(10, 1),
+            key(20, 1),
+            key(5, 2),
+            key(F::NEG_ONE.to_canonical_u64(), 1),
+        ] {
+            tree.insert(k).unwrap();
+        }
+        tree
+    }
+
+    #[test]
+    fn key_comparison_matches_native() {
+        let p_minus_one = F::NEG_ONE.to_canonical_u64();
+        let keys = [
+            QHashOut::ZERO,
+            key(1, 0),
+            key(0, 1),
+            key(p_minus_one, 0),
+            key(p_minus_one, 1),
+            key(1u64 << 32, 1),
+            key((1u64 << 32) - 1, 1),
+            QHashOut::from_values(0, p_minus_one, 0, 1),
+            QHashOut::from_values(0, 0, 1, 1),
+        ];
+        let mut pairs = Vec::new();
+        for a in keys.iter() {
+            for b in keys.iter() {
+                pairs.push((*a, *b));
+            }
+        }
+
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let targets = pairs
+            .iter()
+            .map(|_| {
+                let a = builder.add_virtual_hash();
+                let b = builder.add_virtual_hash();
+                let lt = builder.is_indexed_merkle_key_lt(a, b);
+                let lte = builder.is_indexed_merkle_key_lte(a, b);
+                builder.register_public_input(lt.target);
+                builder.register_public_input(lte.target);
+                (a, b)
+            })
+            .collect::<Vec<_>>();
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        for ((a, b), (a_value, b_value)) in targets.iter().zip(pairs.iter()) {
+            pw.set_hash_target(*a, a_value.0);
+            pw.set_hash_target(*b, b_value.0);
+        }
+        let proof = data.prove(pw).unwrap();
+        for (i, (a, b)) in pairs.iter().enumerate() {
+            let ordering = compare_indexed_merkle_keys(a, b);
+            assert_eq!(
+                proof.public_inputs[2 * i],
+                F::from_bool(ordering == Ordering::Less)
+            );
+            assert_eq!(
+                proof.public_inputs[2 * i + 1],
+                F::from_bool(ordering != Ordering::Greater)
+            );
+        }
+        data.verify(proof).unwrap();
+    }
+
+    #[test]
+    fn non_membership_gadget_matches_native() {
+        let tree = build_tree();
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let gadget = IndexedMerkleNonMembershipProofGadget::add_virtual_to::<H, F, D>(
+            &mut builder,
+            HEIGHT as usize,
+        );
+        builder.register_public_inputs(&gadget.key.elements);
+        builder.register_public_inputs(&gadget.root().elements);
+        let data = builder.build::<C>();
+
+        for absent in [key(1, 0), key(15, 1), key(4, 2), key(0, 3)] {
+            let native_proof = tree.get_non_membership_proof(&absent).unwrap();
+            assert!(native_proof.verify::<H>());
+
+            let mut pw = PartialWitness::new();
+            gadget.set_witness(&mut pw, &native_proof);
+            let proof = data.prove(pw).unwrap();
+            assert_eq!(proof.public_inputs[0..4], absent.0.elements);
+            assert_eq!(proof.public_inputs[4..8], tree.root().0.elements);
+            data.verify(proof).unwrap();
+        }
+    }
+
+    #[test]
+    fn membership_gadget_matches_native() {
+        let tree = build_tree();
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let gadget = IndexedMerkleMembershipProofGadget::add_virtual_to::<H, F, D>(
+            &mut builder,
+            HEIGHT as usize,
+        );
+        builder.register_public_inputs(&gadget.key().elements);
+        builder.register_public_inputs(&gadget.root().elements);
+        let data = builder.build::<C>();
+
+        for present in [key(10, 1), key(5, 2)] {
+            let native_proof = tree.get_membership_proof(&present).unwrap();
+            let mut pw = PartialWitness::new();
+            gadget.set_witness(&mut pw, &native_proof);
+            let proof = data.prove(pw).unwrap();
+            assert_eq!(proof.public_inputs[0..4], present.0.elements);
+            assert_eq!(proof.public_inputs[4..8], tree.root().0.elements);
+            data.verify(proof).unwrap();
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "was set twice with different values")]
+    fn non_membership_gadget_rejects_present_key() {
+        let tree = build_tree();
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let gadget = IndexedMerkleNonMembershipProofGadget::add_virtual_to::<H, F, D>(
+            &mut builder,
+            HEIGHT as usize,
+        );
+        let data = builder.build::<C>();
+
+        // reuse the low leaf of a neighbouring absent key for a key which is in the tree
+        let mut forged = tree.get_non_membership_proof(&key(15, 1)).unwrap();
+        forged.key = key(20, 1);
+        assert!(!forged.verify::<H>());
+
+        let mut pw = PartialWitness::new();
+        gadget.set_witness(&mut pw, &forged);
+        let proof = data.prove(pw).unwrap();
+        data.verify(proof).unwrap();
+    }
+}
@@ -1,4 +1,5 @@
 pub mod core;
+pub mod indexed_merkle;
 pub mod ripemd160;
 pub mod sha256;
 pub mod sha256_truncated;
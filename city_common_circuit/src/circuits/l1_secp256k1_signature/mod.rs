@@ -19,6 +19,7 @@ use crate::{
 };
 
 use super::traits::qstandard::QStandardCircuit;
+use crate::serialization::circuit_cache::CircuitBuilderCachedBuild;
 
 #[derive(Debug)]
 pub struct L1Secp256K1SignatureCircuit<C: GenericConfig<D> + 'static, const D: usize>
@@ -48,7 +49,7 @@ where
             DogeQEDSignatureGadget::add_virtual_to::<C::Hasher, C::F, D>(&mut builder);
 
         builder.register_public_inputs(&signature_gadget.combined_hash.elements);
-        let circuit_data = builder.build::<C>();
+        let circuit_data = builder.build_cached::<C>("l1_secp256k1_signature");
 
         let minifier_chain =
             QEDProofMinifierDynamicChain::<D, C::F, C>::new_with_dynamic_constant_verifier(
@@ -20,6 +20,7 @@ use crate::{
 };
 
 use super::{traits::qstandard::QStandardCircuit, zk_signature::ZKSignatureCircuit};
+use crate::serialization::circuit_cache::CircuitBuilderCachedBuild;
 
 #[derive(Debug)]
 pub struct ZKSignatureWrapperCircuit<C: GenericConfig<D> + 'static, const D: usize>
@@ -32,7 +33,7 @@ where
     pub circuit_data: CircuitData<C::F, C, D>,
     pub fingerprint: QHashOut<C::F>,
 }
-impl<C: GenericConfig<D>, const D: usize> Clone for ZKSignatureWrapperCircuit<C, D>
+impl<C: GenericConfig<D> + 'static, const D: usize> Clone for ZKSignatureWrapperCircuit<C, D>
 where
     C::Hasher: AlgebraicHasher<C::F>,
 {
@@ -40,7 +41,7 @@ where
         Self::new()
     }
 }
-impl<C: GenericConfig<D>, const D: usize> ZKSignatureWrapperCircuit<C, D>
+impl<C: GenericConfig<D> + 'static, const D: usize> ZKSignatureWrapperCircuit<C, D>
 where
     C::Hasher: AlgebraicHasher<C::F>,
 {
@@ -91,7 +92,7 @@ where
         builder.register_public_inputs(&fingerprint_target.elements);
         builder.register_public_inputs(&action_hash.elements);
 
-        let circuit_data = builder.build::<C>();
+        let circuit_data = builder.build_cached::<C>("zk_signature_wrapper");
 
         let fingerprint = QHashOut(get_circuit_fingerprint_generic(&circuit_data.verifier_only));
 
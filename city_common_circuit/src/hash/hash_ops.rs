@@ -127,7 +127,7 @@ pub fn split_le_no_drain<F: RichField + Extendable<D>, const D: usize>(
 }
 
 #[derive(Debug)]
-struct WireSplitGenerator {
+pub(crate) struct WireSplitGenerator {
     integer: Target,
     gates: Vec<usize>,
     num_limbs: usize,
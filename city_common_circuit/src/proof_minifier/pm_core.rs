@@ -14,6 +14,7 @@ use plonky2::{
 };
 
 use super::pm_custom::PMCircuitCustomizer;
+use crate::serialization::circuit_cache::CircuitBuilderCachedBuild;
 
 pub fn get_circuit_fingerprint_generic<
     const D: usize,
@@ -93,7 +94,7 @@ where
             });
         }
 
-        let circuit_data = builder.build::<C>();
+        let circuit_data = builder.build_cached::<C>("proof_minifier");
 
         let circuit_fingerprint = get_circuit_fingerprint_generic(&circuit_data.verifier_only);
 
@@ -130,7 +131,7 @@ where
             customizer.unwrap().augment_circuit(&mut builder);
         }
 
-        let circuit_data = builder.build::<C>();
+        let circuit_data = builder.build_cached::<C>("proof_minifier");
 
         let circuit_fingerprint = get_circuit_fingerprint_generic(&circuit_data.verifier_only);
 
@@ -19,6 +19,7 @@ use plonky2::{
 use crate::builder::verify::CircuitBuilderVerifyProofHelpers;
 
 use super::{pm_core::get_circuit_fingerprint_generic, pm_custom::PMCircuitCustomizer};
+use crate::serialization::circuit_cache::CircuitBuilderCachedBuild;
 
 #[derive(Debug)]
 pub struct QEDProofMinifierDynamic<
@@ -105,7 +106,7 @@ where
             });
         }
 
-        let circuit_data = builder.build::<C>();
+        let circuit_data = builder.build_cached::<C>("proof_minifier_dynamic");
 
         let circuit_fingerprint = get_circuit_fingerprint_generic(&circuit_data.verifier_only);
 
@@ -158,7 +159,7 @@ where
             customizer.unwrap().augment_circuit(&mut builder);
         }
 
-        let circuit_data = builder.build::<C>();
+        let circuit_data = builder.build_cached::<C>("proof_minifier_dynamic");
 
         let circuit_fingerprint = get_circuit_fingerprint_generic(&circuit_data.verifier_only);
 
@@ -0,0 +1,564 @@
+use std::{
+    cell::RefCell,
+    ffi::OsStr,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use city_crypto::hash::core::sha256::CoreSha256Hasher;
+use plonky2::{
+    field::{extension::Extendable, types::PrimeField64},
+    hash::hash_types::RichField,
+    plonk::{
+        circuit_builder::CircuitBuilder,
+        circuit_data::CircuitData,
+        config::{AlgebraicHasher, GenericConfig},
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use super::{gate_serializer::CityGateSerializer, generator_serializer::CityGeneratorSerializer};
+
+pub const CIRCUIT_CACHE_FILE_MAGIC: [u8; 4] = *b"QCDC";
+pub const CIRCUIT_CACHE_FORMAT_VERSION: u32 = 1;
+
+/// Identifies a circuit in a cache session.
+///
+/// Circuits are built in a deterministic order, so the index of the build within the session
+/// (together with the session's network magic and fingerprint key) identifies the circuit, the
+/// remaining fields are checked against the builder as a sanity check.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct QCircuitCacheKey {
+    pub index: u32,
+    pub name: String,
+    pub num_gates: u64,
+    pub num_public_inputs: u64,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+struct QCircuitCacheEntryHeader {
+    version: u32,
+    network_magic: u64,
+    key: QCircuitCacheKey,
+    circuit_digest: Vec<u64>,
+    payload_length: u64,
+    payload_sha256: String,
+}
+
+/// A directory of serialized `CircuitData` for one network and one set of circuit fingerprints
+/// (`<base_dir>/<network_magic>/<fingerprint_key>`).
+#[derive(Clone, Debug)]
+pub struct QCircuitDataCache {
+    pub dir: PathBuf,
+    pub network_magic: u64,
+    // set while the circuits of a new build are cached in a staging directory, see open_build
+    staged_build_id: Option<String>,
+}
+
+fn check_cache_key(kind: &str, key: &str) -> anyhow::Result<()> {
+    if key.is_empty()
+        || !key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        anyhow::bail!("invalid circuit cache {} '{}'", kind, key);
+    }
+    Ok(())
+}
+
+fn get_network_dir(base_dir: &str, network_magic: u64) -> PathBuf {
+    Path::new(base_dir).join(format!("{:016x}", network_magic))
+}
+
+/// Identifies the circuits of the running executable by the sha256 of its binary.
+pub fn get_current_exe_build_id() -> anyhow::Result<String> {
+    let exe = std::env::current_exe()?;
+    Ok(CoreSha256Hasher::hash_bytes(&fs::read(exe)?).to_hex_string())
+}
+
+impl QCircuitDataCache {
+    pub fn new(base_dir: &str, network_magic: u64, fingerprint_key: &str) -> anyhow::Result<Self> {
+        check_cache_key("fingerprint key", fingerprint_key)?;
+        let dir = get_network_dir(base_dir, network_magic).join(fingerprint_key);
+        fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir,
+            network_magic,
+            staged_build_id: None,
+        })
+    }
+    /// Opens the cache for the circuits built by `build_id` (see `get_current_exe_build_id`).
+    ///
+    /// Entries are keyed on the fingerprints of the locally built circuits, so the first session
+    /// of a build caches into a staging directory which `QCircuitDataCacheSession::finish` moves
+    /// under the fingerprint key of the circuits it built.
+    pub fn open_build(base_dir: &str, network_magic: u64, build_id: &str) -> anyhow::Result<Self> {
+        check_cache_key("build id", build_id)?;
+        let network_dir = get_network_dir(base_dir, network_magic);
+        let build_path = network_dir.join("builds").join(build_id);
+        if build_path.exists() {
+            return Self::new(
+                base_dir,
+                network_magic,
+                fs::read_to_string(build_path)?.trim(),
+            );
+        }
+        let dir = network_dir.join(format!("staging-{}-{}", build_id, std::process::id()));
+        if dir.exists() {
+            fs::remove_dir_all(&dir)?;
+        }
+        fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir,
+            network_magic,
+            staged_build_id: Some(build_id.to_string()),
+        })
+    }
+    fn commit(&self, fingerprint_key: &str) -> anyhow::Result<()> {
+        let build_id = match &self.staged_build_id {
+            Some(build_id) => build_id,
+            None if self.dir.file_name() == Some(OsStr::new(fingerprint_key)) => return Ok(()),
+            None => anyhow::bail!(
+                "the circuits loaded from the circuit cache at {} do not match its fingerprint key, delete it",
+                self.dir.display()
+            ),
+        };
+        let network_dir = self.dir.parent().unwrap();
+        let key_dir = network_dir.join(fingerprint_key);
+        if key_dir.exists() {
+            // another build with the same circuits got there first
+            fs::remove_dir_all(&self.dir)?;
+        } else {
+            fs::rename(&self.dir, &key_dir)?;
+        }
+        let builds_dir = network_dir.join("builds");
+        fs::create_dir_all(&builds_dir)?;
+        let build_path = builds_dir.join(build_id);
+        let tmp_path = build_path.with_extension(format!("tmp{}", std::process::id()));
+        fs::write(&tmp_path, fingerprint_key)?;
+        fs::rename(&tmp_path, &build_path)?;
+        Ok(())
+    }
+    fn get_entry_path(&self, key: &QCircuitCacheKey) -> PathBuf {
+        self.dir.join(format!("{:04}-{}.bin", key.index, key.name))
+    }
+    pub fn load<
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F> + 'static,
+        const D: usize,
+    >(
+        &self,
+        key: &QCircuitCacheKey,
+    ) -> anyhow::Result<Option<CircuitData<F, C, D>>>
+    where
+        C::Hasher: AlgebraicHasher<F>,
+    {
+        let path = self.get_entry_path(key);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let bytes = fs::read(&path)?;
+        if bytes.len() < 8 || bytes[0..4] != CIRCUIT_CACHE_FILE_MAGIC {
+            anyhow::bail!("{} is not a circuit cache file", path.display());
+        }
+        let header_length = u32::from_le_bytes(bytes[4..8].try_into().unwrap()) as usize;
+        if bytes.len() < 8 + header_length {
+            anyhow::bail!("{} is truncated", path.display());
+        }
+        let header: QCircuitCacheEntryHeader =
+            serde_json::from_slice(&bytes[8..8 + header_length])?;
+        let payload = &bytes[8 + header_length..];
+
+        if header.version != CIRCUIT_CACHE_FORMAT_VERSION {
+            anyhow::bail!(
+                "{} has format version {}, expected {}",
+                path.display(),
+                header.version,
+                CIRCUIT_CACHE_FORMAT_VERSION
+            );
+        }
+        if header.network_magic != self.network_magic || header.key != *key {
+            anyhow::bail!(
+                "{} was cached for a different circuit (network_magic={:x}, key={:?})",
+                path.display(),
+                header.network_magic,
+                header.key
+            );
+        }
+        if header.payload_length != payload.len() as u64
+            || header.payload_sha256 != CoreSha256Hasher::hash_bytes(payload).to_hex_string()
+        {
+            anyhow::bail!("{} failed its payload integrity check", path.display());
+        }
+
+        let circuit_data = CircuitData::<F, C, D>::from_bytes(
+            payload,
+            &CityGateSerializer,
+            &CityGeneratorSerializer::<C, D>::default(),
+        )
+        .map_err(|_| anyhow::format_err!("failed to deserialize {}", path.display()))?;
+        if get_circuit_digest_u64(&circuit_data) != header.circuit_digest
+            || circuit_data.common.num_public_inputs as u64 != key.num_public_inputs
+        {
+            anyhow::bail!(
+                "{} does not match the circuit recorded in its header",
+                path.display()
+            );
+        }
+        Ok(Some(circuit_data))
+    }
+    pub fn store<
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F> + 'static,
+        const D: usize,
+    >(
+        &self,
+        key: &QCircuitCacheKey,
+        circuit_data: &CircuitData<F, C, D>,
+    ) -> anyhow::Result<()>
+    where
+        C::Hasher: AlgebraicHasher<F>,
+    {
+        let payload = circuit_data
+            .to_bytes(
+                &CityGateSerializer,
+                &CityGeneratorSerializer::<C, D>::default(),
+            )
+            .map_err(|_| {
+                anyhow::format_err!(
+                    "circuit {} uses a gate or generator which can not be serialized",
+                    key.name
+                )
+            })?;
+        let header = serde_json::to_vec(&QCircuitCacheEntryHeader {
+            version: CIRCUIT_CACHE_FORMAT_VERSION,
+            network_magic: self.network_magic,
+            key: key.clone(),
+            circuit_digest: get_circuit_digest_u64(circuit_data),
+            payload_length: payload.len() as u64,
+            payload_sha256: CoreSha256Hasher::hash_bytes(&payload).to_hex_string(),
+        })?;
+
+        let mut bytes = Vec::with_capacity(8 + header.len() + payload.len());
+        bytes.extend_from_slice(&CIRCUIT_CACHE_FILE_MAGIC);
+        bytes.extend_from_slice(&(header.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&header);
+        bytes.extend_from_slice(&payload);
+
+        // write to a temporary file first so a crashed worker never leaves a partial entry behind
+        let path = self.get_entry_path(key);
+        let tmp_path = path.with_extension("bin.tmp");
+        fs::write(&tmp_path, bytes)?;
+        fs::rename(&tmp_path, &path)?;
+        Ok(())
+    }
+    /// Makes `build_cached` on the current thread load from and store to this cache until the
+    /// returned session is dropped.
+    pub fn activate(self) -> QCircuitDataCacheSession {
+        ACTIVE_CIRCUIT_CACHE.with(|active| {
+            *active.borrow_mut() = Some(QActiveCircuitCache {
+                cache: self,
+                next_index: 0,
+                hits: 0,
+                misses: 0,
+                fingerprints: Vec::new(),
+            });
+        });
+        QCircuitDataCacheSession { _private: () }
+    }
+}
+
+fn get_circuit_digest_u64<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    circuit_data: &CircuitData<F, C, D>,
+) -> Vec<u64> {
+    circuit_data
+        .verifier_only
+        .circuit_digest
+        .elements
+        .iter()
+        .map(|x| x.to_canonical_u64())
+        .collect()
+}
+
+struct QActiveCircuitCache {
+    cache: QCircuitDataCache,
+    next_index: u32,
+    hits: usize,
+    misses: usize,
+    fingerprints: Vec<(String, Vec<u64>)>,
+}
+
+impl QActiveCircuitCache {
+    fn record(&mut self, name: &str, circuit_digest: Vec<u64>, hit: bool) {
+        if hit {
+            self.hits += 1;
+        } else {
+            self.misses += 1;
+        }
+        self.fingerprints.push((name.to_string(), circuit_digest));
+    }
+    fn log(&self) {
+        tracing::info!(
+            "circuit cache {}: loaded {} circuits, built {}",
+            self.cache.dir.display(),
+            self.hits,
+            self.misses
+        );
+    }
+}
+
+thread_local! {
+    static ACTIVE_CIRCUIT_CACHE: RefCell<Option<QActiveCircuitCache>> = RefCell::new(None);
+}
+
+pub struct QCircuitDataCacheSession {
+    _private: (),
+}
+
+impl QCircuitDataCacheSession {
+    /// Ends the session and files the cached circuits under a hash of the fingerprints of the
+    /// circuits built during the session, returning that key.
+    pub fn finish(self) -> anyhow::Result<String> {
+        let active = ACTIVE_CIRCUIT_CACHE
+            .with(|active| active.borrow_mut().take())
+            .ok_or_else(|| anyhow::format_err!("no circuit cache session is active"))?;
+        active.log();
+        let fingerprint_key =
+            CoreSha256Hasher::hash_bytes(&serde_json::to_vec(&active.fingerprints)?)
+                .to_hex_string();
+        active.cache.commit(&fingerprint_key)?;
+        Ok(fingerprint_key)
+    }
+}
+
+impl Drop for QCircuitDataCacheSession {
+    fn drop(&mut self) {
+        if let Some(active) = ACTIVE_CIRCUIT_CACHE.with(|active| active.borrow_mut().take()) {
+            active.log();
+        }
+    }
+}
+
+pub trait CircuitBuilderCachedBuild<F: RichField + Extendable<D>, const D: usize> {
+    /// Builds the circuit, or loads it from the circuit cache if one is active on this thread.
+    fn build_cached<C: GenericConfig<D, F = F> + 'static>(self, name: &str) -> CircuitData<F, C, D>
+    where
+        C::Hasher: AlgebraicHasher<F>;
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilderCachedBuild<F, D>
+    for CircuitBuilder<F, D>
+{
+    fn build_cached<C: GenericConfig<D, F = F> + 'static>(self, name: &str) -> CircuitData<F, C, D>
+    where
+        C::Hasher: AlgebraicHasher<F>,
+    {
+        let session = ACTIVE_CIRCUIT_CACHE.with(|active| {
+            active.borrow_mut().as_mut().map(|active| {
+                active.next_index += 1;
+                (active.cache.clone(), active.next_index - 1)
+            })
+        });
+        let (cache, index) = match session {
+            Some(x) => x,
+            None => return self.build::<C>(),
+        };
+        let key = QCircuitCacheKey {
+            index,
+            name: name.to_string(),
+            num_gates: self.num_gates() as u64,
+            num_public_inputs: self.num_public_inputs() as u64,
+        };
+
+        match cache.load::<F, C, D>(&key) {
+            Ok(Some(circuit_data)) => {
+                ACTIVE_CIRCUIT_CACHE.with(|active| {
+                    if let Some(active) = active.borrow_mut().as_mut() {
+                        active.record(name, get_circuit_digest_u64(&circuit_data), true);
+                    }
+                });
+                return circuit_data;
+            }
+            Ok(None) => {}
+            Err(err) => tracing::warn!("ignoring circuit cache entry: {}", err),
+        }
+
+        let circuit_data = self.build::<C>();
+        if let Err(err) = cache.store(&key, &circuit_data) {
+            tracing::info!("not caching circuit {}: {}", key.name, err);
+        }
+        ACTIVE_CIRCUIT_CACHE.with(|active| {
+            if let Some(active) = active.borrow_mut().as_mut() {
+                active.record(name, get_circuit_digest_u64(&circuit_data), false);
+            }
+        });
+        circuit_data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::{
+        iop::witness::PartialWitness,
+        plonk::{
+            circuit_builder::CircuitBuilder,
+            circuit_data::{CircuitConfig, CircuitData},
+            config::{GenericConfig, PoseidonGoldilocksConfig},
+        },
+    };
+
+    use starkyx::machine::hash::sha::sha256::SHA256;
+
+    use super::{CircuitBuilderCachedBuild, QCircuitCacheKey, QCircuitDataCache};
+    use crate::{
+        hash::accelerator::{
+            config::HashAcceleratorConfig,
+            sha256::gadget::{Sha256AcceleratorGadget, Sha256AirParametersGoldilocks},
+        },
+        u32::{
+            arithmetic_u32::{CircuitBuilderU32, U32Target},
+            witness::WitnessU32,
+        },
+    };
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    fn get_test_circuit_builder() -> (CircuitBuilder<F, D>, [U32Target; 2]) {
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let a = builder.add_virtual_u32_target();
+        let b = builder.add_virtual_u32_target();
+        let (low, high) = builder.mul_u32(a, b);
+        let (sum, _) = builder.add_many_u32(&[a, b, low, high]);
+        builder.register_public_input(sum.0);
+        (builder, [a, b])
+    }
+
+    fn build_test_circuit(cache: &QCircuitDataCache) -> (CircuitData<F, C, D>, [U32Target; 2]) {
+        let _session = cache.clone().activate();
+        let (builder, targets) = get_test_circuit_builder();
+        (builder.build_cached::<C>("test_u32"), targets)
+    }
+
+    #[test]
+    fn test_circuit_cache_roundtrip() {
+        let cache_dir =
+            std::env::temp_dir().join(format!("city_circuit_cache_test_{}", std::process::id()));
+        let cache = QCircuitDataCache::new(cache_dir.to_str().unwrap(), 1337, "test").unwrap();
+        let (builder, _) = get_test_circuit_builder();
+        let key = QCircuitCacheKey {
+            index: 0,
+            name: "test_u32".to_string(),
+            num_gates: builder.num_gates() as u64,
+            num_public_inputs: 1,
+        };
+
+        let (built, _) = build_test_circuit(&cache);
+        let (loaded, targets) = build_test_circuit(&cache);
+        assert_eq!(built.verifier_only, loaded.verifier_only);
+        assert!(cache.load::<F, C, D>(&key).unwrap().is_some());
+
+        let mut pw = PartialWitness::new();
+        pw.set_u32_target(targets[0], 0xdeadbeef);
+        pw.set_u32_target(targets[1], 0x12345678);
+        let proof = loaded.prove(pw).unwrap();
+        built.verify(proof).unwrap();
+
+        // a corrupted entry is rejected
+        let entry = std::fs::read_dir(&cache.dir)
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap()
+            .path();
+        let mut bytes = std::fs::read(&entry).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 1;
+        std::fs::write(&entry, bytes).unwrap();
+        assert!(cache.load::<F, C, D>(&key).is_err());
+
+        std::fs::remove_dir_all(cache_dir).unwrap();
+    }
+
+    #[test]
+    fn test_circuit_cache_is_keyed_on_built_circuits() {
+        let cache_dir = std::env::temp_dir().join(format!(
+            "city_circuit_cache_build_test_{}",
+            std::process::id()
+        ));
+        let base_dir = cache_dir.to_str().unwrap();
+
+        // the first session of a build stages its circuits and files them under their fingerprints
+        let staged = QCircuitDataCache::open_build(base_dir, 1337, "build-a").unwrap();
+        let session = staged.clone().activate();
+        let (built, _) = get_test_circuit_builder();
+        let built = built.build_cached::<C>("test_u32");
+        let fingerprint_key = session.finish().unwrap();
+        assert!(!staged.dir.exists());
+
+        let cache = QCircuitDataCache::open_build(base_dir, 1337, "build-a").unwrap();
+        assert_eq!(
+            cache.dir,
+            QCircuitDataCache::new(base_dir, 1337, &fingerprint_key)
+                .unwrap()
+                .dir
+        );
+        let session = cache.activate();
+        let (loaded, _) = get_test_circuit_builder();
+        let loaded = loaded.build_cached::<C>("test_u32");
+        assert_eq!(session.finish().unwrap(), fingerprint_key);
+        assert_eq!(built.verifier_only, loaded.verifier_only);
+
+        // a build with different circuits gets a different key
+        let session = QCircuitDataCache::open_build(base_dir, 1337, "build-b")
+            .unwrap()
+            .activate();
+        let (mut builder, _) = get_test_circuit_builder();
+        let x = builder.add_virtual_u32_target();
+        builder.register_public_input(x.0);
+        builder.build_cached::<C>("test_u32");
+        assert_ne!(session.finish().unwrap(), fingerprint_key);
+
+        std::fs::remove_dir_all(cache_dir).unwrap();
+    }
+
+    #[test]
+    fn test_circuit_cache_sha256_accelerator() {
+        let cache_dir = std::env::temp_dir().join(format!(
+            "city_circuit_cache_sha256_test_{}",
+            std::process::id()
+        ));
+        let cache = QCircuitDataCache::new(cache_dir.to_str().unwrap(), 1337, "test").unwrap();
+        let preimages = vec![b"abc".to_vec(), vec![7u8; 100]];
+
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let gadget = Sha256AcceleratorGadget::<SHA256, Sha256AirParametersGoldilocks, C, D, 64>::add_virtual_to(
+            &mut builder,
+            HashAcceleratorConfig::from_preimage_lengths(
+                &preimages.iter().map(|p| p.len()).collect::<Vec<usize>>(),
+            ),
+        );
+        let key = QCircuitCacheKey {
+            index: 0,
+            name: "test_sha256_accelerator".to_string(),
+            num_gates: builder.num_gates() as u64,
+            num_public_inputs: builder.num_public_inputs() as u64,
+        };
+        let built = builder.build::<C>();
+        cache.store(&key, &built).unwrap();
+        let loaded = cache.load::<F, C, D>(&key).unwrap().unwrap();
+        assert_eq!(built.verifier_only, loaded.verifier_only);
+
+        let mut pw = PartialWitness::new();
+        gadget.set_witness(&mut pw, &preimages);
+        let proof = loaded.prove(pw).unwrap();
+        built.verify(proof).unwrap();
+
+        std::fs::remove_dir_all(cache_dir).unwrap();
+    }
+}
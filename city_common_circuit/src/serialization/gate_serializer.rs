@@ -0,0 +1,110 @@
+use plonky2::{
+    field::extension::Extendable,
+    gates::gate::{Gate, GateRef},
+    hash::hash_types::RichField,
+    plonk::circuit_data::CommonCircuitData,
+    util::serialization::{
+        gate_serialization::{default::DefaultGateSerializer, GateSerializer},
+        Buffer, IoError, IoResult, Read, Write,
+    },
+};
+
+use crate::u32::gates::{
+    add_many_u32::U32AddManyGate, arithmetic_u32::U32ArithmeticGate, comparison::ComparisonGate,
+    interleave_u32::U32InterleaveGate, range_check_u32::U32RangeCheckGate,
+    subtraction_u32::U32SubtractionGate, uninterleave_to_b32::UninterleaveToB32Gate,
+    uninterleave_to_u32::UninterleaveToU32Gate,
+};
+
+pub(crate) const PLONKY2_SERIALIZER_NAMESPACE: u8 = 0;
+pub(crate) const CITY_SERIALIZER_NAMESPACE: u8 = 1;
+
+/// Serializes the plonky2 builtin gates and the custom u32 gates in `city_common_circuit`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CityGateSerializer;
+
+impl CityGateSerializer {
+    fn get_city_gate_tag<F: RichField + Extendable<D>, const D: usize>(
+        gate: &GateRef<F, D>,
+    ) -> Option<u32> {
+        // matched by type, the position in this list is the serialized tag, only append to it
+        let gate = gate.0.as_any();
+        [
+            gate.is::<U32InterleaveGate>(),
+            gate.is::<U32RangeCheckGate<F, D>>(),
+            gate.is::<UninterleaveToU32Gate>(),
+            gate.is::<U32AddManyGate<F, D>>(),
+            gate.is::<UninterleaveToB32Gate>(),
+            gate.is::<ComparisonGate<F, D>>(),
+            gate.is::<U32SubtractionGate<F, D>>(),
+            gate.is::<U32ArithmeticGate<F, D>>(),
+        ]
+        .iter()
+        .position(|x| *x)
+        .map(|tag| tag as u32)
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> GateSerializer<F, D> for CityGateSerializer {
+    fn read_gate(
+        &self,
+        buf: &mut Buffer,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> IoResult<GateRef<F, D>> {
+        match buf.read_u8()? {
+            PLONKY2_SERIALIZER_NAMESPACE => DefaultGateSerializer.read_gate(buf, common_data),
+            CITY_SERIALIZER_NAMESPACE => match buf.read_u32()? {
+                0 => Ok(GateRef::new(
+                    <U32InterleaveGate as Gate<F, D>>::deserialize(buf, common_data)?,
+                )),
+                1 => Ok(GateRef::new(U32RangeCheckGate::<F, D>::deserialize(
+                    buf,
+                    common_data,
+                )?)),
+                2 => Ok(GateRef::new(
+                    <UninterleaveToU32Gate as Gate<F, D>>::deserialize(buf, common_data)?,
+                )),
+                3 => Ok(GateRef::new(U32AddManyGate::<F, D>::deserialize(
+                    buf,
+                    common_data,
+                )?)),
+                4 => Ok(GateRef::new(
+                    <UninterleaveToB32Gate as Gate<F, D>>::deserialize(buf, common_data)?,
+                )),
+                5 => Ok(GateRef::new(ComparisonGate::<F, D>::deserialize(
+                    buf,
+                    common_data,
+                )?)),
+                6 => Ok(GateRef::new(U32SubtractionGate::<F, D>::deserialize(
+                    buf,
+                    common_data,
+                )?)),
+                7 => Ok(GateRef::new(U32ArithmeticGate::<F, D>::deserialize(
+                    buf,
+                    common_data,
+                )?)),
+                _ => Err(IoError),
+            },
+            _ => Err(IoError),
+        }
+    }
+
+    fn write_gate(
+        &self,
+        buf: &mut Vec<u8>,
+        gate: &GateRef<F, D>,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> IoResult<()> {
+        match Self::get_city_gate_tag(gate) {
+            Some(tag) => {
+                buf.write_u8(CITY_SERIALIZER_NAMESPACE)?;
+                buf.write_u32(tag)?;
+                gate.0.serialize(buf, common_data)
+            }
+            None => {
+                buf.write_u8(PLONKY2_SERIALIZER_NAMESPACE)?;
+                DefaultGateSerializer.write_gate(buf, gate, common_data)
+            }
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use std::marker::PhantomData;
+
+use plonky2::{
+    field::extension::Extendable,
+    hash::hash_types::RichField,
+    iop::generator::{SimpleGenerator, SimpleGeneratorAdapter, WitnessGeneratorRef},
+    plonk::{
+        circuit_data::CommonCircuitData,
+        config::{AlgebraicHasher, GenericConfig},
+    },
+    util::serialization::{
+        generator_serialization::{
+            default::DefaultGeneratorSerializer, WitnessGeneratorSerializer,
+        },
+        Buffer, IoError, IoResult, Read, Write,
+    },
+};
+
+use super::gate_serializer::{CITY_SERIALIZER_NAMESPACE, PLONKY2_SERIALIZER_NAMESPACE};
+use crate::{
+    hash::hash_ops::WireSplitGenerator,
+    u32::{
+        gadgets::arithmetic_u32::SplitToU32Generator,
+        gates::{
+            add_many_u32::U32AddManyGenerator, arithmetic_u32::U32ArithmeticGenerator,
+            comparison::ComparisonGenerator, interleave_u32::U32InterleaveGenerator,
+            range_check_u32::U32RangeCheckGenerator, subtraction_u32::U32SubtractionGenerator,
+            uninterleave_to_b32::UninterleaveToB32Generator,
+            uninterleave_to_u32::UninterleaveToU32Generator,
+        },
+    },
+};
+
+fn read_generator_as<
+    F: RichField + Extendable<D>,
+    const D: usize,
+    G: SimpleGenerator<F, D> + 'static,
+>(
+    buf: &mut Buffer,
+    common_data: &CommonCircuitData<F, D>,
+) -> IoResult<WitnessGeneratorRef<F, D>> {
+    let generator = G::deserialize(buf, common_data)?;
+    Ok(WitnessGeneratorRef::new(generator.adapter()))
+}
+
+fn get_city_generator_tag<F: RichField + Extendable<D>, const D: usize>(
+    generator: &WitnessGeneratorRef<F, D>,
+) -> Option<u32> {
+    // simple generators are stored behind an adapter, the position in this list is the serialized
+    // tag, only append to it
+    let generator = generator.0.as_any();
+    [
+        generator.is::<SimpleGeneratorAdapter<F, U32InterleaveGenerator, D>>(),
+        generator.is::<SimpleGeneratorAdapter<F, U32RangeCheckGenerator<F, D>, D>>(),
+        generator.is::<SimpleGeneratorAdapter<F, UninterleaveToU32Generator, D>>(),
+        generator.is::<SimpleGeneratorAdapter<F, U32AddManyGenerator<F, D>, D>>(),
+        generator.is::<SimpleGeneratorAdapter<F, UninterleaveToB32Generator, D>>(),
+        generator.is::<SimpleGeneratorAdapter<F, ComparisonGenerator<F, D>, D>>(),
+        generator.is::<SimpleGeneratorAdapter<F, U32SubtractionGenerator<F, D>, D>>(),
+        generator.is::<SimpleGeneratorAdapter<F, U32ArithmeticGenerator<F, D>, D>>(),
+        generator.is::<SimpleGeneratorAdapter<F, SplitToU32Generator<F, D>, D>>(),
+        generator.is::<SimpleGeneratorAdapter<F, WireSplitGenerator, D>>(),
+    ]
+    .iter()
+    .position(|x| *x)
+    .map(|tag| tag as u32)
+}
+
+/// Serializes the plonky2 builtin witness generators and the generators of the custom u32 gates
+/// and gadgets in `city_common_circuit`.
+///
+/// The sha256 accelerator verifies its stark proof with the builtin gates and generators and its
+/// proof targets are set by the prover, so accelerator circuits serialize with the builtins. The
+/// non-native field (secp256k1) generators are not registered, circuits which use them fail to
+/// serialize and are rebuilt.
+#[derive(Debug)]
+pub struct CityGeneratorSerializer<C: GenericConfig<D>, const D: usize> {
+    pub _phantom: PhantomData<C>,
+}
+
+impl<C: GenericConfig<D>, const D: usize> Default for CityGeneratorSerializer<C, D> {
+    fn default() -> Self {
+        Self {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<F, C, const D: usize> WitnessGeneratorSerializer<F, D> for CityGeneratorSerializer<C, D>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F> + 'static,
+    C::Hasher: AlgebraicHasher<F>,
+{
+    fn read_generator(
+        &self,
+        buf: &mut Buffer,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> IoResult<WitnessGeneratorRef<F, D>> {
+        match buf.read_u8()? {
+            PLONKY2_SERIALIZER_NAMESPACE => DefaultGeneratorSerializer::<C, D> {
+                _phantom: PhantomData,
+            }
+            .read_generator(buf, common_data),
+            CITY_SERIALIZER_NAMESPACE => match buf.read_u32()? {
+                0 => read_generator_as::<F, D, U32InterleaveGenerator>(buf, common_data),
+                1 => read_generator_as::<F, D, U32RangeCheckGenerator<F, D>>(buf, common_data),
+                2 => read_generator_as::<F, D, UninterleaveToU32Generator>(buf, common_data),
+                3 => read_generator_as::<F, D, U32AddManyGenerator<F, D>>(buf, common_data),
+                4 => read_generator_as::<F, D, UninterleaveToB32Generator>(buf, common_data),
+                5 => read_generator_as::<F, D, ComparisonGenerator<F, D>>(buf, common_data),
+                6 => read_generator_as::<F, D, U32SubtractionGenerator<F, D>>(buf, common_data),
+                7 => read_generator_as::<F, D, U32ArithmeticGenerator<F, D>>(buf, common_data),
+                8 => read_generator_as::<F, D, SplitToU32Generator<F, D>>(buf, common_data),
+                9 => read_generator_as::<F, D, WireSplitGenerator>(buf, common_data),
+                _ => Err(IoError),
+            },
+            _ => Err(IoError),
+        }
+    }
+
+    fn write_generator(
+        &self,
+        buf: &mut Vec<u8>,
+        generator: &WitnessGeneratorRef<F, D>,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> IoResult<()> {
+        match get_city_generator_tag(generator) {
+            Some(tag) => {
+                buf.write_u8(CITY_SERIALIZER_NAMESPACE)?;
+                buf.write_u32(tag)?;
+                generator.0.serialize(buf, common_data)
+            }
+            None => {
+                buf.write_u8(PLONKY2_SERIALIZER_NAMESPACE)?;
+                DefaultGeneratorSerializer::<C, D> {
+                    _phantom: PhantomData,
+                }
+                .write_generator(buf, generator, common_data)
+            }
+        }
+    }
+}
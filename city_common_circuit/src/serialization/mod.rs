@@ -0,0 +1,3 @@
+pub mod circuit_cache;
+pub mod gate_serializer;
+pub mod generator_serializer;
@@ -26,6 +26,7 @@ use crate::{
     proof_minifier::pm_core::get_circuit_fingerprint_generic,
     treeprover::traits::TreeProverAggCircuit,
 };
+use crate::serialization::circuit_cache::CircuitBuilderCachedBuild;
 
 #[derive(Debug, Clone)]
 pub struct AggStateTrackableCircuitHeaderGadget {
@@ -128,7 +129,7 @@ pub struct AggStateTransitionCircuit<C: GenericConfig<D>, const D: usize> {
     pub circuit_data: CircuitData<C::F, C, D>,
     pub fingerprint: QHashOut<C::F>,
 }
-impl<C: GenericConfig<D>, const D: usize> Clone for AggStateTransitionCircuit<C, D>
+impl<C: GenericConfig<D> + 'static, const D: usize> Clone for AggStateTransitionCircuit<C, D>
 where
     C::Hasher: AlgebraicHasher<C::F>,
 {
@@ -142,7 +143,7 @@ where
         )
     }
 }
-impl<C: GenericConfig<D>, const D: usize> AggStateTransitionCircuit<C, D>
+impl<C: GenericConfig<D> + 'static, const D: usize> AggStateTransitionCircuit<C, D>
 where
     C::Hasher: AlgebraicHasher<C::F>,
 {
@@ -243,7 +244,7 @@ where
         builder.register_public_inputs(&header_gadget.state_transition_hash.elements);
 
         builder.add_city_common_gates(None);
-        let circuit_data = builder.build::<C>();
+        let circuit_data = builder.build_cached::<C>("agg_state_transition");
 
         let fingerprint = QHashOut(get_circuit_fingerprint_generic(&circuit_data.verifier_only));
 
@@ -317,8 +318,8 @@ impl<C: GenericConfig<D>, const D: usize> QStandardCircuit<C, D>
     }
 }
 
-impl<C: GenericConfig<D>, const D: usize> TreeProverAggCircuit<AggStateTransitionInput<C::F>, C, D>
-    for AggStateTransitionCircuit<C, D>
+impl<C: GenericConfig<D> + 'static, const D: usize>
+    TreeProverAggCircuit<AggStateTransitionInput<C::F>, C, D> for AggStateTransitionCircuit<C, D>
 where
     C::Hasher: AlgebraicHasher<C::F>,
 {
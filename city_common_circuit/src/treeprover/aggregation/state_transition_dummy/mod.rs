@@ -23,6 +23,7 @@ use crate::{
     },
     proof_minifier::pm_core::get_circuit_fingerprint_generic,
 };
+use crate::serialization::circuit_cache::CircuitBuilderCachedBuild;
 
 #[derive(Debug)]
 pub struct AggStateTransitionDummyCircuit<C: GenericConfig<D> + 'static, const D: usize>
@@ -55,7 +56,7 @@ where
 
         builder.add_city_common_gates(Some(coset_gate.clone()));
         pad_circuit_degree::<C::F, D>(&mut builder, 12);
-        let circuit_data = builder.build::<C>();
+        let circuit_data = builder.build_cached::<C>("agg_state_transition_dummy");
 
         let fingerprint = QHashOut(get_circuit_fingerprint_generic(&circuit_data.verifier_only));
 
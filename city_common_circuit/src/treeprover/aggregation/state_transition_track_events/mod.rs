@@ -28,6 +28,7 @@ use crate::{
     proof_minifier::pm_core::get_circuit_fingerprint_generic,
     treeprover::traits::TreeProverAggCircuit,
 };
+use crate::serialization::circuit_cache::CircuitBuilderCachedBuild;
 
 #[derive(Debug, Clone)]
 pub struct AggStateTrackableWithEventsCircuitHeaderGadget {
@@ -143,7 +144,8 @@ pub struct AggStateTransitionWithEventsCircuit<C: GenericConfig<D>, const D: usi
     pub circuit_data: CircuitData<C::F, C, D>,
     pub fingerprint: QHashOut<C::F>,
 }
-impl<C: GenericConfig<D>, const D: usize> Clone for AggStateTransitionWithEventsCircuit<C, D>
+impl<C: GenericConfig<D> + 'static, const D: usize> Clone
+    for AggStateTransitionWithEventsCircuit<C, D>
 where
     C::Hasher: AlgebraicHasher<C::F>,
 {
@@ -157,7 +159,7 @@ where
         )
     }
 }
-impl<C: GenericConfig<D>, const D: usize> AggStateTransitionWithEventsCircuit<C, D>
+impl<C: GenericConfig<D> + 'static, const D: usize> AggStateTransitionWithEventsCircuit<C, D>
 where
     C::Hasher: AlgebraicHasher<C::F>,
 {
@@ -276,7 +278,7 @@ where
         builder.register_public_inputs(&header_gadget.event_transition_hash.elements);
 
         builder.add_city_common_gates(None);
-        let circuit_data = builder.build::<C>();
+        let circuit_data = builder.build_cached::<C>("agg_state_transition_with_events");
 
         let fingerprint = QHashOut(get_circuit_fingerprint_generic(&circuit_data.verifier_only));
 
@@ -351,7 +353,7 @@ impl<C: GenericConfig<D>, const D: usize> QStandardCircuit<C, D>
     }
 }
 
-impl<C: GenericConfig<D>, const D: usize>
+impl<C: GenericConfig<D> + 'static, const D: usize>
     TreeProverAggCircuit<AggStateTransitionWithEventsInput<C::F>, C, D>
     for AggStateTransitionWithEventsCircuit<C, D>
 where
@@ -25,6 +25,7 @@ use crate::{
     },
     proof_minifier::pm_core::get_circuit_fingerprint_generic,
 };
+use crate::serialization::circuit_cache::CircuitBuilderCachedBuild;
 
 #[derive(Debug)]
 pub struct AggStateTransitionWithEventsDummyCircuit<C: GenericConfig<D> + 'static, const D: usize>
@@ -60,7 +61,7 @@ where
 
         builder.add_city_common_gates(Some(coset_gate.clone()));
         pad_circuit_degree::<C::F, D>(&mut builder, 12);
-        let circuit_data = builder.build::<C>();
+        let circuit_data = builder.build_cached::<C>("agg_state_transition_with_events_dummy");
 
         let fingerprint = QHashOut(get_circuit_fingerprint_generic(&circuit_data.verifier_only));
 
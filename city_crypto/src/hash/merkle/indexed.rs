@@ -0,0 +1,411 @@
+use std::{cmp::Ordering, collections::HashMap, marker::PhantomData};
+
+use kvq::traits::KVQSerializable;
+use plonky2::{hash::hash_types::RichField, plonk::config::AlgebraicHasher};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    core::{DeltaMerkleProofCore, MerkleProofCore},
+    multi_proof::MerkleNodePosition,
+};
+use crate::hash::{qhashout::QHashOut, traits::hasher::MerkleHasher};
+
+// keys are compared by their canonical field elements, element 3 is the most significant
+pub fn compare_indexed_merkle_keys<F: RichField>(a: &QHashOut<F>, b: &QHashOut<F>) -> Ordering {
+    let to_ordered = |h: &QHashOut<F>| {
+        [
+            h.0.elements[3].to_canonical_u64(),
+            h.0.elements[2].to_canonical_u64(),
+            h.0.elements[1].to_canonical_u64(),
+            h.0.elements[0].to_canonical_u64(),
+        ]
+    };
+    to_ordered(a).cmp(&to_ordered(b))
+}
+
+/// A leaf of an indexed merkle tree.
+///
+/// The leaves form a linked list sorted by key, starting at the sentinel leaf (key zero) at index
+/// 0. A `next_key` of zero marks the end of the list, so zero can not be inserted as a key.
+///
+/// Stored as 72 bytes: the key, the next key and the big endian next index.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, KVQSerializable)]
+#[serde(bound = "")]
+pub struct IndexedMerkleLeaf<F: RichField> {
+    pub key: QHashOut<F>,
+    pub next_key: QHashOut<F>,
+    pub next_index: u64,
+}
+
+impl<F: RichField> IndexedMerkleLeaf<F> {
+    pub fn sentinel() -> Self {
+        Self {
+            key: QHashOut::ZERO,
+            next_key: QHashOut::ZERO,
+            next_index: 0,
+        }
+    }
+    pub fn to_felts(&self) -> [F; 9] {
+        let key = self.key.0.elements;
+        let next_key = self.next_key.0.elements;
+        [
+            key[0],
+            key[1],
+            key[2],
+            key[3],
+            next_key[0],
+            next_key[1],
+            next_key[2],
+            next_key[3],
+            F::from_canonical_u64(self.next_index),
+        ]
+    }
+    pub fn hash<H: AlgebraicHasher<F>>(&self) -> QHashOut<F> {
+        QHashOut(H::hash_no_pad(&self.to_felts()))
+    }
+    pub fn is_last(&self) -> bool {
+        self.next_key == QHashOut::ZERO
+    }
+    // true if this leaf proves that key is not in the tree, i.e. key sorts strictly between this
+    // leaf's key and the next key in the list
+    pub fn is_low_leaf_of(&self, key: &QHashOut<F>) -> bool {
+        *key != QHashOut::ZERO
+            && compare_indexed_merkle_keys(&self.key, key) == Ordering::Less
+            && (self.is_last()
+                || compare_indexed_merkle_keys(key, &self.next_key) == Ordering::Less)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct IndexedMerkleMembershipProof<F: RichField> {
+    pub leaf: IndexedMerkleLeaf<F>,
+    pub proof: MerkleProofCore<QHashOut<F>>,
+}
+
+impl<F: RichField> IndexedMerkleMembershipProof<F> {
+    pub fn verify<H: AlgebraicHasher<F> + MerkleHasher<QHashOut<F>>>(&self) -> bool {
+        self.leaf.key != QHashOut::ZERO
+            && self.leaf.hash::<H>() == self.proof.value
+            && self.proof.verify::<H>()
+    }
+}
+
+/// Proves that `key` is not in the tree by opening the leaf with the largest key smaller than
+/// `key` (the low leaf), whose next pointer skips over `key`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct IndexedMerkleNonMembershipProof<F: RichField> {
+    pub key: QHashOut<F>,
+    pub low_leaf: IndexedMerkleLeaf<F>,
+    pub low_leaf_proof: MerkleProofCore<QHashOut<F>>,
+}
+
+impl<F: RichField> IndexedMerkleNonMembershipProof<F> {
+    pub fn verify<H: AlgebraicHasher<F> + MerkleHasher<QHashOut<F>>>(&self) -> bool {
+        self.low_leaf.is_low_leaf_of(&self.key)
+            && self.low_leaf.hash::<H>() == self.low_leaf_proof.value
+            && self.low_leaf_proof.verify::<H>()
+    }
+}
+
+/// Inserting a key updates the next pointer of its low leaf and then writes the new leaf to the
+/// next empty index, `low_leaf_delta.new_root == new_leaf_delta.old_root`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct IndexedMerkleInsertProof<F: RichField> {
+    pub key: QHashOut<F>,
+    // the low leaf before the insert
+    pub low_leaf: IndexedMerkleLeaf<F>,
+    pub low_leaf_delta: DeltaMerkleProofCore<QHashOut<F>>,
+    pub new_leaf_delta: DeltaMerkleProofCore<QHashOut<F>>,
+}
+
+impl<F: RichField> IndexedMerkleInsertProof<F> {
+    pub fn old_root(&self) -> QHashOut<F> {
+        self.low_leaf_delta.old_root
+    }
+    pub fn new_root(&self) -> QHashOut<F> {
+        self.new_leaf_delta.new_root
+    }
+    pub fn new_leaf(&self) -> IndexedMerkleLeaf<F> {
+        IndexedMerkleLeaf {
+            key: self.key,
+            next_key: self.low_leaf.next_key,
+            next_index: self.low_leaf.next_index,
+        }
+    }
+    pub fn updated_low_leaf(&self) -> IndexedMerkleLeaf<F> {
+        IndexedMerkleLeaf {
+            key: self.low_leaf.key,
+            next_key: self.key,
+            next_index: self.new_leaf_delta.index,
+        }
+    }
+    pub fn verify<H: AlgebraicHasher<F> + MerkleHasher<QHashOut<F>>>(&self) -> bool {
+        self.low_leaf.is_low_leaf_of(&self.key)
+            && self.low_leaf.hash::<H>() == self.low_leaf_delta.old_value
+            && self.updated_low_leaf().hash::<H>() == self.low_leaf_delta.new_value
+            && self.low_leaf_delta.verify::<H>()
+            && self.new_leaf_delta.old_root == self.low_leaf_delta.new_root
+            && self.new_leaf_delta.old_value == QHashOut::ZERO
+            && self.new_leaf().hash::<H>() == self.new_leaf_delta.new_value
+            && self.new_leaf_delta.verify::<H>()
+    }
+}
+
+/// An in memory indexed merkle tree, used as a reference implementation in tests.
+///
+/// Low leaves are found with a linear scan, so it is only meant for small trees.
+pub struct IndexedMerkleTree<F: RichField, H: AlgebraicHasher<F> + MerkleHasher<QHashOut<F>>> {
+    pub height: u8,
+    pub leaves: Vec<IndexedMerkleLeaf<F>>,
+    nodes: HashMap<MerkleNodePosition, QHashOut<F>>,
+    // indexed by level, the zero hash of the leaves is at `height`
+    zero_hashes: Vec<QHashOut<F>>,
+    _hasher: PhantomData<H>,
+}
+
+impl<F: RichField, H: AlgebraicHasher<F> + MerkleHasher<QHashOut<F>>> IndexedMerkleTree<F, H> {
+    pub fn new(height: u8) -> Self {
+        let mut zero_hashes = vec![QHashOut::ZERO; height as usize + 1];
+        for level in (0..height as usize).rev() {
+            zero_hashes[level] = <H as MerkleHasher<QHashOut<F>>>::two_to_one(
+                &zero_hashes[level + 1],
+                &zero_hashes[level + 1],
+            );
+        }
+        let mut tree = Self {
+            height,
+            leaves: Vec::new(),
+            nodes: HashMap::new(),
+            zero_hashes,
+            _hasher: PhantomData,
+        };
+        let sentinel = IndexedMerkleLeaf::sentinel();
+        tree.set_leaf_value(0, sentinel.hash::<H>());
+        tree.leaves.push(sentinel);
+        tree
+    }
+    fn get_node(&self, level: u8, index: u64) -> QHashOut<F> {
+        self.nodes
+            .get(&MerkleNodePosition { level, index })
+            .copied()
+            .unwrap_or(self.zero_hashes[level as usize])
+    }
+    fn get_siblings(&self, index: u64) -> Vec<QHashOut<F>> {
+        (1..=self.height)
+            .rev()
+            .map(|level| self.get_node(level, (index >> (self.height - level)) ^ 1))
+            .collect()
+    }
+    fn set_leaf_value(
+        &mut self,
+        index: u64,
+        value: QHashOut<F>,
+    ) -> DeltaMerkleProofCore<QHashOut<F>> {
+        let old_root = self.root();
+        let old_value = self.get_node(self.height, index);
+        let siblings = self.get_siblings(index);
+        let mut current = value;
+        let mut current_index = index;
+        for (i, level) in (1..=self.height).rev().enumerate() {
+            self.nodes.insert(
+                MerkleNodePosition {
+                    level,
+                    index: current_index,
+                },
+                current,
+            );
+            current = if current_index & 1 == 0 {
+                <H as MerkleHasher<QHashOut<F>>>::two_to_one(&current, &siblings[i])
+            } else {
+                <H as MerkleHasher<QHashOut<F>>>::two_to_one(&siblings[i], &current)
+            };
+            current_index >>= 1;
+        }
+        self.nodes
+            .insert(MerkleNodePosition { level: 0, index: 0 }, current);
+        DeltaMerkleProofCore {
+            old_root,
+            old_value,
+            new_root: current,
+            new_value: value,
+            index,
+            siblings,
+        }
+    }
+    pub fn root(&self) -> QHashOut<F> {
+        self.get_node(0, 0)
+    }
+    pub fn get_proof(&self, index: u64) -> MerkleProofCore<QHashOut<F>> {
+        MerkleProofCore {
+            root: self.root(),
+            value: self.get_node(self.height, index),
+            index,
+            siblings: self.get_siblings(index),
+        }
+    }
+    pub fn find_leaf_index(&self, key: &QHashOut<F>) -> Option<u64> {
+        self.leaves
+            .iter()
+            .position(|leaf| leaf.key == *key && *key != QHashOut::ZERO)
+            .map(|index| index as u64)
+    }
+    pub fn find_low_leaf_index(&self, key: &QHashOut<F>) -> Option<u64> {
+        self.leaves
+            .iter()
+            .position(|leaf| leaf.is_low_leaf_of(key))
+            .map(|index| index as u64)
+    }
+    pub fn get_membership_proof(
+        &self,
+        key: &QHashOut<F>,
+    ) -> anyhow::Result<IndexedMerkleMembershipProof<F>> {
+        let index = self
+            .find_leaf_index(key)
+            .ok_or_else(|| anyhow::anyhow!("key {} is not in the indexed merkle tree", key))?;
+        Ok(IndexedMerkleMembershipProof {
+            leaf: self.leaves[index as usize],
+            proof: self.get_proof(index),
+        })
+    }
+    pub fn get_non_membership_proof(
+        &self,
+        key: &QHashOut<F>,
+    ) -> anyhow::Result<IndexedMerkleNonMembershipProof<F>> {
+        let index = self
+            .find_low_leaf_index(key)
+            .ok_or_else(|| anyhow::anyhow!("key {} is in the indexed merkle tree", key))?;
+        Ok(IndexedMerkleNonMembershipProof {
+            key: *key,
+            low_leaf: self.leaves[index as usize],
+            low_leaf_proof: self.get_proof(index),
+        })
+    }
+    pub fn insert(&mut self, key: QHashOut<F>) -> anyhow::Result<IndexedMerkleInsertProof<F>> {
+        let low_index = self.find_low_leaf_index(&key).ok_or_else(|| {
+            anyhow::anyhow!("cannot insert key {} into the indexed merkle tree", key)
+        })?;
+        let new_index = self.leaves.len() as u64;
+        if self.height < 64 && new_index >= (1u64 << self.height) {
+            anyhow::bail!("indexed merkle tree is full");
+        }
+        let low_leaf = self.leaves[low_index as usize];
+        let new_leaf = IndexedMerkleLeaf {
+            key,
+            next_key: low_leaf.next_key,
+            next_index: low_leaf.next_index,
+        };
+        let updated_low_leaf = IndexedMerkleLeaf {
+            key: low_leaf.key,
+            next_key: key,
+            next_index: new_index,
+        };
+        let low_leaf_delta = self.set_leaf_value(low_index, updated_low_leaf.hash::<H>());
+        let new_leaf_delta = self.set_leaf_value(new_index, new_leaf.hash::<H>());
+        self.leaves[low_index as usize] = updated_low_leaf;
+        self.leaves.push(new_leaf);
+        Ok(IndexedMerkleInsertProof {
+            key,
+            low_leaf,
+            low_leaf_delta,
+            new_leaf_delta,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::{field::goldilocks_field::GoldilocksField, hash::poseidon::PoseidonHash};
+
+    use super::*;
+    use crate::hash::merkle::core::compute_partial_merkle_root_from_leaves;
+
+    type F = GoldilocksField;
+    type H = PoseidonHash;
+
+    fn key(a: u64, b: u64) -> QHashOut<F> {
+        QHashOut::from_values(a, 0, 0, b)
+    }
+
+    #[test]
+    fn indexed_merkle_leaf_has_a_fixed_layout() {
+        let leaf = IndexedMerkleLeaf::<F> {
+            key: key(5, 1),
+            next_key: key(9, 1),
+            next_index: 3,
+        };
+        let bytes = leaf.to_bytes().unwrap();
+        assert_eq!(bytes.len(), 72);
+        assert_eq!(bytes[64..72], 3u64.to_be_bytes());
+        assert_eq!(IndexedMerkleLeaf::<F>::from_bytes(&bytes).unwrap(), leaf);
+        assert!(IndexedMerkleLeaf::<F>::from_bytes(&bytes[..71]).is_err());
+    }
+
+    #[test]
+    fn indexed_merkle_tree_keeps_sorted_list() {
+        let height = 3;
+        let mut tree = IndexedMerkleTree::<F, H>::new(height);
+        // key(9, 1) > key(1, 1) since element 3 is the most significant
+        let keys = [key(5, 1), key(1, 7), key(9, 1), key(1, 1), key(0, 3)];
+        for k in keys.iter() {
+            let proof = tree.insert(*k).unwrap();
+            assert!(proof.verify::<H>());
+        }
+        assert!(tree.insert(key(1, 7)).is_err());
+        assert!(tree.insert(QHashOut::ZERO).is_err());
+
+        let mut sorted = Vec::new();
+        let mut current = tree.leaves[0];
+        while !current.is_last() {
+            sorted.push(current.next_key);
+            current = tree.leaves[current.next_index as usize];
+        }
+        assert_eq!(
+            sorted,
+            vec![key(1, 1), key(5, 1), key(9, 1), key(0, 3), key(1, 7)]
+        );
+
+        let mut leaf_hashes = tree
+            .leaves
+            .iter()
+            .map(|leaf| leaf.hash::<H>())
+            .collect::<Vec<_>>();
+        leaf_hashes.resize(1 << height, QHashOut::ZERO);
+        assert_eq!(
+            compute_partial_merkle_root_from_leaves::<QHashOut<F>, H>(&leaf_hashes),
+            tree.root()
+        );
+    }
+
+    #[test]
+    fn indexed_merkle_tree_proofs() {
+        let mut tree = IndexedMerkleTree::<F, H>::new(4);
+        for k in [key(10, 0), key(20, 0), key(30, 0)].iter() {
+            tree.insert(*k).unwrap();
+        }
+        assert!(tree
+            .get_membership_proof(&key(20, 0))
+            .unwrap()
+            .verify::<H>());
+        assert!(tree.get_membership_proof(&key(25, 0)).is_err());
+
+        for absent in [key(5, 0), key(25, 0), key(31, 0), key(0, 1)].iter() {
+            assert!(tree.get_non_membership_proof(absent).unwrap().verify::<H>());
+        }
+        assert!(tree.get_non_membership_proof(&key(30, 0)).is_err());
+
+        // a low leaf can not be reused for a key which is in the tree
+        let mut forged = tree.get_non_membership_proof(&key(25, 0)).unwrap();
+        forged.key = key(30, 0);
+        assert!(!forged.verify::<H>());
+        forged.key = key(20, 0);
+        assert!(!forged.verify::<H>());
+
+        // nor for a key outside of its range
+        let mut forged = tree.get_non_membership_proof(&key(5, 0)).unwrap();
+        forged.key = key(15, 0);
+        assert!(!forged.verify::<H>());
+    }
+}
@@ -1,2 +1,4 @@
 pub mod core;
+pub mod indexed;
+pub mod multi_proof;
 pub mod treeprover;
@@ -0,0 +1,266 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::core::MerkleProofCore;
+use crate::hash::traits::hasher::MerkleHasher;
+
+/// The position of a node in a merkle tree, level 0 is the root and level `height` holds the
+/// leaves (the same convention as the kvq merkle model in `city_store`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct MerkleNodePosition {
+    pub level: u8,
+    pub index: u64,
+}
+
+/// A merkle proof for several leaves of the same tree.
+///
+/// A sibling is only included once, and only if it can not be computed from the proven leaves,
+/// in the order given by `get_merkle_multi_proof_sibling_positions`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MerkleMultiProofCore<Hash: PartialEq + Copy> {
+    pub root: Hash,
+    pub height: u8,
+
+    // strictly increasing
+    pub indices: Vec<u64>,
+    pub values: Vec<Hash>,
+    pub siblings: Vec<Hash>,
+}
+
+impl<Hash: PartialEq + Copy> MerkleMultiProofCore<Hash> {
+    pub fn verify<Hasher: MerkleHasher<Hash>>(&self) -> bool {
+        verify_merkle_multi_proof_core::<Hash, Hasher>(self)
+    }
+    pub fn compute_root<Hasher: MerkleHasher<Hash>>(&self) -> Option<Hash> {
+        compute_merkle_multi_proof_nodes::<Hash, Hasher>(
+            self.height,
+            &self.indices,
+            &self.values,
+            &self.siblings,
+        )
+        .map(|nodes| nodes[&MerkleNodePosition { level: 0, index: 0 }])
+    }
+    // expands the multi proof into one single leaf proof per index, returns None if the multi
+    // proof is malformed
+    pub fn to_merkle_proofs<Hasher: MerkleHasher<Hash>>(
+        &self,
+    ) -> Option<Vec<MerkleProofCore<Hash>>> {
+        let nodes = compute_merkle_multi_proof_nodes::<Hash, Hasher>(
+            self.height,
+            &self.indices,
+            &self.values,
+            &self.siblings,
+        )?;
+        Some(
+            self.indices
+                .iter()
+                .zip(self.values.iter())
+                .map(|(index, value)| {
+                    let mut current = *index;
+                    let siblings = (1..=self.height)
+                        .rev()
+                        .map(|level| {
+                            let sibling = nodes[&MerkleNodePosition {
+                                level,
+                                index: current ^ 1,
+                            }];
+                            current >>= 1;
+                            sibling
+                        })
+                        .collect::<Vec<_>>();
+                    MerkleProofCore {
+                        root: self.root,
+                        value: *value,
+                        index: *index,
+                        siblings,
+                    }
+                })
+                .collect(),
+        )
+    }
+}
+
+fn is_valid_multi_proof_index_set(height: u8, indices: &[u64]) -> bool {
+    !indices.is_empty()
+        && indices.windows(2).all(|pair| pair[0] < pair[1])
+        && (height >= 64 || indices[indices.len() - 1] < (1u64 << height))
+}
+
+/// Returns the positions of the siblings a multi proof for `indices` (sorted, without duplicates)
+/// has to include, from the leaves up to the root and from left to right on each level.
+pub fn get_merkle_multi_proof_sibling_positions(
+    height: u8,
+    indices: &[u64],
+) -> Vec<MerkleNodePosition> {
+    let mut positions = Vec::new();
+    let mut current = indices.to_vec();
+    for level in (1..=height).rev() {
+        let mut next = Vec::with_capacity(current.len());
+        let mut i = 0;
+        while i < current.len() {
+            let index = current[i];
+            if i + 1 < current.len() && current[i + 1] == index ^ 1 {
+                // both children are known, no sibling needed
+                i += 2;
+            } else {
+                positions.push(MerkleNodePosition {
+                    level,
+                    index: index ^ 1,
+                });
+                i += 1;
+            }
+            next.push(index >> 1);
+        }
+        current = next;
+    }
+    positions
+}
+
+// computes every node the multi proof touches, including the root at (0, 0)
+fn compute_merkle_multi_proof_nodes<Hash: PartialEq + Copy, Hasher: MerkleHasher<Hash>>(
+    height: u8,
+    indices: &[u64],
+    values: &[Hash],
+    siblings: &[Hash],
+) -> Option<HashMap<MerkleNodePosition, Hash>> {
+    if indices.len() != values.len() || !is_valid_multi_proof_index_set(height, indices) {
+        return None;
+    }
+    let mut nodes = HashMap::new();
+    let mut current = indices
+        .iter()
+        .copied()
+        .zip(values.iter().copied())
+        .collect::<Vec<_>>();
+    let mut siblings = siblings.iter();
+    for level in (1..=height).rev() {
+        let mut next = Vec::with_capacity(current.len());
+        let mut i = 0;
+        while i < current.len() {
+            let (index, value) = current[i];
+            nodes.insert(MerkleNodePosition { level, index }, value);
+            let parent = if i + 1 < current.len() && current[i + 1].0 == index ^ 1 {
+                let right = current[i + 1].1;
+                nodes.insert(
+                    MerkleNodePosition {
+                        level,
+                        index: index ^ 1,
+                    },
+                    right,
+                );
+                i += 2;
+                Hasher::two_to_one(&value, &right)
+            } else {
+                let sibling = *siblings.next()?;
+                nodes.insert(
+                    MerkleNodePosition {
+                        level,
+                        index: index ^ 1,
+                    },
+                    sibling,
+                );
+                i += 1;
+                if index & 1 == 0 {
+                    Hasher::two_to_one(&value, &sibling)
+                } else {
+                    Hasher::two_to_one(&sibling, &value)
+                }
+            };
+            next.push((index >> 1, parent));
+        }
+        current = next;
+    }
+    if siblings.next().is_some() {
+        return None;
+    }
+    nodes.insert(MerkleNodePosition { level: 0, index: 0 }, current[0].1);
+    Some(nodes)
+}
+
+pub fn verify_merkle_multi_proof_core<Hash: PartialEq + Copy, Hasher: MerkleHasher<Hash>>(
+    proof: &MerkleMultiProofCore<Hash>,
+) -> bool {
+    proof
+        .compute_root::<Hasher>()
+        .map_or(false, |root| root == proof.root)
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::{field::goldilocks_field::GoldilocksField, hash::poseidon::PoseidonHash};
+
+    use super::*;
+    use crate::hash::{merkle::core::compute_partial_merkle_root_from_leaves, qhashout::QHashOut};
+
+    type H = QHashOut<GoldilocksField>;
+
+    fn get_leaves(height: u8) -> Vec<H> {
+        (0..(1u64 << height))
+            .map(|i| QHashOut::from_values(i, i * 7, 3, 1))
+            .collect()
+    }
+
+    fn get_node(leaves: &[H], height: u8, position: MerkleNodePosition) -> H {
+        let width = 1usize << (height - position.level);
+        let start = (position.index as usize) * width;
+        compute_partial_merkle_root_from_leaves::<H, PoseidonHash>(&leaves[start..start + width])
+    }
+
+    fn get_multi_proof(leaves: &[H], height: u8, indices: &[u64]) -> MerkleMultiProofCore<H> {
+        MerkleMultiProofCore {
+            root: get_node(leaves, height, MerkleNodePosition { level: 0, index: 0 }),
+            height,
+            indices: indices.to_vec(),
+            values: indices.iter().map(|i| leaves[*i as usize]).collect(),
+            siblings: get_merkle_multi_proof_sibling_positions(height, indices)
+                .into_iter()
+                .map(|position| get_node(leaves, height, position))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn multi_proof_shares_siblings() {
+        let height = 4;
+        let leaves = get_leaves(height);
+        let proof = get_multi_proof(&leaves, height, &[0, 1, 2, 9]);
+        assert!(proof.verify::<PoseidonHash>());
+        // 0 and 1 are siblings, as are their parent and the parent of 2, so only 5 of the 16
+        // siblings of the single proofs are needed
+        assert_eq!(proof.siblings.len(), 5);
+
+        let single_proofs = proof.to_merkle_proofs::<PoseidonHash>().unwrap();
+        assert!(single_proofs.iter().all(|p| p.verify::<PoseidonHash>()));
+        assert!(single_proofs.len() * (height as usize) > proof.siblings.len());
+    }
+
+    #[test]
+    fn multi_proof_rejects_malformed_proofs() {
+        let height = 4;
+        let leaves = get_leaves(height);
+        let proof = get_multi_proof(&leaves, height, &[3, 4, 12]);
+        assert!(proof.verify::<PoseidonHash>());
+
+        let mut wrong_value = proof.clone();
+        wrong_value.values[1] = QHashOut::from_values(1, 2, 3, 4);
+        assert!(!wrong_value.verify::<PoseidonHash>());
+
+        let mut unsorted = proof.clone();
+        unsorted.indices.swap(0, 1);
+        unsorted.values.swap(0, 1);
+        assert!(!unsorted.verify::<PoseidonHash>());
+
+        let mut extra_sibling = proof.clone();
+        extra_sibling.siblings.push(leaves[0]);
+        assert!(!extra_sibling.verify::<PoseidonHash>());
+
+        let mut missing_sibling = proof.clone();
+        missing_sibling.siblings.pop();
+        assert!(!missing_sibling.verify::<PoseidonHash>());
+
+        let mut out_of_range = proof.clone();
+        out_of_range.indices[2] = 16;
+        assert!(!out_of_range.verify::<PoseidonHash>());
+    }
+}
@@ -1,7 +1,7 @@
 use std::{fmt::Display, str::FromStr};
 
 use anyhow::ensure;
-use kvq::traits::KVQSerializable;
+use kvq::{table::KVQKeyField, traits::KVQSerializable};
 use plonky2::{
     field::{
         goldilocks_field::GoldilocksField,
@@ -201,3 +201,13 @@ impl<F: RichField> KVQSerializable for QHashOut<F> {
         )?))
     }
 }
+
+impl<F: RichField> KVQKeyField for QHashOut<F> {
+    const SIZE: usize = 32;
+    fn write_key_bytes(&self, result: &mut Vec<u8>) {
+        self.0.write_key_bytes(result)
+    }
+    fn read_key_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        Ok(QHashOut(HashOut::<F>::read_key_bytes(bytes)?))
+    }
+}
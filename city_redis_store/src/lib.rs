@@ -1,7 +1,11 @@
 use city_rollup_common::api::data::store::CityUserState;
+use city_rollup_common::qworker::fingerprints::CRWorkerToolboxCoreCircuitFingerprints;
+use city_rollup_common::qworker::fleet::QWorkerInfo;
+use city_rollup_common::qworker::fleet::QWorkerRegistrySync;
 use city_rollup_common::qworker::job_id::QProvingJobDataID;
 use city_rollup_common::qworker::proof_store::QProofStoreReaderSync;
 use city_rollup_common::qworker::proof_store::QProofStoreWriterSync;
+use plonky2::field::goldilocks_field::GoldilocksField;
 use plonky2::plonk::config::GenericConfig;
 use plonky2::plonk::proof::ProofWithPublicInputs;
 use r2d2_redis::RedisConnectionManager;
@@ -12,6 +16,8 @@ pub const USER_STATE: &'static str = "user_state";
 
 pub const PROOFS: &'static str = "proofs";
 pub const PROOF_COUNTERS: &'static str = "proof_counters";
+pub const WORKERS: &'static str = "workers";
+pub const FINGERPRINT_MANIFEST: &'static str = "fingerprint_manifest";
 
 #[derive(Clone)]
 pub struct RedisStore {
@@ -65,6 +71,18 @@ impl QProofStoreReaderSync for RedisStore {
         let data: Vec<u8> = conn.hget(PROOFS, <[u8; 24]>::from(&id).to_vec())?;
         Ok(data)
     }
+
+    fn get_bytes_by_id_if_exists(&self, id: QProvingJobDataID) -> anyhow::Result<Option<Vec<u8>>> {
+        let mut conn = self.get_connection()?;
+        let data: Option<Vec<u8>> = conn.hget(PROOFS, <[u8; 24]>::from(&id).to_vec())?;
+        Ok(data)
+    }
+
+    fn get_counter_by_id(&self, id: QProvingJobDataID) -> anyhow::Result<u32> {
+        let mut conn = self.get_connection()?;
+        let value: Option<u32> = conn.hget(PROOF_COUNTERS, <[u8; 24]>::from(&id).to_vec())?;
+        Ok(value.unwrap_or(0))
+    }
 }
 
 impl QProofStoreWriterSync for RedisStore {
@@ -88,6 +106,16 @@ impl QProofStoreWriterSync for RedisStore {
         Ok(value)
     }
 
+    fn mark_job_completed(&mut self, id: QProvingJobDataID) -> anyhow::Result<bool> {
+        let mut conn = self.get_connection()?;
+        let marked: bool = conn.hset_nx(
+            PROOFS,
+            <[u8; 24]>::from(&id.get_completed_id()).to_vec(),
+            1u8,
+        )?;
+        Ok(marked)
+    }
+
     fn set_bytes_by_id(&mut self, id: QProvingJobDataID, data: &[u8]) -> anyhow::Result<()> {
         let mut conn = self.get_connection()?;
         conn.hset_nx(PROOFS, <[u8; 24]>::from(&id).to_vec(), data)?;
@@ -110,3 +138,84 @@ impl QProofStoreWriterSync for RedisStore {
         self.write_multidimensional_jobs_core(jobs_levels, next_jobs)
     }
 }
+
+impl QWorkerRegistrySync for RedisStore {
+    // the redis server's clock, shared by the orchestrator and all workers
+    fn get_time(&self) -> anyhow::Result<u64> {
+        let mut conn = self.get_connection()?;
+        let (seconds, microseconds): (u64, u64) = redis::cmd("TIME").query(&mut *conn)?;
+        Ok(seconds * 1000 + microseconds / 1000)
+    }
+
+    fn register_worker(&mut self, info: &QWorkerInfo) -> anyhow::Result<()> {
+        let now = self.get_time()?;
+        let info = QWorkerInfo {
+            started_at: now,
+            last_heartbeat: now,
+            ..info.clone()
+        };
+        let mut conn = self.get_connection()?;
+        conn.hset(WORKERS, &info.worker_id, serde_json::to_vec(&info)?)?;
+        Ok(())
+    }
+
+    fn heartbeat_worker(&mut self, info: &QWorkerInfo) -> anyhow::Result<bool> {
+        // started_at is kept from the registration, so it stays on the same clock as last_heartbeat
+        let now = self.get_time()?;
+        let mut conn = self.get_connection()?;
+        let registered: Option<Vec<u8>> = conn.hget(WORKERS, &info.worker_id)?;
+        let Some(registered) = registered else {
+            return Ok(false);
+        };
+        let registered: QWorkerInfo = serde_json::from_slice(&registered)?;
+        let info = QWorkerInfo {
+            started_at: registered.started_at,
+            last_heartbeat: now,
+            ..info.clone()
+        };
+        // checked and written in one script so a removal can't land in between
+        let updated: bool = redis::Script::new(
+            r"if redis.call('HEXISTS', KEYS[1], ARGV[1]) == 1 then
+                redis.call('HSET', KEYS[1], ARGV[1], ARGV[2])
+                return 1
+            end
+            return 0",
+        )
+        .key(WORKERS)
+        .arg(&info.worker_id)
+        .arg(serde_json::to_vec(&info)?)
+        .invoke(&mut *conn)?;
+        Ok(updated)
+    }
+
+    fn remove_worker(&mut self, worker_id: &str) -> anyhow::Result<()> {
+        let mut conn = self.get_connection()?;
+        conn.hdel(WORKERS, worker_id)?;
+        Ok(())
+    }
+
+    fn get_workers(&self) -> anyhow::Result<Vec<QWorkerInfo>> {
+        let mut conn = self.get_connection()?;
+        let data: Vec<Vec<u8>> = conn.hvals(WORKERS)?;
+        data.iter()
+            .map(|x| Ok(serde_json::from_slice(x)?))
+            .collect()
+    }
+
+    fn set_fingerprint_manifest(
+        &mut self,
+        manifest: &CRWorkerToolboxCoreCircuitFingerprints<GoldilocksField>,
+    ) -> anyhow::Result<()> {
+        let mut conn = self.get_connection()?;
+        conn.set(FINGERPRINT_MANIFEST, serde_json::to_vec(manifest)?)?;
+        Ok(())
+    }
+
+    fn get_fingerprint_manifest(
+        &self,
+    ) -> anyhow::Result<Option<CRWorkerToolboxCoreCircuitFingerprints<GoldilocksField>>> {
+        let mut conn = self.get_connection()?;
+        let data: Option<Vec<u8>> = conn.get(FINGERPRINT_MANIFEST)?;
+        data.map(|x| Ok(serde_json::from_slice(&x)?)).transpose()
+    }
+}
@@ -1,4 +1,5 @@
 use city_common::config::rollup_constants::L1_DEPOSIT_TREE_HEIGHT;
+use city_common_circuit::serialization::circuit_cache::CircuitBuilderCachedBuild;
 use city_common_circuit::{
     builder::{
         hash::core::CircuitBuilderHashCore,
@@ -41,7 +42,7 @@ where
     pub circuit_data: CircuitData<C::F, C, D>,
     pub fingerprint: QHashOut<C::F>,
 }
-impl<C: GenericConfig<D>, const D: usize> CRAddL1DepositCircuit<C, D>
+impl<C: GenericConfig<D> + 'static, const D: usize> CRAddL1DepositCircuit<C, D>
 where
     C::Hasher: AlgebraicHasher<C::F> + MerkleZeroHasher<HashOut<C::F>>,
 {
@@ -69,7 +70,7 @@ where
         pad_circuit_degree::<C::F, D>(&mut builder, 12);
         builder.add_city_common_gates(Some(coset_gate.clone()));
 
-        let circuit_data = builder.build::<C>();
+        let circuit_data = builder.build_cached::<C>("op_add_l1_deposit");
 
         let fingerprint = QHashOut(get_circuit_fingerprint_generic(&circuit_data.verifier_only));
 
@@ -1,3 +1,4 @@
+use city_common_circuit::serialization::circuit_cache::CircuitBuilderCachedBuild;
 use city_common_circuit::{
     builder::{
         pad_circuit::CircuitBuilderCityCommonGates, verify::CircuitBuilderVerifyProofHelpers,
@@ -44,6 +45,7 @@ where
     pub circuit_data: CircuitData<C::F, C, D>,
     pub fingerprint: QHashOut<C::F>,
     pub network_magic: u64,
+    pub fee_recipient_user_id: u64,
     // start dependencies
 }
 impl<C: GenericConfig<D> + 'static, const D: usize> CRAddL1WithdrawalCircuit<C, D>
@@ -52,6 +54,7 @@ where
 {
     pub fn new_with_signature_circuit_data(
         network_magic: u64,
+        fee_recipient_user_id: u64,
         signature_circuit_common_data: &CommonCircuitData<C::F, D>,
         signature_circuit_verifier_data_cap_height: usize,
         signature_wrapper_fingerprint: QHashOut<C::F>,
@@ -62,7 +65,7 @@ where
             C::Hasher,
             C::F,
             D,
-        >(&mut builder, network_magic);
+        >(&mut builder, network_magic, fee_recipient_user_id);
         let expected_signature_combined_hash = withdrawal_single_gadget.expected_signature_hash;
         let expected_signature_public_key = withdrawal_single_gadget.expected_public_key;
 
@@ -124,7 +127,7 @@ where
         );
 
         builder.add_city_common_gates(None);
-        let circuit_data = builder.build::<C>();
+        let circuit_data = builder.build_cached::<C>("op_add_l1_withdrawal");
 
         let fingerprint = QHashOut(get_circuit_fingerprint_generic(&circuit_data.verifier_only));
         Self {
@@ -134,6 +137,7 @@ where
             circuit_data,
             fingerprint,
             network_magic,
+            fee_recipient_user_id,
             signature_verifier_data_target,
         }
     }
@@ -153,6 +157,9 @@ where
             &mut pw,
             &input.withdrawal_tree_delta_merkle_proof,
             &input.user_tree_delta_merkle_proof,
+            &input.fee_recipient_user_tree_delta_merkle_proof,
+            input.fee,
+            input.withdrawal_hash,
         );
         pw.set_proof_with_pis_target(&self.signature_proof_target, signature_proof);
         pw.set_verifier_data_target(
@@ -0,0 +1,465 @@
+use city_common_circuit::serialization::circuit_cache::CircuitBuilderCachedBuild;
+use city_common_circuit::{
+    builder::{
+        core::CircuitBuilderHelpersCore, hash::core::CircuitBuilderHashCore,
+        pad_circuit::pad_circuit_degree, verify::CircuitBuilderVerifyProofHelpers,
+    },
+    circuits::{
+        traits::qstandard::QStandardCircuit, zk_signature_wrapper::ZKSignatureWrapperCircuit,
+    },
+    proof_minifier::pm_core::get_circuit_fingerprint_generic,
+    treeprover::wrapper::TreeProverLeafCircuitWrapper,
+};
+use city_crypto::hash::qhashout::QHashOut;
+use city_rollup_common::{
+    introspection::rollup::constants::SIG_ACTION_BATCH_TRANSFER_MAGIC,
+    qworker::{
+        job_id::QProvingJobDataID, job_witnesses::op::CRBatchL2TransferCircuitInput,
+        proof_store::QProofStoreReaderSync, verifier::QWorkerVerifyHelper,
+    },
+};
+use plonky2::{
+    field::extension::Extendable,
+    hash::hash_types::{HashOutTarget, RichField},
+    iop::witness::{PartialWitness, WitnessWrite},
+    plonk::{
+        circuit_builder::CircuitBuilder,
+        circuit_data::{
+            CircuitConfig, CircuitData, CommonCircuitData, VerifierCircuitTarget,
+            VerifierOnlyCircuitData,
+        },
+        config::{AlgebraicHasher, GenericConfig},
+        proof::{ProofWithPublicInputs, ProofWithPublicInputsTarget},
+    },
+};
+
+use crate::{
+    introspection::gadgets::rollup::signature::compute_sig_action_hash_circuit,
+    state::user::batch_l2_transfer_state_update::BatchL2TransferStateUpdateGadget,
+    worker::traits::QWorkerCircuitStandardWithDataSync,
+};
+
+#[derive(Debug, Clone)]
+pub struct BatchL2TransferSingleGadget {
+    // inputs:
+    pub batch_l2_transfer_gadget: BatchL2TransferStateUpdateGadget,
+
+    // computed:
+    pub expected_signature_hash: HashOutTarget,
+    pub expected_public_key: HashOutTarget,
+    pub old_user_tree_root: HashOutTarget,
+    pub new_user_tree_root: HashOutTarget,
+}
+impl BatchL2TransferSingleGadget {
+    pub fn add_virtual_to<H: AlgebraicHasher<F>, F: RichField + Extendable<D>, const D: usize>(
+        builder: &mut CircuitBuilder<F, D>,
+        network_magic: u64,
+        fee_recipient_user_id: u64,
+    ) -> Self {
+        let batch_l2_transfer_gadget = BatchL2TransferStateUpdateGadget::add_virtual_to::<H, F, D>(
+            builder,
+            fee_recipient_user_id,
+        );
+        let sig_action_id = builder.constant_u64(SIG_ACTION_BATCH_TRANSFER_MAGIC);
+        let network_magic_target = builder.constant_u64(network_magic);
+        let sender_user_id = batch_l2_transfer_gadget.sender_old_user_state.user_id;
+        let new_sender_user_nonce = batch_l2_transfer_gadget.sender_new_user_state.nonce;
+
+        // [fee, recipient_0, amount_0, ..., recipient_n, amount_n], unused slots are signed as (0, 0)
+        let mut action_arguments = vec![batch_l2_transfer_gadget.fee];
+        for recipient in batch_l2_transfer_gadget.recipients.iter() {
+            action_arguments.push(recipient.signed_recipient_user_id);
+            action_arguments.push(recipient.amount);
+        }
+
+        let expected_signature_hash = compute_sig_action_hash_circuit::<H, F, D>(
+            builder,
+            network_magic_target,
+            sender_user_id,
+            sig_action_id,
+            new_sender_user_nonce,
+            &action_arguments,
+        );
+        let expected_public_key = batch_l2_transfer_gadget.sender_old_user_state.public_key;
+
+        let old_user_tree_root = batch_l2_transfer_gadget
+            .sender_user_tree_delta_merkle_proof_gadget
+            .old_root;
+        let new_user_tree_root = batch_l2_transfer_gadget
+            .fee_credit_gadget
+            .fee_recipient_user_tree_delta_merkle_proof_gadget
+            .new_root;
+
+        Self {
+            batch_l2_transfer_gadget,
+            expected_signature_hash,
+            expected_public_key,
+            old_user_tree_root,
+            new_user_tree_root,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CRBatchL2TransferCircuit<C: GenericConfig<D> + 'static, const D: usize>
+where
+    C::Hasher: AlgebraicHasher<C::F>,
+{
+    pub batch_l2_transfer_single_gadget: BatchL2TransferSingleGadget,
+    pub signature_proof_target: ProofWithPublicInputsTarget<D>,
+    pub signature_verifier_data_target: VerifierCircuitTarget,
+
+    pub allowed_circuit_hashes_root_target: HashOutTarget,
+    // end circuit targets
+    pub circuit_data: CircuitData<C::F, C, D>,
+    pub fingerprint: QHashOut<C::F>,
+    pub network_magic: u64,
+    pub fee_recipient_user_id: u64,
+    // dependencies
+}
+impl<C: GenericConfig<D> + 'static, const D: usize> CRBatchL2TransferCircuit<C, D>
+where
+    C::Hasher: AlgebraicHasher<C::F>,
+{
+    pub fn new(network_magic: u64, fee_recipient_user_id: u64) -> Self {
+        let sig_wrapper = ZKSignatureWrapperCircuit::<C, D>::new().circuit_data;
+
+        Self::new_with_sig_wrapper_data(
+            network_magic,
+            fee_recipient_user_id,
+            &sig_wrapper.common,
+            sig_wrapper.verifier_only.constants_sigmas_cap.height(),
+            QHashOut(get_circuit_fingerprint_generic(&sig_wrapper.verifier_only)),
+        )
+    }
+    pub fn new_with_sig_wrapper_data(
+        network_magic: u64,
+        fee_recipient_user_id: u64,
+        signature_circuit_common_data: &CommonCircuitData<C::F, D>,
+        signature_circuit_verifier_data_cap_height: usize,
+        signature_wrapper_fingerprint: QHashOut<C::F>,
+    ) -> Self {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<C::F, D>::new(config);
+        let batch_l2_transfer_single_gadget =
+            BatchL2TransferSingleGadget::add_virtual_to::<C::Hasher, C::F, D>(
+                &mut builder,
+                network_magic,
+                fee_recipient_user_id,
+            );
+
+        let signature_proof_target =
+            builder.add_virtual_proof_with_pis(&signature_circuit_common_data);
+        let signature_verifier_data_target =
+            builder.add_virtual_verifier_data(signature_circuit_verifier_data_cap_height);
+
+        let signature_proof_public_key = HashOutTarget {
+            elements: [
+                signature_proof_target.public_inputs[0],
+                signature_proof_target.public_inputs[1],
+                signature_proof_target.public_inputs[2],
+                signature_proof_target.public_inputs[3],
+            ],
+        };
+        let signature_proof_message_hash = HashOutTarget {
+            elements: [
+                signature_proof_target.public_inputs[4],
+                signature_proof_target.public_inputs[5],
+                signature_proof_target.public_inputs[6],
+                signature_proof_target.public_inputs[7],
+            ],
+        };
+
+        // ensure the batch is signed with the sender's public key
+        builder.connect_hashes(
+            signature_proof_public_key,
+            batch_l2_transfer_single_gadget.expected_public_key,
+        );
+
+        // ensure the signature signs the correct message hash for the whole batch
+        builder.connect_hashes(
+            signature_proof_message_hash,
+            batch_l2_transfer_single_gadget.expected_signature_hash,
+        );
+
+        // verify the signature proof
+        builder.verify_proof::<C>(
+            &signature_proof_target,
+            &signature_verifier_data_target,
+            &signature_circuit_common_data,
+        );
+        let actual_sig_wrapper_fingerprint =
+            builder.get_circuit_fingerprint::<C::Hasher>(&signature_verifier_data_target);
+        let expected_sig_wrapper_fingerprint =
+            builder.constant_hash(signature_wrapper_fingerprint.0);
+        builder.connect_hashes(
+            actual_sig_wrapper_fingerprint,
+            expected_sig_wrapper_fingerprint,
+        );
+        let allowed_circuit_hashes_root_target = builder.add_virtual_hash();
+
+        let state_transition_hash = builder.hash_two_to_one::<C::Hasher>(
+            batch_l2_transfer_single_gadget.old_user_tree_root,
+            batch_l2_transfer_single_gadget.new_user_tree_root,
+        );
+
+        builder.register_public_inputs(&allowed_circuit_hashes_root_target.elements);
+        builder.register_public_inputs(&state_transition_hash.elements);
+
+        pad_circuit_degree::<C::F, D>(&mut builder, 12);
+        let circuit_data = builder.build_cached::<C>("op_batch_l2_transfer");
+
+        let fingerprint = QHashOut(get_circuit_fingerprint_generic(&circuit_data.verifier_only));
+
+        Self {
+            batch_l2_transfer_single_gadget,
+            signature_proof_target,
+            allowed_circuit_hashes_root_target,
+            circuit_data,
+            fingerprint,
+            network_magic,
+            fee_recipient_user_id,
+            signature_verifier_data_target,
+        }
+    }
+    pub fn prove_base(
+        &self,
+        input: &CRBatchL2TransferCircuitInput<C::F>,
+        signature_proof: &ProofWithPublicInputs<C::F, C, D>,
+        signature_verifier_data: &VerifierOnlyCircuitData<C, D>,
+    ) -> anyhow::Result<ProofWithPublicInputs<C::F, C, D>> {
+        let mut pw = PartialWitness::new();
+        self.batch_l2_transfer_single_gadget
+            .batch_l2_transfer_gadget
+            .set_witness(
+                &mut pw,
+                &input.sender_user_tree_delta_merkle_proof,
+                &input.receiver_user_tree_delta_merkle_proofs,
+                &input.fee_recipient_user_tree_delta_merkle_proof,
+                input.recipient_count,
+                input.fee,
+            );
+
+        pw.set_proof_with_pis_target(&self.signature_proof_target, signature_proof);
+        pw.set_verifier_data_target(
+            &self.signature_verifier_data_target,
+            &signature_verifier_data,
+        );
+        pw.set_hash_target(
+            self.allowed_circuit_hashes_root_target,
+            input.allowed_circuit_hashes_root.0,
+        );
+
+        self.circuit_data.prove(pw)
+    }
+}
+impl<C: GenericConfig<D> + 'static, const D: usize> QStandardCircuit<C, D>
+    for CRBatchL2TransferCircuit<C, D>
+where
+    C::Hasher: AlgebraicHasher<C::F>,
+{
+    fn get_fingerprint(&self) -> QHashOut<C::F> {
+        self.fingerprint
+    }
+
+    fn get_verifier_config_ref(&self) -> &VerifierOnlyCircuitData<C, D> {
+        &self.circuit_data.verifier_only
+    }
+
+    fn get_common_circuit_data_ref(&self) -> &CommonCircuitData<C::F, D> {
+        &self.circuit_data.common
+    }
+}
+
+impl<
+        V: QWorkerVerifyHelper<C, D>,
+        S: QProofStoreReaderSync,
+        C: GenericConfig<D> + 'static,
+        const D: usize,
+    > QWorkerCircuitStandardWithDataSync<V, S, CRBatchL2TransferCircuitInput<C::F>, C, D>
+    for CRBatchL2TransferCircuit<C, D>
+where
+    C::Hasher: AlgebraicHasher<C::F>,
+{
+    fn prove_q_worker_standard_with_input(
+        &self,
+        input: &CRBatchL2TransferCircuitInput<C::F>,
+        verify_helper: &V,
+        store: &S,
+        _job_id: QProvingJobDataID,
+    ) -> anyhow::Result<ProofWithPublicInputs<C::F, C, D>> {
+        let signature_proof = store.get_proof_by_id(input.signature_proof_id)?;
+
+        self.prove_base(
+            input,
+            &signature_proof,
+            verify_helper
+                .get_verifier_triplet_for_circuit_type(input.signature_proof_id.circuit_type)
+                .1,
+        )
+    }
+}
+
+pub type WCRBatchL2TransferCircuit<C, const D: usize> =
+    TreeProverLeafCircuitWrapper<CRBatchL2TransferCircuit<C, D>, C, D>;
+
+#[cfg(test)]
+mod tests {
+    use std::panic::AssertUnwindSafe;
+
+    use city_common::config::rollup_constants::L2_BATCH_TRANSFER_MAX_RECIPIENTS;
+    use city_crypto::hash::qhashout::QHashOut;
+    use city_rollup_common::{
+        api::data::block::{
+            requested_actions::CityBatchTokenTransferRequest,
+            rpc_request::CityBatchTransferRecipient,
+        },
+        introspection::rollup::signature::QEDSigAction,
+        qworker::job_id::QProvingJobDataID,
+    };
+    use city_store::{config::CityHash, store::city::base::CityStore};
+    use kvq::memory::simple::KVQSimpleMemoryBackingStore;
+    use plonky2::{
+        hash::poseidon::PoseidonHash,
+        iop::witness::{PartialWitness, WitnessWrite},
+        plonk::{
+            circuit_builder::CircuitBuilder,
+            circuit_data::CircuitConfig,
+            config::{GenericConfig, PoseidonGoldilocksConfig},
+        },
+    };
+
+    use super::BatchL2TransferSingleGadget;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = KVQSimpleMemoryBackingStore;
+
+    const NETWORK_MAGIC: u64 = 0x4c4c4f52;
+    const FEE_RECIPIENT_USER_ID: u64 = 1;
+    const SENDER_USER_ID: u64 = 3;
+
+    fn batch_request(transfers: &[(u64, u64)], fee: u64) -> CityBatchTokenTransferRequest {
+        CityBatchTokenTransferRequest::new(
+            SENDER_USER_ID,
+            transfers
+                .iter()
+                .map(|(to, value)| CityBatchTransferRecipient {
+                    to: *to,
+                    value: *value,
+                })
+                .collect(),
+            fee,
+            1,
+            QProvingJobDataID::batch_transfer_signature_proof(0, 2, 0),
+        )
+    }
+
+    // the hash a wallet signs for the request
+    fn signed_hash(req: &CityBatchTokenTransferRequest) -> CityHash {
+        let transfers = req
+            .transfers
+            .iter()
+            .map(|t| (t.to, t.value))
+            .collect::<Vec<_>>();
+        QEDSigAction::<F>::new_batch_transfer_action(
+            NETWORK_MAGIC,
+            req.user_id,
+            req.nonce,
+            &transfers,
+            req.fee,
+        )
+        .unwrap()
+        .get_qhash::<PoseidonHash>()
+    }
+
+    // true if the request, applied to the store the way the orchestrator applies it, proves
+    // with a signature over signed_hash
+    fn prove_batch_request(req: &CityBatchTokenTransferRequest, signed_hash: CityHash) -> bool {
+        let mut store = S::new();
+        for user_id in [1, 3, 4, 5] {
+            let public_key = QHashOut::from_values(user_id, user_id, user_id, user_id);
+            CityStore::<S>::register_user(&mut store, 1, user_id, public_key).unwrap();
+        }
+        CityStore::<S>::increment_user_balance(&mut store, 1, SENDER_USER_ID, 1000, None).unwrap();
+
+        let sender_proof = CityStore::<S>::decrement_user_balance(
+            &mut store,
+            2,
+            req.user_id,
+            req.total_value() + req.fee,
+            Some(req.nonce),
+        )
+        .unwrap();
+        let mut receiver_proofs = Vec::with_capacity(L2_BATCH_TRANSFER_MAX_RECIPIENTS);
+        for i in 0..L2_BATCH_TRANSFER_MAX_RECIPIENTS {
+            // unused slots are zero-value updates on the first recipient's leaf
+            let (to, value) = match req.transfers.get(i) {
+                Some(transfer) => (transfer.to, transfer.value),
+                None => (req.transfers[0].to, 0),
+            };
+            receiver_proofs.push(
+                CityStore::<S>::increment_user_balance(&mut store, 2, to, value, None).unwrap(),
+            );
+        }
+        let fee_proof =
+            CityStore::<S>::credit_sequencer_fee(&mut store, 2, FEE_RECIPIENT_USER_ID, req.fee)
+                .unwrap();
+
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let gadget = BatchL2TransferSingleGadget::add_virtual_to::<PoseidonHash, F, D>(
+            &mut builder,
+            NETWORK_MAGIC,
+            FEE_RECIPIENT_USER_ID,
+        );
+        // stand-in for the message hash public input of the signature proof
+        let signature_message_hash = builder.add_virtual_hash();
+        builder.connect_hashes(signature_message_hash, gadget.expected_signature_hash);
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        gadget.batch_l2_transfer_gadget.set_witness(
+            &mut pw,
+            &sender_proof,
+            &receiver_proofs,
+            &fee_proof,
+            req.transfers.len(),
+            req.fee,
+        );
+        pw.set_hash_target(signature_message_hash, signed_hash.0);
+        // witness generation panics on some conflicting constraints instead of returning an error
+        match std::panic::catch_unwind(AssertUnwindSafe(|| data.prove(pw))) {
+            Ok(Ok(proof)) => data.verify(proof).is_ok(),
+            _ => false,
+        }
+    }
+
+    #[test]
+    fn signed_hash_matches_the_batch_request() {
+        let req = batch_request(&[(4, 100), (5, 200)], 5);
+        assert!(prove_batch_request(&req, signed_hash(&req)));
+
+        let full = batch_request(&[(4, 1), (5, 2), (4, 3), (5, 4)], 0);
+        assert!(prove_batch_request(&full, signed_hash(&full)));
+    }
+
+    #[test]
+    fn signature_over_a_different_batch_is_rejected() {
+        let req = batch_request(&[(4, 100), (5, 200)], 5);
+        for signed in [
+            batch_request(&[(5, 200), (4, 100)], 5),
+            batch_request(&[(4, 100), (5, 199)], 6),
+            batch_request(&[(4, 100), (5, 200)], 4),
+            batch_request(&[(4, 100)], 5),
+            batch_request(&[(4, 100), (5, 200), (4, 0)], 5),
+        ] {
+            assert!(!prove_batch_request(&req, signed_hash(&signed)));
+        }
+
+        // a signature for another nonce is rejected
+        let mut stale = req.clone();
+        stale.nonce = 2;
+        assert!(!prove_batch_request(&req, signed_hash(&stale)));
+    }
+}
@@ -0,0 +1,476 @@
+use city_common_circuit::serialization::circuit_cache::CircuitBuilderCachedBuild;
+use city_common_circuit::{
+    builder::{
+        core::CircuitBuilderHelpersCore, hash::core::CircuitBuilderHashCore,
+        pad_circuit::pad_circuit_degree, verify::CircuitBuilderVerifyProofHelpers,
+    },
+    circuits::{
+        traits::qstandard::QStandardCircuit, zk_signature_wrapper::ZKSignatureWrapperCircuit,
+    },
+    proof_minifier::pm_core::get_circuit_fingerprint_generic,
+    treeprover::wrapper::TreeProverLeafCircuitWrapper,
+};
+use city_crypto::hash::qhashout::QHashOut;
+use city_rollup_common::{
+    introspection::rollup::constants::SIG_ACTION_CHANGE_PUBLIC_KEY_MAGIC,
+    qworker::{
+        job_id::QProvingJobDataID, job_witnesses::op::CRChangePublicKeyCircuitInput,
+        proof_store::QProofStoreReaderSync, verifier::QWorkerVerifyHelper,
+    },
+};
+use plonky2::{
+    field::extension::Extendable,
+    hash::hash_types::{HashOutTarget, RichField},
+    iop::witness::{PartialWitness, WitnessWrite},
+    plonk::{
+        circuit_builder::CircuitBuilder,
+        circuit_data::{
+            CircuitConfig, CircuitData, CommonCircuitData, VerifierCircuitTarget,
+            VerifierOnlyCircuitData,
+        },
+        config::{AlgebraicHasher, GenericConfig},
+        proof::{ProofWithPublicInputs, ProofWithPublicInputsTarget},
+    },
+};
+
+use crate::{
+    introspection::gadgets::rollup::signature::compute_sig_action_hash_circuit,
+    state::user::change_public_key::ChangePublicKeyGadget,
+    worker::traits::QWorkerCircuitStandardWithDataSync,
+};
+
+#[derive(Debug, Clone)]
+pub struct ChangePublicKeySingleGadget {
+    // inputs:
+    pub change_public_key_gadget: ChangePublicKeyGadget,
+
+    // computed:
+    pub expected_signature_hash: HashOutTarget,
+    pub expected_public_key: HashOutTarget,
+    pub old_user_tree_root: HashOutTarget,
+    pub new_user_tree_root: HashOutTarget,
+}
+impl ChangePublicKeySingleGadget {
+    pub fn add_virtual_to<H: AlgebraicHasher<F>, F: RichField + Extendable<D>, const D: usize>(
+        builder: &mut CircuitBuilder<F, D>,
+        network_magic: u64,
+    ) -> Self {
+        let change_public_key_gadget = ChangePublicKeyGadget::add_virtual_to::<H, F, D>(builder);
+        let sig_action_id = builder.constant_u64(SIG_ACTION_CHANGE_PUBLIC_KEY_MAGIC);
+        let network_magic_target = builder.constant_u64(network_magic);
+        let user_id = change_public_key_gadget.old_user_state.user_id;
+        let new_user_nonce = change_public_key_gadget.new_user_state.nonce;
+        let new_public_key = change_public_key_gadget.new_public_key;
+
+        let expected_signature_hash = compute_sig_action_hash_circuit::<H, F, D>(
+            builder,
+            network_magic_target,
+            user_id,
+            sig_action_id,
+            new_user_nonce,
+            &new_public_key.elements,
+        );
+        // the key change must be authorized by the key that is being replaced
+        let expected_public_key = change_public_key_gadget.old_public_key;
+
+        let old_user_tree_root = change_public_key_gadget
+            .user_tree_nonce_delta_merkle_proof_gadget
+            .old_root;
+        let new_user_tree_root = change_public_key_gadget
+            .user_tree_public_key_delta_merkle_proof_gadget
+            .new_root;
+
+        Self {
+            change_public_key_gadget,
+            expected_signature_hash,
+            expected_public_key,
+            old_user_tree_root,
+            new_user_tree_root,
+        }
+    }
+    // binds the public inputs of the signature proof to this key change
+    pub fn connect_signature<F: RichField + Extendable<D>, const D: usize>(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        signature_public_key: HashOutTarget,
+        signature_message_hash: HashOutTarget,
+    ) {
+        // ensure the key change is signed with the user's current (old) public key
+        builder.connect_hashes(signature_public_key, self.expected_public_key);
+
+        // ensure the signature signs the correct message hash for this key change
+        builder.connect_hashes(signature_message_hash, self.expected_signature_hash);
+    }
+}
+
+#[derive(Debug)]
+pub struct CRChangePublicKeyCircuit<C: GenericConfig<D> + 'static, const D: usize>
+where
+    C::Hasher: AlgebraicHasher<C::F>,
+{
+    pub change_public_key_single_gadget: ChangePublicKeySingleGadget,
+    pub signature_proof_target: ProofWithPublicInputsTarget<D>,
+    pub signature_verifier_data_target: VerifierCircuitTarget,
+
+    pub allowed_circuit_hashes_root_target: HashOutTarget,
+    // end circuit targets
+    pub circuit_data: CircuitData<C::F, C, D>,
+    pub fingerprint: QHashOut<C::F>,
+    pub network_magic: u64,
+    // dependencies
+}
+impl<C: GenericConfig<D> + 'static, const D: usize> CRChangePublicKeyCircuit<C, D>
+where
+    C::Hasher: AlgebraicHasher<C::F>,
+{
+    pub fn new(network_magic: u64) -> Self {
+        let sig_wrapper = ZKSignatureWrapperCircuit::<C, D>::new().circuit_data;
+
+        Self::new_with_sig_wrapper_data(
+            network_magic,
+            &sig_wrapper.common,
+            sig_wrapper.verifier_only.constants_sigmas_cap.height(),
+            QHashOut(get_circuit_fingerprint_generic(&sig_wrapper.verifier_only)),
+        )
+    }
+    pub fn new_with_sig_wrapper_data(
+        network_magic: u64,
+        signature_circuit_common_data: &CommonCircuitData<C::F, D>,
+        signature_circuit_verifier_data_cap_height: usize,
+        signature_wrapper_fingerprint: QHashOut<C::F>,
+    ) -> Self {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<C::F, D>::new(config);
+        let change_public_key_single_gadget = ChangePublicKeySingleGadget::add_virtual_to::<
+            C::Hasher,
+            C::F,
+            D,
+        >(&mut builder, network_magic);
+
+        let signature_proof_target =
+            builder.add_virtual_proof_with_pis(&signature_circuit_common_data);
+        let signature_verifier_data_target =
+            builder.add_virtual_verifier_data(signature_circuit_verifier_data_cap_height);
+
+        let signature_proof_public_key = HashOutTarget {
+            elements: [
+                signature_proof_target.public_inputs[0],
+                signature_proof_target.public_inputs[1],
+                signature_proof_target.public_inputs[2],
+                signature_proof_target.public_inputs[3],
+            ],
+        };
+        let signature_proof_message_hash = HashOutTarget {
+            elements: [
+                signature_proof_target.public_inputs[4],
+                signature_proof_target.public_inputs[5],
+                signature_proof_target.public_inputs[6],
+                signature_proof_target.public_inputs[7],
+            ],
+        };
+
+        change_public_key_single_gadget.connect_signature(
+            &mut builder,
+            signature_proof_public_key,
+            signature_proof_message_hash,
+        );
+
+        // verify the signature proof
+        builder.verify_proof::<C>(
+            &signature_proof_target,
+            &signature_verifier_data_target,
+            &signature_circuit_common_data,
+        );
+        let actual_sig_wrapper_fingerprint =
+            builder.get_circuit_fingerprint::<C::Hasher>(&signature_verifier_data_target);
+        let expected_sig_wrapper_fingerprint =
+            builder.constant_hash(signature_wrapper_fingerprint.0);
+        builder.connect_hashes(
+            actual_sig_wrapper_fingerprint,
+            expected_sig_wrapper_fingerprint,
+        );
+        let allowed_circuit_hashes_root_target = builder.add_virtual_hash();
+
+        let state_transition_hash = builder.hash_two_to_one::<C::Hasher>(
+            change_public_key_single_gadget.old_user_tree_root,
+            change_public_key_single_gadget.new_user_tree_root,
+        );
+
+        builder.register_public_inputs(&allowed_circuit_hashes_root_target.elements);
+        builder.register_public_inputs(&state_transition_hash.elements);
+
+        pad_circuit_degree::<C::F, D>(&mut builder, 12);
+        let circuit_data = builder.build_cached::<C>("op_change_public_key");
+
+        let fingerprint = QHashOut(get_circuit_fingerprint_generic(&circuit_data.verifier_only));
+
+        Self {
+            change_public_key_single_gadget,
+            signature_proof_target,
+            allowed_circuit_hashes_root_target,
+            circuit_data,
+            fingerprint,
+            network_magic,
+            signature_verifier_data_target,
+        }
+    }
+    pub fn prove_base(
+        &self,
+        input: &CRChangePublicKeyCircuitInput<C::F>,
+        signature_proof: &ProofWithPublicInputs<C::F, C, D>,
+        signature_verifier_data: &VerifierOnlyCircuitData<C, D>,
+    ) -> anyhow::Result<ProofWithPublicInputs<C::F, C, D>> {
+        let mut pw = PartialWitness::new();
+        self.change_public_key_single_gadget
+            .change_public_key_gadget
+            .set_witness(
+                &mut pw,
+                &input.user_tree_nonce_delta_merkle_proof,
+                &input.user_tree_public_key_delta_merkle_proof,
+            );
+
+        pw.set_proof_with_pis_target(&self.signature_proof_target, signature_proof);
+        pw.set_verifier_data_target(
+            &self.signature_verifier_data_target,
+            &signature_verifier_data,
+        );
+        pw.set_hash_target(
+            self.allowed_circuit_hashes_root_target,
+            input.allowed_circuit_hashes_root.0,
+        );
+
+        self.circuit_data.prove(pw)
+    }
+}
+impl<C: GenericConfig<D> + 'static, const D: usize> QStandardCircuit<C, D>
+    for CRChangePublicKeyCircuit<C, D>
+where
+    C::Hasher: AlgebraicHasher<C::F>,
+{
+    fn get_fingerprint(&self) -> QHashOut<C::F> {
+        self.fingerprint
+    }
+
+    fn get_verifier_config_ref(&self) -> &VerifierOnlyCircuitData<C, D> {
+        &self.circuit_data.verifier_only
+    }
+
+    fn get_common_circuit_data_ref(&self) -> &CommonCircuitData<C::F, D> {
+        &self.circuit_data.common
+    }
+}
+
+impl<
+        V: QWorkerVerifyHelper<C, D>,
+        S: QProofStoreReaderSync,
+        C: GenericConfig<D> + 'static,
+        const D: usize,
+    > QWorkerCircuitStandardWithDataSync<V, S, CRChangePublicKeyCircuitInput<C::F>, C, D>
+    for CRChangePublicKeyCircuit<C, D>
+where
+    C::Hasher: AlgebraicHasher<C::F>,
+{
+    fn prove_q_worker_standard_with_input(
+        &self,
+        input: &CRChangePublicKeyCircuitInput<C::F>,
+        verify_helper: &V,
+        store: &S,
+        _job_id: QProvingJobDataID,
+    ) -> anyhow::Result<ProofWithPublicInputs<C::F, C, D>> {
+        let signature_proof = store.get_proof_by_id(input.signature_proof_id)?;
+
+        self.prove_base(
+            input,
+            &signature_proof,
+            verify_helper
+                .get_verifier_triplet_for_circuit_type(input.signature_proof_id.circuit_type)
+                .1,
+        )
+    }
+}
+
+pub type WCRChangePublicKeyCircuit<C, const D: usize> =
+    TreeProverLeafCircuitWrapper<CRChangePublicKeyCircuit<C, D>, C, D>;
+
+#[cfg(test)]
+mod tests {
+    use std::panic::AssertUnwindSafe;
+
+    use city_crypto::hash::qhashout::QHashOut;
+    use city_rollup_common::introspection::rollup::signature::QEDSigAction;
+    use city_store::{
+        config::{CityDeltaMerkleProof, CityHash, GlobalUserTreeStore},
+        models::kvq_merkle::model::KVQFixedConfigMerkleTreeModelCore,
+        store::city::base::CityStore,
+    };
+    use kvq::memory::simple::KVQSimpleMemoryBackingStore;
+    use plonky2::{
+        hash::poseidon::PoseidonHash,
+        iop::witness::{PartialWitness, WitnessWrite},
+        plonk::{
+            circuit_builder::CircuitBuilder,
+            circuit_data::CircuitConfig,
+            config::{GenericConfig, PoseidonGoldilocksConfig},
+        },
+    };
+
+    use super::ChangePublicKeySingleGadget;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = KVQSimpleMemoryBackingStore;
+
+    const NETWORK_MAGIC: u64 = 0x4c4c4f52;
+    const USER_ID: u64 = 3;
+
+    fn old_key() -> CityHash {
+        QHashOut::from_values(3, 3, 3, 3)
+    }
+    fn other_users_key() -> CityHash {
+        QHashOut::from_values(5, 5, 5, 5)
+    }
+    fn new_key() -> CityHash {
+        QHashOut::from_values(9, 9, 9, 9)
+    }
+
+    // users 3 and 5 are registered at checkpoint 1 with nonce 0
+    fn build_store() -> S {
+        let mut store = S::new();
+        CityStore::<S>::register_user(&mut store, 1, USER_ID, old_key()).unwrap();
+        CityStore::<S>::register_user(&mut store, 1, 5, other_users_key()).unwrap();
+        store
+    }
+
+    fn signed_hash(nonce: u64, new_public_key: CityHash) -> CityHash {
+        QHashOut(
+            QEDSigAction::<F>::new_change_public_key_action(
+                NETWORK_MAGIC,
+                USER_ID,
+                nonce,
+                new_public_key,
+            )
+            .get_hash::<PoseidonHash>(),
+        )
+    }
+
+    // true if the key change proves with a signature by signer over signed_hash
+    fn prove_change_public_key(
+        nonce_proof: &CityDeltaMerkleProof,
+        public_key_proof: &CityDeltaMerkleProof,
+        signer: CityHash,
+        signed_hash: CityHash,
+    ) -> bool {
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let gadget = ChangePublicKeySingleGadget::add_virtual_to::<PoseidonHash, F, D>(
+            &mut builder,
+            NETWORK_MAGIC,
+        );
+        // stand-ins for the public inputs of the signature proof
+        let signature_public_key = builder.add_virtual_hash();
+        let signature_message_hash = builder.add_virtual_hash();
+        gadget.connect_signature(&mut builder, signature_public_key, signature_message_hash);
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        gadget
+            .change_public_key_gadget
+            .set_witness(&mut pw, nonce_proof, public_key_proof);
+        pw.set_hash_target(signature_public_key, signer.0);
+        pw.set_hash_target(signature_message_hash, signed_hash.0);
+        // witness generation panics on some conflicting constraints instead of returning an error
+        match std::panic::catch_unwind(AssertUnwindSafe(|| data.prove(pw))) {
+            Ok(Ok(proof)) => data.verify(proof).is_ok(),
+            _ => false,
+        }
+    }
+
+    fn rotate_key(nonce: u64) -> (CityDeltaMerkleProof, CityDeltaMerkleProof) {
+        let mut store = build_store();
+        CityStore::<S>::change_user_public_key(&mut store, 2, USER_ID, new_key(), nonce).unwrap()
+    }
+
+    #[test]
+    fn rotation_signed_with_the_current_key_proves() {
+        let (nonce_proof, public_key_proof) = rotate_key(1);
+        assert!(prove_change_public_key(
+            &nonce_proof,
+            &public_key_proof,
+            old_key(),
+            signed_hash(1, new_key())
+        ));
+    }
+
+    #[test]
+    fn rotation_signed_with_the_new_key_is_rejected() {
+        let (nonce_proof, public_key_proof) = rotate_key(1);
+        assert!(!prove_change_public_key(
+            &nonce_proof,
+            &public_key_proof,
+            new_key(),
+            signed_hash(1, new_key())
+        ));
+    }
+
+    #[test]
+    fn rotation_signed_by_another_user_is_rejected() {
+        let (nonce_proof, public_key_proof) = rotate_key(1);
+        assert!(!prove_change_public_key(
+            &nonce_proof,
+            &public_key_proof,
+            other_users_key(),
+            signed_hash(1, new_key())
+        ));
+    }
+
+    #[test]
+    fn rotation_with_a_wrong_nonce_is_rejected() {
+        // the signature is for a different nonce than the one written to the user leaf
+        let (nonce_proof, public_key_proof) = rotate_key(1);
+        assert!(!prove_change_public_key(
+            &nonce_proof,
+            &public_key_proof,
+            old_key(),
+            signed_hash(2, new_key())
+        ));
+
+        // the nonce must increase, so a signature can not be replayed
+        let mut store = build_store();
+        assert!(
+            CityStore::<S>::change_user_public_key(&mut store, 2, USER_ID, new_key(), 0).is_err()
+        );
+        let left_leaf = CityStore::<S>::get_user_tree_leaf(&store, 2, USER_ID * 2).unwrap();
+        let nonce_proof =
+            GlobalUserTreeStore::<S>::set_leaf_fc(&mut store, 2, USER_ID * 2, left_leaf).unwrap();
+        let public_key_proof =
+            GlobalUserTreeStore::<S>::set_leaf_fc(&mut store, 2, USER_ID * 2 + 1, new_key())
+                .unwrap();
+        assert!(!prove_change_public_key(
+            &nonce_proof,
+            &public_key_proof,
+            old_key(),
+            signed_hash(0, new_key())
+        ));
+    }
+
+    #[test]
+    fn rotation_updates_the_public_key_index() {
+        let mut store = build_store();
+        CityStore::<S>::change_user_public_key(&mut store, 2, USER_ID, new_key(), 1).unwrap();
+        assert_eq!(
+            CityStore::<S>::get_user_ids_for_public_key(&store, new_key()).unwrap(),
+            vec![USER_ID]
+        );
+        assert!(
+            CityStore::<S>::get_user_ids_for_public_key(&store, old_key())
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(
+            CityStore::<S>::get_user_by_id(&store, 2, USER_ID)
+                .unwrap()
+                .public_key,
+            new_key()
+        );
+    }
+}
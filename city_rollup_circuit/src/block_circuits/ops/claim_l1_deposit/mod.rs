@@ -1,3 +1,4 @@
+use city_common_circuit::serialization::circuit_cache::CircuitBuilderCachedBuild;
 use city_common_circuit::{
     builder::{pad_circuit::pad_circuit_degree, verify::CircuitBuilderVerifyProofHelpers},
     circuits::traits::qstandard::QStandardCircuit,
@@ -103,7 +104,7 @@ where
             .register_public_inputs(&claim_single_gadget.combined_state_transition_hash.elements);
         pad_circuit_degree::<C::F, D>(&mut builder, 12);
 
-        let circuit_data = builder.build::<C>();
+        let circuit_data = builder.build_cached::<C>("op_claim_l1_deposit");
 
         let fingerprint = QHashOut(get_circuit_fingerprint_generic(&circuit_data.verifier_only));
         Self {
@@ -1,3 +1,4 @@
+use city_common_circuit::serialization::circuit_cache::CircuitBuilderCachedBuild;
 use city_common_circuit::{
     builder::{
         core::CircuitBuilderHelpersCore, hash::core::CircuitBuilderHashCore,
@@ -53,8 +54,10 @@ impl L2TransferSingleGadget {
     pub fn add_virtual_to<H: AlgebraicHasher<F>, F: RichField + Extendable<D>, const D: usize>(
         builder: &mut CircuitBuilder<F, D>,
         network_magic: u64,
+        fee_recipient_user_id: u64,
     ) -> Self {
-        let l2_transfer_gadget = L2TransferStateUpdateGadget::add_virtual_to::<H, F, D>(builder);
+        let l2_transfer_gadget =
+            L2TransferStateUpdateGadget::add_virtual_to::<H, F, D>(builder, fee_recipient_user_id);
         let sig_action_id = builder.constant_u64(SIG_ACTION_TRANSFER_MAGIC);
         let network_magic_target = builder.constant_u64(network_magic);
         let sender_user_id = l2_transfer_gadget.sender_old_user_state.user_id;
@@ -62,6 +65,7 @@ impl L2TransferSingleGadget {
         let new_sender_user_nonce = l2_transfer_gadget.sender_new_user_state.nonce;
 
         let amount = l2_transfer_gadget.transfer_amount;
+        let fee = l2_transfer_gadget.fee;
 
         let expected_signature_hash = compute_sig_action_hash_circuit::<H, F, D>(
             builder,
@@ -69,7 +73,7 @@ impl L2TransferSingleGadget {
             sender_user_id,
             sig_action_id,
             new_sender_user_nonce,
-            &[recipient_user_id, amount],
+            &[recipient_user_id, amount, fee],
         );
         let expected_public_key = l2_transfer_gadget.sender_old_user_state.public_key;
 
@@ -77,7 +81,8 @@ impl L2TransferSingleGadget {
             .sender_user_tree_delta_merkle_proof_gadget
             .old_root;
         let new_user_tree_root = l2_transfer_gadget
-            .receiver_user_tree_delta_merkle_proof_gadget
+            .fee_credit_gadget
+            .fee_recipient_user_tree_delta_merkle_proof_gadget
             .new_root;
 
         Self {
@@ -104,17 +109,19 @@ where
     pub circuit_data: CircuitData<C::F, C, D>,
     pub fingerprint: QHashOut<C::F>,
     pub network_magic: u64,
+    pub fee_recipient_user_id: u64,
     // dependencies
 }
 impl<C: GenericConfig<D> + 'static, const D: usize> CRL2TransferCircuit<C, D>
 where
     C::Hasher: AlgebraicHasher<C::F>,
 {
-    pub fn new(network_magic: u64) -> Self {
+    pub fn new(network_magic: u64, fee_recipient_user_id: u64) -> Self {
         let sig_wrapper = ZKSignatureWrapperCircuit::<C, D>::new().circuit_data;
 
         Self::new_with_sig_wrapper_data(
             network_magic,
+            fee_recipient_user_id,
             &sig_wrapper.common,
             sig_wrapper.verifier_only.constants_sigmas_cap.height(),
             QHashOut(get_circuit_fingerprint_generic(&sig_wrapper.verifier_only)),
@@ -122,6 +129,7 @@ where
     }
     pub fn new_with_sig_wrapper_data(
         network_magic: u64,
+        fee_recipient_user_id: u64,
         signature_circuit_common_data: &CommonCircuitData<C::F, D>,
         signature_circuit_verifier_data_cap_height: usize,
         signature_wrapper_fingerprint: QHashOut<C::F>,
@@ -131,6 +139,7 @@ where
         let l2_transfer_single_gadget = L2TransferSingleGadget::add_virtual_to::<C::Hasher, C::F, D>(
             &mut builder,
             network_magic,
+            fee_recipient_user_id,
         );
 
         let signature_proof_target =
@@ -192,7 +201,7 @@ where
         builder.register_public_inputs(&state_transition_hash.elements);
 
         pad_circuit_degree::<C::F, D>(&mut builder, 12);
-        let circuit_data = builder.build::<C>();
+        let circuit_data = builder.build_cached::<C>("op_l2_transfer");
 
         let fingerprint = QHashOut(get_circuit_fingerprint_generic(&circuit_data.verifier_only));
 
@@ -203,6 +212,7 @@ where
             circuit_data,
             fingerprint,
             network_magic,
+            fee_recipient_user_id,
             signature_verifier_data_target,
         }
     }
@@ -219,6 +229,8 @@ where
                 &mut pw,
                 &input.sender_user_tree_delta_merkle_proof,
                 &input.receiver_user_tree_delta_merkle_proof,
+                &input.fee_recipient_user_tree_delta_merkle_proof,
+                input.fee,
             );
 
         pw.set_proof_with_pis_target(&self.signature_proof_target, signature_proof);
@@ -1,6 +1,13 @@
 pub mod add_l1_deposit;
 pub mod add_l1_withdrawal;
+pub mod batch_l2_transfer;
+pub mod burn_token;
+pub mod cancel_l1_withdrawal;
+pub mod change_public_key;
 pub mod claim_l1_deposit;
 pub mod l2_transfer;
+pub mod mint_token;
+pub mod open_token_account;
 pub mod process_l1_withdrawal;
 pub mod register_user;
+pub mod send_token;
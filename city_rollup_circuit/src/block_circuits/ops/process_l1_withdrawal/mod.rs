@@ -1,4 +1,5 @@
 use city_common::config::rollup_constants::L1_WITHDRAWAL_TREE_HEIGHT;
+use city_common_circuit::serialization::circuit_cache::CircuitBuilderCachedBuild;
 use city_common_circuit::{
     builder::{
         hash::core::CircuitBuilderHashCore,
@@ -19,9 +20,13 @@ use city_rollup_common::qworker::{
     job_witnesses::op::CRProcessL1WithdrawalCircuitInput, proof_store::QProofStoreReaderSync,
 };
 use plonky2::{
+    field::types::Field,
     gates::gate::GateRef,
     hash::hash_types::{HashOut, HashOutTarget},
-    iop::witness::{PartialWitness, WitnessWrite},
+    iop::{
+        target::Target,
+        witness::{PartialWitness, WitnessWrite},
+    },
     plonk::{
         circuit_builder::CircuitBuilder,
         circuit_data::{CircuitConfig, CircuitData, CommonCircuitData, VerifierOnlyCircuitData},
@@ -30,18 +35,22 @@ use plonky2::{
     },
 };
 
+use crate::state::user::add_l1_withdrawal::compute_withdrawal_leaf_hash_circuit;
+
 #[derive(Debug)]
 pub struct CRProcessL1WithdrawalCircuit<C: GenericConfig<D>, const D: usize>
 where
     C::Hasher: AlgebraicHasher<C::F>,
 {
     pub delta_merkle_proof_gadget: DeltaMerkleProofGadget,
+    pub withdrawal_hash_target: HashOutTarget,
+    pub owner_user_id_target: Target,
     pub allowed_circuit_hashes_root_target: HashOutTarget,
     // end circuit targets
     pub circuit_data: CircuitData<C::F, C, D>,
     pub fingerprint: QHashOut<C::F>,
 }
-impl<C: GenericConfig<D>, const D: usize> CRProcessL1WithdrawalCircuit<C, D>
+impl<C: GenericConfig<D> + 'static, const D: usize> CRProcessL1WithdrawalCircuit<C, D>
 where
     C::Hasher: AlgebraicHasher<C::F> + MerkleZeroHasher<HashOut<C::F>>,
 {
@@ -58,7 +67,17 @@ where
             delta_merkle_proof_gadget.new_root,
         );
 
-        let event_transition_hash = delta_merkle_proof_gadget.old_value;
+        // the leaf commits to the withdrawal's owner, the event is the hash of the L1 output
+        let withdrawal_hash_target = builder.add_virtual_hash();
+        let owner_user_id_target = builder.add_virtual_target();
+        let withdrawal_leaf_hash = compute_withdrawal_leaf_hash_circuit::<C::Hasher, C::F, D>(
+            &mut builder,
+            withdrawal_hash_target,
+            owner_user_id_target,
+        );
+        builder.connect_hashes(delta_merkle_proof_gadget.old_value, withdrawal_leaf_hash);
+
+        let event_transition_hash = withdrawal_hash_target;
         let allowed_circuit_hashes_root_target = builder.add_virtual_hash();
 
         builder.register_public_inputs(&allowed_circuit_hashes_root_target.elements);
@@ -68,12 +87,14 @@ where
         builder.add_city_common_gates(Some(coset_gate.clone()));
 
         pad_circuit_degree::<C::F, D>(&mut builder, 12);
-        let circuit_data = builder.build::<C>();
+        let circuit_data = builder.build_cached::<C>("op_process_l1_withdrawal");
 
         let fingerprint = QHashOut(get_circuit_fingerprint_generic(&circuit_data.verifier_only));
 
         Self {
             delta_merkle_proof_gadget,
+            withdrawal_hash_target,
+            owner_user_id_target,
             allowed_circuit_hashes_root_target,
             circuit_data,
             fingerprint,
@@ -82,6 +103,8 @@ where
     pub fn prove_base(
         &self,
         delta_merkle_proof: &DeltaMerkleProofCore<QHashOut<C::F>>,
+        withdrawal_hash: QHashOut<C::F>,
+        owner_user_id: u64,
         allowed_circuit_hashes_root: QHashOut<C::F>,
     ) -> ProofWithPublicInputs<C::F, C, D> {
         let mut pw = PartialWitness::new();
@@ -91,6 +114,11 @@ where
         );
         self.delta_merkle_proof_gadget
             .set_witness_core_proof_q(&mut pw, &delta_merkle_proof);
+        pw.set_hash_target(self.withdrawal_hash_target, withdrawal_hash.0);
+        pw.set_target(
+            self.owner_user_id_target,
+            C::F::from_noncanonical_u64(owner_user_id),
+        );
         self.circuit_data.prove(pw).unwrap()
     }
 }
@@ -123,6 +151,8 @@ where
     ) -> anyhow::Result<ProofWithPublicInputs<C::F, C, D>> {
         Ok(self.prove_base(
             &input.withdrawal_tree_delta_merkle_proof,
+            input.withdrawal_hash,
+            input.owner_user_id,
             input.allowed_circuit_hashes_root,
         ))
     }
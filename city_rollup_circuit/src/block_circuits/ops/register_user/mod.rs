@@ -1,6 +1,9 @@
-use city_common::config::rollup_constants::GLOBAL_USER_TREE_HEIGHT;
+use city_common::config::rollup_constants::{GLOBAL_USER_TREE_HEIGHT, TOKEN_STATE_USER_ID};
+use city_common_circuit::serialization::circuit_cache::CircuitBuilderCachedBuild;
 use city_common_circuit::{
     builder::{
+        comparison::CircuitBuilderComparison,
+        core::CircuitBuilderHelpersCore,
         hash::core::CircuitBuilderHashCore,
         pad_circuit::{pad_circuit_degree, CircuitBuilderCityCommonGates},
     },
@@ -19,9 +22,10 @@ use city_rollup_common::qworker::{
     job_witnesses::op::CRUserRegistrationCircuitInput, proof_store::QProofStoreReaderSync,
 };
 use plonky2::{
+    field::extension::Extendable,
     gates::gate::GateRef,
-    hash::hash_types::{HashOut, HashOutTarget},
-    iop::witness::{PartialWitness, WitnessWrite},
+    hash::hash_types::{HashOut, HashOutTarget, RichField},
+    iop::witness::{PartialWitness, Witness, WitnessWrite},
     plonk::{
         circuit_builder::CircuitBuilder,
         circuit_data::{CircuitConfig, CircuitData, CommonCircuitData, VerifierOnlyCircuitData},
@@ -30,29 +34,67 @@ use plonky2::{
     },
 };
 
+#[derive(Debug, Clone)]
+pub struct UserRegistrationSingleGadget {
+    // inputs:
+    pub delta_merkle_proof_gadget: DeltaMerkleProofGadget,
+}
+impl UserRegistrationSingleGadget {
+    pub fn add_virtual_to<
+        H: AlgebraicHasher<F> + MerkleZeroHasher<HashOut<F>>,
+        F: RichField + Extendable<D>,
+        const D: usize,
+    >(
+        builder: &mut CircuitBuilder<F, D>,
+    ) -> Self {
+        let delta_merkle_proof_gadget: DeltaMerkleProofGadget =
+            DeltaMerkleProofGadget::add_virtual_to_append_only_skip_left::<H, F, D>(
+                builder,
+                GLOBAL_USER_TREE_HEIGHT as usize,
+            );
+
+        // user 0's leaves hold the token state, so its public key leaf can never be registered
+        let token_state_public_key_leaf_index = builder.constant_u64(TOKEN_STATE_USER_ID * 2 + 1);
+        builder.ensure_not_equal(
+            delta_merkle_proof_gadget.index,
+            token_state_public_key_leaf_index,
+        );
+
+        Self {
+            delta_merkle_proof_gadget,
+        }
+    }
+    pub fn set_witness<W: Witness<F>, F: RichField>(
+        &self,
+        witness: &mut W,
+        delta_merkle_proof: &DeltaMerkleProofCore<QHashOut<F>>,
+    ) {
+        self.delta_merkle_proof_gadget
+            .set_witness_core_proof_q(witness, delta_merkle_proof);
+    }
+}
+
 #[derive(Debug)]
 pub struct CRUserRegistrationCircuit<C: GenericConfig<D>, const D: usize>
 where
     C::Hasher: AlgebraicHasher<C::F>,
 {
-    pub delta_merkle_proof_gadget: DeltaMerkleProofGadget,
+    pub user_registration_single_gadget: UserRegistrationSingleGadget,
     pub allowed_circuit_hashes_root_target: HashOutTarget,
     // end circuit targets
     pub circuit_data: CircuitData<C::F, C, D>,
     pub fingerprint: QHashOut<C::F>,
 }
-impl<C: GenericConfig<D>, const D: usize> CRUserRegistrationCircuit<C, D>
+impl<C: GenericConfig<D> + 'static, const D: usize> CRUserRegistrationCircuit<C, D>
 where
     C::Hasher: AlgebraicHasher<C::F> + MerkleZeroHasher<HashOut<C::F>>,
 {
     pub fn new(coset_gate: &GateRef<C::F, D>) -> Self {
         let config = CircuitConfig::standard_recursion_config();
         let mut builder = CircuitBuilder::<C::F, D>::new(config);
-        let delta_merkle_proof_gadget: DeltaMerkleProofGadget =
-            DeltaMerkleProofGadget::add_virtual_to_append_only_skip_left::<C::Hasher, C::F, D>(
-                &mut builder,
-                GLOBAL_USER_TREE_HEIGHT as usize,
-            );
+        let user_registration_single_gadget =
+            UserRegistrationSingleGadget::add_virtual_to::<C::Hasher, C::F, D>(&mut builder);
+        let delta_merkle_proof_gadget = &user_registration_single_gadget.delta_merkle_proof_gadget;
 
         let state_transition_hash = builder.hash_two_to_one::<C::Hasher>(
             delta_merkle_proof_gadget.old_root,
@@ -71,12 +113,12 @@ where
         builder.add_city_common_gates(Some(coset_gate.clone()));
         pad_circuit_degree::<C::F, D>(&mut builder, 12);
 
-        let circuit_data = builder.build::<C>();
+        let circuit_data = builder.build_cached::<C>("op_register_user");
 
         let fingerprint = QHashOut(get_circuit_fingerprint_generic(&circuit_data.verifier_only));
 
         Self {
-            delta_merkle_proof_gadget,
+            user_registration_single_gadget,
             allowed_circuit_hashes_root_target,
             circuit_data,
             fingerprint,
@@ -92,8 +134,8 @@ where
             self.allowed_circuit_hashes_root_target,
             allowed_circuit_hashes_root.0,
         );
-        self.delta_merkle_proof_gadget
-            .set_witness_core_proof_q(&mut pw, &delta_merkle_proof);
+        self.user_registration_single_gadget
+            .set_witness(&mut pw, &delta_merkle_proof);
         self.circuit_data.prove(pw).unwrap()
     }
 }
@@ -148,3 +190,71 @@ where
 
 pub type WCRUserRegistrationCircuit<C, const D: usize> =
     TreeProverLeafCircuitWrapper<CRUserRegistrationCircuit<C, D>, C, D>;
+
+#[cfg(test)]
+mod tests {
+    use std::panic::AssertUnwindSafe;
+
+    use city_common::config::rollup_constants::TOKEN_STATE_USER_ID;
+    use city_crypto::hash::qhashout::QHashOut;
+    use city_store::{
+        config::{CityDeltaMerkleProof, GlobalUserTreeStore},
+        models::kvq_merkle::model::KVQFixedConfigMerkleTreeModelCore,
+        store::city::base::CityStore,
+    };
+    use kvq::memory::simple::KVQSimpleMemoryBackingStore;
+    use plonky2::{
+        hash::poseidon::PoseidonHash,
+        iop::witness::PartialWitness,
+        plonk::{
+            circuit_builder::CircuitBuilder,
+            circuit_data::CircuitConfig,
+            config::{GenericConfig, PoseidonGoldilocksConfig},
+        },
+    };
+
+    use super::UserRegistrationSingleGadget;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = KVQSimpleMemoryBackingStore;
+
+    fn prove_registration(proof: &CityDeltaMerkleProof) -> bool {
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let gadget =
+            UserRegistrationSingleGadget::add_virtual_to::<PoseidonHash, F, D>(&mut builder);
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        gadget.set_witness(&mut pw, proof);
+        // witness generation panics on some conflicting constraints instead of returning an error
+        match std::panic::catch_unwind(AssertUnwindSafe(|| data.prove(pw))) {
+            Ok(Ok(proof)) => data.verify(proof).is_ok(),
+            _ => false,
+        }
+    }
+
+    #[test]
+    fn registration_proves() {
+        let mut store = S::new();
+        let proof =
+            CityStore::<S>::register_user(&mut store, 1, 3, QHashOut::from_values(3, 3, 3, 3))
+                .unwrap();
+        assert!(prove_registration(&proof));
+    }
+
+    #[test]
+    fn registration_of_the_token_state_user_is_rejected() {
+        // the store refuses to register user 0, so the leaf is written directly
+        let mut store = S::new();
+        let proof = GlobalUserTreeStore::<S>::set_leaf_fc(
+            &mut store,
+            1,
+            TOKEN_STATE_USER_ID * 2 + 1,
+            QHashOut::from_values(3, 3, 3, 3),
+        )
+        .unwrap();
+        assert!(!prove_registration(&proof));
+    }
+}
@@ -1,3 +1,4 @@
+use city_common_circuit::serialization::circuit_cache::CircuitBuilderCachedBuild;
 use city_common_circuit::{
     circuits::traits::qstandard::QStandardCircuit,
     proof_minifier::pm_chain::QEDProofMinifierChain,
@@ -39,6 +40,9 @@ pub struct CRAggAddProcessL1WithdrawalAddL1DepositCircuit<
     pub op_add_l1_withdrawal_proof: ProofWithPublicInputsTarget<D>,
     pub op_add_l1_withdrawal_verifier_data: VerifierCircuitTarget,
 
+    pub op_cancel_l1_withdrawal_proof: ProofWithPublicInputsTarget<D>,
+    pub op_cancel_l1_withdrawal_verifier_data: VerifierCircuitTarget,
+
     pub op_process_l1_withdrawal_proof: ProofWithPublicInputsTarget<D>,
     pub op_process_l1_withdrawal_verifier_data: VerifierCircuitTarget,
 
@@ -49,6 +53,7 @@ pub struct CRAggAddProcessL1WithdrawalAddL1DepositCircuit<
     // end circuit targets
     pub minifier_chain: QEDProofMinifierChain<D, C::F, C>,
     pub op_add_l1_withdrawal_fingerprint: TPCircuitFingerprintConfig<C::F>,
+    pub op_cancel_l1_withdrawal_fingerprint: TPCircuitFingerprintConfig<C::F>,
     pub op_process_l1_withdrawal_fingerprint: TPCircuitFingerprintConfig<C::F>,
     pub op_add_l1_deposit_fingerprint: TPCircuitFingerprintConfig<C::F>,
     pub circuit_data: CircuitData<C::F, C, D>,
@@ -60,6 +65,7 @@ where
 {
     pub fn new(
         op_add_l1_withdrawal_fingerprint: TPCircuitFingerprintConfig<C::F>,
+        op_cancel_l1_withdrawal_fingerprint: TPCircuitFingerprintConfig<C::F>,
         op_process_l1_withdrawal_fingerprint: TPCircuitFingerprintConfig<C::F>,
         op_add_l1_deposit_fingerprint: TPCircuitFingerprintConfig<C::F>,
         child_common_data: &CommonCircuitData<C::F, D>,
@@ -74,6 +80,10 @@ where
         let op_add_l1_withdrawal_verifier_data =
             builder.add_virtual_verifier_data(child_verifier_cap_height);
 
+        let op_cancel_l1_withdrawal_proof = builder.add_virtual_proof_with_pis(child_common_data);
+        let op_cancel_l1_withdrawal_verifier_data =
+            builder.add_virtual_verifier_data(child_verifier_cap_height);
+
         let op_process_l1_withdrawal_proof =
             builder.add_virtual_proof_with_pis(child_with_events_common_data);
         let op_process_l1_withdrawal_verifier_data =
@@ -90,6 +100,12 @@ where
             child_common_data,
         );
 
+        builder.verify_proof::<C>(
+            &op_cancel_l1_withdrawal_proof,
+            &op_cancel_l1_withdrawal_verifier_data,
+            child_common_data,
+        );
+
         builder.verify_proof::<C>(
             &op_process_l1_withdrawal_proof,
             &op_process_l1_withdrawal_verifier_data,
@@ -110,6 +126,14 @@ where
                 &op_add_l1_withdrawal_fingerprint,
             );
 
+        let actual_op_cancel_l1_withdrawal_combined_state_transition =
+            AggStateTransitionProofValidityGadget::add_virtual_to::<C::Hasher, C::F, D>(
+                &mut builder,
+                &op_cancel_l1_withdrawal_proof,
+                &op_cancel_l1_withdrawal_verifier_data,
+                &op_cancel_l1_withdrawal_fingerprint,
+            );
+
         let actual_op_process_l1_withdrawal_combined_state_transition =
             AggStateTransitionWithEventsProofValidityGadget::add_virtual_to::<C::Hasher, C::F, D>(
                 &mut builder,
@@ -133,6 +157,7 @@ where
         transition_gadget.connect_to_proof_results::<C::Hasher, C::F, D>(
             &mut builder,
             actual_op_add_l1_withdrawal_combined_state_transition,
+            actual_op_cancel_l1_withdrawal_combined_state_transition,
             actual_op_process_l1_withdrawal_combined_state_transition
                 .state_transition_combined_hash,
             actual_op_add_l1_deposit_combined_state_transition.state_transition_combined_hash,
@@ -150,18 +175,22 @@ where
                 .elements,
         );
 
-        let circuit_data = builder.build::<C>();
+        let circuit_data =
+            builder.build_cached::<C>("block_agg_add_process_withdrawal_add_deposit");
         let minifier_chain =
             QEDProofMinifierChain::new(&circuit_data.verifier_only, &circuit_data.common, 1);
         Self {
             op_add_l1_withdrawal_proof,
             op_add_l1_withdrawal_verifier_data,
+            op_cancel_l1_withdrawal_proof,
+            op_cancel_l1_withdrawal_verifier_data,
             op_process_l1_withdrawal_proof,
             op_process_l1_withdrawal_verifier_data,
             op_add_l1_deposit_proof,
             op_add_l1_deposit_verifier_data,
             transition_gadget,
             op_add_l1_withdrawal_fingerprint,
+            op_cancel_l1_withdrawal_fingerprint,
             op_process_l1_withdrawal_fingerprint,
             op_add_l1_deposit_fingerprint,
             circuit_data,
@@ -173,6 +202,8 @@ where
         input: &CRAggAddProcessL1WithdrawalAddL1DepositCircuitInput<C::F>,
         op_add_l1_withdrawal_proof: &ProofWithPublicInputs<C::F, C, D>,
         op_add_l1_withdrawal_verifier_data: &VerifierOnlyCircuitData<C, D>,
+        op_cancel_l1_withdrawal_proof: &ProofWithPublicInputs<C::F, C, D>,
+        op_cancel_l1_withdrawal_verifier_data: &VerifierOnlyCircuitData<C, D>,
         op_process_l1_withdrawal_proof: &ProofWithPublicInputs<C::F, C, D>,
         op_process_l1_withdrawal_verifier_data: &VerifierOnlyCircuitData<C, D>,
         op_add_l1_deposit_proof: &ProofWithPublicInputs<C::F, C, D>,
@@ -189,6 +220,15 @@ where
             op_add_l1_withdrawal_verifier_data,
         );
 
+        pw.set_proof_with_pis_target::<C, D>(
+            &self.op_cancel_l1_withdrawal_proof,
+            op_cancel_l1_withdrawal_proof,
+        );
+        pw.set_verifier_data_target::<C, D>(
+            &self.op_cancel_l1_withdrawal_verifier_data,
+            op_cancel_l1_withdrawal_verifier_data,
+        );
+
         pw.set_proof_with_pis_target::<C, D>(
             &self.op_process_l1_withdrawal_proof,
             op_process_l1_withdrawal_proof,
@@ -261,6 +301,13 @@ where
                     .circuit_type
                     .try_into()?,
             );
+        let (_, op_cancel_l1_withdrawal_verifier_data, _) = verify_helper
+            .get_verifier_triplet_for_circuit_type(
+                input
+                    .op_cancel_l1_withdrawal_proof_id
+                    .circuit_type
+                    .try_into()?,
+            );
         let (_, op_process_l1_withdrawal_verifier_data, _) = verify_helper
             .get_verifier_triplet_for_circuit_type(
                 input
@@ -275,6 +322,8 @@ where
 
         let op_add_l1_withdrawal_proof =
             store.get_proof_by_id(input.op_add_l1_withdrawal_proof_id)?;
+        let op_cancel_l1_withdrawal_proof =
+            store.get_proof_by_id(input.op_cancel_l1_withdrawal_proof_id)?;
         let op_process_l1_withdrawal_proof =
             store.get_proof_by_id(input.op_process_l1_withdrawal_proof_id)?;
         let op_add_l1_deposit_proof = store.get_proof_by_id(input.op_add_l1_deposit_proof_id)?;
@@ -283,6 +332,8 @@ where
             &input,
             &op_add_l1_withdrawal_proof,
             &op_add_l1_withdrawal_verifier_data,
+            &op_cancel_l1_withdrawal_proof,
+            &op_cancel_l1_withdrawal_verifier_data,
             &op_process_l1_withdrawal_proof,
             &op_process_l1_withdrawal_verifier_data,
             &op_add_l1_deposit_proof,
@@ -1,3 +1,4 @@
+use city_common_circuit::serialization::circuit_cache::CircuitBuilderCachedBuild;
 use city_common_circuit::{
     circuits::traits::qstandard::QStandardCircuit, proof_minifier::pm_chain::QEDProofMinifierChain,
     treeprover::aggregation::gadgets::AggStateTransitionProofValidityGadget,
@@ -42,12 +43,36 @@ pub struct CRAggUserRegisterClaimDepositL2TransferCircuit<
     pub op_l2_transfer_proof: ProofWithPublicInputsTarget<D>,
     pub op_l2_transfer_verifier_data: VerifierCircuitTarget,
 
+    pub op_change_public_key_proof: ProofWithPublicInputsTarget<D>,
+    pub op_change_public_key_verifier_data: VerifierCircuitTarget,
+
+    pub op_batch_l2_transfer_proof: ProofWithPublicInputsTarget<D>,
+    pub op_batch_l2_transfer_verifier_data: VerifierCircuitTarget,
+
+    pub op_open_token_account_proof: ProofWithPublicInputsTarget<D>,
+    pub op_open_token_account_verifier_data: VerifierCircuitTarget,
+
+    pub op_mint_token_proof: ProofWithPublicInputsTarget<D>,
+    pub op_mint_token_verifier_data: VerifierCircuitTarget,
+
+    pub op_send_token_proof: ProofWithPublicInputsTarget<D>,
+    pub op_send_token_verifier_data: VerifierCircuitTarget,
+
+    pub op_burn_token_proof: ProofWithPublicInputsTarget<D>,
+    pub op_burn_token_verifier_data: VerifierCircuitTarget,
+
     pub transition_gadget: AggUserRegisterClaimDepositL2TransferGadget,
     // end circuit targets
     pub minifier_chain: QEDProofMinifierChain<D, C::F, C>,
     pub op_register_user_fingerprint: TPCircuitFingerprintConfig<C::F>,
     pub op_claim_l1_deposit_fingerprint: TPCircuitFingerprintConfig<C::F>,
     pub op_l2_transfer_fingerprint: TPCircuitFingerprintConfig<C::F>,
+    pub op_change_public_key_fingerprint: TPCircuitFingerprintConfig<C::F>,
+    pub op_batch_l2_transfer_fingerprint: TPCircuitFingerprintConfig<C::F>,
+    pub op_open_token_account_fingerprint: TPCircuitFingerprintConfig<C::F>,
+    pub op_mint_token_fingerprint: TPCircuitFingerprintConfig<C::F>,
+    pub op_send_token_fingerprint: TPCircuitFingerprintConfig<C::F>,
+    pub op_burn_token_fingerprint: TPCircuitFingerprintConfig<C::F>,
     pub circuit_data: CircuitData<C::F, C, D>,
 }
 impl<C: GenericConfig<D> + 'static, const D: usize>
@@ -59,6 +84,12 @@ where
         op_register_user_fingerprint: TPCircuitFingerprintConfig<C::F>,
         op_claim_l1_deposit_fingerprint: TPCircuitFingerprintConfig<C::F>,
         op_l2_transfer_fingerprint: TPCircuitFingerprintConfig<C::F>,
+        op_change_public_key_fingerprint: TPCircuitFingerprintConfig<C::F>,
+        op_batch_l2_transfer_fingerprint: TPCircuitFingerprintConfig<C::F>,
+        op_open_token_account_fingerprint: TPCircuitFingerprintConfig<C::F>,
+        op_mint_token_fingerprint: TPCircuitFingerprintConfig<C::F>,
+        op_send_token_fingerprint: TPCircuitFingerprintConfig<C::F>,
+        op_burn_token_fingerprint: TPCircuitFingerprintConfig<C::F>,
         child_common_data: &CommonCircuitData<C::F, D>,
         child_verifier_cap_height: usize,
     ) -> Self {
@@ -77,6 +108,30 @@ where
         let op_l2_transfer_verifier_data =
             builder.add_virtual_verifier_data(child_verifier_cap_height);
 
+        let op_change_public_key_proof = builder.add_virtual_proof_with_pis(child_common_data);
+        let op_change_public_key_verifier_data =
+            builder.add_virtual_verifier_data(child_verifier_cap_height);
+
+        let op_batch_l2_transfer_proof = builder.add_virtual_proof_with_pis(child_common_data);
+        let op_batch_l2_transfer_verifier_data =
+            builder.add_virtual_verifier_data(child_verifier_cap_height);
+
+        let op_open_token_account_proof = builder.add_virtual_proof_with_pis(child_common_data);
+        let op_open_token_account_verifier_data =
+            builder.add_virtual_verifier_data(child_verifier_cap_height);
+
+        let op_mint_token_proof = builder.add_virtual_proof_with_pis(child_common_data);
+        let op_mint_token_verifier_data =
+            builder.add_virtual_verifier_data(child_verifier_cap_height);
+
+        let op_send_token_proof = builder.add_virtual_proof_with_pis(child_common_data);
+        let op_send_token_verifier_data =
+            builder.add_virtual_verifier_data(child_verifier_cap_height);
+
+        let op_burn_token_proof = builder.add_virtual_proof_with_pis(child_common_data);
+        let op_burn_token_verifier_data =
+            builder.add_virtual_verifier_data(child_verifier_cap_height);
+
         builder.verify_proof::<C>(
             &op_register_user_proof,
             &op_register_user_verifier_data,
@@ -95,6 +150,50 @@ where
             child_common_data,
         );
 
+        builder.verify_proof::<C>(
+            &op_change_public_key_proof,
+            &op_change_public_key_verifier_data,
+            child_common_data,
+        );
+
+        builder.verify_proof::<C>(
+            &op_batch_l2_transfer_proof,
+            &op_batch_l2_transfer_verifier_data,
+            &op_open_token_account_proof,
+            &op_open_token_account_verifier_data,
+            &op_mint_token_proof,
+            &op_mint_token_verifier_data,
+            &op_send_token_proof,
+            &op_send_token_verifier_data,
+            &op_burn_token_proof,
+            &op_burn_token_verifier_data,
+            child_common_data,
+        );
+
+        builder.verify_proof::<C>(
+            &op_open_token_account_proof,
+            &op_open_token_account_verifier_data,
+            child_common_data,
+        );
+
+        builder.verify_proof::<C>(
+            &op_mint_token_proof,
+            &op_mint_token_verifier_data,
+            child_common_data,
+        );
+
+        builder.verify_proof::<C>(
+            &op_send_token_proof,
+            &op_send_token_verifier_data,
+            child_common_data,
+        );
+
+        builder.verify_proof::<C>(
+            &op_burn_token_proof,
+            &op_burn_token_verifier_data,
+            child_common_data,
+        );
+
         let actual_op_register_user_combined_state_transition =
             AggStateTransitionProofValidityGadget::add_virtual_to::<C::Hasher, C::F, D>(
                 &mut builder,
@@ -117,6 +216,48 @@ where
                 &op_l2_transfer_verifier_data,
                 &op_l2_transfer_fingerprint,
             );
+        let actual_op_change_public_key_combined_state_transition =
+            AggStateTransitionProofValidityGadget::add_virtual_to::<C::Hasher, C::F, D>(
+                &mut builder,
+                &op_change_public_key_proof,
+                &op_change_public_key_verifier_data,
+                &op_change_public_key_fingerprint,
+            );
+        let actual_op_batch_l2_transfer_combined_state_transition =
+            AggStateTransitionProofValidityGadget::add_virtual_to::<C::Hasher, C::F, D>(
+                &mut builder,
+                &op_batch_l2_transfer_proof,
+                &op_batch_l2_transfer_verifier_data,
+                &op_batch_l2_transfer_fingerprint,
+            );
+        let actual_op_open_token_account_combined_state_transition =
+            AggStateTransitionProofValidityGadget::add_virtual_to::<C::Hasher, C::F, D>(
+                &mut builder,
+                &op_open_token_account_proof,
+                &op_open_token_account_verifier_data,
+                &op_open_token_account_fingerprint,
+            );
+        let actual_op_mint_token_combined_state_transition =
+            AggStateTransitionProofValidityGadget::add_virtual_to::<C::Hasher, C::F, D>(
+                &mut builder,
+                &op_mint_token_proof,
+                &op_mint_token_verifier_data,
+                &op_mint_token_fingerprint,
+            );
+        let actual_op_send_token_combined_state_transition =
+            AggStateTransitionProofValidityGadget::add_virtual_to::<C::Hasher, C::F, D>(
+                &mut builder,
+                &op_send_token_proof,
+                &op_send_token_verifier_data,
+                &op_send_token_fingerprint,
+            );
+        let actual_op_burn_token_combined_state_transition =
+            AggStateTransitionProofValidityGadget::add_virtual_to::<C::Hasher, C::F, D>(
+                &mut builder,
+                &op_burn_token_proof,
+                &op_burn_token_verifier_data,
+                &op_burn_token_fingerprint,
+            );
 
         let transition_gadget =
             AggUserRegisterClaimDepositL2TransferGadget::add_virtual_to::<C::Hasher, C::F, D>(
@@ -128,10 +269,16 @@ where
             actual_op_register_user_combined_state_transition,
             actual_op_claim_l1_deposit_combined_state_transition,
             actual_op_l2_transfer_combined_state_transition,
+            actual_op_change_public_key_combined_state_transition,
+            actual_op_batch_l2_transfer_combined_state_transition,
+            actual_op_open_token_account_combined_state_transition,
+            actual_op_mint_token_combined_state_transition,
+            actual_op_send_token_combined_state_transition,
+            actual_op_burn_token_combined_state_transition,
         );
 
         builder.register_public_inputs(&transition_gadget.combined_state_transition_hash.elements);
-        let circuit_data = builder.build::<C>();
+        let circuit_data = builder.build_cached::<C>("block_agg_register_claim_deposit_transfer");
         let minifier_chain =
             QEDProofMinifierChain::new(&circuit_data.verifier_only, &circuit_data.common, 1);
         Self {
@@ -141,10 +288,28 @@ where
             op_claim_l1_deposit_verifier_data,
             op_l2_transfer_proof,
             op_l2_transfer_verifier_data,
+            op_change_public_key_proof,
+            op_change_public_key_verifier_data,
+            op_batch_l2_transfer_proof,
+            op_batch_l2_transfer_verifier_data,
+            op_open_token_account_proof,
+            op_open_token_account_verifier_data,
+            op_mint_token_proof,
+            op_mint_token_verifier_data,
+            op_send_token_proof,
+            op_send_token_verifier_data,
+            op_burn_token_proof,
+            op_burn_token_verifier_data,
             transition_gadget,
             op_register_user_fingerprint,
             op_claim_l1_deposit_fingerprint,
             op_l2_transfer_fingerprint,
+            op_change_public_key_fingerprint,
+            op_batch_l2_transfer_fingerprint,
+            op_open_token_account_fingerprint,
+            op_mint_token_fingerprint,
+            op_send_token_fingerprint,
+            op_burn_token_fingerprint,
             circuit_data,
             minifier_chain,
         }
@@ -158,6 +323,18 @@ where
         op_claim_l1_deposit_verifier_data: &VerifierOnlyCircuitData<C, D>,
         op_l2_transfer_proof: &ProofWithPublicInputs<C::F, C, D>,
         op_l2_transfer_verifier_data: &VerifierOnlyCircuitData<C, D>,
+        op_change_public_key_proof: &ProofWithPublicInputs<C::F, C, D>,
+        op_change_public_key_verifier_data: &VerifierOnlyCircuitData<C, D>,
+        op_batch_l2_transfer_proof: &ProofWithPublicInputs<C::F, C, D>,
+        op_batch_l2_transfer_verifier_data: &VerifierOnlyCircuitData<C, D>,
+        op_open_token_account_proof: &ProofWithPublicInputs<C::F, C, D>,
+        op_open_token_account_verifier_data: &VerifierOnlyCircuitData<C, D>,
+        op_mint_token_proof: &ProofWithPublicInputs<C::F, C, D>,
+        op_mint_token_verifier_data: &VerifierOnlyCircuitData<C, D>,
+        op_send_token_proof: &ProofWithPublicInputs<C::F, C, D>,
+        op_send_token_verifier_data: &VerifierOnlyCircuitData<C, D>,
+        op_burn_token_proof: &ProofWithPublicInputs<C::F, C, D>,
+        op_burn_token_verifier_data: &VerifierOnlyCircuitData<C, D>,
     ) -> anyhow::Result<ProofWithPublicInputs<C::F, C, D>> {
         let mut pw = PartialWitness::new();
 
@@ -184,6 +361,57 @@ where
             op_l2_transfer_verifier_data,
         );
 
+        pw.set_proof_with_pis_target::<C, D>(
+            &self.op_change_public_key_proof,
+            op_change_public_key_proof,
+        );
+
+        pw.set_verifier_data_target::<C, D>(
+            &self.op_change_public_key_verifier_data,
+            op_change_public_key_verifier_data,
+        );
+
+        pw.set_proof_with_pis_target::<C, D>(
+            &self.op_batch_l2_transfer_proof,
+            op_batch_l2_transfer_proof,
+        );
+
+        pw.set_verifier_data_target::<C, D>(
+            &self.op_batch_l2_transfer_verifier_data,
+            op_batch_l2_transfer_verifier_data,
+        );
+
+        pw.set_proof_with_pis_target::<C, D>(
+            &self.op_open_token_account_proof,
+            op_open_token_account_proof,
+        );
+
+        pw.set_verifier_data_target::<C, D>(
+            &self.op_open_token_account_verifier_data,
+            op_open_token_account_verifier_data,
+        );
+
+        pw.set_proof_with_pis_target::<C, D>(&self.op_mint_token_proof, op_mint_token_proof);
+
+        pw.set_verifier_data_target::<C, D>(
+            &self.op_mint_token_verifier_data,
+            op_mint_token_verifier_data,
+        );
+
+        pw.set_proof_with_pis_target::<C, D>(&self.op_send_token_proof, op_send_token_proof);
+
+        pw.set_verifier_data_target::<C, D>(
+            &self.op_send_token_verifier_data,
+            op_send_token_verifier_data,
+        );
+
+        pw.set_proof_with_pis_target::<C, D>(&self.op_burn_token_proof, op_burn_token_proof);
+
+        pw.set_verifier_data_target::<C, D>(
+            &self.op_burn_token_verifier_data,
+            op_burn_token_verifier_data,
+        );
+
         self.transition_gadget.set_witness(&mut pw, input);
 
         self.circuit_data.prove(pw)
@@ -243,11 +471,47 @@ where
             .get_verifier_triplet_for_circuit_type(
                 input.op_l2_transfer_proof_id.circuit_type.try_into()?,
             );
+        let (_, op_change_public_key_verifier_data, _) = verify_helper
+            .get_verifier_triplet_for_circuit_type(
+                input.op_change_public_key_proof_id.circuit_type.try_into()?,
+            );
+        let (_, op_batch_l2_transfer_verifier_data, _) = verify_helper
+            .get_verifier_triplet_for_circuit_type(
+                input.op_batch_l2_transfer_proof_id.circuit_type.try_into()?,
+            );
+        let (_, op_open_token_account_verifier_data, _) = verify_helper
+            .get_verifier_triplet_for_circuit_type(
+                input
+                    .op_open_token_account_proof_id
+                    .circuit_type
+                    .try_into()?,
+            );
+        let (_, op_mint_token_verifier_data, _) = verify_helper
+            .get_verifier_triplet_for_circuit_type(
+                input.op_mint_token_proof_id.circuit_type.try_into()?,
+            );
+        let (_, op_send_token_verifier_data, _) = verify_helper
+            .get_verifier_triplet_for_circuit_type(
+                input.op_send_token_proof_id.circuit_type.try_into()?,
+            );
+        let (_, op_burn_token_verifier_data, _) = verify_helper
+            .get_verifier_triplet_for_circuit_type(
+                input.op_burn_token_proof_id.circuit_type.try_into()?,
+            );
 
         let op_register_user_proof = store.get_proof_by_id(input.op_register_user_proof_id)?;
         let op_claim_l1_deposit_proof =
             store.get_proof_by_id(input.op_claim_l1_deposit_proof_id)?;
         let op_l2_transfer_proof = store.get_proof_by_id(input.op_l2_transfer_proof_id)?;
+        let op_change_public_key_proof =
+            store.get_proof_by_id(input.op_change_public_key_proof_id)?;
+        let op_batch_l2_transfer_proof =
+            store.get_proof_by_id(input.op_batch_l2_transfer_proof_id)?;
+        let op_open_token_account_proof =
+            store.get_proof_by_id(input.op_open_token_account_proof_id)?;
+        let op_mint_token_proof = store.get_proof_by_id(input.op_mint_token_proof_id)?;
+        let op_send_token_proof = store.get_proof_by_id(input.op_send_token_proof_id)?;
+        let op_burn_token_proof = store.get_proof_by_id(input.op_burn_token_proof_id)?;
 
         let inner_proof = self.prove_base(
             &input,
@@ -257,6 +521,18 @@ where
             &op_claim_l1_deposit_verifier_data,
             &op_l2_transfer_proof,
             &op_l2_transfer_verifier_data,
+            &op_change_public_key_proof,
+            &op_change_public_key_verifier_data,
+            &op_batch_l2_transfer_proof,
+            &op_batch_l2_transfer_verifier_data,
+            &op_open_token_account_proof,
+            &op_open_token_account_verifier_data,
+            &op_mint_token_proof,
+            &op_mint_token_verifier_data,
+            &op_send_token_proof,
+            &op_send_token_verifier_data,
+            &op_burn_token_proof,
+            &op_burn_token_verifier_data,
         )?;
         self.minifier_chain.prove(&inner_proof)
     }
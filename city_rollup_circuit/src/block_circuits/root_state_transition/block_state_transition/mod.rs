@@ -1,3 +1,4 @@
+use city_common_circuit::serialization::circuit_cache::CircuitBuilderCachedBuild;
 use city_common_circuit::{
     circuits::traits::qstandard::QStandardCircuit,
     proof_minifier::pm_chain_dynamic::QEDProofMinifierDynamicChain,
@@ -91,7 +92,7 @@ where
         builder.register_public_inputs(&transition_gadget.withdrawal_events_hash.elements);
         builder.register_public_inputs(&transition_gadget.deposit_events_hash.elements);
 
-        let circuit_data = builder.build::<C>();
+        let circuit_data = builder.build_cached::<C>("block_state_transition");
         let minifier_chain =
             QEDProofMinifierDynamicChain::new(&circuit_data.verifier_only, &circuit_data.common, 1);
         Self {
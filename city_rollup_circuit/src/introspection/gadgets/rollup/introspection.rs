@@ -1,6 +1,6 @@
 use city_common::logging::trace_timer::TraceTimer;
 use city_common_circuit::{builder::{connect::CircuitBuilderConnectHelpers, core::{CircuitBuilderHelpersCore, WitnessHelpersCore}, select::CircuitBuilderSelectHelpers, signature::CircuitBuilderSignatureHelpers}, hash::{accelerator::sha256::planner::{Sha256AcceleratorDomain, Sha256AcceleratorDomainID, Sha256AcceleratorDomainPlanner, Sha256AcceleratorDomainResolver}, base_types::{felthash248::CircuitBuilderFelt248Hash, felthash252::CircuitBuilderFelt252Hash, hash160bytes::Hash160BytesTarget, hash256bytes::{CircuitBuilderHash256Bytes, Hash256BytesTarget}}}};
-use city_rollup_common::{block_template::config::{GENESIS_STATE_HASH, OP_CHECKGROTH16VERIFY, OP_CHECKGROTH16VERIFY_NOP}, introspection::rollup::introspection::{BlockSpendIntrospectionGadgetConfig, BlockSpendIntrospectionHint}};
+use city_rollup_common::{block_template::config::{GENESIS_STATE_HASH, OP_CHECKGROTH16VERIFY, OP_CHECKGROTH16VERIFY_NOP}, introspection::rollup::{forced_withdrawal::{FORCED_WITHDRAWAL_REQUEST_MAGIC, FORCED_WITHDRAWAL_REQUEST_PAYLOAD_SIZE, FORCED_WITHDRAWAL_REQUEST_SCRIPT_SIZE}, introspection::{BlockSpendIntrospectionGadgetConfig, BlockSpendIntrospectionHint}, introspection_result::WITHDRAWAL_TYPE_BYTE_P2PKH}};
 use plonky2::{
     field::extension::Extendable,
     hash::hash_types::{HashOutTarget, RichField},
@@ -9,7 +9,7 @@ use plonky2::{
 };
 
 
-use crate::introspection::gadgets::{sighash::SigHashPreimageBytesGadget, transaction::BTCTransactionBytesGadget};
+use crate::introspection::gadgets::{sighash::SigHashPreimageBytesGadget, transaction::{BTCTransactionBytesGadget, BTCTransactionBytesOutputGadget}};
 
 use super::introspection_result::{
     BTCRollupIntrospectionResultDepositGadget, BTCRollupIntrospectionResultGadget,
@@ -45,6 +45,51 @@ pub fn ensure_output_script_is_p2pkh<F: RichField + Extendable<D>, const D: usiz
     })
 }
 
+pub fn ensure_forced_withdrawal_request_is_satisfied<F: RichField + Extendable<D>, const D: usize>(builder: &mut CircuitBuilder<F, D>, request_output: &BTCTransactionBytesOutputGadget, withdrawal_outputs: &[BTCTransactionBytesOutputGadget]) {
+    assert_eq!(request_output.script.len(), FORCED_WITHDRAWAL_REQUEST_SCRIPT_SIZE, "forced withdrawal request output scripts must be {} bytes long", FORCED_WITHDRAWAL_REQUEST_SCRIPT_SIZE);
+    assert!(!withdrawal_outputs.is_empty(), "a forced withdrawal request needs at least one withdrawal output to be satisfied");
+
+    /*
+        OP_RETURN OP_PUSHBYTES_49 <payload>
+        6a 31 "CRFW" <user_id> <nonce> <value> <destination_type> <destination>
+    */
+    let zero = builder.zero();
+    for b in request_output.value.iter() {
+        builder.connect(*b, zero);
+    }
+    let op_return = builder.constant_u8(0x6a);
+    let op_pushbytes_49 = builder.constant_u8(FORCED_WITHDRAWAL_REQUEST_PAYLOAD_SIZE as u8);
+    builder.connect(request_output.script[0], op_return);
+    builder.connect(request_output.script[1], op_pushbytes_49);
+    let payload = &request_output.script[2..];
+    for (i, m) in FORCED_WITHDRAWAL_REQUEST_MAGIC.iter().enumerate() {
+        let expected = builder.constant_u8(*m);
+        builder.connect(payload[i], expected);
+    }
+    let destination_type_p2pkh = builder.constant_u8(WITHDRAWAL_TYPE_BYTE_P2PKH);
+    builder.connect(payload[28], destination_type_p2pkh);
+
+    let requested_value = &payload[20..28];
+    let requested_destination = &payload[29..49];
+
+    // at least one withdrawal output must pay the requested value to hash160 of the requested destination
+    let mut satisfied = builder._false();
+    for output in withdrawal_outputs.iter() {
+        let output_destination = ensure_output_script_is_p2pkh(builder, &output.script);
+        let mut matches = builder._true();
+        for (a, b) in output.value.iter().zip(requested_value.iter()) {
+            let eq = builder.is_equal(*a, *b);
+            matches = builder.and(matches, eq);
+        }
+        for (a, b) in output_destination.iter().zip(requested_destination.iter()) {
+            let eq = builder.is_equal(*a, *b);
+            matches = builder.and(matches, eq);
+        }
+        satisfied = builder.or(satisfied, matches);
+    }
+    builder.assert_one(satisfied.target);
+}
+
 #[derive(Debug, Clone)]
 pub struct BTCRollupIntrospectionGadget {
     pub sighash_preimage: SigHashPreimageBytesGadget,
@@ -321,6 +366,15 @@ impl BTCRollupIntrospectionGadget {
         &mut self,
         builder: &mut CircuitBuilder<F, D>,
     ) -> Vec<BTCRollupIntrospectionResultDepositGadget> {
+        let withdrawal_outputs = self
+            .sighash_preimage
+            .transaction
+            .outputs
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != self.block_spend_index)
+            .map(|(_, output)| output.clone())
+            .collect::<Vec<_>>();
         self.funding_transactions
             .iter()
             .enumerate()
@@ -331,11 +385,16 @@ impl BTCRollupIntrospectionGadget {
                         1,
                         "deposits should only have one input (p2pkh)"
                     );
-                    assert_eq!(
-                        funding_tx.outputs.len(),
-                        1,
-                        "deposits should only have one output (send to layer 2)"
-                    );
+                    if funding_tx.outputs.len() == 2 {
+                        // the request output is credited to the requester like a regular deposit
+                        ensure_forced_withdrawal_request_is_satisfied(builder, &funding_tx.outputs[1], &withdrawal_outputs);
+                    } else {
+                        assert_eq!(
+                            funding_tx.outputs.len(),
+                            1,
+                            "deposits should only have one output (send to layer 2)"
+                        );
+                    }
 
                     assert_eq!(
                         funding_tx.inputs[0].script.len(),
@@ -457,3 +516,121 @@ impl BTCRollupIntrospectionGadget {
         dr.set_witness_for_domain(self.hash_domain_id, &[]);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::panic::AssertUnwindSafe;
+
+    use city_crypto::hash::base_types::{hash160::Hash160, hash256::Hash256};
+    use city_rollup_common::{
+        api::data::store::CityL1Withdrawal,
+        introspection::{
+            rollup::{
+                forced_withdrawal::CityForcedWithdrawalRequest,
+                introspection_result::WITHDRAWAL_TYPE_BYTE_P2PKH,
+            },
+            transaction::BTCTransactionOutput,
+        },
+    };
+    use plonky2::{
+        iop::witness::PartialWitness,
+        plonk::{
+            circuit_builder::CircuitBuilder,
+            circuit_data::CircuitConfig,
+            config::{GenericConfig, PoseidonGoldilocksConfig},
+        },
+    };
+
+    use super::ensure_forced_withdrawal_request_is_satisfied;
+    use crate::introspection::gadgets::transaction::BTCTransactionBytesOutputGadget;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    fn example_request() -> CityForcedWithdrawalRequest {
+        CityForcedWithdrawalRequest {
+            txid: Hash256([7u8; 32]),
+            user_id: 12,
+            nonce: 3,
+            value: 150000000,
+            destination_type: WITHDRAWAL_TYPE_BYTE_P2PKH,
+            destination: Hash160([0xab; 20]),
+        }
+    }
+
+    fn p2pkh_output(address: Hash160, value: u64) -> BTCTransactionOutput {
+        CityL1Withdrawal {
+            withdrawal_id: 0,
+            address,
+            address_type: WITHDRAWAL_TYPE_BYTE_P2PKH,
+            value,
+        }
+        .to_btc_tx_out()
+    }
+
+    // true if the request output is accepted alongside the given withdrawal outputs
+    fn prove_request(
+        request_output: &BTCTransactionOutput,
+        withdrawals: &[BTCTransactionOutput],
+    ) -> bool {
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let request_script = builder.add_virtual_targets(request_output.script.len());
+        let request_gadget =
+            BTCTransactionBytesOutputGadget::add_virtual_to(&mut builder, request_script);
+        let withdrawal_gadgets = withdrawals
+            .iter()
+            .map(|w| {
+                let script = builder.add_virtual_targets(w.script.len());
+                BTCTransactionBytesOutputGadget::add_virtual_to(&mut builder, script)
+            })
+            .collect::<Vec<_>>();
+        ensure_forced_withdrawal_request_is_satisfied(
+            &mut builder,
+            &request_gadget,
+            &withdrawal_gadgets,
+        );
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        request_gadget.set_witness(&mut pw, request_output);
+        for (gadget, output) in withdrawal_gadgets.iter().zip(withdrawals.iter()) {
+            gadget.set_witness(&mut pw, output);
+        }
+        match std::panic::catch_unwind(AssertUnwindSafe(|| data.prove(pw))) {
+            Ok(Ok(proof)) => data.verify(proof).is_ok(),
+            _ => false,
+        }
+    }
+
+    #[test]
+    fn request_paid_by_a_withdrawal_output_is_accepted() {
+        let request = example_request();
+        let paying = p2pkh_output(request.destination, request.value);
+        assert!(request.is_paid_by(&paying));
+        let other = p2pkh_output(Hash160([0x11; 20]), 5000000);
+        assert!(prove_request(
+            &request.to_op_return_output(),
+            &[other, paying]
+        ));
+    }
+
+    #[test]
+    fn request_without_a_paying_withdrawal_output_is_rejected() {
+        let request = example_request();
+        let wrong_value = p2pkh_output(request.destination, request.value - 1);
+        let wrong_destination = p2pkh_output(Hash160([0x11; 20]), request.value);
+        assert!(!request.is_paid_by(&wrong_value));
+        assert!(!request.is_paid_by(&wrong_destination));
+        assert!(!prove_request(
+            &request.to_op_return_output(),
+            &[wrong_value, wrong_destination]
+        ));
+
+        // the payload must be a forced withdrawal request
+        let paying = p2pkh_output(request.destination, request.value);
+        let mut bad_magic = request.to_op_return_output();
+        bad_magic.script[2] = b'X';
+        assert!(!prove_request(&bad_magic, &[paying]));
+    }
+}
@@ -250,7 +250,7 @@ impl BTCTransactionBytesGadget {
         if enable_der_pad
             && self.enable_der_pad
             && self.inputs.len() == 1
-            && self.outputs.len() == 1
+            && (self.outputs.len() == 1 || self.outputs.len() == 2)
             && self.inputs[0].script.len() == 106
         {
             let mut clone_with_107 = self.clone();
@@ -330,7 +330,7 @@ impl BTCTransactionBytesGadget {
         witness.set_u32_bytes_le_target(&self.version, tx.version);
         if self.enable_der_pad
             && self.inputs.len() == 1
-            && self.outputs.len() == 1
+            && (self.outputs.len() == 1 || self.outputs.len() == 2)
             && self.inputs[0].script.len() == 106
             &&tx.inputs[0].script.len() == 107
         {
@@ -1,3 +1,4 @@
+use city_common_circuit::serialization::circuit_cache::CircuitBuilderCachedBuild;
 use hashbrown::HashMap;
 
 use city_common_circuit::{
@@ -74,7 +75,7 @@ where
         Self::new(self.introspection_config.clone())
     }
 }
-impl<C: GenericConfig<D>, const D: usize> CRSigHashCircuit<C, D>
+impl<C: GenericConfig<D> + 'static, const D: usize> CRSigHashCircuit<C, D>
 where
     C::Hasher: AlgebraicHasher<C::F>,
     C::F: CubicExtendable,
@@ -109,7 +110,7 @@ where
         >::new(&mut builder, dp);
 
         let targets_to_constants = builder.get_targets_to_constants_map();
-        let circuit_data = builder.build::<C>();
+        let circuit_data = builder.build_cached::<C>("sighash");
 
         let minifier = QEDProofMinifierDynamicChain::new_with_dynamic_constant_verifier(
             &circuit_data.verifier_only,
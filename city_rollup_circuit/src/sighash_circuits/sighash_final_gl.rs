@@ -1,3 +1,4 @@
+use city_common_circuit::serialization::circuit_cache::CircuitBuilderCachedBuild;
 use city_common_circuit::{
     circuits::traits::qstandard::QStandardCircuit,
     hash::base_types::felthash248::CircuitBuilderFelt248Hash,
@@ -50,7 +51,7 @@ where
     pub minifier: QEDProofMinifierDynamicChain<D, C::F, C>,
     //pub tracer: DebugCircuitTracer,
 }
-impl<C: GenericConfig<D>, const D: usize> CRSigHashFinalGLCircuit<C, D>
+impl<C: GenericConfig<D> + 'static, const D: usize> CRSigHashFinalGLCircuit<C, D>
 where
     C::Hasher: AlgebraicHasher<C::F>,
 {
@@ -193,7 +194,7 @@ where
 
         builder.register_public_inputs(&bits_block_start_hash);
         builder.register_public_inputs(&bits_sighash);
-        let circuit_data = builder.build::<C>();
+        let circuit_data = builder.build_cached::<C>("sighash_final_gl");
 
         let minifier = QEDProofMinifierDynamicChain::new_with_dynamic_constant_verifier(
             &circuit_data.verifier_only,
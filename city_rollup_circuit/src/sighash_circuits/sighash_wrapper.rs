@@ -1,3 +1,4 @@
+use city_common_circuit::serialization::circuit_cache::CircuitBuilderCachedBuild;
 use hashbrown::HashMap;
 
 use city_common_circuit::{
@@ -54,14 +55,14 @@ where
     //pub minifier: QEDProofMinifierDynamicChain<D, C::F, C>,
     //pub tracer: DebugCircuitTracer,
 }
-impl<C: GenericConfig<D>, const D: usize> CRSigHashWrapperCircuit<C, D>
+impl<C: GenericConfig<D> + 'static, const D: usize> CRSigHashWrapperCircuit<C, D>
 where
     C::Hasher: AlgebraicHasher<C::F>,
     C::F: CubicExtendable,
 {
     pub fn new(sighash_circuit_whitelist_root: QHashOut<C::F>) -> Self {
         let introspection_config_base = BlockSpendCoreConfig::standard_p2sh_p2pkh()
-            .generate_permutations(1, 1)
+            .generate_permutations(1, 1, 0)
             .first()
             .unwrap()
             .to_owned();
@@ -103,7 +104,7 @@ where
         }
 
         builder.register_public_inputs(&proof_target.public_inputs);
-        let circuit_data = builder.build::<C>();
+        let circuit_data = builder.build_cached::<C>("sighash_wrapper");
         /*
         let minifier = QEDProofMinifierDynamicChain::new_with_dynamic_constant_verifier(
             &circuit_data.verifier_only,
@@ -12,6 +12,9 @@ pub struct AggAddProcessL1WithdrawalAddL1DepositGadget {
     pub op_add_l1_withdrawal_transition_user_state_tree: AggStateTransitionGadget,
     pub op_add_l1_withdrawal_transition_withdrawal_tree: AggStateTransitionGadget,
 
+    pub op_cancel_l1_withdrawal_transition_user_state_tree: AggStateTransitionGadget,
+    pub op_cancel_l1_withdrawal_transition_withdrawal_tree: AggStateTransitionGadget,
+
     pub op_process_l1_withdrawal_transition_withdrawal_tree: AggStateTransitionGadget,
 
     pub op_add_l1_deposit_transition_deposit_tree: AggStateTransitionGadget,
@@ -29,18 +32,37 @@ impl AggAddProcessL1WithdrawalAddL1DepositGadget {
         let op_add_l1_withdrawal_transition_withdrawal_tree =
             AggStateTransitionGadget::add_virtual_to(builder);
 
+        let op_cancel_l1_withdrawal_transition_user_state_tree =
+            AggStateTransitionGadget::add_virtual_to(builder);
+        let op_cancel_l1_withdrawal_transition_withdrawal_tree =
+            AggStateTransitionGadget::add_virtual_to(builder);
+
         let op_process_l1_withdrawal_transition_withdrawal_tree =
             AggStateTransitionGadget::add_virtual_to(builder);
 
         let op_add_l1_deposit_transition_deposit_tree =
             AggStateTransitionGadget::add_virtual_to(builder);
 
+        // withdrawals are added, then cancelled, then processed
+        builder.connect_hashes(
+            op_add_l1_withdrawal_transition_user_state_tree.state_transition_end,
+            op_cancel_l1_withdrawal_transition_user_state_tree.state_transition_start,
+        );
         builder.connect_hashes(
             op_add_l1_withdrawal_transition_withdrawal_tree.state_transition_end,
+            op_cancel_l1_withdrawal_transition_withdrawal_tree.state_transition_start,
+        );
+        builder.connect_hashes(
+            op_cancel_l1_withdrawal_transition_withdrawal_tree.state_transition_end,
             op_process_l1_withdrawal_transition_withdrawal_tree.state_transition_start,
         );
 
-        let user_state_tree_transition = op_add_l1_withdrawal_transition_user_state_tree;
+        let user_state_tree_transition = AggStateTransitionGadget {
+            state_transition_start: op_add_l1_withdrawal_transition_user_state_tree
+                .state_transition_start,
+            state_transition_end: op_cancel_l1_withdrawal_transition_user_state_tree
+                .state_transition_end,
+        };
         let withdrawal_tree_transition = AggStateTransitionGadget {
             state_transition_start: op_add_l1_withdrawal_transition_withdrawal_tree
                 .state_transition_start,
@@ -64,6 +86,8 @@ impl AggAddProcessL1WithdrawalAddL1DepositGadget {
         Self {
             op_add_l1_withdrawal_transition_user_state_tree,
             op_add_l1_withdrawal_transition_withdrawal_tree,
+            op_cancel_l1_withdrawal_transition_user_state_tree,
+            op_cancel_l1_withdrawal_transition_withdrawal_tree,
             op_process_l1_withdrawal_transition_withdrawal_tree,
             op_add_l1_deposit_transition_deposit_tree,
             combined_state_transition,
@@ -78,6 +102,7 @@ impl AggAddProcessL1WithdrawalAddL1DepositGadget {
         &self,
         builder: &mut CircuitBuilder<F, D>,
         actual_op_add_l1_withdrawal_combined_state_transition: HashOutTarget,
+        actual_op_cancel_l1_withdrawal_combined_state_transition: HashOutTarget,
         actual_op_process_l1_withdrawal_combined_state_transition: HashOutTarget,
         actual_op_add_l1_deposit_combined_state_transition: HashOutTarget,
     ) {
@@ -91,6 +116,16 @@ impl AggAddProcessL1WithdrawalAddL1DepositGadget {
             )
             .get_combined_hash::<H, F, D>(builder);
 
+        let expected_op_cancel_l1_withdrawal_combined_state_transition =
+            AggStateTransitionGadget::combine_many::<H, F, D>(
+                builder,
+                &[
+                    self.op_cancel_l1_withdrawal_transition_user_state_tree,
+                    self.op_cancel_l1_withdrawal_transition_withdrawal_tree,
+                ],
+            )
+            .get_combined_hash::<H, F, D>(builder);
+
         let expected_op_process_l1_withdrawal_combined_state_transition = self
             .op_process_l1_withdrawal_transition_withdrawal_tree
             .get_combined_hash::<H, F, D>(builder);
@@ -104,6 +139,11 @@ impl AggAddProcessL1WithdrawalAddL1DepositGadget {
             expected_op_add_l1_withdrawal_combined_state_transition,
         );
 
+        builder.connect_hashes(
+            actual_op_cancel_l1_withdrawal_combined_state_transition,
+            expected_op_cancel_l1_withdrawal_combined_state_transition,
+        );
+
         builder.connect_hashes(
             actual_op_process_l1_withdrawal_combined_state_transition,
             expected_op_process_l1_withdrawal_combined_state_transition,
@@ -131,6 +171,17 @@ impl AggAddProcessL1WithdrawalAddL1DepositGadget {
                 &input.op_add_l1_withdrawal_transition_withdrawal_tree,
             );
 
+        self.op_cancel_l1_withdrawal_transition_user_state_tree
+            .set_witness(
+                witness,
+                &input.op_cancel_l1_withdrawal_transition_user_state_tree,
+            );
+        self.op_cancel_l1_withdrawal_transition_withdrawal_tree
+            .set_witness(
+                witness,
+                &input.op_cancel_l1_withdrawal_transition_withdrawal_tree,
+            );
+
         self.op_process_l1_withdrawal_transition_withdrawal_tree
             .set_witness(
                 witness,
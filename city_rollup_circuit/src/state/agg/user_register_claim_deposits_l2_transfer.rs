@@ -16,6 +16,18 @@ pub struct AggUserRegisterClaimDepositL2TransferGadget {
 
     pub op_l2_transfer_transition_user_state_tree: AggStateTransitionGadget,
 
+    pub op_change_public_key_transition_user_state_tree: AggStateTransitionGadget,
+
+    pub op_batch_l2_transfer_transition_user_state_tree: AggStateTransitionGadget,
+
+    pub op_open_token_account_transition_user_state_tree: AggStateTransitionGadget,
+
+    pub op_mint_token_transition_user_state_tree: AggStateTransitionGadget,
+
+    pub op_send_token_transition_user_state_tree: AggStateTransitionGadget,
+
+    pub op_burn_token_transition_user_state_tree: AggStateTransitionGadget,
+
     pub combined_state_transition: AggStateTransitionGadget,
     pub combined_state_transition_hash: HashOutTarget,
 }
@@ -35,6 +47,24 @@ impl AggUserRegisterClaimDepositL2TransferGadget {
         let op_l2_transfer_transition_user_state_tree =
             AggStateTransitionGadget::add_virtual_to(builder);
 
+        let op_change_public_key_transition_user_state_tree =
+            AggStateTransitionGadget::add_virtual_to(builder);
+
+        let op_batch_l2_transfer_transition_user_state_tree =
+            AggStateTransitionGadget::add_virtual_to(builder);
+
+        let op_open_token_account_transition_user_state_tree =
+            AggStateTransitionGadget::add_virtual_to(builder);
+
+        let op_mint_token_transition_user_state_tree =
+            AggStateTransitionGadget::add_virtual_to(builder);
+
+        let op_send_token_transition_user_state_tree =
+            AggStateTransitionGadget::add_virtual_to(builder);
+
+        let op_burn_token_transition_user_state_tree =
+            AggStateTransitionGadget::add_virtual_to(builder);
+
         builder.connect_hashes(
             op_register_user_transition_user_state_tree.state_transition_end,
             op_claim_l1_deposit_transition_user_state_tree.state_transition_start,
@@ -43,11 +73,35 @@ impl AggUserRegisterClaimDepositL2TransferGadget {
             op_claim_l1_deposit_transition_user_state_tree.state_transition_end,
             op_l2_transfer_transition_user_state_tree.state_transition_start,
         );
+        builder.connect_hashes(
+            op_l2_transfer_transition_user_state_tree.state_transition_end,
+            op_change_public_key_transition_user_state_tree.state_transition_start,
+        );
+        builder.connect_hashes(
+            op_change_public_key_transition_user_state_tree.state_transition_end,
+            op_batch_l2_transfer_transition_user_state_tree.state_transition_start,
+        );
+        builder.connect_hashes(
+            op_batch_l2_transfer_transition_user_state_tree.state_transition_end,
+            op_open_token_account_transition_user_state_tree.state_transition_start,
+        );
+        builder.connect_hashes(
+            op_open_token_account_transition_user_state_tree.state_transition_end,
+            op_mint_token_transition_user_state_tree.state_transition_start,
+        );
+        builder.connect_hashes(
+            op_mint_token_transition_user_state_tree.state_transition_end,
+            op_send_token_transition_user_state_tree.state_transition_start,
+        );
+        builder.connect_hashes(
+            op_send_token_transition_user_state_tree.state_transition_end,
+            op_burn_token_transition_user_state_tree.state_transition_start,
+        );
 
         let user_state_tree_transition = AggStateTransitionGadget {
             state_transition_start: op_register_user_transition_user_state_tree
                 .state_transition_start,
-            state_transition_end: op_l2_transfer_transition_user_state_tree.state_transition_end,
+            state_transition_end: op_burn_token_transition_user_state_tree.state_transition_end,
         };
 
         let deposit_tree_transition = op_claim_l1_deposit_transition_deposit_tree;
@@ -63,6 +117,12 @@ impl AggUserRegisterClaimDepositL2TransferGadget {
             op_claim_l1_deposit_transition_deposit_tree,
             op_claim_l1_deposit_transition_user_state_tree,
             op_l2_transfer_transition_user_state_tree,
+            op_change_public_key_transition_user_state_tree,
+            op_batch_l2_transfer_transition_user_state_tree,
+            op_open_token_account_transition_user_state_tree,
+            op_mint_token_transition_user_state_tree,
+            op_send_token_transition_user_state_tree,
+            op_burn_token_transition_user_state_tree,
             op_register_user_transition_user_state_tree,
             combined_state_transition,
             combined_state_transition_hash,
@@ -78,6 +138,12 @@ impl AggUserRegisterClaimDepositL2TransferGadget {
         actual_op_register_user_combined_state_transition: HashOutTarget,
         actual_op_claim_l1_deposit_combined_state_transition: HashOutTarget,
         actual_op_l2_transfer_combined_state_transition: HashOutTarget,
+        actual_op_change_public_key_combined_state_transition: HashOutTarget,
+        actual_op_batch_l2_transfer_combined_state_transition: HashOutTarget,
+        actual_op_open_token_account_combined_state_transition: HashOutTarget,
+        actual_op_mint_token_combined_state_transition: HashOutTarget,
+        actual_op_send_token_combined_state_transition: HashOutTarget,
+        actual_op_burn_token_combined_state_transition: HashOutTarget,
     ) {
         let expected_op_register_user_combined_state_transition = self
             .op_register_user_transition_user_state_tree
@@ -96,6 +162,30 @@ impl AggUserRegisterClaimDepositL2TransferGadget {
         let expected_op_l2_transfer_combined_state_transition = self
             .op_l2_transfer_transition_user_state_tree
             .get_combined_hash::<H, F, D>(builder);
+
+        let expected_op_change_public_key_combined_state_transition = self
+            .op_change_public_key_transition_user_state_tree
+            .get_combined_hash::<H, F, D>(builder);
+
+        let expected_op_batch_l2_transfer_combined_state_transition = self
+            .op_batch_l2_transfer_transition_user_state_tree
+            .get_combined_hash::<H, F, D>(builder);
+
+        let expected_op_open_token_account_combined_state_transition = self
+            .op_open_token_account_transition_user_state_tree
+            .get_combined_hash::<H, F, D>(builder);
+
+        let expected_op_mint_token_combined_state_transition = self
+            .op_mint_token_transition_user_state_tree
+            .get_combined_hash::<H, F, D>(builder);
+
+        let expected_op_send_token_combined_state_transition = self
+            .op_send_token_transition_user_state_tree
+            .get_combined_hash::<H, F, D>(builder);
+
+        let expected_op_burn_token_combined_state_transition = self
+            .op_burn_token_transition_user_state_tree
+            .get_combined_hash::<H, F, D>(builder);
         builder.connect_hashes(
             actual_op_register_user_combined_state_transition,
             expected_op_register_user_combined_state_transition,
@@ -110,6 +200,36 @@ impl AggUserRegisterClaimDepositL2TransferGadget {
             actual_op_l2_transfer_combined_state_transition,
             expected_op_l2_transfer_combined_state_transition,
         );
+
+        builder.connect_hashes(
+            actual_op_change_public_key_combined_state_transition,
+            expected_op_change_public_key_combined_state_transition,
+        );
+
+        builder.connect_hashes(
+            actual_op_batch_l2_transfer_combined_state_transition,
+            expected_op_batch_l2_transfer_combined_state_transition,
+        );
+
+        builder.connect_hashes(
+            actual_op_open_token_account_combined_state_transition,
+            expected_op_open_token_account_combined_state_transition,
+        );
+
+        builder.connect_hashes(
+            actual_op_mint_token_combined_state_transition,
+            expected_op_mint_token_combined_state_transition,
+        );
+
+        builder.connect_hashes(
+            actual_op_send_token_combined_state_transition,
+            expected_op_send_token_combined_state_transition,
+        );
+
+        builder.connect_hashes(
+            actual_op_burn_token_combined_state_transition,
+            expected_op_burn_token_combined_state_transition,
+        );
     }
 
     pub fn set_witness<W: Witness<F>, F: RichField>(
@@ -130,5 +250,35 @@ impl AggUserRegisterClaimDepositL2TransferGadget {
 
         self.op_l2_transfer_transition_user_state_tree
             .set_witness(witness, &input.op_l2_transfer_transition_user_state_tree);
+
+        self.op_change_public_key_transition_user_state_tree.set_witness(
+            witness,
+            &input.op_change_public_key_transition_user_state_tree,
+        );
+
+        self.op_batch_l2_transfer_transition_user_state_tree.set_witness(
+            witness,
+            &input.op_batch_l2_transfer_transition_user_state_tree,
+        );
+
+        self.op_open_token_account_transition_user_state_tree.set_witness(
+            witness,
+            &input.op_open_token_account_transition_user_state_tree,
+        );
+
+        self.op_mint_token_transition_user_state_tree.set_witness(
+            witness,
+            &input.op_mint_token_transition_user_state_tree,
+        );
+
+        self.op_send_token_transition_user_state_tree.set_witness(
+            witness,
+            &input.op_send_token_transition_user_state_tree,
+        );
+
+        self.op_burn_token_transition_user_state_tree.set_witness(
+            witness,
+            &input.op_burn_token_transition_user_state_tree,
+        );
     }
 }
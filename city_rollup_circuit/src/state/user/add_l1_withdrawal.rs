@@ -1,5 +1,5 @@
 use city_common::config::rollup_constants::{
-    GLOBAL_USER_TREE_HEIGHT, L1_WITHDRAWAL_TREE_HEIGHT, WITHDRAWAL_FEE_AMOUNT,
+    BALANCE_BIT_SIZE, GLOBAL_USER_TREE_HEIGHT, L1_WITHDRAWAL_TREE_HEIGHT, WITHDRAWAL_FEE_AMOUNT,
 };
 use city_common_circuit::{
     builder::{
@@ -24,18 +24,38 @@ use crate::introspection::gadgets::rollup::{
     signature::compute_sig_action_hash_circuit,
 };
 
-use super::user_state::UserStateGadget;
+use super::{fee_credit::SequencerFeeCreditGadget, user_state::UserStateGadget};
+
+// the withdrawal tree leaf is hash(withdrawal_hash, [owner_user_id, 0, 0, 0]), so a withdrawal can
+// only be cancelled by the user who added it
+pub fn compute_withdrawal_leaf_hash_circuit<
+    H: AlgebraicHasher<F>,
+    F: RichField + Extendable<D>,
+    const D: usize,
+>(
+    builder: &mut CircuitBuilder<F, D>,
+    withdrawal_hash: HashOutTarget,
+    owner_user_id: Target,
+) -> HashOutTarget {
+    let zero = builder.zero();
+    let owner = HashOutTarget {
+        elements: [owner_user_id, zero, zero, zero],
+    };
+    builder.hash_two_to_one::<H>(withdrawal_hash, owner)
+}
 
 #[derive(Debug, Clone)]
 pub struct AddL1WithdrawalGadget {
     // inputs:
     pub withdrawal_tree_delta_merkle_proof_gadget: DeltaMerkleProofGadget,
     pub user_tree_delta_merkle_proof_gadget: DeltaMerkleProofGadget,
+    pub fee_credit_gadget: SequencerFeeCreditGadget,
+    pub fee: Target,
+    pub withdrawal_hash: HashOutTarget,
 
     // computed:
     pub old_user_state: UserStateGadget,
     pub new_user_state: UserStateGadget,
-    pub withdrawal_hash: HashOutTarget,
     pub withdrawal_amount: Target,
     pub withdrawal_fee: Target,
     pub actual_user_paid_amount: Target,
@@ -48,6 +68,7 @@ impl AddL1WithdrawalGadget {
         const D: usize,
     >(
         builder: &mut CircuitBuilder<F, D>,
+        fee_recipient_user_id: u64,
     ) -> Self {
         let withdrawal_tree_delta_merkle_proof_gadget: DeltaMerkleProofGadget =
             DeltaMerkleProofGadget::add_virtual_to_append_only::<H, F, D>(
@@ -58,7 +79,7 @@ impl AddL1WithdrawalGadget {
         // ensure that the old value of the leaf is empty (make sure it does not overwrite an existing withdrawal)
         builder.ensure_hash_is_zero(withdrawal_tree_delta_merkle_proof_gadget.old_value);
 
-        let withdrawal_hash = withdrawal_tree_delta_merkle_proof_gadget.new_value;
+        let withdrawal_hash = builder.add_virtual_hash();
 
         // validate the withdrawal hash to make sure it can be processed and get the amount
         let withdrawal_amount =
@@ -75,13 +96,28 @@ impl AddL1WithdrawalGadget {
             &user_tree_delta_merkle_proof_gadget,
         );
 
+        // the new leaf commits to the user who pays for the withdrawal
+        let withdrawal_leaf_hash = compute_withdrawal_leaf_hash_circuit::<H, F, D>(
+            builder,
+            withdrawal_hash,
+            old_user_state.user_id,
+        );
+        builder.connect_hashes(
+            withdrawal_tree_delta_merkle_proof_gadget.new_value,
+            withdrawal_leaf_hash,
+        );
+
         // ensure the withdrawal amount is not zero
         // TODO: make sure the amount is greater than some reasonable value
         let zero = builder.zero();
         builder.ensure_not_equal(withdrawal_amount, zero);
 
         let withdrawal_fee = builder.constant_u64(WITHDRAWAL_FEE_AMOUNT);
-        let expected_user_paid_amount = builder.add(withdrawal_amount, withdrawal_fee);
+        let fee = builder.add_virtual_target();
+        // range check the fee so (withdrawal_amount + withdrawal_fee + fee) cannot wrap around
+        builder.range_check(fee, BALANCE_BIT_SIZE);
+        let l1_paid_amount = builder.add(withdrawal_amount, withdrawal_fee);
+        let expected_user_paid_amount = builder.add(l1_paid_amount, fee);
 
         let (actual_user_paid_amount, new_user_state) = old_user_state
             .ensure_valid_decrease_balance(
@@ -90,15 +126,30 @@ impl AddL1WithdrawalGadget {
                 true,
             );
 
-        // ensure the amount paid by the user is equal to withdrawal_amount + WITHDRAWAL_FEE_AMOUNT
+        // ensure the amount paid by the user is equal to withdrawal_amount + WITHDRAWAL_FEE_AMOUNT + fee
         builder.connect(expected_user_paid_amount, actual_user_paid_amount);
 
+        // credit the fee to the fee recipient right after the user's balance is decreased
+        let fee_credit_gadget = SequencerFeeCreditGadget::add_virtual_to::<H, F, D>(
+            builder,
+            fee,
+            fee_recipient_user_id,
+        );
+        builder.connect_hashes(
+            user_tree_delta_merkle_proof_gadget.new_root,
+            fee_credit_gadget
+                .fee_recipient_user_tree_delta_merkle_proof_gadget
+                .old_root,
+        );
+
         Self {
             withdrawal_tree_delta_merkle_proof_gadget,
             user_tree_delta_merkle_proof_gadget,
+            fee_credit_gadget,
+            fee,
+            withdrawal_hash,
             old_user_state,
             new_user_state,
-            withdrawal_hash,
             withdrawal_amount,
             withdrawal_fee,
             actual_user_paid_amount,
@@ -109,11 +160,18 @@ impl AddL1WithdrawalGadget {
         witness: &mut W,
         withdrawal_tree_delta_merkle_proof: &DeltaMerkleProofCore<QHashOut<F>>,
         user_tree_delta_merkle_proof: &DeltaMerkleProofCore<QHashOut<F>>,
+        fee_recipient_user_tree_delta_merkle_proof: &DeltaMerkleProofCore<QHashOut<F>>,
+        fee: u64,
+        withdrawal_hash: QHashOut<F>,
     ) {
         self.withdrawal_tree_delta_merkle_proof_gadget
             .set_witness_core_proof_q(witness, withdrawal_tree_delta_merkle_proof);
         self.user_tree_delta_merkle_proof_gadget
             .set_witness_core_proof_q(witness, user_tree_delta_merkle_proof);
+        self.fee_credit_gadget
+            .set_witness(witness, fee_recipient_user_tree_delta_merkle_proof);
+        witness.set_target(self.fee, F::from_noncanonical_u64(fee));
+        witness.set_hash_target(self.withdrawal_hash, withdrawal_hash.0);
     }
 }
 
@@ -143,8 +201,10 @@ impl AddL1WithdrawalSingleGadget {
     >(
         builder: &mut CircuitBuilder<F, D>,
         network_magic: u64,
+        fee_recipient_user_id: u64,
     ) -> Self {
-        let withdrawal_gadget = AddL1WithdrawalGadget::add_virtual_to::<H, F, D>(builder);
+        let withdrawal_gadget =
+            AddL1WithdrawalGadget::add_virtual_to::<H, F, D>(builder, fee_recipient_user_id);
 
         let sig_action_id = builder.constant_u64(SIG_ACTION_WITHDRAW_MAGIC);
         let network_magic_target = builder.constant_u64(network_magic);
@@ -152,6 +212,7 @@ impl AddL1WithdrawalSingleGadget {
         let new_user_nonce = withdrawal_gadget.new_user_state.nonce;
         let withdrawal_hash = withdrawal_gadget.withdrawal_hash;
         let withdrawal_fee = withdrawal_gadget.withdrawal_fee;
+        let fee = withdrawal_gadget.fee;
 /*
         let expected_signature_hash = compute_sig_action_hash_circuit::<H, F, D>(
             builder,
@@ -174,6 +235,7 @@ impl AddL1WithdrawalSingleGadget {
                 withdrawal_hash.elements[2],
                 withdrawal_hash.elements[3],
                 withdrawal_fee,
+                fee,
             ],
         );
         let expected_public_key = withdrawal_gadget.old_user_state.public_key;
@@ -182,7 +244,8 @@ impl AddL1WithdrawalSingleGadget {
             .user_tree_delta_merkle_proof_gadget
             .old_root;
         let new_user_tree_root = withdrawal_gadget
-            .user_tree_delta_merkle_proof_gadget
+            .fee_credit_gadget
+            .fee_recipient_user_tree_delta_merkle_proof_gadget
             .new_root;
 
         let old_withdrawal_tree_root = withdrawal_gadget
@@ -0,0 +1,374 @@
+use city_common::config::rollup_constants::{
+    BALANCE_BIT_SIZE, GLOBAL_USER_TREE_HEIGHT, L2_BATCH_TRANSFER_MAX_RECIPIENTS,
+};
+use city_common_circuit::{
+    builder::{comparison::CircuitBuilderComparison, hash::core::CircuitBuilderHashCore},
+    hash::merkle::gadgets::delta_merkle_proof::DeltaMerkleProofGadget,
+};
+use city_crypto::hash::{merkle::core::DeltaMerkleProofCore, qhashout::QHashOut};
+use plonky2::{
+    field::extension::Extendable,
+    hash::hash_types::RichField,
+    iop::{
+        target::{BoolTarget, Target},
+        witness::Witness,
+    },
+    plonk::{circuit_builder::CircuitBuilder, config::AlgebraicHasher},
+};
+
+use super::{fee_credit::SequencerFeeCreditGadget, user_state::UserStateGadget};
+
+#[derive(Debug, Clone)]
+pub struct BatchL2TransferRecipientGadget {
+    // inputs:
+    pub receiver_user_tree_delta_merkle_proof_gadget: DeltaMerkleProofGadget,
+    pub is_active: BoolTarget,
+    pub amount: Target,
+
+    // computed:
+    pub receiver_old_user_state: UserStateGadget,
+    pub receiver_new_user_state: UserStateGadget,
+    // zero for unused slots
+    pub signed_recipient_user_id: Target,
+}
+
+#[derive(Debug, Clone)]
+pub struct BatchL2TransferStateUpdateGadget {
+    // inputs:
+    pub sender_user_tree_delta_merkle_proof_gadget: DeltaMerkleProofGadget,
+    pub recipients: Vec<BatchL2TransferRecipientGadget>,
+    pub fee_credit_gadget: SequencerFeeCreditGadget,
+    pub fee: Target,
+
+    // computed:
+    pub sender_old_user_state: UserStateGadget,
+    pub sender_new_user_state: UserStateGadget,
+    pub total_paid_amount: Target,
+}
+
+impl BatchL2TransferStateUpdateGadget {
+    pub fn add_virtual_to<H: AlgebraicHasher<F>, F: RichField + Extendable<D>, const D: usize>(
+        builder: &mut CircuitBuilder<F, D>,
+        fee_recipient_user_id: u64,
+    ) -> Self {
+        let zero = builder.zero();
+        let sender_user_tree_delta_merkle_proof_gadget: DeltaMerkleProofGadget =
+            DeltaMerkleProofGadget::add_virtual_to_u8h::<H, F, D>(builder, GLOBAL_USER_TREE_HEIGHT);
+
+        // ensure the sender has a non-zero public key (i.e the sender already exists on the network)
+        builder.ensure_hash_is_non_zero(sender_user_tree_delta_merkle_proof_gadget.siblings[0]);
+
+        let sender_old_user_state = UserStateGadget::new_from_delta_merkle_proof_left_leaf(
+            builder,
+            &sender_user_tree_delta_merkle_proof_gadget,
+        );
+
+        // the sender pays for all the transfers and the fee at once, with a single nonce update
+        let (total_paid_amount, sender_new_user_state) = sender_old_user_state
+            .ensure_valid_decrease_balance(
+                builder,
+                sender_user_tree_delta_merkle_proof_gadget.new_value,
+                true,
+            );
+
+        let fee = builder.add_virtual_target();
+        builder.range_check(fee, BALANCE_BIT_SIZE);
+
+        let mut recipients: Vec<BatchL2TransferRecipientGadget> =
+            Vec::with_capacity(L2_BATCH_TRANSFER_MAX_RECIPIENTS);
+        let mut last_root = sender_user_tree_delta_merkle_proof_gadget.new_root;
+        let mut remaining_amount = total_paid_amount;
+        for i in 0..L2_BATCH_TRANSFER_MAX_RECIPIENTS {
+            let receiver_user_tree_delta_merkle_proof_gadget: DeltaMerkleProofGadget =
+                DeltaMerkleProofGadget::add_virtual_to_u8h::<H, F, D>(
+                    builder,
+                    GLOBAL_USER_TREE_HEIGHT,
+                );
+
+            // ensure the receiver has a non-zero public key (i.e the receiver already exists on the network)
+            builder
+                .ensure_hash_is_non_zero(receiver_user_tree_delta_merkle_proof_gadget.siblings[0]);
+
+            // ensure this is not a self transfer
+            builder.ensure_not_equal(
+                sender_user_tree_delta_merkle_proof_gadget.index,
+                receiver_user_tree_delta_merkle_proof_gadget.index,
+            );
+
+            // the transfers are applied back-to-back after the sender's update
+            builder.connect_hashes(
+                last_root,
+                receiver_user_tree_delta_merkle_proof_gadget.old_root,
+            );
+            last_root = receiver_user_tree_delta_merkle_proof_gadget.new_root;
+
+            let is_active = builder.add_virtual_bool_target_safe();
+            if i == 0 {
+                // a batch must have at least one recipient
+                builder.assert_one(is_active.target);
+            } else {
+                // the active slots must come first (is_active[i] implies is_active[i-1])
+                let prev_is_active = recipients[i - 1].is_active;
+                let both_active = builder.and(is_active, prev_is_active);
+                builder.connect(both_active.target, is_active.target);
+            }
+
+            let amount = builder.add_virtual_target();
+            builder.range_check(amount, BALANCE_BIT_SIZE);
+
+            // unused slots are zero-value no-op updates
+            let masked_amount = builder.mul(amount, is_active.target);
+            builder.connect(masked_amount, amount);
+
+            // ensure the sum of the transfers does not exceed the amount paid by the sender
+            builder.ensure_is_greater_than_or_equal(BALANCE_BIT_SIZE, remaining_amount, amount);
+            remaining_amount = builder.sub(remaining_amount, amount);
+
+            let receiver_old_user_state = UserStateGadget::new_from_delta_merkle_proof_left_leaf(
+                builder,
+                &receiver_user_tree_delta_merkle_proof_gadget,
+            );
+            let receiver_new_user_state = receiver_old_user_state
+                .ensure_valid_increase_balance_known_amount_allow_zero(
+                    builder,
+                    receiver_user_tree_delta_merkle_proof_gadget.new_value,
+                    amount,
+                    false,
+                );
+
+            let signed_recipient_user_id =
+                builder.select(is_active, receiver_old_user_state.user_id, zero);
+
+            recipients.push(BatchL2TransferRecipientGadget {
+                receiver_user_tree_delta_merkle_proof_gadget,
+                is_active,
+                amount,
+                receiver_old_user_state,
+                receiver_new_user_state,
+                signed_recipient_user_id,
+            });
+        }
+
+        // whatever is left after the transfers is the fee
+        builder.connect(remaining_amount, fee);
+
+        let fee_credit_gadget = SequencerFeeCreditGadget::add_virtual_to::<H, F, D>(
+            builder,
+            fee,
+            fee_recipient_user_id,
+        );
+        builder.connect_hashes(
+            last_root,
+            fee_credit_gadget
+                .fee_recipient_user_tree_delta_merkle_proof_gadget
+                .old_root,
+        );
+
+        Self {
+            sender_user_tree_delta_merkle_proof_gadget,
+            recipients,
+            fee_credit_gadget,
+            fee,
+            sender_old_user_state,
+            sender_new_user_state,
+            total_paid_amount,
+        }
+    }
+    pub fn set_witness<W: Witness<F>, F: RichField>(
+        &self,
+        witness: &mut W,
+        sender_user_tree_delta_merkle_proof: &DeltaMerkleProofCore<QHashOut<F>>,
+        receiver_user_tree_delta_merkle_proofs: &[DeltaMerkleProofCore<QHashOut<F>>],
+        fee_recipient_user_tree_delta_merkle_proof: &DeltaMerkleProofCore<QHashOut<F>>,
+        recipient_count: usize,
+        fee: u64,
+    ) {
+        assert_eq!(
+            receiver_user_tree_delta_merkle_proofs.len(),
+            self.recipients.len(),
+            "a batch transfer requires exactly one receiver proof per slot"
+        );
+        self.sender_user_tree_delta_merkle_proof_gadget
+            .set_witness_core_proof_q(witness, sender_user_tree_delta_merkle_proof);
+        for (i, (recipient, proof)) in self
+            .recipients
+            .iter()
+            .zip(receiver_user_tree_delta_merkle_proofs.iter())
+            .enumerate()
+        {
+            recipient
+                .receiver_user_tree_delta_merkle_proof_gadget
+                .set_witness_core_proof_q(witness, proof);
+            witness.set_bool_target(recipient.is_active, i < recipient_count);
+            let amount = proof.new_value.0.elements[0] - proof.old_value.0.elements[0];
+            witness.set_target(recipient.amount, amount);
+        }
+        self.fee_credit_gadget
+            .set_witness(witness, fee_recipient_user_tree_delta_merkle_proof);
+        witness.set_target(self.fee, F::from_noncanonical_u64(fee));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic::AssertUnwindSafe;
+
+    use city_common::config::rollup_constants::L2_BATCH_TRANSFER_MAX_RECIPIENTS;
+    use city_crypto::hash::qhashout::QHashOut;
+    use city_store::{config::CityDeltaMerkleProof, store::city::base::CityStore};
+    use kvq::memory::simple::KVQSimpleMemoryBackingStore;
+    use plonky2::{
+        field::types::Field,
+        hash::poseidon::PoseidonHash,
+        iop::witness::{PartialWitness, WitnessWrite},
+        plonk::{
+            circuit_builder::CircuitBuilder,
+            circuit_data::CircuitConfig,
+            config::{GenericConfig, PoseidonGoldilocksConfig},
+        },
+    };
+
+    use super::BatchL2TransferStateUpdateGadget;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = KVQSimpleMemoryBackingStore;
+
+    const FEE_RECIPIENT_USER_ID: u64 = 1;
+    const SENDER_USER_ID: u64 = 3;
+
+    type Slots = [(u64, u64); L2_BATCH_TRANSFER_MAX_RECIPIENTS];
+
+    struct BatchProofs {
+        sender: CityDeltaMerkleProof,
+        receivers: Vec<CityDeltaMerkleProof>,
+        fee_credit: CityDeltaMerkleProof,
+    }
+
+    // users 1, 3, 4, 5 and 6 are registered at checkpoint 1 and the sender (user 3) holds 1000
+    fn build_store() -> S {
+        let mut store = S::new();
+        for user_id in [1, 3, 4, 5, 6] {
+            let public_key = QHashOut::from_values(user_id, user_id, user_id, user_id);
+            CityStore::<S>::register_user(&mut store, 1, user_id, public_key).unwrap();
+        }
+        CityStore::<S>::increment_user_balance(&mut store, 1, SENDER_USER_ID, 1000, None).unwrap();
+        store
+    }
+
+    // debits the sender, applies one (recipient, amount) update per slot and credits the fee
+    fn apply_batch(debit: u64, slots: Slots, fee: u64) -> BatchProofs {
+        let mut store = build_store();
+        let sender =
+            CityStore::<S>::decrement_user_balance(&mut store, 2, SENDER_USER_ID, debit, Some(1))
+                .unwrap();
+        let receivers = slots
+            .iter()
+            .map(|(to, amount)| {
+                CityStore::<S>::increment_user_balance(&mut store, 2, *to, *amount, None).unwrap()
+            })
+            .collect();
+        let fee_credit =
+            CityStore::<S>::credit_sequencer_fee(&mut store, 2, FEE_RECIPIENT_USER_ID, fee)
+                .unwrap();
+        BatchProofs {
+            sender,
+            receivers,
+            fee_credit,
+        }
+    }
+
+    // true if the batch proves with the given active flags. set_witness can only mark the
+    // leading slots as active, so the slots are written by hand
+    fn prove_batch(
+        proofs: &BatchProofs,
+        active: [bool; L2_BATCH_TRANSFER_MAX_RECIPIENTS],
+        fee: u64,
+    ) -> bool {
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let gadget = BatchL2TransferStateUpdateGadget::add_virtual_to::<PoseidonHash, F, D>(
+            &mut builder,
+            FEE_RECIPIENT_USER_ID,
+        );
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        gadget
+            .sender_user_tree_delta_merkle_proof_gadget
+            .set_witness_core_proof_q(&mut pw, &proofs.sender);
+        for ((recipient, proof), is_active) in gadget
+            .recipients
+            .iter()
+            .zip(proofs.receivers.iter())
+            .zip(active)
+        {
+            recipient
+                .receiver_user_tree_delta_merkle_proof_gadget
+                .set_witness_core_proof_q(&mut pw, proof);
+            pw.set_bool_target(recipient.is_active, is_active);
+            pw.set_target(
+                recipient.amount,
+                proof.new_value.0.elements[0] - proof.old_value.0.elements[0],
+            );
+        }
+        gadget
+            .fee_credit_gadget
+            .set_witness(&mut pw, &proofs.fee_credit);
+        pw.set_target(gadget.fee, F::from_canonical_u64(fee));
+        // witness generation panics on some conflicting constraints instead of returning an error
+        match std::panic::catch_unwind(AssertUnwindSafe(|| data.prove(pw))) {
+            Ok(Ok(proof)) => data.verify(proof).is_ok(),
+            _ => false,
+        }
+    }
+
+    const TWO_TRANSFERS: Slots = [(4, 100), (5, 200), (4, 0), (4, 0)];
+
+    #[test]
+    fn sender_debit_must_equal_the_transfers_plus_the_fee() {
+        let active = [true, true, false, false];
+        assert!(prove_batch(&apply_batch(305, TWO_TRANSFERS, 5), active, 5));
+
+        // debiting more than the transfers and the fee is rejected, whether the difference is
+        // left out of the fee or added to it
+        let over_debit = apply_batch(306, TWO_TRANSFERS, 5);
+        assert!(!prove_batch(&over_debit, active, 5));
+        assert!(!prove_batch(&over_debit, active, 6));
+        assert!(!prove_batch(&apply_batch(306, TWO_TRANSFERS, 6), active, 5));
+
+        // debiting less than the transfers and the fee is rejected
+        assert!(!prove_batch(&apply_batch(304, TWO_TRANSFERS, 5), active, 5));
+        assert!(!prove_batch(&apply_batch(299, TWO_TRANSFERS, 0), active, 0));
+    }
+
+    #[test]
+    fn inactive_slot_with_an_amount_is_rejected() {
+        let proofs = apply_batch(355, [(4, 100), (5, 200), (6, 50), (4, 0)], 5);
+        assert!(prove_batch(&proofs, [true, true, true, false], 5));
+        assert!(!prove_batch(&proofs, [true, true, false, false], 5));
+    }
+
+    #[test]
+    fn gap_in_the_active_slots_is_rejected() {
+        let proofs = apply_batch(305, [(4, 100), (4, 0), (5, 200), (4, 0)], 5);
+        // an active slot may transfer zero, so the same updates prove without the gap
+        assert!(prove_batch(&proofs, [true, true, true, false], 5));
+        assert!(!prove_batch(&proofs, [true, false, true, false], 5));
+
+        // the first slot must always be active
+        let proofs = apply_batch(205, [(4, 0), (5, 200), (4, 0), (4, 0)], 5);
+        assert!(prove_batch(&proofs, [true, true, false, false], 5));
+        assert!(!prove_batch(&proofs, [false, true, false, false], 5));
+    }
+
+    #[test]
+    fn self_transfer_is_rejected() {
+        let proofs = apply_batch(105, [(SENDER_USER_ID, 100), (4, 0), (4, 0), (4, 0)], 5);
+        assert!(!prove_batch(&proofs, [true, false, false, false], 5));
+
+        // a zero-value padding slot on the sender's leaf is rejected as well
+        let proofs = apply_batch(105, [(4, 100), (SENDER_USER_ID, 0), (4, 0), (4, 0)], 5);
+        assert!(!prove_batch(&proofs, [true, false, false, false], 5));
+    }
+}
@@ -0,0 +1,66 @@
+use city_rollup_common::qworker::job_witnesses::op::CRTokenStateTransitionInput;
+use plonky2::{
+    field::extension::Extendable,
+    hash::hash_types::RichField,
+    iop::{target::Target, witness::Witness},
+    plonk::{circuit_builder::CircuitBuilder, config::AlgebraicHasher},
+};
+
+use super::token_state::{ensure_valid_token_amount_decrease, TokenStateTransitionGadget};
+
+#[derive(Debug, Clone)]
+pub struct BurnTokenStateUpdateGadget {
+    // inputs:
+    pub token_state_transition_gadget: TokenStateTransitionGadget,
+
+    // computed:
+    pub token_id: Target,
+    pub burn_amount: Target,
+}
+
+impl BurnTokenStateUpdateGadget {
+    pub fn add_virtual_to<H: AlgebraicHasher<F>, F: RichField + Extendable<D>, const D: usize>(
+        builder: &mut CircuitBuilder<F, D>,
+    ) -> Self {
+        let token_state_transition_gadget =
+            TokenStateTransitionGadget::add_virtual_to::<H, F, D>(builder, 1);
+        let token_id = token_state_transition_gadget.token_id;
+        let registry_gadget =
+            &token_state_transition_gadget.token_registry_delta_merkle_proof_gadget;
+        let holder_balance_gadget =
+            &token_state_transition_gadget.token_balance_delta_merkle_proof_gadgets[0];
+
+        // decrement the signer's token balance by X
+        builder.connect(
+            token_state_transition_gadget.token_balance_user_ids[0],
+            token_state_transition_gadget.signer_old_user_state.user_id,
+        );
+        let burn_amount = ensure_valid_token_amount_decrease(
+            builder,
+            holder_balance_gadget.old_value,
+            holder_balance_gadget.new_value,
+        );
+
+        // decrement the supply of the token by X
+        let supply_decrease = ensure_valid_token_amount_decrease(
+            builder,
+            registry_gadget.old_value,
+            registry_gadget.new_value,
+        );
+        builder.connect(supply_decrease, burn_amount);
+
+        Self {
+            token_state_transition_gadget,
+            token_id,
+            burn_amount,
+        }
+    }
+    pub fn set_witness<W: Witness<F>, F: RichField>(
+        &self,
+        witness: &mut W,
+        input: &CRTokenStateTransitionInput<F>,
+    ) {
+        self.token_state_transition_gadget
+            .set_witness(witness, input);
+    }
+}
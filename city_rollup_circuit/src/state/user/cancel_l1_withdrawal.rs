@@ -0,0 +1,317 @@
+use city_common::config::rollup_constants::{
+    GLOBAL_USER_TREE_HEIGHT, L1_WITHDRAWAL_TREE_HEIGHT, WITHDRAWAL_FEE_AMOUNT,
+};
+use city_common_circuit::{
+    builder::{core::CircuitBuilderHelpersCore, hash::core::CircuitBuilderHashCore},
+    hash::merkle::gadgets::delta_merkle_proof::DeltaMerkleProofGadget,
+};
+use city_crypto::hash::{
+    merkle::core::DeltaMerkleProofCore, qhashout::QHashOut, traits::hasher::MerkleZeroHasher,
+};
+use city_rollup_common::introspection::rollup::constants::SIG_ACTION_CANCEL_WITHDRAWAL_MAGIC;
+use plonky2::{
+    field::extension::Extendable,
+    hash::hash_types::{HashOut, HashOutTarget, RichField},
+    iop::{target::Target, witness::Witness},
+    plonk::{circuit_builder::CircuitBuilder, config::AlgebraicHasher},
+};
+
+use crate::introspection::gadgets::rollup::{
+    introspection_result::BTCRollupIntrospectionResultWithdrawalGadget,
+    signature::compute_sig_action_hash_circuit,
+};
+
+use super::{add_l1_withdrawal::compute_withdrawal_leaf_hash_circuit, user_state::UserStateGadget};
+
+#[derive(Debug, Clone)]
+pub struct CancelL1WithdrawalGadget {
+    // inputs:
+    pub withdrawal_tree_delta_merkle_proof_gadget: DeltaMerkleProofGadget,
+    pub user_tree_delta_merkle_proof_gadget: DeltaMerkleProofGadget,
+    pub withdrawal_hash: HashOutTarget,
+
+    // computed:
+    pub old_user_state: UserStateGadget,
+    pub new_user_state: UserStateGadget,
+    pub withdrawal_id: Target,
+    pub withdrawal_amount: Target,
+    pub refund_amount: Target,
+}
+
+impl CancelL1WithdrawalGadget {
+    pub fn add_virtual_to<
+        H: MerkleZeroHasher<HashOut<F>> + AlgebraicHasher<F>,
+        F: RichField + Extendable<D>,
+        const D: usize,
+    >(
+        builder: &mut CircuitBuilder<F, D>,
+    ) -> Self {
+        let withdrawal_tree_delta_merkle_proof_gadget: DeltaMerkleProofGadget =
+            DeltaMerkleProofGadget::add_virtual_to::<H, F, D>(
+                builder,
+                L1_WITHDRAWAL_TREE_HEIGHT as usize,
+            );
+
+        // the withdrawal must still be pending (processed or cancelled withdrawals are zeroed)
+        builder.ensure_hash_is_non_zero(withdrawal_tree_delta_merkle_proof_gadget.old_value);
+
+        // the cancelled withdrawal is removed from the queue
+        builder.ensure_hash_is_zero(withdrawal_tree_delta_merkle_proof_gadget.new_value);
+
+        let withdrawal_id = withdrawal_tree_delta_merkle_proof_gadget.index;
+        let withdrawal_hash = builder.add_virtual_hash();
+
+        let withdrawal_amount =
+            BTCRollupIntrospectionResultWithdrawalGadget::validate_withdrawal_hash_get_amount(
+                builder,
+                withdrawal_hash,
+            );
+
+        let user_tree_delta_merkle_proof_gadget: DeltaMerkleProofGadget =
+            DeltaMerkleProofGadget::add_virtual_to_u8h::<H, F, D>(builder, GLOBAL_USER_TREE_HEIGHT);
+
+        let old_user_state = UserStateGadget::new_from_delta_merkle_proof_left_leaf(
+            builder,
+            &user_tree_delta_merkle_proof_gadget,
+        );
+
+        // the leaf commits to the user who added the withdrawal, only they can cancel it
+        let withdrawal_leaf_hash = compute_withdrawal_leaf_hash_circuit::<H, F, D>(
+            builder,
+            withdrawal_hash,
+            old_user_state.user_id,
+        );
+        builder.connect_hashes(
+            withdrawal_tree_delta_merkle_proof_gadget.old_value,
+            withdrawal_leaf_hash,
+        );
+
+        // refund everything the withdrawal locked except the sequencer fee, which was already credited
+        let withdrawal_fee = builder.constant_u64(WITHDRAWAL_FEE_AMOUNT);
+        let refund_amount = builder.add(withdrawal_amount, withdrawal_fee);
+
+        let new_user_state = old_user_state.ensure_valid_increase_balance_known_amount(
+            builder,
+            user_tree_delta_merkle_proof_gadget.new_value,
+            refund_amount,
+            true,
+        );
+
+        Self {
+            withdrawal_tree_delta_merkle_proof_gadget,
+            user_tree_delta_merkle_proof_gadget,
+            withdrawal_hash,
+            old_user_state,
+            new_user_state,
+            withdrawal_id,
+            withdrawal_amount,
+            refund_amount,
+        }
+    }
+    pub fn set_witness<W: Witness<F>, F: RichField>(
+        &self,
+        witness: &mut W,
+        withdrawal_tree_delta_merkle_proof: &DeltaMerkleProofCore<QHashOut<F>>,
+        user_tree_delta_merkle_proof: &DeltaMerkleProofCore<QHashOut<F>>,
+        withdrawal_hash: QHashOut<F>,
+    ) {
+        self.withdrawal_tree_delta_merkle_proof_gadget
+            .set_witness_core_proof_q(witness, withdrawal_tree_delta_merkle_proof);
+        self.user_tree_delta_merkle_proof_gadget
+            .set_witness_core_proof_q(witness, user_tree_delta_merkle_proof);
+        witness.set_hash_target(self.withdrawal_hash, withdrawal_hash.0);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CancelL1WithdrawalSingleGadget {
+    // inputs:
+    pub cancel_withdrawal_gadget: CancelL1WithdrawalGadget,
+
+    // computed:
+    pub expected_signature_hash: HashOutTarget,
+    pub expected_public_key: HashOutTarget,
+
+    pub old_user_tree_root: HashOutTarget,
+    pub new_user_tree_root: HashOutTarget,
+
+    pub old_withdrawal_tree_root: HashOutTarget,
+    pub new_withdrawal_tree_root: HashOutTarget,
+
+    pub combined_state_transition_hash: HashOutTarget,
+}
+impl CancelL1WithdrawalSingleGadget {
+    pub fn add_virtual_to<
+        H: AlgebraicHasher<F> + MerkleZeroHasher<HashOut<F>>,
+        F: RichField + Extendable<D>,
+        const D: usize,
+    >(
+        builder: &mut CircuitBuilder<F, D>,
+        network_magic: u64,
+    ) -> Self {
+        let cancel_withdrawal_gadget = CancelL1WithdrawalGadget::add_virtual_to::<H, F, D>(builder);
+
+        let sig_action_id = builder.constant_u64(SIG_ACTION_CANCEL_WITHDRAWAL_MAGIC);
+        let network_magic_target = builder.constant_u64(network_magic);
+        let user_id = cancel_withdrawal_gadget.old_user_state.user_id;
+        let new_user_nonce = cancel_withdrawal_gadget.new_user_state.nonce;
+        let withdrawal_id = cancel_withdrawal_gadget.withdrawal_id;
+        let withdrawal_hash = cancel_withdrawal_gadget.withdrawal_hash;
+
+        let expected_signature_hash = compute_sig_action_hash_circuit::<H, F, D>(
+            builder,
+            network_magic_target,
+            user_id,
+            sig_action_id,
+            new_user_nonce,
+            &[
+                withdrawal_id,
+                withdrawal_hash.elements[0],
+                withdrawal_hash.elements[1],
+                withdrawal_hash.elements[2],
+                withdrawal_hash.elements[3],
+            ],
+        );
+        let expected_public_key = cancel_withdrawal_gadget.old_user_state.public_key;
+
+        let old_user_tree_root = cancel_withdrawal_gadget
+            .user_tree_delta_merkle_proof_gadget
+            .old_root;
+        let new_user_tree_root = cancel_withdrawal_gadget
+            .user_tree_delta_merkle_proof_gadget
+            .new_root;
+
+        let old_withdrawal_tree_root = cancel_withdrawal_gadget
+            .withdrawal_tree_delta_merkle_proof_gadget
+            .old_root;
+        let new_withdrawal_tree_root = cancel_withdrawal_gadget
+            .withdrawal_tree_delta_merkle_proof_gadget
+            .new_root;
+
+        let old_state_transition_hash =
+            builder.hash_two_to_one::<H>(old_user_tree_root, old_withdrawal_tree_root);
+
+        let new_state_transition_hash =
+            builder.hash_two_to_one::<H>(new_user_tree_root, new_withdrawal_tree_root);
+
+        let combined_state_transition_hash =
+            builder.hash_two_to_one::<H>(old_state_transition_hash, new_state_transition_hash);
+
+        Self {
+            cancel_withdrawal_gadget,
+            expected_signature_hash,
+            expected_public_key,
+            old_user_tree_root,
+            new_user_tree_root,
+            old_withdrawal_tree_root,
+            new_withdrawal_tree_root,
+            combined_state_transition_hash,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic::AssertUnwindSafe;
+
+    use city_common::config::rollup_constants::WITHDRAWAL_FEE_AMOUNT;
+    use city_crypto::hash::{base_types::hash160::Hash160, qhashout::QHashOut};
+    use city_rollup_common::{
+        api::data::block::requested_actions::CityAddWithdrawalRequest,
+        qworker::job_id::QProvingJobDataID,
+    };
+    use city_store::{
+        config::{CityDeltaMerkleProof, CityHash},
+        store::city::base::CityStore,
+    };
+    use kvq::memory::simple::KVQSimpleMemoryBackingStore;
+    use plonky2::{
+        hash::poseidon::PoseidonHash,
+        iop::witness::PartialWitness,
+        plonk::{
+            circuit_builder::CircuitBuilder,
+            circuit_data::CircuitConfig,
+            config::{GenericConfig, PoseidonGoldilocksConfig},
+        },
+    };
+
+    use super::CancelL1WithdrawalGadget;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = KVQSimpleMemoryBackingStore;
+
+    // users 3 and 5 are registered and user 3 has queued withdrawal 0
+    fn store_with_withdrawal() -> S {
+        let mut store = S::new();
+        CityStore::<S>::register_user(&mut store, 1, 3, QHashOut::from_values(3, 3, 3, 3)).unwrap();
+        CityStore::<S>::register_user(&mut store, 1, 5, QHashOut::from_values(5, 5, 5, 5)).unwrap();
+        let req = CityAddWithdrawalRequest::new(
+            3,
+            1000,
+            0,
+            1,
+            0,
+            Hash160([7u8; 20]),
+            QProvingJobDataID::withdrawal_signature_proof(0, 1, 0),
+        );
+        CityStore::<S>::add_withdrawal_to_tree_from_request(&mut store, 1, 0, &req).unwrap();
+        store
+    }
+
+    // cancels withdrawal 0 at checkpoint 2 and refunds user_id
+    fn cancel_withdrawal(user_id: u64) -> (CityDeltaMerkleProof, CityDeltaMerkleProof, CityHash) {
+        let mut store = store_with_withdrawal();
+        let withdrawal_hash = CityStore::<S>::get_withdrawal_hash(&store, 2, 0).unwrap();
+        let withdrawal_proof = CityStore::<S>::cancel_withdrawal(&mut store, 2, 0).unwrap();
+        let user_proof = CityStore::<S>::increment_user_balance(
+            &mut store,
+            2,
+            user_id,
+            1000 + WITHDRAWAL_FEE_AMOUNT,
+            Some(1),
+        )
+        .unwrap();
+        (withdrawal_proof, user_proof, withdrawal_hash)
+    }
+
+    // true if the cancellation proves
+    fn prove_cancel(
+        withdrawal_proof: &CityDeltaMerkleProof,
+        user_proof: &CityDeltaMerkleProof,
+        withdrawal_hash: CityHash,
+    ) -> bool {
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let gadget = CancelL1WithdrawalGadget::add_virtual_to::<PoseidonHash, F, D>(&mut builder);
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        gadget.set_witness(&mut pw, withdrawal_proof, user_proof, withdrawal_hash);
+        // witness generation panics on some conflicting constraints instead of returning an error
+        match std::panic::catch_unwind(AssertUnwindSafe(|| data.prove(pw))) {
+            Ok(Ok(proof)) => data.verify(proof).is_ok(),
+            _ => false,
+        }
+    }
+
+    #[test]
+    fn owner_can_cancel_withdrawal() {
+        let (withdrawal_proof, user_proof, withdrawal_hash) = cancel_withdrawal(3);
+        assert!(prove_cancel(
+            &withdrawal_proof,
+            &user_proof,
+            withdrawal_hash
+        ));
+    }
+
+    #[test]
+    fn cancelling_another_users_withdrawal_is_rejected() {
+        let (withdrawal_proof, user_proof, withdrawal_hash) = cancel_withdrawal(5);
+        assert!(!prove_cancel(
+            &withdrawal_proof,
+            &user_proof,
+            withdrawal_hash
+        ));
+    }
+}
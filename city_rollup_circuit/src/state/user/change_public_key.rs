@@ -0,0 +1,109 @@
+use city_common::config::rollup_constants::GLOBAL_USER_TREE_HEIGHT;
+use city_common_circuit::{
+    builder::hash::core::CircuitBuilderHashCore,
+    hash::merkle::gadgets::delta_merkle_proof::DeltaMerkleProofGadget,
+};
+use city_crypto::hash::{merkle::core::DeltaMerkleProofCore, qhashout::QHashOut};
+use plonky2::{
+    field::extension::Extendable,
+    hash::hash_types::{HashOutTarget, RichField},
+    iop::witness::Witness,
+    plonk::{circuit_builder::CircuitBuilder, config::AlgebraicHasher},
+};
+
+use super::user_state::UserStateGadget;
+
+#[derive(Debug, Clone)]
+pub struct ChangePublicKeyGadget {
+    // inputs:
+    pub user_tree_nonce_delta_merkle_proof_gadget: DeltaMerkleProofGadget,
+    pub user_tree_public_key_delta_merkle_proof_gadget: DeltaMerkleProofGadget,
+
+    // computed:
+    pub old_user_state: UserStateGadget,
+    pub new_user_state: UserStateGadget,
+    pub old_public_key: HashOutTarget,
+    pub new_public_key: HashOutTarget,
+}
+
+impl ChangePublicKeyGadget {
+    pub fn add_virtual_to<H: AlgebraicHasher<F>, F: RichField + Extendable<D>, const D: usize>(
+        builder: &mut CircuitBuilder<F, D>,
+    ) -> Self {
+        // step 1: bump the nonce in the user's left leaf
+        let user_tree_nonce_delta_merkle_proof_gadget: DeltaMerkleProofGadget =
+            DeltaMerkleProofGadget::add_virtual_to_u8h::<H, F, D>(builder, GLOBAL_USER_TREE_HEIGHT);
+
+        let old_user_state = UserStateGadget::new_from_delta_merkle_proof_left_leaf(
+            builder,
+            &user_tree_nonce_delta_merkle_proof_gadget,
+        );
+        let old_public_key = old_user_state.public_key;
+
+        // ensure the user exists (a key can only be rotated for a registered user)
+        builder.ensure_hash_is_non_zero(old_public_key);
+
+        // the balance and alt slots must stay the same, only the nonce is increased
+        let zero = builder.zero();
+        let nonce_user_state = old_user_state
+            .ensure_valid_increase_balance_known_amount_allow_zero(
+                builder,
+                user_tree_nonce_delta_merkle_proof_gadget.new_value,
+                zero,
+                true,
+            );
+
+        // step 2: replace the public key in the user's right leaf
+        let user_tree_public_key_delta_merkle_proof_gadget: DeltaMerkleProofGadget =
+            DeltaMerkleProofGadget::add_virtual_to_u8h::<H, F, D>(builder, GLOBAL_USER_TREE_HEIGHT);
+
+        // the right leaf proof must be for the same user (index = user_id * 2 + 1)
+        let public_key_user_state = UserStateGadget::new_from_right_leaf_index_known_user_id(
+            builder,
+            old_user_state.user_id,
+            user_tree_public_key_delta_merkle_proof_gadget.index,
+            user_tree_public_key_delta_merkle_proof_gadget.siblings[0],
+            user_tree_public_key_delta_merkle_proof_gadget.old_value,
+        );
+
+        // the public key proof must be applied on top of the nonce update
+        builder.connect_hashes(
+            user_tree_nonce_delta_merkle_proof_gadget.new_root,
+            user_tree_public_key_delta_merkle_proof_gadget.old_root,
+        );
+        builder.connect_hashes(
+            user_tree_public_key_delta_merkle_proof_gadget.siblings[0],
+            user_tree_nonce_delta_merkle_proof_gadget.new_value,
+        );
+        builder.connect_hashes(public_key_user_state.public_key, old_public_key);
+
+        // the new public key should be non-zero, otherwise the account would become unregistered
+        let new_public_key = user_tree_public_key_delta_merkle_proof_gadget.new_value;
+        builder.ensure_hash_is_non_zero(new_public_key);
+
+        let new_user_state = UserStateGadget {
+            public_key: new_public_key,
+            ..nonce_user_state
+        };
+
+        Self {
+            user_tree_nonce_delta_merkle_proof_gadget,
+            user_tree_public_key_delta_merkle_proof_gadget,
+            old_user_state,
+            new_user_state,
+            old_public_key,
+            new_public_key,
+        }
+    }
+    pub fn set_witness<W: Witness<F>, F: RichField>(
+        &self,
+        witness: &mut W,
+        user_tree_nonce_delta_merkle_proof: &DeltaMerkleProofCore<QHashOut<F>>,
+        user_tree_public_key_delta_merkle_proof: &DeltaMerkleProofCore<QHashOut<F>>,
+    ) {
+        self.user_tree_nonce_delta_merkle_proof_gadget
+            .set_witness_core_proof_q(witness, user_tree_nonce_delta_merkle_proof);
+        self.user_tree_public_key_delta_merkle_proof_gadget
+            .set_witness_core_proof_q(witness, user_tree_public_key_delta_merkle_proof);
+    }
+}
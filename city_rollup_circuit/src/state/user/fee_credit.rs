@@ -0,0 +1,149 @@
+use city_common::config::rollup_constants::GLOBAL_USER_TREE_HEIGHT;
+use city_common_circuit::{
+    builder::{core::CircuitBuilderHelpersCore, hash::core::CircuitBuilderHashCore},
+    hash::merkle::gadgets::delta_merkle_proof::DeltaMerkleProofGadget,
+};
+use city_crypto::hash::{merkle::core::DeltaMerkleProofCore, qhashout::QHashOut};
+use plonky2::{
+    field::extension::Extendable,
+    hash::hash_types::RichField,
+    iop::{target::Target, witness::Witness},
+    plonk::{circuit_builder::CircuitBuilder, config::AlgebraicHasher},
+};
+
+use super::user_state::UserStateGadget;
+
+#[derive(Debug, Clone)]
+pub struct SequencerFeeCreditGadget {
+    // inputs:
+    pub fee_recipient_user_tree_delta_merkle_proof_gadget: DeltaMerkleProofGadget,
+
+    // computed:
+    pub fee_recipient_old_user_state: UserStateGadget,
+    pub fee_recipient_new_user_state: UserStateGadget,
+}
+
+impl SequencerFeeCreditGadget {
+    pub fn add_virtual_to<H: AlgebraicHasher<F>, F: RichField + Extendable<D>, const D: usize>(
+        builder: &mut CircuitBuilder<F, D>,
+        fee: Target,
+        fee_recipient_user_id: u64,
+    ) -> Self {
+        let fee_recipient_user_tree_delta_merkle_proof_gadget: DeltaMerkleProofGadget =
+            DeltaMerkleProofGadget::add_virtual_to_u8h::<H, F, D>(builder, GLOBAL_USER_TREE_HEIGHT);
+
+        // ensure the fee recipient has a non-zero public key (i.e the fee recipient already exists on the network)
+        builder
+            .ensure_hash_is_non_zero(fee_recipient_user_tree_delta_merkle_proof_gadget.siblings[0]);
+
+        let fee_recipient_old_user_state = UserStateGadget::new_from_delta_merkle_proof_left_leaf(
+            builder,
+            &fee_recipient_user_tree_delta_merkle_proof_gadget,
+        );
+
+        // ensure the fee is credited to the designated fee recipient
+        let expected_fee_recipient_user_id = builder.constant_u64(fee_recipient_user_id);
+        builder.connect(
+            fee_recipient_old_user_state.user_id,
+            expected_fee_recipient_user_id,
+        );
+
+        // the fee may be zero, in which case the fee recipient's leaf is left unchanged
+        let fee_recipient_new_user_state = fee_recipient_old_user_state
+            .ensure_valid_increase_balance_known_amount_allow_zero(
+                builder,
+                fee_recipient_user_tree_delta_merkle_proof_gadget.new_value,
+                fee,
+                false,
+            );
+
+        Self {
+            fee_recipient_user_tree_delta_merkle_proof_gadget,
+            fee_recipient_old_user_state,
+            fee_recipient_new_user_state,
+        }
+    }
+    pub fn set_witness<W: Witness<F>, F: RichField>(
+        &self,
+        witness: &mut W,
+        fee_recipient_user_tree_delta_merkle_proof: &DeltaMerkleProofCore<QHashOut<F>>,
+    ) {
+        self.fee_recipient_user_tree_delta_merkle_proof_gadget
+            .set_witness_core_proof_q(witness, fee_recipient_user_tree_delta_merkle_proof);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic::AssertUnwindSafe;
+
+    use city_crypto::hash::qhashout::QHashOut;
+    use city_store::{config::CityDeltaMerkleProof, store::city::base::CityStore};
+    use kvq::memory::simple::KVQSimpleMemoryBackingStore;
+    use plonky2::{
+        field::types::Field,
+        hash::poseidon::PoseidonHash,
+        iop::witness::{PartialWitness, WitnessWrite},
+        plonk::{
+            circuit_builder::CircuitBuilder,
+            circuit_data::CircuitConfig,
+            config::{GenericConfig, PoseidonGoldilocksConfig},
+        },
+    };
+
+    use super::SequencerFeeCreditGadget;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = KVQSimpleMemoryBackingStore;
+
+    // credits `fee` to user 3, users 3 and 5 are registered
+    fn credit_fee(fee: u64) -> CityDeltaMerkleProof {
+        let mut store = S::new();
+        CityStore::<S>::register_user(&mut store, 1, 3, QHashOut::from_values(3, 3, 3, 3)).unwrap();
+        CityStore::<S>::register_user(&mut store, 1, 5, QHashOut::from_values(5, 5, 5, 5)).unwrap();
+        CityStore::<S>::credit_sequencer_fee(&mut store, 1, 3, fee).unwrap()
+    }
+
+    // true if the fee credit proves in a circuit built for fee_recipient_user_id
+    fn prove_fee_credit(
+        fee_recipient_user_id: u64,
+        fee: u64,
+        proof: &CityDeltaMerkleProof,
+    ) -> bool {
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let fee_target = builder.add_virtual_target();
+        let gadget = SequencerFeeCreditGadget::add_virtual_to::<PoseidonHash, F, D>(
+            &mut builder,
+            fee_target,
+            fee_recipient_user_id,
+        );
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(fee_target, F::from_canonical_u64(fee));
+        gadget.set_witness(&mut pw, proof);
+        // witness generation panics on some conflicting constraints instead of returning an error
+        match std::panic::catch_unwind(AssertUnwindSafe(|| data.prove(pw))) {
+            Ok(Ok(proof)) => data.verify(proof).is_ok(),
+            _ => false,
+        }
+    }
+
+    #[test]
+    fn fee_is_credited_to_the_configured_recipient() {
+        let proof = credit_fee(100);
+        assert!(prove_fee_credit(3, 100, &proof));
+        // a zero fee leaves the recipient's leaf unchanged
+        assert!(prove_fee_credit(3, 0, &credit_fee(0)));
+    }
+
+    #[test]
+    fn fee_credited_to_another_user_is_rejected() {
+        let proof = credit_fee(100);
+        assert!(!prove_fee_credit(5, 100, &proof));
+        // the credited amount must match the fee
+        assert!(!prove_fee_credit(3, 99, &proof));
+    }
+}
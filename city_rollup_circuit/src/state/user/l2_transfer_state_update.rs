@@ -1,4 +1,4 @@
-use city_common::config::rollup_constants::GLOBAL_USER_TREE_HEIGHT;
+use city_common::config::rollup_constants::{BALANCE_BIT_SIZE, GLOBAL_USER_TREE_HEIGHT};
 use city_common_circuit::{
     builder::{comparison::CircuitBuilderComparison, hash::core::CircuitBuilderHashCore},
     hash::merkle::gadgets::delta_merkle_proof::DeltaMerkleProofGadget,
@@ -11,13 +11,15 @@ use plonky2::{
     plonk::{circuit_builder::CircuitBuilder, config::AlgebraicHasher},
 };
 
-use super::user_state::UserStateGadget;
+use super::{fee_credit::SequencerFeeCreditGadget, user_state::UserStateGadget};
 
 #[derive(Debug, Clone)]
 pub struct L2TransferStateUpdateGadget {
     // inputs:
     pub sender_user_tree_delta_merkle_proof_gadget: DeltaMerkleProofGadget,
     pub receiver_user_tree_delta_merkle_proof_gadget: DeltaMerkleProofGadget,
+    pub fee_credit_gadget: SequencerFeeCreditGadget,
+    pub fee: Target,
 
     // computed:
     pub sender_old_user_state: UserStateGadget,
@@ -25,11 +27,13 @@ pub struct L2TransferStateUpdateGadget {
     pub receiver_old_user_state: UserStateGadget,
     pub receiver_new_user_state: UserStateGadget,
     pub transfer_amount: Target,
+    pub total_paid_amount: Target,
 }
 
 impl L2TransferStateUpdateGadget {
     pub fn add_virtual_to<H: AlgebraicHasher<F>, F: RichField + Extendable<D>, const D: usize>(
         builder: &mut CircuitBuilder<F, D>,
+        fee_recipient_user_id: u64,
     ) -> Self {
         let sender_user_tree_delta_merkle_proof_gadget: DeltaMerkleProofGadget =
             DeltaMerkleProofGadget::add_virtual_to_u8h::<H, F, D>(builder, GLOBAL_USER_TREE_HEIGHT);
@@ -49,26 +53,45 @@ impl L2TransferStateUpdateGadget {
             receiver_user_tree_delta_merkle_proof_gadget.index,
         );
 
+        let fee = builder.add_virtual_target();
+        builder.range_check(fee, BALANCE_BIT_SIZE);
+        let fee_credit_gadget = SequencerFeeCreditGadget::add_virtual_to::<H, F, D>(
+            builder,
+            fee,
+            fee_recipient_user_id,
+        );
+
         // ensure that the delta merkle proofs are back-to-back state transitions
-        // 1. decrement sender's balance by X and update sender's nonce
+        // 1. decrement sender's balance by X + fee and update sender's nonce
         // 2. increment receiver's balance by X
+        // 3. increment fee recipient's balance by fee
         builder.connect_hashes(
             sender_user_tree_delta_merkle_proof_gadget.new_root,
             receiver_user_tree_delta_merkle_proof_gadget.old_root,
         );
+        builder.connect_hashes(
+            receiver_user_tree_delta_merkle_proof_gadget.new_root,
+            fee_credit_gadget
+                .fee_recipient_user_tree_delta_merkle_proof_gadget
+                .old_root,
+        );
 
         let sender_old_user_state = UserStateGadget::new_from_delta_merkle_proof_left_leaf(
             builder,
             &sender_user_tree_delta_merkle_proof_gadget,
         );
 
-        let (transfer_amount, sender_new_user_state) = sender_old_user_state
+        let (total_paid_amount, sender_new_user_state) = sender_old_user_state
             .ensure_valid_decrease_balance(
                 builder,
                 sender_user_tree_delta_merkle_proof_gadget.new_value,
                 true,
             );
 
+        // ensure the fee does not exceed the amount paid by the sender (i.e that (total_paid_amount - fee) does not underflow)
+        builder.ensure_is_greater_than_or_equal(BALANCE_BIT_SIZE, total_paid_amount, fee);
+        let transfer_amount = builder.sub(total_paid_amount, fee);
+
         let receiver_old_user_state = UserStateGadget::new_from_delta_merkle_proof_left_leaf(
             builder,
             &receiver_user_tree_delta_merkle_proof_gadget,
@@ -83,11 +106,14 @@ impl L2TransferStateUpdateGadget {
         Self {
             sender_user_tree_delta_merkle_proof_gadget,
             receiver_user_tree_delta_merkle_proof_gadget,
+            fee_credit_gadget,
+            fee,
             sender_old_user_state,
             sender_new_user_state,
             receiver_old_user_state,
             receiver_new_user_state,
             transfer_amount: transfer_amount,
+            total_paid_amount,
         }
     }
     pub fn set_witness<W: Witness<F>, F: RichField>(
@@ -95,10 +121,15 @@ impl L2TransferStateUpdateGadget {
         witness: &mut W,
         sender_user_tree_delta_merkle_proof: &DeltaMerkleProofCore<QHashOut<F>>,
         receiver_user_tree_delta_merkle_proof: &DeltaMerkleProofCore<QHashOut<F>>,
+        fee_recipient_user_tree_delta_merkle_proof: &DeltaMerkleProofCore<QHashOut<F>>,
+        fee: u64,
     ) {
         self.sender_user_tree_delta_merkle_proof_gadget
             .set_witness_core_proof_q(witness, sender_user_tree_delta_merkle_proof);
         self.receiver_user_tree_delta_merkle_proof_gadget
             .set_witness_core_proof_q(witness, receiver_user_tree_delta_merkle_proof);
+        self.fee_credit_gadget
+            .set_witness(witness, fee_recipient_user_tree_delta_merkle_proof);
+        witness.set_target(self.fee, F::from_noncanonical_u64(fee));
     }
 }
@@ -0,0 +1,76 @@
+use city_rollup_common::qworker::job_witnesses::op::CRTokenStateTransitionInput;
+use plonky2::{
+    field::extension::Extendable,
+    hash::hash_types::RichField,
+    iop::{target::Target, witness::Witness},
+    plonk::{circuit_builder::CircuitBuilder, config::AlgebraicHasher},
+};
+
+use super::token_state::{ensure_valid_token_amount_increase, TokenStateTransitionGadget};
+
+#[derive(Debug, Clone)]
+pub struct MintTokenStateUpdateGadget {
+    // inputs:
+    pub token_state_transition_gadget: TokenStateTransitionGadget,
+
+    // computed:
+    pub token_id: Target,
+    pub receiver_user_id: Target,
+    pub mint_amount: Target,
+}
+
+impl MintTokenStateUpdateGadget {
+    pub fn add_virtual_to<H: AlgebraicHasher<F>, F: RichField + Extendable<D>, const D: usize>(
+        builder: &mut CircuitBuilder<F, D>,
+    ) -> Self {
+        let token_state_transition_gadget =
+            TokenStateTransitionGadget::add_virtual_to::<H, F, D>(builder, 1);
+        let registry_gadget =
+            &token_state_transition_gadget.token_registry_delta_merkle_proof_gadget;
+        let receiver_balance_gadget =
+            &token_state_transition_gadget.token_balance_delta_merkle_proof_gadgets[0];
+
+        // only the issuer can mint, the token id of a token is the user id of its issuer
+        let token_id = token_state_transition_gadget.token_id;
+        builder.connect(
+            token_id,
+            token_state_transition_gadget.signer_old_user_state.user_id,
+        );
+
+        // increase the supply of the (registered) token by X
+        let mint_amount = builder.sub(
+            registry_gadget.new_value.elements[0],
+            registry_gadget.old_value.elements[0],
+        );
+        ensure_valid_token_amount_increase(
+            builder,
+            registry_gadget.old_value,
+            registry_gadget.new_value,
+            mint_amount,
+        );
+
+        // increase the receiver's balance by X, the receiver must have opened an account for the token
+        let receiver_user_id = token_state_transition_gadget.token_balance_user_ids[0];
+        ensure_valid_token_amount_increase(
+            builder,
+            receiver_balance_gadget.old_value,
+            receiver_balance_gadget.new_value,
+            mint_amount,
+        );
+
+        Self {
+            token_state_transition_gadget,
+            token_id,
+            receiver_user_id,
+            mint_amount,
+        }
+    }
+    pub fn set_witness<W: Witness<F>, F: RichField>(
+        &self,
+        witness: &mut W,
+        input: &CRTokenStateTransitionInput<F>,
+    ) {
+        self.token_state_transition_gadget
+            .set_witness(witness, input);
+    }
+}
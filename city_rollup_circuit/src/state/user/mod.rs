@@ -1,4 +1,13 @@
 pub mod add_l1_withdrawal;
+pub mod batch_l2_transfer_state_update;
+pub mod burn_token;
+pub mod cancel_l1_withdrawal;
+pub mod change_public_key;
 pub mod claim_l1_deposit;
+pub mod fee_credit;
 pub mod l2_transfer_state_update;
+pub mod mint_token;
+pub mod open_token_account;
+pub mod send_token;
+pub mod token_state;
 pub mod user_state;
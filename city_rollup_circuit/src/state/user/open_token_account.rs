@@ -0,0 +1,80 @@
+use city_common_circuit::builder::{
+    comparison::CircuitBuilderComparison, hash::core::CircuitBuilderHashCore,
+};
+use city_rollup_common::qworker::job_witnesses::op::CRTokenStateTransitionInput;
+use plonky2::{
+    field::extension::Extendable,
+    hash::hash_types::{HashOutTarget, RichField},
+    iop::{
+        target::{BoolTarget, Target},
+        witness::Witness,
+    },
+    plonk::{circuit_builder::CircuitBuilder, config::AlgebraicHasher},
+};
+
+use super::token_state::{ensure_valid_token_leaf, TokenStateTransitionGadget};
+
+#[derive(Debug, Clone)]
+pub struct OpenTokenAccountGadget {
+    // inputs:
+    pub token_state_transition_gadget: TokenStateTransitionGadget,
+
+    // computed:
+    pub token_id: Target,
+    pub is_issuer: BoolTarget,
+}
+
+impl OpenTokenAccountGadget {
+    pub fn add_virtual_to<H: AlgebraicHasher<F>, F: RichField + Extendable<D>, const D: usize>(
+        builder: &mut CircuitBuilder<F, D>,
+    ) -> Self {
+        let token_state_transition_gadget =
+            TokenStateTransitionGadget::add_virtual_to::<H, F, D>(builder, 1);
+        let user_id = token_state_transition_gadget.signer_old_user_state.user_id;
+        let token_id = token_state_transition_gadget.token_id;
+        let registry_gadget =
+            &token_state_transition_gadget.token_registry_delta_merkle_proof_gadget;
+        let balance_gadget =
+            &token_state_transition_gadget.token_balance_delta_merkle_proof_gadgets[0];
+
+        // token id 0 is reserved
+        let zero = builder.zero();
+        builder.ensure_not_equal(token_id, zero);
+
+        // the signer opens the account, it must not have been opened before and starts with a balance of 0
+        builder.connect(
+            token_state_transition_gadget.token_balance_user_ids[0],
+            user_id,
+        );
+        builder.ensure_hash_is_zero(balance_gadget.old_value);
+        let new_balance = ensure_valid_token_leaf(builder, balance_gadget.new_value);
+        builder.connect(new_balance, zero);
+
+        // if token_id == user_id, a new token is registered with a supply of 0, otherwise the token must
+        // already be registered and its registry leaf is left unchanged
+        let is_issuer = builder.is_equal(token_id, user_id);
+        let new_supply = ensure_valid_token_leaf(builder, registry_gadget.new_value);
+        let new_issuer_supply = builder.mul(is_issuer.target, new_supply);
+        builder.connect(new_issuer_supply, zero);
+        let zero_hash = HashOutTarget {
+            elements: [zero, zero, zero, zero],
+        };
+        let expected_old_registry_leaf =
+            builder.select_hash(is_issuer, zero_hash, registry_gadget.new_value);
+        builder.connect_hashes(registry_gadget.old_value, expected_old_registry_leaf);
+
+        Self {
+            token_state_transition_gadget,
+            token_id,
+            is_issuer,
+        }
+    }
+    pub fn set_witness<W: Witness<F>, F: RichField>(
+        &self,
+        witness: &mut W,
+        input: &CRTokenStateTransitionInput<F>,
+    ) {
+        self.token_state_transition_gadget
+            .set_witness(witness, input);
+    }
+}
@@ -0,0 +1,85 @@
+use city_common_circuit::builder::comparison::CircuitBuilderComparison;
+use city_rollup_common::qworker::job_witnesses::op::CRTokenStateTransitionInput;
+use plonky2::{
+    field::extension::Extendable,
+    hash::hash_types::RichField,
+    iop::{target::Target, witness::Witness},
+    plonk::{circuit_builder::CircuitBuilder, config::AlgebraicHasher},
+};
+
+use super::token_state::{
+    ensure_valid_token_amount_decrease, ensure_valid_token_amount_increase,
+    ensure_valid_token_leaf, TokenStateTransitionGadget,
+};
+
+#[derive(Debug, Clone)]
+pub struct SendTokenStateUpdateGadget {
+    // inputs:
+    pub token_state_transition_gadget: TokenStateTransitionGadget,
+
+    // computed:
+    pub token_id: Target,
+    pub receiver_user_id: Target,
+    pub transfer_amount: Target,
+}
+
+impl SendTokenStateUpdateGadget {
+    pub fn add_virtual_to<H: AlgebraicHasher<F>, F: RichField + Extendable<D>, const D: usize>(
+        builder: &mut CircuitBuilder<F, D>,
+    ) -> Self {
+        let token_state_transition_gadget =
+            TokenStateTransitionGadget::add_virtual_to::<H, F, D>(builder, 2);
+        let token_id = token_state_transition_gadget.token_id;
+        let registry_gadget =
+            &token_state_transition_gadget.token_registry_delta_merkle_proof_gadget;
+        let sender_balance_gadget =
+            &token_state_transition_gadget.token_balance_delta_merkle_proof_gadgets[0];
+        let receiver_balance_gadget =
+            &token_state_transition_gadget.token_balance_delta_merkle_proof_gadgets[1];
+
+        // the token must be registered, a transfer does not change its supply
+        ensure_valid_token_leaf(builder, registry_gadget.old_value);
+        builder.connect_hashes(registry_gadget.old_value, registry_gadget.new_value);
+
+        // ensure that the balance updates are back-to-back state transitions
+        // 1. decrement the signer's token balance by X
+        // 2. increment the receiver's token balance by X
+        builder.connect(
+            token_state_transition_gadget.token_balance_user_ids[0],
+            token_state_transition_gadget.signer_old_user_state.user_id,
+        );
+        let transfer_amount = ensure_valid_token_amount_decrease(
+            builder,
+            sender_balance_gadget.old_value,
+            sender_balance_gadget.new_value,
+        );
+
+        // ensure this is not a self transfer
+        let receiver_user_id = token_state_transition_gadget.token_balance_user_ids[1];
+        builder.ensure_not_equal(
+            receiver_user_id,
+            token_state_transition_gadget.signer_old_user_state.user_id,
+        );
+        ensure_valid_token_amount_increase(
+            builder,
+            receiver_balance_gadget.old_value,
+            receiver_balance_gadget.new_value,
+            transfer_amount,
+        );
+
+        Self {
+            token_state_transition_gadget,
+            token_id,
+            receiver_user_id,
+            transfer_amount,
+        }
+    }
+    pub fn set_witness<W: Witness<F>, F: RichField>(
+        &self,
+        witness: &mut W,
+        input: &CRTokenStateTransitionInput<F>,
+    ) {
+        self.token_state_transition_gadget
+            .set_witness(witness, input);
+    }
+}
@@ -0,0 +1,358 @@
+use city_common::config::rollup_constants::{
+    BALANCE_BIT_SIZE, GLOBAL_USER_TREE_HEIGHT, TOKEN_BALANCE_TREE_HEIGHT,
+    TOKEN_REGISTRY_TREE_HEIGHT, TOKEN_STATE_USER_ID,
+};
+use city_common_circuit::{
+    builder::{
+        comparison::CircuitBuilderComparison, core::CircuitBuilderHelpersCore,
+        hash::core::CircuitBuilderHashCore,
+    },
+    hash::merkle::gadgets::delta_merkle_proof::DeltaMerkleProofGadget,
+};
+use city_rollup_common::qworker::job_witnesses::op::CRTokenStateTransitionInput;
+use plonky2::{
+    field::{extension::Extendable, types::Field},
+    hash::hash_types::{HashOutTarget, RichField},
+    iop::{target::Target, witness::Witness},
+    plonk::{circuit_builder::CircuitBuilder, config::AlgebraicHasher},
+};
+
+use super::user_state::UserStateGadget;
+
+// registry leaves are [supply, 1, 0, 0] and balance leaves are [balance, 1, 0, 0], returns the amount
+pub fn ensure_valid_token_leaf<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    leaf: HashOutTarget,
+) -> Target {
+    let zero = builder.zero();
+    let one = builder.one();
+    builder.connect(leaf.elements[1], one);
+    builder.connect(leaf.elements[2], zero);
+    builder.connect(leaf.elements[3], zero);
+    leaf.elements[0]
+}
+
+// ensure new_leaf holds the amount of old_leaf plus a non-zero amount
+pub fn ensure_valid_token_amount_increase<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    old_leaf: HashOutTarget,
+    new_leaf: HashOutTarget,
+    amount: Target,
+) {
+    let old_amount = ensure_valid_token_leaf(builder, old_leaf);
+    let new_amount = ensure_valid_token_leaf(builder, new_leaf);
+    let expected_new_amount = builder.add(old_amount, amount);
+    builder.connect(new_amount, expected_new_amount);
+    // ensure that (old_amount + amount) did not overflow
+    builder.ensure_is_greater_than(BALANCE_BIT_SIZE, new_amount, old_amount);
+}
+
+// ensure new_leaf holds the amount of old_leaf minus some amount, returns the amount
+pub fn ensure_valid_token_amount_decrease<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    old_leaf: HashOutTarget,
+    new_leaf: HashOutTarget,
+) -> Target {
+    let old_amount = ensure_valid_token_leaf(builder, old_leaf);
+    let new_amount = ensure_valid_token_leaf(builder, new_leaf);
+    let amount = builder.sub(old_amount, new_amount);
+    // ensure that (old_amount - amount) does not underflow
+    builder.ensure_is_greater_than_or_equal(BALANCE_BIT_SIZE, old_amount, amount);
+    amount
+}
+
+#[derive(Debug, Clone)]
+pub struct TokenStateTransitionGadget {
+    // inputs:
+    pub user_tree_delta_merkle_proof_gadget: DeltaMerkleProofGadget,
+    pub token_state_user_tree_delta_merkle_proof_gadget: DeltaMerkleProofGadget,
+    pub token_registry_delta_merkle_proof_gadget: DeltaMerkleProofGadget,
+    pub token_balance_delta_merkle_proof_gadgets: Vec<DeltaMerkleProofGadget>,
+
+    // computed:
+    pub signer_old_user_state: UserStateGadget,
+    pub signer_new_user_state: UserStateGadget,
+    pub token_id: Target,
+    pub token_balance_user_ids: Vec<Target>,
+    pub old_user_tree_root: HashOutTarget,
+    pub new_user_tree_root: HashOutTarget,
+}
+
+impl TokenStateTransitionGadget {
+    pub fn add_virtual_to<H: AlgebraicHasher<F>, F: RichField + Extendable<D>, const D: usize>(
+        builder: &mut CircuitBuilder<F, D>,
+        token_balance_update_count: usize,
+    ) -> Self {
+        assert!(
+            token_balance_update_count > 0,
+            "a token state transition updates at least one balance"
+        );
+        let user_tree_delta_merkle_proof_gadget: DeltaMerkleProofGadget =
+            DeltaMerkleProofGadget::add_virtual_to_u8h::<H, F, D>(builder, GLOBAL_USER_TREE_HEIGHT);
+        let token_state_user_tree_delta_merkle_proof_gadget: DeltaMerkleProofGadget =
+            DeltaMerkleProofGadget::add_virtual_to_u8h::<H, F, D>(builder, GLOBAL_USER_TREE_HEIGHT);
+        let token_registry_delta_merkle_proof_gadget: DeltaMerkleProofGadget =
+            DeltaMerkleProofGadget::add_virtual_to_u8h::<H, F, D>(
+                builder,
+                TOKEN_REGISTRY_TREE_HEIGHT,
+            );
+        let token_balance_delta_merkle_proof_gadgets = (0..token_balance_update_count)
+            .map(|_| {
+                DeltaMerkleProofGadget::add_virtual_to_u8h::<H, F, D>(
+                    builder,
+                    TOKEN_BALANCE_TREE_HEIGHT,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        // ensure the signer exists (the token state user has no public key, so it can never sign)
+        builder.ensure_hash_is_non_zero(user_tree_delta_merkle_proof_gadget.siblings[0]);
+        let signer_old_user_state = UserStateGadget::new_from_delta_merkle_proof_left_leaf(
+            builder,
+            &user_tree_delta_merkle_proof_gadget,
+        );
+        // only the signer's nonce changes
+        let zero = builder.zero();
+        let signer_new_user_state = signer_old_user_state
+            .ensure_valid_increase_balance_known_amount_allow_zero(
+                builder,
+                user_tree_delta_merkle_proof_gadget.new_value,
+                zero,
+                true,
+            );
+
+        // ensure that the delta merkle proofs are back-to-back state transitions
+        // 1. update the signer's nonce
+        // 2. replace the token state leaf with the hash of the new token tree roots
+        let token_state_leaf_index = builder.constant_u64(TOKEN_STATE_USER_ID * 2);
+        builder.connect(
+            token_state_user_tree_delta_merkle_proof_gadget.index,
+            token_state_leaf_index,
+        );
+        builder.connect_hashes(
+            user_tree_delta_merkle_proof_gadget.new_root,
+            token_state_user_tree_delta_merkle_proof_gadget.old_root,
+        );
+        for i in 1..token_balance_update_count {
+            builder.connect_hashes(
+                token_balance_delta_merkle_proof_gadgets[i - 1].new_root,
+                token_balance_delta_merkle_proof_gadgets[i].old_root,
+            );
+        }
+
+        // ensure the token state leaf commits to the token trees before and after the transition
+        let last_balance_gadget =
+            &token_balance_delta_merkle_proof_gadgets[token_balance_update_count - 1];
+        let old_token_state_leaf = builder.hash_two_to_one::<H>(
+            token_registry_delta_merkle_proof_gadget.old_root,
+            token_balance_delta_merkle_proof_gadgets[0].old_root,
+        );
+        let new_token_state_leaf = builder.hash_two_to_one::<H>(
+            token_registry_delta_merkle_proof_gadget.new_root,
+            last_balance_gadget.new_root,
+        );
+        builder.connect_hashes(
+            token_state_user_tree_delta_merkle_proof_gadget.old_value,
+            old_token_state_leaf,
+        );
+        builder.connect_hashes(
+            token_state_user_tree_delta_merkle_proof_gadget.new_value,
+            new_token_state_leaf,
+        );
+
+        // the registry is indexed by token id, the balance tree by (user_id << TOKEN_REGISTRY_TREE_HEIGHT) + token_id
+        let token_id = token_registry_delta_merkle_proof_gadget.index;
+        let inv_user_id_shift = F::from_canonical_u64(1u64 << TOKEN_REGISTRY_TREE_HEIGHT).inverse();
+        let token_balance_user_ids = token_balance_delta_merkle_proof_gadgets
+            .iter()
+            .map(|gadget| {
+                let shifted_user_id = builder.sub(gadget.index, token_id);
+                let user_id = builder.mul_const(inv_user_id_shift, shifted_user_id);
+                // fails unless the low bits of the index are the token id
+                builder.range_check(user_id, TOKEN_REGISTRY_TREE_HEIGHT as usize);
+                user_id
+            })
+            .collect::<Vec<_>>();
+
+        let old_user_tree_root = user_tree_delta_merkle_proof_gadget.old_root;
+        let new_user_tree_root = token_state_user_tree_delta_merkle_proof_gadget.new_root;
+
+        Self {
+            user_tree_delta_merkle_proof_gadget,
+            token_state_user_tree_delta_merkle_proof_gadget,
+            token_registry_delta_merkle_proof_gadget,
+            token_balance_delta_merkle_proof_gadgets,
+            signer_old_user_state,
+            signer_new_user_state,
+            token_id,
+            token_balance_user_ids,
+            old_user_tree_root,
+            new_user_tree_root,
+        }
+    }
+    pub fn set_witness<W: Witness<F>, F: RichField>(
+        &self,
+        witness: &mut W,
+        input: &CRTokenStateTransitionInput<F>,
+    ) {
+        assert_eq!(
+            self.token_balance_delta_merkle_proof_gadgets.len(),
+            input.token_balance_delta_merkle_proofs.len(),
+            "wrong number of token balance updates"
+        );
+        self.user_tree_delta_merkle_proof_gadget
+            .set_witness_core_proof_q(witness, &input.user_tree_delta_merkle_proof);
+        self.token_state_user_tree_delta_merkle_proof_gadget
+            .set_witness_core_proof_q(witness, &input.token_state_user_tree_delta_merkle_proof);
+        self.token_registry_delta_merkle_proof_gadget
+            .set_witness_core_proof_q(witness, &input.token_registry_delta_merkle_proof);
+        for (gadget, proof) in self
+            .token_balance_delta_merkle_proof_gadgets
+            .iter()
+            .zip(input.token_balance_delta_merkle_proofs.iter())
+        {
+            gadget.set_witness_core_proof_q(witness, proof);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic::AssertUnwindSafe;
+
+    use city_crypto::hash::qhashout::QHashOut;
+    use city_rollup_common::qworker::job_witnesses::op::CRTokenStateTransitionInput;
+    use city_store::{
+        config::TokenBalanceTreeStore,
+        models::kvq_merkle::model::KVQFixedConfigMerkleTreeModelCore,
+        store::city::{base::CityStore, token::get_token_balance_index},
+    };
+    use kvq::memory::simple::KVQSimpleMemoryBackingStore;
+    use plonky2::{
+        hash::poseidon::PoseidonHash,
+        iop::witness::PartialWitness,
+        plonk::{
+            circuit_builder::CircuitBuilder,
+            circuit_data::CircuitConfig,
+            config::{GenericConfig, PoseidonGoldilocksConfig},
+        },
+    };
+
+    use super::TokenStateTransitionGadget;
+    use crate::state::user::{
+        burn_token::BurnTokenStateUpdateGadget, mint_token::MintTokenStateUpdateGadget,
+        open_token_account::OpenTokenAccountGadget, send_token::SendTokenStateUpdateGadget,
+    };
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = KVQSimpleMemoryBackingStore;
+
+    #[derive(Clone, Copy)]
+    enum TokenOp {
+        Open,
+        Mint,
+        Send,
+        Burn,
+    }
+
+    // true if the transition proves in the circuit of the given token op
+    fn prove_token_op(op: TokenOp, input: &CRTokenStateTransitionInput<F>) -> bool {
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let gadget: TokenStateTransitionGadget = match op {
+            TokenOp::Open => {
+                OpenTokenAccountGadget::add_virtual_to::<PoseidonHash, F, D>(&mut builder)
+                    .token_state_transition_gadget
+            }
+            TokenOp::Mint => {
+                MintTokenStateUpdateGadget::add_virtual_to::<PoseidonHash, F, D>(&mut builder)
+                    .token_state_transition_gadget
+            }
+            TokenOp::Send => {
+                SendTokenStateUpdateGadget::add_virtual_to::<PoseidonHash, F, D>(&mut builder)
+                    .token_state_transition_gadget
+            }
+            TokenOp::Burn => {
+                BurnTokenStateUpdateGadget::add_virtual_to::<PoseidonHash, F, D>(&mut builder)
+                    .token_state_transition_gadget
+            }
+        };
+        if gadget.token_balance_delta_merkle_proof_gadgets.len()
+            != input.token_balance_delta_merkle_proofs.len()
+        {
+            return false;
+        }
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        gadget.set_witness(&mut pw, input);
+        // witness generation panics on some conflicting constraints instead of returning an error
+        match std::panic::catch_unwind(AssertUnwindSafe(|| data.prove(pw))) {
+            Ok(Ok(proof)) => data.verify(proof).is_ok(),
+            _ => false,
+        }
+    }
+
+    // users 1-3 are registered, user 1 issues token 1 and users 2 and 3 open accounts for it
+    fn setup_token_store() -> (S, Vec<CRTokenStateTransitionInput<F>>) {
+        let mut store = S::new();
+        CityStore::init_token_state(&mut store, 0).unwrap();
+        for user_id in 1..=3 {
+            CityStore::register_user(
+                &mut store,
+                1,
+                user_id,
+                QHashOut::from_values(user_id, 7, 7, 7),
+            )
+            .unwrap();
+        }
+        let opens = (1..=3)
+            .map(|user_id| CityStore::open_token_account(&mut store, 1, user_id, 1, 1).unwrap())
+            .collect();
+        (store, opens)
+    }
+
+    #[test]
+    fn token_operations_prove() {
+        let (mut store, opens) = setup_token_store();
+        for open in opens.iter() {
+            assert!(prove_token_op(TokenOp::Open, open));
+        }
+        let mint = CityStore::mint_token(&mut store, 1, 1, 2, 100, 2).unwrap();
+        assert!(prove_token_op(TokenOp::Mint, &mint));
+        let send = CityStore::send_token(&mut store, 1, 2, 1, 3, 40, 2).unwrap();
+        assert!(prove_token_op(TokenOp::Send, &send));
+        let burn = CityStore::burn_token(&mut store, 1, 3, 1, 15, 3).unwrap();
+        assert!(prove_token_op(TokenOp::Burn, &burn));
+    }
+
+    #[test]
+    fn token_transition_is_rejected_by_other_op_circuits() {
+        let (mut store, opens) = setup_token_store();
+        let mint = CityStore::mint_token(&mut store, 1, 1, 2, 100, 2).unwrap();
+        let burn = CityStore::burn_token(&mut store, 1, 2, 1, 15, 2).unwrap();
+
+        assert!(!prove_token_op(TokenOp::Mint, &opens[1]));
+        assert!(!prove_token_op(TokenOp::Burn, &mint));
+        assert!(!prove_token_op(TokenOp::Mint, &burn));
+        assert!(!prove_token_op(TokenOp::Open, &mint));
+    }
+
+    #[test]
+    fn balance_changed_outside_the_token_state_is_rejected() {
+        let (mut store, _) = setup_token_store();
+        CityStore::mint_token(&mut store, 1, 1, 2, 100, 2).unwrap();
+        // credit user 2 without updating the token state leaf in the user tree
+        TokenBalanceTreeStore::<S>::set_leaf_fc(
+            &mut store,
+            1,
+            get_token_balance_index(2, 1).unwrap(),
+            QHashOut::from_values(1000, 1, 0, 0),
+        )
+        .unwrap();
+
+        let send = CityStore::send_token(&mut store, 1, 2, 1, 3, 500, 2).unwrap();
+        assert!(!prove_token_op(TokenOp::Send, &send));
+    }
+}
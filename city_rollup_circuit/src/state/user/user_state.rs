@@ -203,6 +203,44 @@ impl UserStateGadget {
         new_left_leaf: HashOutTarget,
         increase_amount: Target,
         requires_nonce_update: bool,
+    ) -> Self {
+        self.ensure_valid_increase_balance_known_amount_core(
+            builder,
+            new_left_leaf,
+            increase_amount,
+            requires_nonce_update,
+            false,
+        )
+    }
+    // same as ensure_valid_increase_balance_known_amount, but an increase of 0 is allowed (used for crediting fees)
+    pub fn ensure_valid_increase_balance_known_amount_allow_zero<
+        F: RichField + Extendable<D>,
+        const D: usize,
+    >(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        new_left_leaf: HashOutTarget,
+        increase_amount: Target,
+        requires_nonce_update: bool,
+    ) -> Self {
+        self.ensure_valid_increase_balance_known_amount_core(
+            builder,
+            new_left_leaf,
+            increase_amount,
+            requires_nonce_update,
+            true,
+        )
+    }
+    fn ensure_valid_increase_balance_known_amount_core<
+        F: RichField + Extendable<D>,
+        const D: usize,
+    >(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        new_left_leaf: HashOutTarget,
+        increase_amount: Target,
+        requires_nonce_update: bool,
+        allow_zero_amount: bool,
     ) -> Self {
         assert!(
             self.is_left_leaf_index,
@@ -219,7 +257,11 @@ impl UserStateGadget {
         // ensure that new_balance == balance + increase_amount
         builder.connect(new_balance, expected_new_balance);
         // ensure that (balance + increase_amount) did not overflow
-        builder.ensure_is_greater_than(BALANCE_BIT_SIZE, new_balance, self.balance);
+        if allow_zero_amount {
+            builder.ensure_is_greater_than_or_equal(BALANCE_BIT_SIZE, new_balance, self.balance);
+        } else {
+            builder.ensure_is_greater_than(BALANCE_BIT_SIZE, new_balance, self.balance);
+        }
 
         // ensure that alt_user_state_slot_a did not change
         builder.connect(new_alt_user_state_slot_a, self.alt_user_state_slot_a);
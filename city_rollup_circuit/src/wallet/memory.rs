@@ -19,7 +19,11 @@ use city_crypto::{
 use city_rollup_common::{
   api::data::{
       block::rpc_request::{
-          CityAddWithdrawalRPCRequest, CityClaimDepositRPCRequest, CityTokenTransferRPCRequest,
+          CityAddWithdrawalRPCRequest, CityBatchTokenTransferRPCRequest,
+          CityBatchTransferRecipient, CityBurnTokenRPCRequest, CityCancelWithdrawalRPCRequest,
+          CityChangePublicKeyRPCRequest,
+          CityClaimDepositRPCRequest, CityMintTokenRPCRequest, CityOpenTokenAccountRPCRequest,
+          CitySendTokenRPCRequest, CityTokenTransferRPCRequest,
       },
       store::CityL1Deposit,
   },
@@ -166,10 +170,11 @@ where
       from: u64,
       to: u64,
       value: u64,
+      fee: u64,
       nonce: u64,
   ) -> anyhow::Result<CityTokenTransferRPCRequest> {
       let sig_preimage =
-          QEDSigAction::<C::F>::new_transfer_action(network_magic, from, nonce, to, value);
+          QEDSigAction::<C::F>::new_transfer_action(network_magic, from, nonce, to, value, fee);
       let hash = sig_preimage.get_qhash::<PoseidonHash>();
       let proof = self.zk_sign(public_key, hash)?;
       let signature_proof = bincode::serialize(&proof)?;
@@ -179,6 +184,163 @@ where
           nonce,
           user_id: from,
           value: value,
+          fee,
+      })
+  }
+  pub fn sign_batch_l2_transfer(
+      &self,
+      public_key: QHashOut<C::F>,
+      network_magic: u64,
+      from: u64,
+      transfers: &[CityBatchTransferRecipient],
+      fee: u64,
+      nonce: u64,
+  ) -> anyhow::Result<CityBatchTokenTransferRPCRequest> {
+      let sig_preimage = QEDSigAction::<C::F>::new_batch_transfer_action(
+          network_magic,
+          from,
+          nonce,
+          &transfers.iter().map(|t| (t.to, t.value)).collect::<Vec<_>>(),
+          fee,
+      )?;
+      let hash = sig_preimage.get_qhash::<PoseidonHash>();
+      let proof = self.zk_sign(public_key, hash)?;
+      let signature_proof = bincode::serialize(&proof)?;
+      Ok(CityBatchTokenTransferRPCRequest {
+          user_id: from,
+          transfers: transfers.to_vec(),
+          fee,
+          nonce,
+          signature_proof,
+      })
+  }
+  // the request must be signed with the user's current public key
+  pub fn sign_change_public_key(
+      &self,
+      public_key: QHashOut<C::F>,
+      network_magic: u64,
+      user_id: u64,
+      new_public_key: QHashOut<C::F>,
+      nonce: u64,
+  ) -> anyhow::Result<CityChangePublicKeyRPCRequest<C::F>> {
+      let sig_preimage = QEDSigAction::<C::F>::new_change_public_key_action(
+          network_magic,
+          user_id,
+          nonce,
+          new_public_key,
+      );
+      let hash = sig_preimage.get_qhash::<PoseidonHash>();
+      let proof = self.zk_sign(public_key, hash)?;
+      let signature_proof = bincode::serialize(&proof)?;
+      Ok(CityChangePublicKeyRPCRequest {
+          user_id,
+          new_public_key,
+          nonce,
+          signature_proof,
+      })
+  }
+  // token_id == user_id also registers a new token issued by user_id
+  pub fn sign_open_token_account(
+      &self,
+      public_key: QHashOut<C::F>,
+      network_magic: u64,
+      user_id: u64,
+      token_id: u64,
+      nonce: u64,
+  ) -> anyhow::Result<CityOpenTokenAccountRPCRequest> {
+      let sig_preimage = QEDSigAction::<C::F>::new_open_token_account_action(
+          network_magic,
+          user_id,
+          nonce,
+          token_id,
+      );
+      let hash = sig_preimage.get_qhash::<PoseidonHash>();
+      let proof = self.zk_sign(public_key, hash)?;
+      let signature_proof = bincode::serialize(&proof)?;
+      Ok(CityOpenTokenAccountRPCRequest {
+          user_id,
+          token_id,
+          nonce,
+          signature_proof,
+      })
+  }
+  pub fn sign_mint_token(
+      &self,
+      public_key: QHashOut<C::F>,
+      network_magic: u64,
+      issuer: u64,
+      to: u64,
+      value: u64,
+      nonce: u64,
+  ) -> anyhow::Result<CityMintTokenRPCRequest> {
+      let sig_preimage =
+          QEDSigAction::<C::F>::new_mint_token_action(network_magic, issuer, nonce, to, value);
+      let hash = sig_preimage.get_qhash::<PoseidonHash>();
+      let proof = self.zk_sign(public_key, hash)?;
+      let signature_proof = bincode::serialize(&proof)?;
+      Ok(CityMintTokenRPCRequest {
+          user_id: issuer,
+          to,
+          value,
+          nonce,
+          signature_proof,
+      })
+  }
+  pub fn sign_send_token(
+      &self,
+      public_key: QHashOut<C::F>,
+      network_magic: u64,
+      from: u64,
+      token_id: u64,
+      to: u64,
+      value: u64,
+      nonce: u64,
+  ) -> anyhow::Result<CitySendTokenRPCRequest> {
+      let sig_preimage = QEDSigAction::<C::F>::new_send_token_action(
+          network_magic,
+          from,
+          nonce,
+          token_id,
+          to,
+          value,
+      );
+      let hash = sig_preimage.get_qhash::<PoseidonHash>();
+      let proof = self.zk_sign(public_key, hash)?;
+      let signature_proof = bincode::serialize(&proof)?;
+      Ok(CitySendTokenRPCRequest {
+          user_id: from,
+          token_id,
+          to,
+          value,
+          nonce,
+          signature_proof,
+      })
+  }
+  pub fn sign_burn_token(
+      &self,
+      public_key: QHashOut<C::F>,
+      network_magic: u64,
+      user_id: u64,
+      token_id: u64,
+      value: u64,
+      nonce: u64,
+  ) -> anyhow::Result<CityBurnTokenRPCRequest> {
+      let sig_preimage = QEDSigAction::<C::F>::new_burn_token_action(
+          network_magic,
+          user_id,
+          nonce,
+          token_id,
+          value,
+      );
+      let hash = sig_preimage.get_qhash::<PoseidonHash>();
+      let proof = self.zk_sign(public_key, hash)?;
+      let signature_proof = bincode::serialize(&proof)?;
+      Ok(CityBurnTokenRPCRequest {
+          user_id,
+          token_id,
+          value,
+          nonce,
+          signature_proof,
       })
   }
   pub fn sign_withdrawal(
@@ -188,6 +350,7 @@ where
       user_id: u64,
       l1_address: Hash160,
       value: u64,
+      fee: u64,
       nonce: u64,
   ) -> anyhow::Result<CityAddWithdrawalRPCRequest> {
       let sig_preimage: QEDSigAction<C::F> =
@@ -199,6 +362,7 @@ where
               0,
               value,
               WITHDRAWAL_FEE_AMOUNT,
+              fee,
           );
       let hash = sig_preimage.get_qhash::<PoseidonHash>();
       let proof = self.zk_sign(public_key, hash)?;
@@ -209,8 +373,36 @@ where
           nonce,
           user_id: user_id,
           value: value,
+          fee,
           destination_type: 0,
           destination: l1_address,
       })
   }
+  pub fn sign_cancel_withdrawal(
+      &self,
+      public_key: QHashOut<C::F>,
+      network_magic: u64,
+      user_id: u64,
+      withdrawal_id: u64,
+      withdrawal_hash: QHashOut<C::F>,
+      nonce: u64,
+  ) -> anyhow::Result<CityCancelWithdrawalRPCRequest<C::F>> {
+      let sig_preimage = QEDSigAction::<C::F>::new_cancel_withdrawal_action(
+          network_magic,
+          user_id,
+          nonce,
+          withdrawal_id,
+          withdrawal_hash,
+      );
+      let hash = sig_preimage.get_qhash::<PoseidonHash>();
+      let proof = self.zk_sign(public_key, hash)?;
+      let signature_proof = bincode::serialize(&proof)?;
+      Ok(CityCancelWithdrawalRPCRequest {
+          user_id,
+          withdrawal_id,
+          withdrawal_hash,
+          nonce,
+          signature_proof,
+      })
+  }
 }
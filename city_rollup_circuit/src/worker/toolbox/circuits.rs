@@ -36,9 +36,13 @@ use plonky2::{
 use crate::{
     block_circuits::ops::{
         add_l1_deposit::CRAddL1DepositCircuit, add_l1_withdrawal::CRAddL1WithdrawalCircuit,
-        claim_l1_deposit::CRClaimL1DepositCircuit, l2_transfer::circuit::CRL2TransferCircuit,
+        batch_l2_transfer::CRBatchL2TransferCircuit, burn_token::CRBurnTokenCircuit,
+        cancel_l1_withdrawal::CRCancelL1WithdrawalCircuit,
+        change_public_key::CRChangePublicKeyCircuit, claim_l1_deposit::CRClaimL1DepositCircuit,
+        l2_transfer::circuit::CRL2TransferCircuit, mint_token::CRMintTokenCircuit,
+        open_token_account::CROpenTokenAccountCircuit,
         process_l1_withdrawal::CRProcessL1WithdrawalCircuit,
-        register_user::CRUserRegistrationCircuit,
+        register_user::CRUserRegistrationCircuit, send_token::CRSendTokenCircuit,
     },
     worker::traits::{
         QWorkerCircuitAggWithDataSync, QWorkerCircuitSimpleWithDataSync,
@@ -51,6 +55,7 @@ where
     C::Hasher: AlgebraicHasher<C::F> + MerkleZeroHasher<HashOut<C::F>>,
 {
     pub network_magic: u64,
+    pub fee_recipient_user_id: u64,
 
     // user circuits
     pub zk_signature_wrapper: ZKSignatureWrapperCircuit<C, D>,
@@ -61,6 +66,13 @@ where
     pub op_claim_l1_deposit: CRClaimL1DepositCircuit<C, D>, // signed
     pub op_l2_transfer: CRL2TransferCircuit<C, D>,          // signed
     pub op_add_l1_withdrawal: CRAddL1WithdrawalCircuit<C, D>, // signed
+    pub op_change_public_key: CRChangePublicKeyCircuit<C, D>, // signed
+    pub op_batch_l2_transfer: CRBatchL2TransferCircuit<C, D>, // signed
+    pub op_open_token_account: CROpenTokenAccountCircuit<C, D>, // signed
+    pub op_mint_token: CRMintTokenCircuit<C, D>,            // signed
+    pub op_send_token: CRSendTokenCircuit<C, D>,            // signed
+    pub op_burn_token: CRBurnTokenCircuit<C, D>,            // signed
+    pub op_cancel_l1_withdrawal: CRCancelL1WithdrawalCircuit<C, D>, // signed
 
     // state transition with events operations
     pub op_add_l1_deposit: CRAddL1DepositCircuit<C, D>,
@@ -78,7 +90,7 @@ impl<C: GenericConfig<D> + 'static, const D: usize> CRWorkerToolboxCoreCircuits<
 where
     C::Hasher: AlgebraicHasher<C::F> + MerkleZeroHasher<HashOut<C::F>>,
 {
-    pub fn new(network_magic: u64) -> Self {
+    pub fn new(network_magic: u64, fee_recipient_user_id: u64) -> Self {
         let mut trace_timer = TraceTimer::new("CRWorkerToolboxCoreCircuits");
         trace_timer.lap("start => build core toolbox circuits");
         // user circuits
@@ -115,6 +127,7 @@ where
 
         let op_l2_transfer = CRL2TransferCircuit::new_with_sig_wrapper_data(
             network_magic,
+            fee_recipient_user_id,
             zk_signature_wrapper.get_common_circuit_data_ref(),
             zk_signature_wrapper
                 .get_verifier_config_ref()
@@ -126,6 +139,7 @@ where
 
         let op_add_l1_withdrawal = CRAddL1WithdrawalCircuit::new_with_signature_circuit_data(
             network_magic,
+            fee_recipient_user_id,
             zk_signature_wrapper.get_common_circuit_data_ref(),
             zk_signature_wrapper
                 .get_verifier_config_ref()
@@ -135,6 +149,84 @@ where
         );
         trace_timer.lap("built op_add_l1_withdrawal");
 
+        let op_change_public_key = CRChangePublicKeyCircuit::new_with_sig_wrapper_data(
+            network_magic,
+            zk_signature_wrapper.get_common_circuit_data_ref(),
+            zk_signature_wrapper
+                .get_verifier_config_ref()
+                .constants_sigmas_cap
+                .height(),
+            zk_signature_wrapper.get_fingerprint(),
+        );
+        trace_timer.lap("built op_change_public_key");
+
+        let op_batch_l2_transfer = CRBatchL2TransferCircuit::new_with_sig_wrapper_data(
+            network_magic,
+            fee_recipient_user_id,
+            zk_signature_wrapper.get_common_circuit_data_ref(),
+            zk_signature_wrapper
+                .get_verifier_config_ref()
+                .constants_sigmas_cap
+                .height(),
+            zk_signature_wrapper.get_fingerprint(),
+        );
+        trace_timer.lap("built op_batch_l2_transfer");
+
+        let op_open_token_account = CROpenTokenAccountCircuit::new_with_sig_wrapper_data(
+            network_magic,
+            zk_signature_wrapper.get_common_circuit_data_ref(),
+            zk_signature_wrapper
+                .get_verifier_config_ref()
+                .constants_sigmas_cap
+                .height(),
+            zk_signature_wrapper.get_fingerprint(),
+        );
+        trace_timer.lap("built op_open_token_account");
+
+        let op_mint_token = CRMintTokenCircuit::new_with_sig_wrapper_data(
+            network_magic,
+            zk_signature_wrapper.get_common_circuit_data_ref(),
+            zk_signature_wrapper
+                .get_verifier_config_ref()
+                .constants_sigmas_cap
+                .height(),
+            zk_signature_wrapper.get_fingerprint(),
+        );
+        trace_timer.lap("built op_mint_token");
+
+        let op_send_token = CRSendTokenCircuit::new_with_sig_wrapper_data(
+            network_magic,
+            zk_signature_wrapper.get_common_circuit_data_ref(),
+            zk_signature_wrapper
+                .get_verifier_config_ref()
+                .constants_sigmas_cap
+                .height(),
+            zk_signature_wrapper.get_fingerprint(),
+        );
+        trace_timer.lap("built op_send_token");
+
+        let op_burn_token = CRBurnTokenCircuit::new_with_sig_wrapper_data(
+            network_magic,
+            zk_signature_wrapper.get_common_circuit_data_ref(),
+            zk_signature_wrapper
+                .get_verifier_config_ref()
+                .constants_sigmas_cap
+                .height(),
+            zk_signature_wrapper.get_fingerprint(),
+        );
+        trace_timer.lap("built op_burn_token");
+
+        let op_cancel_l1_withdrawal = CRCancelL1WithdrawalCircuit::new_with_sig_wrapper_data(
+            network_magic,
+            zk_signature_wrapper.get_common_circuit_data_ref(),
+            zk_signature_wrapper
+                .get_verifier_config_ref()
+                .constants_sigmas_cap
+                .height(),
+            zk_signature_wrapper.get_fingerprint(),
+        );
+        trace_timer.lap("built op_cancel_l1_withdrawal");
+
         // state transition with events operations
         let op_add_l1_deposit = CRAddL1DepositCircuit::new(coset_gate);
         trace_timer.lap("built op_add_l1_deposit");
@@ -169,12 +261,20 @@ where
 
         let mut result = Self {
             network_magic,
+            fee_recipient_user_id,
             zk_signature_wrapper,
             l1_secp256k1_signature,
             op_register_user,
             op_claim_l1_deposit,
             op_l2_transfer,
             op_add_l1_withdrawal,
+            op_change_public_key,
+            op_batch_l2_transfer,
+            op_open_token_account,
+            op_mint_token,
+            op_send_token,
+            op_burn_token,
+            op_cancel_l1_withdrawal,
             op_add_l1_deposit,
             op_process_l1_withdrawal,
             agg_state_transition,
@@ -201,6 +301,7 @@ where
 
         CRWorkerToolboxCoreCircuitFingerprints {
             network_magic: self.network_magic,
+            fee_recipient_user_id: self.fee_recipient_user_id,
             zk_signature_wrapper: self.zk_signature_wrapper.get_fingerprint(),
             l1_secp256k1_signature: self.l1_secp256k1_signature.get_fingerprint(),
 
@@ -238,6 +339,65 @@ where
                     ProvingJobCircuitType::AddL1Withdrawal.to_u8(),
                     ProvingJobCircuitType::AddL1WithdrawalAggregate.to_u8(),
                 ),
+            op_change_public_key:
+                TPCircuitFingerprintConfig::from_leaf_and_agg_fingerprints_with_type::<C::Hasher>(
+                    self.op_change_public_key.get_fingerprint(),
+                    agg_state_transition_fingerprint,
+                    agg_state_transition_dummy_fingerprint,
+                    ProvingJobCircuitType::ChangePublicKey.to_u8(),
+                    ProvingJobCircuitType::ChangePublicKeyAggregate.to_u8(),
+                ),
+            op_batch_l2_transfer:
+                TPCircuitFingerprintConfig::from_leaf_and_agg_fingerprints_with_type::<C::Hasher>(
+                    self.op_batch_l2_transfer.get_fingerprint(),
+                    agg_state_transition_fingerprint,
+                    agg_state_transition_dummy_fingerprint,
+                    ProvingJobCircuitType::BatchTransferTokensL2.to_u8(),
+                    ProvingJobCircuitType::BatchTransferTokensL2Aggregate.to_u8(),
+                ),
+            op_open_token_account:
+                TPCircuitFingerprintConfig::from_leaf_and_agg_fingerprints_with_type::<C::Hasher>(
+                    self.op_open_token_account.get_fingerprint(),
+                    agg_state_transition_fingerprint,
+                    agg_state_transition_dummy_fingerprint,
+                    ProvingJobCircuitType::OpenTokenAccount.to_u8(),
+                    ProvingJobCircuitType::OpenTokenAccountAggregate.to_u8(),
+                ),
+            op_mint_token: TPCircuitFingerprintConfig::from_leaf_and_agg_fingerprints_with_type::<
+                C::Hasher,
+            >(
+                self.op_mint_token.get_fingerprint(),
+                agg_state_transition_fingerprint,
+                agg_state_transition_dummy_fingerprint,
+                ProvingJobCircuitType::MintToken.to_u8(),
+                ProvingJobCircuitType::MintTokenAggregate.to_u8(),
+            ),
+            op_send_token: TPCircuitFingerprintConfig::from_leaf_and_agg_fingerprints_with_type::<
+                C::Hasher,
+            >(
+                self.op_send_token.get_fingerprint(),
+                agg_state_transition_fingerprint,
+                agg_state_transition_dummy_fingerprint,
+                ProvingJobCircuitType::SendToken.to_u8(),
+                ProvingJobCircuitType::SendTokenAggregate.to_u8(),
+            ),
+            op_burn_token: TPCircuitFingerprintConfig::from_leaf_and_agg_fingerprints_with_type::<
+                C::Hasher,
+            >(
+                self.op_burn_token.get_fingerprint(),
+                agg_state_transition_fingerprint,
+                agg_state_transition_dummy_fingerprint,
+                ProvingJobCircuitType::BurnToken.to_u8(),
+                ProvingJobCircuitType::BurnTokenAggregate.to_u8(),
+            ),
+            op_cancel_l1_withdrawal:
+                TPCircuitFingerprintConfig::from_leaf_and_agg_fingerprints_with_type::<C::Hasher>(
+                    self.op_cancel_l1_withdrawal.get_fingerprint(),
+                    agg_state_transition_fingerprint,
+                    agg_state_transition_dummy_fingerprint,
+                    ProvingJobCircuitType::CancelL1Withdrawal.to_u8(),
+                    ProvingJobCircuitType::CancelL1WithdrawalAggregate.to_u8(),
+                ),
             op_add_l1_deposit: TPCircuitFingerprintConfig::from_leaf_and_agg_fingerprints_with_type::<
                 C::Hasher,
             >(
@@ -274,6 +434,24 @@ where
         self.op_add_l1_withdrawal
             .print_config_with_name("op_add_l1_withdrawal");
 
+        self.op_change_public_key
+            .print_config_with_name("op_change_public_key");
+
+        self.op_batch_l2_transfer
+            .print_config_with_name("op_batch_l2_transfer");
+
+        self.op_open_token_account
+            .print_config_with_name("op_open_token_account");
+
+        self.op_mint_token.print_config_with_name("op_mint_token");
+
+        self.op_send_token.print_config_with_name("op_send_token");
+
+        self.op_burn_token.print_config_with_name("op_burn_token");
+
+        self.op_cancel_l1_withdrawal
+            .print_config_with_name("op_cancel_l1_withdrawal");
+
         self.op_add_l1_deposit
             .print_config_with_name("op_add_l1_deposit");
 
@@ -338,6 +516,42 @@ where
             ProvingJobCircuitType::ProcessL1WithdrawalAggregate => {
                 self.agg_state_transition_with_events.get_verifier_triplet()
             }
+            ProvingJobCircuitType::ChangePublicKey => {
+                self.op_change_public_key.get_verifier_triplet()
+            }
+            ProvingJobCircuitType::ChangePublicKeyAggregate => {
+                self.agg_state_transition.get_verifier_triplet()
+            }
+            ProvingJobCircuitType::BatchTransferTokensL2 => {
+                self.op_batch_l2_transfer.get_verifier_triplet()
+            }
+            ProvingJobCircuitType::BatchTransferTokensL2Aggregate => {
+                self.agg_state_transition.get_verifier_triplet()
+            }
+            ProvingJobCircuitType::OpenTokenAccount => {
+                self.op_open_token_account.get_verifier_triplet()
+            }
+            ProvingJobCircuitType::OpenTokenAccountAggregate => {
+                self.agg_state_transition.get_verifier_triplet()
+            }
+            ProvingJobCircuitType::MintToken => self.op_mint_token.get_verifier_triplet(),
+            ProvingJobCircuitType::MintTokenAggregate => {
+                self.agg_state_transition.get_verifier_triplet()
+            }
+            ProvingJobCircuitType::SendToken => self.op_send_token.get_verifier_triplet(),
+            ProvingJobCircuitType::SendTokenAggregate => {
+                self.agg_state_transition.get_verifier_triplet()
+            }
+            ProvingJobCircuitType::BurnToken => self.op_burn_token.get_verifier_triplet(),
+            ProvingJobCircuitType::BurnTokenAggregate => {
+                self.agg_state_transition.get_verifier_triplet()
+            }
+            ProvingJobCircuitType::CancelL1Withdrawal => {
+                self.op_cancel_l1_withdrawal.get_verifier_triplet()
+            }
+            ProvingJobCircuitType::CancelL1WithdrawalAggregate => {
+                self.agg_state_transition.get_verifier_triplet()
+            }
             ProvingJobCircuitType::GenerateRollupStateTransitionProof => todo!(),
             ProvingJobCircuitType::GenerateSigHashIntrospectionProof => todo!(),
             ProvingJobCircuitType::GenerateFinalSigHashProof => todo!(),
@@ -360,6 +574,27 @@ where
             ProvingJobCircuitType::DummyProcessL1WithdrawalAggregate => self
                 .agg_state_transition_with_events_dummy
                 .get_verifier_triplet(),
+            ProvingJobCircuitType::DummyChangePublicKeyAggregate => {
+                self.agg_state_transition_dummy.get_verifier_triplet()
+            }
+            ProvingJobCircuitType::DummyBatchTransferTokensL2Aggregate => {
+                self.agg_state_transition_dummy.get_verifier_triplet()
+            }
+            ProvingJobCircuitType::DummyOpenTokenAccountAggregate => {
+                self.agg_state_transition_dummy.get_verifier_triplet()
+            }
+            ProvingJobCircuitType::DummyMintTokenAggregate => {
+                self.agg_state_transition_dummy.get_verifier_triplet()
+            }
+            ProvingJobCircuitType::DummySendTokenAggregate => {
+                self.agg_state_transition_dummy.get_verifier_triplet()
+            }
+            ProvingJobCircuitType::DummyBurnTokenAggregate => {
+                self.agg_state_transition_dummy.get_verifier_triplet()
+            }
+            ProvingJobCircuitType::DummyCancelL1WithdrawalAggregate => {
+                self.agg_state_transition_dummy.get_verifier_triplet()
+            }
             ProvingJobCircuitType::WrappedSignatureProof => {
                 self.zk_signature_wrapper.get_verifier_triplet()
             }
@@ -398,6 +633,21 @@ where
             ProvingJobCircuitType::ProcessL1WithdrawalAggregate => {
                 Ok(self.fingerprints.op_process_l1_withdrawal)
             }
+            ProvingJobCircuitType::ChangePublicKeyAggregate => {
+                Ok(self.fingerprints.op_change_public_key)
+            }
+            ProvingJobCircuitType::BatchTransferTokensL2Aggregate => {
+                Ok(self.fingerprints.op_batch_l2_transfer)
+            }
+            ProvingJobCircuitType::OpenTokenAccountAggregate => {
+                Ok(self.fingerprints.op_open_token_account)
+            }
+            ProvingJobCircuitType::MintTokenAggregate => Ok(self.fingerprints.op_mint_token),
+            ProvingJobCircuitType::SendTokenAggregate => Ok(self.fingerprints.op_send_token),
+            ProvingJobCircuitType::BurnTokenAggregate => Ok(self.fingerprints.op_burn_token),
+            ProvingJobCircuitType::CancelL1WithdrawalAggregate => {
+                Ok(self.fingerprints.op_cancel_l1_withdrawal)
+            }
             _ => Err(anyhow::anyhow!(
                 "circuit of type {:?} does not have a leaf fingerprint",
                 circuit_type
@@ -454,6 +704,48 @@ where
             ProvingJobCircuitType::ProcessL1WithdrawalAggregate => self
                 .agg_state_transition_with_events
                 .prove_q_worker_agg(self, store, job_id),
+            ProvingJobCircuitType::ChangePublicKey => self
+                .op_change_public_key
+                .prove_q_worker_standard(self, store, job_id),
+            ProvingJobCircuitType::ChangePublicKeyAggregate => self
+                .agg_state_transition
+                .prove_q_worker_agg(self, store, job_id),
+            ProvingJobCircuitType::BatchTransferTokensL2 => self
+                .op_batch_l2_transfer
+                .prove_q_worker_standard(self, store, job_id),
+            ProvingJobCircuitType::BatchTransferTokensL2Aggregate => self
+                .agg_state_transition
+                .prove_q_worker_agg(self, store, job_id),
+            ProvingJobCircuitType::OpenTokenAccount => self
+                .op_open_token_account
+                .prove_q_worker_standard(self, store, job_id),
+            ProvingJobCircuitType::OpenTokenAccountAggregate => self
+                .agg_state_transition
+                .prove_q_worker_agg(self, store, job_id),
+            ProvingJobCircuitType::MintToken => self
+                .op_mint_token
+                .prove_q_worker_standard(self, store, job_id),
+            ProvingJobCircuitType::MintTokenAggregate => self
+                .agg_state_transition
+                .prove_q_worker_agg(self, store, job_id),
+            ProvingJobCircuitType::SendToken => self
+                .op_send_token
+                .prove_q_worker_standard(self, store, job_id),
+            ProvingJobCircuitType::SendTokenAggregate => self
+                .agg_state_transition
+                .prove_q_worker_agg(self, store, job_id),
+            ProvingJobCircuitType::BurnToken => self
+                .op_burn_token
+                .prove_q_worker_standard(self, store, job_id),
+            ProvingJobCircuitType::BurnTokenAggregate => self
+                .agg_state_transition
+                .prove_q_worker_agg(self, store, job_id),
+            ProvingJobCircuitType::CancelL1Withdrawal => self
+                .op_cancel_l1_withdrawal
+                .prove_q_worker_standard(self, store, job_id),
+            ProvingJobCircuitType::CancelL1WithdrawalAggregate => self
+                .agg_state_transition
+                .prove_q_worker_agg(self, store, job_id),
             ProvingJobCircuitType::GenerateRollupStateTransitionProof => todo!(),
             ProvingJobCircuitType::GenerateSigHashIntrospectionProof => todo!(),
             ProvingJobCircuitType::GenerateFinalSigHashProof => todo!(),
@@ -476,6 +768,27 @@ where
             ProvingJobCircuitType::DummyProcessL1WithdrawalAggregate => self
                 .agg_state_transition_with_events_dummy
                 .prove_q_worker_simple(self, store, job_id),
+            ProvingJobCircuitType::DummyChangePublicKeyAggregate => self
+                .agg_state_transition_dummy
+                .prove_q_worker_simple(self, store, job_id),
+            ProvingJobCircuitType::DummyBatchTransferTokensL2Aggregate => self
+                .agg_state_transition_dummy
+                .prove_q_worker_simple(self, store, job_id),
+            ProvingJobCircuitType::DummyOpenTokenAccountAggregate => self
+                .agg_state_transition_dummy
+                .prove_q_worker_simple(self, store, job_id),
+            ProvingJobCircuitType::DummyMintTokenAggregate => self
+                .agg_state_transition_dummy
+                .prove_q_worker_simple(self, store, job_id),
+            ProvingJobCircuitType::DummySendTokenAggregate => self
+                .agg_state_transition_dummy
+                .prove_q_worker_simple(self, store, job_id),
+            ProvingJobCircuitType::DummyBurnTokenAggregate => self
+                .agg_state_transition_dummy
+                .prove_q_worker_simple(self, store, job_id),
+            ProvingJobCircuitType::DummyCancelL1WithdrawalAggregate => self
+                .agg_state_transition_dummy
+                .prove_q_worker_simple(self, store, job_id),
             ProvingJobCircuitType::WrappedSignatureProof => todo!(),
             ProvingJobCircuitType::Secp256K1SignatureProof => todo!(),
             ProvingJobCircuitType::Unknown => todo!(),
@@ -72,8 +72,12 @@ where
     C::Hasher: AlgebraicHasher<C::F> + MerkleZeroHasher<HashOut<C::F>>,
     C::F: CubicExtendable + QRichField,
 {
-    pub fn new(network_magic: u64, sighash_whitelist_root: QHashOut<C::F>) -> Self {
-        let core = CRWorkerToolboxCoreCircuits::<C, D>::new(network_magic);
+    pub fn new(
+        network_magic: u64,
+        fee_recipient_user_id: u64,
+        sighash_whitelist_root: QHashOut<C::F>,
+    ) -> Self {
+        let core = CRWorkerToolboxCoreCircuits::<C, D>::new(network_magic, fee_recipient_user_id);
         let sighash_wrapper = CRSigHashWrapperCircuit::<C, D>::new(sighash_whitelist_root);
 
         let block_agg_register_claim_deposit_transfer =
@@ -81,6 +85,12 @@ where
                 core.fingerprints.op_register_user,
                 core.fingerprints.op_claim_l1_deposit,
                 core.fingerprints.op_l2_transfer,
+                core.fingerprints.op_change_public_key,
+                core.fingerprints.op_batch_l2_transfer,
+                core.fingerprints.op_open_token_account,
+                core.fingerprints.op_mint_token,
+                core.fingerprints.op_send_token,
+                core.fingerprints.op_burn_token,
                 core.agg_state_transition.get_common_circuit_data_ref(),
                 core.agg_state_transition
                     .get_verifier_config_ref()
@@ -90,6 +100,7 @@ where
         let block_agg_add_process_withdrawal_add_deposit =
             CRAggAddProcessL1WithdrawalAddL1DepositCircuit::<C, D>::new(
                 core.fingerprints.op_add_l1_withdrawal,
+                core.fingerprints.op_cancel_l1_withdrawal,
                 core.fingerprints.op_process_l1_withdrawal,
                 core.fingerprints.op_add_l1_deposit,
                 core.agg_state_transition.get_common_circuit_data_ref(),
@@ -13,6 +13,11 @@ use crate::subcommand::rpcserver;
 use crate::subcommand::dumpblock;
 use crate::subcommand::qbench;
 use crate::subcommand::inspectdump;
+use crate::subcommand::watchblock;
+use crate::subcommand::benchreport;
+use crate::subcommand::genfingerprintmanifest;
+use crate::subcommand::snapshot;
+use crate::subcommand::migrate;
 use crate::subcommand::Cli;
 use crate::subcommand::Commands;
 
@@ -43,6 +48,24 @@ fn main() -> anyhow::Result<()> {
         Commands::InspectDump(args) => {
             inspectdump::run(args)?;
         }
+        Commands::WatchBlock(args) => {
+            watchblock::run(args)?;
+        }
+        Commands::BenchReport(args) => {
+            benchreport::run(args)?;
+        }
+        Commands::GenFingerprintManifest(args) => {
+            genfingerprintmanifest::run(args)?;
+        }
+        Commands::SnapshotExport(args) => {
+            snapshot::run_export(args)?;
+        }
+        Commands::SnapshotImport(args) => {
+            snapshot::run_import(args)?;
+        }
+        Commands::Migrate(args) => {
+            migrate::run(args)?;
+        }
     };
     Ok::<_, anyhow::Error>(())
 }
@@ -9,6 +9,11 @@ pub mod apiserver;
 pub mod dumpblock;
 pub mod qbench;
 pub mod inspectdump;
+pub mod watchblock;
+pub mod benchreport;
+pub mod genfingerprintmanifest;
+pub mod snapshot;
+pub mod migrate;
 #[derive(Parser)]
 pub struct Cli {
     #[command(subcommand)]
@@ -24,4 +29,10 @@ pub enum Commands {
     DumpBlock(city_common::cli::args::L2DumpProofStoreArgs),
     QBench(city_common::cli::args::QBenchArgs),
     InspectDump(city_common::cli::args::InspectL2DumpArgs),
+    WatchBlock(city_common::cli::args::WatchBlockArgs),
+    BenchReport(city_common::cli::args::BenchReportArgs),
+    GenFingerprintManifest(city_common::cli::args::GenFingerprintManifestArgs),
+    SnapshotExport(city_common::cli::args::SnapshotExportArgs),
+    SnapshotImport(city_common::cli::args::SnapshotImportArgs),
+    Migrate(city_common::cli::args::MigrateArgs),
 }
@@ -0,0 +1,76 @@
+use city_common::cli::{args::BenchReportArgs, modes::QReportFormat};
+use city_redis_store::RedisStore;
+use city_rollup_common::qworker::{
+    benchmark::{
+        benchmark_comparison_to_csv, benchmark_report_to_csv, compare_benchmark_reports,
+        get_block_job_benchmarks, summarize_job_benchmarks, QBenchmarkReport,
+    },
+    job_id::QWorkerJobBenchmark,
+};
+
+fn load_benchmarks(args: &BenchReportArgs) -> anyhow::Result<Vec<QWorkerJobBenchmark>> {
+    if args.input.is_empty() {
+        // the proof store only holds the durations recorded next to each block's proofs
+        let (Some(start), Some(end)) = (args.checkpoint_start, args.checkpoint_end) else {
+            anyhow::bail!("--checkpoint-start and --checkpoint-end are required without --input");
+        };
+        let store = RedisStore::new(&args.redis_uri)?;
+        let mut benchmarks = vec![];
+        for checkpoint_id in start..=end {
+            benchmarks.append(&mut get_block_job_benchmarks(&store, checkpoint_id)?);
+        }
+        return Ok(benchmarks);
+    }
+    let mut benchmarks = vec![];
+    for input in args.input.iter() {
+        let mut file_benchmarks: Vec<QWorkerJobBenchmark> =
+            serde_json::from_slice(&std::fs::read(input)?)?;
+        benchmarks.append(&mut file_benchmarks);
+    }
+    Ok(benchmarks)
+}
+
+pub fn run(args: BenchReportArgs) -> anyhow::Result<()> {
+    let benchmarks = load_benchmarks(&args)?;
+    let checkpoint_range = if args.checkpoint_start.is_some() || args.checkpoint_end.is_some() {
+        Some((
+            args.checkpoint_start.unwrap_or(0),
+            args.checkpoint_end.unwrap_or(u64::MAX),
+        ))
+    } else {
+        None
+    };
+    let report = summarize_job_benchmarks(&benchmarks, checkpoint_range)?;
+
+    let mut regressions = 0;
+    let output = match args.baseline.as_ref() {
+        Some(baseline_path) => {
+            let baseline: QBenchmarkReport =
+                serde_json::from_slice(&std::fs::read(baseline_path)?)?;
+            let comparison =
+                compare_benchmark_reports(&baseline, &report, args.regression_threshold);
+            regressions = comparison.iter().filter(|x| x.is_regression).count();
+            match args.format {
+                QReportFormat::Csv => benchmark_comparison_to_csv(&comparison),
+                QReportFormat::Json => serde_json::to_string_pretty(&comparison)?,
+            }
+        }
+        None => match args.format {
+            QReportFormat::Csv => benchmark_report_to_csv(&report),
+            QReportFormat::Json => serde_json::to_string_pretty(&report)?,
+        },
+    };
+
+    match args.output.as_ref() {
+        Some(path) => std::fs::write(path, output)?,
+        None => println!("{}", output),
+    }
+    if regressions != 0 {
+        anyhow::bail!(
+            "{} circuit(s) regressed by more than {}% compared to the baseline",
+            regressions,
+            args.regression_threshold
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,14 @@
+use city_common::cli::args::GenFingerprintManifestArgs;
+
+pub fn run(args: GenFingerprintManifestArgs) -> anyhow::Result<()> {
+    city_rollup_core_worker::write_fingerprint_manifest(
+        &args.network,
+        args.fee_recipient_user_id,
+        &args.output,
+    )?;
+    println!(
+        "wrote circuit fingerprint manifest for {} to {}",
+        args.network, args.output
+    );
+    Ok(())
+}
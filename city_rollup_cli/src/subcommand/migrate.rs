@@ -0,0 +1,55 @@
+use city_common::cli::args::MigrateArgs;
+use city_rollup_core_api::KV;
+use city_store::{
+    migrations::{city_store_migrations, verify_city_store},
+    store::city::base::CityStore,
+};
+use kvq_store_redb::KVQReDBStore;
+use redb::Database;
+
+pub fn run(args: MigrateArgs) -> anyhow::Result<()> {
+    let db = Database::open(&args.db_path)?;
+
+    // dropping the write transaction on error discards a partial migration
+    let wxn = db.begin_write()?;
+    let report = {
+        let mut store = KVQReDBStore::new(wxn.open_table(KV)?);
+        let report = CityStore::migrate(&mut store, &city_store_migrations())?;
+        CityStore::verify_schema(&store, &city_store_migrations())?;
+        report
+    };
+    for step in report.steps.iter() {
+        println!(
+            "schema version {} -> {}: {} ({} records rewritten)",
+            step.from_version,
+            step.from_version + 1,
+            step.description,
+            step.records
+        );
+    }
+    if report.steps.is_empty() {
+        println!(
+            "{} is already at schema version {}",
+            args.db_path, report.to_version
+        );
+    }
+
+    if args.dry_run {
+        wxn.abort()?;
+        println!(
+            "dry run: migrating {} from schema version {} to {} succeeded, nothing was written",
+            args.db_path, report.from_version, report.to_version
+        );
+        return Ok(());
+    }
+    wxn.commit()?;
+
+    let rxn = db.begin_read()?;
+    let store = KVQReDBStore::new(rxn.open_table(KV)?);
+    verify_city_store(&store)?;
+    println!(
+        "migrated {} from schema version {} to {}",
+        args.db_path, report.from_version, report.to_version
+    );
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use std::path::Path;
+
+use city_common::cli::args::{SnapshotExportArgs, SnapshotImportArgs};
+use city_rollup_core_api::KV;
+use city_rollup_rpc_provider::{CityRpcProviderSync, RpcProviderSync};
+use city_store::{
+    config::CityHash,
+    store::city::{base::CityStore, snapshot::CityStateSnapshot},
+};
+use kvq_store_redb::KVQReDBStore;
+use redb::Database;
+
+pub fn run_export(args: SnapshotExportArgs) -> anyhow::Result<()> {
+    let db = Database::open(&args.db_path)?;
+    let rxn = db.begin_read()?;
+    let store = KVQReDBStore::new(rxn.open_table(KV)?);
+    CityStore::check_schema_version(&store)?;
+
+    let snapshot = CityStore::export_snapshot(&store, args.checkpoint_id)?;
+    std::fs::write(&args.output, snapshot.to_file_bytes()?)?;
+    println!(
+        "wrote snapshot of checkpoint {} ({} user, {} deposit and {} withdrawal leaves, city root {}) to {}",
+        snapshot.checkpoint_id,
+        snapshot.user_leaves.len(),
+        snapshot.deposit_leaves.len(),
+        snapshot.withdrawal_leaves.len(),
+        snapshot.city_root.to_string(),
+        args.output
+    );
+    Ok(())
+}
+
+fn import_into_new_db(
+    db_path: &str,
+    snapshot: &CityStateSnapshot,
+    check_city_root: impl FnOnce(&CityHash) -> anyhow::Result<()>,
+) -> anyhow::Result<CityHash> {
+    let db = Database::create(db_path)?;
+    let wxn = db.begin_write()?;
+    let city_root = {
+        let mut store = KVQReDBStore::new(wxn.open_table(KV)?);
+        CityStore::import_snapshot(&mut store, snapshot)?
+    };
+    check_city_root(&city_root)?;
+    wxn.commit()?;
+    Ok(city_root)
+}
+
+pub fn run_import(args: SnapshotImportArgs) -> anyhow::Result<()> {
+    if Path::new(&args.db_path).exists() {
+        anyhow::bail!(
+            "{} already exists, snapshots can only be imported into a new database",
+            args.db_path
+        );
+    }
+    let snapshot = CityStateSnapshot::from_file_bytes(&std::fs::read(&args.input)?)?;
+    let provider = RpcProviderSync::new(&args.api_server_address);
+
+    let result = import_into_new_db(&args.db_path, &snapshot, |city_root| {
+        let expected_city_root = provider.get_city_root_sync(snapshot.checkpoint_id)?;
+        if expected_city_root != *city_root {
+            anyhow::bail!(
+                "recomputed city root {} does not match getCityRoot({}) = {} from {}",
+                city_root.to_string(),
+                snapshot.checkpoint_id,
+                expected_city_root.to_string(),
+                args.api_server_address
+            );
+        }
+        Ok(())
+    });
+    // don't leave a partially imported database behind
+    let city_root = match result {
+        Ok(city_root) => city_root,
+        Err(err) => {
+            let _ = std::fs::remove_file(&args.db_path);
+            return Err(err);
+        }
+    };
+    println!(
+        "imported checkpoint {} into {}, city root {} matches getCityRoot",
+        snapshot.checkpoint_id,
+        args.db_path,
+        city_root.to_string()
+    );
+    Ok(())
+}
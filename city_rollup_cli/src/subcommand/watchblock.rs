@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+use city_common::cli::args::WatchBlockArgs;
+use city_rollup_common::qworker::{job_id::QProvingJobDataID, status::QBlockProvingStatus};
+use city_rollup_rpc_provider::{CityRpcProvider, RpcProvider};
+
+fn print_status(status: &QBlockProvingStatus) {
+    println!(
+        "checkpoint {}: {}/{} jobs complete, ~{}ms of work remaining, ~{}ms to completion",
+        status.checkpoint_id,
+        status.completed_jobs,
+        status.total_jobs,
+        status.estimated_remaining_work,
+        status.estimated_time_to_completion
+    );
+    for level in status.levels.iter() {
+        println!(
+            "  [depth {:>2}] {:<48} {:>4}/{:<4}",
+            level.depth,
+            format!("{:?}", level.circuit_type),
+            level.completed,
+            level.goal
+        );
+    }
+    for job in status.outstanding_jobs.iter() {
+        let job_id = QProvingJobDataID::try_from(job.job_id.0)
+            .map(|x| format!("{:?}", x))
+            .unwrap_or_default();
+        println!(
+            "  outstanding {} ~{}ms {}",
+            hex::encode(job.job_id.0),
+            job.estimated_duration,
+            job_id
+        );
+    }
+}
+
+#[tokio::main]
+pub async fn run(args: WatchBlockArgs) -> anyhow::Result<()> {
+    let provider = RpcProvider::new(&args.api_server_address);
+    loop {
+        match provider.get_block_proving_status(args.checkpoint_id).await {
+            Ok(status) => {
+                print_status(&status);
+                if status.is_complete {
+                    return Ok(());
+                }
+            }
+            Err(err) => tracing::info!("failed to get block proving status: {:?}", err),
+        }
+        tokio::time::sleep(Duration::from_millis(args.interval)).await;
+    }
+}
@@ -3,8 +3,11 @@ use std::collections::HashSet;
 use crate::{
     api::data::{
         block::requested_actions::{
-            CityAddDepositRequest, CityAddWithdrawalRequest, CityClaimDepositRequest,
-            CityProcessWithdrawalRequest, CityRegisterUserRequest, CityTokenTransferRequest,
+            CityAddDepositRequest, CityAddWithdrawalRequest, CityBatchTokenTransferRequest,
+            CityBurnTokenRequest, CityCancelWithdrawalRequest, CityChangePublicKeyRequest,
+            CityClaimDepositRequest, CityMintTokenRequest, CityOpenTokenAccountRequest,
+            CityProcessWithdrawalRequest, CityRegisterUserRequest, CitySendTokenRequest,
+            CityTokenTransferRequest,
         },
         store::CityL2BlockState,
     },
@@ -25,6 +28,19 @@ pub struct CityScenarioRequestedActions<F: RichField> {
     pub token_transfers: Vec<CityTokenTransferRequest>,
     pub process_withdrawals: Vec<CityProcessWithdrawalRequest>,
     pub register_users: Vec<CityRegisterUserRequest<F>>,
+    pub change_public_keys: Vec<CityChangePublicKeyRequest<F>>,
+    #[serde(default)]
+    pub batch_token_transfers: Vec<CityBatchTokenTransferRequest>,
+    #[serde(default)]
+    pub open_token_accounts: Vec<CityOpenTokenAccountRequest>,
+    #[serde(default)]
+    pub mint_tokens: Vec<CityMintTokenRequest>,
+    #[serde(default)]
+    pub send_tokens: Vec<CitySendTokenRequest>,
+    #[serde(default)]
+    pub burn_tokens: Vec<CityBurnTokenRequest>,
+    #[serde(default)]
+    pub cancel_withdrawals: Vec<CityCancelWithdrawalRequest<F>>,
 }
 impl<F: RichField> CityScenarioRequestedActions<F> {
     pub fn new() -> Self {
@@ -35,6 +51,13 @@ impl<F: RichField> CityScenarioRequestedActions<F> {
             token_transfers: Vec::new(),
             process_withdrawals: Vec::new(),
             register_users: Vec::new(),
+            change_public_keys: Vec::new(),
+            batch_token_transfers: Vec::new(),
+            open_token_accounts: Vec::new(),
+            mint_tokens: Vec::new(),
+            send_tokens: Vec::new(),
+            burn_tokens: Vec::new(),
+            cancel_withdrawals: Vec::new(),
         }
     }
     pub fn new_from_requested_rpc<'a>(
@@ -64,6 +87,13 @@ impl<F: RichField> CityScenarioRequestedActions<F> {
             token_transfers: requested_from_rpc.token_transfers,
             process_withdrawals,
             register_users: requested_from_rpc.register_users,
+            change_public_keys: requested_from_rpc.change_public_keys,
+            batch_token_transfers: requested_from_rpc.batch_token_transfers,
+            open_token_accounts: requested_from_rpc.open_token_accounts,
+            mint_tokens: requested_from_rpc.mint_tokens,
+            send_tokens: requested_from_rpc.send_tokens,
+            burn_tokens: requested_from_rpc.burn_tokens,
+            cancel_withdrawals: requested_from_rpc.cancel_withdrawals,
         }
     }
     pub fn accessed_users(&self) -> HashSet<u64> {
@@ -79,6 +109,34 @@ impl<F: RichField> CityScenarioRequestedActions<F> {
             res.insert(token_transfer.user_id);
             res.insert(token_transfer.to);
         }
+        for change_public_key in &self.change_public_keys {
+            res.insert(change_public_key.user_id);
+        }
+        for batch_token_transfer in &self.batch_token_transfers {
+            res.insert(batch_token_transfer.user_id);
+            for transfer in &batch_token_transfer.transfers {
+                res.insert(transfer.to);
+            }
+        }
+        for open_token_account in &self.open_token_accounts {
+            res.insert(open_token_account.user_id);
+        }
+        for mint_token in &self.mint_tokens {
+            // the issuer's user id is the token id
+            res.insert(mint_token.user_id);
+            res.insert(mint_token.to);
+        }
+        for send_token in &self.send_tokens {
+            res.insert(send_token.user_id);
+            res.insert(send_token.to);
+        }
+        // burns also touch the issuer account, which is resolved from the holder's token id at block processing time
+        for burn_token in &self.burn_tokens {
+            res.insert(burn_token.user_id);
+        }
+        for cancel_withdrawal in &self.cancel_withdrawals {
+            res.insert(cancel_withdrawal.user_id);
+        }
 
         res
     }
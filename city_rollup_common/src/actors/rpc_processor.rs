@@ -1,17 +1,24 @@
 use crate::{
     api::data::block::{
         requested_actions::{
-            CityAddWithdrawalRequest, CityClaimDepositRequest, CityRegisterUserRequest,
-            CityTokenTransferRequest,
+            CityAddWithdrawalRequest, CityBatchTokenTransferRequest, CityBurnTokenRequest,
+            CityCancelWithdrawalRequest, CityChangePublicKeyRequest, CityClaimDepositRequest,
+            CityMintTokenRequest, CityOpenTokenAccountRequest, CityRegisterUserRequest,
+            CitySendTokenRequest, CityTokenTransferRequest,
         },
         rpc_request::{
-            CityAddWithdrawalRPCRequest, CityClaimDepositRPCRequest, CityRegisterUserRPCRequest,
-            CityTokenTransferRPCRequest,
+            CityAddWithdrawalRPCRequest, CityBatchTokenTransferRPCRequest, CityBurnTokenRPCRequest,
+            CityCancelWithdrawalRPCRequest, CityChangePublicKeyRPCRequest,
+            CityClaimDepositRPCRequest, CityMintTokenRPCRequest, CityOpenTokenAccountRPCRequest,
+            CityRegisterUserRPCRequest, CitySendTokenRPCRequest, CityTokenTransferRPCRequest,
         },
     },
     qworker::{job_id::QProvingJobDataID, proof_store::QProofStore},
 };
 
+use city_common::config::rollup_constants::{
+    L2_BATCH_TRANSFER_MAX_RECIPIENTS, TOKEN_STATE_USER_ID,
+};
 use plonky2::hash::hash_types::RichField;
 use serde::{Deserialize, Serialize};
 
@@ -24,6 +31,19 @@ pub struct CityScenarioRequestedActionsFromRPC<F: RichField> {
     pub register_users: Vec<CityRegisterUserRequest<F>>,
     pub claim_l1_deposits: Vec<CityClaimDepositRequest>,
     pub add_withdrawals: Vec<CityAddWithdrawalRequest>,
+    pub change_public_keys: Vec<CityChangePublicKeyRequest<F>>,
+    #[serde(default)]
+    pub batch_token_transfers: Vec<CityBatchTokenTransferRequest>,
+    #[serde(default)]
+    pub open_token_accounts: Vec<CityOpenTokenAccountRequest>,
+    #[serde(default)]
+    pub mint_tokens: Vec<CityMintTokenRequest>,
+    #[serde(default)]
+    pub send_tokens: Vec<CitySendTokenRequest>,
+    #[serde(default)]
+    pub burn_tokens: Vec<CityBurnTokenRequest>,
+    #[serde(default)]
+    pub cancel_withdrawals: Vec<CityCancelWithdrawalRequest<F>>,
 }
 impl<F: RichField> CityScenarioRequestedActionsFromRPC<F> {
     pub fn new() -> Self {
@@ -32,6 +52,13 @@ impl<F: RichField> CityScenarioRequestedActionsFromRPC<F> {
             register_users: Vec::new(),
             claim_l1_deposits: Vec::new(),
             add_withdrawals: Vec::new(),
+            change_public_keys: Vec::new(),
+            batch_token_transfers: Vec::new(),
+            open_token_accounts: Vec::new(),
+            mint_tokens: Vec::new(),
+            send_tokens: Vec::new(),
+            burn_tokens: Vec::new(),
+            cancel_withdrawals: Vec::new(),
         }
     }
 }
@@ -60,6 +87,52 @@ impl<F: RichField> OrchestratorEventReceiverSync<F> for CityScenarioRequestedAct
         Ok(result)
     }
 
+    fn flush_change_public_keys(
+        &mut self,
+    ) -> anyhow::Result<Vec<CityChangePublicKeyRequest<F>>> {
+        let mut result = vec![];
+        result.append(&mut self.change_public_keys);
+        Ok(result)
+    }
+
+    fn flush_batch_token_transfers(
+        &mut self,
+    ) -> anyhow::Result<Vec<CityBatchTokenTransferRequest>> {
+        let mut result = vec![];
+        result.append(&mut self.batch_token_transfers);
+        Ok(result)
+    }
+
+    fn flush_open_token_accounts(&mut self) -> anyhow::Result<Vec<CityOpenTokenAccountRequest>> {
+        let mut result = vec![];
+        result.append(&mut self.open_token_accounts);
+        Ok(result)
+    }
+
+    fn flush_mint_tokens(&mut self) -> anyhow::Result<Vec<CityMintTokenRequest>> {
+        let mut result = vec![];
+        result.append(&mut self.mint_tokens);
+        Ok(result)
+    }
+
+    fn flush_send_tokens(&mut self) -> anyhow::Result<Vec<CitySendTokenRequest>> {
+        let mut result = vec![];
+        result.append(&mut self.send_tokens);
+        Ok(result)
+    }
+
+    fn flush_burn_tokens(&mut self) -> anyhow::Result<Vec<CityBurnTokenRequest>> {
+        let mut result = vec![];
+        result.append(&mut self.burn_tokens);
+        Ok(result)
+    }
+
+    fn flush_cancel_withdrawals(&mut self) -> anyhow::Result<Vec<CityCancelWithdrawalRequest<F>>> {
+        let mut result = vec![];
+        result.append(&mut self.cancel_withdrawals);
+        Ok(result)
+    }
+
     fn wait_for_produce_block(&mut self) -> anyhow::Result<bool> {
         Ok(false)
     }
@@ -85,6 +158,53 @@ impl<F: RichField> OrchestratorEventSenderSync<F> for CityScenarioRequestedActio
         Ok(())
     }
 
+    fn notify_change_public_key(
+        &mut self,
+        event: &CityChangePublicKeyRequest<F>,
+    ) -> anyhow::Result<()> {
+        self.change_public_keys.push(event.clone());
+        Ok(())
+    }
+
+    fn notify_batch_token_transfer(
+        &mut self,
+        event: &CityBatchTokenTransferRequest,
+    ) -> anyhow::Result<()> {
+        self.batch_token_transfers.push(event.clone());
+        Ok(())
+    }
+
+    fn notify_open_token_account(
+        &mut self,
+        event: &CityOpenTokenAccountRequest,
+    ) -> anyhow::Result<()> {
+        self.open_token_accounts.push(event.clone());
+        Ok(())
+    }
+
+    fn notify_mint_token(&mut self, event: &CityMintTokenRequest) -> anyhow::Result<()> {
+        self.mint_tokens.push(event.clone());
+        Ok(())
+    }
+
+    fn notify_send_token(&mut self, event: &CitySendTokenRequest) -> anyhow::Result<()> {
+        self.send_tokens.push(event.clone());
+        Ok(())
+    }
+
+    fn notify_burn_token(&mut self, event: &CityBurnTokenRequest) -> anyhow::Result<()> {
+        self.burn_tokens.push(event.clone());
+        Ok(())
+    }
+
+    fn notify_cancel_withdrawal(
+        &mut self,
+        event: &CityCancelWithdrawalRequest<F>,
+    ) -> anyhow::Result<()> {
+        self.cancel_withdrawals.push(event.clone());
+        Ok(())
+    }
+
     fn notify_produce_block(&mut self) -> anyhow::Result<()> {
         Ok(())
     }
@@ -150,6 +270,7 @@ impl<F: RichField> QRPCProcessor<F> {
             req.user_id,
             req.to,
             req.value,
+            req.fee,
             req.nonce,
             signature_proof_id,
         ))
@@ -169,12 +290,177 @@ impl<F: RichField> QRPCProcessor<F> {
         Ok(CityAddWithdrawalRequest::new(
             req.user_id,
             req.value,
+            req.fee,
             req.nonce,
             req.destination_type,
             req.destination,
             signature_proof_id,
         ))
     }
+    pub fn injest_rpc_change_public_key<PS: QProofStore>(
+        &self,
+        ps: &mut PS,
+        rpc_node_id: u32,
+        req: &CityChangePublicKeyRPCRequest<F>,
+    ) -> anyhow::Result<CityChangePublicKeyRequest<F>> {
+        let count = self.output.change_public_keys.len() as u32;
+        let signature_proof_id = QProvingJobDataID::change_public_key_signature_proof(
+            rpc_node_id,
+            self.checkpoint_id,
+            count,
+        );
+
+        ps.set_bytes_by_id(signature_proof_id, &req.signature_proof)?;
+
+        Ok(CityChangePublicKeyRequest::new(
+            req.user_id,
+            req.new_public_key,
+            req.nonce,
+            signature_proof_id,
+        ))
+    }
+    pub fn injest_rpc_batch_token_transfer<PS: QProofStore>(
+        &self,
+        ps: &mut PS,
+        rpc_node_id: u32,
+        req: &CityBatchTokenTransferRPCRequest,
+    ) -> anyhow::Result<CityBatchTokenTransferRequest> {
+        if req.transfers.len() == 0 || req.transfers.len() > L2_BATCH_TRANSFER_MAX_RECIPIENTS {
+            anyhow::bail!(
+                "a batch transfer must have between 1 and {} recipients, got {}",
+                L2_BATCH_TRANSFER_MAX_RECIPIENTS,
+                req.transfers.len()
+            );
+        }
+        let count = self.output.batch_token_transfers.len() as u32;
+        let signature_proof_id = QProvingJobDataID::batch_transfer_signature_proof(
+            rpc_node_id,
+            self.checkpoint_id,
+            count,
+        );
+
+        ps.set_bytes_by_id(signature_proof_id, &req.signature_proof)?;
+
+        Ok(CityBatchTokenTransferRequest::new(
+            req.user_id,
+            req.transfers.clone(),
+            req.fee,
+            req.nonce,
+            signature_proof_id,
+        ))
+    }
+    pub fn injest_rpc_open_token_account<PS: QProofStore>(
+        &self,
+        ps: &mut PS,
+        rpc_node_id: u32,
+        req: &CityOpenTokenAccountRPCRequest,
+    ) -> anyhow::Result<CityOpenTokenAccountRequest> {
+        if req.token_id == TOKEN_STATE_USER_ID {
+            anyhow::bail!("token id {} is reserved", TOKEN_STATE_USER_ID);
+        }
+        let count = self.output.open_token_accounts.len() as u32;
+        let signature_proof_id = QProvingJobDataID::open_token_account_signature_proof(
+            rpc_node_id,
+            self.checkpoint_id,
+            count,
+        );
+
+        ps.set_bytes_by_id(signature_proof_id, &req.signature_proof)?;
+
+        Ok(CityOpenTokenAccountRequest::new(
+            req.user_id,
+            req.token_id,
+            req.nonce,
+            signature_proof_id,
+        ))
+    }
+    pub fn injest_rpc_mint_token<PS: QProofStore>(
+        &self,
+        ps: &mut PS,
+        rpc_node_id: u32,
+        req: &CityMintTokenRPCRequest,
+    ) -> anyhow::Result<CityMintTokenRequest> {
+        let count = self.output.mint_tokens.len() as u32;
+        let signature_proof_id =
+            QProvingJobDataID::mint_token_signature_proof(rpc_node_id, self.checkpoint_id, count);
+
+        ps.set_bytes_by_id(signature_proof_id, &req.signature_proof)?;
+
+        Ok(CityMintTokenRequest::new(
+            req.user_id,
+            req.to,
+            req.value,
+            req.nonce,
+            signature_proof_id,
+        ))
+    }
+    pub fn injest_rpc_send_token<PS: QProofStore>(
+        &self,
+        ps: &mut PS,
+        rpc_node_id: u32,
+        req: &CitySendTokenRPCRequest,
+    ) -> anyhow::Result<CitySendTokenRequest> {
+        if req.to == req.user_id {
+            anyhow::bail!("cannot send tokens to self");
+        }
+        let count = self.output.send_tokens.len() as u32;
+        let signature_proof_id =
+            QProvingJobDataID::send_token_signature_proof(rpc_node_id, self.checkpoint_id, count);
+
+        ps.set_bytes_by_id(signature_proof_id, &req.signature_proof)?;
+
+        Ok(CitySendTokenRequest::new(
+            req.user_id,
+            req.token_id,
+            req.to,
+            req.value,
+            req.nonce,
+            signature_proof_id,
+        ))
+    }
+    pub fn injest_rpc_burn_token<PS: QProofStore>(
+        &self,
+        ps: &mut PS,
+        rpc_node_id: u32,
+        req: &CityBurnTokenRPCRequest,
+    ) -> anyhow::Result<CityBurnTokenRequest> {
+        let count = self.output.burn_tokens.len() as u32;
+        let signature_proof_id =
+            QProvingJobDataID::burn_token_signature_proof(rpc_node_id, self.checkpoint_id, count);
+
+        ps.set_bytes_by_id(signature_proof_id, &req.signature_proof)?;
+
+        Ok(CityBurnTokenRequest::new(
+            req.user_id,
+            req.token_id,
+            req.value,
+            req.nonce,
+            signature_proof_id,
+        ))
+    }
+    pub fn injest_rpc_cancel_withdrawal<PS: QProofStore>(
+        &self,
+        ps: &mut PS,
+        rpc_node_id: u32,
+        req: &CityCancelWithdrawalRPCRequest<F>,
+    ) -> anyhow::Result<CityCancelWithdrawalRequest<F>> {
+        let count = self.output.cancel_withdrawals.len() as u32;
+        let signature_proof_id = QProvingJobDataID::cancel_withdrawal_signature_proof(
+            rpc_node_id,
+            self.checkpoint_id,
+            count,
+        );
+
+        ps.set_bytes_by_id(signature_proof_id, &req.signature_proof)?;
+
+        Ok(CityCancelWithdrawalRequest::new(
+            req.user_id,
+            req.withdrawal_id,
+            req.withdrawal_hash,
+            req.nonce,
+            signature_proof_id,
+        ))
+    }
     pub fn process_withdrawals<PS: QProofStore>(
         &mut self,
         ps: &mut PS,
@@ -211,6 +497,90 @@ impl<F: RichField> QRPCProcessor<F> {
         }
         Ok(())
     }
+    pub fn process_change_public_keys<PS: QProofStore>(
+        &mut self,
+        ps: &mut PS,
+        rpc_node_id: u32,
+        reqs: &[CityChangePublicKeyRPCRequest<F>],
+    ) -> anyhow::Result<()> {
+        for req in reqs {
+            let change_public_key = self.injest_rpc_change_public_key(ps, rpc_node_id, req)?;
+            self.output.change_public_keys.push(change_public_key);
+        }
+        Ok(())
+    }
+    pub fn process_batch_token_transfers<PS: QProofStore>(
+        &mut self,
+        ps: &mut PS,
+        rpc_node_id: u32,
+        reqs: &[CityBatchTokenTransferRPCRequest],
+    ) -> anyhow::Result<()> {
+        for req in reqs {
+            let batch_token_transfer = self.injest_rpc_batch_token_transfer(ps, rpc_node_id, req)?;
+            self.output.batch_token_transfers.push(batch_token_transfer);
+        }
+        Ok(())
+    }
+    pub fn process_open_token_accounts<PS: QProofStore>(
+        &mut self,
+        ps: &mut PS,
+        rpc_node_id: u32,
+        reqs: &[CityOpenTokenAccountRPCRequest],
+    ) -> anyhow::Result<()> {
+        for req in reqs {
+            let open_token_account = self.injest_rpc_open_token_account(ps, rpc_node_id, req)?;
+            self.output.open_token_accounts.push(open_token_account);
+        }
+        Ok(())
+    }
+    pub fn process_mint_tokens<PS: QProofStore>(
+        &mut self,
+        ps: &mut PS,
+        rpc_node_id: u32,
+        reqs: &[CityMintTokenRPCRequest],
+    ) -> anyhow::Result<()> {
+        for req in reqs {
+            let mint_token = self.injest_rpc_mint_token(ps, rpc_node_id, req)?;
+            self.output.mint_tokens.push(mint_token);
+        }
+        Ok(())
+    }
+    pub fn process_send_tokens<PS: QProofStore>(
+        &mut self,
+        ps: &mut PS,
+        rpc_node_id: u32,
+        reqs: &[CitySendTokenRPCRequest],
+    ) -> anyhow::Result<()> {
+        for req in reqs {
+            let send_token = self.injest_rpc_send_token(ps, rpc_node_id, req)?;
+            self.output.send_tokens.push(send_token);
+        }
+        Ok(())
+    }
+    pub fn process_burn_tokens<PS: QProofStore>(
+        &mut self,
+        ps: &mut PS,
+        rpc_node_id: u32,
+        reqs: &[CityBurnTokenRPCRequest],
+    ) -> anyhow::Result<()> {
+        for req in reqs {
+            let burn_token = self.injest_rpc_burn_token(ps, rpc_node_id, req)?;
+            self.output.burn_tokens.push(burn_token);
+        }
+        Ok(())
+    }
+    pub fn process_cancel_withdrawals<PS: QProofStore>(
+        &mut self,
+        ps: &mut PS,
+        rpc_node_id: u32,
+        reqs: &[CityCancelWithdrawalRPCRequest<F>],
+    ) -> anyhow::Result<()> {
+        for req in reqs {
+            let cancel_withdrawal = self.injest_rpc_cancel_withdrawal(ps, rpc_node_id, req)?;
+            self.output.cancel_withdrawals.push(cancel_withdrawal);
+        }
+        Ok(())
+    }
     pub fn process_register_users(
         &mut self,
         rpc_node_id: u32,
@@ -1,12 +1,13 @@
-use std::collections::VecDeque;
-
 use crate::{
     actors::traits::{WorkerEventReceiverSync, WorkerEventTransmitterSync},
-    qworker::job_id::{QProvingJobDataID, QWorkerJobBenchmark},
+    qworker::{
+        job_id::{QProvingJobDataID, QWorkerJobBenchmark},
+        priority::{QJobPriorityQueue, QPrioritizedJob},
+    },
 };
 
 pub struct CityEventProcessorMemory {
-    pub job_queue: VecDeque<QProvingJobDataID>,
+    pub job_queue: QJobPriorityQueue,
     pub benchmarks_enabled: bool,
     pub benchmarks: Vec<QWorkerJobBenchmark>,
     pub core_job_completed: bool,
@@ -17,7 +18,7 @@ impl CityEventProcessorMemory {
     }
     pub fn new_with_config(benchmarks_enabled: bool) -> Self {
         Self {
-            job_queue: VecDeque::new(),
+            job_queue: QJobPriorityQueue::new(),
             benchmarks_enabled,
             benchmarks: Vec::new(),
             core_job_completed: true,
@@ -29,13 +30,13 @@ impl WorkerEventReceiverSync for CityEventProcessorMemory {
         if self.job_queue.is_empty() {
             Err(anyhow::format_err!("No jobs in queue, note that CityEventProcessorMemory::wait_for_next_job does not block the thread like other implementations of WorkerEventReceiverSync do."))
         } else {
-            Ok(self.job_queue.pop_front().unwrap())
+            Ok(self.job_queue.pop().unwrap())
         }
     }
 
-    fn enqueue_jobs(&mut self, jobs: &[QProvingJobDataID]) -> anyhow::Result<()> {
+    fn enqueue_prioritized_jobs(&mut self, jobs: &[QPrioritizedJob]) -> anyhow::Result<()> {
         self.core_job_completed = false;
-        self.job_queue.extend(jobs.into_iter());
+        self.job_queue.extend(jobs.iter().copied());
         Ok(())
     }
 
@@ -56,8 +57,8 @@ impl WorkerEventReceiverSync for CityEventProcessorMemory {
 }
 
 impl WorkerEventTransmitterSync for CityEventProcessorMemory {
-    fn enqueue_jobs(&mut self, jobs: &[QProvingJobDataID]) -> anyhow::Result<()> {
-        self.job_queue.extend(jobs.into_iter());
+    fn enqueue_prioritized_jobs(&mut self, jobs: &[QPrioritizedJob]) -> anyhow::Result<()> {
+        self.job_queue.extend(jobs.iter().copied());
         Ok(())
     }
 
@@ -6,8 +6,10 @@ use crate::{
         traits::OrchestratorRPCEventSenderSync,
     },
     api::data::block::rpc_request::{
-        CityAddWithdrawalRPCRequest, CityClaimDepositRPCRequest, CityRegisterUserRPCRequest,
-        CityTokenTransferRPCRequest,
+        CityAddWithdrawalRPCRequest, CityBatchTokenTransferRPCRequest, CityBurnTokenRPCRequest,
+        CityCancelWithdrawalRPCRequest, CityChangePublicKeyRPCRequest, CityClaimDepositRPCRequest,
+        CityMintTokenRPCRequest, CityOpenTokenAccountRPCRequest, CityRegisterUserRPCRequest,
+        CitySendTokenRPCRequest, CityTokenTransferRPCRequest,
     },
     qworker::proof_store::QProofStore,
 };
@@ -17,6 +19,13 @@ pub struct SimpleCoordinatatorRPCQueueMemory<F: RichField> {
     pub register_users: Vec<CityRegisterUserRPCRequest<F>>,
     pub add_withdrawals: Vec<CityAddWithdrawalRPCRequest>,
     pub token_transfers: Vec<CityTokenTransferRPCRequest>,
+    pub change_public_keys: Vec<CityChangePublicKeyRPCRequest<F>>,
+    pub batch_token_transfers: Vec<CityBatchTokenTransferRPCRequest>,
+    pub open_token_accounts: Vec<CityOpenTokenAccountRPCRequest>,
+    pub mint_tokens: Vec<CityMintTokenRPCRequest>,
+    pub send_tokens: Vec<CitySendTokenRPCRequest>,
+    pub burn_tokens: Vec<CityBurnTokenRPCRequest>,
+    pub cancel_withdrawals: Vec<CityCancelWithdrawalRPCRequest<F>>,
 }
 impl<F: RichField> SimpleCoordinatatorRPCQueueMemory<F> {
     pub fn new() -> Self {
@@ -25,6 +34,13 @@ impl<F: RichField> SimpleCoordinatatorRPCQueueMemory<F> {
             register_users: Vec::new(),
             add_withdrawals: Vec::new(),
             token_transfers: Vec::new(),
+            change_public_keys: Vec::new(),
+            batch_token_transfers: Vec::new(),
+            open_token_accounts: Vec::new(),
+            mint_tokens: Vec::new(),
+            send_tokens: Vec::new(),
+            burn_tokens: Vec::new(),
+            cancel_withdrawals: Vec::new(),
         }
     }
     pub fn get_requested_actions_from_rpc<PS: QProofStore>(
@@ -37,6 +53,13 @@ impl<F: RichField> SimpleCoordinatatorRPCQueueMemory<F> {
         rpc_processor.process_deposits(proof_store, 0, &self.claim_l1_deposits)?;
         rpc_processor.process_transfers(proof_store, 0, &self.token_transfers)?;
         rpc_processor.process_withdrawals(proof_store, 0, &self.add_withdrawals)?;
+        rpc_processor.process_change_public_keys(proof_store, 0, &self.change_public_keys)?;
+        rpc_processor.process_batch_token_transfers(proof_store, 0, &self.batch_token_transfers)?;
+        rpc_processor.process_open_token_accounts(proof_store, 0, &self.open_token_accounts)?;
+        rpc_processor.process_mint_tokens(proof_store, 0, &self.mint_tokens)?;
+        rpc_processor.process_send_tokens(proof_store, 0, &self.send_tokens)?;
+        rpc_processor.process_burn_tokens(proof_store, 0, &self.burn_tokens)?;
+        rpc_processor.process_cancel_withdrawals(proof_store, 0, &self.cancel_withdrawals)?;
         Ok(rpc_processor.output)
     }
 }
@@ -73,6 +96,53 @@ impl<F: RichField> OrchestratorRPCEventSenderSync<F> for SimpleCoordinatatorRPCQ
         Ok(())
     }
 
+    fn notify_rpc_change_public_key(
+        &mut self,
+        event: &CityChangePublicKeyRPCRequest<F>,
+    ) -> anyhow::Result<()> {
+        self.change_public_keys.push(event.clone());
+        Ok(())
+    }
+
+    fn notify_rpc_batch_token_transfer(
+        &mut self,
+        event: &CityBatchTokenTransferRPCRequest,
+    ) -> anyhow::Result<()> {
+        self.batch_token_transfers.push(event.clone());
+        Ok(())
+    }
+
+    fn notify_rpc_open_token_account(
+        &mut self,
+        event: &CityOpenTokenAccountRPCRequest,
+    ) -> anyhow::Result<()> {
+        self.open_token_accounts.push(event.clone());
+        Ok(())
+    }
+
+    fn notify_rpc_mint_token(&mut self, event: &CityMintTokenRPCRequest) -> anyhow::Result<()> {
+        self.mint_tokens.push(event.clone());
+        Ok(())
+    }
+
+    fn notify_rpc_send_token(&mut self, event: &CitySendTokenRPCRequest) -> anyhow::Result<()> {
+        self.send_tokens.push(event.clone());
+        Ok(())
+    }
+
+    fn notify_rpc_burn_token(&mut self, event: &CityBurnTokenRPCRequest) -> anyhow::Result<()> {
+        self.burn_tokens.push(event.clone());
+        Ok(())
+    }
+
+    fn notify_rpc_cancel_withdrawal(
+        &mut self,
+        event: &CityCancelWithdrawalRPCRequest<F>,
+    ) -> anyhow::Result<()> {
+        self.cancel_withdrawals.push(event.clone());
+        Ok(())
+    }
+
     fn notify_rpc_produce_block(&mut self) -> anyhow::Result<()> {
         Ok(())
     }
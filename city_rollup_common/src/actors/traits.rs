@@ -4,17 +4,22 @@ use crate::{
     api::data::{
         block::{
             requested_actions::{
-                CityAddWithdrawalRequest, CityClaimDepositRequest, CityRegisterUserRequest,
-                CityTokenTransferRequest,
+                CityAddWithdrawalRequest, CityBatchTokenTransferRequest, CityBurnTokenRequest,
+                CityCancelWithdrawalRequest, CityChangePublicKeyRequest, CityClaimDepositRequest,
+                CityMintTokenRequest, CityOpenTokenAccountRequest, CityRegisterUserRequest,
+                CitySendTokenRequest, CityTokenTransferRequest,
             },
             rpc_request::{
-                CityAddWithdrawalRPCRequest, CityClaimDepositRPCRequest,
-                CityRegisterUserRPCRequest, CityTokenTransferRPCRequest,
+                CityAddWithdrawalRPCRequest, CityBatchTokenTransferRPCRequest,
+                CityBurnTokenRPCRequest, CityCancelWithdrawalRPCRequest,
+                CityChangePublicKeyRPCRequest, CityClaimDepositRPCRequest, CityMintTokenRPCRequest,
+                CityOpenTokenAccountRPCRequest, CityRegisterUserRPCRequest,
+                CitySendTokenRPCRequest, CityTokenTransferRPCRequest,
             },
         },
         store::{CityL1Deposit, CityL1Withdrawal, CityL2BlockState, CityUserState},
     },
-    qworker::job_id::QProvingJobDataID,
+    qworker::{job_id::QProvingJobDataID, priority::QPrioritizedJob},
 };
 
 pub trait OrchestratorRPCEventSenderSync<F: RichField> {
@@ -34,6 +39,25 @@ pub trait OrchestratorRPCEventSenderSync<F: RichField> {
         &mut self,
         event: &CityTokenTransferRPCRequest,
     ) -> anyhow::Result<()>;
+    fn notify_rpc_change_public_key(
+        &mut self,
+        event: &CityChangePublicKeyRPCRequest<F>,
+    ) -> anyhow::Result<()>;
+    fn notify_rpc_batch_token_transfer(
+        &mut self,
+        event: &CityBatchTokenTransferRPCRequest,
+    ) -> anyhow::Result<()>;
+    fn notify_rpc_open_token_account(
+        &mut self,
+        event: &CityOpenTokenAccountRPCRequest,
+    ) -> anyhow::Result<()>;
+    fn notify_rpc_mint_token(&mut self, event: &CityMintTokenRPCRequest) -> anyhow::Result<()>;
+    fn notify_rpc_send_token(&mut self, event: &CitySendTokenRPCRequest) -> anyhow::Result<()>;
+    fn notify_rpc_burn_token(&mut self, event: &CityBurnTokenRPCRequest) -> anyhow::Result<()>;
+    fn notify_rpc_cancel_withdrawal(
+        &mut self,
+        event: &CityCancelWithdrawalRPCRequest<F>,
+    ) -> anyhow::Result<()>;
     fn notify_rpc_produce_block(&mut self) -> anyhow::Result<()>;
 }
 
@@ -58,6 +82,41 @@ pub trait OrchestratorRPCEventSenderAsync<F: RichField> {
         &mut self,
         event: &CityTokenTransferRPCRequest,
     ) -> anyhow::Result<()>;
+
+    async fn notify_rpc_change_public_key_async(
+        &mut self,
+        event: &CityChangePublicKeyRPCRequest<F>,
+    ) -> anyhow::Result<()>;
+
+    async fn notify_rpc_batch_token_transfer_async(
+        &mut self,
+        event: &CityBatchTokenTransferRPCRequest,
+    ) -> anyhow::Result<()>;
+
+    async fn notify_rpc_open_token_account_async(
+        &mut self,
+        event: &CityOpenTokenAccountRPCRequest,
+    ) -> anyhow::Result<()>;
+
+    async fn notify_rpc_mint_token_async(
+        &mut self,
+        event: &CityMintTokenRPCRequest,
+    ) -> anyhow::Result<()>;
+
+    async fn notify_rpc_send_token_async(
+        &mut self,
+        event: &CitySendTokenRPCRequest,
+    ) -> anyhow::Result<()>;
+
+    async fn notify_rpc_burn_token_async(
+        &mut self,
+        event: &CityBurnTokenRPCRequest,
+    ) -> anyhow::Result<()>;
+
+    async fn notify_rpc_cancel_withdrawal_async(
+        &mut self,
+        event: &CityCancelWithdrawalRPCRequest<F>,
+    ) -> anyhow::Result<()>;
     async fn notify_rpc_produce_block_async(&mut self) -> anyhow::Result<()>;
 }
 
@@ -66,6 +125,25 @@ pub trait OrchestratorEventSenderSync<F: RichField> {
     fn notify_register_user(&mut self, event: &CityRegisterUserRequest<F>) -> anyhow::Result<()>;
     fn notify_add_withdrawal(&mut self, event: &CityAddWithdrawalRequest) -> anyhow::Result<()>;
     fn notify_token_transfer(&mut self, event: &CityTokenTransferRequest) -> anyhow::Result<()>;
+    fn notify_change_public_key(
+        &mut self,
+        event: &CityChangePublicKeyRequest<F>,
+    ) -> anyhow::Result<()>;
+    fn notify_batch_token_transfer(
+        &mut self,
+        event: &CityBatchTokenTransferRequest,
+    ) -> anyhow::Result<()>;
+    fn notify_open_token_account(
+        &mut self,
+        event: &CityOpenTokenAccountRequest,
+    ) -> anyhow::Result<()>;
+    fn notify_mint_token(&mut self, event: &CityMintTokenRequest) -> anyhow::Result<()>;
+    fn notify_send_token(&mut self, event: &CitySendTokenRequest) -> anyhow::Result<()>;
+    fn notify_burn_token(&mut self, event: &CityBurnTokenRequest) -> anyhow::Result<()>;
+    fn notify_cancel_withdrawal(
+        &mut self,
+        event: &CityCancelWithdrawalRequest<F>,
+    ) -> anyhow::Result<()>;
     fn notify_produce_block(&mut self) -> anyhow::Result<()>;
 }
 
@@ -102,17 +180,45 @@ pub trait OrchestratorEventReceiverSync<F: RichField> {
 
     fn flush_token_transfers(&mut self) -> anyhow::Result<Vec<CityTokenTransferRequest>>;
 
+    fn flush_change_public_keys(&mut self)
+        -> anyhow::Result<Vec<CityChangePublicKeyRequest<F>>>;
+
+    fn flush_batch_token_transfers(&mut self)
+        -> anyhow::Result<Vec<CityBatchTokenTransferRequest>>;
+
+    fn flush_open_token_accounts(&mut self) -> anyhow::Result<Vec<CityOpenTokenAccountRequest>>;
+
+    fn flush_mint_tokens(&mut self) -> anyhow::Result<Vec<CityMintTokenRequest>>;
+
+    fn flush_send_tokens(&mut self) -> anyhow::Result<Vec<CitySendTokenRequest>>;
+
+    fn flush_burn_tokens(&mut self) -> anyhow::Result<Vec<CityBurnTokenRequest>>;
+
+    fn flush_cancel_withdrawals(&mut self) -> anyhow::Result<Vec<CityCancelWithdrawalRequest<F>>>;
+
     fn wait_for_produce_block(&mut self) -> anyhow::Result<bool>;
 }
 pub trait WorkerEventReceiverSync {
     fn wait_for_next_job(&mut self) -> anyhow::Result<QProvingJobDataID>;
-    fn enqueue_jobs(&mut self, jobs: &[QProvingJobDataID]) -> anyhow::Result<()>;
+    fn enqueue_jobs(&mut self, jobs: &[QProvingJobDataID]) -> anyhow::Result<()> {
+        self.enqueue_prioritized_jobs(&QPrioritizedJob::with_default_priority(jobs))
+    }
+    fn enqueue_prioritized_jobs(&mut self, jobs: &[QPrioritizedJob]) -> anyhow::Result<()>;
     fn notify_core_goal_completed(&mut self, job: QProvingJobDataID) -> anyhow::Result<()>;
     fn record_job_bench(&mut self, job: QProvingJobDataID, duration: u64) -> anyhow::Result<()>;
+    fn notify_job_started(&mut self, _job: QProvingJobDataID) -> anyhow::Result<()> {
+        Ok(())
+    }
+    fn notify_job_finished(&mut self, _job: QProvingJobDataID) -> anyhow::Result<()> {
+        Ok(())
+    }
 }
 
 pub trait WorkerEventTransmitterSync {
-    fn enqueue_jobs(&mut self, jobs: &[QProvingJobDataID]) -> anyhow::Result<()>;
+    fn enqueue_jobs(&mut self, jobs: &[QProvingJobDataID]) -> anyhow::Result<()> {
+        self.enqueue_prioritized_jobs(&QPrioritizedJob::with_default_priority(jobs))
+    }
+    fn enqueue_prioritized_jobs(&mut self, jobs: &[QPrioritizedJob]) -> anyhow::Result<()>;
     fn wait_for_block_proving_jobs(&mut self, checkpoint_id: u64) -> anyhow::Result<bool>;
 }
 
@@ -6,7 +6,14 @@ use city_crypto::hash::{
 use plonky2::hash::hash_types::RichField;
 use serde::{Deserialize, Serialize};
 
-use crate::{introspection::transaction::BTCTransaction, qworker::job_id::QProvingJobDataID};
+use crate::{
+    introspection::{
+        rollup::forced_withdrawal::CityForcedWithdrawalRequest, transaction::BTCTransaction,
+    },
+    qworker::job_id::QProvingJobDataID,
+};
+
+use super::rpc_request::CityBatchTransferRecipient;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Hash, Copy, PartialEq, Eq)]
 pub struct CityTokenTransferRequest {
@@ -14,6 +21,7 @@ pub struct CityTokenTransferRequest {
     pub user_id: u64,
     pub to: u64,
     pub value: u64,
+    pub fee: u64,
     pub nonce: u64,
     pub signature_proof_id: QProvingJobDataID,
 }
@@ -22,6 +30,7 @@ impl CityTokenTransferRequest {
         user_id: u64,
         to: u64,
         value: u64,
+        fee: u64,
         nonce: u64,
         signature_proof_id: QProvingJobDataID,
     ) -> Self {
@@ -30,6 +39,150 @@ impl CityTokenTransferRequest {
             user_id,
             to,
             value,
+            fee,
+            nonce,
+            signature_proof_id,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub struct CityBatchTokenTransferRequest {
+    request_type: u8,
+    pub user_id: u64,
+    pub transfers: Vec<CityBatchTransferRecipient>,
+    pub fee: u64,
+    pub nonce: u64,
+    pub signature_proof_id: QProvingJobDataID,
+}
+impl CityBatchTokenTransferRequest {
+    pub fn new(
+        user_id: u64,
+        transfers: Vec<CityBatchTransferRecipient>,
+        fee: u64,
+        nonce: u64,
+        signature_proof_id: QProvingJobDataID,
+    ) -> Self {
+        Self {
+            request_type: 7,
+            user_id,
+            transfers,
+            fee,
+            nonce,
+            signature_proof_id,
+        }
+    }
+    pub fn total_value(&self) -> u64 {
+        self.transfers.iter().map(|t| t.value).sum()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, Copy, PartialEq, Eq)]
+pub struct CityOpenTokenAccountRequest {
+    request_type: u8,
+    pub user_id: u64,
+    pub token_id: u64,
+    pub nonce: u64,
+    pub signature_proof_id: QProvingJobDataID,
+}
+impl CityOpenTokenAccountRequest {
+    pub fn new(
+        user_id: u64,
+        token_id: u64,
+        nonce: u64,
+        signature_proof_id: QProvingJobDataID,
+    ) -> Self {
+        Self {
+            request_type: 8,
+            user_id,
+            token_id,
+            nonce,
+            signature_proof_id,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, Copy, PartialEq, Eq)]
+pub struct CityMintTokenRequest {
+    request_type: u8,
+    pub user_id: u64,
+    pub to: u64,
+    pub value: u64,
+    pub nonce: u64,
+    pub signature_proof_id: QProvingJobDataID,
+}
+impl CityMintTokenRequest {
+    pub fn new(
+        user_id: u64,
+        to: u64,
+        value: u64,
+        nonce: u64,
+        signature_proof_id: QProvingJobDataID,
+    ) -> Self {
+        Self {
+            request_type: 9,
+            user_id,
+            to,
+            value,
+            nonce,
+            signature_proof_id,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, Copy, PartialEq, Eq)]
+pub struct CitySendTokenRequest {
+    request_type: u8,
+    pub user_id: u64,
+    pub token_id: u64,
+    pub to: u64,
+    pub value: u64,
+    pub nonce: u64,
+    pub signature_proof_id: QProvingJobDataID,
+}
+impl CitySendTokenRequest {
+    pub fn new(
+        user_id: u64,
+        token_id: u64,
+        to: u64,
+        value: u64,
+        nonce: u64,
+        signature_proof_id: QProvingJobDataID,
+    ) -> Self {
+        Self {
+            request_type: 10,
+            user_id,
+            token_id,
+            to,
+            value,
+            nonce,
+            signature_proof_id,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, Copy, PartialEq, Eq)]
+pub struct CityBurnTokenRequest {
+    request_type: u8,
+    pub user_id: u64,
+    pub token_id: u64,
+    pub value: u64,
+    pub nonce: u64,
+    pub signature_proof_id: QProvingJobDataID,
+}
+impl CityBurnTokenRequest {
+    pub fn new(
+        user_id: u64,
+        token_id: u64,
+        value: u64,
+        nonce: u64,
+        signature_proof_id: QProvingJobDataID,
+    ) -> Self {
+        Self {
+            request_type: 11,
+            user_id,
+            token_id,
+            value,
             nonce,
             signature_proof_id,
         }
@@ -90,9 +243,10 @@ impl CityAddDepositRequest {
             1,
             "deposits should only have one input (p2pkh)"
         );
-        assert_eq!(
-            funding_tx.outputs.len(),
-            1,
+        // forced withdrawal requests spent by the block are credited as deposits to the requester
+        assert!(
+            funding_tx.outputs.len() == 1
+                || CityForcedWithdrawalRequest::from_funding_transaction(funding_tx, 0).is_some(),
             "deposits should only have one output (send to layer 2)"
         );
         assert!(
@@ -119,6 +273,7 @@ pub struct CityAddWithdrawalRequest {
     request_type: u8,
     pub user_id: u64,
     pub value: u64,
+    pub fee: u64,
     pub nonce: u64,
 
     pub destination_type: u8,
@@ -130,6 +285,7 @@ impl CityAddWithdrawalRequest {
     pub fn new(
         user_id: u64,
         value: u64,
+        fee: u64,
         nonce: u64,
         destination_type: u8,
         destination: Hash160,
@@ -139,6 +295,7 @@ impl CityAddWithdrawalRequest {
             request_type: 3,
             user_id,
             value,
+            fee,
             nonce,
             destination_type,
             destination,
@@ -177,3 +334,60 @@ impl<F: RichField> CityRegisterUserRequest<F> {
         }
     }
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct CityChangePublicKeyRequest<F: RichField> {
+    request_type: u8,
+    pub user_id: u64,
+    pub new_public_key: QHashOut<F>,
+    pub nonce: u64,
+    pub signature_proof_id: QProvingJobDataID,
+}
+
+impl<F: RichField> CityChangePublicKeyRequest<F> {
+    pub fn new(
+        user_id: u64,
+        new_public_key: QHashOut<F>,
+        nonce: u64,
+        signature_proof_id: QProvingJobDataID,
+    ) -> Self {
+        Self {
+            request_type: 6,
+            user_id,
+            new_public_key,
+            nonce,
+            signature_proof_id,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct CityCancelWithdrawalRequest<F: RichField> {
+    request_type: u8,
+    pub user_id: u64,
+    pub withdrawal_id: u64,
+    pub withdrawal_hash: QHashOut<F>,
+    pub nonce: u64,
+    pub signature_proof_id: QProvingJobDataID,
+}
+
+impl<F: RichField> CityCancelWithdrawalRequest<F> {
+    pub fn new(
+        user_id: u64,
+        withdrawal_id: u64,
+        withdrawal_hash: QHashOut<F>,
+        nonce: u64,
+        signature_proof_id: QProvingJobDataID,
+    ) -> Self {
+        Self {
+            request_type: 12,
+            user_id,
+            withdrawal_id,
+            withdrawal_hash,
+            nonce,
+            signature_proof_id,
+        }
+    }
+}
@@ -12,6 +12,25 @@ pub struct CityTokenTransferRPCRequest {
     pub user_id: u64,
     pub to: u64,
     pub value: u64,
+    pub fee: u64,
+    pub nonce: u64,
+
+    #[serde_as(as = "serde_with::hex::Hex")]
+    pub signature_proof: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Hash, PartialEq, Eq)]
+pub struct CityBatchTransferRecipient {
+    pub to: u64,
+    pub value: u64,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CityBatchTokenTransferRPCRequest {
+    pub user_id: u64,
+    pub transfers: Vec<CityBatchTransferRecipient>,
+    pub fee: u64,
     pub nonce: u64,
 
     #[serde_as(as = "serde_with::hex::Hex")]
@@ -39,6 +58,7 @@ pub struct CityClaimDepositRPCRequest {
 pub struct CityAddWithdrawalRPCRequest {
     pub user_id: u64,
     pub value: u64,
+    pub fee: u64,
     pub nonce: u64,
 
     pub destination_type: u8,
@@ -48,6 +68,79 @@ pub struct CityAddWithdrawalRPCRequest {
     pub signature_proof: Vec<u8>,
 }
 
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct CityChangePublicKeyRPCRequest<F: RichField> {
+    pub user_id: u64,
+    pub new_public_key: QHashOut<F>,
+    pub nonce: u64,
+
+    #[serde_as(as = "serde_with::hex::Hex")]
+    pub signature_proof: Vec<u8>,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CityOpenTokenAccountRPCRequest {
+    pub user_id: u64,
+    pub token_id: u64,
+    pub nonce: u64,
+
+    #[serde_as(as = "serde_with::hex::Hex")]
+    pub signature_proof: Vec<u8>,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CityMintTokenRPCRequest {
+    pub user_id: u64,
+    pub to: u64,
+    pub value: u64,
+    pub nonce: u64,
+
+    #[serde_as(as = "serde_with::hex::Hex")]
+    pub signature_proof: Vec<u8>,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CitySendTokenRPCRequest {
+    pub user_id: u64,
+    pub token_id: u64,
+    pub to: u64,
+    pub value: u64,
+    pub nonce: u64,
+
+    #[serde_as(as = "serde_with::hex::Hex")]
+    pub signature_proof: Vec<u8>,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CityBurnTokenRPCRequest {
+    pub user_id: u64,
+    pub token_id: u64,
+    pub value: u64,
+    pub nonce: u64,
+
+    #[serde_as(as = "serde_with::hex::Hex")]
+    pub signature_proof: Vec<u8>,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct CityCancelWithdrawalRPCRequest<F: RichField> {
+    pub user_id: u64,
+    pub withdrawal_id: u64,
+    pub withdrawal_hash: QHashOut<F>,
+    pub nonce: u64,
+
+    #[serde_as(as = "serde_with::hex::Hex")]
+    pub signature_proof: Vec<u8>,
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 #[serde(bound = "")]
 #[serde(transparent)]
@@ -72,4 +165,11 @@ pub enum CityRPCRequest<F: RichField> {
     CityClaimDepositRPCRequest((u32, CityClaimDepositRPCRequest)),
     CityAddWithdrawalRPCRequest((u32, CityAddWithdrawalRPCRequest)),
     CityRegisterUserRPCRequest((u32, CityRegisterUserRPCRequest<F>)),
+    CityChangePublicKeyRPCRequest((u32, CityChangePublicKeyRPCRequest<F>)),
+    CityBatchTokenTransferRPCRequest((u32, CityBatchTokenTransferRPCRequest)),
+    CityOpenTokenAccountRPCRequest((u32, CityOpenTokenAccountRPCRequest)),
+    CityMintTokenRPCRequest((u32, CityMintTokenRPCRequest)),
+    CitySendTokenRPCRequest((u32, CitySendTokenRPCRequest)),
+    CityBurnTokenRPCRequest((u32, CityBurnTokenRPCRequest)),
+    CityCancelWithdrawalRPCRequest((u32, CityCancelWithdrawalRPCRequest<F>)),
 }
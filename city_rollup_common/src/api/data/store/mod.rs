@@ -6,12 +6,19 @@ use city_crypto::hash::{
 use kvq::traits::KVQSerializable;
 use plonky2::{
     field::{goldilocks_field::GoldilocksField, types::PrimeField64},
-    hash::hash_types::RichField,
+    hash::{
+        hash_types::{HashOut, RichField},
+        poseidon::PoseidonHash,
+    },
+    plonk::config::Hasher,
 };
 use serde::{Deserialize, Serialize};
 
 use crate::introspection::{
-    rollup::introspection_result::BTCRollupIntrospectionResultWithdrawal,
+    rollup::{
+        forced_withdrawal::CityForcedWithdrawalRequest,
+        introspection_result::BTCRollupIntrospectionResultWithdrawal,
+    },
     transaction::BTCTransactionOutput,
 };
 
@@ -127,6 +134,49 @@ impl CityUserState {
     }
 }
 
+// token ids are the user ids of their issuers
+#[derive(Debug, Clone, Serialize, Deserialize, Copy, Hash, Eq, PartialEq)]
+pub struct CityTokenInfo {
+    pub token_id: u64,
+    pub issuer_user_id: u64,
+    pub supply: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Copy, Hash, Eq, PartialEq)]
+pub struct CityForcedWithdrawalRequestStatus {
+    pub request: CityForcedWithdrawalRequest,
+    // the checkpoint whose funding transactions included the request, and the request's index in that block
+    pub observed_checkpoint_id: u64,
+    pub index: u64,
+    pub deadline_checkpoint_id: u64,
+    // 0 until a matching withdrawal has been added to the withdrawal tree
+    pub included_checkpoint_id: u64,
+}
+impl CityForcedWithdrawalRequestStatus {
+    pub fn is_included(&self) -> bool {
+        self.included_checkpoint_id != 0
+    }
+    pub fn is_expired(&self, checkpoint_id: u64) -> bool {
+        !self.is_included() && checkpoint_id > self.deadline_checkpoint_id
+    }
+}
+impl KVQSerializable for CityForcedWithdrawalRequestStatus {
+    fn to_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        Ok(bincode::serialize(self)?)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        Ok(bincode::deserialize(bytes)?)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Copy, Hash, Eq, PartialEq)]
+pub struct CityTokenBalance {
+    pub user_id: u64,
+    pub token_id: u64,
+    pub balance: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Copy, Hash, Eq, PartialEq)]
 pub struct CityL1DepositJSON {
     pub deposit_id: u64,
@@ -273,6 +323,27 @@ impl CityL1Withdrawal {
             value,
         }
     }
+    // the withdrawal tree leaf commits to the user who added the withdrawal, so only they can
+    // cancel it
+    pub fn get_leaf_hash<F: RichField>(&self, owner_user_id: u64) -> QHashOut<F> {
+        Self::leaf_hash_from_withdrawal_hash(QHashOut::from(self), owner_user_id)
+    }
+    pub fn leaf_hash_from_withdrawal_hash<F: RichField>(
+        withdrawal_hash: QHashOut<F>,
+        owner_user_id: u64,
+    ) -> QHashOut<F> {
+        QHashOut(PoseidonHash::two_to_one(
+            withdrawal_hash.0,
+            HashOut {
+                elements: [
+                    F::from_noncanonical_u64(owner_user_id),
+                    F::ZERO,
+                    F::ZERO,
+                    F::ZERO,
+                ],
+            },
+        ))
+    }
     pub fn to_btc_tx_out(&self) -> BTCTransactionOutput {
         BTCTransactionOutput {
             value: self.value,
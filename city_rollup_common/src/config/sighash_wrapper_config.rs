@@ -1,18 +1,17 @@
+// @generated by `city-rollup-dev-cli gen-sighash-whitelist`, do not edit by hand
 use city_crypto::hash::qhashout::QHashOut;
 use plonky2::{field::goldilocks_field::GoldilocksField, hash::hash_types::HashOut};
 
 type F = GoldilocksField;
 
-
 pub const SIGHASH_CIRCUIT_WHITELIST_TREE_HEIGHT: u8 = 16;
 
-
 // set SIGHASH_WHITELIST_DISABLED_DEV_MODE = true in development ONLY, this sighash whitelist inclusion check for debugging circuits
 pub const SIGHASH_WHITELIST_DISABLED_DEV_MODE: bool = false;
 
-
 pub const SIGHASH_CIRCUIT_MAX_WITHDRAWALS: usize = 4;
 pub const SIGHASH_CIRCUIT_MAX_DEPOSITS: usize = 4;
+pub const SIGHASH_CIRCUIT_MAX_FORCED_WITHDRAWAL_REQUESTS: usize = 0;
 pub const SIGHASH_WHITELIST_TREE_ROOT: QHashOut<F> = QHashOut(HashOut {
     elements: [
         GoldilocksField(17337617638752727703),
@@ -1897,188 +1896,4 @@ pub const SIGHASH_CIRCUIT_FINGERPRINTS: [QHashOut<F>; 1875] = [
     QHashOut(HashOut{elements: [GoldilocksField(4588570795053488330), GoldilocksField(7333493645061851450), GoldilocksField(4073002074925396845), GoldilocksField(17460991817126411247)]}),
     QHashOut(HashOut{elements: [GoldilocksField(13471447011697432990), GoldilocksField(1379366118113344088), GoldilocksField(5477506746841419969), GoldilocksField(11697575545003014843)]}),
     QHashOut(HashOut{elements: [GoldilocksField(16541940191672912703), GoldilocksField(6824583360954749029), GoldilocksField(16182800053238010806), GoldilocksField(10718011556885963982)]}),
-    
-    ];
-    
-    
-/*
-// OP_NOP_VERSION
-pub const SIGHASH_CIRCUIT_MAX_WITHDRAWALS: usize = 2;
-pub const SIGHASH_CIRCUIT_MAX_DEPOSITS: usize = 2;
-pub const SIGHASH_WHITELIST_TREE_ROOT: QHashOut<F> = QHashOut(HashOut {
-    elements: [
-        GoldilocksField(3955197275384853261),
-        GoldilocksField(4233408120910680438),
-        GoldilocksField(9482222823114134518),
-        GoldilocksField(2988611172217115292),
-    ],
-});
-
-pub const SIGHASH_CIRCUIT_FINGERPRINTS: [QHashOut<F>; 162] = [
-
-    QHashOut(HashOut{elements: [GoldilocksField(10531113755850169053), GoldilocksField(9797158116342438439), GoldilocksField(6123980908818528685), GoldilocksField(8274337692923815974)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(9421180252152179845), GoldilocksField(14990902754104202309), GoldilocksField(2470717698501042445), GoldilocksField(12619628326608752753)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(7236138986579285337), GoldilocksField(14373820379144824753), GoldilocksField(6400138107579035464), GoldilocksField(16440487215933968142)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(7835813152876493774), GoldilocksField(3710982265604498981), GoldilocksField(14426263031798500301), GoldilocksField(1091848750668125101)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(12505473951574648541), GoldilocksField(18129989008936126077), GoldilocksField(6359266291867864790), GoldilocksField(5800052753517505960)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(1457249705900793116), GoldilocksField(11919373481811106326), GoldilocksField(2861009726378787174), GoldilocksField(17968193302261895966)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(2374169639827966936), GoldilocksField(1762379056926699835), GoldilocksField(9622689025603285616), GoldilocksField(9286642298314585095)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(14327788464792648128), GoldilocksField(17820805539565191886), GoldilocksField(8589675092820864913), GoldilocksField(15343683098504609735)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(9170872587036185531), GoldilocksField(8818206317118134115), GoldilocksField(17143301656174275415), GoldilocksField(3337059864032771179)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(4992174498908954900), GoldilocksField(16624097414882782975), GoldilocksField(17325838242604137591), GoldilocksField(9651300079956600050)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(8704536745975474278), GoldilocksField(10298170501754060656), GoldilocksField(402310835896696650), GoldilocksField(1444619277604945230)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(9479065549008444355), GoldilocksField(15626611751557697894), GoldilocksField(1574478627248619972), GoldilocksField(2297656265616307194)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(17046402495870805759), GoldilocksField(3088586082436906530), GoldilocksField(7003446975486995953), GoldilocksField(7854136173737966919)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(2780635250583375155), GoldilocksField(10018787557538244728), GoldilocksField(13064082257165927970), GoldilocksField(15296746838861243589)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(13469033118961917892), GoldilocksField(3197191239730942676), GoldilocksField(9289424005702189563), GoldilocksField(16964816933803713624)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(460525398904121375), GoldilocksField(14832601512561363407), GoldilocksField(6537899579479906637), GoldilocksField(15997985658595718897)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(6636178223630666589), GoldilocksField(7877158926417504167), GoldilocksField(8072333886895366816), GoldilocksField(4290030933672611986)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(1310552172409608214), GoldilocksField(5215525037041928971), GoldilocksField(2232061463801363057), GoldilocksField(2637771197714040242)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(1812031416229017288), GoldilocksField(2954825025899743096), GoldilocksField(4598920715701006644), GoldilocksField(11541204205376246346)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(11795483366770455443), GoldilocksField(412105912341615910), GoldilocksField(9634355484866422929), GoldilocksField(12918295710408028334)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(7873547492377520887), GoldilocksField(8666363790831039219), GoldilocksField(14289464751108313817), GoldilocksField(16001164926600887346)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(15689894764043746421), GoldilocksField(771877774938436634), GoldilocksField(11948686536395499679), GoldilocksField(10511679113970051519)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(8207016837589655730), GoldilocksField(13109740127890619179), GoldilocksField(12421432614440307463), GoldilocksField(6746185637085331612)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(9870201877948585582), GoldilocksField(13809341660309910920), GoldilocksField(9777574140793208666), GoldilocksField(4135446512735777238)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(3562001008734381847), GoldilocksField(10162820319223975552), GoldilocksField(10457169506246403321), GoldilocksField(10562113585984754831)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(11650947320982123104), GoldilocksField(4759653283155340300), GoldilocksField(14766128238181201657), GoldilocksField(15014110729806747611)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(6362983707126358299), GoldilocksField(4038084900913756481), GoldilocksField(12505671482526699696), GoldilocksField(15513473540333585325)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(17290331780280725428), GoldilocksField(15029551653438226571), GoldilocksField(9306165911904692626), GoldilocksField(1205371528270612752)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(15281223712699090748), GoldilocksField(5548217012963433786), GoldilocksField(7217122718443225529), GoldilocksField(17088461334574466202)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(12642701435677696275), GoldilocksField(16181258869153397966), GoldilocksField(2279322348273959130), GoldilocksField(15998051925307558616)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(9227428640258779227), GoldilocksField(520583082925095651), GoldilocksField(13920009732720952211), GoldilocksField(13835311435051985176)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(8659401082507018492), GoldilocksField(122971038560079129), GoldilocksField(16377601784375844483), GoldilocksField(15193879587916507180)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(5035017440978955629), GoldilocksField(15653233531007544500), GoldilocksField(2270603885251235356), GoldilocksField(8021499688832180186)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(15848924170279417531), GoldilocksField(4167787332810832888), GoldilocksField(6059767657687308510), GoldilocksField(540975929143320750)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(17452636187315154739), GoldilocksField(34838630665090509), GoldilocksField(3022216481063450749), GoldilocksField(550685101826802659)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(7022304491949894187), GoldilocksField(12997994681168975948), GoldilocksField(6824308739535913835), GoldilocksField(5477842799304786377)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(11632485948897851894), GoldilocksField(14475067602338298763), GoldilocksField(17257469364547378835), GoldilocksField(7242055785598521866)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(1203730228964176170), GoldilocksField(2263156810246020084), GoldilocksField(4407583771809204498), GoldilocksField(267448544957616805)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(1290992941749635244), GoldilocksField(4085434864862931764), GoldilocksField(1000636686869423804), GoldilocksField(10599518064461410373)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(14188416695420395828), GoldilocksField(3841873363562205647), GoldilocksField(3601700181800648677), GoldilocksField(1122386268410747374)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(13089898141804216728), GoldilocksField(11300031861602549753), GoldilocksField(9537252359447255536), GoldilocksField(9138678839050140214)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(15572465388127216513), GoldilocksField(2158648012945405525), GoldilocksField(8717903172786408063), GoldilocksField(4554073627957480145)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(4147508874639357320), GoldilocksField(15147207917737930521), GoldilocksField(1402023430957453323), GoldilocksField(5928765397760194343)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(13706412978827276304), GoldilocksField(13189777577533962778), GoldilocksField(1036433761034408722), GoldilocksField(8910627190934277264)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(12788718776299532521), GoldilocksField(11686244687950247255), GoldilocksField(360990641194527174), GoldilocksField(5545764312926814508)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(17575015485969455994), GoldilocksField(7863602624578061412), GoldilocksField(2457531560891384595), GoldilocksField(3794170297020625254)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(12818160393107285540), GoldilocksField(10064033393036821465), GoldilocksField(18325779747086947523), GoldilocksField(9005875267708317521)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(6842216565913642024), GoldilocksField(18213912019224581209), GoldilocksField(10722861861690083065), GoldilocksField(3763211348819566510)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(13287370494962078436), GoldilocksField(11148363381730860901), GoldilocksField(11207046519460652636), GoldilocksField(14337471979591626836)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(15451440329031816163), GoldilocksField(2520292119736520767), GoldilocksField(15928079764083475071), GoldilocksField(8844758647062684038)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(1037773689274212819), GoldilocksField(9648384117636385174), GoldilocksField(13040151999218765618), GoldilocksField(11795893526178985484)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(5209951978168643490), GoldilocksField(1655397726914493644), GoldilocksField(16134518070973520595), GoldilocksField(11427147376537360446)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(14033524627254954771), GoldilocksField(4294409809408654516), GoldilocksField(4766262879871604653), GoldilocksField(12252845118384172252)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(3392326255840689709), GoldilocksField(13939178963107391333), GoldilocksField(4544638769063741374), GoldilocksField(1315466397715091679)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(9151545978941243634), GoldilocksField(4426736058265103878), GoldilocksField(11973486173184641905), GoldilocksField(3736717384288915843)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(18030725719275455170), GoldilocksField(12506419609270421087), GoldilocksField(18149901658172636548), GoldilocksField(1108055309418625854)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(15005640130508777061), GoldilocksField(9017668431558454478), GoldilocksField(7524245452352608076), GoldilocksField(2330411253727482555)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(10195817359056690966), GoldilocksField(5208143067596332023), GoldilocksField(16214431429222014894), GoldilocksField(12855225411692389920)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(10654492738578357009), GoldilocksField(16956498247286729206), GoldilocksField(17207302186598410682), GoldilocksField(9467889988418983556)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(18192570913710395494), GoldilocksField(1205233893717254693), GoldilocksField(15368094314437552447), GoldilocksField(9570901175631818946)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(16088073460662194534), GoldilocksField(13587202464831230144), GoldilocksField(8287272225928791511), GoldilocksField(10688537136220313164)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(2911522171377164801), GoldilocksField(12185037618488332562), GoldilocksField(12264500335526937365), GoldilocksField(10959954313371424565)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(485462130855718433), GoldilocksField(17014091391126689956), GoldilocksField(10072025605366984469), GoldilocksField(6203997527790110896)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(17895426243453414749), GoldilocksField(15497341571208055785), GoldilocksField(412033752682930410), GoldilocksField(10487563438671312873)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(18373451106418233169), GoldilocksField(5212927167758127271), GoldilocksField(9970876144546988385), GoldilocksField(4896261413689171569)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(3015913568091700251), GoldilocksField(8444012755415277470), GoldilocksField(15511217704390976106), GoldilocksField(13864355365356464194)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(15911872938179112150), GoldilocksField(10195269648909642562), GoldilocksField(17025027388571551184), GoldilocksField(16220448697401226635)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(2075144166047132739), GoldilocksField(16332216459216555773), GoldilocksField(886139512956861007), GoldilocksField(1686652089761733236)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(13446359702414496995), GoldilocksField(16504211984551269815), GoldilocksField(10174085811425881384), GoldilocksField(8128955034785724360)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(5357325371805366952), GoldilocksField(7558998687007141732), GoldilocksField(18402155555444294499), GoldilocksField(853455706908945449)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(2227285541876253918), GoldilocksField(17636931306212501939), GoldilocksField(17995983784000582976), GoldilocksField(16113359451073787195)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(8644392876983799585), GoldilocksField(2674418929279589426), GoldilocksField(11063420402981422256), GoldilocksField(13400248814928954009)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(7783639029878150946), GoldilocksField(12936839391085608862), GoldilocksField(3105800479847993474), GoldilocksField(4021097588791419587)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(14300310054646795287), GoldilocksField(6532446375899103902), GoldilocksField(7599136610167008218), GoldilocksField(15013889038016416129)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(5734901781821421120), GoldilocksField(4108827760171238638), GoldilocksField(2161929544024124444), GoldilocksField(6793867439409384516)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(8045523026414448351), GoldilocksField(18075589888387138232), GoldilocksField(2702913579700301990), GoldilocksField(11661368558174871063)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(9870924677556725063), GoldilocksField(13884110524839103538), GoldilocksField(993817000406656880), GoldilocksField(10955554507635574481)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(17358396510818376244), GoldilocksField(4319128793631015228), GoldilocksField(6787617452445853994), GoldilocksField(9636045889670869002)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(15164258909151021736), GoldilocksField(7958563070574451817), GoldilocksField(1884410029356365802), GoldilocksField(3020288922730494964)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(10240792072085637728), GoldilocksField(16700514624511691876), GoldilocksField(5951894083132025534), GoldilocksField(16279327952636398561)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(17355882446209471757), GoldilocksField(904667992661609354), GoldilocksField(12044536780006585956), GoldilocksField(5602535902102011648)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(1311884331479738808), GoldilocksField(9475004387699500622), GoldilocksField(7820992926128933664), GoldilocksField(884256920940251207)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(8654457782080387707), GoldilocksField(6267082387134451220), GoldilocksField(132443630071784576), GoldilocksField(6897872509714484125)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(6762382733958885902), GoldilocksField(13452507260412049803), GoldilocksField(14211971656533799141), GoldilocksField(1897165785915519290)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(6826067814879718156), GoldilocksField(1372342199090212842), GoldilocksField(8714504702961404486), GoldilocksField(11369654883143685230)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(5911629951686986004), GoldilocksField(523175734409021431), GoldilocksField(7656407094192326277), GoldilocksField(2899796131147164883)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(14423656968060676294), GoldilocksField(17497685441123053725), GoldilocksField(9146957145732967248), GoldilocksField(722396427778282878)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(2408034308202402918), GoldilocksField(12026436354841848073), GoldilocksField(4035851124828425855), GoldilocksField(17111502111966869928)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(17357186919757295208), GoldilocksField(3040493089374338321), GoldilocksField(135538313909462538), GoldilocksField(14832651886456697342)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(18343834138110735028), GoldilocksField(6728443490333079063), GoldilocksField(11939758412020082238), GoldilocksField(15229932325069386329)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(1262443373284245801), GoldilocksField(4595808836216387538), GoldilocksField(5201266832685967102), GoldilocksField(17755637697343955442)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(607390425710007918), GoldilocksField(12396200661160840660), GoldilocksField(12856326324831679948), GoldilocksField(15761001339987578850)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(12949914530811066084), GoldilocksField(5087412403614767320), GoldilocksField(3568041606730258316), GoldilocksField(7727457339058053222)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(11255318043041226076), GoldilocksField(9264525060960278509), GoldilocksField(6203912367188559966), GoldilocksField(8190369626380868773)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(2785614324143472973), GoldilocksField(16195233175920570047), GoldilocksField(17894045942921176483), GoldilocksField(9808803347957123796)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(3479838654739406893), GoldilocksField(13344874615211363441), GoldilocksField(15230677195080745022), GoldilocksField(15383564948946386991)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(811409835264349458), GoldilocksField(6567106081593997489), GoldilocksField(15936246618930339709), GoldilocksField(2084265916811558984)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(15941000151759618266), GoldilocksField(4378624783377553486), GoldilocksField(13147172614097720073), GoldilocksField(2940701181053190308)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(5357836708881446724), GoldilocksField(7811856631424000876), GoldilocksField(13602863005104174972), GoldilocksField(15379907125490098732)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(9384743056095578106), GoldilocksField(16450915893182327953), GoldilocksField(2776058721615404266), GoldilocksField(17912680121084554918)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(2200700652699457234), GoldilocksField(7230051363875072199), GoldilocksField(8850648808925681088), GoldilocksField(14508778072592658744)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(6316954359851666680), GoldilocksField(11767024762803699411), GoldilocksField(12503873700773813154), GoldilocksField(17857385717930806080)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(6577450913058528854), GoldilocksField(10709603505572539773), GoldilocksField(2679949894253594476), GoldilocksField(2137051162022890830)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(1858492960757144477), GoldilocksField(13375625669028091584), GoldilocksField(14342801450568116546), GoldilocksField(17069977378426337147)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(15157276661922342913), GoldilocksField(13990130332127308537), GoldilocksField(3634934512800929383), GoldilocksField(2714539426039112504)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(13374056449684797057), GoldilocksField(4831200506038068967), GoldilocksField(13317082230000429316), GoldilocksField(17952467322071863478)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(13674243302968128482), GoldilocksField(17462882829816161049), GoldilocksField(5479694109907351279), GoldilocksField(13694383239732715228)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(17945660893970317359), GoldilocksField(12611426624942879833), GoldilocksField(6188958506157508977), GoldilocksField(2160004226050164577)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(1809664260332403092), GoldilocksField(4377407051030726963), GoldilocksField(7411218067165415159), GoldilocksField(15611692436937216695)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(4543998353177410712), GoldilocksField(679814635764593985), GoldilocksField(11665417490102911858), GoldilocksField(5383956922726270475)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(11610303195661630357), GoldilocksField(16020932928239611878), GoldilocksField(4326025422669196634), GoldilocksField(12970193912566375179)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(15307739491458849848), GoldilocksField(1908181964060474046), GoldilocksField(13032283130479162893), GoldilocksField(4703741356801979160)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(1081884115494360040), GoldilocksField(2018171157858491461), GoldilocksField(11004564379266252736), GoldilocksField(8833573051062566833)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(11237639611051791023), GoldilocksField(4133167721935840719), GoldilocksField(18374031809376108252), GoldilocksField(6926144268510909029)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(8254148582453295709), GoldilocksField(4773960455197516836), GoldilocksField(11454013042645515042), GoldilocksField(6543118830463178925)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(445168132246049268), GoldilocksField(12287739837703922569), GoldilocksField(11892353691954816354), GoldilocksField(10568159496280330517)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(4412750421627530731), GoldilocksField(12123657321716753684), GoldilocksField(12772420302110836583), GoldilocksField(2472206848070530603)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(7146816775182797582), GoldilocksField(14656294565821263310), GoldilocksField(6488388515566360654), GoldilocksField(16613344329201971058)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(6900729821812176317), GoldilocksField(13932599324777208756), GoldilocksField(11470043374865070005), GoldilocksField(17783918612956282669)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(1135019501809050054), GoldilocksField(5751932830345475546), GoldilocksField(18125844018958592203), GoldilocksField(15980701784467621455)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(6117093609961381980), GoldilocksField(8673421643700740367), GoldilocksField(8218779019010309342), GoldilocksField(14144113363501693965)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(16089787892172944720), GoldilocksField(141476210402457701), GoldilocksField(18412687111947828640), GoldilocksField(12192368359831963869)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(4460789618256141554), GoldilocksField(780025371947414988), GoldilocksField(15574423746805178879), GoldilocksField(1001334680033802369)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(17991319266712940436), GoldilocksField(9652788205113036110), GoldilocksField(12690069868552967646), GoldilocksField(3757442966404416710)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(14887588798987572374), GoldilocksField(15904650941754963221), GoldilocksField(4464179812546465039), GoldilocksField(4825377490134870508)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(11134444034274115498), GoldilocksField(4217237658504126508), GoldilocksField(3098287335465918509), GoldilocksField(2147714699716305039)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(6795777966812341081), GoldilocksField(16821536968713699048), GoldilocksField(2988374270256890162), GoldilocksField(1773153184584050753)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(13813462237093911577), GoldilocksField(10339883362740834837), GoldilocksField(17628828428244716853), GoldilocksField(18128404632365068584)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(11242075686944639121), GoldilocksField(15366781375515257385), GoldilocksField(4626134256311964791), GoldilocksField(9572019151793388685)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(12545876401351396010), GoldilocksField(1217903806710399559), GoldilocksField(13903411889831945369), GoldilocksField(3679217626225680775)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(9426606354234392613), GoldilocksField(3296393435858093730), GoldilocksField(16917460241901156562), GoldilocksField(3081848206179147762)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(9689524996194028895), GoldilocksField(16898040171908496571), GoldilocksField(7951134069781028007), GoldilocksField(1081867299428447397)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(8787027791402409704), GoldilocksField(9570499361915081916), GoldilocksField(8967574360685686448), GoldilocksField(9169156907884232414)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(17143232055395714424), GoldilocksField(1630620125142906643), GoldilocksField(15260094792392894370), GoldilocksField(10959228560655090634)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(1747920947512553618), GoldilocksField(16089594779453353187), GoldilocksField(2272918954760271078), GoldilocksField(7187837901268733314)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(16817992056235545307), GoldilocksField(13382826416158206799), GoldilocksField(361549298289041618), GoldilocksField(16996551013248616486)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(455789861171537909), GoldilocksField(11298344363209399249), GoldilocksField(777797354328581638), GoldilocksField(8298857793449094417)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(10369996744627025357), GoldilocksField(16880920182316565305), GoldilocksField(5925454881715007821), GoldilocksField(17310831324934037069)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(7082137473457459814), GoldilocksField(1764060050312999363), GoldilocksField(2714719293742550296), GoldilocksField(865861585932607146)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(9359951144572749558), GoldilocksField(6321562266347413744), GoldilocksField(14918693334193467691), GoldilocksField(7289347298599913343)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(4191552944918749093), GoldilocksField(15849261459156235445), GoldilocksField(15631453105801406106), GoldilocksField(15587829728988606735)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(13748179969268477888), GoldilocksField(9027803172443461945), GoldilocksField(15391664955304854126), GoldilocksField(7531565972355136744)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(2846164384103317956), GoldilocksField(5967684436971977270), GoldilocksField(669047337959826291), GoldilocksField(15086133574706620240)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(2380406122964825554), GoldilocksField(1901405705314369669), GoldilocksField(9316288499990456338), GoldilocksField(16123272125600865610)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(7973318985726895562), GoldilocksField(5832697574166769476), GoldilocksField(16945996012165066538), GoldilocksField(5590811154139214660)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(8480463681992873350), GoldilocksField(14518372836739543312), GoldilocksField(2471087608689995344), GoldilocksField(14158242834640770632)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(17694913055887667224), GoldilocksField(1071539120225434513), GoldilocksField(13786585617621821216), GoldilocksField(3576263036994572005)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(13200490677632962933), GoldilocksField(11186887122028739532), GoldilocksField(8361979431385219417), GoldilocksField(26627849216641525)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(17759088585848342665), GoldilocksField(7828119742517601651), GoldilocksField(5299452666825441845), GoldilocksField(17844506229539724284)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(18231578146636334922), GoldilocksField(232119063057124447), GoldilocksField(4269807373739100076), GoldilocksField(4928768958428198142)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(4531318536739361431), GoldilocksField(15748243683828061624), GoldilocksField(7449725576836989528), GoldilocksField(16235478293395941415)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(11689825011610980915), GoldilocksField(750506684083735588), GoldilocksField(4471644402649730176), GoldilocksField(11148316557852589175)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(18359522201540124538), GoldilocksField(6853852754865997935), GoldilocksField(16741781783118258923), GoldilocksField(5737292892996669935)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(3612416397796454524), GoldilocksField(13925746397341971394), GoldilocksField(16375593271583432543), GoldilocksField(10736496176150789849)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(14356037767670299804), GoldilocksField(6167763602354783267), GoldilocksField(16876695922722343480), GoldilocksField(7914517248737918957)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(9538733529527870677), GoldilocksField(1430739438343369430), GoldilocksField(1448521223906734118), GoldilocksField(9424643229546652133)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(2367023749936608499), GoldilocksField(2604375740157057205), GoldilocksField(2223027760765008602), GoldilocksField(1353676268307377814)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(13612794232932334447), GoldilocksField(1604858959007019354), GoldilocksField(14415767824280677187), GoldilocksField(15463885399785801387)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(3167281936805805902), GoldilocksField(945611613063110455), GoldilocksField(3893283950118129022), GoldilocksField(10044071908527518829)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(10084446561640385752), GoldilocksField(4597274428178194999), GoldilocksField(3744312645490324822), GoldilocksField(11814742174444785938)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(9369139913713831567), GoldilocksField(18338899212191937540), GoldilocksField(11643714195182412878), GoldilocksField(954094473850826613)]}),
-    QHashOut(HashOut{elements: [GoldilocksField(4232593422893852280), GoldilocksField(4957192232567996497), GoldilocksField(9340281738821162292), GoldilocksField(6465869090317965014)]}),
-
 ];
-
-*/
\ No newline at end of file
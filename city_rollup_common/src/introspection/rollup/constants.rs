@@ -13,6 +13,27 @@ pub const SIG_ACTION_WITHDRAW_MAGIC: u64 = 0x5741524448544957u64;
 // SENDDOGE (little-endian)
 pub const SIG_ACTION_TRANSFER_MAGIC: u64 = 0x45474F44444E4553u64;
 
+// CHPUBKEY (little-endian)
+pub const SIG_ACTION_CHANGE_PUBLIC_KEY_MAGIC: u64 = 0x59454B4255504843u64;
+
+// BATCHTXS (little-endian)
+pub const SIG_ACTION_BATCH_TRANSFER_MAGIC: u64 = 0x5358544843544142u64;
+
+// OPENTKAC (little-endian)
+pub const SIG_ACTION_OPEN_TOKEN_ACCOUNT_MAGIC: u64 = 0x43414B544E45504Fu64;
+
+// MINTTOKN (little-endian)
+pub const SIG_ACTION_MINT_TOKEN_MAGIC: u64 = 0x4E4B4F54544E494Du64;
+
+// SENDTOKN (little-endian)
+pub const SIG_ACTION_SEND_TOKEN_MAGIC: u64 = 0x4E4B4F54444E4553u64;
+
+// BURNTOKN (little-endian)
+pub const SIG_ACTION_BURN_TOKEN_MAGIC: u64 = 0x4E4B4F544E525542u64;
+
+// CANCELWD (little-endian)
+pub const SIG_ACTION_CANCEL_WITHDRAWAL_MAGIC: u64 = 0x44574C45434E4143u64;
+
 pub fn get_network_magic_for_str(network: String) -> anyhow::Result<u64> {
     match network.as_str() {
         "dogeregtest" => Ok(NETWORK_MAGIC_DOGE_REGTEST),
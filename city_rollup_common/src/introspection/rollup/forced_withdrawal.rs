@@ -0,0 +1,245 @@
+use city_crypto::hash::base_types::{hash160::Hash160, hash256::Hash256};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    api::data::block::requested_actions::CityAddWithdrawalRequest,
+    introspection::transaction::{BTCTransaction, BTCTransactionOutput},
+};
+
+use super::introspection_result::WITHDRAWAL_TYPE_BYTE_P2PKH;
+
+// CRFW
+pub const FORCED_WITHDRAWAL_REQUEST_MAGIC: [u8; 4] = *b"CRFW";
+
+const OP_RETURN: u8 = 0x6a;
+
+// magic (4) + user_id (8) + nonce (8) + value (8) + destination_type (1) + destination (20)
+pub const FORCED_WITHDRAWAL_REQUEST_PAYLOAD_SIZE: usize = 49;
+
+// OP_RETURN <push 49 bytes> <payload>
+pub const FORCED_WITHDRAWAL_REQUEST_SCRIPT_SIZE: usize = FORCED_WITHDRAWAL_REQUEST_PAYLOAD_SIZE + 2;
+
+/*
+A forced withdrawal request is an L1 transaction with two outputs:
+  0. a payment of at least FORCED_WITHDRAWAL_REQUEST_MIN_VALUE to the current block's deposit address
+  1. an OP_RETURN output containing the request payload
+
+The request commits to a withdrawal the user has signed on layer 2 (user_id, nonce, value, destination).
+Only the block whose deposit address is paid can spend the request. The sighash circuit of that block
+treats the request as a deposit (so the request output is credited back to the requester on layer 2) and
+requires one of the block's withdrawal outputs to pay the requested value to the requested destination.
+Block withdrawal outputs are always p2pkh, so only p2pkh destinations can be requested.
+
+This is not an escape hatch: the block can only pay the withdrawal if the operator already has the signed L2
+withdrawal, and nothing forces the block to spend the request. A request the block does not spend is stuck at
+the block's deposit address, like any other unclaimed output. A censorship resistant exit would need an
+authorization which can be checked on L1 together with either a timelocked exit path in the block script or
+a view of the deposit address outputs in the block circuit.
+*/
+#[derive(Serialize, Deserialize, PartialEq, Clone, Copy, Debug, Hash, Eq)]
+pub struct CityForcedWithdrawalRequest {
+    pub txid: Hash256,
+    pub user_id: u64,
+    pub nonce: u64,
+    pub value: u64,
+    pub destination_type: u8,
+    pub destination: Hash160,
+}
+
+impl CityForcedWithdrawalRequest {
+    pub fn to_payload(&self) -> [u8; FORCED_WITHDRAWAL_REQUEST_PAYLOAD_SIZE] {
+        let mut payload = [0u8; FORCED_WITHDRAWAL_REQUEST_PAYLOAD_SIZE];
+        payload[0..4].copy_from_slice(&FORCED_WITHDRAWAL_REQUEST_MAGIC);
+        payload[4..12].copy_from_slice(&self.user_id.to_le_bytes());
+        payload[12..20].copy_from_slice(&self.nonce.to_le_bytes());
+        payload[20..28].copy_from_slice(&self.value.to_le_bytes());
+        payload[28] = self.destination_type;
+        payload[29..49].copy_from_slice(&self.destination.0);
+        payload
+    }
+    pub fn to_op_return_script(&self) -> Vec<u8> {
+        [
+            vec![OP_RETURN, FORCED_WITHDRAWAL_REQUEST_PAYLOAD_SIZE as u8],
+            self.to_payload().to_vec(),
+        ]
+        .concat()
+    }
+    pub fn to_op_return_output(&self) -> BTCTransactionOutput {
+        BTCTransactionOutput {
+            value: 0,
+            script: self.to_op_return_script(),
+        }
+    }
+    pub fn from_op_return_script(txid: Hash256, script: &[u8]) -> Option<Self> {
+        if script.len() != FORCED_WITHDRAWAL_REQUEST_SCRIPT_SIZE
+            || script[0] != OP_RETURN
+            || script[1] != FORCED_WITHDRAWAL_REQUEST_PAYLOAD_SIZE as u8
+        {
+            return None;
+        }
+        let payload = &script[2..];
+        if payload[0..4] != FORCED_WITHDRAWAL_REQUEST_MAGIC {
+            return None;
+        }
+        let destination_type = payload[28];
+        if destination_type != WITHDRAWAL_TYPE_BYTE_P2PKH {
+            return None;
+        }
+        Some(Self {
+            txid,
+            user_id: u64::from_le_bytes(payload[4..12].try_into().unwrap()),
+            nonce: u64::from_le_bytes(payload[12..20].try_into().unwrap()),
+            value: u64::from_le_bytes(payload[20..28].try_into().unwrap()),
+            destination_type,
+            destination: Hash160(payload[29..49].try_into().unwrap()),
+        })
+    }
+    // returns None if the funding transaction is not a forced withdrawal request
+    pub fn from_funding_transaction(funding_tx: &BTCTransaction, min_value: u64) -> Option<Self> {
+        if funding_tx.inputs.len() != 1
+            || funding_tx.outputs.len() != 2
+            || funding_tx.outputs[0].value < min_value
+            || funding_tx.outputs[1].value != 0
+        {
+            return None;
+        }
+        Self::from_op_return_script(funding_tx.get_hash(), &funding_tx.outputs[1].script)
+    }
+    pub fn is_satisfied_by(&self, withdrawal: &CityAddWithdrawalRequest) -> bool {
+        self.user_id == withdrawal.user_id
+            && self.nonce == withdrawal.nonce
+            && self.value == withdrawal.value
+            && self.destination_type == withdrawal.destination_type
+            && self.destination == withdrawal.destination
+    }
+    // mirrors the check in the sighash circuit, the block must have an output paying the request
+    pub fn is_paid_by(&self, output: &BTCTransactionOutput) -> bool {
+        output.value == self.value
+            && output.script.len() == 25
+            && output.script[0..3] == [0x76, 0xa9, 0x14]
+            && output.script[3..23] == self.destination.0
+            && output.script[23..25] == [0x88, 0xac]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use city_crypto::hash::base_types::{hash160::Hash160, hash256::Hash256};
+
+    use super::{CityForcedWithdrawalRequest, FORCED_WITHDRAWAL_REQUEST_SCRIPT_SIZE};
+    use crate::introspection::{
+        rollup::{
+            introspection::BlockSpendCoreConfig, introspection_result::WITHDRAWAL_TYPE_BYTE_P2PKH,
+        },
+        transaction::{
+            BTCTransaction, BTCTransactionConfig, BTCTransactionInput, BTCTransactionOutput,
+        },
+    };
+
+    fn example_request() -> CityForcedWithdrawalRequest {
+        CityForcedWithdrawalRequest {
+            txid: Hash256([7u8; 32]),
+            user_id: 12,
+            nonce: 3,
+            value: 150000000,
+            destination_type: WITHDRAWAL_TYPE_BYTE_P2PKH,
+            destination: Hash160([0xab; 20]),
+        }
+    }
+
+    #[test]
+    fn test_forced_withdrawal_request_script_roundtrip() {
+        let request = example_request();
+        let script = request.to_op_return_script();
+        assert_eq!(script.len(), FORCED_WITHDRAWAL_REQUEST_SCRIPT_SIZE);
+        let decoded = CityForcedWithdrawalRequest::from_op_return_script(request.txid, &script);
+        assert_eq!(decoded, Some(request));
+    }
+
+    #[test]
+    fn test_forced_withdrawal_request_rejects_invalid_scripts() {
+        let request = example_request();
+        let script = request.to_op_return_script();
+
+        let mut bad_magic = script.clone();
+        bad_magic[2] = b'X';
+        assert!(
+            CityForcedWithdrawalRequest::from_op_return_script(request.txid, &bad_magic).is_none()
+        );
+
+        let mut bad_type = script.clone();
+        bad_type[2 + 28] = 7;
+        assert!(
+            CityForcedWithdrawalRequest::from_op_return_script(request.txid, &bad_type).is_none()
+        );
+
+        assert!(CityForcedWithdrawalRequest::from_op_return_script(
+            request.txid,
+            &script[0..script.len() - 1]
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn test_forced_withdrawal_request_funding_transaction() {
+        let request = example_request();
+        let funding_tx = BTCTransaction {
+            version: 2,
+            inputs: vec![BTCTransactionInput {
+                hash: Hash256([1u8; 32]),
+                index: 0,
+                script: vec![0x30; 106],
+                sequence: 0xffffffff,
+            }],
+            outputs: vec![
+                BTCTransactionOutput {
+                    value: 1000000,
+                    script: [vec![0xa9, 0x14], vec![0xcd; 20], vec![0x87]].concat(),
+                },
+                request.to_op_return_output(),
+            ],
+            locktime: 0,
+        };
+        let decoded =
+            CityForcedWithdrawalRequest::from_funding_transaction(&funding_tx, 1000000).unwrap();
+        assert_eq!(decoded.txid, funding_tx.get_hash());
+        assert_eq!(
+            CityForcedWithdrawalRequest {
+                txid: request.txid,
+                ..decoded
+            },
+            request
+        );
+        assert!(
+            CityForcedWithdrawalRequest::from_funding_transaction(&funding_tx, 1000001).is_none()
+        );
+
+        // the request is spent with the layout the sighash circuits are generated for
+        assert_eq!(
+            funding_tx.get_tx_config(),
+            BTCTransactionConfig::generate_funding_forced_withdrawal_request_tx_from_template(
+                &BlockSpendCoreConfig::standard_p2sh_p2pkh()
+            )
+        );
+    }
+
+    #[test]
+    fn test_forced_withdrawal_request_gadget_ids() {
+        let core_config = BlockSpendCoreConfig::standard_p2sh_p2pkh();
+        let configs = core_config.generate_permutations(2, 2, 1);
+        let ids = core_config.generate_id_permutations(2, 2, 1);
+        assert_eq!(configs.len(), ids.len());
+        for (config, id) in configs.iter().zip(ids.iter()) {
+            assert_eq!(config.get_gadget_config_id(), *id);
+            assert!(id.num_forced_withdrawal_requests <= id.num_deposits);
+            assert!(id.num_forced_withdrawal_requests <= id.num_withdrawals);
+        }
+
+        // ids without requests come first and match the whitelist generated without requests
+        let ids_without_requests = core_config.generate_id_permutations(2, 2, 0);
+        assert_eq!(ids[0..ids_without_requests.len()], ids_without_requests[..]);
+        assert!(ids[ids_without_requests.len()..]
+            .iter()
+            .all(|id| id.num_forced_withdrawal_requests == 1));
+    }
+}
@@ -155,6 +155,8 @@ pub struct SigHashGadgetIdWithIndex {
 }
 #[derive(Serialize, Deserialize, PartialEq, Clone, Debug, Hash, Eq, Copy, Ord, PartialOrd)]
 pub struct SigHashGadgetId {
+    // first so that ids without forced withdrawal requests keep their order in the whitelist tree
+    pub num_forced_withdrawal_requests: usize,
     pub num_deposits: usize,
     pub num_withdrawals: usize,
     pub last_block_num_deposits: usize,
@@ -163,6 +165,7 @@ pub struct SigHashGadgetId {
 }
 impl SigHashGadgetId {
     pub fn new(
+        num_forced_withdrawal_requests: usize,
         num_deposits: usize,
         num_withdrawals: usize,
         last_block_num_deposits: usize,
@@ -170,6 +173,7 @@ impl SigHashGadgetId {
         current_spend_index: usize,
     ) -> Self {
         SigHashGadgetId {
+            num_forced_withdrawal_requests,
             num_deposits,
             num_withdrawals,
             last_block_num_deposits,
@@ -177,6 +181,7 @@ impl SigHashGadgetId {
             current_spend_index,
         }
     }
+    // the from_index variants only enumerate ids without forced withdrawal requests
     pub fn from_index(
         index: usize,
         max_withdrawals: usize,
@@ -194,6 +199,7 @@ impl SigHashGadgetId {
                         for current_spend_index in 0..num_deposits {
                             if ind == index {
                                 return Ok(SigHashGadgetId {
+                                    num_forced_withdrawal_requests: 0,
                                     num_deposits,
                                     num_withdrawals,
                                     last_block_num_deposits,
@@ -259,6 +265,7 @@ impl SigHashGadgetId {
         let last_block_num_withdrawals = index_div_withdrawals / max_total_inputs;
 
         Ok(SigHashGadgetId {
+            num_forced_withdrawal_requests: 0,
             num_deposits,
             num_withdrawals,
             last_block_num_deposits,
@@ -319,6 +326,7 @@ impl SigHashGadgetId {
         let last_block_num_withdrawals = index_div_withdrawals / max_total_inputs;
 
         Ok(SigHashGadgetId {
+            num_forced_withdrawal_requests: 0,
             num_deposits,
             num_withdrawals,
             last_block_num_deposits,
@@ -332,6 +340,7 @@ impl SigHashGadgetId {
 #[serde(bound = "")]
 pub struct SigHashGadgetFingerprint<F: RichField> {
     pub fingerprint: QHashOut<F>,
+    pub num_forced_withdrawal_requests: usize,
     pub num_deposits: usize,
     pub num_withdrawals: usize,
     pub last_block_num_deposits: usize,
@@ -366,31 +375,37 @@ impl BlockSpendCoreConfig {
             version: 2,
         }
     }
+    // forced withdrawal requests are counted as deposits (they occupy the last deposit slots) and each
+    // one needs a withdrawal output to be satisfied by
     pub fn generate_permutations(
         &self,
         max_deposits: usize,
         max_withdrawals: usize,
+        max_forced_withdrawal_requests: usize,
     ) -> Vec<BlockSpendIntrospectionGadgetConfig> {
         let mut result = Vec::new();
 
         let max_total_inputs = max_deposits + 1;
         let max_total_outputs = max_withdrawals + 1;
 
-        for last_block_num_withdrawals in 0..max_total_outputs {
-            for last_block_num_deposits in 0..max_total_inputs {
-                for num_withdrawals in 0..max_total_outputs {
-                    for num_deposits in 0..max_total_inputs {
-                        for current_spend_index in 0..(num_deposits + 1) {
-                            result.push(
-                                BlockSpendIntrospectionGadgetConfig::generate_from_template(
-                                    self,
-                                    last_block_num_deposits,
-                                    last_block_num_withdrawals,
-                                    num_deposits,
-                                    num_withdrawals,
-                                    current_spend_index,
-                                ),
-                            );
+        for num_forced_withdrawal_requests in 0..(max_forced_withdrawal_requests + 1) {
+            for last_block_num_withdrawals in 0..max_total_outputs {
+                for last_block_num_deposits in 0..max_total_inputs {
+                    for num_withdrawals in num_forced_withdrawal_requests..max_total_outputs {
+                        for num_deposits in num_forced_withdrawal_requests..max_total_inputs {
+                            for current_spend_index in 0..(num_deposits + 1) {
+                                result.push(
+                                    BlockSpendIntrospectionGadgetConfig::generate_from_template(
+                                        self,
+                                        last_block_num_deposits,
+                                        last_block_num_withdrawals,
+                                        num_deposits,
+                                        num_withdrawals,
+                                        current_spend_index,
+                                        num_forced_withdrawal_requests,
+                                    ),
+                                );
+                            }
                         }
                     }
                 }
@@ -403,24 +418,28 @@ impl BlockSpendCoreConfig {
         &self,
         max_deposits: usize,
         max_withdrawals: usize,
+        max_forced_withdrawal_requests: usize,
     ) -> Vec<SigHashGadgetId> {
         let mut result = Vec::new();
 
         let max_total_inputs = max_deposits + 1;
         let max_total_outputs = max_withdrawals + 1;
 
-        for last_block_num_withdrawals in 0..max_total_outputs {
-            for last_block_num_deposits in 0..max_total_inputs {
-                for num_withdrawals in 0..max_total_outputs {
-                    for num_deposits in 0..max_total_inputs {
-                        for current_spend_index in 0..(num_deposits + 1) {
-                            result.push(SigHashGadgetId {
-                                last_block_num_deposits,
-                                last_block_num_withdrawals,
-                                num_deposits,
-                                num_withdrawals,
-                                current_spend_index,
-                            });
+        for num_forced_withdrawal_requests in 0..(max_forced_withdrawal_requests + 1) {
+            for last_block_num_withdrawals in 0..max_total_outputs {
+                for last_block_num_deposits in 0..max_total_inputs {
+                    for num_withdrawals in num_forced_withdrawal_requests..max_total_outputs {
+                        for num_deposits in num_forced_withdrawal_requests..max_total_inputs {
+                            for current_spend_index in 0..(num_deposits + 1) {
+                                result.push(SigHashGadgetId {
+                                    num_forced_withdrawal_requests,
+                                    last_block_num_deposits,
+                                    last_block_num_withdrawals,
+                                    num_deposits,
+                                    num_withdrawals,
+                                    current_spend_index,
+                                });
+                            }
                         }
                     }
                 }
@@ -460,6 +479,7 @@ impl BlockSpendIntrospectionGadgetConfig {
         num_deposits: usize,
         num_withdrawals: usize,
         current_spend_index: usize,
+        num_forced_withdrawal_requests: usize,
     ) -> Self {
         let sighash_preimage_config = SigHashPreimageConfig::generate_from_template(
             config,
@@ -475,6 +495,10 @@ impl BlockSpendIntrospectionGadgetConfig {
                         last_block_num_deposits,
                         last_block_num_withdrawals,
                     )
+                } else if i + num_forced_withdrawal_requests > num_deposits {
+                    BTCTransactionConfig::generate_funding_forced_withdrawal_request_tx_from_template(
+                        config,
+                    )
                 } else {
                     BTCTransactionConfig::generate_funding_deposit_tx_from_template(config)
                 }
@@ -500,6 +524,14 @@ impl BlockSpendIntrospectionGadgetConfig {
             panic!("last_block_spend_index must be non-negative");
         }
         SigHashGadgetId {
+            num_forced_withdrawal_requests: self
+                .funding_transaction_configs
+                .iter()
+                .enumerate()
+                .filter(|(i, tx)| {
+                    *i != self.block_spend_index && tx.layout.output_script_sizes.len() == 2
+                })
+                .count(),
             last_block_num_deposits: self.funding_transaction_configs[self.block_spend_index]
                 .layout
                 .input_script_sizes
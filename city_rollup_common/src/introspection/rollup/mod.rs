@@ -1,4 +1,5 @@
 pub mod constants;
+pub mod forced_withdrawal;
 pub mod introspection;
 pub mod introspection_result;
 pub mod signature;
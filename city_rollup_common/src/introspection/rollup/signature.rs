@@ -1,3 +1,4 @@
+use city_common::config::rollup_constants::L2_BATCH_TRANSFER_MAX_RECIPIENTS;
 use city_crypto::{
     hash::{
         base_types::{hash160::Hash160, hash256::Hash256},
@@ -15,7 +16,11 @@ use serde_with::serde_as;
 
 use super::{
     constants::{
-        SIG_ACTION_CLAIM_DEPOSIT_MAGIC, SIG_ACTION_TRANSFER_MAGIC, SIG_ACTION_WITHDRAW_MAGIC,
+        SIG_ACTION_BATCH_TRANSFER_MAGIC, SIG_ACTION_BURN_TOKEN_MAGIC,
+        SIG_ACTION_CANCEL_WITHDRAWAL_MAGIC, SIG_ACTION_CHANGE_PUBLIC_KEY_MAGIC,
+        SIG_ACTION_CLAIM_DEPOSIT_MAGIC, SIG_ACTION_MINT_TOKEN_MAGIC,
+        SIG_ACTION_OPEN_TOKEN_ACCOUNT_MAGIC, SIG_ACTION_SEND_TOKEN_MAGIC,
+        SIG_ACTION_TRANSFER_MAGIC, SIG_ACTION_WITHDRAW_MAGIC,
     },
     introspection_result::BTCRollupIntrospectionResultWithdrawal,
 };
@@ -111,6 +116,7 @@ impl<F: RichField> QEDSigAction<F> {
         nonce: u64,
         recipient: u64,
         amount: u64,
+        fee: u64,
     ) -> Self {
         let network_magic = F::from_canonical_u64(network_magic);
         let nonce = F::from_canonical_u64(nonce);
@@ -119,7 +125,11 @@ impl<F: RichField> QEDSigAction<F> {
             network_magic,
             sig_action: F::from_canonical_u64(SIG_ACTION_TRANSFER_MAGIC),
             nonce,
-            action_arguments: vec![recipient, F::from_noncanonical_u64(amount)],
+            action_arguments: vec![
+                recipient,
+                F::from_noncanonical_u64(amount),
+                F::from_noncanonical_u64(fee),
+            ],
             user: F::from_noncanonical_u64(user),
         }
     }
@@ -131,6 +141,7 @@ impl<F: RichField> QEDSigAction<F> {
         address_type_flag: u8,
         amount: u64,
         withdrawal_fee: u64,
+        fee: u64,
     ) -> Self {
         let withdrawal_hash =
             BTCRollupIntrospectionResultWithdrawal::<F>::hash_from_public_key_hash(
@@ -151,6 +162,156 @@ impl<F: RichField> QEDSigAction<F> {
                 withdrawal_hash.0.elements[2],
                 withdrawal_hash.0.elements[3],
                 F::from_noncanonical_u64(withdrawal_fee),
+                F::from_noncanonical_u64(fee),
+            ],
+            user: F::from_noncanonical_u64(user),
+        }
+    }
+    pub fn new_change_public_key_action(
+        network_magic: u64,
+        user: u64,
+        nonce: u64,
+        new_public_key: QHashOut<F>,
+    ) -> Self {
+        let network_magic = F::from_canonical_u64(network_magic);
+        let nonce = F::from_canonical_u64(nonce);
+        Self {
+            network_magic,
+            sig_action: F::from_canonical_u64(SIG_ACTION_CHANGE_PUBLIC_KEY_MAGIC),
+            nonce,
+            action_arguments: new_public_key.0.elements.to_vec(),
+            user: F::from_noncanonical_u64(user),
+        }
+    }
+    // the withdrawal hash is signed along with the id so a reused id cannot cancel a different withdrawal
+    pub fn new_cancel_withdrawal_action(
+        network_magic: u64,
+        user: u64,
+        nonce: u64,
+        withdrawal_id: u64,
+        withdrawal_hash: QHashOut<F>,
+    ) -> Self {
+        let network_magic = F::from_canonical_u64(network_magic);
+        let nonce = F::from_canonical_u64(nonce);
+        let mut action_arguments = vec![F::from_canonical_u64(withdrawal_id)];
+        action_arguments.extend_from_slice(&withdrawal_hash.0.elements);
+        Self {
+            network_magic,
+            sig_action: F::from_canonical_u64(SIG_ACTION_CANCEL_WITHDRAWAL_MAGIC),
+            nonce,
+            action_arguments,
+            user: F::from_noncanonical_u64(user),
+        }
+    }
+    // arguments: [fee, recipient_0, amount_0, ..., recipient_n, amount_n]
+    // the list is padded with (0, 0) pairs up to L2_BATCH_TRANSFER_MAX_RECIPIENTS
+    pub fn new_batch_transfer_action(
+        network_magic: u64,
+        user: u64,
+        nonce: u64,
+        transfers: &[(u64, u64)],
+        fee: u64,
+    ) -> anyhow::Result<Self> {
+        if transfers.len() == 0 || transfers.len() > L2_BATCH_TRANSFER_MAX_RECIPIENTS {
+            anyhow::bail!(
+                "a batch transfer must have between 1 and {} recipients, got {}",
+                L2_BATCH_TRANSFER_MAX_RECIPIENTS,
+                transfers.len()
+            );
+        }
+        let network_magic = F::from_canonical_u64(network_magic);
+        let nonce = F::from_canonical_u64(nonce);
+        let mut action_arguments = Vec::with_capacity(1 + L2_BATCH_TRANSFER_MAX_RECIPIENTS * 2);
+        action_arguments.push(F::from_noncanonical_u64(fee));
+        for i in 0..L2_BATCH_TRANSFER_MAX_RECIPIENTS {
+            let (recipient, amount) = transfers.get(i).copied().unwrap_or((0, 0));
+            action_arguments.push(F::from_canonical_u64(recipient));
+            action_arguments.push(F::from_noncanonical_u64(amount));
+        }
+        Ok(Self {
+            network_magic,
+            sig_action: F::from_canonical_u64(SIG_ACTION_BATCH_TRANSFER_MAGIC),
+            nonce,
+            action_arguments,
+            user: F::from_noncanonical_u64(user),
+        })
+    }
+    // opening a token account for token_id == user also registers the token with the user as its issuer
+    pub fn new_open_token_account_action(
+        network_magic: u64,
+        user: u64,
+        nonce: u64,
+        token_id: u64,
+    ) -> Self {
+        let network_magic = F::from_canonical_u64(network_magic);
+        let nonce = F::from_canonical_u64(nonce);
+        Self {
+            network_magic,
+            sig_action: F::from_canonical_u64(SIG_ACTION_OPEN_TOKEN_ACCOUNT_MAGIC),
+            nonce,
+            action_arguments: vec![F::from_canonical_u64(token_id)],
+            user: F::from_noncanonical_u64(user),
+        }
+    }
+    // signed by the issuer account, the token id is the issuer's user id
+    pub fn new_mint_token_action(
+        network_magic: u64,
+        user: u64,
+        nonce: u64,
+        recipient: u64,
+        amount: u64,
+    ) -> Self {
+        let network_magic = F::from_canonical_u64(network_magic);
+        let nonce = F::from_canonical_u64(nonce);
+        Self {
+            network_magic,
+            sig_action: F::from_canonical_u64(SIG_ACTION_MINT_TOKEN_MAGIC),
+            nonce,
+            action_arguments: vec![
+                F::from_canonical_u64(recipient),
+                F::from_noncanonical_u64(amount),
+            ],
+            user: F::from_noncanonical_u64(user),
+        }
+    }
+    pub fn new_send_token_action(
+        network_magic: u64,
+        user: u64,
+        nonce: u64,
+        token_id: u64,
+        recipient: u64,
+        amount: u64,
+    ) -> Self {
+        let network_magic = F::from_canonical_u64(network_magic);
+        let nonce = F::from_canonical_u64(nonce);
+        Self {
+            network_magic,
+            sig_action: F::from_canonical_u64(SIG_ACTION_SEND_TOKEN_MAGIC),
+            nonce,
+            action_arguments: vec![
+                F::from_canonical_u64(token_id),
+                F::from_canonical_u64(recipient),
+                F::from_noncanonical_u64(amount),
+            ],
+            user: F::from_noncanonical_u64(user),
+        }
+    }
+    pub fn new_burn_token_action(
+        network_magic: u64,
+        user: u64,
+        nonce: u64,
+        token_id: u64,
+        amount: u64,
+    ) -> Self {
+        let network_magic = F::from_canonical_u64(network_magic);
+        let nonce = F::from_canonical_u64(nonce);
+        Self {
+            network_magic,
+            sig_action: F::from_canonical_u64(SIG_ACTION_BURN_TOKEN_MAGIC),
+            nonce,
+            action_arguments: vec![
+                F::from_canonical_u64(token_id),
+                F::from_noncanonical_u64(amount),
             ],
             user: F::from_noncanonical_u64(user),
         }
@@ -9,6 +9,7 @@ use serde::{Deserialize, Serialize};
 use crate::block_template::BLOCK_SCRIPT_LENGTH;
 use crate::link::data::{AddressToBTCScript, BTCAddress160};
 
+use super::rollup::forced_withdrawal::FORCED_WITHDRAWAL_REQUEST_SCRIPT_SIZE;
 use super::rollup::introspection::BlockSpendCoreConfig;
 use super::sighash::SigHashPreimage;
 use super::size::BTCTransactionLayout;
@@ -90,6 +91,21 @@ impl BTCTransactionConfig {
             version: config.version,
         }
     }
+    pub fn generate_funding_forced_withdrawal_request_tx_from_template(
+        config: &BlockSpendCoreConfig,
+    ) -> Self {
+        Self {
+            layout: BTCTransactionLayout {
+                input_script_sizes: vec![config.deposit_funding_script_size],
+                output_script_sizes: vec![
+                    config.block_output_script_size,
+                    FORCED_WITHDRAWAL_REQUEST_SCRIPT_SIZE,
+                ],
+            },
+            locktime: config.locktime,
+            version: config.version,
+        }
+    }
 }
 
 #[serde_as]
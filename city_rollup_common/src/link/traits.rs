@@ -4,8 +4,9 @@ use city_crypto::{
     signature::secp256k1::wallet::{MemorySecp256K1Wallet, Secp256K1WalletProvider},
 };
 
-use crate::introspection::transaction::{
-    BTCTransaction, BTCTransactionInputWithoutScript, BTCTransactionOutput,
+use crate::introspection::{
+    rollup::forced_withdrawal::CityForcedWithdrawalRequest,
+    transaction::{BTCTransaction, BTCTransactionInputWithoutScript, BTCTransactionOutput},
 };
 
 use super::{
@@ -85,6 +86,41 @@ pub trait QBitcoinAPIFunderSync: QBitcoinAPISync {
         )?;
         self.send_transaction(&tx)
     }
+    // the request's txid is ignored, it is the id of the transaction which is sent
+    fn fund_forced_withdrawal_request<W: Secp256K1WalletProvider>(
+        &self,
+        wallet: &W,
+        from: BTCAddress160,
+        deposit_address: BTCAddress160,
+        request: &CityForcedWithdrawalRequest,
+        amount: u64,
+    ) -> anyhow::Result<Hash256> {
+        let fund_txid_0 = self.fund_address(from, amount + UNIT_BTC * 2)?;
+        let full_tx = self.get_transaction(fund_txid_0)?;
+        let vout = full_tx
+            .get_vouts_for_address(&from)
+            .first()
+            .unwrap()
+            .to_owned();
+
+        let tx = create_p2pkh_tx(
+            wallet,
+            from.address,
+            &[BTCTransactionInputWithoutScript {
+                hash: fund_txid_0.reversed(),
+                sequence: 0xffff_ffff,
+                index: vout,
+            }],
+            vec![
+                BTCTransactionOutput {
+                    value: amount,
+                    script: deposit_address.to_btc_script(),
+                },
+                request.to_op_return_output(),
+            ],
+        )?;
+        self.send_transaction(&tx)
+    }
     fn mine_blocks(&self, count: u32) -> anyhow::Result<Vec<Hash256>>;
     fn mine_blocks_to_address(&self, count: u32, address: BTCAddress160) -> anyhow::Result<()>;
 }
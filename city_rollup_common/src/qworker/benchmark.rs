@@ -0,0 +1,318 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    dump::dump_job_dependencies_from_store,
+    job_id::{ProvingJobCircuitType, QProvingJobDataID, QWorkerJobBenchmark},
+    proof_store::QProofStoreReaderSync,
+    status::get_recorded_duration,
+};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy, Eq)]
+pub struct QCircuitBenchmarkSummary {
+    pub circuit_type: ProvingJobCircuitType,
+    pub samples: u32,
+    // all durations are in milliseconds
+    pub min: u64,
+    pub p50: u64,
+    pub p95: u64,
+    pub max: u64,
+    pub mean: u64,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Eq)]
+pub struct QBenchmarkReport {
+    pub checkpoint_ids: Vec<u64>,
+    pub circuits: Vec<QCircuitBenchmarkSummary>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct QCircuitBenchmarkComparison {
+    pub circuit_type: ProvingJobCircuitType,
+    pub baseline_p50: u64,
+    pub current_p50: u64,
+    pub p50_change_percent: f64,
+    pub baseline_p95: u64,
+    pub current_p95: u64,
+    pub p95_change_percent: f64,
+    pub is_regression: bool,
+}
+
+// nearest-rank percentile of an already sorted list
+pub fn get_percentile(sorted_values: &[u64], percentile: u32) -> u64 {
+    if sorted_values.is_empty() {
+        return 0;
+    }
+    let rank = ((percentile as usize) * sorted_values.len() + 99) / 100;
+    sorted_values[rank.clamp(1, sorted_values.len()) - 1]
+}
+
+// reads the durations workers recorded next to each proof of the block, an empty list if no jobs
+// were planned for the checkpoint
+pub fn get_block_job_benchmarks<PS: QProofStoreReaderSync>(
+    store: &PS,
+    checkpoint_id: u64,
+) -> anyhow::Result<Vec<QWorkerJobBenchmark>> {
+    let leaf_jobs_bytes = match store
+        .get_bytes_by_id_if_exists(QProvingJobDataID::block_leaf_jobs_list(checkpoint_id))?
+    {
+        Some(bytes) if !bytes.is_empty() => bytes,
+        _ => return Ok(vec![]),
+    };
+    let leaf_jobs: Vec<QProvingJobDataID> = bincode::deserialize(&leaf_jobs_bytes)?;
+    let dependency_map = dump_job_dependencies_from_store(store, &leaf_jobs)?;
+    let mut benchmarks = vec![];
+    for job in dependency_map
+        .get_dependency_tree_for_block(checkpoint_id)
+        .to_job_id_list()
+        .into_iter()
+        .unique()
+    {
+        if let Some(duration) = get_recorded_duration(store, job)? {
+            benchmarks.push(QWorkerJobBenchmark {
+                job_id: job.to_fixed_bytes(),
+                duration,
+            });
+        }
+    }
+    Ok(benchmarks)
+}
+
+pub fn summarize_job_benchmarks(
+    benchmarks: &[QWorkerJobBenchmark],
+    checkpoint_range: Option<(u64, u64)>,
+) -> anyhow::Result<QBenchmarkReport> {
+    let mut durations: HashMap<ProvingJobCircuitType, Vec<u64>> = HashMap::new();
+    let mut checkpoint_ids = vec![];
+    for benchmark in benchmarks.iter() {
+        let job_id = QProvingJobDataID::try_from(benchmark.job_id)?;
+        if let Some((start, end)) = checkpoint_range {
+            if job_id.goal_id < start || job_id.goal_id > end {
+                continue;
+            }
+        }
+        checkpoint_ids.push(job_id.goal_id);
+        durations
+            .entry(job_id.circuit_type)
+            .or_default()
+            .push(benchmark.duration);
+    }
+    let circuits = durations
+        .into_iter()
+        .map(|(circuit_type, mut samples)| {
+            samples.sort();
+            QCircuitBenchmarkSummary {
+                circuit_type,
+                samples: samples.len() as u32,
+                min: samples[0],
+                p50: get_percentile(&samples, 50),
+                p95: get_percentile(&samples, 95),
+                max: samples[samples.len() - 1],
+                mean: samples.iter().sum::<u64>() / (samples.len() as u64),
+            }
+        })
+        .sorted_by_key(|x| x.circuit_type)
+        .collect();
+    Ok(QBenchmarkReport {
+        checkpoint_ids: checkpoint_ids.into_iter().sorted().dedup().collect(),
+        circuits,
+    })
+}
+
+fn get_change_percent(baseline: u64, current: u64) -> f64 {
+    if baseline == 0 {
+        0.0
+    } else {
+        100.0 * ((current as f64) - (baseline as f64)) / (baseline as f64)
+    }
+}
+
+// only circuits present in both reports are compared
+pub fn compare_benchmark_reports(
+    baseline: &QBenchmarkReport,
+    current: &QBenchmarkReport,
+    regression_threshold_percent: f64,
+) -> Vec<QCircuitBenchmarkComparison> {
+    current
+        .circuits
+        .iter()
+        .filter_map(|current| {
+            let baseline = baseline
+                .circuits
+                .iter()
+                .find(|x| x.circuit_type == current.circuit_type)?;
+            let p50_change_percent = get_change_percent(baseline.p50, current.p50);
+            let p95_change_percent = get_change_percent(baseline.p95, current.p95);
+            Some(QCircuitBenchmarkComparison {
+                circuit_type: current.circuit_type,
+                baseline_p50: baseline.p50,
+                current_p50: current.p50,
+                p50_change_percent,
+                baseline_p95: baseline.p95,
+                current_p95: current.p95,
+                p95_change_percent,
+                is_regression: p50_change_percent > regression_threshold_percent
+                    || p95_change_percent > regression_threshold_percent,
+            })
+        })
+        .collect()
+}
+
+pub fn benchmark_report_to_csv(report: &QBenchmarkReport) -> String {
+    let mut csv = "circuit_type,samples,min,p50,p95,max,mean\n".to_string();
+    for summary in report.circuits.iter() {
+        csv.push_str(&format!(
+            "{:?},{},{},{},{},{},{}\n",
+            summary.circuit_type,
+            summary.samples,
+            summary.min,
+            summary.p50,
+            summary.p95,
+            summary.max,
+            summary.mean
+        ));
+    }
+    csv
+}
+
+pub fn benchmark_comparison_to_csv(comparison: &[QCircuitBenchmarkComparison]) -> String {
+    let mut csv = "circuit_type,baseline_p50,current_p50,p50_change_percent,baseline_p95,current_p95,p95_change_percent,is_regression\n".to_string();
+    for row in comparison.iter() {
+        csv.push_str(&format!(
+            "{:?},{},{},{:.2},{},{},{:.2},{}\n",
+            row.circuit_type,
+            row.baseline_p50,
+            row.current_p50,
+            row.p50_change_percent,
+            row.baseline_p95,
+            row.current_p95,
+            row.p95_change_percent,
+            row.is_regression
+        ));
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        compare_benchmark_reports, get_block_job_benchmarks, get_percentile,
+        summarize_job_benchmarks,
+    };
+    use crate::qworker::{
+        job_id::{ProvingJobCircuitType, QProvingJobDataID, QWorkerJobBenchmark},
+        memory_proof_store::SimpleProofStoreMemory,
+        proof_store::QProofStoreWriterSync,
+    };
+
+    fn bench(
+        circuit_type: ProvingJobCircuitType,
+        checkpoint_id: u64,
+        duration: u64,
+    ) -> QWorkerJobBenchmark {
+        let job_id =
+            QProvingJobDataID::core_op_witness(circuit_type, checkpoint_id, duration as u32);
+        QWorkerJobBenchmark {
+            job_id: job_id.to_fixed_bytes(),
+            duration,
+        }
+    }
+
+    #[test]
+    fn test_benchmark_report() {
+        assert_eq!(get_percentile(&[], 50), 0);
+        assert_eq!(get_percentile(&[7], 95), 7);
+        let values = (1..=20).collect::<Vec<u64>>();
+        assert_eq!(get_percentile(&values, 50), 10);
+        assert_eq!(get_percentile(&values, 95), 19);
+
+        let baseline_benchmarks = (1..=20)
+            .map(|i| bench(ProvingJobCircuitType::TransferTokensL2, 2, i * 10))
+            .chain([bench(ProvingJobCircuitType::RegisterUser, 3, 100)])
+            .collect::<Vec<_>>();
+        let baseline = summarize_job_benchmarks(&baseline_benchmarks, None).unwrap();
+        assert_eq!(baseline.checkpoint_ids, vec![2, 3]);
+        assert_eq!(baseline.circuits.len(), 2);
+        assert_eq!(
+            baseline.circuits[0].circuit_type,
+            ProvingJobCircuitType::RegisterUser
+        );
+        let transfer = baseline.circuits[1];
+        assert_eq!(transfer.samples, 20);
+        assert_eq!((transfer.min, transfer.max, transfer.mean), (10, 200, 105));
+        assert_eq!((transfer.p50, transfer.p95), (100, 190));
+
+        let only_checkpoint_3 =
+            summarize_job_benchmarks(&baseline_benchmarks, Some((3, 3))).unwrap();
+        assert_eq!(only_checkpoint_3.circuits.len(), 1);
+
+        let current_benchmarks = [
+            bench(ProvingJobCircuitType::TransferTokensL2, 4, 100),
+            bench(ProvingJobCircuitType::RegisterUser, 4, 150),
+        ];
+        let current = summarize_job_benchmarks(&current_benchmarks, None).unwrap();
+        let comparison = compare_benchmark_reports(&baseline, &current, 10.0);
+        assert_eq!(comparison.len(), 2);
+        assert!(comparison[0].is_regression);
+        assert_eq!(comparison[0].p50_change_percent, 50.0);
+        assert!(!comparison[1].is_regression);
+        assert_eq!(comparison[1].p50_change_percent, 0.0);
+    }
+
+    #[test]
+    fn test_block_job_benchmarks() {
+        let checkpoint_id = 2;
+        let mut store = SimpleProofStoreMemory::new();
+        assert!(get_block_job_benchmarks(&store, checkpoint_id)
+            .unwrap()
+            .is_empty());
+
+        let leaves = (0..2)
+            .map(|i| {
+                QProvingJobDataID::core_op_witness(
+                    ProvingJobCircuitType::TransferTokensL2,
+                    checkpoint_id,
+                    i,
+                )
+            })
+            .collect::<Vec<_>>();
+        let agg = QProvingJobDataID::get_block_aggregate_jobs_group(checkpoint_id, 1, 0);
+        store.write_next_jobs(&leaves, &[agg]).unwrap();
+        store
+            .write_next_jobs(
+                &[agg],
+                &[QProvingJobDataID::notify_block_complete(checkpoint_id)],
+            )
+            .unwrap();
+        store
+            .set_bytes_by_id(
+                QProvingJobDataID::block_leaf_jobs_list(checkpoint_id),
+                &bincode::serialize(&leaves).unwrap(),
+            )
+            .unwrap();
+        store
+            .set_bytes_by_id(leaves[1].get_benchmark_id(), &100u64.to_le_bytes())
+            .unwrap();
+        store
+            .set_bytes_by_id(agg.get_benchmark_id(), &250u64.to_le_bytes())
+            .unwrap();
+
+        let mut benchmarks = get_block_job_benchmarks(&store, checkpoint_id).unwrap();
+        benchmarks.sort_by_key(|x| x.duration);
+        assert_eq!(
+            benchmarks,
+            vec![
+                QWorkerJobBenchmark {
+                    job_id: leaves[1].to_fixed_bytes(),
+                    duration: 100,
+                },
+                QWorkerJobBenchmark {
+                    job_id: agg.to_fixed_bytes(),
+                    duration: 250,
+                },
+            ]
+        );
+    }
+}
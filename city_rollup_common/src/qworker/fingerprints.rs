@@ -6,6 +6,9 @@ use serde::{Deserialize, Serialize};
 #[serde(bound = "")]
 pub struct CRWorkerToolboxCoreCircuitFingerprints<F: RichField> {
     pub network_magic: u64,
+    // user credited with the sequencer fees, manifests made before it was configurable used user 0
+    #[serde(default)]
+    pub fee_recipient_user_id: u64,
 
     pub zk_signature_wrapper: QHashOut<F>,
     pub l1_secp256k1_signature: QHashOut<F>,
@@ -15,6 +18,21 @@ pub struct CRWorkerToolboxCoreCircuitFingerprints<F: RichField> {
     pub op_claim_l1_deposit: TPCircuitFingerprintConfig<F>,
     pub op_l2_transfer: TPCircuitFingerprintConfig<F>,
     pub op_add_l1_withdrawal: TPCircuitFingerprintConfig<F>,
+    // defaults to zero so fingerprint dumps made before public key rotation still load
+    #[serde(default)]
+    pub op_change_public_key: TPCircuitFingerprintConfig<F>,
+    #[serde(default)]
+    pub op_batch_l2_transfer: TPCircuitFingerprintConfig<F>,
+    #[serde(default)]
+    pub op_open_token_account: TPCircuitFingerprintConfig<F>,
+    #[serde(default)]
+    pub op_mint_token: TPCircuitFingerprintConfig<F>,
+    #[serde(default)]
+    pub op_send_token: TPCircuitFingerprintConfig<F>,
+    #[serde(default)]
+    pub op_burn_token: TPCircuitFingerprintConfig<F>,
+    #[serde(default)]
+    pub op_cancel_l1_withdrawal: TPCircuitFingerprintConfig<F>,
 
     // state transition with events operations
     pub op_add_l1_deposit: TPCircuitFingerprintConfig<F>,
@@ -36,3 +54,131 @@ pub struct CRWorkerToolboxRootCircuitFingerprints<F: RichField> {
     pub block_agg_add_process_withdrawal_add_deposit: QHashOut<F>,
     pub block_state_transition: QHashOut<F>,
 }
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CircuitFingerprintMismatch {
+    pub circuit: String,
+    pub expected: serde_json::Value,
+    pub actual: serde_json::Value,
+}
+
+impl<F: RichField> CRWorkerToolboxCoreCircuitFingerprints<F> {
+    pub fn from_manifest_file(path: &str) -> anyhow::Result<Self> {
+        let data = std::fs::read(path).map_err(|err| {
+            anyhow::format_err!(
+                "failed to read circuit fingerprint manifest {}: {} (generate it with `city-rollup-cli gen-fingerprint-manifest`)",
+                path,
+                err
+            )
+        })?;
+        Ok(serde_json::from_slice(&data)?)
+    }
+    pub fn write_manifest_file(&self, path: &str) -> anyhow::Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+    // lists the circuits whose fingerprints differ, self being the expected (manifest) side
+    pub fn diff(&self, actual: &Self) -> anyhow::Result<Vec<CircuitFingerprintMismatch>> {
+        let expected = serde_json::to_value(self)?;
+        let actual = serde_json::to_value(actual)?;
+        let (expected, actual) = match (expected, actual) {
+            (serde_json::Value::Object(expected), serde_json::Value::Object(actual)) => {
+                (expected, actual)
+            }
+            _ => anyhow::bail!("circuit fingerprints should serialize to a json object"),
+        };
+        Ok(expected
+            .into_iter()
+            .filter_map(|(circuit, expected)| {
+                let actual = actual
+                    .get(&circuit)
+                    .cloned()
+                    .unwrap_or(serde_json::Value::Null);
+                if expected == actual {
+                    None
+                } else {
+                    Some(CircuitFingerprintMismatch {
+                        circuit,
+                        expected,
+                        actual,
+                    })
+                }
+            })
+            .collect())
+    }
+    // a manifest generated for another network describes circuits with another network magic
+    pub fn ensure_network_magic(&self, network: &str, network_magic: u64) -> anyhow::Result<()> {
+        if self.network_magic != network_magic {
+            anyhow::bail!(
+                "circuit fingerprint manifest has network magic {} but network {} has network magic {}, regenerate it with `city-rollup-cli gen-fingerprint-manifest --network {}`",
+                self.network_magic,
+                network,
+                network_magic,
+                network
+            );
+        }
+        Ok(())
+    }
+    pub fn ensure_matches_manifest(&self, manifest: &Self) -> anyhow::Result<()> {
+        let mismatches = manifest.diff(self)?;
+        if mismatches.is_empty() {
+            return Ok(());
+        }
+        let details = mismatches
+            .iter()
+            .map(|x| {
+                format!(
+                    "  {}:\n    manifest: {}\n    computed: {}",
+                    x.circuit, x.expected, x.actual
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        anyhow::bail!(
+            "computed circuit fingerprints do not match the manifest ({} mismatching circuits):\n{}",
+            mismatches.len(),
+            details
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use city_crypto::hash::qhashout::QHashOut;
+    use plonky2::field::goldilocks_field::GoldilocksField;
+
+    use super::CRWorkerToolboxCoreCircuitFingerprints;
+
+    type F = GoldilocksField;
+
+    #[test]
+    fn test_fingerprint_manifest_diff() {
+        let manifest = CRWorkerToolboxCoreCircuitFingerprints::<F> {
+            network_magic: 1,
+            ..Default::default()
+        };
+        assert!(manifest.ensure_matches_manifest(&manifest).is_ok());
+
+        let mut computed = manifest;
+        computed.zk_signature_wrapper = QHashOut::from_values(1, 2, 3, 4);
+        computed.op_l2_transfer.leaf_fingerprint = QHashOut::from_values(5, 6, 7, 8);
+        let mismatches = manifest.diff(&computed).unwrap();
+        let mut circuits = mismatches
+            .iter()
+            .map(|x| x.circuit.as_str())
+            .collect::<Vec<_>>();
+        circuits.sort();
+        assert_eq!(circuits, vec!["op_l2_transfer", "zk_signature_wrapper"]);
+        assert!(computed.ensure_matches_manifest(&manifest).is_err());
+    }
+
+    #[test]
+    fn test_fingerprint_manifest_network_magic() {
+        let manifest = CRWorkerToolboxCoreCircuitFingerprints::<F> {
+            network_magic: 1,
+            ..Default::default()
+        };
+        assert!(manifest.ensure_network_magic("dogeregtest", 1).is_ok());
+        assert!(manifest.ensure_network_magic("dogetestnet", 2).is_err());
+    }
+}
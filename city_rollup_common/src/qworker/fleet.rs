@@ -0,0 +1,396 @@
+use std::{
+    collections::HashMap,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use city_common::cli::modes::QWorkerMode;
+use plonky2::field::goldilocks_field::GoldilocksField;
+use serde::{Deserialize, Serialize};
+
+use crate::actors::traits::WorkerEventTransmitterSync;
+
+use super::{
+    fingerprints::CRWorkerToolboxCoreCircuitFingerprints,
+    job_id::{QProvingJobDataID, QProvingJobDataIDSerializedWrapped},
+    proof_store::QProofStoreReaderSync,
+};
+
+// a worker is considered dead if it misses this many heartbeats in a row
+pub const WORKER_HEARTBEAT_TIMEOUT_INTERVALS: u64 = 4;
+
+pub fn get_unix_timestamp_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|x| x.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Eq)]
+pub struct QWorkerInfo {
+    pub worker_id: String,
+    pub mode: QWorkerMode,
+    pub hostname: String,
+    pub pid: u32,
+    // all timestamps are unix time in milliseconds
+    pub started_at: u64,
+    pub last_heartbeat: u64,
+    pub heartbeat_interval: u64,
+    pub current_job: Option<QProvingJobDataIDSerializedWrapped>,
+    pub current_job_started_at: Option<u64>,
+    pub jobs_completed: u64,
+    // total time spent processing jobs (ms)
+    pub busy_time: u64,
+    // the fingerprints of the circuits the worker built, so mismatched deployments are visible in the registry
+    #[serde(default)]
+    pub circuit_fingerprints: Option<CRWorkerToolboxCoreCircuitFingerprints<GoldilocksField>>,
+}
+
+impl QWorkerInfo {
+    pub fn new(
+        worker_id: String,
+        mode: QWorkerMode,
+        hostname: String,
+        pid: u32,
+        heartbeat_interval: u64,
+    ) -> Self {
+        let now = get_unix_timestamp_ms();
+        Self {
+            worker_id,
+            mode,
+            hostname,
+            pid,
+            started_at: now,
+            last_heartbeat: now,
+            heartbeat_interval,
+            current_job: None,
+            current_job_started_at: None,
+            jobs_completed: 0,
+            busy_time: 0,
+            circuit_fingerprints: None,
+        }
+    }
+    pub fn with_circuit_fingerprints(
+        mut self,
+        fingerprints: CRWorkerToolboxCoreCircuitFingerprints<GoldilocksField>,
+    ) -> Self {
+        self.circuit_fingerprints = Some(fingerprints);
+        self
+    }
+    pub fn start_job(&mut self, job: QProvingJobDataID, now: u64) {
+        self.current_job = Some(QProvingJobDataIDSerializedWrapped(job.to_fixed_bytes()));
+        self.current_job_started_at = Some(now);
+    }
+    pub fn finish_job(&mut self, now: u64) {
+        if let Some(started_at) = self.current_job_started_at.take() {
+            self.busy_time += now.saturating_sub(started_at);
+            self.jobs_completed += 1;
+        }
+        self.current_job = None;
+    }
+    pub fn get_current_job(&self) -> anyhow::Result<Option<QProvingJobDataID>> {
+        self.current_job
+            .map(|job| QProvingJobDataID::try_from(job.0))
+            .transpose()
+    }
+    pub fn is_alive(&self, now: u64) -> bool {
+        now.saturating_sub(self.last_heartbeat)
+            <= self.heartbeat_interval * WORKER_HEARTBEAT_TIMEOUT_INTERVALS
+    }
+    pub fn get_jobs_per_minute(&self) -> f64 {
+        let uptime = self.last_heartbeat.saturating_sub(self.started_at);
+        if uptime == 0 {
+            0.0
+        } else {
+            (self.jobs_completed as f64) * 60_000.0 / (uptime as f64)
+        }
+    }
+    pub fn to_status(&self, now: u64) -> QWorkerStatus {
+        QWorkerStatus {
+            is_alive: self.is_alive(now),
+            jobs_per_minute: self.get_jobs_per_minute(),
+            utilization: if self.last_heartbeat > self.started_at {
+                (self.busy_time as f64) / ((self.last_heartbeat - self.started_at) as f64)
+            } else {
+                0.0
+            },
+            info: self.clone(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct QWorkerStatus {
+    #[serde(flatten)]
+    pub info: QWorkerInfo,
+    pub is_alive: bool,
+    pub jobs_per_minute: f64,
+    // fraction of the worker's uptime spent processing jobs
+    pub utilization: f64,
+}
+
+pub trait QWorkerRegistrySync {
+    // the registry's clock (unix time in ms). registrations and heartbeats are stamped with it when
+    // they are received and liveness is checked against it, so skewed worker clocks don't matter
+    fn get_time(&self) -> anyhow::Result<u64> {
+        Ok(get_unix_timestamp_ms())
+    }
+    // registering an existing worker id overwrites it, started_at and last_heartbeat are set to the
+    // registry's time
+    fn register_worker(&mut self, info: &QWorkerInfo) -> anyhow::Result<()>;
+    // updates a registered worker and sets its last_heartbeat to the registry's time. returns false
+    // if the worker was removed, so a worker which was declared dead (and had its job requeued)
+    // can't silently rejoin the fleet under the same id
+    fn heartbeat_worker(&mut self, info: &QWorkerInfo) -> anyhow::Result<bool>;
+    fn remove_worker(&mut self, worker_id: &str) -> anyhow::Result<()>;
+    fn get_workers(&self) -> anyhow::Result<Vec<QWorkerInfo>>;
+    // the orchestrator publishes the fingerprint manifest it loaded, workers check against it on startup
+    fn set_fingerprint_manifest(
+        &mut self,
+        manifest: &CRWorkerToolboxCoreCircuitFingerprints<GoldilocksField>,
+    ) -> anyhow::Result<()>;
+    fn get_fingerprint_manifest(
+        &self,
+    ) -> anyhow::Result<Option<CRWorkerToolboxCoreCircuitFingerprints<GoldilocksField>>>;
+    fn get_worker_statuses(&self) -> anyhow::Result<Vec<QWorkerStatus>> {
+        let now = self.get_time()?;
+        let mut statuses = self
+            .get_workers()?
+            .iter()
+            .map(|x| x.to_status(now))
+            .collect::<Vec<_>>();
+        statuses.sort_by(|a, b| a.info.worker_id.cmp(&b.info.worker_id));
+        Ok(statuses)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct QWorkerRegistryMemory {
+    pub workers: HashMap<String, QWorkerInfo>,
+    pub fingerprint_manifest: Option<CRWorkerToolboxCoreCircuitFingerprints<GoldilocksField>>,
+}
+impl QWorkerRegistryMemory {
+    pub fn new() -> Self {
+        Self {
+            workers: HashMap::new(),
+            fingerprint_manifest: None,
+        }
+    }
+}
+impl QWorkerRegistrySync for QWorkerRegistryMemory {
+    fn register_worker(&mut self, info: &QWorkerInfo) -> anyhow::Result<()> {
+        let now = self.get_time()?;
+        self.workers.insert(
+            info.worker_id.clone(),
+            QWorkerInfo {
+                started_at: now,
+                last_heartbeat: now,
+                ..info.clone()
+            },
+        );
+        Ok(())
+    }
+
+    fn heartbeat_worker(&mut self, info: &QWorkerInfo) -> anyhow::Result<bool> {
+        let now = self.get_time()?;
+        match self.workers.get_mut(&info.worker_id) {
+            Some(worker) => {
+                *worker = QWorkerInfo {
+                    started_at: worker.started_at,
+                    last_heartbeat: now,
+                    ..info.clone()
+                };
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    fn remove_worker(&mut self, worker_id: &str) -> anyhow::Result<()> {
+        self.workers.remove(worker_id);
+        Ok(())
+    }
+
+    fn get_workers(&self) -> anyhow::Result<Vec<QWorkerInfo>> {
+        Ok(self.workers.values().cloned().collect())
+    }
+
+    fn set_fingerprint_manifest(
+        &mut self,
+        manifest: &CRWorkerToolboxCoreCircuitFingerprints<GoldilocksField>,
+    ) -> anyhow::Result<()> {
+        self.fingerprint_manifest = Some(*manifest);
+        Ok(())
+    }
+
+    fn get_fingerprint_manifest(
+        &self,
+    ) -> anyhow::Result<Option<CRWorkerToolboxCoreCircuitFingerprints<GoldilocksField>>> {
+        Ok(self.fingerprint_manifest)
+    }
+}
+
+// removes the workers which stopped sending heartbeats and puts the jobs they were working on back in the queue.
+// a stalled worker may still finish its job after it was requeued, so both copies can complete. only the first
+// completion bumps the group counter (see QProofStoreWriterSync::mark_job_completed). the removed worker's
+// heartbeats are rejected from then on, so it rejoins the registry under a new id
+pub fn requeue_dead_worker_jobs<
+    R: QWorkerRegistrySync,
+    PS: QProofStoreReaderSync,
+    WQ: WorkerEventTransmitterSync,
+>(
+    registry: &mut R,
+    proof_store: &PS,
+    worker_queue: &mut WQ,
+    now: u64,
+) -> anyhow::Result<Vec<QWorkerInfo>> {
+    let dead_workers = registry
+        .get_workers()?
+        .into_iter()
+        .filter(|x| !x.is_alive(now))
+        .collect::<Vec<_>>();
+    for worker in dead_workers.iter() {
+        if let Some(job) = worker.get_current_job()? {
+            tracing::warn!(
+                "worker {} ({}) stopped responding, requeuing job {:?}",
+                worker.worker_id,
+                worker.hostname,
+                job
+            );
+            worker_queue.enqueue_prioritized_jobs(&proof_store.get_prioritized_jobs(&[job])?)?;
+        } else {
+            tracing::warn!(
+                "worker {} ({}) stopped responding",
+                worker.worker_id,
+                worker.hostname
+            );
+        }
+        registry.remove_worker(&worker.worker_id)?;
+    }
+    Ok(dead_workers)
+}
+
+#[cfg(test)]
+mod tests {
+    use city_common::cli::modes::QWorkerMode;
+
+    use super::{
+        requeue_dead_worker_jobs, QWorkerInfo, QWorkerRegistryMemory, QWorkerRegistrySync,
+    };
+    use crate::{
+        actors::simple::events::CityEventProcessorMemory,
+        qworker::{
+            job_id::{ProvingJobCircuitType, QProvingJobDataID},
+            memory_proof_store::SimpleProofStoreMemory,
+            proof_store::{QProofStoreReaderSync, QProofStoreWriterSync},
+        },
+    };
+
+    #[test]
+    fn test_requeue_dead_worker_jobs() {
+        let job = QProvingJobDataID::core_op_witness(ProvingJobCircuitType::RegisterUser, 1, 0);
+        let mut registry = QWorkerRegistryMemory::new();
+        let mut alive =
+            QWorkerInfo::new("a".to_string(), QWorkerMode::ALL, "host".to_string(), 1, 1000);
+        let mut dead = QWorkerInfo::new(
+            "b".to_string(),
+            QWorkerMode::NO_GROTH16,
+            "host".to_string(),
+            2,
+            1000,
+        );
+        dead.start_job(job, dead.started_at + 500);
+        registry.register_worker(&alive).unwrap();
+        registry.register_worker(&dead).unwrap();
+        let now = registry.get_time().unwrap() + 10_000;
+        for (worker, last_heartbeat) in [(&mut alive, now - 1000), (&mut dead, now - 5000)] {
+            let registered = registry.workers.get_mut(&worker.worker_id).unwrap();
+            registered.last_heartbeat = last_heartbeat;
+            *worker = registered.clone();
+        }
+
+        let store = SimpleProofStoreMemory::new();
+        let mut queue = CityEventProcessorMemory::new();
+        let removed = requeue_dead_worker_jobs(&mut registry, &store, &mut queue, now).unwrap();
+        assert_eq!(removed, vec![dead]);
+        assert_eq!(registry.get_workers().unwrap(), vec![alive.clone()]);
+        assert_eq!(queue.job_queue.pop(), Some(job));
+        assert!(queue.job_queue.is_empty());
+
+        alive.start_job(job, now);
+        alive.finish_job(now + 2000);
+        assert_eq!(alive.jobs_completed, 1);
+        assert_eq!(alive.busy_time, 2000);
+        assert!(alive.current_job.is_none());
+    }
+
+    #[test]
+    fn test_removed_worker_heartbeat_is_rejected() {
+        let mut registry = QWorkerRegistryMemory::new();
+        let mut worker = QWorkerInfo::new(
+            "a".to_string(),
+            QWorkerMode::ALL,
+            "host".to_string(),
+            1,
+            1000,
+        );
+        assert!(!registry.heartbeat_worker(&worker).unwrap());
+
+        registry.register_worker(&worker).unwrap();
+        worker.jobs_completed += 1;
+        assert!(registry.heartbeat_worker(&worker).unwrap());
+        assert_eq!(
+            registry.get_workers().unwrap()[0].jobs_completed,
+            worker.jobs_completed
+        );
+
+        registry.remove_worker(&worker.worker_id).unwrap();
+        assert!(!registry.heartbeat_worker(&worker).unwrap());
+        assert!(registry.get_workers().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_heartbeats_use_the_registry_clock() {
+        let mut registry = QWorkerRegistryMemory::new();
+        let mut worker = QWorkerInfo::new(
+            "a".to_string(),
+            QWorkerMode::ALL,
+            "host".to_string(),
+            1,
+            1000,
+        );
+        // the worker's clock is an hour behind
+        worker.started_at -= 3_600_000;
+        worker.last_heartbeat -= 3_600_000;
+        let before = registry.get_time().unwrap();
+        registry.register_worker(&worker).unwrap();
+        assert!(registry.heartbeat_worker(&worker).unwrap());
+
+        let registered = registry.get_workers().unwrap()[0].clone();
+        assert!(registered.started_at >= before);
+        assert!(registered.last_heartbeat >= registered.started_at);
+        assert!(registry.get_worker_statuses().unwrap()[0].is_alive);
+    }
+
+    #[test]
+    fn test_requeued_job_is_counted_once() {
+        let job = QProvingJobDataID::core_op_witness(ProvingJobCircuitType::RegisterUser, 1, 0);
+        let mut store = SimpleProofStoreMemory::new();
+        store.write_next_jobs(&[job], &[]).unwrap();
+
+        // the requeued copy and the stalled worker both complete the job
+        for _ in 0..2 {
+            if store.mark_job_completed(job).unwrap() {
+                store
+                    .inc_counter_by_id(job.get_sub_group_counter_id())
+                    .unwrap();
+            }
+        }
+        assert_eq!(
+            store
+                .get_counter_by_id(job.get_sub_group_counter_id())
+                .unwrap(),
+            1
+        );
+        assert!(!store.mark_job_completed(job).unwrap());
+    }
+}
@@ -1,4 +1,4 @@
-use city_common::cli::modes::QWorkerMode;
+use city_common::cli::modes::{QWorkerCapability, QWorkerMode};
 use hex::FromHexError;
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
@@ -49,6 +49,9 @@ pub enum ProvingJobDataType {
     BaseInputProof = 1,
     OutputProof = 8,
     Counter = 16,
+    Benchmark = 17,
+    Priority = 18,
+    Completed = 19,
 }
 impl ProvingJobDataType {
     pub fn to_u8(&self) -> u8 {
@@ -63,6 +66,9 @@ impl TryFrom<u8> for ProvingJobDataType {
             1 => Ok(ProvingJobDataType::BaseInputProof),
             8 => Ok(ProvingJobDataType::OutputProof),
             16 => Ok(ProvingJobDataType::Counter),
+            17 => Ok(ProvingJobDataType::Benchmark),
+            18 => Ok(ProvingJobDataType::Priority),
+            19 => Ok(ProvingJobDataType::Completed),
             _ => Err(anyhow::format_err!(
                 "Invalid ProvingJobDataType value: {}",
                 value
@@ -99,6 +105,27 @@ pub enum ProvingJobCircuitType {
     ProcessL1Withdrawal = 10,
     ProcessL1WithdrawalAggregate = 11,
 
+    ChangePublicKey = 12,
+    ChangePublicKeyAggregate = 13,
+
+    BatchTransferTokensL2 = 14,
+    BatchTransferTokensL2Aggregate = 15,
+
+    OpenTokenAccount = 16,
+    OpenTokenAccountAggregate = 17,
+
+    MintToken = 18,
+    MintTokenAggregate = 19,
+
+    SendToken = 20,
+    SendTokenAggregate = 21,
+
+    BurnToken = 22,
+    BurnTokenAggregate = 23,
+
+    CancelL1Withdrawal = 24,
+    CancelL1WithdrawalAggregate = 25,
+
     GenerateRollupStateTransitionProof = 32,
     GenerateSigHashIntrospectionProof = 33,
     GenerateFinalSigHashProof = 34,
@@ -114,6 +141,13 @@ pub enum ProvingJobCircuitType {
     DummyTransferTokensL2Aggregate = 51,
     DummyAddL1WithdrawalAggregate = 52,
     DummyProcessL1WithdrawalAggregate = 53,
+    DummyChangePublicKeyAggregate = 54,
+    DummyBatchTransferTokensL2Aggregate = 55,
+    DummyOpenTokenAccountAggregate = 56,
+    DummyMintTokenAggregate = 57,
+    DummySendTokenAggregate = 58,
+    DummyBurnTokenAggregate = 59,
+    DummyCancelL1WithdrawalAggregate = 60,
 
     WrappedSignatureProof = 64,
     Secp256K1SignatureProof = 65,
@@ -145,6 +179,20 @@ impl TryFrom<u8> for ProvingJobCircuitType {
             9 => Ok(ProvingJobCircuitType::AddL1WithdrawalAggregate),
             10 => Ok(ProvingJobCircuitType::ProcessL1Withdrawal),
             11 => Ok(ProvingJobCircuitType::ProcessL1WithdrawalAggregate),
+            12 => Ok(ProvingJobCircuitType::ChangePublicKey),
+            13 => Ok(ProvingJobCircuitType::ChangePublicKeyAggregate),
+            14 => Ok(ProvingJobCircuitType::BatchTransferTokensL2),
+            15 => Ok(ProvingJobCircuitType::BatchTransferTokensL2Aggregate),
+            16 => Ok(ProvingJobCircuitType::OpenTokenAccount),
+            17 => Ok(ProvingJobCircuitType::OpenTokenAccountAggregate),
+            18 => Ok(ProvingJobCircuitType::MintToken),
+            19 => Ok(ProvingJobCircuitType::MintTokenAggregate),
+            20 => Ok(ProvingJobCircuitType::SendToken),
+            21 => Ok(ProvingJobCircuitType::SendTokenAggregate),
+            22 => Ok(ProvingJobCircuitType::BurnToken),
+            23 => Ok(ProvingJobCircuitType::BurnTokenAggregate),
+            24 => Ok(ProvingJobCircuitType::CancelL1Withdrawal),
+            25 => Ok(ProvingJobCircuitType::CancelL1WithdrawalAggregate),
             32 => Ok(ProvingJobCircuitType::GenerateRollupStateTransitionProof),
             33 => Ok(ProvingJobCircuitType::GenerateSigHashIntrospectionProof),
             34 => Ok(ProvingJobCircuitType::GenerateFinalSigHashProof),
@@ -158,6 +206,13 @@ impl TryFrom<u8> for ProvingJobCircuitType {
             51 => Ok(ProvingJobCircuitType::DummyTransferTokensL2Aggregate),
             52 => Ok(ProvingJobCircuitType::DummyAddL1WithdrawalAggregate),
             53 => Ok(ProvingJobCircuitType::DummyProcessL1WithdrawalAggregate),
+            54 => Ok(ProvingJobCircuitType::DummyChangePublicKeyAggregate),
+            55 => Ok(ProvingJobCircuitType::DummyBatchTransferTokensL2Aggregate),
+            56 => Ok(ProvingJobCircuitType::DummyOpenTokenAccountAggregate),
+            57 => Ok(ProvingJobCircuitType::DummyMintTokenAggregate),
+            58 => Ok(ProvingJobCircuitType::DummySendTokenAggregate),
+            59 => Ok(ProvingJobCircuitType::DummyBurnTokenAggregate),
+            60 => Ok(ProvingJobCircuitType::DummyCancelL1WithdrawalAggregate),
             64 => Ok(ProvingJobCircuitType::WrappedSignatureProof),
             65 => Ok(ProvingJobCircuitType::Secp256K1SignatureProof),
             255 => Ok(ProvingJobCircuitType::Unknown),
@@ -309,6 +364,106 @@ impl QProvingJobDataID {
             data_index: 0,
         }
     }
+    pub fn change_public_key_signature_proof(
+        rpc_node_id: u32,
+        block_id: u64,
+        change_public_key_id: u32,
+    ) -> Self {
+        Self {
+            topic: QJobTopic::BlockUserSignatureProof,
+            goal_id: block_id,
+            group_id: 4,
+            circuit_type: ProvingJobCircuitType::WrappedSignatureProof,
+            sub_group_id: rpc_node_id,
+            task_index: change_public_key_id,
+            data_type: ProvingJobDataType::BaseInputProof,
+            data_index: 0,
+        }
+    }
+    pub fn batch_transfer_signature_proof(
+        rpc_node_id: u32,
+        block_id: u64,
+        batch_transfer_id: u32,
+    ) -> Self {
+        Self {
+            topic: QJobTopic::BlockUserSignatureProof,
+            goal_id: block_id,
+            group_id: 5,
+            circuit_type: ProvingJobCircuitType::WrappedSignatureProof,
+            sub_group_id: rpc_node_id,
+            task_index: batch_transfer_id,
+            data_type: ProvingJobDataType::BaseInputProof,
+            data_index: 0,
+        }
+    }
+    pub fn open_token_account_signature_proof(
+        rpc_node_id: u32,
+        block_id: u64,
+        open_token_account_id: u32,
+    ) -> Self {
+        Self {
+            topic: QJobTopic::BlockUserSignatureProof,
+            goal_id: block_id,
+            group_id: 6,
+            circuit_type: ProvingJobCircuitType::WrappedSignatureProof,
+            sub_group_id: rpc_node_id,
+            task_index: open_token_account_id,
+            data_type: ProvingJobDataType::BaseInputProof,
+            data_index: 0,
+        }
+    }
+    pub fn mint_token_signature_proof(rpc_node_id: u32, block_id: u64, mint_token_id: u32) -> Self {
+        Self {
+            topic: QJobTopic::BlockUserSignatureProof,
+            goal_id: block_id,
+            group_id: 7,
+            circuit_type: ProvingJobCircuitType::WrappedSignatureProof,
+            sub_group_id: rpc_node_id,
+            task_index: mint_token_id,
+            data_type: ProvingJobDataType::BaseInputProof,
+            data_index: 0,
+        }
+    }
+    pub fn send_token_signature_proof(rpc_node_id: u32, block_id: u64, send_token_id: u32) -> Self {
+        Self {
+            topic: QJobTopic::BlockUserSignatureProof,
+            goal_id: block_id,
+            group_id: 8,
+            circuit_type: ProvingJobCircuitType::WrappedSignatureProof,
+            sub_group_id: rpc_node_id,
+            task_index: send_token_id,
+            data_type: ProvingJobDataType::BaseInputProof,
+            data_index: 0,
+        }
+    }
+    pub fn burn_token_signature_proof(rpc_node_id: u32, block_id: u64, burn_token_id: u32) -> Self {
+        Self {
+            topic: QJobTopic::BlockUserSignatureProof,
+            goal_id: block_id,
+            group_id: 9,
+            circuit_type: ProvingJobCircuitType::WrappedSignatureProof,
+            sub_group_id: rpc_node_id,
+            task_index: burn_token_id,
+            data_type: ProvingJobDataType::BaseInputProof,
+            data_index: 0,
+        }
+    }
+    pub fn cancel_withdrawal_signature_proof(
+        rpc_node_id: u32,
+        block_id: u64,
+        cancel_withdrawal_id: u32,
+    ) -> Self {
+        Self {
+            topic: QJobTopic::BlockUserSignatureProof,
+            goal_id: block_id,
+            group_id: 10,
+            circuit_type: ProvingJobCircuitType::WrappedSignatureProof,
+            sub_group_id: rpc_node_id,
+            task_index: cancel_withdrawal_id,
+            data_type: ProvingJobDataType::BaseInputProof,
+            data_index: 0,
+        }
+    }
     pub fn claim_deposit_l1_signature_proof(
         rpc_node_id: u32,
         block_id: u64,
@@ -385,6 +540,13 @@ impl QProvingJobDataID {
             data_index: 0,
         }
     }
+    pub fn block_leaf_jobs_list(block_id: u64) -> Self {
+        Self {
+            data_type: ProvingJobDataType::Counter,
+            data_index: 3,
+            ..Self::notify_block_complete(block_id)
+        }
+    }
     pub fn block_agg_state_part_1_input_witness(block_id: u64) -> Self {
         Self {
             topic: QJobTopic::GenerateStandardProof,
@@ -503,6 +665,36 @@ impl QProvingJobDataID {
             ProvingJobCircuitType::ProcessL1WithdrawalAggregate => {
                 ProvingJobCircuitType::ProcessL1WithdrawalAggregate
             }
+            ProvingJobCircuitType::ChangePublicKey => {
+                ProvingJobCircuitType::ChangePublicKeyAggregate
+            }
+            ProvingJobCircuitType::ChangePublicKeyAggregate => {
+                ProvingJobCircuitType::ChangePublicKeyAggregate
+            }
+            ProvingJobCircuitType::BatchTransferTokensL2 => {
+                ProvingJobCircuitType::BatchTransferTokensL2Aggregate
+            }
+            ProvingJobCircuitType::BatchTransferTokensL2Aggregate => {
+                ProvingJobCircuitType::BatchTransferTokensL2Aggregate
+            }
+            ProvingJobCircuitType::OpenTokenAccount => {
+                ProvingJobCircuitType::OpenTokenAccountAggregate
+            }
+            ProvingJobCircuitType::OpenTokenAccountAggregate => {
+                ProvingJobCircuitType::OpenTokenAccountAggregate
+            }
+            ProvingJobCircuitType::MintToken => ProvingJobCircuitType::MintTokenAggregate,
+            ProvingJobCircuitType::MintTokenAggregate => ProvingJobCircuitType::MintTokenAggregate,
+            ProvingJobCircuitType::SendToken => ProvingJobCircuitType::SendTokenAggregate,
+            ProvingJobCircuitType::SendTokenAggregate => ProvingJobCircuitType::SendTokenAggregate,
+            ProvingJobCircuitType::BurnToken => ProvingJobCircuitType::BurnTokenAggregate,
+            ProvingJobCircuitType::BurnTokenAggregate => ProvingJobCircuitType::BurnTokenAggregate,
+            ProvingJobCircuitType::CancelL1Withdrawal => {
+                ProvingJobCircuitType::CancelL1WithdrawalAggregate
+            }
+            ProvingJobCircuitType::CancelL1WithdrawalAggregate => {
+                ProvingJobCircuitType::CancelL1WithdrawalAggregate
+            }
             ProvingJobCircuitType::DummyRegisterUserAggregate => {
                 ProvingJobCircuitType::RegisterUserAggregate
             }
@@ -521,6 +713,27 @@ impl QProvingJobDataID {
             ProvingJobCircuitType::DummyProcessL1WithdrawalAggregate => {
                 ProvingJobCircuitType::ProcessL1WithdrawalAggregate
             }
+            ProvingJobCircuitType::DummyChangePublicKeyAggregate => {
+                ProvingJobCircuitType::ChangePublicKeyAggregate
+            }
+            ProvingJobCircuitType::DummyBatchTransferTokensL2Aggregate => {
+                ProvingJobCircuitType::BatchTransferTokensL2Aggregate
+            }
+            ProvingJobCircuitType::DummyOpenTokenAccountAggregate => {
+                ProvingJobCircuitType::OpenTokenAccountAggregate
+            }
+            ProvingJobCircuitType::DummyMintTokenAggregate => {
+                ProvingJobCircuitType::MintTokenAggregate
+            }
+            ProvingJobCircuitType::DummySendTokenAggregate => {
+                ProvingJobCircuitType::SendTokenAggregate
+            }
+            ProvingJobCircuitType::DummyBurnTokenAggregate => {
+                ProvingJobCircuitType::BurnTokenAggregate
+            }
+            ProvingJobCircuitType::DummyCancelL1WithdrawalAggregate => {
+                ProvingJobCircuitType::CancelL1WithdrawalAggregate
+            }
             _ => self.circuit_type,
         };
         Self {
@@ -539,6 +752,27 @@ impl QProvingJobDataID {
             ..*self
         }
     }
+    pub fn get_benchmark_id(&self) -> Self {
+        Self {
+            data_type: ProvingJobDataType::Benchmark,
+            data_index: 0,
+            ..*self
+        }
+    }
+    pub fn get_priority_id(&self) -> Self {
+        Self {
+            data_type: ProvingJobDataType::Priority,
+            data_index: 0,
+            ..*self
+        }
+    }
+    pub fn get_completed_id(&self) -> Self {
+        Self {
+            data_type: ProvingJobDataType::Completed,
+            data_index: 0,
+            ..*self
+        }
+    }
     pub fn get_sub_group_counter_id(&self) -> Self {
         Self {
             data_type: ProvingJobDataType::Counter,
@@ -575,6 +809,17 @@ impl QProvingJobDataID {
     pub fn to_hex_string(&self) -> String {
         hex::encode(&self.to_fixed_bytes())
     }
+    pub fn get_required_capability(&self) -> QWorkerCapability {
+        if self.topic == QJobTopic::GenerateGroth16Proof
+            || self.circuit_type == ProvingJobCircuitType::WrapFinalSigHashProofBLS12381
+        {
+            QWorkerCapability::Groth16Proof
+        } else if self.topic == QJobTopic::BlockUserSignatureProof {
+            QWorkerCapability::SignatureProof
+        } else {
+            QWorkerCapability::StandardProof
+        }
+    }
 }
 
 
@@ -584,11 +829,7 @@ pub trait QWorkerModeFilter {
 }
 impl QWorkerModeFilter for QWorkerMode {
     fn can_process_job(&self, job_id: QProvingJobDataID) -> bool {
-        match *self {
-            QWorkerMode::All => true,
-            QWorkerMode::NoGroth16 => job_id.circuit_type != ProvingJobCircuitType::WrapFinalSigHashProofBLS12381,
-            QWorkerMode::OnlyGroth16 => job_id.circuit_type == ProvingJobCircuitType::WrapFinalSigHashProofBLS12381,
-        }
+        self.has_capability(job_id.get_required_capability())
     }
 }
 
@@ -16,6 +16,24 @@ pub struct CRAggUserRegisterClaimDepositL2TransferCircuitInput<F: RichField> {
 
     pub op_l2_transfer_transition_user_state_tree: AggStateTransition<F>,
     pub op_l2_transfer_proof_id: QProvingJobDataID,
+
+    pub op_change_public_key_transition_user_state_tree: AggStateTransition<F>,
+    pub op_change_public_key_proof_id: QProvingJobDataID,
+
+    pub op_batch_l2_transfer_transition_user_state_tree: AggStateTransition<F>,
+    pub op_batch_l2_transfer_proof_id: QProvingJobDataID,
+
+    pub op_open_token_account_transition_user_state_tree: AggStateTransition<F>,
+    pub op_open_token_account_proof_id: QProvingJobDataID,
+
+    pub op_mint_token_transition_user_state_tree: AggStateTransition<F>,
+    pub op_mint_token_proof_id: QProvingJobDataID,
+
+    pub op_send_token_transition_user_state_tree: AggStateTransition<F>,
+    pub op_send_token_proof_id: QProvingJobDataID,
+
+    pub op_burn_token_transition_user_state_tree: AggStateTransition<F>,
+    pub op_burn_token_proof_id: QProvingJobDataID,
 }
 impl<F: RichField> CRAggUserRegisterClaimDepositL2TransferCircuitInput<F> {
     pub fn get_agg_state_transition(
@@ -26,7 +44,7 @@ impl<F: RichField> CRAggUserRegisterClaimDepositL2TransferCircuitInput<F> {
             user_state_tree_transition: AggStateTransition::new(
                 self.op_register_user_transition_user_state_tree
                     .state_transition_start,
-                self.op_l2_transfer_transition_user_state_tree
+                self.op_burn_token_transition_user_state_tree
                     .state_transition_end,
             ),
             deposit_tree_transition: self.op_claim_l1_deposit_transition_deposit_tree,
@@ -49,6 +67,10 @@ pub struct CRAggAddProcessL1WithdrawalAddL1DepositCircuitInput<F: RichField> {
     pub op_add_l1_withdrawal_transition_withdrawal_tree: AggStateTransition<F>,
     pub op_add_l1_withdrawal_proof_id: QProvingJobDataID,
 
+    pub op_cancel_l1_withdrawal_transition_user_state_tree: AggStateTransition<F>,
+    pub op_cancel_l1_withdrawal_transition_withdrawal_tree: AggStateTransition<F>,
+    pub op_cancel_l1_withdrawal_proof_id: QProvingJobDataID,
+
     pub op_process_l1_withdrawal_transition_withdrawal_tree: AggStateTransition<F>,
     pub op_process_l1_withdrawal_proof_id: QProvingJobDataID,
 
@@ -62,7 +84,12 @@ impl<F: RichField> CRAggAddProcessL1WithdrawalAddL1DepositCircuitInput<F> {
         proof_id: QProvingJobDataID,
     ) -> CRAggAddProcessL1WithdrawalAddL1DepositStateTransition<F> {
         CRAggAddProcessL1WithdrawalAddL1DepositStateTransition {
-            user_state_tree_transition: self.op_add_l1_withdrawal_transition_user_state_tree,
+            user_state_tree_transition: AggStateTransition::new(
+                self.op_add_l1_withdrawal_transition_user_state_tree
+                    .state_transition_start,
+                self.op_cancel_l1_withdrawal_transition_user_state_tree
+                    .state_transition_end,
+            ),
             deposit_tree_transition: self.op_add_l1_deposit_transition_deposit_tree,
             withdrawal_tree_transition: AggStateTransition::new(
                 self.op_add_l1_withdrawal_transition_withdrawal_tree
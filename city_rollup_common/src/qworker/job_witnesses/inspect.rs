@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::qworker::job_id::{ProvingJobCircuitType, QProvingJobDataID};
 
-use super::{agg::{CRAggAddProcessL1WithdrawalAddL1DepositCircuitInput, CRAggUserRegisterClaimDepositL2TransferCircuitInput, CRBlockStateTransitionCircuitInput}, op::{CRAddL1DepositCircuitInput, CRAddL1WithdrawalCircuitInput, CRClaimL1DepositCircuitInput, CRL2TransferCircuitInput, CRProcessL1WithdrawalCircuitInput, CRUserRegistrationCircuitInput}, sighash::{CRSigHashFinalGLCircuitInput, CRSigHashWrapperCircuitInput}};
+use super::{agg::{CRAggAddProcessL1WithdrawalAddL1DepositCircuitInput, CRAggUserRegisterClaimDepositL2TransferCircuitInput, CRBlockStateTransitionCircuitInput}, op::{CRAddL1DepositCircuitInput, CRAddL1WithdrawalCircuitInput, CRBatchL2TransferCircuitInput, CRBurnTokenCircuitInput, CRCancelL1WithdrawalCircuitInput, CRChangePublicKeyCircuitInput, CRClaimL1DepositCircuitInput, CRL2TransferCircuitInput, CRMintTokenCircuitInput, CROpenTokenAccountCircuitInput, CRProcessL1WithdrawalCircuitInput, CRSendTokenCircuitInput, CRUserRegistrationCircuitInput}, sighash::{CRSigHashFinalGLCircuitInput, CRSigHashWrapperCircuitInput}};
 
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(bound = "")]
@@ -48,6 +48,20 @@ pub enum QJobWitness<F: RichField> {
     AddL1WithdrawalAggregate(AggStateTransitionInput<F>),
     ProcessL1Withdrawal(CRProcessL1WithdrawalCircuitInput<F>),
     ProcessL1WithdrawalAggregate(AggStateTransitionWithEventsInput<F>),
+    ChangePublicKey(CRChangePublicKeyCircuitInput<F>),
+    ChangePublicKeyAggregate(AggStateTransitionInput<F>),
+    BatchTransferTokensL2(CRBatchL2TransferCircuitInput<F>),
+    BatchTransferTokensL2Aggregate(AggStateTransitionInput<F>),
+    OpenTokenAccount(CROpenTokenAccountCircuitInput<F>),
+    OpenTokenAccountAggregate(AggStateTransitionInput<F>),
+    MintToken(CRMintTokenCircuitInput<F>),
+    MintTokenAggregate(AggStateTransitionInput<F>),
+    SendToken(CRSendTokenCircuitInput<F>),
+    SendTokenAggregate(AggStateTransitionInput<F>),
+    BurnToken(CRBurnTokenCircuitInput<F>),
+    BurnTokenAggregate(AggStateTransitionInput<F>),
+    CancelL1Withdrawal(CRCancelL1WithdrawalCircuitInput<F>),
+    CancelL1WithdrawalAggregate(AggStateTransitionInput<F>),
     GenerateRollupStateTransitionProof(CRBlockStateTransitionCircuitInput<F>),
     GenerateSigHashIntrospectionProof(CRSigHashWrapperCircuitInput<F>),
     GenerateFinalSigHashProof(CRSigHashFinalGLCircuitInput<F>),
@@ -60,6 +74,13 @@ pub enum QJobWitness<F: RichField> {
     DummyTransferTokensL2Aggregate(DummyAggStateTransition<F>),
     DummyAddL1WithdrawalAggregate(DummyAggStateTransition<F>),
     DummyProcessL1WithdrawalAggregate(DummyAggStateTransitionWithEvents<F>),
+    DummyChangePublicKeyAggregate(DummyAggStateTransition<F>),
+    DummyBatchTransferTokensL2Aggregate(DummyAggStateTransition<F>),
+    DummyOpenTokenAccountAggregate(DummyAggStateTransition<F>),
+    DummyMintTokenAggregate(DummyAggStateTransition<F>),
+    DummySendTokenAggregate(DummyAggStateTransition<F>),
+    DummyBurnTokenAggregate(DummyAggStateTransition<F>),
+    DummyCancelL1WithdrawalAggregate(DummyAggStateTransition<F>),
     RawBytes(U8Bytes),
 }
 
@@ -78,6 +99,20 @@ impl<F: RichField> QJobWitness<F> {
             ProvingJobCircuitType::AddL1WithdrawalAggregate => true,
             ProvingJobCircuitType::ProcessL1Withdrawal => true,
             ProvingJobCircuitType::ProcessL1WithdrawalAggregate => true,
+            ProvingJobCircuitType::ChangePublicKey => true,
+            ProvingJobCircuitType::ChangePublicKeyAggregate => true,
+            ProvingJobCircuitType::BatchTransferTokensL2 => true,
+            ProvingJobCircuitType::BatchTransferTokensL2Aggregate => true,
+            ProvingJobCircuitType::OpenTokenAccount => true,
+            ProvingJobCircuitType::OpenTokenAccountAggregate => true,
+            ProvingJobCircuitType::MintToken => true,
+            ProvingJobCircuitType::MintTokenAggregate => true,
+            ProvingJobCircuitType::SendToken => true,
+            ProvingJobCircuitType::SendTokenAggregate => true,
+            ProvingJobCircuitType::BurnToken => true,
+            ProvingJobCircuitType::BurnTokenAggregate => true,
+            ProvingJobCircuitType::CancelL1Withdrawal => true,
+            ProvingJobCircuitType::CancelL1WithdrawalAggregate => true,
             ProvingJobCircuitType::GenerateRollupStateTransitionProof => true,
             ProvingJobCircuitType::GenerateSigHashIntrospectionProof => true,
             ProvingJobCircuitType::GenerateFinalSigHashProof => true,
@@ -90,6 +125,13 @@ impl<F: RichField> QJobWitness<F> {
             ProvingJobCircuitType::DummyTransferTokensL2Aggregate => true,
             ProvingJobCircuitType::DummyAddL1WithdrawalAggregate => true,
             ProvingJobCircuitType::DummyProcessL1WithdrawalAggregate => true,
+            ProvingJobCircuitType::DummyChangePublicKeyAggregate => true,
+            ProvingJobCircuitType::DummyBatchTransferTokensL2Aggregate => true,
+            ProvingJobCircuitType::DummyOpenTokenAccountAggregate => true,
+            ProvingJobCircuitType::DummyMintTokenAggregate => true,
+            ProvingJobCircuitType::DummySendTokenAggregate => true,
+            ProvingJobCircuitType::DummyBurnTokenAggregate => true,
+            ProvingJobCircuitType::DummyCancelL1WithdrawalAggregate => true,
             _ => false,
         }
     }
@@ -107,6 +149,20 @@ impl<F: RichField> QJobWitness<F> {
             ProvingJobCircuitType::AddL1WithdrawalAggregate => Ok(Self::AddL1WithdrawalAggregate(bincode::deserialize(data)?)),
             ProvingJobCircuitType::ProcessL1Withdrawal => Ok(Self::ProcessL1Withdrawal(bincode::deserialize(data)?)),
             ProvingJobCircuitType::ProcessL1WithdrawalAggregate => Ok(Self::ProcessL1WithdrawalAggregate(bincode::deserialize(data)?)),
+            ProvingJobCircuitType::ChangePublicKey => Ok(Self::ChangePublicKey(bincode::deserialize(data)?)),
+            ProvingJobCircuitType::ChangePublicKeyAggregate => Ok(Self::ChangePublicKeyAggregate(bincode::deserialize(data)?)),
+            ProvingJobCircuitType::BatchTransferTokensL2 => Ok(Self::BatchTransferTokensL2(bincode::deserialize(data)?)),
+            ProvingJobCircuitType::BatchTransferTokensL2Aggregate => Ok(Self::BatchTransferTokensL2Aggregate(bincode::deserialize(data)?)),
+            ProvingJobCircuitType::OpenTokenAccount => Ok(Self::OpenTokenAccount(bincode::deserialize(data)?)),
+            ProvingJobCircuitType::OpenTokenAccountAggregate => Ok(Self::OpenTokenAccountAggregate(bincode::deserialize(data)?)),
+            ProvingJobCircuitType::MintToken => Ok(Self::MintToken(bincode::deserialize(data)?)),
+            ProvingJobCircuitType::MintTokenAggregate => Ok(Self::MintTokenAggregate(bincode::deserialize(data)?)),
+            ProvingJobCircuitType::SendToken => Ok(Self::SendToken(bincode::deserialize(data)?)),
+            ProvingJobCircuitType::SendTokenAggregate => Ok(Self::SendTokenAggregate(bincode::deserialize(data)?)),
+            ProvingJobCircuitType::BurnToken => Ok(Self::BurnToken(bincode::deserialize(data)?)),
+            ProvingJobCircuitType::BurnTokenAggregate => Ok(Self::BurnTokenAggregate(bincode::deserialize(data)?)),
+            ProvingJobCircuitType::CancelL1Withdrawal => Ok(Self::CancelL1Withdrawal(bincode::deserialize(data)?)),
+            ProvingJobCircuitType::CancelL1WithdrawalAggregate => Ok(Self::CancelL1WithdrawalAggregate(bincode::deserialize(data)?)),
             ProvingJobCircuitType::GenerateRollupStateTransitionProof => Ok(Self::GenerateRollupStateTransitionProof(bincode::deserialize(data)?)),
             ProvingJobCircuitType::GenerateSigHashIntrospectionProof => Ok(Self::GenerateSigHashIntrospectionProof(bincode::deserialize(data)?)),
             ProvingJobCircuitType::GenerateFinalSigHashProof => Ok(Self::GenerateFinalSigHashProof(bincode::deserialize(data)?)),
@@ -119,6 +175,13 @@ impl<F: RichField> QJobWitness<F> {
             ProvingJobCircuitType::DummyTransferTokensL2Aggregate => Ok(Self::DummyTransferTokensL2Aggregate(bincode::deserialize(data)?)),
             ProvingJobCircuitType::DummyAddL1WithdrawalAggregate => Ok(Self::DummyAddL1WithdrawalAggregate(bincode::deserialize(data)?)),
             ProvingJobCircuitType::DummyProcessL1WithdrawalAggregate => Ok(Self::DummyProcessL1WithdrawalAggregate(bincode::deserialize(data)?)),
+            ProvingJobCircuitType::DummyChangePublicKeyAggregate => Ok(Self::DummyChangePublicKeyAggregate(bincode::deserialize(data)?)),
+            ProvingJobCircuitType::DummyBatchTransferTokensL2Aggregate => Ok(Self::DummyBatchTransferTokensL2Aggregate(bincode::deserialize(data)?)),
+            ProvingJobCircuitType::DummyOpenTokenAccountAggregate => Ok(Self::DummyOpenTokenAccountAggregate(bincode::deserialize(data)?)),
+            ProvingJobCircuitType::DummyMintTokenAggregate => Ok(Self::DummyMintTokenAggregate(bincode::deserialize(data)?)),
+            ProvingJobCircuitType::DummySendTokenAggregate => Ok(Self::DummySendTokenAggregate(bincode::deserialize(data)?)),
+            ProvingJobCircuitType::DummyBurnTokenAggregate => Ok(Self::DummyBurnTokenAggregate(bincode::deserialize(data)?)),
+            ProvingJobCircuitType::DummyCancelL1WithdrawalAggregate => Ok(Self::DummyCancelL1WithdrawalAggregate(bincode::deserialize(data)?)),
             _ => Ok(Self::RawBytes(U8Bytes::from(data.to_vec()))),
         }
     }
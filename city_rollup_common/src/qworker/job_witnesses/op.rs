@@ -112,7 +112,11 @@ impl<F: RichField> KVQSerializable for CRAddL1DepositCircuitInput<F> {
 #[serde(bound = "")]
 pub struct CRAddL1WithdrawalCircuitInput<F: RichField> {
     pub user_tree_delta_merkle_proof: DeltaMerkleProofCore<QHashOut<F>>,
+    pub fee_recipient_user_tree_delta_merkle_proof: DeltaMerkleProofCore<QHashOut<F>>,
+    pub fee: u64,
     pub withdrawal_tree_delta_merkle_proof: DeltaMerkleProofCore<QHashOut<F>>,
+    // the hash of the withdrawal's L1 output, the new leaf also commits to the user
+    pub withdrawal_hash: QHashOut<F>,
     pub allowed_circuit_hashes_root: QHashOut<F>,
     pub signature_proof_id: QProvingJobDataID,
 }
@@ -124,7 +128,7 @@ impl<F: RichField> AggStateTrackableInput<F> for CRAddL1WithdrawalCircuitInput<F
                 self.withdrawal_tree_delta_merkle_proof.old_root.0,
             )),
             state_transition_end: QHashOut(PoseidonHash::two_to_one(
-                self.user_tree_delta_merkle_proof.new_root.0,
+                self.fee_recipient_user_tree_delta_merkle_proof.new_root.0,
                 self.withdrawal_tree_delta_merkle_proof.new_root.0,
             )),
         }
@@ -197,6 +201,8 @@ impl<F: RichField> CRClaimL1DepositCircuitInput<F> {
 pub struct CRL2TransferCircuitInput<F: RichField> {
     pub sender_user_tree_delta_merkle_proof: DeltaMerkleProofCore<QHashOut<F>>,
     pub receiver_user_tree_delta_merkle_proof: DeltaMerkleProofCore<QHashOut<F>>,
+    pub fee_recipient_user_tree_delta_merkle_proof: DeltaMerkleProofCore<QHashOut<F>>,
+    pub fee: u64,
     pub allowed_circuit_hashes_root: QHashOut<F>,
     pub signature_proof_id: QProvingJobDataID,
 }
@@ -204,7 +210,7 @@ impl<F: RichField> AggStateTrackableInput<F> for CRL2TransferCircuitInput<F> {
     fn get_state_transition(&self) -> AggStateTransition<F> {
         AggStateTransition {
             state_transition_start: self.sender_user_tree_delta_merkle_proof.old_root,
-            state_transition_end: self.receiver_user_tree_delta_merkle_proof.new_root,
+            state_transition_end: self.fee_recipient_user_tree_delta_merkle_proof.new_root,
         }
     }
 }
@@ -218,10 +224,214 @@ impl<F: RichField> KVQSerializable for CRL2TransferCircuitInput<F> {
     }
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(bound = "")]
+pub struct CRBatchL2TransferCircuitInput<F: RichField> {
+    pub sender_user_tree_delta_merkle_proof: DeltaMerkleProofCore<QHashOut<F>>,
+    // always L2_BATCH_TRANSFER_MAX_RECIPIENTS proofs, unused slots are zero-value no-op proofs
+    pub receiver_user_tree_delta_merkle_proofs: Vec<DeltaMerkleProofCore<QHashOut<F>>>,
+    pub fee_recipient_user_tree_delta_merkle_proof: DeltaMerkleProofCore<QHashOut<F>>,
+    pub recipient_count: usize,
+    pub fee: u64,
+    pub allowed_circuit_hashes_root: QHashOut<F>,
+    pub signature_proof_id: QProvingJobDataID,
+}
+impl<F: RichField> AggStateTrackableInput<F> for CRBatchL2TransferCircuitInput<F> {
+    fn get_state_transition(&self) -> AggStateTransition<F> {
+        AggStateTransition {
+            state_transition_start: self.sender_user_tree_delta_merkle_proof.old_root,
+            state_transition_end: self.fee_recipient_user_tree_delta_merkle_proof.new_root,
+        }
+    }
+}
+impl<F: RichField> KVQSerializable for CRBatchL2TransferCircuitInput<F> {
+    fn to_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        bincode::serialize(self).map_err(Into::into)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        bincode::deserialize(bytes).map_err(Into::into)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(bound = "")]
+pub struct CRTokenStateTransitionInput<F: RichField> {
+    // bumps the nonce of the signer
+    pub user_tree_delta_merkle_proof: DeltaMerkleProofCore<QHashOut<F>>,
+    // replaces hash(token registry root, token balance root) in the token state leaf of the user tree
+    pub token_state_user_tree_delta_merkle_proof: DeltaMerkleProofCore<QHashOut<F>>,
+    pub token_registry_delta_merkle_proof: DeltaMerkleProofCore<QHashOut<F>>,
+    pub token_balance_delta_merkle_proofs: Vec<DeltaMerkleProofCore<QHashOut<F>>>,
+}
+impl<F: RichField> AggStateTrackableInput<F> for CRTokenStateTransitionInput<F> {
+    fn get_state_transition(&self) -> AggStateTransition<F> {
+        AggStateTransition {
+            state_transition_start: self.user_tree_delta_merkle_proof.old_root,
+            state_transition_end: self.token_state_user_tree_delta_merkle_proof.new_root,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(bound = "")]
+pub struct CROpenTokenAccountCircuitInput<F: RichField> {
+    pub token_state_transition: CRTokenStateTransitionInput<F>,
+    pub allowed_circuit_hashes_root: QHashOut<F>,
+    pub signature_proof_id: QProvingJobDataID,
+}
+impl<F: RichField> AggStateTrackableInput<F> for CROpenTokenAccountCircuitInput<F> {
+    fn get_state_transition(&self) -> AggStateTransition<F> {
+        self.token_state_transition.get_state_transition()
+    }
+}
+impl<F: RichField> KVQSerializable for CROpenTokenAccountCircuitInput<F> {
+    fn to_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        bincode::serialize(self).map_err(Into::into)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        bincode::deserialize(bytes).map_err(Into::into)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(bound = "")]
+pub struct CRMintTokenCircuitInput<F: RichField> {
+    pub token_state_transition: CRTokenStateTransitionInput<F>,
+    pub allowed_circuit_hashes_root: QHashOut<F>,
+    pub signature_proof_id: QProvingJobDataID,
+}
+impl<F: RichField> AggStateTrackableInput<F> for CRMintTokenCircuitInput<F> {
+    fn get_state_transition(&self) -> AggStateTransition<F> {
+        self.token_state_transition.get_state_transition()
+    }
+}
+impl<F: RichField> KVQSerializable for CRMintTokenCircuitInput<F> {
+    fn to_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        bincode::serialize(self).map_err(Into::into)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        bincode::deserialize(bytes).map_err(Into::into)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(bound = "")]
+pub struct CRSendTokenCircuitInput<F: RichField> {
+    pub token_state_transition: CRTokenStateTransitionInput<F>,
+    pub allowed_circuit_hashes_root: QHashOut<F>,
+    pub signature_proof_id: QProvingJobDataID,
+}
+impl<F: RichField> AggStateTrackableInput<F> for CRSendTokenCircuitInput<F> {
+    fn get_state_transition(&self) -> AggStateTransition<F> {
+        self.token_state_transition.get_state_transition()
+    }
+}
+impl<F: RichField> KVQSerializable for CRSendTokenCircuitInput<F> {
+    fn to_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        bincode::serialize(self).map_err(Into::into)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        bincode::deserialize(bytes).map_err(Into::into)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(bound = "")]
+pub struct CRBurnTokenCircuitInput<F: RichField> {
+    pub token_state_transition: CRTokenStateTransitionInput<F>,
+    pub allowed_circuit_hashes_root: QHashOut<F>,
+    pub signature_proof_id: QProvingJobDataID,
+}
+impl<F: RichField> AggStateTrackableInput<F> for CRBurnTokenCircuitInput<F> {
+    fn get_state_transition(&self) -> AggStateTransition<F> {
+        self.token_state_transition.get_state_transition()
+    }
+}
+impl<F: RichField> KVQSerializable for CRBurnTokenCircuitInput<F> {
+    fn to_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        bincode::serialize(self).map_err(Into::into)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        bincode::deserialize(bytes).map_err(Into::into)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(bound = "")]
+pub struct CRChangePublicKeyCircuitInput<F: RichField> {
+    // updates the nonce in the user's left leaf
+    pub user_tree_nonce_delta_merkle_proof: DeltaMerkleProofCore<QHashOut<F>>,
+    // updates the public key in the user's right leaf
+    pub user_tree_public_key_delta_merkle_proof: DeltaMerkleProofCore<QHashOut<F>>,
+    pub allowed_circuit_hashes_root: QHashOut<F>,
+    pub signature_proof_id: QProvingJobDataID,
+}
+impl<F: RichField> AggStateTrackableInput<F> for CRChangePublicKeyCircuitInput<F> {
+    fn get_state_transition(&self) -> AggStateTransition<F> {
+        AggStateTransition {
+            state_transition_start: self.user_tree_nonce_delta_merkle_proof.old_root,
+            state_transition_end: self.user_tree_public_key_delta_merkle_proof.new_root,
+        }
+    }
+}
+impl<F: RichField> KVQSerializable for CRChangePublicKeyCircuitInput<F> {
+    fn to_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        bincode::serialize(self).map_err(Into::into)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        bincode::deserialize(bytes).map_err(Into::into)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(bound = "")]
+pub struct CRCancelL1WithdrawalCircuitInput<F: RichField> {
+    // zeroes the pending withdrawal's leaf
+    pub withdrawal_tree_delta_merkle_proof: DeltaMerkleProofCore<QHashOut<F>>,
+    // the hash of the withdrawal's L1 output, the leaf also commits to the user
+    pub withdrawal_hash: QHashOut<F>,
+    // refunds the withdrawal amount and the L1 withdrawal fee to the user
+    pub user_tree_delta_merkle_proof: DeltaMerkleProofCore<QHashOut<F>>,
+    pub allowed_circuit_hashes_root: QHashOut<F>,
+    pub signature_proof_id: QProvingJobDataID,
+}
+impl<F: RichField> AggStateTrackableInput<F> for CRCancelL1WithdrawalCircuitInput<F> {
+    fn get_state_transition(&self) -> AggStateTransition<F> {
+        AggStateTransition {
+            state_transition_start: QHashOut(PoseidonHash::two_to_one(
+                self.user_tree_delta_merkle_proof.old_root.0,
+                self.withdrawal_tree_delta_merkle_proof.old_root.0,
+            )),
+            state_transition_end: QHashOut(PoseidonHash::two_to_one(
+                self.user_tree_delta_merkle_proof.new_root.0,
+                self.withdrawal_tree_delta_merkle_proof.new_root.0,
+            )),
+        }
+    }
+}
+impl<F: RichField> KVQSerializable for CRCancelL1WithdrawalCircuitInput<F> {
+    fn to_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        bincode::serialize(self).map_err(Into::into)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        bincode::deserialize(bytes).map_err(Into::into)
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 #[serde(bound = "")]
 pub struct CRProcessL1WithdrawalCircuitInput<F: RichField> {
     pub withdrawal_tree_delta_merkle_proof: DeltaMerkleProofCore<QHashOut<F>>,
+    // the preimage of the zeroed leaf, the withdrawal hash is the emitted event
+    pub withdrawal_hash: QHashOut<F>,
+    pub owner_user_id: u64,
     pub allowed_circuit_hashes_root: QHashOut<F>,
 }
 
@@ -230,7 +440,7 @@ impl<F: RichField> AggStateTrackableWithEventsInput<F> for CRProcessL1Withdrawal
         AggStateTransitionWithEvents {
             state_transition_start: self.withdrawal_tree_delta_merkle_proof.old_root,
             state_transition_end: self.withdrawal_tree_delta_merkle_proof.new_root,
-            event_hash: self.withdrawal_tree_delta_merkle_proof.old_value,
+            event_hash: self.withdrawal_hash,
         }
     }
 }
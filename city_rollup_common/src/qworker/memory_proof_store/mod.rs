@@ -59,6 +59,14 @@ impl QProofStoreReaderSync for SimpleProofStoreMemory {
         })?;
         Ok(data.to_vec())
     }
+
+    fn get_bytes_by_id_if_exists(&self, id: QProvingJobDataID) -> anyhow::Result<Option<Vec<u8>>> {
+        Ok(self.proofs.get(&id).cloned())
+    }
+
+    fn get_counter_by_id(&self, id: QProvingJobDataID) -> anyhow::Result<u32> {
+        Ok(*self.counters.get(&id).unwrap_or(&0))
+    }
 }
 
 impl QProofStoreWriterSync for SimpleProofStoreMemory {
@@ -79,6 +87,15 @@ impl QProofStoreWriterSync for SimpleProofStoreMemory {
         Ok(new_value)
     }
 
+    fn mark_job_completed(&mut self, id: QProvingJobDataID) -> anyhow::Result<bool> {
+        let completed_id = id.get_completed_id();
+        if self.proofs.contains_key(&completed_id) {
+            return Ok(false);
+        }
+        self.proofs.insert(completed_id, vec![1]);
+        Ok(true)
+    }
+
     fn set_bytes_by_id(&mut self, id: QProvingJobDataID, data: &[u8]) -> anyhow::Result<()> {
         self.proofs.insert(id, data.to_vec());
         Ok(())
@@ -1,7 +1,11 @@
+pub mod benchmark;
 pub mod fingerprints;
+pub mod fleet;
 pub mod job_id;
 pub mod job_witnesses;
 pub mod memory_proof_store;
 pub mod proof_store;
 pub mod verifier;
-pub mod dump;
\ No newline at end of file
+pub mod dump;
+pub mod priority;
+pub mod status;
@@ -0,0 +1,269 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+};
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    dump::QDependencyMap,
+    job_id::{ProvingJobCircuitType, QJobTopic, QProvingJobDataID},
+};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy, Eq, Hash)]
+pub struct QPrioritizedJob {
+    pub job_id: QProvingJobDataID,
+    pub priority: u32,
+}
+impl QPrioritizedJob {
+    pub fn new(job_id: QProvingJobDataID, priority: u32) -> Self {
+        Self { job_id, priority }
+    }
+    pub fn with_default_priority(jobs: &[QProvingJobDataID]) -> Vec<Self> {
+        jobs.iter().map(|job_id| Self::new(*job_id, 0)).collect()
+    }
+}
+
+// relative proving cost of each circuit, only the ratios matter for prioritization
+pub fn get_circuit_type_priority_weight(circuit_type: ProvingJobCircuitType) -> u32 {
+    match circuit_type {
+        ProvingJobCircuitType::WrapFinalSigHashProofBLS12381 => 64,
+        ProvingJobCircuitType::GenerateFinalSigHashProofGroth16 => 8,
+        ProvingJobCircuitType::Secp256K1SignatureProof => 8,
+        ProvingJobCircuitType::GenerateRollupStateTransitionProof
+        | ProvingJobCircuitType::GenerateSigHashIntrospectionProof
+        | ProvingJobCircuitType::GenerateFinalSigHashProof => 4,
+        ProvingJobCircuitType::AggUserRegisterClaimDepositL2Transfer
+        | ProvingJobCircuitType::AggAddProcessL1WithdrawalAddL1Deposit
+        | ProvingJobCircuitType::WrappedSignatureProof => 2,
+        _ => 1,
+    }
+}
+
+pub fn get_job_priority_weight(job: &QProvingJobDataID) -> u32 {
+    match job.topic {
+        QJobTopic::GenerateStandardProof
+        | QJobTopic::GenerateGroth16Proof
+        | QJobTopic::BlockUserSignatureProof => get_circuit_type_priority_weight(job.circuit_type),
+        // aggregate/notify jobs only bump counters
+        QJobTopic::AggregateJobs | QJobTopic::NotifyOrchestratorComplete => 0,
+    }
+}
+
+fn get_dependents_map(
+    dependency_map: &QDependencyMap,
+) -> HashMap<QProvingJobDataID, Vec<QProvingJobDataID>> {
+    let mut dependents: HashMap<QProvingJobDataID, Vec<QProvingJobDataID>> = HashMap::new();
+    for (parent, dependencies) in dependency_map.dependencies.iter() {
+        for dependency in dependencies.iter() {
+            dependents.entry(*dependency).or_default().push(*parent);
+        }
+    }
+    dependents
+}
+
+// the priority of a job is the weight of the heaviest path from the job to the root (Hu's level),
+// so jobs on the block's critical path are always dispatched before jobs with more slack
+pub fn compute_job_priorities<W: Fn(&QProvingJobDataID) -> u32>(
+    dependency_map: &QDependencyMap,
+    root: QProvingJobDataID,
+    weight: W,
+) -> HashMap<QProvingJobDataID, u32> {
+    // visit the jobs in topological order (root first), so every dependent is final before its dependencies
+    let mut remaining_dependents: HashMap<QProvingJobDataID, usize> = HashMap::new();
+    let mut stack = vec![root];
+    while let Some(job) = stack.pop() {
+        for dependency in dependency_map.get_dependencies(job) {
+            let count = remaining_dependents.entry(dependency).or_insert(0);
+            if *count == 0 {
+                stack.push(dependency);
+            }
+            *count += 1;
+        }
+    }
+
+    let mut priorities = HashMap::with_capacity(remaining_dependents.len() + 1);
+    priorities.insert(root, weight(&root));
+    let mut queue = VecDeque::from([root]);
+    while let Some(job) = queue.pop_front() {
+        let priority = priorities[&job];
+        for dependency in dependency_map.get_dependencies(job) {
+            let dependency_priority = priority + weight(&dependency);
+            let entry = priorities.entry(dependency).or_insert(0);
+            *entry = (*entry).max(dependency_priority);
+
+            let count = remaining_dependents.get_mut(&dependency).unwrap();
+            *count -= 1;
+            if *count == 0 {
+                queue.push_back(dependency);
+            }
+        }
+    }
+    priorities
+}
+
+#[derive(Debug, Clone)]
+pub struct QJobPriorityQueue {
+    jobs: BinaryHeap<(u32, Reverse<u64>, QProvingJobDataID)>,
+    sequence: u64,
+}
+impl QJobPriorityQueue {
+    pub fn new() -> Self {
+        Self {
+            jobs: BinaryHeap::new(),
+            sequence: 0,
+        }
+    }
+    // jobs with the same priority are popped in the order they were pushed
+    pub fn push(&mut self, job: QPrioritizedJob) {
+        self.jobs
+            .push((job.priority, Reverse(self.sequence), job.job_id));
+        self.sequence += 1;
+    }
+    pub fn pop(&mut self) -> Option<QProvingJobDataID> {
+        self.jobs.pop().map(|(_, _, job_id)| job_id)
+    }
+    pub fn len(&self) -> usize {
+        self.jobs.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.jobs.is_empty()
+    }
+}
+impl Extend<QPrioritizedJob> for QJobPriorityQueue {
+    fn extend<T: IntoIterator<Item = QPrioritizedJob>>(&mut self, iter: T) {
+        iter.into_iter().for_each(|job| self.push(job));
+    }
+}
+
+// simulates proving a block with a fixed number of workers, returning the wall clock time to reach the root.
+// without priorities, jobs are dispatched in the order they become ready (the previous FIFO behaviour)
+pub fn simulate_block_proving_time<D: Fn(&QProvingJobDataID) -> u64>(
+    dependency_map: &QDependencyMap,
+    root: QProvingJobDataID,
+    num_workers: usize,
+    duration: D,
+    priorities: Option<&HashMap<QProvingJobDataID, u32>>,
+) -> u64 {
+    assert!(num_workers > 0, "num_workers must be greater than 0");
+    let dependents = get_dependents_map(dependency_map);
+    let mut remaining_dependencies = HashMap::from([(root, 0usize)]);
+    let mut stack = vec![root];
+    while let Some(job) = stack.pop() {
+        let dependencies = dependency_map.get_dependencies(job);
+        *remaining_dependencies.get_mut(&job).unwrap() = dependencies.len();
+        for dependency in dependencies {
+            if !remaining_dependencies.contains_key(&dependency) {
+                remaining_dependencies.insert(dependency, 0);
+                stack.push(dependency);
+            }
+        }
+    }
+    let get_priority = |job: &QProvingJobDataID| {
+        priorities
+            .and_then(|p| p.get(job).copied())
+            .unwrap_or(0)
+    };
+
+    let mut ready = QJobPriorityQueue::new();
+    ready.extend(
+        remaining_dependencies
+            .iter()
+            .filter(|(_, count)| **count == 0)
+            .map(|(job, _)| *job)
+            .sorted()
+            .map(|job| QPrioritizedJob::new(job, get_priority(&job))),
+    );
+
+    let mut running: BinaryHeap<Reverse<(u64, QProvingJobDataID)>> = BinaryHeap::new();
+    let mut now = 0u64;
+    loop {
+        while running.len() < num_workers {
+            match ready.pop() {
+                Some(job) => running.push(Reverse((now + duration(&job), job))),
+                None => break,
+            }
+        }
+        let Some(Reverse((finish_time, job))) = running.pop() else {
+            break;
+        };
+        now = finish_time;
+        for dependent in dependents.get(&job).into_iter().flatten() {
+            let count = remaining_dependencies.get_mut(dependent).unwrap();
+            *count -= 1;
+            if *count == 0 {
+                ready.push(QPrioritizedJob::new(*dependent, get_priority(dependent)));
+            }
+        }
+    }
+    now
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compute_job_priorities, simulate_block_proving_time, QJobPriorityQueue, QPrioritizedJob};
+    use crate::qworker::{
+        dump::QDependencyMap,
+        job_id::{ProvingJobCircuitType, QProvingJobDataID},
+        memory_proof_store::SimpleProofStoreMemory,
+        proof_store::{QProofStoreReaderSync, QProofStoreWriterSync},
+    };
+
+    #[test]
+    fn test_critical_path_priorities() {
+        let checkpoint_id = 1;
+        let root = QProvingJobDataID::notify_block_complete(checkpoint_id);
+        let job = |i: u32| {
+            QProvingJobDataID::core_op_witness(
+                ProvingJobCircuitType::TransferTokensL2,
+                checkpoint_id,
+                i,
+            )
+        };
+        // 6 independent short jobs (0..6) and a long chain (6 <- 7 <- 8 <- 9), all feeding into the root
+        let mut dependency_map = QDependencyMap::new();
+        for i in 0..6 {
+            dependency_map.add_dependency(root, job(i));
+        }
+        dependency_map.add_dependency(root, job(6));
+        dependency_map.add_dependency(job(6), job(7));
+        dependency_map.add_dependency(job(7), job(8));
+        dependency_map.add_dependency(job(8), job(9));
+
+        let weight = |x: &QProvingJobDataID| if *x == root { 0 } else { 1 };
+        let priorities = compute_job_priorities(&dependency_map, root, weight);
+        assert_eq!(priorities[&root], 0);
+        assert_eq!(priorities[&job(9)], 4);
+        assert_eq!(priorities[&job(6)], 1);
+        assert_eq!(priorities[&job(0)], 1);
+
+        let mut queue = QJobPriorityQueue::new();
+        queue.extend([0, 1, 9].map(|i| QPrioritizedJob::new(job(i), priorities[&job(i)])));
+        assert_eq!(queue.pop(), Some(job(9)));
+        assert_eq!(queue.pop(), Some(job(0)));
+        assert_eq!(queue.pop(), Some(job(1)));
+        assert!(queue.is_empty());
+
+        let duration = |x: &QProvingJobDataID| if *x == root { 0 } else { 10 };
+        let fifo_time = simulate_block_proving_time(&dependency_map, root, 2, duration, None);
+        let priority_time =
+            simulate_block_proving_time(&dependency_map, root, 2, duration, Some(&priorities));
+        assert_eq!(fifo_time, 70);
+        assert_eq!(priority_time, 50);
+    }
+
+    #[test]
+    fn test_stored_job_priorities() {
+        let job = QProvingJobDataID::core_op_witness(ProvingJobCircuitType::TransferTokensL2, 1, 0);
+        let mut store = SimpleProofStoreMemory::new();
+        // jobs without a stored priority are FIFO, corrupt priorities are errors
+        assert_eq!(store.get_job_priority(job).unwrap(), 0);
+        store
+            .write_job_priorities(&[(job, 7)].into_iter().collect())
+            .unwrap();
+        assert_eq!(store.get_job_priority(job).unwrap(), 7);
+        store.proofs.insert(job.get_priority_id(), vec![1, 2]);
+        assert!(store.get_job_priority(job).is_err());
+    }
+}
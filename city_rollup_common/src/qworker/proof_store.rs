@@ -1,7 +1,9 @@
 use async_trait::async_trait;
 use plonky2::plonk::{config::GenericConfig, proof::ProofWithPublicInputs};
 
-use super::job_id::QProvingJobDataID;
+use std::collections::HashMap;
+
+use super::{job_id::QProvingJobDataID, priority::QPrioritizedJob};
 
 
 
@@ -11,6 +13,8 @@ pub trait QProofStoreReaderSync {
         id: QProvingJobDataID,
     ) -> anyhow::Result<ProofWithPublicInputs<C::F, C, D>>;
     fn get_bytes_by_id(&self, id: QProvingJobDataID) -> anyhow::Result<Vec<u8>>;
+    fn get_bytes_by_id_if_exists(&self, id: QProvingJobDataID) -> anyhow::Result<Option<Vec<u8>>>;
+    fn get_counter_by_id(&self, id: QProvingJobDataID) -> anyhow::Result<u32>;
     fn get_goal_by_job_id(&self, id: QProvingJobDataID) -> anyhow::Result<u32> {
         let counter_id = id.get_sub_group_counter_id();
         let goal_id = counter_id.get_sub_group_counter_goal_id();
@@ -27,6 +31,29 @@ pub trait QProofStoreReaderSync {
         let next_jobs = self.get_bytes_by_id(next_jobs_id)?;
         Ok(bincode::deserialize(&next_jobs)?)
     }
+    fn get_job_priority(&self, id: QProvingJobDataID) -> anyhow::Result<u32> {
+        // jobs planned without priorities fall back to FIFO order
+        match self.get_bytes_by_id_if_exists(id.get_priority_id())? {
+            Some(priority) => Ok(u32::from_le_bytes(priority.try_into().map_err(
+                |priority: Vec<u8>| {
+                    anyhow::anyhow!(
+                        "expected 4 bytes for the priority of {:?}, got {} bytes",
+                        id,
+                        priority.len()
+                    )
+                },
+            )?)),
+            None => Ok(0),
+        }
+    }
+    fn get_prioritized_jobs(
+        &self,
+        jobs: &[QProvingJobDataID],
+    ) -> anyhow::Result<Vec<QPrioritizedJob>> {
+        jobs.iter()
+            .map(|job| Ok(QPrioritizedJob::new(*job, self.get_job_priority(*job)?)))
+            .collect()
+    }
 }
 
 pub trait QProofStoreWriterSync {
@@ -38,6 +65,9 @@ pub trait QProofStoreWriterSync {
     fn set_bytes_by_id(&mut self, id: QProvingJobDataID, data: &[u8]) -> anyhow::Result<()>;
 
     fn inc_counter_by_id(&mut self, id: QProvingJobDataID) -> anyhow::Result<u32>;
+    // atomically marks the job as completed, returns false if it was already marked. a requeued job
+    // can be completed by two workers, only the first one may bump the group counter
+    fn mark_job_completed(&mut self, id: QProvingJobDataID) -> anyhow::Result<bool>;
     fn write_next_jobs(
         &mut self,
         jobs: &[QProvingJobDataID],
@@ -56,6 +86,15 @@ pub trait QProofStoreWriterSync {
         self.set_bytes_by_id(next_jobs_id, &bincode::serialize(next_jobs)?)?;
         Ok(())
     }
+    fn write_job_priorities(
+        &mut self,
+        priorities: &HashMap<QProvingJobDataID, u32>,
+    ) -> anyhow::Result<()> {
+        for (job, priority) in priorities.iter() {
+            self.set_bytes_by_id(job.get_priority_id(), &priority.to_le_bytes())?;
+        }
+        Ok(())
+    }
 
     fn write_multidimensional_jobs(
         &mut self,
@@ -135,6 +174,14 @@ impl QProofStoreReaderSync for QDummyProofStore {
     fn get_bytes_by_id(&self, _id: QProvingJobDataID) -> anyhow::Result<Vec<u8>> {
         Ok(vec![])
     }
+
+    fn get_bytes_by_id_if_exists(&self, _id: QProvingJobDataID) -> anyhow::Result<Option<Vec<u8>>> {
+        Ok(None)
+    }
+
+    fn get_counter_by_id(&self, _id: QProvingJobDataID) -> anyhow::Result<u32> {
+        Ok(0)
+    }
 }
 impl QProofStoreWriterSync for QDummyProofStore {
     fn set_proof_by_id<C: GenericConfig<D>, const D: usize>(
@@ -153,6 +200,10 @@ impl QProofStoreWriterSync for QDummyProofStore {
         anyhow::bail!("Not implemented")
     }
 
+    fn mark_job_completed(&mut self, _id: QProvingJobDataID) -> anyhow::Result<bool> {
+        anyhow::bail!("Not implemented")
+    }
+
     fn write_next_jobs(
         &mut self,
         _jobs: &[QProvingJobDataID],
@@ -0,0 +1,375 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    dump::{dump_job_dependencies_from_store, QDependencyMap},
+    job_id::{
+        ProvingJobCircuitType, QJobTopic, QProvingJobDataID, QProvingJobDataIDSerializedWrapped,
+    },
+    proof_store::QProofStoreReaderSync,
+};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy, Eq)]
+pub struct QJobGroupProvingStatus {
+    pub counter_id: QProvingJobDataIDSerializedWrapped,
+    pub topic: QJobTopic,
+    pub circuit_type: ProvingJobCircuitType,
+    // distance from the block's NotifyOrchestratorComplete job
+    pub depth: u32,
+    pub completed: u32,
+    pub goal: u32,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy, Eq)]
+pub struct QOutstandingJobStatus {
+    pub job_id: QProvingJobDataIDSerializedWrapped,
+    pub circuit_type: ProvingJobCircuitType,
+    pub depth: u32,
+    pub estimated_duration: u64,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy, Eq)]
+pub struct QCircuitDurationEstimate {
+    pub circuit_type: ProvingJobCircuitType,
+    pub samples: u32,
+    pub average_duration: u64,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Eq)]
+pub struct QBlockProvingStatus {
+    pub checkpoint_id: u64,
+    pub is_complete: bool,
+    pub completed_jobs: u32,
+    pub total_jobs: u32,
+    pub levels: Vec<QJobGroupProvingStatus>,
+    pub outstanding_jobs: Vec<QOutstandingJobStatus>,
+    pub duration_estimates: Vec<QCircuitDurationEstimate>,
+
+    // sum of the estimated durations of all outstanding jobs (ms)
+    pub estimated_remaining_work: u64,
+    // longest chain of outstanding jobs to the root, i.e. the time to completion with enough workers (ms)
+    pub estimated_time_to_completion: u64,
+}
+
+// a missing output means the job is not done, store errors are returned so an outage is not
+// reported as a stalled block
+fn has_output<PS: QProofStoreReaderSync>(
+    store: &PS,
+    job: QProvingJobDataID,
+) -> anyhow::Result<bool> {
+    Ok(store
+        .get_bytes_by_id_if_exists(job.get_output_id())?
+        .is_some_and(|x| !x.is_empty()))
+}
+
+pub fn get_recorded_duration<PS: QProofStoreReaderSync>(
+    store: &PS,
+    job: QProvingJobDataID,
+) -> anyhow::Result<Option<u64>> {
+    match store.get_bytes_by_id_if_exists(job.get_benchmark_id())? {
+        Some(bytes) if !bytes.is_empty() => {
+            let bytes: [u8; 8] = bytes.try_into().map_err(|bytes: Vec<u8>| {
+                anyhow::anyhow!(
+                    "expected 8 bytes for the benchmark of job {:?}, got {} bytes",
+                    job,
+                    bytes.len()
+                )
+            })?;
+            Ok(Some(u64::from_le_bytes(bytes)))
+        }
+        _ => Ok(None),
+    }
+}
+
+fn is_proof_job(job: &QProvingJobDataID) -> bool {
+    job.topic == QJobTopic::GenerateStandardProof || job.topic == QJobTopic::GenerateGroth16Proof
+}
+
+fn compute_job_depths(
+    dependency_map: &QDependencyMap,
+    root: QProvingJobDataID,
+) -> HashMap<QProvingJobDataID, u32> {
+    let mut depths = HashMap::new();
+    let mut current_level = vec![root];
+    let mut depth = 0u32;
+    while !current_level.is_empty() {
+        let mut next_level = vec![];
+        for job in current_level {
+            if depths.contains_key(&job) {
+                continue;
+            }
+            depths.insert(job, depth);
+            next_level.extend(dependency_map.get_dependencies(job));
+        }
+        current_level = next_level;
+        depth += 1;
+    }
+    depths
+}
+
+fn compute_critical_path(
+    dependency_map: &QDependencyMap,
+    job: QProvingJobDataID,
+    remaining: &HashMap<QProvingJobDataID, u64>,
+    memo: &mut HashMap<QProvingJobDataID, u64>,
+) -> u64 {
+    if let Some(result) = memo.get(&job) {
+        return *result;
+    }
+    let longest_dependency = dependency_map
+        .get_dependencies(job)
+        .into_iter()
+        .map(|dep| compute_critical_path(dependency_map, dep, remaining, memo))
+        .max()
+        .unwrap_or(0);
+    let result = longest_dependency + remaining.get(&job).copied().unwrap_or(0);
+    memo.insert(job, result);
+    result
+}
+
+pub fn get_block_proving_status_from_dependency_map<PS: QProofStoreReaderSync>(
+    store: &PS,
+    dependency_map: &QDependencyMap,
+    checkpoint_id: u64,
+) -> anyhow::Result<QBlockProvingStatus> {
+    let root = QProvingJobDataID::notify_block_complete(checkpoint_id);
+    let depths = compute_job_depths(dependency_map, root);
+    let all_jobs = depths
+        .keys()
+        .filter(|x| !x.is_notify_orchestrator_complete())
+        .copied()
+        .sorted()
+        .collect::<Vec<_>>();
+
+    let groups = all_jobs
+        .iter()
+        .copied()
+        .into_group_map_by(|x| x.get_sub_group_counter_id());
+
+    let mut levels = Vec::with_capacity(groups.len());
+    let mut pending_jobs = vec![];
+    let mut durations: HashMap<ProvingJobCircuitType, Vec<u64>> = HashMap::new();
+
+    for (counter_id, jobs) in groups.iter() {
+        let goal = store.get_goal_by_job_id(jobs[0])?;
+        let completed = store.get_counter_by_id(*counter_id)?.min(goal);
+        levels.push(QJobGroupProvingStatus {
+            counter_id: QProvingJobDataIDSerializedWrapped(counter_id.to_fixed_bytes()),
+            topic: counter_id.topic,
+            circuit_type: counter_id.circuit_type,
+            depth: depths[&jobs[0]],
+            completed,
+            goal,
+        });
+
+        for job in jobs.iter() {
+            // aggregate jobs leave no output behind, so they are only known to be done once their whole group is
+            let is_done = if is_proof_job(job) {
+                has_output(store, *job)?
+            } else {
+                completed >= goal
+            };
+            if is_done {
+                if let Some(duration) = get_recorded_duration(store, *job)? {
+                    durations.entry(job.circuit_type).or_default().push(duration);
+                }
+            } else {
+                pending_jobs.push(*job);
+            }
+        }
+    }
+
+    let duration_estimates = durations
+        .iter()
+        .map(|(circuit_type, samples)| QCircuitDurationEstimate {
+            circuit_type: *circuit_type,
+            samples: samples.len() as u32,
+            average_duration: samples.iter().sum::<u64>() / (samples.len() as u64),
+        })
+        .sorted_by_key(|x| x.circuit_type)
+        .collect::<Vec<_>>();
+    let all_samples = durations.values().flatten().copied().collect::<Vec<_>>();
+    let fallback_duration = if all_samples.is_empty() {
+        0
+    } else {
+        all_samples.iter().sum::<u64>() / (all_samples.len() as u64)
+    };
+
+    let remaining = pending_jobs
+        .iter()
+        .map(|job| {
+            let estimated_duration = if is_proof_job(job) {
+                duration_estimates
+                    .iter()
+                    .find(|x| x.circuit_type == job.circuit_type)
+                    .map(|x| x.average_duration)
+                    .unwrap_or(fallback_duration)
+            } else {
+                0
+            };
+            (*job, estimated_duration)
+        })
+        .collect::<HashMap<_, _>>();
+
+    let outstanding_jobs = pending_jobs
+        .iter()
+        .map(|job| QOutstandingJobStatus {
+            job_id: QProvingJobDataIDSerializedWrapped(job.to_fixed_bytes()),
+            circuit_type: job.circuit_type,
+            depth: depths[job],
+            estimated_duration: remaining[job],
+        })
+        .sorted_by_key(|x| std::cmp::Reverse(x.depth))
+        .collect::<Vec<_>>();
+
+    let estimated_time_to_completion =
+        compute_critical_path(dependency_map, root, &remaining, &mut HashMap::new());
+
+    levels.sort_by_key(|x| (std::cmp::Reverse(x.depth), x.counter_id.0));
+
+    Ok(QBlockProvingStatus {
+        checkpoint_id,
+        is_complete: !all_jobs.is_empty() && levels.iter().all(|x| x.completed >= x.goal),
+        completed_jobs: levels.iter().map(|x| x.completed).sum(),
+        total_jobs: levels.iter().map(|x| x.goal).sum(),
+        levels,
+        estimated_remaining_work: outstanding_jobs.iter().map(|x| x.estimated_duration).sum(),
+        outstanding_jobs,
+        duration_estimates,
+        estimated_time_to_completion,
+    })
+}
+
+pub fn get_block_proving_status<PS: QProofStoreReaderSync>(
+    store: &PS,
+    checkpoint_id: u64,
+) -> anyhow::Result<QBlockProvingStatus> {
+    let leaf_jobs_bytes =
+        store.get_bytes_by_id(QProvingJobDataID::block_leaf_jobs_list(checkpoint_id))?;
+    if leaf_jobs_bytes.is_empty() {
+        anyhow::bail!("no jobs have been planned for checkpoint {}", checkpoint_id);
+    }
+    let leaf_jobs: Vec<QProvingJobDataID> = bincode::deserialize(&leaf_jobs_bytes)?;
+    let dependency_map = dump_job_dependencies_from_store(store, &leaf_jobs)?;
+    get_block_proving_status_from_dependency_map(store, &dependency_map, checkpoint_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::plonk::{config::GenericConfig, proof::ProofWithPublicInputs};
+
+    use super::get_block_proving_status;
+    use crate::qworker::{
+        job_id::{ProvingJobCircuitType, QProvingJobDataID},
+        memory_proof_store::SimpleProofStoreMemory,
+        proof_store::{QProofStoreReaderSync, QProofStoreWriterSync},
+    };
+
+    // fails every lookup of data which may be missing, like a proof store which lost its connection
+    struct UnavailableOutputsStore(SimpleProofStoreMemory);
+
+    impl QProofStoreReaderSync for UnavailableOutputsStore {
+        fn get_proof_by_id<C: GenericConfig<D>, const D: usize>(
+            &self,
+            id: QProvingJobDataID,
+        ) -> anyhow::Result<ProofWithPublicInputs<C::F, C, D>> {
+            self.0.get_proof_by_id(id)
+        }
+        fn get_bytes_by_id(&self, id: QProvingJobDataID) -> anyhow::Result<Vec<u8>> {
+            self.0.get_bytes_by_id(id)
+        }
+        fn get_bytes_by_id_if_exists(
+            &self,
+            _id: QProvingJobDataID,
+        ) -> anyhow::Result<Option<Vec<u8>>> {
+            anyhow::bail!("proof store is unavailable")
+        }
+        fn get_counter_by_id(&self, id: QProvingJobDataID) -> anyhow::Result<u32> {
+            self.0.get_counter_by_id(id)
+        }
+    }
+
+    fn plan_block(
+        store: &mut SimpleProofStoreMemory,
+        checkpoint_id: u64,
+    ) -> Vec<QProvingJobDataID> {
+        let leaves = (0..2)
+            .map(|i| {
+                QProvingJobDataID::core_op_witness(
+                    ProvingJobCircuitType::TransferTokensL2,
+                    checkpoint_id,
+                    i,
+                )
+            })
+            .collect::<Vec<_>>();
+        let agg = QProvingJobDataID::get_block_aggregate_jobs_group(checkpoint_id, 1, 0);
+        store.write_next_jobs(&leaves, &[agg]).unwrap();
+        store
+            .write_next_jobs(&[agg], &[QProvingJobDataID::notify_block_complete(checkpoint_id)])
+            .unwrap();
+        store
+            .set_bytes_by_id(
+                QProvingJobDataID::block_leaf_jobs_list(checkpoint_id),
+                &bincode::serialize(&leaves).unwrap(),
+            )
+            .unwrap();
+        leaves
+    }
+
+    #[test]
+    fn test_block_proving_status() {
+        let checkpoint_id = 2;
+        let mut store = SimpleProofStoreMemory::new();
+        let leaves = plan_block(&mut store, checkpoint_id);
+        let agg = QProvingJobDataID::get_block_aggregate_jobs_group(checkpoint_id, 1, 0);
+
+        store.set_bytes_by_id(leaves[0].get_output_id(), &[1u8]).unwrap();
+        store
+            .set_bytes_by_id(leaves[0].get_benchmark_id(), &100u64.to_le_bytes())
+            .unwrap();
+        store
+            .inc_counter_by_id(leaves[0].get_sub_group_counter_id())
+            .unwrap();
+
+        let status = get_block_proving_status(&store, checkpoint_id).unwrap();
+        assert!(!status.is_complete);
+        assert_eq!(status.total_jobs, 3);
+        assert_eq!(status.completed_jobs, 1);
+        assert_eq!(status.levels.len(), 2);
+        assert_eq!(status.levels[0].depth, 2);
+        assert_eq!(status.levels[0].completed, 1);
+        assert_eq!(status.levels[0].goal, 2);
+        assert_eq!(status.outstanding_jobs.len(), 2);
+        assert_eq!(
+            status.outstanding_jobs[0].job_id.0,
+            leaves[1].to_fixed_bytes()
+        );
+        assert_eq!(status.outstanding_jobs[0].estimated_duration, 100);
+        assert_eq!(status.estimated_remaining_work, 100);
+        assert_eq!(status.estimated_time_to_completion, 100);
+
+        store.set_bytes_by_id(leaves[1].get_output_id(), &[1u8]).unwrap();
+        store
+            .inc_counter_by_id(leaves[1].get_sub_group_counter_id())
+            .unwrap();
+        store.inc_counter_by_id(agg.get_sub_group_counter_id()).unwrap();
+
+        let status = get_block_proving_status(&store, checkpoint_id).unwrap();
+        assert!(status.is_complete);
+        assert_eq!(status.completed_jobs, 3);
+        assert!(status.outstanding_jobs.is_empty());
+        assert_eq!(status.estimated_time_to_completion, 0);
+    }
+
+    #[test]
+    fn test_block_proving_status_returns_store_errors() {
+        let checkpoint_id = 2;
+        let mut store = SimpleProofStoreMemory::new();
+        plan_block(&mut store, checkpoint_id);
+        let err = get_block_proving_status(&UnavailableOutputsStore(store), checkpoint_id)
+            .unwrap_err();
+        assert_eq!(err.to_string(), "proof store is unavailable");
+    }
+}
@@ -1,26 +1,34 @@
 use std::sync::Arc;
 
+use city_common::binaryhelpers::bytes::CompressedPublicKey;
 use city_common::data::kv::SimpleKVPair;
 use city_common::data::u8bytes::U8Bytes;
 use city_crypto::hash::base_types::hash160::Hash160;
 use city_crypto::hash::base_types::hash256::Hash256;
 use city_macros::define_table;
 use city_rollup_common::api::data::store::{
-    CityL1DepositJSON, CityL1Withdrawal, CityL2BlockState, CityUserState,
+    CityForcedWithdrawalRequestStatus, CityL1DepositJSON, CityL1Withdrawal, CityL2BlockState,
+    CityTokenBalance, CityTokenInfo, CityUserState,
 };
+use city_rollup_common::link::data::BTCAddress160;
+use city_rollup_common::qworker::fleet::{QWorkerRegistrySync, QWorkerStatus};
 use city_rollup_common::qworker::job_id::{QProvingJobDataID, QProvingJobDataIDSerializedWrapped};
 use city_rollup_common::qworker::proof_store::QProofStoreReaderSync;
-use city_store::config::{CityHash, CityJobWitness, CityMerkleProof};
+use city_rollup_common::qworker::status::{get_block_proving_status, QBlockProvingStatus};
+use city_store::config::{CityHash, CityJobWitness, CityMerkleMultiProof, CityMerkleProof};
 use city_store::store::city::base::CityStore;
 use jsonrpsee::core::async_trait;
 use jsonrpsee::proc_macros::rpc;
 use jsonrpsee::server::Server;
 use jsonrpsee::types::{ErrorCode, ErrorObject, ErrorObjectOwned};
-use kvq_store_redb::KVQReDBStore;
-use redb::{Database, ReadOnlyTable, TableDefinition};
+use kvq_store_redb::{KVQReDBBlockingStore, KVQReDBStore, KVQReDBTableProvider};
+use redb::{Database, ReadOnlyTable, TableDefinition, TableError};
 
 define_table! { KV, &[u8], &[u8] }
 
+// the most leaves a single cr_getUserTreeMultiProof request may open
+pub const MAX_MULTI_PROOF_LEAF_IDS: usize = 256;
+
 use hyper::Method;
 use tower_http::cors::{Any, CorsLayer};
 
@@ -63,6 +71,34 @@ pub trait Rpc {
         leaf_id: u64,
     ) -> Result<CityMerkleProof, ErrorObjectOwned>;
 
+    #[method(name = "getUserTreeMultiProof")]
+    async fn get_user_tree_multi_proof(
+        &self,
+        checkpoint_id: u64,
+        leaf_ids: Vec<u64>,
+    ) -> Result<CityMerkleMultiProof, ErrorObjectOwned>;
+
+    #[method(name = "getTokenBalance")]
+    async fn get_token_balance(
+        &self,
+        checkpoint_id: u64,
+        user_id: u64,
+        token_id: u64,
+    ) -> Result<CityTokenBalance, ErrorObjectOwned>;
+
+    #[method(name = "getTokenInfo")]
+    async fn get_token_info(
+        &self,
+        checkpoint_id: u64,
+        token_id: u64,
+    ) -> Result<CityTokenInfo, ErrorObjectOwned>;
+
+    #[method(name = "getForcedWithdrawalRequests")]
+    async fn get_forced_withdrawal_requests(
+        &self,
+        checkpoint_id: u64,
+    ) -> Result<Vec<CityForcedWithdrawalRequestStatus>, ErrorObjectOwned>;
+
     #[method(name = "getDepositTreeRoot")]
     async fn get_deposit_tree_root(&self, checkpoint_id: u64)
         -> Result<CityHash, ErrorObjectOwned>;
@@ -93,6 +129,13 @@ pub trait Rpc {
         transaction_ids: Vec<Hash256>,
     ) -> Result<Vec<CityL1DepositJSON>, ErrorObjectOwned>;
 
+    #[method(name = "getDepositIdsForPublicKey")]
+    async fn get_deposit_ids_for_public_key(
+        &self,
+        checkpoint_id: u64,
+        public_key: CompressedPublicKey,
+    ) -> Result<Vec<u64>, ErrorObjectOwned>;
+
     #[method(name = "getDepositHash")]
     async fn get_deposit_hash(
         &self,
@@ -154,6 +197,13 @@ pub trait Rpc {
         withdrawal_ids: Vec<u64>,
     ) -> Result<Vec<CityL1Withdrawal>, ErrorObjectOwned>;
 
+    #[method(name = "getWithdrawalIdsForDestination")]
+    async fn get_withdrawal_ids_for_destination(
+        &self,
+        checkpoint_id: u64,
+        destination: BTCAddress160,
+    ) -> Result<Vec<u64>, ErrorObjectOwned>;
+
     #[method(name = "getWithdrawalHash")]
     async fn get_withdrawal_hash(
         &self,
@@ -191,31 +241,54 @@ pub trait Rpc {
         &self,
         keys: Vec<QProvingJobDataIDSerializedWrapped>,
     ) -> Result<Vec<SimpleKVPair<QProvingJobDataIDSerializedWrapped, CityJobWitness>>, ErrorObjectOwned>;
+
+    #[method(name = "getBlockProvingStatus")]
+    async fn get_block_proving_status(
+        &self,
+        checkpoint_id: u64,
+    ) -> Result<QBlockProvingStatus, ErrorObjectOwned>;
+
+    #[method(name = "getWorkers")]
+    async fn get_workers(&self) -> Result<Vec<QWorkerStatus>, ErrorObjectOwned>;
 }
 
 #[derive(Clone)]
-pub struct RpcServerImpl<PS: QProofStoreReaderSync> {
-    db: Arc<Database>,
+pub struct RpcServerImpl<PS: QProofStoreReaderSync, WR: QWorkerRegistrySync> {
+    store: KVQReDBBlockingStore,
     proof_store: PS,
+    worker_registry: WR,
 }
 
-impl<PS: QProofStoreReaderSync> RpcServerImpl<PS> {
-    pub fn query_store<T>(
+impl<PS: QProofStoreReaderSync, WR: QWorkerRegistrySync> RpcServerImpl<PS, WR> {
+    pub fn new(db: Arc<Database>, proof_store: PS, worker_registry: WR) -> Self {
+        Self {
+            store: KVQReDBBlockingStore::new(KVQReDBTableProvider::new(db, KV)),
+            proof_store,
+            worker_registry,
+        }
+    }
+
+    // runs on the blocking thread pool so a slow read does not hold up the other requests
+    pub async fn query_store<T: Send + 'static>(
         &self,
-        f: impl FnOnce(KVQReDBStore<ReadOnlyTable<&'static [u8], &'static [u8]>>) -> anyhow::Result<T>,
+        f: impl FnOnce(KVQReDBStore<ReadOnlyTable<&'static [u8], &'static [u8]>>) -> anyhow::Result<T>
+            + Send
+            + 'static,
     ) -> anyhow::Result<T> {
-        let rxn = self.db.begin_read()?;
-        let table = rxn.open_table(KV)?;
-
-        f(KVQReDBStore::new(table))
+        self.store.run(move |provider| provider.read(f)).await
     }
 }
 
 #[async_trait]
-impl<PS: QProofStoreReaderSync + Clone + Sync + Send + 'static> RpcServer for RpcServerImpl<PS> {
+impl<
+        PS: QProofStoreReaderSync + Clone + Sync + Send + 'static,
+        WR: QWorkerRegistrySync + Clone + Sync + Send + 'static,
+    > RpcServer for RpcServerImpl<PS, WR>
+{
     async fn get_user_tree_root(&self, checkpoint_id: u64) -> Result<CityHash, ErrorObjectOwned> {
         Ok(self
-            .query_store(|store| Ok(CityStore::get_user_tree_root(&store, checkpoint_id)?))
+            .query_store(move |store| Ok(CityStore::get_user_tree_root(&store, checkpoint_id)?))
+            .await
             .map_err(|_| ErrorObject::from(ErrorCode::InternalError))?)
     }
 
@@ -225,7 +298,10 @@ impl<PS: QProofStoreReaderSync + Clone + Sync + Send + 'static> RpcServer for Rp
         user_id: u64,
     ) -> Result<CityUserState, ErrorObjectOwned> {
         Ok(self
-            .query_store(|store| Ok(CityStore::get_user_by_id(&store, checkpoint_id, user_id)?))
+            .query_store(move |store| {
+                Ok(CityStore::get_user_by_id(&store, checkpoint_id, user_id)?)
+            })
+            .await
             .map_err(|_| ErrorObject::from(ErrorCode::InternalError))?)
     }
 
@@ -234,7 +310,57 @@ impl<PS: QProofStoreReaderSync + Clone + Sync + Send + 'static> RpcServer for Rp
         public_key: CityHash,
     ) -> Result<Vec<u64>, ErrorObjectOwned> {
         Ok(self
-            .query_store(|store| Ok(CityStore::get_user_ids_for_public_key(&store, public_key)?))
+            .query_store(move |store| {
+                Ok(CityStore::get_user_ids_for_public_key(&store, public_key)?)
+            })
+            .await
+            .map_err(|_| ErrorObject::from(ErrorCode::InternalError))?)
+    }
+
+    async fn get_token_balance(
+        &self,
+        checkpoint_id: u64,
+        user_id: u64,
+        token_id: u64,
+    ) -> Result<CityTokenBalance, ErrorObjectOwned> {
+        Ok(self
+            .query_store(move |store| {
+                Ok(CityStore::get_token_balance(
+                    &store,
+                    checkpoint_id,
+                    user_id,
+                    token_id,
+                )?)
+            })
+            .await
+            .map_err(|_| ErrorObject::from(ErrorCode::InternalError))?)
+    }
+
+    async fn get_token_info(
+        &self,
+        checkpoint_id: u64,
+        token_id: u64,
+    ) -> Result<CityTokenInfo, ErrorObjectOwned> {
+        Ok(self
+            .query_store(move |store| {
+                Ok(CityStore::get_token_info(&store, checkpoint_id, token_id)?)
+            })
+            .await
+            .map_err(|_| ErrorObject::from(ErrorCode::InternalError))?)
+    }
+
+    async fn get_forced_withdrawal_requests(
+        &self,
+        checkpoint_id: u64,
+    ) -> Result<Vec<CityForcedWithdrawalRequestStatus>, ErrorObjectOwned> {
+        Ok(self
+            .query_store(move |store| {
+                Ok(CityStore::get_forced_withdrawal_requests(
+                    &store,
+                    checkpoint_id,
+                )?)
+            })
+            .await
             .map_err(|_| ErrorObject::from(ErrorCode::InternalError))?)
     }
 
@@ -244,13 +370,14 @@ impl<PS: QProofStoreReaderSync + Clone + Sync + Send + 'static> RpcServer for Rp
         user_id: u64,
     ) -> Result<CityMerkleProof, ErrorObjectOwned> {
         Ok(self
-            .query_store(|store| {
+            .query_store(move |store| {
                 Ok(CityStore::get_user_merkle_proof_by_id(
                     &store,
                     checkpoint_id,
                     user_id,
                 )?)
             })
+            .await
             .map_err(|_| ErrorObject::from(ErrorCode::InternalError))?)
     }
 
@@ -260,13 +387,14 @@ impl<PS: QProofStoreReaderSync + Clone + Sync + Send + 'static> RpcServer for Rp
         leaf_id: u64,
     ) -> Result<CityHash, ErrorObjectOwned> {
         Ok(self
-            .query_store(|store| {
+            .query_store(move |store| {
                 Ok(CityStore::get_user_tree_leaf(
                     &store,
                     checkpoint_id,
                     leaf_id,
                 )?)
             })
+            .await
             .map_err(|_| ErrorObject::from(ErrorCode::InternalError))?)
     }
 
@@ -276,13 +404,34 @@ impl<PS: QProofStoreReaderSync + Clone + Sync + Send + 'static> RpcServer for Rp
         leaf_id: u64,
     ) -> Result<CityMerkleProof, ErrorObjectOwned> {
         Ok(self
-            .query_store(|store| {
+            .query_store(move |store| {
                 Ok(CityStore::get_user_tree_leaf_merkle_proof(
                     &store,
                     checkpoint_id,
                     leaf_id,
                 )?)
             })
+            .await
+            .map_err(|_| ErrorObject::from(ErrorCode::InternalError))?)
+    }
+
+    async fn get_user_tree_multi_proof(
+        &self,
+        checkpoint_id: u64,
+        leaf_ids: Vec<u64>,
+    ) -> Result<CityMerkleMultiProof, ErrorObjectOwned> {
+        if leaf_ids.len() > MAX_MULTI_PROOF_LEAF_IDS {
+            return Err(ErrorObject::from(ErrorCode::InvalidParams));
+        }
+        Ok(self
+            .query_store(move |store| {
+                Ok(CityStore::get_user_tree_multi_proof(
+                    &store,
+                    checkpoint_id,
+                    &leaf_ids,
+                )?)
+            })
+            .await
             .map_err(|_| ErrorObject::from(ErrorCode::InternalError))?)
     }
 
@@ -291,7 +440,8 @@ impl<PS: QProofStoreReaderSync + Clone + Sync + Send + 'static> RpcServer for Rp
         checkpoint_id: u64,
     ) -> Result<CityHash, ErrorObjectOwned> {
         Ok(self
-            .query_store(|store| Ok(CityStore::get_deposit_tree_root(&store, checkpoint_id)?))
+            .query_store(move |store| Ok(CityStore::get_deposit_tree_root(&store, checkpoint_id)?))
+            .await
             .map_err(|_| ErrorObject::from(ErrorCode::InternalError))?)
     }
 
@@ -301,13 +451,14 @@ impl<PS: QProofStoreReaderSync + Clone + Sync + Send + 'static> RpcServer for Rp
         deposit_id: u64,
     ) -> Result<CityL1DepositJSON, ErrorObjectOwned> {
         Ok(self
-            .query_store(|store| {
+            .query_store(move |store| {
                 Ok(CityStore::get_deposit_by_id(
                     &store,
                     checkpoint_id,
                     deposit_id,
                 )?)
             })
+            .await
             .map_err(|_| ErrorObject::from(ErrorCode::InternalError))?
             .to_json_variant())
     }
@@ -318,13 +469,14 @@ impl<PS: QProofStoreReaderSync + Clone + Sync + Send + 'static> RpcServer for Rp
         deposit_ids: Vec<u64>,
     ) -> Result<Vec<CityL1DepositJSON>, ErrorObjectOwned> {
         Ok(self
-            .query_store(|store| {
+            .query_store(move |store| {
                 Ok(CityStore::get_deposits_by_id(
                     &store,
                     checkpoint_id,
                     &deposit_ids,
                 )?)
             })
+            .await
             .map_err(|_| ErrorObject::from(ErrorCode::InternalError))?
             .into_iter()
             .map(|x| x.to_json_variant())
@@ -336,12 +488,13 @@ impl<PS: QProofStoreReaderSync + Clone + Sync + Send + 'static> RpcServer for Rp
         transaction_id: Hash256,
     ) -> Result<CityL1DepositJSON, ErrorObjectOwned> {
         Ok(self
-            .query_store(|store| {
+            .query_store(move |store| {
                 Ok(CityStore::get_deposit_by_txid(
                     &store,
                     transaction_id.reversed(),
                 )?)
             })
+            .await
             .map_err(|_| ErrorObject::from(ErrorCode::InternalError))?
             .to_json_variant())
     }
@@ -351,7 +504,7 @@ impl<PS: QProofStoreReaderSync + Clone + Sync + Send + 'static> RpcServer for Rp
         transaction_ids: Vec<Hash256>,
     ) -> Result<Vec<CityL1DepositJSON>, ErrorObjectOwned> {
         Ok(self
-            .query_store(|store| {
+            .query_store(move |store| {
                 Ok(CityStore::get_deposits_by_txid(
                     &store,
                     &transaction_ids
@@ -360,25 +513,44 @@ impl<PS: QProofStoreReaderSync + Clone + Sync + Send + 'static> RpcServer for Rp
                         .collect::<Vec<_>>(),
                 )?)
             })
+            .await
             .map_err(|_| ErrorObject::from(ErrorCode::InternalError))?
             .into_iter()
             .map(|x| x.to_json_variant())
             .collect::<Vec<_>>())
     }
 
+    async fn get_deposit_ids_for_public_key(
+        &self,
+        checkpoint_id: u64,
+        public_key: CompressedPublicKey,
+    ) -> Result<Vec<u64>, ErrorObjectOwned> {
+        Ok(self
+            .query_store(move |store| {
+                Ok(CityStore::get_deposit_ids_for_public_key(
+                    &store,
+                    checkpoint_id,
+                    public_key,
+                )?)
+            })
+            .await
+            .map_err(|_| ErrorObject::from(ErrorCode::InternalError))?)
+    }
+
     async fn get_deposit_hash(
         &self,
         checkpoint_id: u64,
         deposit_id: u64,
     ) -> Result<CityHash, ErrorObjectOwned> {
         Ok(self
-            .query_store(|store| {
+            .query_store(move |store| {
                 Ok(CityStore::get_deposit_hash(
                     &store,
                     checkpoint_id,
                     deposit_id,
                 )?)
             })
+            .await
             .map_err(|_| ErrorObject::from(ErrorCode::InternalError))?)
     }
 
@@ -388,13 +560,14 @@ impl<PS: QProofStoreReaderSync + Clone + Sync + Send + 'static> RpcServer for Rp
         deposit_id: u64,
     ) -> Result<CityMerkleProof, ErrorObjectOwned> {
         Ok(self
-            .query_store(|store| {
+            .query_store(move |store| {
                 Ok(CityStore::get_deposit_leaf_merkle_proof(
                     &store,
                     checkpoint_id,
                     deposit_id,
                 )?)
             })
+            .await
             .map_err(|_| ErrorObject::from(ErrorCode::InternalError))?)
     }
 
@@ -403,26 +576,32 @@ impl<PS: QProofStoreReaderSync + Clone + Sync + Send + 'static> RpcServer for Rp
         checkpoint_id: u64,
     ) -> Result<CityL2BlockState, ErrorObjectOwned> {
         Ok(self
-            .query_store(|store| Ok(CityStore::get_block_state(&store, checkpoint_id)?))
+            .query_store(move |store| Ok(CityStore::get_block_state(&store, checkpoint_id)?))
+            .await
             .map_err(|_| ErrorObject::from(ErrorCode::InternalError))?)
     }
 
     async fn get_latest_block_state(&self) -> Result<CityL2BlockState, ErrorObjectOwned> {
         Ok(self
-            .query_store(|store| Ok(CityStore::get_latest_block_state(&store)?))
+            .query_store(move |store| Ok(CityStore::get_latest_block_state(&store)?))
+            .await
             .map_err(|_| ErrorObject::from(ErrorCode::InternalError))?)
     }
 
     async fn get_city_root(&self, checkpoint_id: u64) -> Result<CityHash, ErrorObjectOwned> {
         Ok(self
-            .query_store(|store| Ok(CityStore::get_city_root(&store, checkpoint_id)?))
+            .query_store(move |store| Ok(CityStore::get_city_root(&store, checkpoint_id)?))
+            .await
             .map_err(|_| ErrorObject::from(ErrorCode::InternalError))?)
     }
 
     async fn get_city_block_script(&self, checkpoint_id: u64) -> Result<String, ErrorObjectOwned> {
         Ok(hex::encode(
             &self
-                .query_store(|store| Ok(CityStore::get_city_block_script(&store, checkpoint_id)?))
+                .query_store(move |store| {
+                    Ok(CityStore::get_city_block_script(&store, checkpoint_id)?)
+                })
+                .await
                 .map_err(|_| ErrorObject::from(ErrorCode::InternalError))?,
         ))
     }
@@ -432,12 +611,13 @@ impl<PS: QProofStoreReaderSync + Clone + Sync + Send + 'static> RpcServer for Rp
         checkpoint_id: u64,
     ) -> Result<Hash160, ErrorObjectOwned> {
         Ok(self
-            .query_store(|store| {
+            .query_store(move |store| {
                 Ok(CityStore::get_city_block_deposit_address(
                     &store,
                     checkpoint_id,
                 )?)
             })
+            .await
             .map_err(|_| ErrorObject::from(ErrorCode::InternalError))?)
     }
 
@@ -446,12 +626,13 @@ impl<PS: QProofStoreReaderSync + Clone + Sync + Send + 'static> RpcServer for Rp
         checkpoint_id: u64,
     ) -> Result<String, ErrorObjectOwned> {
         Ok(self
-            .query_store(|store| {
+            .query_store(move |store| {
                 Ok(CityStore::get_city_block_deposit_address_string(
                     &store,
                     checkpoint_id,
                 )?)
             })
+            .await
             .map_err(|_| ErrorObject::from(ErrorCode::InternalError))?)
     }
 
@@ -460,7 +641,10 @@ impl<PS: QProofStoreReaderSync + Clone + Sync + Send + 'static> RpcServer for Rp
         checkpoint_id: u64,
     ) -> Result<CityHash, ErrorObjectOwned> {
         Ok(self
-            .query_store(|store| Ok(CityStore::get_withdrawal_tree_root(&store, checkpoint_id)?))
+            .query_store(move |store| {
+                Ok(CityStore::get_withdrawal_tree_root(&store, checkpoint_id)?)
+            })
+            .await
             .map_err(|_| ErrorObject::from(ErrorCode::InternalError))?)
     }
 
@@ -470,13 +654,14 @@ impl<PS: QProofStoreReaderSync + Clone + Sync + Send + 'static> RpcServer for Rp
         withdrawal_id: u64,
     ) -> Result<CityL1Withdrawal, ErrorObjectOwned> {
         Ok(self
-            .query_store(|store| {
+            .query_store(move |store| {
                 Ok(CityStore::get_withdrawal_by_id(
                     &store,
                     checkpoint_id,
                     withdrawal_id,
                 )?)
             })
+            .await
             .map_err(|_| ErrorObject::from(ErrorCode::InternalError))?)
     }
 
@@ -486,13 +671,31 @@ impl<PS: QProofStoreReaderSync + Clone + Sync + Send + 'static> RpcServer for Rp
         withdrawal_ids: Vec<u64>,
     ) -> Result<Vec<CityL1Withdrawal>, ErrorObjectOwned> {
         Ok(self
-            .query_store(|store| {
+            .query_store(move |store| {
                 Ok(CityStore::get_withdrawals_by_id(
                     &store,
                     checkpoint_id,
                     &withdrawal_ids,
                 )?)
             })
+            .await
+            .map_err(|_| ErrorObject::from(ErrorCode::InternalError))?)
+    }
+
+    async fn get_withdrawal_ids_for_destination(
+        &self,
+        checkpoint_id: u64,
+        destination: BTCAddress160,
+    ) -> Result<Vec<u64>, ErrorObjectOwned> {
+        Ok(self
+            .query_store(move |store| {
+                Ok(CityStore::get_withdrawal_ids_for_destination(
+                    &store,
+                    checkpoint_id,
+                    destination,
+                )?)
+            })
+            .await
             .map_err(|_| ErrorObject::from(ErrorCode::InternalError))?)
     }
 
@@ -502,13 +705,14 @@ impl<PS: QProofStoreReaderSync + Clone + Sync + Send + 'static> RpcServer for Rp
         withdrawal_id: u64,
     ) -> Result<CityHash, ErrorObjectOwned> {
         Ok(self
-            .query_store(|store| {
+            .query_store(move |store| {
                 Ok(CityStore::get_withdrawal_hash(
                     &store,
                     checkpoint_id,
                     withdrawal_id,
                 )?)
             })
+            .await
             .map_err(|_| ErrorObject::from(ErrorCode::InternalError))?)
     }
 
@@ -518,13 +722,14 @@ impl<PS: QProofStoreReaderSync + Clone + Sync + Send + 'static> RpcServer for Rp
         withdrawal_id: u64,
     ) -> Result<CityMerkleProof, ErrorObjectOwned> {
         Ok(self
-            .query_store(|store| {
+            .query_store(move |store| {
                 Ok(CityStore::get_withdrawal_leaf_merkle_proof(
                     &store,
                     checkpoint_id,
                     withdrawal_id,
                 )?)
             })
+            .await
             .map_err(|_| ErrorObject::from(ErrorCode::InternalError))?)
     }
 
@@ -596,13 +801,38 @@ impl<PS: QProofStoreReaderSync + Clone + Sync + Send + 'static> RpcServer for Rp
         }).collect::<Result<Vec<SimpleKVPair<QProvingJobDataIDSerializedWrapped, CityJobWitness>>, ErrorObjectOwned>>()
 
     }
+
+    async fn get_block_proving_status(
+        &self,
+        checkpoint_id: u64,
+    ) -> Result<QBlockProvingStatus, ErrorObjectOwned> {
+        get_block_proving_status(&self.proof_store, checkpoint_id).map_err(|err| {
+            ErrorObject::owned(ErrorCode::InternalError.code(), err.to_string(), None::<()>)
+        })
+    }
+
+    async fn get_workers(&self) -> Result<Vec<QWorkerStatus>, ErrorObjectOwned> {
+        self.worker_registry
+            .get_worker_statuses()
+            .map_err(|_| ErrorObject::from(ErrorCode::InternalError))
+    }
 }
 
-pub async fn run_server<PS: QProofStoreReaderSync + Send + Sync + Clone + 'static>(
+pub async fn run_server<
+    PS: QProofStoreReaderSync + Send + Sync + Clone + 'static,
+    WR: QWorkerRegistrySync + Send + Sync + Clone + 'static,
+>(
     server_addr: String,
     db: Arc<Database>,
     proof_store: PS,
+    worker_registry: WR,
 ) -> anyhow::Result<()> {
+    // refuse to serve a store with another schema version, a new database has no table yet
+    match db.begin_read()?.open_table(KV) {
+        Ok(table) => CityStore::check_schema_version(&KVQReDBStore::new(table))?,
+        Err(TableError::TableDoesNotExist(_)) => {}
+        Err(err) => return Err(err.into()),
+    }
 
 	let cors = CorsLayer::new()
         // Allow `POST` when accessing the resource
@@ -614,8 +844,91 @@ pub async fn run_server<PS: QProofStoreReaderSync + Send + Sync + Clone + 'stati
     let server = Server::builder().set_http_middleware(middleware).build(server_addr).await?;
 
 
-    let rpc_server_impl = RpcServerImpl { db, proof_store };
+    let rpc_server_impl = RpcServerImpl::new(db, proof_store, worker_registry);
     let handle = server.start(rpc_server_impl.into_rpc());
     tokio::spawn(handle.stopped());
     Ok(futures::future::pending::<()>().await)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    use city_rollup_common::qworker::fleet::QWorkerRegistryMemory;
+    use city_rollup_common::qworker::memory_proof_store::SimpleProofStoreMemory;
+    use redb::backends::InMemoryBackend;
+
+    use super::*;
+
+    fn test_server() -> RpcServerImpl<SimpleProofStoreMemory, QWorkerRegistryMemory> {
+        let db = Arc::new(
+            Database::builder()
+                .create_with_backend(InMemoryBackend::new())
+                .unwrap(),
+        );
+        let wxn = db.begin_write().unwrap();
+        {
+            let table = wxn.open_table(KV).unwrap();
+            let mut store = KVQReDBStore::new(table);
+            CityStore::set_block_state(
+                &mut store,
+                &CityL2BlockState {
+                    checkpoint_id: 1,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        }
+        wxn.commit().unwrap();
+        RpcServerImpl::new(
+            db,
+            SimpleProofStoreMemory::new(),
+            QWorkerRegistryMemory::new(),
+        )
+    }
+
+    // tokio::test runs on a single thread, so the requests only overlap if the store reads are
+    // moved off of it. the first read is only released once the handler has answered, so a
+    // serialized handler would leave it waiting until the timeout instead.
+    #[tokio::test]
+    async fn concurrent_requests_are_not_serialized() {
+        let server = test_server();
+        let (release, released) = mpsc::channel::<()>();
+        let blocked = server.query_store(move |store| {
+            released.recv_timeout(Duration::from_secs(30))?;
+            Ok(CityStore::get_block_state(&store, 1)?)
+        });
+        let handler = async {
+            let state = RpcServer::get_latest_block_state(&server).await.unwrap();
+            release.send(()).unwrap();
+            state
+        };
+        let (blocked, state) = futures::join!(blocked, handler);
+
+        assert_eq!(blocked.unwrap().checkpoint_id, 1);
+        assert_eq!(state.checkpoint_id, 1);
+    }
+
+    #[tokio::test]
+    async fn multi_proof_leaf_ids_are_capped() {
+        let server = test_server();
+        let proof = RpcServer::get_user_tree_multi_proof(
+            &server,
+            1,
+            (0..MAX_MULTI_PROOF_LEAF_IDS as u64).collect(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(proof.indices.len(), MAX_MULTI_PROOF_LEAF_IDS);
+
+        let err = RpcServer::get_user_tree_multi_proof(
+            &server,
+            1,
+            (0..=MAX_MULTI_PROOF_LEAF_IDS as u64).collect(),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.code(), ErrorCode::InvalidParams.code());
+    }
+}
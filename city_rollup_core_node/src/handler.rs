@@ -11,8 +11,15 @@ use city_rollup_worker_dispatch::implementations::redis::QueueCmd;
 use city_rollup_worker_dispatch::implementations::redis::RedisQueue;
 use city_rollup_worker_dispatch::implementations::redis::Q_CMD;
 use city_rollup_worker_dispatch::implementations::redis::Q_RPC_ADD_WITHDRAWAL;
+use city_rollup_worker_dispatch::implementations::redis::Q_RPC_BATCH_TOKEN_TRANSFER;
+use city_rollup_worker_dispatch::implementations::redis::Q_RPC_BURN_TOKEN;
+use city_rollup_worker_dispatch::implementations::redis::Q_RPC_CANCEL_WITHDRAWAL;
+use city_rollup_worker_dispatch::implementations::redis::Q_RPC_CHANGE_PUBLIC_KEY;
 use city_rollup_worker_dispatch::implementations::redis::Q_RPC_CLAIM_DEPOSIT;
+use city_rollup_worker_dispatch::implementations::redis::Q_RPC_MINT_TOKEN;
+use city_rollup_worker_dispatch::implementations::redis::Q_RPC_OPEN_TOKEN_ACCOUNT;
 use city_rollup_worker_dispatch::implementations::redis::Q_RPC_REGISTER_USER;
+use city_rollup_worker_dispatch::implementations::redis::Q_RPC_SEND_TOKEN;
 use city_rollup_worker_dispatch::implementations::redis::Q_RPC_TOKEN_TRANSFER;
 use city_rollup_worker_dispatch::traits::proving_dispatcher::ProvingDispatcher;
 use http_body_util::BodyExt;
@@ -101,6 +108,34 @@ impl<F: RichField> CityRollupRPCServerHandler<F> {
                 request: RegisterUser(req),
                 ..
             }) => self.register_user(req).map(|r| json!(r)),
+            Ok(RpcRequest {
+                request: ChangePublicKey(req),
+                ..
+            }) => self.change_public_key(req).await.map(|r| json!(r)),
+            Ok(RpcRequest {
+                request: BatchTokenTransfer(req),
+                ..
+            }) => self.batch_token_transfer(req).await.map(|r| json!(r)),
+            Ok(RpcRequest {
+                request: OpenTokenAccount(req),
+                ..
+            }) => self.open_token_account(req).await.map(|r| json!(r)),
+            Ok(RpcRequest {
+                request: MintToken(req),
+                ..
+            }) => self.mint_token(req).await.map(|r| json!(r)),
+            Ok(RpcRequest {
+                request: SendToken(req),
+                ..
+            }) => self.send_token(req).await.map(|r| json!(r)),
+            Ok(RpcRequest {
+                request: BurnToken(req),
+                ..
+            }) => self.burn_token(req).await.map(|r| json!(r)),
+            Ok(RpcRequest {
+                request: CancelWithdrawal(req),
+                ..
+            }) => self.cancel_withdrawal(req).await.map(|r| json!(r)),
             Ok(RpcRequest {
                 request: ProduceBlock,
                 ..
@@ -187,6 +222,67 @@ impl<F: RichField> CityRollupRPCServerHandler<F> {
         Ok(())
     }
 
+    async fn change_public_key(
+        &mut self,
+        req: CityChangePublicKeyRPCRequest<F>,
+    ) -> Result<(), anyhow::Error> {
+        self.verify_signature_proof(req.user_id, req.signature_proof.clone())
+            .await?;
+        self.notify_rpc_change_public_key(&req)?;
+        Ok(())
+    }
+
+    async fn batch_token_transfer(
+        &mut self,
+        req: CityBatchTokenTransferRPCRequest,
+    ) -> Result<(), anyhow::Error> {
+        self.verify_signature_proof(req.user_id, req.signature_proof.clone())
+            .await?;
+        self.notify_rpc_batch_token_transfer(&req)?;
+        Ok(())
+    }
+
+    async fn open_token_account(
+        &mut self,
+        req: CityOpenTokenAccountRPCRequest,
+    ) -> Result<(), anyhow::Error> {
+        self.verify_signature_proof(req.user_id, req.signature_proof.clone())
+            .await?;
+        self.notify_rpc_open_token_account(&req)?;
+        Ok(())
+    }
+
+    async fn mint_token(&mut self, req: CityMintTokenRPCRequest) -> Result<(), anyhow::Error> {
+        self.verify_signature_proof(req.user_id, req.signature_proof.clone())
+            .await?;
+        self.notify_rpc_mint_token(&req)?;
+        Ok(())
+    }
+
+    async fn send_token(&mut self, req: CitySendTokenRPCRequest) -> Result<(), anyhow::Error> {
+        self.verify_signature_proof(req.user_id, req.signature_proof.clone())
+            .await?;
+        self.notify_rpc_send_token(&req)?;
+        Ok(())
+    }
+
+    async fn burn_token(&mut self, req: CityBurnTokenRPCRequest) -> Result<(), anyhow::Error> {
+        self.verify_signature_proof(req.user_id, req.signature_proof.clone())
+            .await?;
+        self.notify_rpc_burn_token(&req)?;
+        Ok(())
+    }
+
+    async fn cancel_withdrawal(
+        &mut self,
+        req: CityCancelWithdrawalRPCRequest<F>,
+    ) -> Result<(), anyhow::Error> {
+        self.verify_signature_proof(req.user_id, req.signature_proof.clone())
+            .await?;
+        self.notify_rpc_cancel_withdrawal(&req)?;
+        Ok(())
+    }
+
     async fn verify_signature_proof(
         &self,
         _user_id: u64,
@@ -239,6 +335,57 @@ impl<F: RichField> OrchestratorRPCEventSenderSync<F> for CityRollupRPCServerHand
         Ok(())
     }
 
+    fn notify_rpc_change_public_key(
+        &mut self,
+        event: &CityChangePublicKeyRPCRequest<F>,
+    ) -> anyhow::Result<()> {
+        self.tx_queue
+            .dispatch(Q_RPC_CHANGE_PUBLIC_KEY, event.clone())?;
+        Ok(())
+    }
+
+    fn notify_rpc_batch_token_transfer(
+        &mut self,
+        event: &CityBatchTokenTransferRPCRequest,
+    ) -> anyhow::Result<()> {
+        self.tx_queue
+            .dispatch(Q_RPC_BATCH_TOKEN_TRANSFER, event.clone())?;
+        Ok(())
+    }
+
+    fn notify_rpc_open_token_account(
+        &mut self,
+        event: &CityOpenTokenAccountRPCRequest,
+    ) -> anyhow::Result<()> {
+        self.tx_queue
+            .dispatch(Q_RPC_OPEN_TOKEN_ACCOUNT, event.clone())?;
+        Ok(())
+    }
+
+    fn notify_rpc_mint_token(&mut self, event: &CityMintTokenRPCRequest) -> anyhow::Result<()> {
+        self.tx_queue.dispatch(Q_RPC_MINT_TOKEN, event.clone())?;
+        Ok(())
+    }
+
+    fn notify_rpc_send_token(&mut self, event: &CitySendTokenRPCRequest) -> anyhow::Result<()> {
+        self.tx_queue.dispatch(Q_RPC_SEND_TOKEN, event.clone())?;
+        Ok(())
+    }
+
+    fn notify_rpc_burn_token(&mut self, event: &CityBurnTokenRPCRequest) -> anyhow::Result<()> {
+        self.tx_queue.dispatch(Q_RPC_BURN_TOKEN, event.clone())?;
+        Ok(())
+    }
+
+    fn notify_rpc_cancel_withdrawal(
+        &mut self,
+        event: &CityCancelWithdrawalRPCRequest<F>,
+    ) -> anyhow::Result<()> {
+        self.tx_queue
+            .dispatch(Q_RPC_CANCEL_WITHDRAWAL, event.clone())?;
+        Ok(())
+    }
+
     fn notify_rpc_produce_block(&mut self) -> anyhow::Result<()> {
         self.tx_queue.dispatch(Q_CMD, QueueCmd::ProduceBlock)?;
         Ok(())
@@ -1,8 +1,15 @@
 use std::borrow::Cow;
 
 use city_rollup_common::api::data::block::rpc_request::CityAddWithdrawalRPCRequest;
+use city_rollup_common::api::data::block::rpc_request::CityBatchTokenTransferRPCRequest;
+use city_rollup_common::api::data::block::rpc_request::CityBurnTokenRPCRequest;
+use city_rollup_common::api::data::block::rpc_request::CityCancelWithdrawalRPCRequest;
+use city_rollup_common::api::data::block::rpc_request::CityChangePublicKeyRPCRequest;
 use city_rollup_common::api::data::block::rpc_request::CityClaimDepositRPCRequest;
+use city_rollup_common::api::data::block::rpc_request::CityMintTokenRPCRequest;
+use city_rollup_common::api::data::block::rpc_request::CityOpenTokenAccountRPCRequest;
 use city_rollup_common::api::data::block::rpc_request::CityRegisterUserRPCRequest;
+use city_rollup_common::api::data::block::rpc_request::CitySendTokenRPCRequest;
 use city_rollup_common::api::data::block::rpc_request::CityTokenTransferRPCRequest;
 use jsonrpsee::core::traits::ToRpcParams;
 use plonky2::hash::hash_types::RichField;
@@ -41,6 +48,20 @@ pub enum RequestParams<F: RichField> {
     AddWithdrawal(CityAddWithdrawalRPCRequest),
     #[serde(rename = "cr_register_user")]
     RegisterUser(CityRegisterUserRPCRequest<F>),
+    #[serde(rename = "cr_change_public_key")]
+    ChangePublicKey(CityChangePublicKeyRPCRequest<F>),
+    #[serde(rename = "cr_batch_token_transfer")]
+    BatchTokenTransfer(CityBatchTokenTransferRPCRequest),
+    #[serde(rename = "cr_open_token_account")]
+    OpenTokenAccount(CityOpenTokenAccountRPCRequest),
+    #[serde(rename = "cr_mint_token")]
+    MintToken(CityMintTokenRPCRequest),
+    #[serde(rename = "cr_send_token")]
+    SendToken(CitySendTokenRPCRequest),
+    #[serde(rename = "cr_burn_token")]
+    BurnToken(CityBurnTokenRPCRequest),
+    #[serde(rename = "cr_cancel_withdrawal")]
+    CancelWithdrawal(CityCancelWithdrawalRPCRequest<F>),
     #[serde(rename = "cr_produce_block")]
     ProduceBlock,
 }
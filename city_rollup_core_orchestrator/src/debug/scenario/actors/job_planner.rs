@@ -1,4 +1,9 @@
-use city_rollup_common::qworker::{job_id::QProvingJobDataID, proof_store::QProofStore};
+use city_rollup_common::qworker::{
+    dump::dump_job_dependencies_from_store,
+    job_id::QProvingJobDataID,
+    priority::{compute_job_priorities, get_job_priority_weight},
+    proof_store::QProofStore,
+};
 
 use crate::debug::scenario::block_planner::transition::CityOpJobIds;
 
@@ -86,12 +91,30 @@ pub fn plan_jobs<PS: QProofStore>(
         QProvingJobDataID::get_block_aggregate_jobs_group(checkpoint_id, op_agg_group_part_1_id, 1);
     let transfer_tokens_agg_job_id =
         QProvingJobDataID::get_block_aggregate_jobs_group(checkpoint_id, op_agg_group_part_1_id, 2);
+    let change_public_keys_agg_job_id =
+        QProvingJobDataID::get_block_aggregate_jobs_group(checkpoint_id, op_agg_group_part_1_id, 3);
+    let batch_token_transfers_agg_job_id =
+        QProvingJobDataID::get_block_aggregate_jobs_group(checkpoint_id, op_agg_group_part_1_id, 4);
+    let open_token_accounts_agg_job_id =
+        QProvingJobDataID::get_block_aggregate_jobs_group(checkpoint_id, op_agg_group_part_1_id, 5);
+    let mint_tokens_agg_job_id =
+        QProvingJobDataID::get_block_aggregate_jobs_group(checkpoint_id, op_agg_group_part_1_id, 6);
+    let send_tokens_agg_job_id =
+        QProvingJobDataID::get_block_aggregate_jobs_group(checkpoint_id, op_agg_group_part_1_id, 7);
+    let burn_tokens_agg_job_id =
+        QProvingJobDataID::get_block_aggregate_jobs_group(checkpoint_id, op_agg_group_part_1_id, 8);
 
     proof_store.write_next_jobs(
         &[
             register_users_agg_job_id,
             claim_deposits_agg_job_id,
             transfer_tokens_agg_job_id,
+            change_public_keys_agg_job_id,
+            batch_token_transfers_agg_job_id,
+            open_token_accounts_agg_job_id,
+            mint_tokens_agg_job_id,
+            send_tokens_agg_job_id,
+            burn_tokens_agg_job_id,
         ],
         &[state_part_1_id],
     )?;
@@ -103,12 +126,15 @@ pub fn plan_jobs<PS: QProofStore>(
         QProvingJobDataID::get_block_aggregate_jobs_group(checkpoint_id, op_agg_group_part_2_id, 1);
     let add_deposits_agg_job_id =
         QProvingJobDataID::get_block_aggregate_jobs_group(checkpoint_id, op_agg_group_part_2_id, 2);
+    let cancel_withdrawals_agg_job_id =
+        QProvingJobDataID::get_block_aggregate_jobs_group(checkpoint_id, op_agg_group_part_2_id, 3);
 
     proof_store.write_next_jobs(
         &[
             add_withdrawals_agg_job_id,
             process_withdrawals_agg_job_id,
             add_deposits_agg_job_id,
+            cancel_withdrawals_agg_job_id,
         ],
         &[state_part_2_id],
     )?;
@@ -125,11 +151,39 @@ pub fn plan_jobs<PS: QProofStore>(
         &block_op_job_ids.token_transfer_job_ids,
         &[transfer_tokens_agg_job_id],
     )?;
+    proof_store.write_multidimensional_jobs(
+        &block_op_job_ids.change_public_key_job_ids,
+        &[change_public_keys_agg_job_id],
+    )?;
+    proof_store.write_multidimensional_jobs(
+        &block_op_job_ids.batch_token_transfer_job_ids,
+        &[batch_token_transfers_agg_job_id],
+    )?;
+    proof_store.write_multidimensional_jobs(
+        &block_op_job_ids.open_token_account_job_ids,
+        &[open_token_accounts_agg_job_id],
+    )?;
+    proof_store.write_multidimensional_jobs(
+        &block_op_job_ids.mint_token_job_ids,
+        &[mint_tokens_agg_job_id],
+    )?;
+    proof_store.write_multidimensional_jobs(
+        &block_op_job_ids.send_token_job_ids,
+        &[send_tokens_agg_job_id],
+    )?;
+    proof_store.write_multidimensional_jobs(
+        &block_op_job_ids.burn_token_job_ids,
+        &[burn_tokens_agg_job_id],
+    )?;
 
     proof_store.write_multidimensional_jobs(
         &block_op_job_ids.add_withdrawal_job_ids,
         &[add_withdrawals_agg_job_id],
     )?;
+    proof_store.write_multidimensional_jobs(
+        &block_op_job_ids.cancel_withdrawal_job_ids,
+        &[cancel_withdrawals_agg_job_id],
+    )?;
     proof_store.write_multidimensional_jobs(
         &block_op_job_ids.process_withdrawal_job_ids,
         &[process_withdrawals_agg_job_id],
@@ -144,11 +198,31 @@ pub fn plan_jobs<PS: QProofStore>(
         block_op_job_ids.register_user_job_ids[0].to_vec(),
         block_op_job_ids.claim_deposit_job_ids[0].to_vec(),
         block_op_job_ids.token_transfer_job_ids[0].to_vec(),
+        block_op_job_ids.change_public_key_job_ids[0].to_vec(),
+        block_op_job_ids.batch_token_transfer_job_ids[0].to_vec(),
+        block_op_job_ids.open_token_account_job_ids[0].to_vec(),
+        block_op_job_ids.mint_token_job_ids[0].to_vec(),
+        block_op_job_ids.send_token_job_ids[0].to_vec(),
+        block_op_job_ids.burn_token_job_ids[0].to_vec(),
         block_op_job_ids.add_withdrawal_job_ids[0].to_vec(),
+        block_op_job_ids.cancel_withdrawal_job_ids[0].to_vec(),
         block_op_job_ids.process_withdrawal_job_ids[0].to_vec(),
         block_op_job_ids.add_deposit_job_ids[0].to_vec(),
     ]
     .concat();
 
+    proof_store.set_bytes_by_id(
+        QProvingJobDataID::block_leaf_jobs_list(checkpoint_id),
+        &bincode::serialize(&leaf_jobs)?,
+    )?;
+
+    let dependency_map = dump_job_dependencies_from_store(proof_store, &leaf_jobs)?;
+    let priorities = compute_job_priorities(
+        &dependency_map,
+        QProvingJobDataID::notify_block_complete(checkpoint_id),
+        get_job_priority_weight,
+    );
+    proof_store.write_job_priorities(&priorities)?;
+
     Ok(leaf_jobs)
 }
@@ -1,5 +1,8 @@
 use city_common::{
-    config::rollup_constants::{BLOCK_SCRIPT_SPEND_BASE_FEE_AMOUNT, WITHDRAWAL_FEE_AMOUNT},
+    config::rollup_constants::{
+        BLOCK_SCRIPT_SPEND_BASE_FEE_AMOUNT, FORCED_WITHDRAWAL_REQUEST_MIN_VALUE,
+        WITHDRAWAL_FEE_AMOUNT,
+    },
     logging::debug_timer::DebugTimer,
 };
 use city_crypto::hash::base_types::{felt252::felt252_hashout_to_hash256_le, hash160::Hash160, hash256::Hash256};
@@ -11,9 +14,14 @@ use city_rollup_common::{
     },
     api::data::store::CityL1Withdrawal,
     block_template::{data::CityGroth16ProofData, BLOCK_GROTH16_ENCODED_VERIFIER_DATA},
-    config::sighash_wrapper_config::SIGHASH_CIRCUIT_MAX_WITHDRAWALS,
+    config::sighash_wrapper_config::{
+        SIGHASH_CIRCUIT_MAX_FORCED_WITHDRAWAL_REQUESTS, SIGHASH_CIRCUIT_MAX_WITHDRAWALS,
+    },
     introspection::{
-        rollup::introspection::BlockSpendIntrospectionHint,
+        rollup::{
+            forced_withdrawal::CityForcedWithdrawalRequest,
+            introspection::BlockSpendIntrospectionHint,
+        },
         sighash::{SigHashPreimage, SIGHASH_ALL},
         transaction::{BTCTransaction, BTCTransactionInput, BTCTransactionOutput},
     },
@@ -148,7 +156,7 @@ impl SimpleActorOrchestrator {
                 fingerprints,
                 sighash_whitelist_tree,
             )?;
-        worker_queue.enqueue_jobs(&leaf_jobs)?;
+        worker_queue.enqueue_prioritized_jobs(&proof_store.get_prioritized_jobs(&leaf_jobs)?)?;
         Ok(SimpleActorOrchestratorProduceBlockStep1Result {
             checkpoint_id,
             num_input_witnesses,
@@ -233,8 +241,15 @@ impl SimpleActorOrchestrator {
 
         let register_users = event_receiver.flush_register_users()?;
         let claim_l1_deposits = event_receiver.flush_claim_deposits()?;
-        let add_withdrawals = event_receiver.flush_add_withdrawals()?;
+        let mut add_withdrawals = event_receiver.flush_add_withdrawals()?;
         let token_transfers = event_receiver.flush_token_transfers()?;
+        let change_public_keys = event_receiver.flush_change_public_keys()?;
+        let batch_token_transfers = event_receiver.flush_batch_token_transfers()?;
+        let open_token_accounts = event_receiver.flush_open_token_accounts()?;
+        let mint_tokens = event_receiver.flush_mint_tokens()?;
+        let send_tokens = event_receiver.flush_send_tokens()?;
+        let burn_tokens = event_receiver.flush_burn_tokens()?;
+        let cancel_withdrawals = event_receiver.flush_cancel_withdrawals()?;
         tracing::info!(
             "last_block_address: {}",
             BTCAddress160::new_p2sh(last_block_address,).to_address_string()
@@ -258,12 +273,20 @@ impl SimpleActorOrchestrator {
             .collect::<Vec<BTCTransaction>>();
 
         let mut deposit_utxos = vec![];
+        let mut forced_withdrawal_requests = vec![];
+        let mut forced_withdrawal_request_utxos = vec![];
         let mut last_block_utxo = BTCTransaction::dummy();
         for utxo in utxos.into_iter() {
             //tracing::info!("utxos: {}", hex::encode(&utxo.to_bytes()));
 
             if utxo.is_block_spend_for_state(last_block_address) {
                 last_block_utxo = utxo;
+            } else if let Some(request) = CityForcedWithdrawalRequest::from_funding_transaction(
+                &utxo,
+                FORCED_WITHDRAWAL_REQUEST_MIN_VALUE,
+            ) {
+                forced_withdrawal_requests.push(request);
+                forced_withdrawal_request_utxos.push((utxo, request));
             } else if utxo.is_p2pkh() {
                 deposit_utxos.push(utxo);
             } else {
@@ -282,12 +305,66 @@ impl SimpleActorOrchestrator {
         let mut all_inputs = vec![last_block_utxo];
         all_inputs.append(&mut deposit_utxos);
 
+        CityStore::add_forced_withdrawal_requests(
+            store,
+            checkpoint_id,
+            &forced_withdrawal_requests,
+        )?;
+        let pending_forced_withdrawals =
+            CityStore::get_pending_forced_withdrawal_requests(store, checkpoint_id)?;
+        // withdrawals which satisfy a forced withdrawal request are added (and therefore processed on L1) first
+        add_withdrawals.sort_by_key(|w| {
+            !pending_forced_withdrawals
+                .iter()
+                .any(|status| status.request.is_satisfied_by(w))
+        });
+
+        // a request can only be spent (and is credited back as a deposit) if one of the withdrawals processed
+        // by this block satisfies it, the sighash circuit checks this against the block's withdrawal outputs
+        let last_block_pending_withdrawals =
+            (last_block.next_add_withdrawal_id - last_block.next_process_withdrawal_id) as usize;
+        let num_new_withdrawals_processed = SIGHASH_CIRCUIT_MAX_WITHDRAWALS
+            .saturating_sub(last_block_pending_withdrawals)
+            .min(add_withdrawals.len());
+        let mut used_withdrawals = vec![false; num_new_withdrawals_processed];
+        let mut spent_forced_withdrawal_requests = vec![];
+        if SIGHASH_CIRCUIT_MAX_FORCED_WITHDRAWAL_REQUESTS == 0
+            && !forced_withdrawal_request_utxos.is_empty()
+        {
+            tracing::warn!(
+                "found {} forced withdrawal requests for block {}, but the sighash whitelist has no circuits which spend them",
+                forced_withdrawal_request_utxos.len(),
+                checkpoint_id
+            );
+        }
+        for (utxo, request) in forced_withdrawal_request_utxos.into_iter() {
+            if spent_forced_withdrawal_requests.len()
+                >= SIGHASH_CIRCUIT_MAX_FORCED_WITHDRAWAL_REQUESTS
+            {
+                break;
+            }
+            if let Some(i) = (0..num_new_withdrawals_processed)
+                .find(|i| !used_withdrawals[*i] && request.is_satisfied_by(&add_withdrawals[*i]))
+            {
+                used_withdrawals[i] = true;
+                spent_forced_withdrawal_requests.push(request);
+                all_inputs.push(utxo);
+            }
+        }
+
         let block_requested = CityScenarioRequestedActions::new_from_requested_rpc(
             CityScenarioRequestedActionsFromRPC {
                 register_users,
                 claim_l1_deposits,
                 add_withdrawals,
                 token_transfers,
+                change_public_keys,
+                batch_token_transfers,
+                open_token_accounts,
+                mint_tokens,
+                send_tokens,
+                burn_tokens,
+                cancel_withdrawals,
             },
             all_inputs.iter().skip(1),
             &last_block,
@@ -305,6 +382,35 @@ impl SimpleActorOrchestrator {
         let (_block_state, block_op_job_ids, _block_state_transition, _block_end_jobs, withdrawals) =
             block_planner.process_requests(store, proof_store, &block_requested)?;
 
+        for request in spent_forced_withdrawal_requests.iter() {
+            if !withdrawals
+                .iter()
+                .any(|w| request.is_paid_by(&w.to_btc_tx_out()))
+            {
+                anyhow::bail!(
+                    "forced withdrawal request {} is spent by block {} but none of its withdrawals pay it",
+                    request.txid.to_hex_string(),
+                    checkpoint_id
+                );
+            }
+        }
+        for status in pending_forced_withdrawals.iter() {
+            if spent_forced_withdrawal_requests.contains(&status.request) {
+                CityStore::mark_forced_withdrawal_request_as_included(
+                    store,
+                    checkpoint_id,
+                    status,
+                )?;
+            } else {
+                // no later block can spend an output at this block's deposit address
+                tracing::warn!(
+                    "forced withdrawal request {} for user {} was not spent by its block and is stuck",
+                    status.request.txid.to_hex_string(),
+                    status.request.user_id
+                );
+            }
+        }
+
         let next_address = CityStore::get_city_block_deposit_address(store, checkpoint_id + 1)?;
         let next_script = CityStore::get_city_block_script(store, checkpoint_id + 1)?;
         /*tracing::info!(
@@ -153,6 +153,198 @@ impl<S: KVQBinaryStore, PS: QProofStore> CityOrchestratorBlockPlanner<S, PS> {
                     .allowed_circuit_hashes_root,
             )?;
 
+        let change_public_key_dummy_state_root = if requested_actions.change_public_keys.len() == 0
+        {
+            CityStore::get_user_tree_root(store, self.processor.checkpoint_id)?
+        } else {
+            dummy_state_root
+        };
+        let (change_public_key_job_ids, root_transition_change_public_keys) =
+            plan_tree_prover_from_leaves::<PS, AggWTLeafAggregator, _, AggStateTransitionInput<F>>(
+                &requested_actions
+                    .change_public_keys
+                    .iter()
+                    .map(|req| {
+                        self.processor
+                            .process_change_public_key(store, proof_store, req)
+                    })
+                    .collect::<anyhow::Result<Vec<_>>>()?,
+                proof_store,
+                QProvingJobDataID::new_proof_job_id(
+                    self.processor.checkpoint_id,
+                    ProvingJobCircuitType::DummyChangePublicKeyAggregate,
+                    0xDD,
+                    0,
+                    0,
+                ),
+                change_public_key_dummy_state_root,
+                self.processor
+                    .op_processor
+                    .fingerprints
+                    .op_change_public_key
+                    .allowed_circuit_hashes_root,
+            )?;
+
+        let batch_token_transfer_dummy_state_root = if requested_actions.batch_token_transfers.len() == 0
+        {
+            CityStore::get_user_tree_root(store, self.processor.checkpoint_id)?
+        } else {
+            dummy_state_root
+        };
+        let (batch_token_transfer_job_ids, root_transition_batch_token_transfers) =
+            plan_tree_prover_from_leaves::<PS, AggWTLeafAggregator, _, AggStateTransitionInput<F>>(
+                &requested_actions
+                    .batch_token_transfers
+                    .iter()
+                    .map(|req| {
+                        self.processor
+                            .process_batch_l2_transfer(store, proof_store, req)
+                    })
+                    .collect::<anyhow::Result<Vec<_>>>()?,
+                proof_store,
+                QProvingJobDataID::new_proof_job_id(
+                    self.processor.checkpoint_id,
+                    ProvingJobCircuitType::DummyBatchTransferTokensL2Aggregate,
+                    0xDD,
+                    0,
+                    0,
+                ),
+                batch_token_transfer_dummy_state_root,
+                self.processor
+                    .op_processor
+                    .fingerprints
+                    .op_batch_l2_transfer
+                    .allowed_circuit_hashes_root,
+            )?;
+
+        let open_token_account_dummy_state_root = if requested_actions.open_token_accounts.len() == 0
+        {
+            CityStore::get_user_tree_root(store, self.processor.checkpoint_id)?
+        } else {
+            dummy_state_root
+        };
+        let (open_token_account_job_ids, root_transition_open_token_accounts) =
+            plan_tree_prover_from_leaves::<PS, AggWTLeafAggregator, _, AggStateTransitionInput<F>>(
+                &requested_actions
+                    .open_token_accounts
+                    .iter()
+                    .map(|req| {
+                        self.processor
+                            .process_open_token_account(store, proof_store, req)
+                    })
+                    .collect::<anyhow::Result<Vec<_>>>()?,
+                proof_store,
+                QProvingJobDataID::new_proof_job_id(
+                    self.processor.checkpoint_id,
+                    ProvingJobCircuitType::DummyOpenTokenAccountAggregate,
+                    0xDD,
+                    0,
+                    0,
+                ),
+                open_token_account_dummy_state_root,
+                self.processor
+                    .op_processor
+                    .fingerprints
+                    .op_open_token_account
+                    .allowed_circuit_hashes_root,
+            )?;
+
+        let mint_token_dummy_state_root = if requested_actions.mint_tokens.len() == 0
+        {
+            CityStore::get_user_tree_root(store, self.processor.checkpoint_id)?
+        } else {
+            dummy_state_root
+        };
+        let (mint_token_job_ids, root_transition_mint_tokens) =
+            plan_tree_prover_from_leaves::<PS, AggWTLeafAggregator, _, AggStateTransitionInput<F>>(
+                &requested_actions
+                    .mint_tokens
+                    .iter()
+                    .map(|req| {
+                        self.processor
+                            .process_mint_token(store, proof_store, req)
+                    })
+                    .collect::<anyhow::Result<Vec<_>>>()?,
+                proof_store,
+                QProvingJobDataID::new_proof_job_id(
+                    self.processor.checkpoint_id,
+                    ProvingJobCircuitType::DummyMintTokenAggregate,
+                    0xDD,
+                    0,
+                    0,
+                ),
+                mint_token_dummy_state_root,
+                self.processor
+                    .op_processor
+                    .fingerprints
+                    .op_mint_token
+                    .allowed_circuit_hashes_root,
+            )?;
+
+        let send_token_dummy_state_root = if requested_actions.send_tokens.len() == 0
+        {
+            CityStore::get_user_tree_root(store, self.processor.checkpoint_id)?
+        } else {
+            dummy_state_root
+        };
+        let (send_token_job_ids, root_transition_send_tokens) =
+            plan_tree_prover_from_leaves::<PS, AggWTLeafAggregator, _, AggStateTransitionInput<F>>(
+                &requested_actions
+                    .send_tokens
+                    .iter()
+                    .map(|req| {
+                        self.processor
+                            .process_send_token(store, proof_store, req)
+                    })
+                    .collect::<anyhow::Result<Vec<_>>>()?,
+                proof_store,
+                QProvingJobDataID::new_proof_job_id(
+                    self.processor.checkpoint_id,
+                    ProvingJobCircuitType::DummySendTokenAggregate,
+                    0xDD,
+                    0,
+                    0,
+                ),
+                send_token_dummy_state_root,
+                self.processor
+                    .op_processor
+                    .fingerprints
+                    .op_send_token
+                    .allowed_circuit_hashes_root,
+            )?;
+
+        let burn_token_dummy_state_root = if requested_actions.burn_tokens.len() == 0
+        {
+            CityStore::get_user_tree_root(store, self.processor.checkpoint_id)?
+        } else {
+            dummy_state_root
+        };
+        let (burn_token_job_ids, root_transition_burn_tokens) =
+            plan_tree_prover_from_leaves::<PS, AggWTLeafAggregator, _, AggStateTransitionInput<F>>(
+                &requested_actions
+                    .burn_tokens
+                    .iter()
+                    .map(|req| {
+                        self.processor
+                            .process_burn_token(store, proof_store, req)
+                    })
+                    .collect::<anyhow::Result<Vec<_>>>()?,
+                proof_store,
+                QProvingJobDataID::new_proof_job_id(
+                    self.processor.checkpoint_id,
+                    ProvingJobCircuitType::DummyBurnTokenAggregate,
+                    0xDD,
+                    0,
+                    0,
+                ),
+                burn_token_dummy_state_root,
+                self.processor
+                    .op_processor
+                    .fingerprints
+                    .op_burn_token
+                    .allowed_circuit_hashes_root,
+            )?;
+
         let add_withdrawal_dummy_state_root = if requested_actions.add_withdrawals.len() == 0 {
             PoseidonHash::two_to_one(
                 &CityStore::get_user_tree_root(store, self.processor.checkpoint_id)?,
@@ -187,17 +379,73 @@ impl<S: KVQBinaryStore, PS: QProofStore> CityOrchestratorBlockPlanner<S, PS> {
                     .allowed_circuit_hashes_root,
             )?;
 
-        let process_withdrawals_dummy_state_root =
-            if requested_actions.process_withdrawals.len() == 0 {
-                CityStore::get_withdrawal_tree_root(store, self.processor.checkpoint_id)?
+        let add_withdrawals_end_user_state_tree_root =
+            CityStore::get_user_tree_root(store, self.processor.checkpoint_id)?;
+        let add_withdrawals_end_withdrawal_tree_root =
+            CityStore::get_withdrawal_tree_root(store, self.processor.checkpoint_id)?;
+
+        let cancel_withdrawal_dummy_state_root = if requested_actions.cancel_withdrawals.len() == 0
+        {
+            PoseidonHash::two_to_one(
+                &add_withdrawals_end_user_state_tree_root,
+                &add_withdrawals_end_withdrawal_tree_root,
+            )
+        } else {
+            dummy_state_root
+        };
+        let (cancel_withdrawal_job_ids, root_transition_cancel_withdrawals) =
+            plan_tree_prover_from_leaves::<PS, AggWTLeafAggregator, _, AggStateTransitionInput<F>>(
+                &requested_actions
+                    .cancel_withdrawals
+                    .iter()
+                    .map(|req| {
+                        self.processor
+                            .process_cancel_withdrawal(store, proof_store, req)
+                    })
+                    .collect::<anyhow::Result<Vec<_>>>()?,
+                proof_store,
+                QProvingJobDataID::new_proof_job_id(
+                    self.processor.checkpoint_id,
+                    ProvingJobCircuitType::DummyCancelL1WithdrawalAggregate,
+                    0xDD,
+                    0,
+                    0,
+                ),
+                cancel_withdrawal_dummy_state_root,
+                self.processor
+                    .op_processor
+                    .fingerprints
+                    .op_cancel_l1_withdrawal
+                    .allowed_circuit_hashes_root,
+            )?;
+
+        // cancelled withdrawals have already been zeroed, so they are skipped instead of processed
+        let mut process_withdrawal_requests =
+            Vec::with_capacity(requested_actions.process_withdrawals.len());
+        for req in requested_actions.process_withdrawals.iter() {
+            let withdrawal_hash = CityStore::<S>::get_withdrawal_hash(
+                store,
+                self.processor.checkpoint_id,
+                req.withdrawal_id,
+            )?;
+            if withdrawal_hash == QHashOut::ZERO {
+                self.processor
+                    .op_processor
+                    .skip_cancelled_l1_withdrawal(req);
             } else {
-                dummy_state_root
-            };
+                process_withdrawal_requests.push(req.clone());
+            }
+        }
+
+        let process_withdrawals_dummy_state_root = if process_withdrawal_requests.len() == 0 {
+            CityStore::get_withdrawal_tree_root(store, self.processor.checkpoint_id)?
+        } else {
+            dummy_state_root
+        };
         let processed_withdrawals = CityStore::get_withdrawals_by_id(
             store,
             self.processor.checkpoint_id,
-            &requested_actions
-                .process_withdrawals
+            &process_withdrawal_requests
                 .iter()
                 .map(|x| x.withdrawal_id)
                 .collect::<Vec<_>>(),
@@ -210,8 +458,7 @@ impl<S: KVQBinaryStore, PS: QProofStore> CityOrchestratorBlockPlanner<S, PS> {
                 _,
                 AggStateTransitionWithEventsInput<F>,
             >(
-                &requested_actions
-                    .process_withdrawals
+                &process_withdrawal_requests
                     .iter()
                     .map(|req| {
                         self.processor
@@ -270,7 +517,14 @@ impl<S: KVQBinaryStore, PS: QProofStore> CityOrchestratorBlockPlanner<S, PS> {
             register_user_job_ids,
             claim_deposit_job_ids,
             token_transfer_job_ids,
+            change_public_key_job_ids,
+            batch_token_transfer_job_ids,
+            open_token_account_job_ids,
+            mint_token_job_ids,
+            send_token_job_ids,
+            burn_token_job_ids,
             add_withdrawal_job_ids,
+            cancel_withdrawal_job_ids,
             process_withdrawal_job_ids,
             add_deposit_job_ids,
         };
@@ -285,7 +539,16 @@ impl<S: KVQBinaryStore, PS: QProofStore> CityOrchestratorBlockPlanner<S, PS> {
             register_users: root_transition_register_users,
             claim_deposits: root_transition_claim_deposits,
             token_transfers: root_transition_transfer_tokens,
+            change_public_keys: root_transition_change_public_keys,
+            batch_token_transfers: root_transition_batch_token_transfers,
+            open_token_accounts: root_transition_open_token_accounts,
+            mint_tokens: root_transition_mint_tokens,
+            send_tokens: root_transition_send_tokens,
+            burn_tokens: root_transition_burn_tokens,
             add_withdrawals: root_transition_add_withdrawals,
+            add_withdrawals_end_user_state_tree_root,
+            add_withdrawals_end_withdrawal_tree_root,
+            cancel_withdrawals: root_transition_cancel_withdrawals,
             process_withdrawals: root_transition_process_withdrawals,
             add_deposits: root_transition_add_deposits,
         };
@@ -25,7 +25,18 @@ pub struct CityRootStateTransitions<F: RichField> {
     pub register_users: AggStateTransition<F>,
     pub claim_deposits: AggStateTransition<F>,
     pub token_transfers: AggStateTransition<F>,
+    pub change_public_keys: AggStateTransition<F>,
+    pub batch_token_transfers: AggStateTransition<F>,
+    pub open_token_accounts: AggStateTransition<F>,
+    pub mint_tokens: AggStateTransition<F>,
+    pub send_tokens: AggStateTransition<F>,
+    pub burn_tokens: AggStateTransition<F>,
     pub add_withdrawals: AggStateTransition<F>,
+    // the add and cancel withdrawal transitions hash the user and withdrawal roots together,
+    // so the roots between them are recorded separately
+    pub add_withdrawals_end_user_state_tree_root: QHashOut<F>,
+    pub add_withdrawals_end_withdrawal_tree_root: QHashOut<F>,
+    pub cancel_withdrawals: AggStateTransition<F>,
     pub process_withdrawals: AggStateTransitionWithEvents<F>,
     pub add_deposits: AggStateTransitionWithEvents<F>,
 }
@@ -49,6 +60,18 @@ impl<F: RichField> CityRootStateTransitions<F> {
             op_claim_l1_deposit_proof_id: jobs.claim_deposit_job_root_id,
             op_l2_transfer_transition_user_state_tree: self.token_transfers,
             op_l2_transfer_proof_id: jobs.token_transfer_job_root_id,
+            op_change_public_key_transition_user_state_tree: self.change_public_keys,
+            op_change_public_key_proof_id: jobs.change_public_key_job_root_id,
+            op_batch_l2_transfer_transition_user_state_tree: self.batch_token_transfers,
+            op_batch_l2_transfer_proof_id: jobs.batch_token_transfer_job_root_id,
+            op_open_token_account_transition_user_state_tree: self.open_token_accounts,
+            op_open_token_account_proof_id: jobs.open_token_account_job_root_id,
+            op_mint_token_transition_user_state_tree: self.mint_tokens,
+            op_mint_token_proof_id: jobs.mint_token_job_root_id,
+            op_send_token_transition_user_state_tree: self.send_tokens,
+            op_send_token_proof_id: jobs.send_token_job_root_id,
+            op_burn_token_transition_user_state_tree: self.burn_tokens,
+            op_burn_token_proof_id: jobs.burn_token_job_root_id,
         }
     }
     pub fn get_block_state_witness_part_2(
@@ -57,14 +80,23 @@ impl<F: RichField> CityRootStateTransitions<F> {
     ) -> CRAggAddProcessL1WithdrawalAddL1DepositCircuitInput<F> {
         CRAggAddProcessL1WithdrawalAddL1DepositCircuitInput {
             op_add_l1_withdrawal_transition_user_state_tree: AggStateTransition::new(
-                self.token_transfers.state_transition_end,
-                self.end_user_state_tree_root,
+                self.burn_tokens.state_transition_end,
+                self.add_withdrawals_end_user_state_tree_root,
             ),
             op_add_l1_withdrawal_transition_withdrawal_tree: AggStateTransition::new(
                 self.start_withdrawal_tree_root,
-                self.process_withdrawals.state_transition_start,
+                self.add_withdrawals_end_withdrawal_tree_root,
             ),
             op_add_l1_withdrawal_proof_id: jobs.add_withdrawal_job_root_id,
+            op_cancel_l1_withdrawal_transition_user_state_tree: AggStateTransition::new(
+                self.add_withdrawals_end_user_state_tree_root,
+                self.end_user_state_tree_root,
+            ),
+            op_cancel_l1_withdrawal_transition_withdrawal_tree: AggStateTransition::new(
+                self.add_withdrawals_end_withdrawal_tree_root,
+                self.process_withdrawals.state_transition_start,
+            ),
+            op_cancel_l1_withdrawal_proof_id: jobs.cancel_withdrawal_job_root_id,
             op_process_l1_withdrawal_transition_withdrawal_tree: self
                 .process_withdrawals
                 .get_state_transition(),
@@ -80,7 +112,14 @@ pub struct CityOpRootJobIds {
     pub register_user_job_root_id: QProvingJobDataID,
     pub claim_deposit_job_root_id: QProvingJobDataID,
     pub token_transfer_job_root_id: QProvingJobDataID,
+    pub change_public_key_job_root_id: QProvingJobDataID,
+    pub batch_token_transfer_job_root_id: QProvingJobDataID,
+    pub open_token_account_job_root_id: QProvingJobDataID,
+    pub mint_token_job_root_id: QProvingJobDataID,
+    pub send_token_job_root_id: QProvingJobDataID,
+    pub burn_token_job_root_id: QProvingJobDataID,
     pub add_withdrawal_job_root_id: QProvingJobDataID,
+    pub cancel_withdrawal_job_root_id: QProvingJobDataID,
     pub process_withdrawal_job_root_id: QProvingJobDataID,
     pub add_deposit_job_root_id: QProvingJobDataID,
 }
@@ -89,7 +128,21 @@ pub struct CityOpJobConfig {
     pub register_user_count: usize,
     pub claim_deposit_count: usize,
     pub token_transfer_count: usize,
+    #[serde(default)]
+    pub change_public_key_count: usize,
+    #[serde(default)]
+    pub batch_token_transfer_count: usize,
+    #[serde(default)]
+    pub open_token_account_count: usize,
+    #[serde(default)]
+    pub mint_token_count: usize,
+    #[serde(default)]
+    pub send_token_count: usize,
+    #[serde(default)]
+    pub burn_token_count: usize,
     pub add_withdrawal_count: usize,
+    #[serde(default)]
+    pub cancel_withdrawal_count: usize,
     pub process_withdrawal_count: usize,
     pub add_deposit_count: usize,
 }
@@ -99,7 +152,14 @@ pub struct CityOpJobIds {
     pub register_user_job_ids: Vec<Vec<QProvingJobDataID>>,
     pub claim_deposit_job_ids: Vec<Vec<QProvingJobDataID>>,
     pub token_transfer_job_ids: Vec<Vec<QProvingJobDataID>>,
+    pub change_public_key_job_ids: Vec<Vec<QProvingJobDataID>>,
+    pub batch_token_transfer_job_ids: Vec<Vec<QProvingJobDataID>>,
+    pub open_token_account_job_ids: Vec<Vec<QProvingJobDataID>>,
+    pub mint_token_job_ids: Vec<Vec<QProvingJobDataID>>,
+    pub send_token_job_ids: Vec<Vec<QProvingJobDataID>>,
+    pub burn_token_job_ids: Vec<Vec<QProvingJobDataID>>,
     pub add_withdrawal_job_ids: Vec<Vec<QProvingJobDataID>>,
+    pub cancel_withdrawal_job_ids: Vec<Vec<QProvingJobDataID>>,
 
     pub process_withdrawal_job_ids: Vec<Vec<QProvingJobDataID>>,
     pub add_deposit_job_ids: Vec<Vec<QProvingJobDataID>>,
@@ -127,12 +187,54 @@ impl CityOpJobIds {
             checkpoint_id,
             config.token_transfer_count,
         );
+        let change_public_key_job_ids = get_dummy_tree_prover_ids_op_circuit(
+            ProvingJobCircuitType::ChangePublicKey,
+            ProvingJobCircuitType::DummyChangePublicKeyAggregate,
+            checkpoint_id,
+            config.change_public_key_count,
+        );
+        let batch_token_transfer_job_ids = get_dummy_tree_prover_ids_op_circuit(
+            ProvingJobCircuitType::BatchTransferTokensL2,
+            ProvingJobCircuitType::DummyBatchTransferTokensL2Aggregate,
+            checkpoint_id,
+            config.batch_token_transfer_count,
+        );
+        let open_token_account_job_ids = get_dummy_tree_prover_ids_op_circuit(
+            ProvingJobCircuitType::OpenTokenAccount,
+            ProvingJobCircuitType::DummyOpenTokenAccountAggregate,
+            checkpoint_id,
+            config.open_token_account_count,
+        );
+        let mint_token_job_ids = get_dummy_tree_prover_ids_op_circuit(
+            ProvingJobCircuitType::MintToken,
+            ProvingJobCircuitType::DummyMintTokenAggregate,
+            checkpoint_id,
+            config.mint_token_count,
+        );
+        let send_token_job_ids = get_dummy_tree_prover_ids_op_circuit(
+            ProvingJobCircuitType::SendToken,
+            ProvingJobCircuitType::DummySendTokenAggregate,
+            checkpoint_id,
+            config.send_token_count,
+        );
+        let burn_token_job_ids = get_dummy_tree_prover_ids_op_circuit(
+            ProvingJobCircuitType::BurnToken,
+            ProvingJobCircuitType::DummyBurnTokenAggregate,
+            checkpoint_id,
+            config.burn_token_count,
+        );
         let add_withdrawal_job_ids = get_dummy_tree_prover_ids_op_circuit(
             ProvingJobCircuitType::AddL1Withdrawal,
             ProvingJobCircuitType::DummyAddL1WithdrawalAggregate,
             checkpoint_id,
             config.add_withdrawal_count,
         );
+        let cancel_withdrawal_job_ids = get_dummy_tree_prover_ids_op_circuit(
+            ProvingJobCircuitType::CancelL1Withdrawal,
+            ProvingJobCircuitType::DummyCancelL1WithdrawalAggregate,
+            checkpoint_id,
+            config.cancel_withdrawal_count,
+        );
         let process_withdrawal_job_ids = get_dummy_tree_prover_ids_op_circuit(
             ProvingJobCircuitType::ProcessL1Withdrawal,
             ProvingJobCircuitType::DummyProcessL1WithdrawalAggregate,
@@ -149,7 +251,14 @@ impl CityOpJobIds {
             register_user_job_ids,
             claim_deposit_job_ids,
             token_transfer_job_ids,
+            change_public_key_job_ids,
+            batch_token_transfer_job_ids,
+            open_token_account_job_ids,
+            mint_token_job_ids,
+            send_token_job_ids,
+            burn_token_job_ids,
             add_withdrawal_job_ids,
+            cancel_withdrawal_job_ids,
             process_withdrawal_job_ids,
             add_deposit_job_ids,
         
@@ -159,7 +268,14 @@ impl CityOpJobIds {
         vec_2d_size(&self.register_user_job_ids)
             + vec_2d_size(&self.claim_deposit_job_ids)
             + vec_2d_size(&self.token_transfer_job_ids)
+            + vec_2d_size(&self.change_public_key_job_ids)
+            + vec_2d_size(&self.batch_token_transfer_job_ids)
+            + vec_2d_size(&self.open_token_account_job_ids)
+            + vec_2d_size(&self.mint_token_job_ids)
+            + vec_2d_size(&self.send_token_job_ids)
+            + vec_2d_size(&self.burn_token_job_ids)
             + vec_2d_size(&self.add_withdrawal_job_ids)
+            + vec_2d_size(&self.cancel_withdrawal_job_ids)
             + vec_2d_size(&self.process_withdrawal_job_ids)
             + vec_2d_size(&self.add_deposit_job_ids)
     }
@@ -170,7 +286,14 @@ impl CityOpJobIds {
             .len()
             .max(self.claim_deposit_job_ids.len())
             .max(self.token_transfer_job_ids.len())
+            .max(self.change_public_key_job_ids.len())
+            .max(self.batch_token_transfer_job_ids.len())
+            .max(self.open_token_account_job_ids.len())
+            .max(self.mint_token_job_ids.len())
+            .max(self.send_token_job_ids.len())
+            .max(self.burn_token_job_ids.len())
             .max(self.add_withdrawal_job_ids.len())
+            .max(self.cancel_withdrawal_job_ids.len())
             .max(self.process_withdrawal_job_ids.len())
             .max(self.add_deposit_job_ids.len());
 
@@ -184,9 +307,30 @@ impl CityOpJobIds {
             if i < self.token_transfer_job_ids.len() {
                 job_ids.extend(&self.token_transfer_job_ids[i]);
             }
+            if i < self.change_public_key_job_ids.len() {
+                job_ids.extend(&self.change_public_key_job_ids[i]);
+            }
+            if i < self.batch_token_transfer_job_ids.len() {
+                job_ids.extend(&self.batch_token_transfer_job_ids[i]);
+            }
+            if i < self.open_token_account_job_ids.len() {
+                job_ids.extend(&self.open_token_account_job_ids[i]);
+            }
+            if i < self.mint_token_job_ids.len() {
+                job_ids.extend(&self.mint_token_job_ids[i]);
+            }
+            if i < self.send_token_job_ids.len() {
+                job_ids.extend(&self.send_token_job_ids[i]);
+            }
+            if i < self.burn_token_job_ids.len() {
+                job_ids.extend(&self.burn_token_job_ids[i]);
+            }
             if i < self.add_withdrawal_job_ids.len() {
                 job_ids.extend(&self.add_withdrawal_job_ids[i]);
             }
+            if i < self.cancel_withdrawal_job_ids.len() {
+                job_ids.extend(&self.cancel_withdrawal_job_ids[i]);
+            }
             if i < self.process_withdrawal_job_ids.len() {
                 job_ids.extend(&self.process_withdrawal_job_ids[i]);
             }
@@ -220,6 +364,48 @@ impl CityOpJobIds {
                 .last()
                 .unwrap()
                 .get_output_id(),
+            change_public_key_job_root_id: self
+                .change_public_key_job_ids
+                .last()
+                .unwrap()
+                .last()
+                .unwrap()
+                .get_output_id(),
+            batch_token_transfer_job_root_id: self
+                .batch_token_transfer_job_ids
+                .last()
+                .unwrap()
+                .last()
+                .unwrap()
+                .get_output_id(),
+            open_token_account_job_root_id: self
+                .open_token_account_job_ids
+                .last()
+                .unwrap()
+                .last()
+                .unwrap()
+                .get_output_id(),
+            mint_token_job_root_id: self
+                .mint_token_job_ids
+                .last()
+                .unwrap()
+                .last()
+                .unwrap()
+                .get_output_id(),
+            send_token_job_root_id: self
+                .send_token_job_ids
+                .last()
+                .unwrap()
+                .last()
+                .unwrap()
+                .get_output_id(),
+            burn_token_job_root_id: self
+                .burn_token_job_ids
+                .last()
+                .unwrap()
+                .last()
+                .unwrap()
+                .get_output_id(),
             add_withdrawal_job_root_id: self
                 .add_withdrawal_job_ids
                 .last()
@@ -227,6 +413,13 @@ impl CityOpJobIds {
                 .last()
                 .unwrap()
                 .get_output_id(),
+            cancel_withdrawal_job_root_id: self
+                .cancel_withdrawal_job_ids
+                .last()
+                .unwrap()
+                .last()
+                .unwrap()
+                .get_output_id(),
             process_withdrawal_job_root_id: self
                 .process_withdrawal_job_ids
                 .last()
@@ -250,7 +443,14 @@ impl CityOpJobIds {
             register_user_job_ids: Vec::new(),
             claim_deposit_job_ids: Vec::new(),
             token_transfer_job_ids: Vec::new(),
+            change_public_key_job_ids: Vec::new(),
+            batch_token_transfer_job_ids: Vec::new(),
+            open_token_account_job_ids: Vec::new(),
+            mint_token_job_ids: Vec::new(),
+            send_token_job_ids: Vec::new(),
+            burn_token_job_ids: Vec::new(),
             add_withdrawal_job_ids: Vec::new(),
+            cancel_withdrawal_job_ids: Vec::new(),
 
             process_withdrawal_job_ids: Vec::new(),
             add_deposit_job_ids: Vec::new(),
@@ -3,8 +3,11 @@ use std::marker::PhantomData;
 use city_rollup_common::{
     api::data::{
         block::requested_actions::{
-            CityAddDepositRequest, CityAddWithdrawalRequest, CityClaimDepositRequest,
-            CityProcessWithdrawalRequest, CityRegisterUserRequest, CityTokenTransferRequest,
+            CityAddDepositRequest, CityAddWithdrawalRequest, CityBatchTokenTransferRequest,
+            CityBurnTokenRequest, CityCancelWithdrawalRequest, CityChangePublicKeyRequest,
+            CityClaimDepositRequest, CityMintTokenRequest, CityOpenTokenAccountRequest,
+            CityProcessWithdrawalRequest, CityRegisterUserRequest, CitySendTokenRequest,
+            CityTokenTransferRequest,
         },
         store::CityL2BlockState,
     },
@@ -13,7 +16,10 @@ use city_rollup_common::{
         job_id::{ProvingJobCircuitType, QProvingJobDataID},
         job_witnesses::op::{
             CRAddL1DepositCircuitInput, CRAddL1WithdrawalCircuitInput,
-            CRClaimL1DepositCircuitInput, CRL2TransferCircuitInput,
+            CRBatchL2TransferCircuitInput, CRBurnTokenCircuitInput,
+            CRCancelL1WithdrawalCircuitInput, CRChangePublicKeyCircuitInput,
+            CRClaimL1DepositCircuitInput, CRL2TransferCircuitInput, CRMintTokenCircuitInput,
+            CROpenTokenAccountCircuitInput, CRSendTokenCircuitInput,
             CRProcessL1WithdrawalCircuitInput, CRUserRegistrationCircuitInput,
             CircuitInputWithJobId,
         },
@@ -29,8 +35,15 @@ pub struct CityOrchestratorBlockProcessor<S: KVQBinaryStore, PS: QProofStore> {
     pub checkpoint_id: u64,
     pub block_add_deposit_count: u64,
     pub block_add_withdrawal_count: u64,
+    pub block_cancel_withdrawal_count: u64,
     pub block_claim_deposit_count: u64,
     pub block_l2_transfer_count: u64,
+    pub block_change_public_key_count: u64,
+    pub block_batch_l2_transfer_count: u64,
+    pub block_open_token_account_count: u64,
+    pub block_mint_token_count: u64,
+    pub block_send_token_count: u64,
+    pub block_burn_token_count: u64,
     pub block_process_withdrawal_count: u64,
     pub block_register_user_count: u64,
 
@@ -47,8 +60,15 @@ impl<S: KVQBinaryStore, PS: QProofStore> CityOrchestratorBlockProcessor<S, PS> {
             checkpoint_id: last_block_state.checkpoint_id + 1,
             block_add_deposit_count: 0,
             block_add_withdrawal_count: 0,
+            block_cancel_withdrawal_count: 0,
             block_claim_deposit_count: 0,
             block_l2_transfer_count: 0,
+            block_change_public_key_count: 0,
+            block_batch_l2_transfer_count: 0,
+            block_open_token_account_count: 0,
+            block_mint_token_count: 0,
+            block_send_token_count: 0,
+            block_burn_token_count: 0,
             block_process_withdrawal_count: 0,
             block_register_user_count: 0,
 
@@ -96,6 +116,126 @@ impl<S: KVQBinaryStore, PS: QProofStore> CityOrchestratorBlockProcessor<S, PS> {
         Ok(CircuitInputWithJobId::new(op_result, job_id))
     }
 
+    pub fn process_change_public_key(
+        &mut self,
+        store: &mut S,
+        proof_store: &mut PS,
+        req: &CityChangePublicKeyRequest<F>,
+    ) -> anyhow::Result<CircuitInputWithJobId<CRChangePublicKeyCircuitInput<F>>> {
+        let op_result = self
+            .op_processor
+            .process_change_public_key_request(store, req)?;
+        let job_id = QProvingJobDataID::core_op_witness(
+            ProvingJobCircuitType::ChangePublicKey,
+            self.checkpoint_id,
+            self.block_change_public_key_count as u32,
+        );
+
+        proof_store.set_bytes_by_id(job_id, &op_result.to_bytes()?)?;
+        self.block_change_public_key_count += 1;
+        Ok(CircuitInputWithJobId::new(op_result, job_id))
+    }
+
+    pub fn process_batch_l2_transfer(
+        &mut self,
+        store: &mut S,
+        proof_store: &mut PS,
+        req: &CityBatchTokenTransferRequest,
+    ) -> anyhow::Result<CircuitInputWithJobId<CRBatchL2TransferCircuitInput<F>>> {
+        let op_result = self
+            .op_processor
+            .process_batch_l2_transfer_request(store, req)?;
+        let job_id = QProvingJobDataID::core_op_witness(
+            ProvingJobCircuitType::BatchTransferTokensL2,
+            self.checkpoint_id,
+            self.block_batch_l2_transfer_count as u32,
+        );
+
+        proof_store.set_bytes_by_id(job_id, &op_result.to_bytes()?)?;
+        self.block_batch_l2_transfer_count += 1;
+        Ok(CircuitInputWithJobId::new(op_result, job_id))
+    }
+
+    pub fn process_open_token_account(
+        &mut self,
+        store: &mut S,
+        proof_store: &mut PS,
+        req: &CityOpenTokenAccountRequest,
+    ) -> anyhow::Result<CircuitInputWithJobId<CROpenTokenAccountCircuitInput<F>>> {
+        let op_result = self
+            .op_processor
+            .process_open_token_account_request(store, req)?;
+        let job_id = QProvingJobDataID::core_op_witness(
+            ProvingJobCircuitType::OpenTokenAccount,
+            self.checkpoint_id,
+            self.block_open_token_account_count as u32,
+        );
+
+        proof_store.set_bytes_by_id(job_id, &op_result.to_bytes()?)?;
+        self.block_open_token_account_count += 1;
+        Ok(CircuitInputWithJobId::new(op_result, job_id))
+    }
+
+    pub fn process_mint_token(
+        &mut self,
+        store: &mut S,
+        proof_store: &mut PS,
+        req: &CityMintTokenRequest,
+    ) -> anyhow::Result<CircuitInputWithJobId<CRMintTokenCircuitInput<F>>> {
+        let op_result = self
+            .op_processor
+            .process_mint_token_request(store, req)?;
+        let job_id = QProvingJobDataID::core_op_witness(
+            ProvingJobCircuitType::MintToken,
+            self.checkpoint_id,
+            self.block_mint_token_count as u32,
+        );
+
+        proof_store.set_bytes_by_id(job_id, &op_result.to_bytes()?)?;
+        self.block_mint_token_count += 1;
+        Ok(CircuitInputWithJobId::new(op_result, job_id))
+    }
+
+    pub fn process_send_token(
+        &mut self,
+        store: &mut S,
+        proof_store: &mut PS,
+        req: &CitySendTokenRequest,
+    ) -> anyhow::Result<CircuitInputWithJobId<CRSendTokenCircuitInput<F>>> {
+        let op_result = self
+            .op_processor
+            .process_send_token_request(store, req)?;
+        let job_id = QProvingJobDataID::core_op_witness(
+            ProvingJobCircuitType::SendToken,
+            self.checkpoint_id,
+            self.block_send_token_count as u32,
+        );
+
+        proof_store.set_bytes_by_id(job_id, &op_result.to_bytes()?)?;
+        self.block_send_token_count += 1;
+        Ok(CircuitInputWithJobId::new(op_result, job_id))
+    }
+
+    pub fn process_burn_token(
+        &mut self,
+        store: &mut S,
+        proof_store: &mut PS,
+        req: &CityBurnTokenRequest,
+    ) -> anyhow::Result<CircuitInputWithJobId<CRBurnTokenCircuitInput<F>>> {
+        let op_result = self
+            .op_processor
+            .process_burn_token_request(store, req)?;
+        let job_id = QProvingJobDataID::core_op_witness(
+            ProvingJobCircuitType::BurnToken,
+            self.checkpoint_id,
+            self.block_burn_token_count as u32,
+        );
+
+        proof_store.set_bytes_by_id(job_id, &op_result.to_bytes()?)?;
+        self.block_burn_token_count += 1;
+        Ok(CircuitInputWithJobId::new(op_result, job_id))
+    }
+
     pub fn process_add_withdrawal(
         &mut self,
         store: &mut S,
@@ -118,6 +258,27 @@ impl<S: KVQBinaryStore, PS: QProofStore> CityOrchestratorBlockProcessor<S, PS> {
         Ok(CircuitInputWithJobId::new(op_result, job_id))
     }
 
+    pub fn process_cancel_withdrawal(
+        &mut self,
+        store: &mut S,
+        proof_store: &mut PS,
+        req: &CityCancelWithdrawalRequest<F>,
+    ) -> anyhow::Result<CircuitInputWithJobId<CRCancelL1WithdrawalCircuitInput<F>>> {
+        let op_result = self
+            .op_processor
+            .process_cancel_withdrawal_request(store, req)?;
+
+        let job_id = QProvingJobDataID::core_op_witness(
+            ProvingJobCircuitType::CancelL1Withdrawal,
+            self.checkpoint_id,
+            self.block_cancel_withdrawal_count as u32,
+        );
+
+        proof_store.set_bytes_by_id(job_id, &op_result.to_bytes()?)?;
+        self.block_cancel_withdrawal_count += 1;
+        Ok(CircuitInputWithJobId::new(op_result, job_id))
+    }
+
     pub fn process_complete_l1_withdrawal(
         &mut self,
         store: &mut S,
@@ -1,12 +1,17 @@
 use std::marker::PhantomData;
 
-use city_common::config::rollup_constants::{DEPOSIT_FEE_AMOUNT, WITHDRAWAL_FEE_AMOUNT};
+use city_common::config::rollup_constants::{
+    DEPOSIT_FEE_AMOUNT, L2_BATCH_TRANSFER_MAX_RECIPIENTS, WITHDRAWAL_FEE_AMOUNT,
+};
 use city_crypto::hash::qhashout::QHashOut;
 use city_rollup_common::{
     api::data::{
         block::requested_actions::{
-            CityAddDepositRequest, CityAddWithdrawalRequest, CityClaimDepositRequest,
-            CityProcessWithdrawalRequest, CityRegisterUserRequest, CityTokenTransferRequest,
+            CityAddDepositRequest, CityAddWithdrawalRequest, CityBatchTokenTransferRequest,
+            CityBurnTokenRequest, CityCancelWithdrawalRequest, CityChangePublicKeyRequest,
+            CityClaimDepositRequest, CityMintTokenRequest, CityOpenTokenAccountRequest,
+            CityProcessWithdrawalRequest, CityRegisterUserRequest, CitySendTokenRequest,
+            CityTokenTransferRequest,
         },
         store::CityL2BlockState,
     },
@@ -15,8 +20,11 @@ use city_rollup_common::{
         fingerprints::CRWorkerToolboxCoreCircuitFingerprints,
         job_witnesses::op::{
             CRAddL1DepositCircuitInput, CRAddL1WithdrawalCircuitInput,
-            CRClaimL1DepositCircuitInput, CRL2TransferCircuitInput,
-            CRProcessL1WithdrawalCircuitInput, CRUserRegistrationCircuitInput,
+            CRBatchL2TransferCircuitInput, CRBurnTokenCircuitInput,
+            CRCancelL1WithdrawalCircuitInput, CRChangePublicKeyCircuitInput,
+            CRClaimL1DepositCircuitInput, CRL2TransferCircuitInput, CRMintTokenCircuitInput,
+            CROpenTokenAccountCircuitInput, CRProcessL1WithdrawalCircuitInput,
+            CRSendTokenCircuitInput, CRUserRegistrationCircuitInput,
         },
     },
 };
@@ -36,6 +44,7 @@ pub struct CityOrchestratorOpRequestProcessor<S: KVQBinaryStore> {
     pub added_deposit_hashes: Vec<QHashOut<F>>,
     pub block_total_deposited: u64,
     pub block_total_withdrawn: u64,
+    pub block_total_cancelled: u64,
 
     _store: PhantomData<S>,
 }
@@ -48,12 +57,12 @@ impl<S: KVQBinaryStore> CityOrchestratorOpRequestProcessor<S> {
         Self {
             last_block_state,
             checkpoint_id: last_block_state.checkpoint_id + 1,
-            next_add_withdrawal_id: if last_block_state.next_add_withdrawal_id == last_block_state.next_process_withdrawal_id{
+            next_add_withdrawal_id: if last_block_state.next_add_withdrawal_id <= last_block_state.next_process_withdrawal_id{
                 0
             }else{
                 last_block_state.next_add_withdrawal_id
             },
-            next_process_withdrawal_id: if last_block_state.next_add_withdrawal_id == last_block_state.next_process_withdrawal_id{
+            next_process_withdrawal_id: if last_block_state.next_add_withdrawal_id <= last_block_state.next_process_withdrawal_id{
                 0
             }else{
                 last_block_state.next_process_withdrawal_id
@@ -66,6 +75,7 @@ impl<S: KVQBinaryStore> CityOrchestratorOpRequestProcessor<S> {
             processed_withdrawal_hashes: Vec::new(),
             block_total_deposited: 0,
             block_total_withdrawn: 0,
+            block_total_cancelled: 0,
 
             _store: PhantomData,
         }
@@ -74,12 +84,14 @@ impl<S: KVQBinaryStore> CityOrchestratorOpRequestProcessor<S> {
     pub fn get_finalized_block_state(&self) -> CityL2BlockState {
         CityL2BlockState {
             checkpoint_id: self.checkpoint_id,
-            next_add_withdrawal_id: if self.next_add_withdrawal_id == self.next_process_withdrawal_id {
+            // cancelled withdrawals dropped from the end of the queue can still be skipped in the
+            // same block, which moves next_process_withdrawal_id past next_add_withdrawal_id
+            next_add_withdrawal_id: if self.next_add_withdrawal_id <= self.next_process_withdrawal_id {
                 0
             }else{
                 self.next_add_withdrawal_id
             },
-            next_process_withdrawal_id: if self.next_add_withdrawal_id == self.next_process_withdrawal_id {
+            next_process_withdrawal_id: if self.next_add_withdrawal_id <= self.next_process_withdrawal_id {
                 0
             }else{
                 self.next_process_withdrawal_id
@@ -87,7 +99,9 @@ impl<S: KVQBinaryStore> CityOrchestratorOpRequestProcessor<S> {
             next_deposit_id: self.next_deposit_id,
             next_user_id: self.next_user_id,
             total_deposits_claimed_epoch: self.total_deposits_claimed_epoch,
-            end_balance: self.last_block_state.end_balance + self.block_total_deposited
+            end_balance: self.last_block_state.end_balance
+                + self.block_total_deposited
+                + self.block_total_cancelled
                 - self.block_total_withdrawn,
         }
     }
@@ -127,9 +141,16 @@ impl<S: KVQBinaryStore> CityOrchestratorOpRequestProcessor<S> {
             store,
             self.checkpoint_id,
             req.user_id,
-            req.value + WITHDRAWAL_FEE_AMOUNT,
+            req.value + WITHDRAWAL_FEE_AMOUNT + req.fee,
             Some(req.nonce),
         )?;
+        let fee_recipient_user_tree_delta_merkle_proof = CityStore::<S>::credit_sequencer_fee(
+            store,
+            self.checkpoint_id,
+            self.fingerprints.fee_recipient_user_id,
+            req.fee,
+        )?;
+        // the sequencer fee stays on layer 2, only the withdrawal and L1 fee leave the rollup
         self.block_total_withdrawn += req.value + WITHDRAWAL_FEE_AMOUNT;
         let withdrawal_tree_delta_merkle_proof =
             CityStore::<S>::add_withdrawal_to_tree_from_request(
@@ -138,6 +159,8 @@ impl<S: KVQBinaryStore> CityOrchestratorOpRequestProcessor<S> {
                 withdrawal_id,
                 req,
             )?;
+        let withdrawal_hash =
+            CityStore::<S>::get_withdrawal_hash(store, self.checkpoint_id, withdrawal_id)?;
         self.next_add_withdrawal_id += 1;
         Ok(CRAddL1WithdrawalCircuitInput {
             allowed_circuit_hashes_root: self
@@ -145,7 +168,76 @@ impl<S: KVQBinaryStore> CityOrchestratorOpRequestProcessor<S> {
                 .op_add_l1_withdrawal
                 .allowed_circuit_hashes_root,
             user_tree_delta_merkle_proof,
+            fee_recipient_user_tree_delta_merkle_proof,
+            fee: req.fee,
             withdrawal_tree_delta_merkle_proof,
+            withdrawal_hash,
+            signature_proof_id: req.signature_proof_id,
+        })
+    }
+    pub fn process_cancel_withdrawal_request(
+        &mut self,
+        store: &mut S,
+        req: &CityCancelWithdrawalRequest<F>,
+    ) -> anyhow::Result<CRCancelL1WithdrawalCircuitInput<F>> {
+        if req.withdrawal_id < self.next_process_withdrawal_id {
+            anyhow::bail!(
+                "withdrawal {} has already been processed",
+                req.withdrawal_id
+            );
+        }
+        let (withdrawal, owner) = CityStore::<S>::get_pending_withdrawal_with_owner(
+            store,
+            self.checkpoint_id,
+            req.withdrawal_id,
+        )?
+        .ok_or_else(|| anyhow::anyhow!("withdrawal {} is not pending", req.withdrawal_id))?;
+        // the circuit rejects the cancellation as well, checked here to fail before proving
+        if owner != req.user_id {
+            anyhow::bail!(
+                "withdrawal {} does not belong to user {}",
+                req.withdrawal_id,
+                req.user_id
+            );
+        }
+        let withdrawal_hash: QHashOut<F> = (&withdrawal).into();
+        if withdrawal_hash != req.withdrawal_hash {
+            anyhow::bail!(
+                "withdrawal {} does not match the signed withdrawal hash",
+                req.withdrawal_id
+            );
+        }
+        let withdrawal_tree_delta_merkle_proof =
+            CityStore::<S>::cancel_withdrawal(store, self.checkpoint_id, req.withdrawal_id)?;
+        // the add withdrawal circuit requires the left siblings of a new leaf to be non-zero, so
+        // cancelled withdrawals at the end of the queue are dropped and their ids are reused
+        while self.next_add_withdrawal_id > self.next_process_withdrawal_id
+            && CityStore::<S>::get_withdrawal_hash(
+                store,
+                self.checkpoint_id,
+                self.next_add_withdrawal_id - 1,
+            )? == QHashOut::ZERO
+        {
+            self.next_add_withdrawal_id -= 1;
+        }
+        // the sequencer fee paid when the withdrawal was added is not refunded
+        let refund_amount = withdrawal.value + WITHDRAWAL_FEE_AMOUNT;
+        let user_tree_delta_merkle_proof = CityStore::<S>::increment_user_balance(
+            store,
+            self.checkpoint_id,
+            req.user_id,
+            refund_amount,
+            Some(req.nonce),
+        )?;
+        self.block_total_cancelled += refund_amount;
+        Ok(CRCancelL1WithdrawalCircuitInput {
+            withdrawal_tree_delta_merkle_proof,
+            withdrawal_hash,
+            user_tree_delta_merkle_proof,
+            allowed_circuit_hashes_root: self
+                .fingerprints
+                .op_cancel_l1_withdrawal
+                .allowed_circuit_hashes_root,
             signature_proof_id: req.signature_proof_id,
         })
     }
@@ -195,7 +287,7 @@ impl<S: KVQBinaryStore> CityOrchestratorOpRequestProcessor<S> {
             store,
             self.checkpoint_id,
             req.user_id,
-            req.value,
+            req.value + req.fee,
             Some(req.nonce),
         )?;
 
@@ -207,9 +299,18 @@ impl<S: KVQBinaryStore> CityOrchestratorOpRequestProcessor<S> {
             None,
         )?;
 
+        let fee_recipient_user_tree_delta_merkle_proof = CityStore::<S>::credit_sequencer_fee(
+            store,
+            self.checkpoint_id,
+            self.fingerprints.fee_recipient_user_id,
+            req.fee,
+        )?;
+
         Ok(CRL2TransferCircuitInput {
             sender_user_tree_delta_merkle_proof,
             receiver_user_tree_delta_merkle_proof,
+            fee_recipient_user_tree_delta_merkle_proof,
+            fee: req.fee,
             allowed_circuit_hashes_root: self
                 .fingerprints
                 .op_l2_transfer
@@ -217,27 +318,221 @@ impl<S: KVQBinaryStore> CityOrchestratorOpRequestProcessor<S> {
             signature_proof_id: req.signature_proof_id,
         })
     }
+    pub fn process_batch_l2_transfer_request(
+        &mut self,
+        store: &mut S,
+        req: &CityBatchTokenTransferRequest,
+    ) -> anyhow::Result<CRBatchL2TransferCircuitInput<F>> {
+        let recipient_count = req.transfers.len();
+        if recipient_count == 0 || recipient_count > L2_BATCH_TRANSFER_MAX_RECIPIENTS {
+            anyhow::bail!(
+                "a batch transfer must have between 1 and {} recipients, got {}",
+                L2_BATCH_TRANSFER_MAX_RECIPIENTS,
+                recipient_count
+            );
+        }
+        if req.transfers.iter().any(|t| t.to == req.user_id) {
+            anyhow::bail!("a batch transfer cannot include the sender as a recipient");
+        }
+        let sender_user_tree_delta_merkle_proof = CityStore::<S>::decrement_user_balance(
+            store,
+            self.checkpoint_id,
+            req.user_id,
+            req.total_value() + req.fee,
+            Some(req.nonce),
+        )?;
+
+        let mut receiver_user_tree_delta_merkle_proofs =
+            Vec::with_capacity(L2_BATCH_TRANSFER_MAX_RECIPIENTS);
+        for transfer in req.transfers.iter() {
+            receiver_user_tree_delta_merkle_proofs.push(CityStore::<S>::increment_user_balance(
+                store,
+                self.checkpoint_id,
+                transfer.to,
+                transfer.value,
+                None,
+            )?);
+        }
+        // pad the unused slots with zero-value no-op updates on the first recipient's leaf
+        for _ in recipient_count..L2_BATCH_TRANSFER_MAX_RECIPIENTS {
+            receiver_user_tree_delta_merkle_proofs.push(CityStore::<S>::increment_user_balance(
+                store,
+                self.checkpoint_id,
+                req.transfers[0].to,
+                0,
+                None,
+            )?);
+        }
+
+        let fee_recipient_user_tree_delta_merkle_proof = CityStore::<S>::credit_sequencer_fee(
+            store,
+            self.checkpoint_id,
+            self.fingerprints.fee_recipient_user_id,
+            req.fee,
+        )?;
+
+        Ok(CRBatchL2TransferCircuitInput {
+            sender_user_tree_delta_merkle_proof,
+            receiver_user_tree_delta_merkle_proofs,
+            fee_recipient_user_tree_delta_merkle_proof,
+            recipient_count,
+            fee: req.fee,
+            allowed_circuit_hashes_root: self
+                .fingerprints
+                .op_batch_l2_transfer
+                .allowed_circuit_hashes_root,
+            signature_proof_id: req.signature_proof_id,
+        })
+    }
+    pub fn process_change_public_key_request(
+        &mut self,
+        store: &mut S,
+        req: &CityChangePublicKeyRequest<F>,
+    ) -> anyhow::Result<CRChangePublicKeyCircuitInput<F>> {
+        let (user_tree_nonce_delta_merkle_proof, user_tree_public_key_delta_merkle_proof) =
+            CityStore::<S>::change_user_public_key(
+                store,
+                self.checkpoint_id,
+                req.user_id,
+                req.new_public_key,
+                req.nonce,
+            )?;
+
+        Ok(CRChangePublicKeyCircuitInput {
+            user_tree_nonce_delta_merkle_proof,
+            user_tree_public_key_delta_merkle_proof,
+            allowed_circuit_hashes_root: self
+                .fingerprints
+                .op_change_public_key
+                .allowed_circuit_hashes_root,
+            signature_proof_id: req.signature_proof_id,
+        })
+    }
+    pub fn process_open_token_account_request(
+        &mut self,
+        store: &mut S,
+        req: &CityOpenTokenAccountRequest,
+    ) -> anyhow::Result<CROpenTokenAccountCircuitInput<F>> {
+        let token_state_transition = CityStore::<S>::open_token_account(
+            store,
+            self.checkpoint_id,
+            req.user_id,
+            req.token_id,
+            req.nonce,
+        )?;
+
+        Ok(CROpenTokenAccountCircuitInput {
+            token_state_transition,
+            allowed_circuit_hashes_root: self
+                .fingerprints
+                .op_open_token_account
+                .allowed_circuit_hashes_root,
+            signature_proof_id: req.signature_proof_id,
+        })
+    }
+    pub fn process_mint_token_request(
+        &mut self,
+        store: &mut S,
+        req: &CityMintTokenRequest,
+    ) -> anyhow::Result<CRMintTokenCircuitInput<F>> {
+        let token_state_transition = CityStore::<S>::mint_token(
+            store,
+            self.checkpoint_id,
+            req.user_id,
+            req.to,
+            req.value,
+            req.nonce,
+        )?;
+
+        Ok(CRMintTokenCircuitInput {
+            token_state_transition,
+            allowed_circuit_hashes_root: self
+                .fingerprints
+                .op_mint_token
+                .allowed_circuit_hashes_root,
+            signature_proof_id: req.signature_proof_id,
+        })
+    }
+    pub fn process_send_token_request(
+        &mut self,
+        store: &mut S,
+        req: &CitySendTokenRequest,
+    ) -> anyhow::Result<CRSendTokenCircuitInput<F>> {
+        let token_state_transition = CityStore::<S>::send_token(
+            store,
+            self.checkpoint_id,
+            req.user_id,
+            req.token_id,
+            req.to,
+            req.value,
+            req.nonce,
+        )?;
+
+        Ok(CRSendTokenCircuitInput {
+            token_state_transition,
+            allowed_circuit_hashes_root: self
+                .fingerprints
+                .op_send_token
+                .allowed_circuit_hashes_root,
+            signature_proof_id: req.signature_proof_id,
+        })
+    }
+    pub fn process_burn_token_request(
+        &mut self,
+        store: &mut S,
+        req: &CityBurnTokenRequest,
+    ) -> anyhow::Result<CRBurnTokenCircuitInput<F>> {
+        let token_state_transition = CityStore::<S>::burn_token(
+            store,
+            self.checkpoint_id,
+            req.user_id,
+            req.token_id,
+            req.value,
+            req.nonce,
+        )?;
+
+        Ok(CRBurnTokenCircuitInput {
+            token_state_transition,
+            allowed_circuit_hashes_root: self
+                .fingerprints
+                .op_burn_token
+                .allowed_circuit_hashes_root,
+            signature_proof_id: req.signature_proof_id,
+        })
+    }
     pub fn process_complete_l1_withdrawal_request(
         &mut self,
         store: &mut S,
         req: &CityProcessWithdrawalRequest,
     ) -> anyhow::Result<CRProcessL1WithdrawalCircuitInput<F>> {
+        let (withdrawal, owner_user_id) = CityStore::<S>::get_pending_withdrawal_with_owner(
+            store,
+            self.checkpoint_id,
+            req.withdrawal_id,
+        )?
+        .ok_or_else(|| anyhow::anyhow!("withdrawal {} is not pending", req.withdrawal_id))?;
+        let withdrawal_hash: QHashOut<F> = (&withdrawal).into();
         let withdrawal_tree_delta_merkle_proof = CityStore::<S>::mark_withdrawal_as_completed(
             store,
             self.checkpoint_id,
             req.withdrawal_id,
         )?;
-        self.processed_withdrawal_hashes
-            .push(withdrawal_tree_delta_merkle_proof.new_value);
+        self.processed_withdrawal_hashes.push(withdrawal_hash);
         self.next_process_withdrawal_id += 1;
         Ok(CRProcessL1WithdrawalCircuitInput {
             withdrawal_tree_delta_merkle_proof,
+            withdrawal_hash,
+            owner_user_id,
             allowed_circuit_hashes_root: self
                 .fingerprints
                 .op_process_l1_withdrawal
                 .allowed_circuit_hashes_root,
         })
     }
+    // cancelled withdrawals are already zeroed, so they are skipped without a proof
+    pub fn skip_cancelled_l1_withdrawal(&mut self, _req: &CityProcessWithdrawalRequest) {
+        self.next_process_withdrawal_id += 1;
+    }
     pub fn process_register_user_request(
         &mut self,
         store: &mut S,
@@ -259,3 +554,119 @@ impl<S: KVQBinaryStore> CityOrchestratorOpRequestProcessor<S> {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use city_common::config::rollup_constants::L2_BATCH_TRANSFER_MAX_RECIPIENTS;
+    use city_crypto::hash::qhashout::QHashOut;
+    use city_rollup_common::{
+        api::data::{
+            block::{
+                requested_actions::CityBatchTokenTransferRequest,
+                rpc_request::CityBatchTransferRecipient,
+            },
+            store::CityL2BlockState,
+        },
+        qworker::{
+            fingerprints::CRWorkerToolboxCoreCircuitFingerprints, job_id::QProvingJobDataID,
+        },
+    };
+    use city_store::store::city::base::CityStore;
+    use kvq::memory::simple::KVQSimpleMemoryBackingStore;
+    use plonky2::field::types::PrimeField64;
+
+    use super::CityOrchestratorOpRequestProcessor;
+
+    type S = KVQSimpleMemoryBackingStore;
+
+    const SENDER_USER_ID: u64 = 3;
+
+    // users 1 and 3 to 7 are registered at checkpoint 1, the sender (user 3) holds 1000
+    fn build_store() -> S {
+        let mut store = S::new();
+        for user_id in [1, 3, 4, 5, 6, 7] {
+            let public_key = QHashOut::from_values(user_id, user_id, user_id, user_id);
+            CityStore::<S>::register_user(&mut store, 1, user_id, public_key).unwrap();
+        }
+        CityStore::<S>::increment_user_balance(&mut store, 1, SENDER_USER_ID, 1000, None).unwrap();
+        store
+    }
+
+    fn new_processor() -> CityOrchestratorOpRequestProcessor<S> {
+        let last_block_state = CityL2BlockState {
+            checkpoint_id: 1,
+            ..Default::default()
+        };
+        let fingerprints = CRWorkerToolboxCoreCircuitFingerprints {
+            fee_recipient_user_id: 1,
+            ..Default::default()
+        };
+        CityOrchestratorOpRequestProcessor::new(last_block_state, fingerprints)
+    }
+
+    fn batch_request(recipients: &[u64]) -> CityBatchTokenTransferRequest {
+        CityBatchTokenTransferRequest::new(
+            SENDER_USER_ID,
+            recipients
+                .iter()
+                .map(|to| CityBatchTransferRecipient { to: *to, value: 10 })
+                .collect(),
+            5,
+            1,
+            QProvingJobDataID::batch_transfer_signature_proof(0, 2, 0),
+        )
+    }
+
+    // the sender's balance and nonce at checkpoint 2
+    fn sender_leaf(store: &S) -> (u64, u64) {
+        let leaf = CityStore::<S>::get_user_tree_leaf(store, 2, SENDER_USER_ID * 2).unwrap();
+        (
+            leaf.0.elements[0].to_canonical_u64(),
+            leaf.0.elements[1].to_canonical_u64(),
+        )
+    }
+
+    #[test]
+    fn batch_transfer_pads_every_slot() {
+        let mut store = build_store();
+        let input = new_processor()
+            .process_batch_l2_transfer_request(&mut store, &batch_request(&[4, 5]))
+            .unwrap();
+        assert_eq!(input.recipient_count, 2);
+        assert_eq!(
+            input.receiver_user_tree_delta_merkle_proofs.len(),
+            L2_BATCH_TRANSFER_MAX_RECIPIENTS
+        );
+        assert_eq!(sender_leaf(&store), (1000 - 20 - 5, 1));
+    }
+
+    #[test]
+    fn batch_transfer_without_recipients_is_rejected() {
+        let mut store = build_store();
+        assert!(new_processor()
+            .process_batch_l2_transfer_request(&mut store, &batch_request(&[]))
+            .is_err());
+        assert_eq!(sender_leaf(&store), (1000, 0));
+    }
+
+    #[test]
+    fn batch_transfer_with_too_many_recipients_is_rejected() {
+        let mut store = build_store();
+        let recipients = (0..=L2_BATCH_TRANSFER_MAX_RECIPIENTS as u64)
+            .map(|i| 4 + i % 4)
+            .collect::<Vec<_>>();
+        assert!(new_processor()
+            .process_batch_l2_transfer_request(&mut store, &batch_request(&recipients))
+            .is_err());
+        assert_eq!(sender_leaf(&store), (1000, 0));
+    }
+
+    #[test]
+    fn batch_transfer_to_the_sender_is_rejected() {
+        let mut store = build_store();
+        assert!(new_processor()
+            .process_batch_l2_transfer_request(&mut store, &batch_request(&[4, SENDER_USER_ID]))
+            .is_err());
+        assert_eq!(sender_leaf(&store), (1000, 0));
+    }
+}
@@ -9,12 +9,16 @@ use city_rollup_common::actors::traits::{
 };
 use city_rollup_common::api::data::block::requested_actions::*;
 use city_rollup_common::api::data::block::rpc_request::{
-    CityAddWithdrawalRPCRequest, CityClaimDepositRPCRequest, CityRegisterUserRPCRequest,
-    CityTokenTransferRPCRequest,
+    CityAddWithdrawalRPCRequest, CityBatchTokenTransferRPCRequest, CityBurnTokenRPCRequest,
+    CityCancelWithdrawalRPCRequest, CityChangePublicKeyRPCRequest, CityClaimDepositRPCRequest,
+    CityMintTokenRPCRequest, CityOpenTokenAccountRPCRequest, CityRegisterUserRPCRequest,
+    CitySendTokenRPCRequest, CityTokenTransferRPCRequest,
 };
 use city_rollup_common::qworker::proof_store::QProofStore;
 use city_rollup_worker_dispatch::implementations::redis::{
-    QueueCmd, RedisQueue, Q_CMD, Q_RPC_ADD_WITHDRAWAL, Q_RPC_CLAIM_DEPOSIT, Q_RPC_REGISTER_USER,
+    QueueCmd, RedisQueue, Q_CMD, Q_RPC_ADD_WITHDRAWAL, Q_RPC_BATCH_TOKEN_TRANSFER,
+    Q_RPC_BURN_TOKEN, Q_RPC_CANCEL_WITHDRAWAL, Q_RPC_CHANGE_PUBLIC_KEY, Q_RPC_CLAIM_DEPOSIT,
+    Q_RPC_MINT_TOKEN, Q_RPC_OPEN_TOKEN_ACCOUNT, Q_RPC_REGISTER_USER, Q_RPC_SEND_TOKEN,
     Q_RPC_TOKEN_TRANSFER,
 };
 use city_rollup_worker_dispatch::traits::proving_dispatcher::ProvingDispatcher;
@@ -78,6 +82,46 @@ impl<F: RichField> CityEventReceiver<F> {
             0,
             &self.flush_rpc_requests::<CityAddWithdrawalRPCRequest>(Q_RPC_ADD_WITHDRAWAL)?,
         )?;
+        rpc_processor.process_change_public_keys(
+            proof_store,
+            0,
+            &self.flush_rpc_requests::<CityChangePublicKeyRPCRequest<F>>(
+                Q_RPC_CHANGE_PUBLIC_KEY,
+            )?,
+        )?;
+        rpc_processor.process_batch_token_transfers(
+            proof_store,
+            0,
+            &self.flush_rpc_requests::<CityBatchTokenTransferRPCRequest>(
+                Q_RPC_BATCH_TOKEN_TRANSFER,
+            )?,
+        )?;
+        rpc_processor.process_open_token_accounts(
+            proof_store,
+            0,
+            &self.flush_rpc_requests::<CityOpenTokenAccountRPCRequest>(Q_RPC_OPEN_TOKEN_ACCOUNT)?,
+        )?;
+        rpc_processor.process_mint_tokens(
+            proof_store,
+            0,
+            &self.flush_rpc_requests::<CityMintTokenRPCRequest>(Q_RPC_MINT_TOKEN)?,
+        )?;
+        rpc_processor.process_send_tokens(
+            proof_store,
+            0,
+            &self.flush_rpc_requests::<CitySendTokenRPCRequest>(Q_RPC_SEND_TOKEN)?,
+        )?;
+        rpc_processor.process_burn_tokens(
+            proof_store,
+            0,
+            &self.flush_rpc_requests::<CityBurnTokenRPCRequest>(Q_RPC_BURN_TOKEN)?,
+        )?;
+        rpc_processor.process_cancel_withdrawals(
+            proof_store,
+            0,
+            &self
+                .flush_rpc_requests::<CityCancelWithdrawalRPCRequest<F>>(Q_RPC_CANCEL_WITHDRAWAL)?,
+        )?;
         tracing::info!(
             "rpc requests: {}",
             serde_json::to_string(&rpc_processor.output).unwrap()
@@ -121,6 +165,78 @@ impl<F: RichField> OrchestratorEventReceiverSync<F> for CityEventReceiver<F> {
         Ok(res)
     }
 
+    fn flush_change_public_keys(
+        &mut self,
+    ) -> anyhow::Result<Vec<CityChangePublicKeyRequest<F>>> {
+        let reqs = self
+            .flush_rpc_requests::<CityChangePublicKeyRPCRequest<F>>(Q_RPC_CHANGE_PUBLIC_KEY)?;
+        self.rpc_processor
+            .process_change_public_keys(&mut self.proof_store, 0, &reqs)?;
+        let mut res: Vec<CityChangePublicKeyRequest<F>> = Vec::new();
+        res.append(&mut self.rpc_processor.output.change_public_keys);
+        Ok(res)
+    }
+
+    fn flush_batch_token_transfers(
+        &mut self,
+    ) -> anyhow::Result<Vec<CityBatchTokenTransferRequest>> {
+        let reqs = self.flush_rpc_requests::<CityBatchTokenTransferRPCRequest>(
+            Q_RPC_BATCH_TOKEN_TRANSFER,
+        )?;
+        self.rpc_processor
+            .process_batch_token_transfers(&mut self.proof_store, 0, &reqs)?;
+        let mut res: Vec<CityBatchTokenTransferRequest> = Vec::new();
+        res.append(&mut self.rpc_processor.output.batch_token_transfers);
+        Ok(res)
+    }
+
+    fn flush_open_token_accounts(&mut self) -> anyhow::Result<Vec<CityOpenTokenAccountRequest>> {
+        let reqs =
+            self.flush_rpc_requests::<CityOpenTokenAccountRPCRequest>(Q_RPC_OPEN_TOKEN_ACCOUNT)?;
+        self.rpc_processor
+            .process_open_token_accounts(&mut self.proof_store, 0, &reqs)?;
+        let mut res: Vec<CityOpenTokenAccountRequest> = Vec::new();
+        res.append(&mut self.rpc_processor.output.open_token_accounts);
+        Ok(res)
+    }
+
+    fn flush_mint_tokens(&mut self) -> anyhow::Result<Vec<CityMintTokenRequest>> {
+        let reqs = self.flush_rpc_requests::<CityMintTokenRPCRequest>(Q_RPC_MINT_TOKEN)?;
+        self.rpc_processor
+            .process_mint_tokens(&mut self.proof_store, 0, &reqs)?;
+        let mut res: Vec<CityMintTokenRequest> = Vec::new();
+        res.append(&mut self.rpc_processor.output.mint_tokens);
+        Ok(res)
+    }
+
+    fn flush_send_tokens(&mut self) -> anyhow::Result<Vec<CitySendTokenRequest>> {
+        let reqs = self.flush_rpc_requests::<CitySendTokenRPCRequest>(Q_RPC_SEND_TOKEN)?;
+        self.rpc_processor
+            .process_send_tokens(&mut self.proof_store, 0, &reqs)?;
+        let mut res: Vec<CitySendTokenRequest> = Vec::new();
+        res.append(&mut self.rpc_processor.output.send_tokens);
+        Ok(res)
+    }
+
+    fn flush_burn_tokens(&mut self) -> anyhow::Result<Vec<CityBurnTokenRequest>> {
+        let reqs = self.flush_rpc_requests::<CityBurnTokenRPCRequest>(Q_RPC_BURN_TOKEN)?;
+        self.rpc_processor
+            .process_burn_tokens(&mut self.proof_store, 0, &reqs)?;
+        let mut res: Vec<CityBurnTokenRequest> = Vec::new();
+        res.append(&mut self.rpc_processor.output.burn_tokens);
+        Ok(res)
+    }
+
+    fn flush_cancel_withdrawals(&mut self) -> anyhow::Result<Vec<CityCancelWithdrawalRequest<F>>> {
+        let reqs =
+            self.flush_rpc_requests::<CityCancelWithdrawalRPCRequest<F>>(Q_RPC_CANCEL_WITHDRAWAL)?;
+        self.rpc_processor
+            .process_cancel_withdrawals(&mut self.proof_store, 0, &reqs)?;
+        let mut res: Vec<CityCancelWithdrawalRequest<F>> = Vec::new();
+        res.append(&mut self.rpc_processor.output.cancel_withdrawals);
+        Ok(res)
+    }
+
     fn wait_for_produce_block(&mut self) -> anyhow::Result<bool> {
         loop {
             match self
@@ -174,6 +290,57 @@ impl<F: RichField> OrchestratorRPCEventSenderSync<F> for CityEventReceiver<F> {
         Ok(())
     }
 
+    fn notify_rpc_change_public_key(
+        &mut self,
+        event: &CityChangePublicKeyRPCRequest<F>,
+    ) -> anyhow::Result<()> {
+        self.tx_queue
+            .dispatch(Q_RPC_CHANGE_PUBLIC_KEY, event.clone())?;
+        Ok(())
+    }
+
+    fn notify_rpc_batch_token_transfer(
+        &mut self,
+        event: &CityBatchTokenTransferRPCRequest,
+    ) -> anyhow::Result<()> {
+        self.tx_queue
+            .dispatch(Q_RPC_BATCH_TOKEN_TRANSFER, event.clone())?;
+        Ok(())
+    }
+
+    fn notify_rpc_open_token_account(
+        &mut self,
+        event: &CityOpenTokenAccountRPCRequest,
+    ) -> anyhow::Result<()> {
+        self.tx_queue
+            .dispatch(Q_RPC_OPEN_TOKEN_ACCOUNT, event.clone())?;
+        Ok(())
+    }
+
+    fn notify_rpc_mint_token(&mut self, event: &CityMintTokenRPCRequest) -> anyhow::Result<()> {
+        self.tx_queue.dispatch(Q_RPC_MINT_TOKEN, event.clone())?;
+        Ok(())
+    }
+
+    fn notify_rpc_send_token(&mut self, event: &CitySendTokenRPCRequest) -> anyhow::Result<()> {
+        self.tx_queue.dispatch(Q_RPC_SEND_TOKEN, event.clone())?;
+        Ok(())
+    }
+
+    fn notify_rpc_burn_token(&mut self, event: &CityBurnTokenRPCRequest) -> anyhow::Result<()> {
+        self.tx_queue.dispatch(Q_RPC_BURN_TOKEN, event.clone())?;
+        Ok(())
+    }
+
+    fn notify_rpc_cancel_withdrawal(
+        &mut self,
+        event: &CityCancelWithdrawalRPCRequest<F>,
+    ) -> anyhow::Result<()> {
+        self.tx_queue
+            .dispatch(Q_RPC_CANCEL_WITHDRAWAL, event.clone())?;
+        Ok(())
+    }
+
     fn notify_rpc_produce_block(&mut self) -> anyhow::Result<()> {
         self.tx_queue.dispatch(Q_CMD, QueueCmd::ProduceBlock)?;
         Ok(())
@@ -1,5 +1,3 @@
-use std::time::Duration;
-
 use city_common::{cli::modes::QWorkerMode, logging::trace_timer::TraceTimer};
 use city_rollup_circuit::worker::traits::{QWorkerGenericProverGroth16, QWorkerGenericProverMut};
 use city_rollup_common::{
@@ -26,7 +24,7 @@ impl SimpleActorWorker {
         prover: &mut G,
     ) -> anyhow::Result<()> {
         loop {
-            Self::process_next_job(store, event_receiver, prover, QWorkerMode::All)?;
+            Self::process_next_job(store, event_receiver, prover, QWorkerMode::ALL)?;
         }
     }
     pub fn process_next_job<
@@ -46,11 +44,14 @@ impl SimpleActorWorker {
         let job = event_receiver.wait_for_next_job()?;
         if mode.can_process_job(job) {
             tracing::info!("job: {:?}", job);
+            event_receiver.notify_job_started(job)?;
             Self::process_job(store, event_receiver, prover, job)?;
+            event_receiver.notify_job_finished(job)?;
             //timer.lap("processed next job");
         } else {
-            event_receiver.enqueue_jobs(&[job])?;
-            std::thread::sleep(Duration::from_millis(750));
+            // jobs are routed to per-capability queues, so this only happens if a job was dispatched to the wrong queue
+            tracing::warn!("worker mode {} cannot process job {:?}, rerouting", mode, job);
+            event_receiver.enqueue_prioritized_jobs(&store.get_prioritized_jobs(&[job])?)?;
         }
         Ok(())
     }
@@ -87,6 +88,7 @@ impl SimpleActorWorker {
                 }
             };
             let duration = start_time.elapsed().as_millis() as u64;
+            store.set_bytes_by_id(job_id.get_benchmark_id(), &duration.to_le_bytes())?;
             event_receiver.record_job_bench(job_id, duration)?;
         }
         if job_id.topic == QJobTopic::NotifyOrchestratorComplete {
@@ -96,12 +98,13 @@ impl SimpleActorWorker {
 
         let goal_counter = store.get_goal_by_job_id(job_id)?;
         //tracing::info!("goal_counter: {}", goal_counter);
-        if goal_counter != 0 {
+        // a requeued job may also be completed by the worker it was taken from
+        if goal_counter != 0 && store.mark_job_completed(job_id)? {
             let result = store.inc_counter_by_id(job_id.get_sub_group_counter_id())?;
             if result == goal_counter {
                 let jobs = store.get_next_jobs_by_job_id(job_id)?;
                 //tracing::info!("[{:?}] enqueuing_jobs: {:?}", job_id, jobs);
-                event_receiver.enqueue_jobs(&jobs)?;
+                event_receiver.enqueue_prioritized_jobs(&store.get_prioritized_jobs(&jobs)?)?;
             }
         }
         timer.event(format!(
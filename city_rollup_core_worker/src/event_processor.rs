@@ -1,49 +1,94 @@
 use std::time::Duration;
 
+use city_common::cli::modes::QWorkerMode;
 use city_rollup_common::{
     actors::traits::{WorkerEventReceiverSync, WorkerEventTransmitterSync},
-    qworker::job_id::{QProvingJobDataID, QWorkerJobBenchmark},
+    qworker::{
+        job_id::{QProvingJobDataID, QWorkerJobBenchmark},
+        priority::QPrioritizedJob,
+    },
 };
 use city_rollup_worker_dispatch::{
-    implementations::redis::{QueueNotification, RedisQueue, Q_JOB, Q_NOTIFICATIONS},
-    traits::{proving_dispatcher::ProvingDispatcher, proving_worker::ProvingWorkerListener},
+    implementations::redis::{
+        get_job_queue_for_capability, QueueNotification, RedisQueue, Q_NOTIFICATIONS,
+    },
+    traits::{
+        proving_dispatcher::ProvingDispatcher, proving_priority_queue::ProvingPriorityQueue,
+        proving_worker::ProvingWorkerListener,
+    },
 };
+
+use crate::heartbeat::QWorkerHeartbeat;
+
 #[derive(Clone)]
 pub struct CityEventProcessor {
     pub job_queue: RedisQueue,
+    pub job_topics: Vec<&'static str>,
     pub benckmarks_enabled: bool,
     pub benchmarks: Vec<QWorkerJobBenchmark>,
+    pub heartbeat: Option<QWorkerHeartbeat>,
 }
 impl CityEventProcessor {
     pub fn new(dispatcher: RedisQueue) -> Self {
         Self::new_with_config(dispatcher, false)
     }
     pub fn new_with_config(dispatcher: RedisQueue, benckmarks_enabled: bool) -> Self {
+        Self::new_with_mode(dispatcher, QWorkerMode::ALL, benckmarks_enabled)
+    }
+    pub fn new_with_mode(
+        dispatcher: RedisQueue,
+        mode: QWorkerMode,
+        benckmarks_enabled: bool,
+    ) -> Self {
         Self {
             job_queue: dispatcher,
+            job_topics: mode
+                .get_capabilities()
+                .into_iter()
+                .map(get_job_queue_for_capability)
+                .collect(),
             benckmarks_enabled,
             benchmarks: Vec::new(),
+            heartbeat: None,
         }
     }
+    pub fn with_heartbeat(self, heartbeat: QWorkerHeartbeat) -> Self {
+        Self {
+            heartbeat: Some(heartbeat),
+            ..self
+        }
+    }
+    pub fn has_pending_jobs(&mut self) -> bool {
+        self.job_topics
+            .iter()
+            .any(|topic| !self.job_queue.is_topic_empty(*topic))
+    }
+    fn dispatch_jobs(&mut self, jobs: &[QPrioritizedJob]) -> anyhow::Result<()> {
+        for job in jobs {
+            self.job_queue.dispatch_with_priority(
+                get_job_queue_for_capability(job.job_id.get_required_capability()),
+                job.job_id,
+                job.priority,
+            )?;
+        }
+        Ok(())
+    }
 }
 impl WorkerEventReceiverSync for CityEventProcessor {
     fn wait_for_next_job(&mut self) -> anyhow::Result<QProvingJobDataID> {
         loop {
-            let job = self.job_queue.pop_one(Q_JOB)?;
-            if job.is_some() {
-                return Ok(serde_json::from_slice(&job.unwrap())?)
-            }else{
-                std::thread::sleep(Duration::from_millis(250));
-                continue;
+            for topic in self.job_topics.iter() {
+                let job = self.job_queue.pop_highest_priority(*topic)?;
+                if job.is_some() {
+                    return Ok(serde_json::from_slice(&job.unwrap())?);
+                }
             }
+            std::thread::sleep(Duration::from_millis(250));
         }
     }
 
-    fn enqueue_jobs(&mut self, jobs: &[QProvingJobDataID]) -> anyhow::Result<()> {
-        for job in jobs {
-            self.job_queue.dispatch(Q_JOB, job.clone())?;
-        }
-        Ok(())
+    fn enqueue_prioritized_jobs(&mut self, jobs: &[QPrioritizedJob]) -> anyhow::Result<()> {
+        self.dispatch_jobs(jobs)
     }
 
     fn notify_core_goal_completed(&mut self, _job: QProvingJobDataID) -> anyhow::Result<()> {
@@ -60,16 +105,27 @@ impl WorkerEventReceiverSync for CityEventProcessor {
         }
         Ok(())
     }
-}
 
-impl WorkerEventTransmitterSync for CityEventProcessor {
-    fn enqueue_jobs(&mut self, jobs: &[QProvingJobDataID]) -> anyhow::Result<()> {
-        for job in jobs {
-            self.job_queue.dispatch(Q_JOB, job.clone())?;
+    fn notify_job_started(&mut self, job: QProvingJobDataID) -> anyhow::Result<()> {
+        if let Some(heartbeat) = self.heartbeat.as_ref() {
+            heartbeat.start_job(job);
         }
         Ok(())
     }
 
+    fn notify_job_finished(&mut self, _job: QProvingJobDataID) -> anyhow::Result<()> {
+        if let Some(heartbeat) = self.heartbeat.as_ref() {
+            heartbeat.finish_job();
+        }
+        Ok(())
+    }
+}
+
+impl WorkerEventTransmitterSync for CityEventProcessor {
+    fn enqueue_prioritized_jobs(&mut self, jobs: &[QPrioritizedJob]) -> anyhow::Result<()> {
+        self.dispatch_jobs(jobs)
+    }
+
     fn wait_for_block_proving_jobs(&mut self, _checkpoint_id: u64) -> anyhow::Result<bool> {
         loop {
             match self
@@ -0,0 +1,129 @@
+use std::{
+    sync::{
+        mpsc::{channel, RecvTimeoutError, Sender},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use city_common::cli::args::L2WorkerArgs;
+use city_rollup_common::qworker::{
+    fingerprints::CRWorkerToolboxCoreCircuitFingerprints,
+    fleet::{get_unix_timestamp_ms, QWorkerInfo, QWorkerRegistrySync},
+    job_id::QProvingJobDataID,
+};
+use plonky2::field::goldilocks_field::GoldilocksField;
+
+pub fn get_hostname() -> String {
+    std::env::var("HOSTNAME")
+        .or_else(|_| std::fs::read_to_string("/etc/hostname"))
+        .map(|x| x.trim().to_string())
+        .ok()
+        .filter(|x| !x.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+pub fn get_rejoined_worker_id(worker_id: &str, rejoins: u64) -> String {
+    format!("{}-rejoined-{}", worker_id, rejoins)
+}
+
+#[derive(Clone)]
+pub struct QWorkerHeartbeat {
+    info: Arc<Mutex<QWorkerInfo>>,
+    // wakes the heartbeat thread so job changes are published right away
+    notify: Sender<()>,
+}
+
+impl QWorkerHeartbeat {
+    pub fn start<R: QWorkerRegistrySync + Send + 'static>(
+        mut registry: R,
+        info: QWorkerInfo,
+    ) -> anyhow::Result<Self> {
+        registry.register_worker(&info)?;
+        let interval = Duration::from_millis(info.heartbeat_interval);
+        let info = Arc::new(Mutex::new(info));
+        let (notify, receiver) = channel::<()>();
+
+        let thread_info = info.clone();
+        let base_worker_id = info.lock().unwrap().worker_id.clone();
+        let mut rejoins = 0u64;
+        std::thread::spawn(move || loop {
+            if let Err(RecvTimeoutError::Disconnected) = receiver.recv_timeout(interval) {
+                break;
+            }
+            let snapshot = thread_info.lock().unwrap().clone();
+            match registry.heartbeat_worker(&snapshot) {
+                Ok(true) => {}
+                // the orchestrator declared this worker dead (e.g. after a long pause) and requeued
+                // its job, so rejoin the fleet under a new id instead of vanishing from the registry
+                Ok(false) => {
+                    rejoins += 1;
+                    let rejoined = {
+                        let mut info = thread_info.lock().unwrap();
+                        info.worker_id = get_rejoined_worker_id(&base_worker_id, rejoins);
+                        info.jobs_completed = 0;
+                        info.busy_time = 0;
+                        info.clone()
+                    };
+                    tracing::warn!(
+                        "worker {} was removed from the registry, rejoining as {}",
+                        snapshot.worker_id,
+                        rejoined.worker_id
+                    );
+                    if let Err(err) = registry.register_worker(&rejoined) {
+                        tracing::error!(
+                            "failed to register worker {}: {}",
+                            rejoined.worker_id,
+                            err
+                        );
+                    }
+                }
+                Err(err) => {
+                    tracing::error!(
+                        "failed to send heartbeat for worker {}: {}",
+                        snapshot.worker_id,
+                        err
+                    );
+                }
+            }
+        });
+        Ok(Self { info, notify })
+    }
+    pub fn start_for_worker<R: QWorkerRegistrySync + Send + 'static>(
+        registry: R,
+        args: &L2WorkerArgs,
+        fingerprints: CRWorkerToolboxCoreCircuitFingerprints<GoldilocksField>,
+    ) -> anyhow::Result<Self> {
+        let hostname = get_hostname();
+        let pid = std::process::id();
+        let worker_id = args
+            .worker_id
+            .clone()
+            .unwrap_or_else(|| format!("{}-{}", hostname, pid));
+        Self::start(
+            registry,
+            QWorkerInfo::new(
+                worker_id,
+                args.worker_mode,
+                hostname,
+                pid,
+                args.heartbeat_interval,
+            )
+            .with_circuit_fingerprints(fingerprints),
+        )
+    }
+    pub fn start_job(&self, job: QProvingJobDataID) {
+        self.info
+            .lock()
+            .unwrap()
+            .start_job(job, get_unix_timestamp_ms());
+        let _ = self.notify.send(());
+    }
+    pub fn finish_job(&self) {
+        self.info.lock().unwrap().finish_job(get_unix_timestamp_ms());
+        let _ = self.notify.send(());
+    }
+    pub fn get_info(&self) -> QWorkerInfo {
+        self.info.lock().unwrap().clone()
+    }
+}
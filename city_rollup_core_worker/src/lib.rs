@@ -3,31 +3,94 @@ pub mod actors;
 use std::time::Duration;
 
 use city_common::cli::args::L2WorkerArgs;
+use city_common_circuit::serialization::circuit_cache::{
+    get_current_exe_build_id, QCircuitDataCache,
+};
 use city_redis_store::RedisStore;
+use city_rollup_circuit::worker::toolbox::circuits::CRWorkerToolboxCoreCircuits;
 use city_rollup_circuit::worker::toolbox::root::CRWorkerToolboxRootCircuits;
 use city_rollup_common::{block_template::config::GROTH16_DISABLED_DEV_MODE, config::sighash_wrapper_config::SIGHASH_WHITELIST_TREE_ROOT};
 use city_rollup_common::introspection::rollup::constants::get_network_magic_for_str;
+use city_rollup_common::qworker::fingerprints::CRWorkerToolboxCoreCircuitFingerprints;
+use city_rollup_common::qworker::fleet::QWorkerRegistrySync;
 use city_rollup_worker_dispatch::implementations::redis::RedisQueue;
-use city_rollup_worker_dispatch::traits::proving_worker::ProvingWorkerListener;
+use plonky2::field::goldilocks_field::GoldilocksField;
 use plonky2::plonk::config::PoseidonGoldilocksConfig;
 
 use crate::actors::simple::SimpleActorWorker;
 use crate::event_processor::CityEventProcessor;
+use crate::heartbeat::QWorkerHeartbeat;
 
 use crossterm::{
     event::{poll, read, Event, KeyCode},
     terminal::{disable_raw_mode, enable_raw_mode},
 };
 pub mod event_processor;
+pub mod heartbeat;
 
 pub const PROVING_INTERVAL: u64 = 30000;
 
 const D: usize = 2;
 type C = PoseidonGoldilocksConfig;
-pub fn run_debug_outer(args: L2WorkerArgs) -> anyhow::Result<()> {
+type F = GoldilocksField;
+
+const NO_FINGERPRINT_MANIFEST_ERROR: &str = "no circuit fingerprint manifest has been published by the orchestrator, start the orchestrator before the workers";
+
+// refuses to start a worker whose circuits differ from the manifest published by the orchestrator
+pub fn check_fingerprint_manifest<R: QWorkerRegistrySync>(
+    registry: &R,
+    fingerprints: &CRWorkerToolboxCoreCircuitFingerprints<F>,
+) -> anyhow::Result<()> {
+    match registry.get_fingerprint_manifest()? {
+        Some(manifest) => fingerprints.ensure_matches_manifest(&manifest),
+        None => anyhow::bail!(NO_FINGERPRINT_MANIFEST_ERROR),
+    }
+}
+
+// computes the core circuit fingerprints for a network and writes them as a deployment manifest
+pub fn write_fingerprint_manifest(
+    network: &str,
+    fee_recipient_user_id: u64,
+    path: &str,
+) -> anyhow::Result<()> {
+    let network_magic = get_network_magic_for_str(network.to_string())?;
+    let toolbox = CRWorkerToolboxCoreCircuits::<C, D>::new(network_magic, fee_recipient_user_id);
+    toolbox.fingerprints.write_manifest_file(path)
+}
+
+// builds the worker's circuits, loading them from the circuit cache when --circuit-cache-dir is set
+pub fn build_toolbox<R: QWorkerRegistrySync>(
+    args: &L2WorkerArgs,
+    registry: &R,
+) -> anyhow::Result<CRWorkerToolboxRootCircuits<C, D>> {
     let network_magic = get_network_magic_for_str(args.network.to_string())?;
-    let mut toolbox =
-        CRWorkerToolboxRootCircuits::<C, D>::new(network_magic, SIGHASH_WHITELIST_TREE_ROOT);
+    // fail before building the circuits, they are checked against the manifest afterwards
+    let manifest = registry
+        .get_fingerprint_manifest()?
+        .ok_or_else(|| anyhow::anyhow!(NO_FINGERPRINT_MANIFEST_ERROR))?;
+    manifest.ensure_network_magic(&args.network, network_magic)?;
+    // the cache is keyed on the circuits this executable builds, never on the manifest
+    let session = match &args.circuit_cache_dir {
+        Some(dir) => Some(
+            QCircuitDataCache::open_build(dir, network_magic, &get_current_exe_build_id()?)?
+                .activate(),
+        ),
+        None => None,
+    };
+    let toolbox = CRWorkerToolboxRootCircuits::<C, D>::new(
+        network_magic,
+        args.fee_recipient_user_id,
+        SIGHASH_WHITELIST_TREE_ROOT,
+    );
+    if let Some(session) = session {
+        session.finish()?;
+    }
+
+    check_fingerprint_manifest(registry, &toolbox.core.fingerprints)?;
+    Ok(toolbox)
+}
+pub fn run_debug_outer(args: L2WorkerArgs) -> anyhow::Result<()> {
+    let mut toolbox = build_toolbox(&args, &RedisStore::new(&args.redis_uri)?)?;
     /*println!(
         "CRWorkerToolboxCoreCircuitFingerprints: {}",
         serde_json::to_string(&toolbox.core.fingerprints).unwrap()
@@ -63,12 +126,18 @@ pub fn run_debug_inner(
 ) -> anyhow::Result<()> {
     let job_queue = RedisQueue::new(&args.redis_uri)?;
     let mut proof_store = RedisStore::new(&args.redis_uri)?;
-    let mut event_processor = CityEventProcessor::new_with_config(job_queue.clone(), true);
+    let mut event_processor =
+        CityEventProcessor::new_with_mode(job_queue.clone(), args.worker_mode, true)
+            .with_heartbeat(QWorkerHeartbeat::start_for_worker(
+                proof_store.clone(),
+                args,
+                toolbox.core.fingerprints,
+            )?);
 
     let mut should_print_benchmark = false;
     loop {
         'inner: loop {
-            if event_processor.job_queue.is_empty() {
+            if !event_processor.has_pending_jobs() {
                 break 'inner;
             }
             SimpleActorWorker::process_next_job(
@@ -107,11 +176,15 @@ pub fn run(args: L2WorkerArgs) -> anyhow::Result<()> {
     }
     let job_queue = RedisQueue::new(&args.redis_uri)?;
     let mut proof_store = RedisStore::new(&args.redis_uri)?;
-    let network_magic = get_network_magic_for_str(args.network.to_string())?;
-    let mut event_processor = CityEventProcessor::new(job_queue.clone());
+    let mut toolbox = build_toolbox(&args, &proof_store)?;
 
-    let mut toolbox =
-        CRWorkerToolboxRootCircuits::<C, D>::new(network_magic, SIGHASH_WHITELIST_TREE_ROOT);
+    let mut event_processor =
+        CityEventProcessor::new_with_mode(job_queue.clone(), args.worker_mode, false)
+            .with_heartbeat(QWorkerHeartbeat::start_for_worker(
+                proof_store.clone(),
+                &args,
+                toolbox.core.fingerprints,
+            )?);
 
     //println!("fingerprints:\n{}", serde_json::to_string(&toolbox.core.fingerprints).unwrap());
     if GROTH16_DISABLED_DEV_MODE {
@@ -130,7 +203,7 @@ pub fn run(args: L2WorkerArgs) -> anyhow::Result<()> {
 
     loop {
         'inner: loop {
-            if event_processor.job_queue.is_empty() {
+            if !event_processor.has_pending_jobs() {
                 break 'inner;
             }
             SimpleActorWorker::process_next_job(
@@ -144,3 +217,23 @@ pub fn run(args: L2WorkerArgs) -> anyhow::Result<()> {
         std::thread::sleep(Duration::from_secs(1))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use city_rollup_common::qworker::{
+        fingerprints::CRWorkerToolboxCoreCircuitFingerprints,
+        fleet::{QWorkerRegistryMemory, QWorkerRegistrySync},
+    };
+
+    use super::check_fingerprint_manifest;
+
+    #[test]
+    fn missing_fingerprint_manifest_is_an_error() {
+        let mut registry = QWorkerRegistryMemory::new();
+        let fingerprints = CRWorkerToolboxCoreCircuitFingerprints::default();
+        assert!(check_fingerprint_manifest(&registry, &fingerprints).is_err());
+
+        registry.set_fingerprint_manifest(&fingerprints).unwrap();
+        assert!(check_fingerprint_manifest(&registry, &fingerprints).is_ok());
+    }
+}
@@ -47,6 +47,30 @@ pub fn get_rpc_proof_dependencies(
         )
     });
 
+    let change_public_key_signature_proof_ids = (0..config.job_config.change_public_key_count).map(|i| {
+        QProvingJobDataID::change_public_key_signature_proof(config.rpc_node_id, config.checkpoint_id, i as u32)
+    });
+
+    let batch_token_transfer_signature_proof_ids = (0..config.job_config.batch_token_transfer_count).map(|i| {
+        QProvingJobDataID::batch_transfer_signature_proof(config.rpc_node_id, config.checkpoint_id, i as u32)
+    });
+
+    let open_token_account_signature_proof_ids = (0..config.job_config.open_token_account_count).map(|i| {
+        QProvingJobDataID::open_token_account_signature_proof(config.rpc_node_id, config.checkpoint_id, i as u32)
+    });
+
+    let mint_token_signature_proof_ids = (0..config.job_config.mint_token_count).map(|i| {
+        QProvingJobDataID::mint_token_signature_proof(config.rpc_node_id, config.checkpoint_id, i as u32)
+    });
+
+    let send_token_signature_proof_ids = (0..config.job_config.send_token_count).map(|i| {
+        QProvingJobDataID::send_token_signature_proof(config.rpc_node_id, config.checkpoint_id, i as u32)
+    });
+
+    let burn_token_signature_proof_ids = (0..config.job_config.burn_token_count).map(|i| {
+        QProvingJobDataID::burn_token_signature_proof(config.rpc_node_id, config.checkpoint_id, i as u32)
+    });
+
     let withdrawal_signature_proof_ids = (0..config.job_config.token_transfer_count).map(|i| {
         QProvingJobDataID::withdrawal_signature_proof(config.rpc_node_id, config.checkpoint_id, i as u32)
     });
@@ -54,6 +78,12 @@ pub fn get_rpc_proof_dependencies(
     Ok(token_transfer_signature_proof_ids
         .chain(claim_deposit_signature_proof_ids)
         .chain(withdrawal_signature_proof_ids)
+        .chain(change_public_key_signature_proof_ids)
+        .chain(batch_token_transfer_signature_proof_ids)
+        .chain(open_token_account_signature_proof_ids)
+        .chain(mint_token_signature_proof_ids)
+        .chain(send_token_signature_proof_ids)
+        .chain(burn_token_signature_proof_ids)
         .collect())
 }
 pub fn dump_proof_store<PS: QProofStoreReaderSync>(
@@ -129,14 +159,28 @@ pub fn get_proof_store_config<PS: QProofStoreReaderSync>(store: &PS, checkpoint_
     let register_user_count = get_leaf_count_or_dummy(store, ProvingJobCircuitType::RegisterUser, ProvingJobCircuitType::DummyRegisterUserAggregate, checkpoint_id)?;
     let add_deposit_count = get_leaf_count_or_dummy(store, ProvingJobCircuitType::AddL1Deposit, ProvingJobCircuitType::DummyAddL1DepositAggregate, checkpoint_id)?;
     let token_transfer_count = get_leaf_count_or_dummy(store, ProvingJobCircuitType::TransferTokensL2, ProvingJobCircuitType::DummyTransferTokensL2Aggregate, checkpoint_id)?;
+    let change_public_key_count = get_leaf_count_or_dummy(store, ProvingJobCircuitType::ChangePublicKey, ProvingJobCircuitType::DummyChangePublicKeyAggregate, checkpoint_id)?;
+    let batch_token_transfer_count = get_leaf_count_or_dummy(store, ProvingJobCircuitType::BatchTransferTokensL2, ProvingJobCircuitType::DummyBatchTransferTokensL2Aggregate, checkpoint_id)?;
+    let open_token_account_count = get_leaf_count_or_dummy(store, ProvingJobCircuitType::OpenTokenAccount, ProvingJobCircuitType::DummyOpenTokenAccountAggregate, checkpoint_id)?;
+    let mint_token_count = get_leaf_count_or_dummy(store, ProvingJobCircuitType::MintToken, ProvingJobCircuitType::DummyMintTokenAggregate, checkpoint_id)?;
+    let send_token_count = get_leaf_count_or_dummy(store, ProvingJobCircuitType::SendToken, ProvingJobCircuitType::DummySendTokenAggregate, checkpoint_id)?;
+    let burn_token_count = get_leaf_count_or_dummy(store, ProvingJobCircuitType::BurnToken, ProvingJobCircuitType::DummyBurnTokenAggregate, checkpoint_id)?;
     let add_withdrawal_count = get_leaf_count_or_dummy(store, ProvingJobCircuitType::AddL1Withdrawal, ProvingJobCircuitType::DummyAddL1WithdrawalAggregate, checkpoint_id)?;
+    let cancel_withdrawal_count = get_leaf_count_or_dummy(store, ProvingJobCircuitType::CancelL1Withdrawal, ProvingJobCircuitType::DummyCancelL1WithdrawalAggregate, checkpoint_id)?;
     let process_withdrawal_count = get_leaf_count_or_dummy(store, ProvingJobCircuitType::ProcessL1Withdrawal, ProvingJobCircuitType::DummyProcessL1WithdrawalAggregate, checkpoint_id)?;
     let claim_deposit_count = get_leaf_count_or_dummy(store, ProvingJobCircuitType::ClaimL1Deposit, ProvingJobCircuitType::DummyClaimL1DepositAggregate, checkpoint_id)?;
     let job_config = CityOpJobConfig {
         register_user_count,
         claim_deposit_count,
         token_transfer_count,
+        change_public_key_count,
+        batch_token_transfer_count,
+        open_token_account_count,
+        mint_token_count,
+        send_token_count,
+        burn_token_count,
         add_withdrawal_count,
+        cancel_withdrawal_count,
         process_withdrawal_count,
         add_deposit_count,
     };
@@ -1,11 +1,21 @@
+use std::collections::HashMap;
+
 use crate::dump::BlockProofStoreDump;
-use city_common::cli::{args::QBenchArgs, modes::QWorkerMode};
+use city_common::{
+    cli::{args::QBenchArgs, modes::QWorkerMode},
+    config::rollup_constants::DEFAULT_SEQUENCER_FEE_RECIPIENT_USER_ID,
+};
 use city_rollup_circuit::worker::toolbox::root::CRWorkerToolboxRootCircuits;
 use city_rollup_common::{
     actors::{simple::events::CityEventProcessorMemory, traits::WorkerEventTransmitterSync},
     config::sighash_wrapper_config::SIGHASH_WHITELIST_TREE_ROOT,
     introspection::rollup::constants::get_network_magic_for_str,
-    qworker::job_id::QWorkerJobBenchmark,
+    qworker::{
+        dump::dump_job_dependencies_from_store,
+        job_id::{QProvingJobDataID, QWorkerJobBenchmark},
+        priority::{compute_job_priorities, get_job_priority_weight, simulate_block_proving_time},
+        proof_store::QProofStoreReaderSync,
+    },
 };
 use city_rollup_core_orchestrator::debug::scenario::actors::job_planner::plan_jobs;
 use city_rollup_core_orchestrator::debug::scenario::block_planner::transition::CityOpJobIds;
@@ -21,8 +31,11 @@ pub fn run_worker_qbench(
     println!("Initializing QBench (this may take a few minutes)...");
     let network_magic = get_network_magic_for_str(args.network.to_string())?;
 
-    let mut toolbox =
-        CRWorkerToolboxRootCircuits::<C, D>::new(network_magic, SIGHASH_WHITELIST_TREE_ROOT);
+    let mut toolbox = CRWorkerToolboxRootCircuits::<C, D>::new(
+        network_magic,
+        DEFAULT_SEQUENCER_FEE_RECIPIENT_USER_ID,
+        SIGHASH_WHITELIST_TREE_ROOT,
+    );
 
     gnark_plonky2_wrapper::initialize(&format!(
         "{}/.city-rollup/keystore/",
@@ -54,14 +67,70 @@ pub fn run_worker_qbench(
                     &mut proof_store,
                     &mut event_processor,
                     &mut toolbox,
-                    QWorkerMode::All,
+                    QWorkerMode::ALL,
                 )?
             }
         }
+        if let Some(num_workers) = args.simulate_workers {
+            let leaves = plan_jobs(
+                &mut proof_store,
+                &block_op_job_ids,
+                num_input_witnesses,
+                dump_config.checkpoint_id,
+            )?;
+            print_scheduling_simulation(
+                &proof_store,
+                &leaves,
+                dump_config.checkpoint_id,
+                num_workers,
+                &event_processor.benchmarks,
+            )?;
+        }
         benchmark_results.append(&mut event_processor.benchmarks);
     }
     Ok(benchmark_results)
 }
+fn print_scheduling_simulation<PS: QProofStoreReaderSync>(
+    proof_store: &PS,
+    leaves: &[QProvingJobDataID],
+    checkpoint_id: u64,
+    num_workers: usize,
+    benchmarks: &[QWorkerJobBenchmark],
+) -> anyhow::Result<()> {
+    let mut samples: HashMap<QProvingJobDataID, Vec<u64>> = HashMap::new();
+    for bench in benchmarks.iter() {
+        samples
+            .entry(QProvingJobDataID::try_from(bench.job_id)?)
+            .or_default()
+            .push(bench.duration);
+    }
+    let durations = samples
+        .into_iter()
+        .map(|(job, s)| (job, s.iter().sum::<u64>() / (s.len() as u64)))
+        .collect::<HashMap<_, _>>();
+    let duration = |job: &QProvingJobDataID| durations.get(job).copied().unwrap_or(0);
+
+    let root = QProvingJobDataID::notify_block_complete(checkpoint_id);
+    let dependency_map = dump_job_dependencies_from_store(proof_store, leaves)?;
+    let priorities = compute_job_priorities(&dependency_map, root, get_job_priority_weight);
+    let fifo_time = simulate_block_proving_time(&dependency_map, root, num_workers, duration, None);
+    let priority_time = simulate_block_proving_time(
+        &dependency_map,
+        root,
+        num_workers,
+        duration,
+        Some(&priorities),
+    );
+    println!(
+        "block {} with {} workers: fifo {}ms, critical path first {}ms ({:.1}% faster)",
+        checkpoint_id,
+        num_workers,
+        fifo_time,
+        priority_time,
+        100.0 * (fifo_time as f64 - priority_time as f64) / (fifo_time.max(1) as f64)
+    );
+    Ok(())
+}
 pub fn run_qbench(args: &QBenchArgs) -> anyhow::Result<()> {
     let root = std::env::current_dir()?;
     let input_paths = args
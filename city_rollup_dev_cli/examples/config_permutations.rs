@@ -13,10 +13,16 @@ fn main() {
     let max_widthdrawals = 1;
     let mut timer = TraceTimer::new("config_permutations");
     timer.lap("start");
-    let permutations = BlockSpendCoreConfig::standard_p2sh_p2pkh()
-        .generate_permutations(max_deposits, max_widthdrawals);
-    let id_permutations = BlockSpendCoreConfig::standard_p2sh_p2pkh()
-        .generate_id_permutations(max_deposits, max_widthdrawals);
+    let permutations = BlockSpendCoreConfig::standard_p2sh_p2pkh().generate_permutations(
+        max_deposits,
+        max_widthdrawals,
+        0,
+    );
+    let id_permutations = BlockSpendCoreConfig::standard_p2sh_p2pkh().generate_id_permutations(
+        max_deposits,
+        max_widthdrawals,
+        0,
+    );
     println!(
         "id_permutations: {}",
         serde_json::to_string(&id_permutations).unwrap()
@@ -50,7 +50,7 @@ pub struct SighashPermutationSetResult {
 fn get_perm_set(prev_max_deposits: i32, prev_max_withdrawals: i32, target_max_deposits: usize, target_max_withdrawals: usize) -> SighashPermutationSet {
   if prev_max_deposits < 0 || prev_max_withdrawals < 0 {
     let target_configs = BlockSpendCoreConfig::standard_p2sh_p2pkh()
-    .generate_permutations(target_max_deposits, target_max_withdrawals);
+    .generate_permutations(target_max_deposits, target_max_withdrawals, 0);
     let count = target_configs.len();
     SighashPermutationSet{
       existing_set: vec![],
@@ -60,9 +60,9 @@ fn get_perm_set(prev_max_deposits: i32, prev_max_withdrawals: i32, target_max_de
 
   }else{
     let existing_configs = BlockSpendCoreConfig::standard_p2sh_p2pkh()
-      .generate_permutations(prev_max_deposits as usize, prev_max_withdrawals as usize);
+      .generate_permutations(prev_max_deposits as usize, prev_max_withdrawals as usize, 0);
     let all_target_configs = BlockSpendCoreConfig::standard_p2sh_p2pkh()
-      .generate_permutations(target_max_deposits, target_max_withdrawals);
+      .generate_permutations(target_max_deposits, target_max_withdrawals, 0);
     let mut existing_index_map: HashMap<BlockSpendIntrospectionGadgetConfig, usize> = HashMap::new();
     for (i, config) in existing_configs.iter().enumerate() {
       existing_index_map.insert(config.clone(), i);
@@ -1,6 +1,9 @@
 use std::{fs, path::PathBuf};
 
-use city_common::logging::debug_timer::DebugTimer;
+use city_common::{
+    config::rollup_constants::{DEFAULT_SEQUENCER_FEE_RECIPIENT_USER_ID, FIRST_USER_ID},
+    logging::debug_timer::DebugTimer,
+};
 use city_crypto::hash::{
     base_types::{felt252::felt252_hashout_to_hash256_le, hash256::Hash256},
     qhashout::QHashOut,
@@ -56,8 +59,11 @@ fn prove_block_demo(hints: &[BlockSpendIntrospectionHint]) -> anyhow::Result<()>
         "sighash_whitelist_tree.root: {:?}",
         sighash_whitelist_tree.root.0
     );
-    let toolbox_circuits =
-        CRWorkerToolboxRootCircuits::<C, D>::new(network_magic, SIGHASH_WHITELIST_TREE_ROOT);
+    let toolbox_circuits = CRWorkerToolboxRootCircuits::<C, D>::new(
+        network_magic,
+        DEFAULT_SEQUENCER_FEE_RECIPIENT_USER_ID,
+        SIGHASH_WHITELIST_TREE_ROOT,
+    );
     //toolbox_circuits.print_op_common_data();
 
     let mut proof_store = PS::new();
@@ -94,11 +100,12 @@ fn prove_block_demo(hints: &[BlockSpendIntrospectionHint]) -> anyhow::Result<()>
         next_process_withdrawal_id: 0,
         next_deposit_id: 0,
         total_deposits_claimed_epoch: 0,
-        next_user_id: 0,
+        next_user_id: FIRST_USER_ID,
         end_balance: 0,
     };
 
     CityStore::set_block_state(&mut store, &block_0_state)?;
+    CityStore::init_token_state(&mut store, 0)?;
 
     timer.lap("end setup initial state");
     timer.lap("start process state block 1 RPC");
@@ -1,7 +1,10 @@
 use std::{thread::sleep, time::Duration};
 
 use city_common::{
-    cli::message::CITY_ROLLUP_BANNER, logging::debug_timer::DebugTimer, units::UNIT_BTC,
+    cli::message::CITY_ROLLUP_BANNER,
+    config::rollup_constants::{DEFAULT_SEQUENCER_FEE_RECIPIENT_USER_ID, FIRST_USER_ID},
+    logging::debug_timer::DebugTimer,
+    units::UNIT_BTC,
 };
 use city_crypto::hash::{base_types::hash256::Hash256, qhashout::QHashOut};
 use city_redis_store::RedisStore;
@@ -88,7 +91,7 @@ fn run_full_block() -> anyhow::Result<()> {
         next_process_withdrawal_id: 0,
         next_deposit_id: 0,
         total_deposits_claimed_epoch: 0,
-        next_user_id: 0,
+        next_user_id: FIRST_USER_ID,
         end_balance: 0,
     };
     let block_1_state = CityL2BlockState {
@@ -97,12 +100,13 @@ fn run_full_block() -> anyhow::Result<()> {
         next_process_withdrawal_id: 0,
         next_deposit_id: 0,
         total_deposits_claimed_epoch: 0,
-        next_user_id: 0,
+        next_user_id: FIRST_USER_ID,
         end_balance: 0,
     };
 
     CityStore::set_block_state(&mut store, &block_0_state)?;
     CityStore::set_block_state(&mut store, &block_1_state)?;
+    CityStore::init_token_state(&mut store, 0)?;
     let genesis_state_hash = CityStore::get_city_root(&store, 0)?;
     let setup_fee = 100000 * 500;
     let fund_genesis_txid = api.fund_address_from_random_p2pkh_address(
@@ -138,8 +142,11 @@ fn run_full_block() -> anyhow::Result<()> {
     timer.lap("end creating wallets");
 
     timer.lap("start creating worker");
-    let root_toolbox =
-        CRWorkerToolboxRootCircuits::<C, D>::new(network_magic, sighash_whitelist_tree.root);
+    let root_toolbox = CRWorkerToolboxRootCircuits::<C, D>::new(
+        network_magic,
+        DEFAULT_SEQUENCER_FEE_RECIPIENT_USER_ID,
+        sighash_whitelist_tree.root,
+    );
     let fingerprints = root_toolbox.core.fingerprints.clone();
     timer.lap("end creating worker");
 
@@ -212,29 +219,31 @@ fn run_full_block() -> anyhow::Result<()> {
 
     rpc_queue.notify_rpc_claim_deposit(&wallet.sign_claim_deposit(
         network_magic,
-        0,
+        1,
         &CityStore::<S>::get_deposit_by_id(&store, checkpoint_id, 0)?,
     )?)?;
     rpc_queue.notify_rpc_claim_deposit(&wallet.sign_claim_deposit(
         network_magic,
-        1,
+        2,
         &CityStore::<S>::get_deposit_by_id(&store, checkpoint_id, 1)?,
     )?)?;
 
     rpc_queue.notify_rpc_token_transfer(&wallet.sign_l2_transfer(
         user_0_public_key,
         network_magic,
-        0,
         1,
+        2,
         2 * UNIT_BTC,
+        0,
         1,
     )?)?;
     rpc_queue.notify_rpc_token_transfer(&wallet.sign_l2_transfer(
         user_1_public_key,
         network_magic,
-        1,
         2,
+        3,
         5 * UNIT_BTC,
+        0,
         1,
     )?)?;
 
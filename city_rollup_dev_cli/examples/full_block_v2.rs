@@ -1,6 +1,9 @@
 use std::{fs, path::PathBuf};
 
-use city_common::logging::debug_timer::DebugTimer;
+use city_common::{
+    config::rollup_constants::{DEFAULT_SEQUENCER_FEE_RECIPIENT_USER_ID, FIRST_USER_ID},
+    logging::debug_timer::DebugTimer,
+};
 use city_crypto::hash::{
     base_types::{felt252::felt252_hashout_to_hash256_le, hash256::Hash256},
     qhashout::QHashOut,
@@ -40,8 +43,11 @@ fn prove_block_demo(hints: &[BlockSpendIntrospectionHint]) -> anyhow::Result<()>
     let network_magic = NETWORK_MAGIC_DOGE_REGTEST;
 
     let sighash_whitelist_tree = SigHashMerkleTree::new();
-    let toolbox_circuits =
-        CRWorkerToolboxRootCircuits::<C, D>::new(network_magic, SIGHASH_WHITELIST_TREE_ROOT);
+    let toolbox_circuits = CRWorkerToolboxRootCircuits::<C, D>::new(
+        network_magic,
+        DEFAULT_SEQUENCER_FEE_RECIPIENT_USER_ID,
+        SIGHASH_WHITELIST_TREE_ROOT,
+    );
     //toolbox_circuits.print_op_common_data();
 
     let mut proof_store = PS::new();
@@ -78,11 +84,12 @@ fn prove_block_demo(hints: &[BlockSpendIntrospectionHint]) -> anyhow::Result<()>
         next_process_withdrawal_id: 0,
         next_deposit_id: 0,
         total_deposits_claimed_epoch: 0,
-        next_user_id: 0,
+        next_user_id: FIRST_USER_ID,
         end_balance: 0,
     };
 
     CityStore::set_block_state(&mut store, &block_0_state)?;
+    CityStore::init_token_state(&mut store, 0)?;
 
     timer.lap("end setup initial state");
     timer.lap("start process state block 1 RPC");
@@ -196,12 +203,12 @@ fn prove_block_demo(hints: &[BlockSpendIntrospectionHint]) -> anyhow::Result<()>
 
     let l1_deposit_0 = CityStore::<S>::get_deposit_by_id(&store, 1, 0)?;
     let l1_deposit_1 = CityStore::<S>::get_deposit_by_id(&store, 1, 1)?;
-    let claim_deposit_0_req = wallet.sign_claim_deposit(network_magic, 0, &l1_deposit_0)?;
-    let claim_deposit_1_req = wallet.sign_claim_deposit(network_magic, 1, &l1_deposit_1)?;
+    let claim_deposit_0_req = wallet.sign_claim_deposit(network_magic, 1, &l1_deposit_0)?;
+    let claim_deposit_1_req = wallet.sign_claim_deposit(network_magic, 2, &l1_deposit_1)?;
     let send_transfer_1_req =
-        wallet.sign_l2_transfer(user_0_public_key, network_magic, 0, 1, 200000, 1)?;
+        wallet.sign_l2_transfer(user_0_public_key, network_magic, 1, 2, 200000, 0, 1)?;
     let send_transfer_2_req =
-        wallet.sign_l2_transfer(user_1_public_key, network_magic, 1, 2, 300000, 1)?;
+        wallet.sign_l2_transfer(user_1_public_key, network_magic, 2, 3, 300000, 0, 1)?;
 
     block_2_builder.process_deposits(
         &mut proof_store,
@@ -5,7 +5,7 @@ use plonky2::{
 };
 
 fn main() {
-    let transition_str = "{\"op_register_user_transition_user_state_tree\":{\"state_transition_start\":\"d65af5933a094e8329332a714327ba72b1e4dac93c0cde8ee479b9bb36c3fc43\",\"state_transition_end\":\"7f0c3ea541b21b3c2676d289afc67a83301beb7cebc82b291d3113054669813d\"},\"op_register_user_proof_id\":{\"topic\":0,\"goal_id\":1,\"circuit_type\":1,\"group_id\":52992,\"sub_group_id\":2,\"task_index\":0,\"data_type\":8,\"data_index\":0},\"op_claim_l1_deposit_transition_deposit_tree\":{\"state_transition_start\":\"d65af5933a094e8329332a714327ba72b1e4dac93c0cde8ee479b9bb36c3fc43\",\"state_transition_end\":\"d65af5933a094e8329332a714327ba72b1e4dac93c0cde8ee479b9bb36c3fc43\"},\"op_claim_l1_deposit_transition_user_state_tree\":{\"state_transition_start\":\"7f0c3ea541b21b3c2676d289afc67a83301beb7cebc82b291d3113054669813d\",\"state_transition_end\":\"7f0c3ea541b21b3c2676d289afc67a83301beb7cebc82b291d3113054669813d\"},\"op_claim_l1_deposit_proof_id\":{\"topic\":0,\"goal_id\":1,\"circuit_type\":50,\"group_id\":221,\"sub_group_id\":0,\"task_index\":0,\"data_type\":8,\"data_index\":0},\"op_l2_transfer_transition_user_state_tree\":{\"state_transition_start\":\"7f0c3ea541b21b3c2676d289afc67a83301beb7cebc82b291d3113054669813d\",\"state_transition_end\":\"7f0c3ea541b21b3c2676d289afc67a83301beb7cebc82b291d3113054669813d\"},\"op_l2_transfer_proof_id\":{\"topic\":0,\"goal_id\":1,\"circuit_type\":51,\"group_id\":221,\"sub_group_id\":0,\"task_index\":0,\"data_type\":8,\"data_index\":0}}";
+    let transition_str = "{\"op_register_user_transition_user_state_tree\":{\"state_transition_start\":\"d65af5933a094e8329332a714327ba72b1e4dac93c0cde8ee479b9bb36c3fc43\",\"state_transition_end\":\"7f0c3ea541b21b3c2676d289afc67a83301beb7cebc82b291d3113054669813d\"},\"op_register_user_proof_id\":{\"topic\":0,\"goal_id\":1,\"circuit_type\":1,\"group_id\":52992,\"sub_group_id\":2,\"task_index\":0,\"data_type\":8,\"data_index\":0},\"op_claim_l1_deposit_transition_deposit_tree\":{\"state_transition_start\":\"d65af5933a094e8329332a714327ba72b1e4dac93c0cde8ee479b9bb36c3fc43\",\"state_transition_end\":\"d65af5933a094e8329332a714327ba72b1e4dac93c0cde8ee479b9bb36c3fc43\"},\"op_claim_l1_deposit_transition_user_state_tree\":{\"state_transition_start\":\"7f0c3ea541b21b3c2676d289afc67a83301beb7cebc82b291d3113054669813d\",\"state_transition_end\":\"7f0c3ea541b21b3c2676d289afc67a83301beb7cebc82b291d3113054669813d\"},\"op_claim_l1_deposit_proof_id\":{\"topic\":0,\"goal_id\":1,\"circuit_type\":50,\"group_id\":221,\"sub_group_id\":0,\"task_index\":0,\"data_type\":8,\"data_index\":0},\"op_l2_transfer_transition_user_state_tree\":{\"state_transition_start\":\"7f0c3ea541b21b3c2676d289afc67a83301beb7cebc82b291d3113054669813d\",\"state_transition_end\":\"7f0c3ea541b21b3c2676d289afc67a83301beb7cebc82b291d3113054669813d\"},\"op_l2_transfer_proof_id\":{\"topic\":0,\"goal_id\":1,\"circuit_type\":51,\"group_id\":221,\"sub_group_id\":0,\"task_index\":0,\"data_type\":8,\"data_index\":0},\"op_change_public_key_transition_user_state_tree\":{\"state_transition_start\":\"7f0c3ea541b21b3c2676d289afc67a83301beb7cebc82b291d3113054669813d\",\"state_transition_end\":\"7f0c3ea541b21b3c2676d289afc67a83301beb7cebc82b291d3113054669813d\"},\"op_change_public_key_proof_id\":{\"topic\":0,\"goal_id\":1,\"circuit_type\":54,\"group_id\":221,\"sub_group_id\":0,\"task_index\":0,\"data_type\":8,\"data_index\":0},\"op_batch_l2_transfer_transition_user_state_tree\":{\"state_transition_start\":\"7f0c3ea541b21b3c2676d289afc67a83301beb7cebc82b291d3113054669813d\",\"state_transition_end\":\"7f0c3ea541b21b3c2676d289afc67a83301beb7cebc82b291d3113054669813d\"},\"op_batch_l2_transfer_proof_id\":{\"topic\":0,\"goal_id\":1,\"circuit_type\":55,\"group_id\":221,\"sub_group_id\":0,\"task_index\":0,\"data_type\":8,\"data_index\":0},\"op_open_token_account_transition_user_state_tree\":{\"state_transition_start\":\"7f0c3ea541b21b3c2676d289afc67a83301beb7cebc82b291d3113054669813d\",\"state_transition_end\":\"7f0c3ea541b21b3c2676d289afc67a83301beb7cebc82b291d3113054669813d\"},\"op_open_token_account_proof_id\":{\"topic\":0,\"goal_id\":1,\"circuit_type\":56,\"group_id\":221,\"sub_group_id\":0,\"task_index\":0,\"data_type\":8,\"data_index\":0},\"op_mint_token_transition_user_state_tree\":{\"state_transition_start\":\"7f0c3ea541b21b3c2676d289afc67a83301beb7cebc82b291d3113054669813d\",\"state_transition_end\":\"7f0c3ea541b21b3c2676d289afc67a83301beb7cebc82b291d3113054669813d\"},\"op_mint_token_proof_id\":{\"topic\":0,\"goal_id\":1,\"circuit_type\":57,\"group_id\":221,\"sub_group_id\":0,\"task_index\":0,\"data_type\":8,\"data_index\":0},\"op_send_token_transition_user_state_tree\":{\"state_transition_start\":\"7f0c3ea541b21b3c2676d289afc67a83301beb7cebc82b291d3113054669813d\",\"state_transition_end\":\"7f0c3ea541b21b3c2676d289afc67a83301beb7cebc82b291d3113054669813d\"},\"op_send_token_proof_id\":{\"topic\":0,\"goal_id\":1,\"circuit_type\":58,\"group_id\":221,\"sub_group_id\":0,\"task_index\":0,\"data_type\":8,\"data_index\":0},\"op_burn_token_transition_user_state_tree\":{\"state_transition_start\":\"7f0c3ea541b21b3c2676d289afc67a83301beb7cebc82b291d3113054669813d\",\"state_transition_end\":\"7f0c3ea541b21b3c2676d289afc67a83301beb7cebc82b291d3113054669813d\"},\"op_burn_token_proof_id\":{\"topic\":0,\"goal_id\":1,\"circuit_type\":59,\"group_id\":221,\"sub_group_id\":0,\"task_index\":0,\"data_type\":8,\"data_index\":0}}";
     let input: CRAggUserRegisterClaimDepositL2TransferCircuitInput<GoldilocksField> =
         serde_json::from_str(&transition_str).unwrap();
     tracing::info!("{:?}", input);
@@ -9,6 +9,7 @@ use clap::Parser;
 use error::Result;
 
 use crate::subcommand::full_block;
+use crate::subcommand::gen_sighash_whitelist;
 use crate::subcommand::print_circuit_info;
 use crate::subcommand::tree_prove_test;
 use crate::subcommand::Cli;
@@ -24,6 +25,7 @@ async fn main() -> Result<()> {
         Commands::PrintCircuitInfo(args) => print_circuit_info::run(args).await?,
         Commands::TreeProveTest(args) => tree_prove_test::run(args).await?,
         Commands::FullBlock(args) => full_block::run(args).await?,
+        Commands::GenSighashWhitelist(args) => gen_sighash_whitelist::run(args).await?,
     }
 
     Ok(())
@@ -2,6 +2,7 @@ use clap::command;
 use clap::Parser;
 use clap::Subcommand;
 pub mod full_block;
+pub mod gen_sighash_whitelist;
 pub mod print_circuit_info;
 pub mod tree_prove_test;
 #[derive(Parser)]
@@ -15,4 +16,5 @@ pub enum Commands {
     PrintCircuitInfo(city_common::cli::dev_args::PrintCircuitInfoArgs),
     TreeProveTest(city_common::cli::dev_args::TreeProveTestArgs),
     FullBlock(city_common::cli::dev_args::TreeProveTestArgs),
+    GenSighashWhitelist(city_common::cli::dev_args::GenSighashWhitelistArgs),
 }
@@ -0,0 +1,283 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use crate::build;
+use crate::error::Result;
+use city_common::cli::dev_args::GenSighashWhitelistArgs;
+use city_common::logging::trace_timer::TraceTimer;
+use city_common_circuit::circuits::traits::qstandard::QStandardCircuit;
+use city_crypto::hash::qhashout::QHashOut;
+use city_rollup_circuit::sighash_circuits::sighash::CRSigHashCircuit;
+use city_rollup_common::config::sighash_wrapper_config::{
+    SIGHASH_CIRCUIT_FINGERPRINTS, SIGHASH_CIRCUIT_MAX_DEPOSITS,
+    SIGHASH_CIRCUIT_MAX_FORCED_WITHDRAWAL_REQUESTS, SIGHASH_CIRCUIT_MAX_WITHDRAWALS,
+    SIGHASH_CIRCUIT_WHITELIST_TREE_HEIGHT, SIGHASH_WHITELIST_DISABLED_DEV_MODE,
+    SIGHASH_WHITELIST_TREE_ROOT,
+};
+use city_rollup_common::introspection::rollup::introspection::{
+    BlockSpendCoreConfig, BlockSpendIntrospectionGadgetConfig,
+};
+use city_store::store::sighash::SigHashMerkleTree;
+use plonky2::field::goldilocks_field::GoldilocksField;
+use plonky2::field::types::PrimeField64;
+use plonky2::plonk::config::PoseidonGoldilocksConfig;
+
+const D: usize = 2;
+type C = PoseidonGoldilocksConfig;
+type F = GoldilocksField;
+
+fn compute_fingerprints(
+    configs: &[BlockSpendIntrospectionGadgetConfig],
+    threads: usize,
+) -> Vec<QHashOut<F>> {
+    let next_index = AtomicUsize::new(0);
+    let fingerprints: Mutex<Vec<Option<QHashOut<F>>>> = Mutex::new(vec![None; configs.len()]);
+    let timer = Mutex::new(TraceTimer::new("gen_sighash_whitelist"));
+
+    std::thread::scope(|s| {
+        for _ in 0..threads {
+            s.spawn(|| loop {
+                let index = next_index.fetch_add(1, Ordering::SeqCst);
+                if index >= configs.len() {
+                    break;
+                }
+                let circuit = CRSigHashCircuit::<C, D>::new(configs[index].clone());
+                let fingerprint = circuit.get_fingerprint();
+                fingerprints.lock().unwrap()[index] = Some(fingerprint);
+                timer.lock().unwrap().event(format!(
+                    "generated fingerprint {}/{}: {}",
+                    index + 1,
+                    configs.len(),
+                    fingerprint.to_string()
+                ));
+            });
+        }
+    });
+
+    fingerprints
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|x| x.unwrap())
+        .collect()
+}
+
+fn format_qhashout(value: &QHashOut<F>) -> String {
+    format!(
+        "QHashOut(HashOut{{elements: [{}]}})",
+        value
+            .0
+            .elements
+            .iter()
+            .map(|x| format!("GoldilocksField({})", x.to_canonical_u64()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+fn render_config_file(
+    max_deposits: usize,
+    max_withdrawals: usize,
+    max_forced_withdrawal_requests: usize,
+    root: &QHashOut<F>,
+    fingerprints: &[QHashOut<F>],
+) -> String {
+    let mut out = String::new();
+    out.push_str(
+        "// @generated by `city-rollup-dev-cli gen-sighash-whitelist`, do not edit by hand\n",
+    );
+    out.push_str("use city_crypto::hash::qhashout::QHashOut;\n");
+    out.push_str(
+        "use plonky2::{field::goldilocks_field::GoldilocksField, hash::hash_types::HashOut};\n\n",
+    );
+    out.push_str("type F = GoldilocksField;\n\n");
+    out.push_str(&format!(
+        "pub const SIGHASH_CIRCUIT_WHITELIST_TREE_HEIGHT: u8 = {};\n\n",
+        SIGHASH_CIRCUIT_WHITELIST_TREE_HEIGHT
+    ));
+    out.push_str("// set SIGHASH_WHITELIST_DISABLED_DEV_MODE = true in development ONLY, this sighash whitelist inclusion check for debugging circuits\n");
+    out.push_str(&format!(
+        "pub const SIGHASH_WHITELIST_DISABLED_DEV_MODE: bool = {};\n\n",
+        SIGHASH_WHITELIST_DISABLED_DEV_MODE
+    ));
+    out.push_str(&format!(
+        "pub const SIGHASH_CIRCUIT_MAX_WITHDRAWALS: usize = {};\n",
+        max_withdrawals
+    ));
+    out.push_str(&format!(
+        "pub const SIGHASH_CIRCUIT_MAX_DEPOSITS: usize = {};\n",
+        max_deposits
+    ));
+    out.push_str(&format!(
+        "pub const SIGHASH_CIRCUIT_MAX_FORCED_WITHDRAWAL_REQUESTS: usize = {};\n",
+        max_forced_withdrawal_requests
+    ));
+    out.push_str("pub const SIGHASH_WHITELIST_TREE_ROOT: QHashOut<F> = QHashOut(HashOut {\n    elements: [\n");
+    for x in root.0.elements.iter() {
+        out.push_str(&format!(
+            "        GoldilocksField({}),\n",
+            x.to_canonical_u64()
+        ));
+    }
+    out.push_str("    ],\n});\n");
+    out.push_str(&format!(
+        "pub const SIGHASH_CIRCUIT_FINGERPRINTS: [QHashOut<F>; {}] = [\n",
+        fingerprints.len()
+    ));
+    for fingerprint in fingerprints.iter() {
+        out.push_str(&format!("    {},\n", format_qhashout(fingerprint)));
+    }
+    out.push_str("];\n");
+    out
+}
+
+fn check_committed_whitelist(
+    args: &GenSighashWhitelistArgs,
+    root: &QHashOut<F>,
+    fingerprints: &[QHashOut<F>],
+) -> anyhow::Result<()> {
+    if args.max_deposits != SIGHASH_CIRCUIT_MAX_DEPOSITS
+        || args.max_withdrawals != SIGHASH_CIRCUIT_MAX_WITHDRAWALS
+        || args.max_forced_withdrawal_requests != SIGHASH_CIRCUIT_MAX_FORCED_WITHDRAWAL_REQUESTS
+    {
+        anyhow::bail!(
+            "committed whitelist is for max_deposits={}, max_withdrawals={}, max_forced_withdrawal_requests={} but max_deposits={}, max_withdrawals={}, max_forced_withdrawal_requests={} was requested",
+            SIGHASH_CIRCUIT_MAX_DEPOSITS,
+            SIGHASH_CIRCUIT_MAX_WITHDRAWALS,
+            SIGHASH_CIRCUIT_MAX_FORCED_WITHDRAWAL_REQUESTS,
+            args.max_deposits,
+            args.max_withdrawals,
+            args.max_forced_withdrawal_requests
+        );
+    }
+    if fingerprints.len() != SIGHASH_CIRCUIT_FINGERPRINTS.len() {
+        anyhow::bail!(
+            "committed whitelist has {} fingerprints, generated {}",
+            SIGHASH_CIRCUIT_FINGERPRINTS.len(),
+            fingerprints.len()
+        );
+    }
+    let mismatches = fingerprints
+        .iter()
+        .zip(SIGHASH_CIRCUIT_FINGERPRINTS.iter())
+        .enumerate()
+        .filter(|(_, (generated, committed))| generated != committed)
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    if !mismatches.is_empty() {
+        anyhow::bail!(
+            "{} committed sighash circuit fingerprints are stale (first at index {}), run gen-sighash-whitelist to regenerate {}",
+            mismatches.len(),
+            mismatches[0],
+            args.output
+        );
+    }
+    if *root != SIGHASH_WHITELIST_TREE_ROOT {
+        anyhow::bail!(
+            "committed SIGHASH_WHITELIST_TREE_ROOT {} does not match generated root {}",
+            SIGHASH_WHITELIST_TREE_ROOT.to_string(),
+            root.to_string()
+        );
+    }
+    Ok(())
+}
+
+// the sighash whitelist root and the fingerprints of every sighash circuit permutation
+fn generate_whitelist(
+    args: &GenSighashWhitelistArgs,
+) -> anyhow::Result<(QHashOut<F>, Vec<QHashOut<F>>)> {
+    let configs = BlockSpendCoreConfig::standard_p2sh_p2pkh().generate_permutations(
+        args.max_deposits,
+        args.max_withdrawals,
+        args.max_forced_withdrawal_requests,
+    );
+    let threads = args.threads.unwrap_or_else(|| {
+        std::thread::available_parallelism()
+            .map(|x| x.get())
+            .unwrap_or(1)
+    });
+    tracing::info!(
+        "generating {} sighash circuit fingerprints on {} threads",
+        configs.len(),
+        threads
+    );
+    let fingerprints = compute_fingerprints(&configs, threads.max(1));
+    let tree = SigHashMerkleTree::new_with_fingerprints(
+        args.max_deposits,
+        args.max_withdrawals,
+        args.max_forced_withdrawal_requests,
+        &fingerprints,
+    )?;
+    tracing::info!("sighash whitelist root: {}", tree.root.to_string());
+    Ok((tree.root, fingerprints))
+}
+
+pub async fn run(args: GenSighashWhitelistArgs) -> Result<()> {
+    tracing::info!(
+        "
+----------------------------------------
+|           CityRollup v{}             |
+----------------------------------------
+",
+        build::PKG_VERSION
+    );
+
+    let (root, fingerprints) = generate_whitelist(&args)?;
+
+    if args.check {
+        check_committed_whitelist(&args, &root, &fingerprints)?;
+        tracing::info!("committed sighash whitelist is up to date");
+    } else {
+        let contents = render_config_file(
+            args.max_deposits,
+            args.max_withdrawals,
+            args.max_forced_withdrawal_requests,
+            &root,
+            &fingerprints,
+        );
+        std::fs::write(&args.output, contents).map_err(anyhow::Error::from)?;
+        tracing::info!("wrote sighash whitelist to {}", args.output);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMMITTED_CONFIG_FILE: &str =
+        include_str!("../../../city_rollup_common/src/config/sighash_wrapper_config.rs");
+
+    // the committed file must be exactly what gen-sighash-whitelist writes for its constants
+    #[test]
+    fn committed_config_file_matches_render_output() {
+        let rendered = render_config_file(
+            SIGHASH_CIRCUIT_MAX_DEPOSITS,
+            SIGHASH_CIRCUIT_MAX_WITHDRAWALS,
+            SIGHASH_CIRCUIT_MAX_FORCED_WITHDRAWAL_REQUESTS,
+            &SIGHASH_WHITELIST_TREE_ROOT,
+            &SIGHASH_CIRCUIT_FINGERPRINTS,
+        );
+        assert!(
+            rendered == COMMITTED_CONFIG_FILE,
+            "sighash_wrapper_config.rs was edited by hand, regenerate it with gen-sighash-whitelist"
+        );
+    }
+
+    // builds every sighash circuit, equivalent to `gen-sighash-whitelist --check`
+    #[test]
+    #[ignore]
+    fn committed_whitelist_is_up_to_date() {
+        let args = GenSighashWhitelistArgs {
+            output: "city_rollup_common/src/config/sighash_wrapper_config.rs".to_string(),
+            max_deposits: SIGHASH_CIRCUIT_MAX_DEPOSITS,
+            max_withdrawals: SIGHASH_CIRCUIT_MAX_WITHDRAWALS,
+            max_forced_withdrawal_requests: SIGHASH_CIRCUIT_MAX_FORCED_WITHDRAWAL_REQUESTS,
+            threads: None,
+            check: true,
+        };
+        let (root, fingerprints) = generate_whitelist(&args).unwrap();
+        check_committed_whitelist(&args, &root, &fingerprints).unwrap();
+    }
+}
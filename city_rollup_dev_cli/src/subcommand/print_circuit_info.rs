@@ -1,6 +1,10 @@
 use crate::build;
 use crate::error::Result;
 use city_common::cli::dev_args::PrintCircuitInfoArgs;
+use city_common::config::rollup_constants::DEFAULT_SEQUENCER_FEE_RECIPIENT_USER_ID;
+use city_common_circuit::serialization::circuit_cache::{
+    get_current_exe_build_id, QCircuitDataCache,
+};
 use city_rollup_circuit::worker::toolbox::circuits::CRWorkerToolboxCoreCircuits;
 use city_rollup_common::introspection::rollup::constants::get_network_magic_for_str;
 use plonky2::plonk::config::PoseidonGoldilocksConfig;
@@ -18,7 +22,20 @@ pub async fn run(args: PrintCircuitInfoArgs) -> Result<()> {
     const D: usize = 2;
     type C = PoseidonGoldilocksConfig;
     let network_magic = get_network_magic_for_str(args.network)?;
-    let toolbox_circuits = CRWorkerToolboxCoreCircuits::<C, D>::new(network_magic);
+    let session = match &args.circuit_cache_dir {
+        Some(dir) => Some(
+            QCircuitDataCache::open_build(dir, network_magic, &get_current_exe_build_id()?)?
+                .activate(),
+        ),
+        None => None,
+    };
+    let toolbox_circuits = CRWorkerToolboxCoreCircuits::<C, D>::new(
+        network_magic,
+        DEFAULT_SEQUENCER_FEE_RECIPIENT_USER_ID,
+    );
+    if let Some(session) = session {
+        session.finish()?;
+    }
     toolbox_circuits.print_op_common_data();
 
     Ok(())
@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use city_common::binaryhelpers::bytes::CompressedPublicKey;
 use city_common::data::{kv::SimpleKVPair, u8bytes::U8Bytes};
 use city_crypto::hash::base_types::hash160::Hash160;
 use city_crypto::hash::base_types::hash256::Hash256;
@@ -9,15 +10,22 @@ use city_macros::{
 use city_rollup_common::{
     api::data::{
         block::rpc_request::*,
-        store::{CityL1DepositJSON, CityL1Withdrawal, CityL2BlockState, CityUserState},
+        store::{
+            CityForcedWithdrawalRequestStatus, CityL1DepositJSON, CityL1Withdrawal,
+            CityL2BlockState, CityTokenBalance, CityTokenInfo, CityUserState,
+        },
+    },
+    link::data::BTCAddress160,
+    qworker::{
+        fleet::QWorkerStatus, job_id::QProvingJobDataIDSerializedWrapped,
+        job_witnesses::inspect::QJobWitness, status::QBlockProvingStatus,
     },
-    qworker::{job_id::QProvingJobDataIDSerializedWrapped, job_witnesses::inspect::QJobWitness},
 };
 use city_rollup_core_node::rpc::{
     ExternalRequestParams, Id, RequestParams, ResponseResult, RpcParams, RpcRequest, RpcResponse,
     Version,
 };
-use city_store::config::{CityHash, CityMerkleProof};
+use city_store::config::{CityHash, CityMerkleMultiProof, CityMerkleProof};
 use plonky2::hash::hash_types::RichField;
 use reqwest::Client;
 use serde_json::json;
@@ -80,6 +88,30 @@ pub trait CityRpcProvider {
         leaf_id: u64,
     ) -> anyhow::Result<CityMerkleProof>;
 
+    async fn get_user_tree_multi_proof(
+        &self,
+        checkpoint_id: u64,
+        leaf_ids: &[u64],
+    ) -> anyhow::Result<CityMerkleMultiProof>;
+
+    async fn get_token_balance(
+        &self,
+        checkpoint_id: u64,
+        user_id: u64,
+        token_id: u64,
+    ) -> anyhow::Result<CityTokenBalance>;
+
+    async fn get_token_info(
+        &self,
+        checkpoint_id: u64,
+        token_id: u64,
+    ) -> anyhow::Result<CityTokenInfo>;
+
+    async fn get_forced_withdrawal_requests(
+        &self,
+        checkpoint_id: u64,
+    ) -> anyhow::Result<Vec<CityForcedWithdrawalRequestStatus>>;
+
     async fn get_deposit_tree_root(&self, checkpoint_id: u64) -> anyhow::Result<CityHash>;
 
     async fn get_deposit_by_id(
@@ -104,6 +136,12 @@ pub trait CityRpcProvider {
         transaction_ids: Vec<Hash256>,
     ) -> anyhow::Result<Vec<CityL1DepositJSON>>;
 
+    async fn get_deposit_ids_for_public_key(
+        &self,
+        checkpoint_id: u64,
+        public_key: CompressedPublicKey,
+    ) -> anyhow::Result<Vec<u64>>;
+
     async fn get_deposit_hash(
         &self,
         checkpoint_id: u64,
@@ -145,6 +183,12 @@ pub trait CityRpcProvider {
         withdrawal_ids: Vec<u64>,
     ) -> anyhow::Result<Vec<CityL1Withdrawal>>;
 
+    async fn get_withdrawal_ids_for_destination(
+        &self,
+        checkpoint_id: u64,
+        destination: BTCAddress160,
+    ) -> anyhow::Result<Vec<u64>>;
+
     async fn get_withdrawal_hash(
         &self,
         checkpoint_id: u64,
@@ -177,6 +221,13 @@ pub trait CityRpcProvider {
         keys: Vec<QProvingJobDataIDSerializedWrapped>,
     ) -> anyhow::Result<Vec<SimpleKVPair<QProvingJobDataIDSerializedWrapped, QJobWitness<F>>>>;
 
+    async fn get_block_proving_status(
+        &self,
+        checkpoint_id: u64,
+    ) -> anyhow::Result<QBlockProvingStatus>;
+
+    async fn get_workers(&self) -> anyhow::Result<Vec<QWorkerStatus>>;
+
     async fn register_user<F: RichField>(
         &self,
         req: CityRegisterUserRPCRequest<F>,
@@ -196,6 +247,32 @@ pub trait CityRpcProvider {
         &self,
         req: CityTokenTransferRPCRequest,
     ) -> anyhow::Result<()>;
+
+    async fn change_public_key<F: RichField>(
+        &self,
+        req: CityChangePublicKeyRPCRequest<F>,
+    ) -> anyhow::Result<()>;
+
+    async fn batch_token_transfer<F: RichField>(
+        &self,
+        req: CityBatchTokenTransferRPCRequest,
+    ) -> anyhow::Result<()>;
+
+    async fn open_token_account<F: RichField>(
+        &self,
+        req: CityOpenTokenAccountRPCRequest,
+    ) -> anyhow::Result<()>;
+
+    async fn mint_token<F: RichField>(&self, req: CityMintTokenRPCRequest) -> anyhow::Result<()>;
+
+    async fn send_token<F: RichField>(&self, req: CitySendTokenRPCRequest) -> anyhow::Result<()>;
+
+    async fn burn_token<F: RichField>(&self, req: CityBurnTokenRPCRequest) -> anyhow::Result<()>;
+
+    async fn cancel_withdrawal<F: RichField>(
+        &self,
+        req: CityCancelWithdrawalRPCRequest<F>,
+    ) -> anyhow::Result<()>;
 }
 
 pub trait CityRpcProviderSync {
@@ -223,6 +300,30 @@ pub trait CityRpcProviderSync {
         leaf_id: u64,
     ) -> anyhow::Result<CityMerkleProof>;
 
+    fn get_user_tree_multi_proof_sync(
+        &self,
+        checkpoint_id: u64,
+        leaf_ids: &[u64],
+    ) -> anyhow::Result<CityMerkleMultiProof>;
+
+    fn get_token_balance_sync(
+        &self,
+        checkpoint_id: u64,
+        user_id: u64,
+        token_id: u64,
+    ) -> anyhow::Result<CityTokenBalance>;
+
+    fn get_token_info_sync(
+        &self,
+        checkpoint_id: u64,
+        token_id: u64,
+    ) -> anyhow::Result<CityTokenInfo>;
+
+    fn get_forced_withdrawal_requests_sync(
+        &self,
+        checkpoint_id: u64,
+    ) -> anyhow::Result<Vec<CityForcedWithdrawalRequestStatus>>;
+
     fn get_deposit_tree_root_sync(&self, checkpoint_id: u64) -> anyhow::Result<CityHash>;
 
     fn get_deposit_by_id_sync(
@@ -247,6 +348,12 @@ pub trait CityRpcProviderSync {
         transaction_ids: Vec<Hash256>,
     ) -> anyhow::Result<Vec<CityL1DepositJSON>>;
 
+    fn get_deposit_ids_for_public_key_sync(
+        &self,
+        checkpoint_id: u64,
+        public_key: CompressedPublicKey,
+    ) -> anyhow::Result<Vec<u64>>;
+
     fn get_deposit_hash_sync(
         &self,
         checkpoint_id: u64,
@@ -288,6 +395,12 @@ pub trait CityRpcProviderSync {
         withdrawal_ids: Vec<u64>,
     ) -> anyhow::Result<Vec<CityL1Withdrawal>>;
 
+    fn get_withdrawal_ids_for_destination_sync(
+        &self,
+        checkpoint_id: u64,
+        destination: BTCAddress160,
+    ) -> anyhow::Result<Vec<u64>>;
+
     fn get_withdrawal_hash_sync(
         &self,
         checkpoint_id: u64,
@@ -320,6 +433,11 @@ pub trait CityRpcProviderSync {
         keys: Vec<QProvingJobDataIDSerializedWrapped>,
     ) -> anyhow::Result<Vec<SimpleKVPair<QProvingJobDataIDSerializedWrapped, QJobWitness<F>>>>;
 
+    fn get_block_proving_status_sync(&self, checkpoint_id: u64)
+        -> anyhow::Result<QBlockProvingStatus>;
+
+    fn get_workers_sync(&self) -> anyhow::Result<Vec<QWorkerStatus>>;
+
     fn register_user_sync<F: RichField>(
         &self,
         req: CityRegisterUserRPCRequest<F>,
@@ -339,6 +457,32 @@ pub trait CityRpcProviderSync {
         &self,
         req: CityTokenTransferRPCRequest,
     ) -> anyhow::Result<()>;
+
+    fn change_public_key_sync<F: RichField>(
+        &self,
+        req: CityChangePublicKeyRPCRequest<F>,
+    ) -> anyhow::Result<()>;
+
+    fn batch_token_transfer_sync<F: RichField>(
+        &self,
+        req: CityBatchTokenTransferRPCRequest,
+    ) -> anyhow::Result<()>;
+
+    fn open_token_account_sync<F: RichField>(
+        &self,
+        req: CityOpenTokenAccountRPCRequest,
+    ) -> anyhow::Result<()>;
+
+    fn mint_token_sync<F: RichField>(&self, req: CityMintTokenRPCRequest) -> anyhow::Result<()>;
+
+    fn send_token_sync<F: RichField>(&self, req: CitySendTokenRPCRequest) -> anyhow::Result<()>;
+
+    fn burn_token_sync<F: RichField>(&self, req: CityBurnTokenRPCRequest) -> anyhow::Result<()>;
+
+    fn cancel_withdrawal_sync<F: RichField>(
+        &self,
+        req: CityCancelWithdrawalRPCRequest<F>,
+    ) -> anyhow::Result<()>;
 }
 
 #[async_trait::async_trait]
@@ -408,6 +552,58 @@ impl CityRpcProvider for RpcProvider {
         )
     }
 
+    async fn get_user_tree_multi_proof(
+        &self,
+        checkpoint_id: u64,
+        leaf_ids: &[u64],
+    ) -> anyhow::Result<CityMerkleMultiProof> {
+        city_external_rpc_call!(
+            self,
+            "cr_getUserTreeMultiProof",
+            json!([checkpoint_id, leaf_ids]),
+            CityMerkleMultiProof
+        )
+    }
+
+    async fn get_token_balance(
+        &self,
+        checkpoint_id: u64,
+        user_id: u64,
+        token_id: u64,
+    ) -> anyhow::Result<CityTokenBalance> {
+        city_external_rpc_call!(
+            self,
+            "cr_getTokenBalance",
+            json!([checkpoint_id, user_id, token_id]),
+            CityTokenBalance
+        )
+    }
+
+    async fn get_token_info(
+        &self,
+        checkpoint_id: u64,
+        token_id: u64,
+    ) -> anyhow::Result<CityTokenInfo> {
+        city_external_rpc_call!(
+            self,
+            "cr_getTokenInfo",
+            json!([checkpoint_id, token_id]),
+            CityTokenInfo
+        )
+    }
+
+    async fn get_forced_withdrawal_requests(
+        &self,
+        checkpoint_id: u64,
+    ) -> anyhow::Result<Vec<CityForcedWithdrawalRequestStatus>> {
+        city_external_rpc_call!(
+            self,
+            "cr_getForcedWithdrawalRequests",
+            json!([checkpoint_id]),
+            Vec<CityForcedWithdrawalRequestStatus>
+        )
+    }
+
     async fn get_deposit_tree_root(&self, checkpoint_id: u64) -> anyhow::Result<CityHash> {
         city_external_rpc_call!(
             self,
@@ -467,6 +663,19 @@ impl CityRpcProvider for RpcProvider {
         )
     }
 
+    async fn get_deposit_ids_for_public_key(
+        &self,
+        checkpoint_id: u64,
+        public_key: CompressedPublicKey,
+    ) -> anyhow::Result<Vec<u64>> {
+        city_external_rpc_call!(
+            self,
+            "cr_getDepositIdsForPublicKey",
+            json!([checkpoint_id, public_key]),
+            Vec<u64>
+        )
+    }
+
     async fn get_deposit_hash(
         &self,
         checkpoint_id: u64,
@@ -575,6 +784,19 @@ impl CityRpcProvider for RpcProvider {
         )
     }
 
+    async fn get_withdrawal_ids_for_destination(
+        &self,
+        checkpoint_id: u64,
+        destination: BTCAddress160,
+    ) -> anyhow::Result<Vec<u64>> {
+        city_external_rpc_call!(
+            self,
+            "cr_getWithdrawalIdsForDestination",
+            json!([checkpoint_id, destination]),
+            Vec<u64>
+        )
+    }
+
     async fn get_withdrawal_hash(
         &self,
         checkpoint_id: u64,
@@ -642,6 +864,22 @@ impl CityRpcProvider for RpcProvider {
         )
     }
 
+    async fn get_block_proving_status(
+        &self,
+        checkpoint_id: u64,
+    ) -> anyhow::Result<QBlockProvingStatus> {
+        city_external_rpc_call!(
+            self,
+            "cr_getBlockProvingStatus",
+            json!([checkpoint_id]),
+            QBlockProvingStatus
+        )
+    }
+
+    async fn get_workers(&self) -> anyhow::Result<Vec<QWorkerStatus>> {
+        city_external_rpc_call!(self, "cr_getWorkers", json!([]), Vec<QWorkerStatus>)
+    }
+
     async fn register_user<F: RichField>(
         &self,
         req: CityRegisterUserRPCRequest<F>,
@@ -669,6 +907,46 @@ impl CityRpcProvider for RpcProvider {
     ) -> anyhow::Result<()> {
         city_rpc_call!(self, RequestParams::<F>::TokenTransfer(req))
     }
+
+    async fn change_public_key<F: RichField>(
+        &self,
+        req: CityChangePublicKeyRPCRequest<F>,
+    ) -> anyhow::Result<()> {
+        city_rpc_call!(self, RequestParams::<F>::ChangePublicKey(req))
+    }
+
+    async fn batch_token_transfer<F: RichField>(
+        &self,
+        req: CityBatchTokenTransferRPCRequest,
+    ) -> anyhow::Result<()> {
+        city_rpc_call!(self, RequestParams::<F>::BatchTokenTransfer(req))
+    }
+
+    async fn open_token_account<F: RichField>(
+        &self,
+        req: CityOpenTokenAccountRPCRequest,
+    ) -> anyhow::Result<()> {
+        city_rpc_call!(self, RequestParams::<F>::OpenTokenAccount(req))
+    }
+
+    async fn mint_token<F: RichField>(&self, req: CityMintTokenRPCRequest) -> anyhow::Result<()> {
+        city_rpc_call!(self, RequestParams::<F>::MintToken(req))
+    }
+
+    async fn send_token<F: RichField>(&self, req: CitySendTokenRPCRequest) -> anyhow::Result<()> {
+        city_rpc_call!(self, RequestParams::<F>::SendToken(req))
+    }
+
+    async fn burn_token<F: RichField>(&self, req: CityBurnTokenRPCRequest) -> anyhow::Result<()> {
+        city_rpc_call!(self, RequestParams::<F>::BurnToken(req))
+    }
+
+    async fn cancel_withdrawal<F: RichField>(
+        &self,
+        req: CityCancelWithdrawalRPCRequest<F>,
+    ) -> anyhow::Result<()> {
+        city_rpc_call!(self, RequestParams::<F>::CancelWithdrawal(req))
+    }
 }
 
 impl CityRpcProviderSync for RpcProviderSync {
@@ -737,6 +1015,58 @@ impl CityRpcProviderSync for RpcProviderSync {
         )
     }
 
+    fn get_user_tree_multi_proof_sync(
+        &self,
+        checkpoint_id: u64,
+        leaf_ids: &[u64],
+    ) -> anyhow::Result<CityMerkleMultiProof> {
+        city_external_rpc_call_sync!(
+            self,
+            "cr_getUserTreeMultiProof",
+            json!([checkpoint_id, leaf_ids]),
+            CityMerkleMultiProof
+        )
+    }
+
+    fn get_token_balance_sync(
+        &self,
+        checkpoint_id: u64,
+        user_id: u64,
+        token_id: u64,
+    ) -> anyhow::Result<CityTokenBalance> {
+        city_external_rpc_call_sync!(
+            self,
+            "cr_getTokenBalance",
+            json!([checkpoint_id, user_id, token_id]),
+            CityTokenBalance
+        )
+    }
+
+    fn get_token_info_sync(
+        &self,
+        checkpoint_id: u64,
+        token_id: u64,
+    ) -> anyhow::Result<CityTokenInfo> {
+        city_external_rpc_call_sync!(
+            self,
+            "cr_getTokenInfo",
+            json!([checkpoint_id, token_id]),
+            CityTokenInfo
+        )
+    }
+
+    fn get_forced_withdrawal_requests_sync(
+        &self,
+        checkpoint_id: u64,
+    ) -> anyhow::Result<Vec<CityForcedWithdrawalRequestStatus>> {
+        city_external_rpc_call_sync!(
+            self,
+            "cr_getForcedWithdrawalRequests",
+            json!([checkpoint_id]),
+            Vec<CityForcedWithdrawalRequestStatus>
+        )
+    }
+
     fn get_deposit_tree_root_sync(&self, checkpoint_id: u64) -> anyhow::Result<CityHash> {
         city_external_rpc_call_sync!(
             self,
@@ -796,6 +1126,19 @@ impl CityRpcProviderSync for RpcProviderSync {
         )
     }
 
+    fn get_deposit_ids_for_public_key_sync(
+        &self,
+        checkpoint_id: u64,
+        public_key: CompressedPublicKey,
+    ) -> anyhow::Result<Vec<u64>> {
+        city_external_rpc_call_sync!(
+            self,
+            "cr_getDepositIdsForPublicKey",
+            json!([checkpoint_id, public_key]),
+            Vec<u64>
+        )
+    }
+
     fn get_deposit_hash_sync(
         &self,
         checkpoint_id: u64,
@@ -904,6 +1247,19 @@ impl CityRpcProviderSync for RpcProviderSync {
         )
     }
 
+    fn get_withdrawal_ids_for_destination_sync(
+        &self,
+        checkpoint_id: u64,
+        destination: BTCAddress160,
+    ) -> anyhow::Result<Vec<u64>> {
+        city_external_rpc_call_sync!(
+            self,
+            "cr_getWithdrawalIdsForDestination",
+            json!([checkpoint_id, destination]),
+            Vec<u64>
+        )
+    }
+
     fn get_withdrawal_hash_sync(
         &self,
         checkpoint_id: u64,
@@ -973,6 +1329,22 @@ impl CityRpcProviderSync for RpcProviderSync {
         )
     }
 
+    fn get_block_proving_status_sync(
+        &self,
+        checkpoint_id: u64,
+    ) -> anyhow::Result<QBlockProvingStatus> {
+        city_external_rpc_call_sync!(
+            self,
+            "cr_getBlockProvingStatus",
+            json!([checkpoint_id]),
+            QBlockProvingStatus
+        )
+    }
+
+    fn get_workers_sync(&self) -> anyhow::Result<Vec<QWorkerStatus>> {
+        city_external_rpc_call_sync!(self, "cr_getWorkers", json!([]), Vec<QWorkerStatus>)
+    }
+
     fn register_user_sync<F: RichField>(
         &self,
         req: CityRegisterUserRPCRequest<F>,
@@ -1000,4 +1372,44 @@ impl CityRpcProviderSync for RpcProviderSync {
     ) -> anyhow::Result<()> {
         city_rpc_call_sync!(self, RequestParams::<F>::TokenTransfer(req))
     }
+
+    fn change_public_key_sync<F: RichField>(
+        &self,
+        req: CityChangePublicKeyRPCRequest<F>,
+    ) -> anyhow::Result<()> {
+        city_rpc_call_sync!(self, RequestParams::<F>::ChangePublicKey(req))
+    }
+
+    fn batch_token_transfer_sync<F: RichField>(
+        &self,
+        req: CityBatchTokenTransferRPCRequest,
+    ) -> anyhow::Result<()> {
+        city_rpc_call_sync!(self, RequestParams::<F>::BatchTokenTransfer(req))
+    }
+
+    fn open_token_account_sync<F: RichField>(
+        &self,
+        req: CityOpenTokenAccountRPCRequest,
+    ) -> anyhow::Result<()> {
+        city_rpc_call_sync!(self, RequestParams::<F>::OpenTokenAccount(req))
+    }
+
+    fn mint_token_sync<F: RichField>(&self, req: CityMintTokenRPCRequest) -> anyhow::Result<()> {
+        city_rpc_call_sync!(self, RequestParams::<F>::MintToken(req))
+    }
+
+    fn send_token_sync<F: RichField>(&self, req: CitySendTokenRPCRequest) -> anyhow::Result<()> {
+        city_rpc_call_sync!(self, RequestParams::<F>::SendToken(req))
+    }
+
+    fn burn_token_sync<F: RichField>(&self, req: CityBurnTokenRPCRequest) -> anyhow::Result<()> {
+        city_rpc_call_sync!(self, RequestParams::<F>::BurnToken(req))
+    }
+
+    fn cancel_withdrawal_sync<F: RichField>(
+        &self,
+        req: CityCancelWithdrawalRPCRequest<F>,
+    ) -> anyhow::Result<()> {
+        city_rpc_call_sync!(self, RequestParams::<F>::CancelWithdrawal(req))
+    }
 }
@@ -12,7 +12,15 @@ use crate::subcommand::add_withdrawal;
 use crate::subcommand::claim_deposit;
 use crate::subcommand::register_user;
 use crate::subcommand::token_transfer;
+use crate::subcommand::batch_token_transfer;
+use crate::subcommand::change_public_key;
+use crate::subcommand::open_token_account;
+use crate::subcommand::mint_token;
+use crate::subcommand::send_token;
+use crate::subcommand::burn_token;
+use crate::subcommand::cancel_withdrawal;
 use crate::subcommand::l1_deposit;
+use crate::subcommand::forced_withdrawal;
 
 use crate::subcommand::get_public_key;
 use crate::subcommand::random_wallet;
@@ -34,7 +42,15 @@ async fn main() -> Result<()> {
         Commands::ClaimDeposit(args) => claim_deposit::run(args).await?,
         Commands::RegisterUser(args) => register_user::run(args).await?,
         Commands::TokenTransfer(args) => token_transfer::run(args).await?,
+        Commands::BatchTokenTransfer(args) => batch_token_transfer::run(args).await?,
+        Commands::ChangePublicKey(args) => change_public_key::run(args).await?,
+        Commands::OpenTokenAccount(args) => open_token_account::run(args).await?,
+        Commands::MintToken(args) => mint_token::run(args).await?,
+        Commands::SendToken(args) => send_token::run(args).await?,
+        Commands::BurnToken(args) => burn_token::run(args).await?,
+        Commands::CancelWithdrawal(args) => cancel_withdrawal::run(args).await?,
         Commands::L1Deposit(args) => l1_deposit::run(args).await?,
+        Commands::ForcedWithdrawal(args) => forced_withdrawal::run(args).await?,
 
         Commands::SignHash(args) => sign_hash::run(args).await?,
         Commands::GetPublicKey(args) => get_public_key::run(args).await?,
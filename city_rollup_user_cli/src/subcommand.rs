@@ -6,7 +6,15 @@ pub mod add_withdrawal;
 pub mod claim_deposit;
 pub mod register_user;
 pub mod token_transfer;
+pub mod batch_token_transfer;
+pub mod change_public_key;
+pub mod open_token_account;
+pub mod mint_token;
+pub mod send_token;
+pub mod burn_token;
+pub mod cancel_withdrawal;
 pub mod l1_deposit;
+pub mod forced_withdrawal;
 
 pub mod sign_hash;
 pub mod get_public_key;
@@ -26,7 +34,15 @@ pub enum Commands {
     ClaimDeposit(city_common::cli::user_args::ClaimDepositArgs),
     RegisterUser(city_common::cli::user_args::RegisterUserArgs),
     TokenTransfer(city_common::cli::user_args::TokenTransferArgs),
+    BatchTokenTransfer(city_common::cli::user_args::BatchTokenTransferArgs),
+    ChangePublicKey(city_common::cli::user_args::ChangePublicKeyArgs),
+    OpenTokenAccount(city_common::cli::user_args::OpenTokenAccountArgs),
+    MintToken(city_common::cli::user_args::MintTokenArgs),
+    SendToken(city_common::cli::user_args::SendTokenArgs),
+    BurnToken(city_common::cli::user_args::BurnTokenArgs),
+    CancelWithdrawal(city_common::cli::user_args::CancelWithdrawalArgs),
     L1Deposit(city_common::cli::user_args::L1DepositArgs),
+    ForcedWithdrawal(city_common::cli::user_args::ForcedWithdrawalArgs),
 
     SignHash(city_common::cli::user_args::SignHashArgs),
     GetPublicKey(city_common::cli::user_args::GetPublicKeyArgs),
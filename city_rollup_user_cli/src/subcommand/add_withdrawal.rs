@@ -34,6 +34,7 @@ pub async fn run(args: AddWithdrawalArgs) -> Result<()> {
         args.user_id,
         destination,
         args.value,
+        args.fee,
         args.nonce,
     )?;
 
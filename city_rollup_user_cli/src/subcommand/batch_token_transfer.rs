@@ -0,0 +1,63 @@
+use std::str::FromStr;
+
+use anyhow::Result;
+
+use city_common::cli::user_args::BatchTokenTransferArgs;
+use city_crypto::hash::qhashout::QHashOut;
+
+use city_rollup_circuit::wallet::memory::CityMemoryWallet;
+use city_rollup_common::{
+    api::data::block::rpc_request::CityBatchTransferRecipient,
+    introspection::rollup::constants::get_network_magic_for_str,
+};
+
+use city_rollup_rpc_provider::{CityRpcProvider, RpcProvider};
+use plonky2::{field::goldilocks_field::GoldilocksField, plonk::config::PoseidonGoldilocksConfig};
+
+const D: usize = 2;
+type C = PoseidonGoldilocksConfig;
+type F = GoldilocksField;
+
+fn parse_transfer(transfer: &str) -> Result<CityBatchTransferRecipient> {
+    let (to, value) = transfer.split_once(':').ok_or_else(|| {
+        anyhow::format_err!("invalid transfer '{}', expected <to>:<value>", transfer)
+    })?;
+    Ok(CityBatchTransferRecipient {
+        to: to.parse()?,
+        value: value.parse()?,
+    })
+}
+
+pub async fn run(args: BatchTokenTransferArgs) -> Result<()> {
+    let provider = RpcProvider::new(&args.rpc_address);
+
+    let network_magic = get_network_magic_for_str(args.network)?;
+
+    let private_key = QHashOut::<GoldilocksField>::from_str(&args.private_key)
+        .map_err(|e| anyhow::format_err!("{}", e.to_string()))?;
+
+    let transfers = args
+        .transfers
+        .iter()
+        .map(|t| parse_transfer(t))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut wallet = CityMemoryWallet::<C, D>::new_fast_setup();
+
+    let public_key = wallet.add_zk_private_key(private_key);
+
+    let city_batch_token_transfer_rpcrequest = wallet.sign_batch_l2_transfer(
+        public_key,
+        network_magic,
+        args.from,
+        &transfers,
+        args.fee,
+        args.nonce,
+    )?;
+
+    provider
+        .batch_token_transfer::<F>(city_batch_token_transfer_rpcrequest)
+        .await?;
+
+    Ok(())
+}
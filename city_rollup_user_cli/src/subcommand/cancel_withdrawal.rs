@@ -0,0 +1,53 @@
+use std::str::FromStr;
+
+use anyhow::Result;
+
+use city_common::cli::user_args::CancelWithdrawalArgs;
+use city_crypto::hash::qhashout::QHashOut;
+
+use city_rollup_circuit::wallet::memory::CityMemoryWallet;
+use city_rollup_common::introspection::rollup::constants::get_network_magic_for_str;
+
+use city_rollup_rpc_provider::{CityRpcProvider, RpcProvider};
+use plonky2::{field::goldilocks_field::GoldilocksField, plonk::config::PoseidonGoldilocksConfig};
+
+const D: usize = 2;
+type C = PoseidonGoldilocksConfig;
+type F = GoldilocksField;
+
+pub async fn run(args: CancelWithdrawalArgs) -> Result<()> {
+    let provider = RpcProvider::new(&args.rpc_address);
+
+    let network_magic = get_network_magic_for_str(args.network)?;
+
+    let private_key = QHashOut::<GoldilocksField>::from_str(&args.private_key)
+        .map_err(|e| anyhow::format_err!("{}", e.to_string()))?;
+
+    let mut wallet = CityMemoryWallet::<C, D>::new_fast_setup();
+
+    let public_key = wallet.add_zk_private_key(private_key);
+
+    // the withdrawal hash is signed so the request cannot cancel a different withdrawal if the id is reused
+    let latest_block_state = provider.get_latest_block_state().await?;
+    let withdrawal_hash = provider
+        .get_withdrawal_hash(latest_block_state.checkpoint_id, args.withdrawal_id)
+        .await?;
+    if withdrawal_hash == QHashOut::ZERO {
+        anyhow::bail!("withdrawal {} is not pending", args.withdrawal_id);
+    }
+
+    let city_cancel_withdrawal_rpcrequest = wallet.sign_cancel_withdrawal(
+        public_key,
+        network_magic,
+        args.user_id,
+        args.withdrawal_id,
+        withdrawal_hash,
+        args.nonce,
+    )?;
+
+    provider
+        .cancel_withdrawal::<F>(city_cancel_withdrawal_rpcrequest)
+        .await?;
+
+    Ok(())
+}
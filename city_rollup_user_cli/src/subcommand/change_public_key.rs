@@ -0,0 +1,46 @@
+use std::str::FromStr;
+
+use anyhow::Result;
+
+use city_common::cli::user_args::ChangePublicKeyArgs;
+use city_crypto::hash::qhashout::QHashOut;
+
+use city_rollup_common::introspection::rollup::constants::get_network_magic_for_str;
+use city_rollup_circuit::wallet::memory::CityMemoryWallet;
+
+use city_rollup_rpc_provider::{CityRpcProvider, RpcProvider};
+use plonky2::{field::goldilocks_field::GoldilocksField, plonk::config::PoseidonGoldilocksConfig};
+
+const D: usize = 2;
+type C = PoseidonGoldilocksConfig;
+type F = GoldilocksField;
+
+pub async fn run(args: ChangePublicKeyArgs) -> Result<()> {
+    let provider = RpcProvider::new(&args.rpc_address);
+
+    let network_magic = get_network_magic_for_str(args.network)?;
+
+    let private_key = QHashOut::<GoldilocksField>::from_str(&args.private_key)
+        .map_err(|e| anyhow::format_err!("{}", e.to_string()))?;
+
+    let new_public_key = QHashOut::<GoldilocksField>::from_str(&args.new_public_key)
+        .map_err(|e| anyhow::format_err!("{}", e.to_string()))?;
+
+    let mut wallet = CityMemoryWallet::<C, D>::new_fast_setup();
+
+    let public_key = wallet.add_zk_private_key(private_key);
+
+    let city_change_public_key_rpcrequest = wallet.sign_change_public_key(
+        public_key,
+        network_magic,
+        args.user_id,
+        new_public_key,
+        args.nonce,
+    )?;
+
+    provider
+        .change_public_key::<F>(city_change_public_key_rpcrequest)
+        .await?;
+
+    Ok(())
+}
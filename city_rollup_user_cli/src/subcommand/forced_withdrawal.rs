@@ -0,0 +1,84 @@
+use std::time::Duration;
+
+use city_common::{
+    cli::user_args::ForcedWithdrawalArgs,
+    config::rollup_constants::FORCED_WITHDRAWAL_REQUEST_MIN_VALUE,
+};
+use city_crypto::{
+    hash::base_types::{hash160::Hash160, hash256::Hash256},
+    signature::secp256k1::wallet::MemorySecp256K1Wallet,
+};
+use city_rollup_common::{
+    config::sighash_wrapper_config::SIGHASH_CIRCUIT_MAX_FORCED_WITHDRAWAL_REQUESTS,
+    introspection::rollup::{
+        forced_withdrawal::CityForcedWithdrawalRequest,
+        introspection_result::WITHDRAWAL_TYPE_BYTE_P2PKH,
+    },
+    link::{data::BTCAddress160, link_api::BTCLinkAPI, traits::QBitcoinAPIFunderSync},
+};
+use city_rollup_rpc_provider::{CityRpcProvider, RpcProvider};
+
+use anyhow::Result;
+const MAX_CHECKPOINT_ID: u64 = 0xffffffff;
+
+pub async fn run(args: ForcedWithdrawalArgs) -> Result<()> {
+    if SIGHASH_CIRCUIT_MAX_FORCED_WITHDRAWAL_REQUESTS == 0 {
+        // an unspent request output can never be recovered from the deposit address
+        anyhow::bail!(
+            "the sighash whitelist has no circuits which spend forced withdrawal requests, the request would be stuck at the deposit address"
+        );
+    }
+    if args.amount < FORCED_WITHDRAWAL_REQUEST_MIN_VALUE {
+        anyhow::bail!(
+            "forced withdrawal requests must pay at least {} to the deposit address",
+            FORCED_WITHDRAWAL_REQUEST_MIN_VALUE
+        );
+    }
+    let (destination_type, destination) = if args.destination.len() == 40 {
+        (0u8, Hash160::from_hex_string(&args.destination)?)
+    } else {
+        let address = BTCAddress160::try_from_string(&args.destination)?;
+        (address.address_type.to_u8(), address.address)
+    };
+    if destination_type != WITHDRAWAL_TYPE_BYTE_P2PKH {
+        anyhow::bail!("forced withdrawals can only be sent to a p2pkh address");
+    }
+    let provider = RpcProvider::new(&args.rpc_address);
+    let mut wallet = MemorySecp256K1Wallet::new();
+    let api = BTCLinkAPI::new_str(&args.bitcoin_rpc, &args.electrs_api);
+    let from = BTCAddress160::from_p2pkh_key(
+        wallet.add_private_key(Hash256::from_hex_string(&args.private_key)?)?,
+    );
+
+    let deposit_address = if args.deposit_address.is_empty() {
+        provider
+            .get_city_block_deposit_address(MAX_CHECKPOINT_ID)
+            .await?
+    } else {
+        BTCAddress160::try_from_string(&args.deposit_address)?.address
+    };
+    let request = CityForcedWithdrawalRequest {
+        txid: Hash256::ZERO,
+        user_id: args.user_id,
+        nonce: args.nonce,
+        value: args.value,
+        destination_type,
+        destination,
+    };
+    let txid = api.fund_forced_withdrawal_request(
+        &wallet,
+        from,
+        BTCAddress160::new_p2sh(deposit_address),
+        &request,
+        args.amount,
+    )?;
+    if api.is_regtest() {
+        // make sure to mine some blocks so the address is indexed by electrs
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        api.mine_blocks(10)?;
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        api.mine_blocks(10)?;
+    }
+    println!("{{\"txid\": \"{}\"}}", txid.to_hex_string());
+    Ok(())
+}
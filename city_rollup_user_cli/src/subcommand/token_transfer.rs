@@ -33,6 +33,7 @@ pub async fn run(args: TokenTransferArgs) -> Result<()> {
         args.from,
         args.to,
         args.value,
+        args.fee,
         args.nonce,
     )?;
 
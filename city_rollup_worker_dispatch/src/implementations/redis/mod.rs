@@ -1,6 +1,8 @@
 use std::time::Duration;
 
 use anyhow::Result;
+use city_common::cli::modes::QWorkerCapability;
+use redis::Commands;
 use rsmq::PooledRsmq;
 use rsmq::RedisConnectionManager;
 use rsmq::RsmqConnection;
@@ -11,12 +13,15 @@ use serde_repr::Deserialize_repr;
 use serde_repr::Serialize_repr;
 
 use crate::traits::proving_dispatcher::ProvingDispatcher;
+use crate::traits::proving_priority_queue::ProvingPriorityQueue;
 use crate::traits::proving_worker::ProvingWorkerListener;
 
 #[derive(Clone)]
 pub struct RedisQueue {
     // we use queue here because pubsub is mpmc
     queue: PooledRsmq,
+    // proving jobs are kept in sorted sets so they can be popped by priority
+    jobs_pool: r2d2::Pool<r2d2_redis::RedisConnectionManager>,
 }
 
 pub const Q_HIDDEN: Option<Duration> = Some(Duration::from_secs(600));
@@ -27,11 +32,24 @@ pub const Q_RPC_TOKEN_TRANSFER: &'static str = "RPC_TOKEN_TRANSFER";
 pub const Q_RPC_CLAIM_DEPOSIT: &'static str = "RPC_CLAIM_DEPOSIT";
 pub const Q_RPC_ADD_WITHDRAWAL: &'static str = "RPC_ADD_WITHDRAWAL";
 pub const Q_RPC_REGISTER_USER: &'static str = "RPC_REGISTER_USER";
+pub const Q_RPC_CHANGE_PUBLIC_KEY: &'static str = "RPC_CHANGE_PUBLIC_KEY";
+pub const Q_RPC_BATCH_TOKEN_TRANSFER: &'static str = "RPC_BATCH_TOKEN_TRANSFER";
+pub const Q_RPC_OPEN_TOKEN_ACCOUNT: &'static str = "RPC_OPEN_TOKEN_ACCOUNT";
+pub const Q_RPC_MINT_TOKEN: &'static str = "RPC_MINT_TOKEN";
+pub const Q_RPC_SEND_TOKEN: &'static str = "RPC_SEND_TOKEN";
+pub const Q_RPC_BURN_TOKEN: &'static str = "RPC_BURN_TOKEN";
+pub const Q_RPC_CANCEL_WITHDRAWAL: &'static str = "RPC_CANCEL_WITHDRAWAL";
 
 pub const Q_CMD: &'static str = "CMD";
 pub const Q_JOB: &'static str = "JOB";
+pub const Q_JOB_GROTH16: &'static str = "JOB_GROTH16";
+pub const Q_JOB_SIGNATURE: &'static str = "JOB_SIGNATURE";
+pub const Q_JOB_TOPICS: [&'static str; 3] = [Q_JOB, Q_JOB_GROTH16, Q_JOB_SIGNATURE];
 pub const Q_NOTIFICATIONS: &'static str = "NOTIFICATIONS";
 
+pub const Q_PRIORITY_PREFIX: &'static str = "pq:";
+pub const Q_PRIORITY_SEQUENCE: &'static str = "pq_sequence";
+
 #[derive(Clone, Copy, PartialEq, Debug, Serialize_repr, Deserialize_repr)]
 #[repr(u8)]
 pub enum QueueCmd {
@@ -44,8 +62,21 @@ pub enum QueueNotification {
     CoreJobCompleted = 0,
 }
 
+pub fn get_job_queue_for_capability(capability: QWorkerCapability) -> &'static str {
+    match capability {
+        QWorkerCapability::StandardProof => Q_JOB,
+        QWorkerCapability::Groth16Proof => Q_JOB_GROTH16,
+        QWorkerCapability::SignatureProof => Q_JOB_SIGNATURE,
+    }
+}
+
+fn get_priority_queue_key(topic: &str) -> String {
+    format!("{}{}", Q_PRIORITY_PREFIX, topic)
+}
+
 impl RedisQueue {
     pub fn new(uri: &str) -> Result<Self> {
+        let jobs_pool = r2d2::Pool::builder().build(r2d2_redis::RedisConnectionManager::new(uri)?)?;
         let client = redis::Client::open(uri)?;
         let manager = RedisConnectionManager::from_client(client)?;
         let queue = {
@@ -56,8 +87,14 @@ impl RedisQueue {
                 Q_RPC_CLAIM_DEPOSIT,
                 Q_RPC_ADD_WITHDRAWAL,
                 Q_RPC_REGISTER_USER,
+                Q_RPC_CHANGE_PUBLIC_KEY,
+                Q_RPC_BATCH_TOKEN_TRANSFER,
+                Q_RPC_OPEN_TOKEN_ACCOUNT,
+                Q_RPC_MINT_TOKEN,
+                Q_RPC_SEND_TOKEN,
+                Q_RPC_BURN_TOKEN,
+                Q_RPC_CANCEL_WITHDRAWAL,
                 Q_CMD,
-                Q_JOB,
                 Q_NOTIFICATIONS,
             ] {
                 if matches!(
@@ -69,12 +106,15 @@ impl RedisQueue {
             }
             Ok::<_, anyhow::Error>(queue)
         }?;
-        Ok(Self { queue })
+        Ok(Self { queue, jobs_pool })
     }
 
-    pub fn new_with_pool(pool: r2d2::Pool<RedisConnectionManager>) -> Result<Self> {
+    pub fn new_with_pool(
+        pool: r2d2::Pool<RedisConnectionManager>,
+        jobs_pool: r2d2::Pool<r2d2_redis::RedisConnectionManager>,
+    ) -> Result<Self> {
         let queue = PooledRsmq::new_with_pool(pool, false, None);
-        Ok(Self { queue })
+        Ok(Self { queue, jobs_pool })
     }
 }
 
@@ -90,6 +130,33 @@ impl ProvingDispatcher for RedisQueue {
     }
 }
 
+impl ProvingPriorityQueue for RedisQueue {
+    fn dispatch_with_priority(
+        &mut self,
+        topic: &'static str,
+        value: impl Serialize + Send + 'static,
+        priority: u32,
+    ) -> Result<()> {
+        let mut conn = self.jobs_pool.get()?;
+        // the big endian sequence prefix makes members with the same score sort in dispatch order
+        let sequence: u64 = conn.incr(Q_PRIORITY_SEQUENCE, 1)?;
+        let member = [sequence.to_be_bytes().to_vec(), serde_json::to_vec(&value)?].concat();
+        conn.zadd(get_priority_queue_key(topic), member, -(priority as i64))?;
+        Ok(())
+    }
+
+    fn pop_highest_priority(&mut self, topic: &'static str) -> Result<Option<Vec<u8>>> {
+        let mut conn = self.jobs_pool.get()?;
+        let result: Vec<(Vec<u8>, i64)> = conn.zpopmin(get_priority_queue_key(topic), 1)?;
+        Ok(result.into_iter().next().map(|(member, _)| member[8..].to_vec()))
+    }
+
+    fn get_priority_topic_len(&mut self, topic: &'static str) -> Result<usize> {
+        let mut conn = self.jobs_pool.get()?;
+        Ok(conn.zcard(get_priority_queue_key(topic))?)
+    }
+}
+
 impl ProvingWorkerListener for RedisQueue {
     fn subscribe(&mut self, _topic: &str) -> anyhow::Result<()> {
         Ok(())
@@ -141,10 +208,17 @@ impl ProvingWorkerListener for RedisQueue {
         Ok(self.queue.delete_message(topic, &id)?)
     }
 
-    fn is_empty(&mut self) -> bool {
+    fn is_topic_empty(&mut self, topic: &'static str) -> bool {
+        if Q_JOB_TOPICS.contains(&topic) {
+            return matches!(self.get_priority_topic_len(topic), Ok(0));
+        }
         matches!(
-            self.queue.get_queue_attributes(Q_JOB).map(|x| x.msgs == 0),
+            self.queue.get_queue_attributes(topic).map(|x| x.msgs == 0),
             Ok(true)
         )
     }
+
+    fn is_empty(&mut self) -> bool {
+        Q_JOB_TOPICS.iter().all(|topic| self.is_topic_empty(topic))
+    }
 }
@@ -1,2 +1,3 @@
 pub mod proving_dispatcher;
+pub mod proving_priority_queue;
 pub mod proving_worker;
@@ -0,0 +1,15 @@
+use serde::Serialize;
+
+use crate::traits::proving_dispatcher::ProvingDispatcher;
+
+pub trait ProvingPriorityQueue: ProvingDispatcher {
+    // higher priorities are popped first, equal priorities are popped in the order they were dispatched
+    fn dispatch_with_priority(
+        &mut self,
+        topic: &'static str,
+        value: impl Serialize + Send + 'static,
+        priority: u32,
+    ) -> anyhow::Result<()>;
+    fn pop_highest_priority(&mut self, topic: &'static str) -> anyhow::Result<Option<Vec<u8>>>;
+    fn get_priority_topic_len(&mut self, topic: &'static str) -> anyhow::Result<usize>;
+}
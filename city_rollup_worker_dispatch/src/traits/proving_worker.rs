@@ -9,5 +9,6 @@ pub trait ProvingWorkerListener: ProvingDispatcher {
     fn receive_all(&mut self, topic: &'static str, hidden: Option<Duration>) -> anyhow::Result<Vec<(String, Vec<u8>)>>;
     fn pop_all(&mut self, topic: &'static str) -> anyhow::Result<Vec<Vec<u8>>>;
     fn delete_message(&mut self, topic: &'static str, id: String) -> anyhow::Result<bool>;
+    fn is_topic_empty(&mut self, topic: &'static str) -> bool;
     fn is_empty(&mut self) -> bool;
 }
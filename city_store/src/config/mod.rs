@@ -1,24 +1,39 @@
 use city_common::config::rollup_constants::{
     GLOBAL_USER_TREE_HEIGHT, L1_DEPOSIT_TREE_HEIGHT, L1_WITHDRAWAL_TREE_HEIGHT,
+    TOKEN_BALANCE_TREE_HEIGHT, TOKEN_REGISTRY_TREE_HEIGHT, USER_PUBLIC_KEY_TREE_HEIGHT,
 };
 use city_crypto::hash::{
-    merkle::core::{DeltaMerkleProofCore, MerkleProofCore},
+    merkle::{
+        core::{DeltaMerkleProofCore, MerkleProofCore},
+        indexed::IndexedMerkleLeaf,
+        multi_proof::MerkleMultiProofCore,
+    },
     qhashout::QHashOut,
 };
-use city_rollup_common::{api::data::store::{CityL1Deposit, CityL2BlockState}, qworker::job_witnesses::inspect::QJobWitness};
-use kvq::adapters::standard::KVQStandardAdapter;
+use city_rollup_common::{api::data::store::{CityForcedWithdrawalRequestStatus, CityL1Deposit, CityL2BlockState}, qworker::job_witnesses::inspect::QJobWitness};
+use kvq::{adapters::standard::KVQStandardAdapter, kvq_table_registry};
 use plonky2::{
     field::goldilocks_field::GoldilocksField, hash::poseidon::PoseidonHash,
     plonk::config::PoseidonGoldilocksConfig,
 };
 
 use crate::models::{
+    forced_withdrawal::{data::L1ForcedWithdrawalKeyCore, model::L1ForcedWithdrawalsModel},
+    indexed_merkle::{
+        data::{IndexedMerkleKeyEntry, IndexedMerkleLeafKeyCore, IndexedMerkleSortedKeyCore},
+        model::IndexedMerkleTreeModel,
+    },
     kvq_merkle::{key::KVQMerkleNodeKey, model::KVQFixedConfigMerkleTreeModel},
     l1_deposits::{
         data::{L1DepositKeyByDepositIdCore, L1DepositKeyByTransactionIdCore},
         model::L1DepositsModel,
     },
+    l1_index::{
+        data::{L1IdsByOwnerKeyCore, L1OwnerByIdKeyCore},
+        model::{L1IdsByOwnerModel, L1OwnerByIdModel},
+    },
     l2_block_state::{data::L2BlockStateKeyCore, model::L2BlockStatesModel},
+    schema::data::SchemaVersionKeyCore,
     user::{data::L2UserIdKeyByPubicKeyIdCore, model::L2UserIdsModel},
 };
 
@@ -28,6 +43,7 @@ pub type CityHasher = PoseidonHash;
 pub type CityHash = QHashOut<F>;
 pub type CityMerkleProof = MerkleProofCore<CityHash>;
 pub type CityDeltaMerkleProof = DeltaMerkleProofCore<CityHash>;
+pub type CityMerkleMultiProof = MerkleMultiProofCore<CityHash>;
 pub type CityJobWitness = QJobWitness<F>;
 
 pub const D: usize = 2;
@@ -37,49 +53,338 @@ pub const L1_DEPOSITS_BY_ID_TABLE_TYPE: u16 = 2;
 pub const L1_DEPOSITS_BY_TXID_TABLE_TYPE: u16 = 3;
 pub const L2_BLOCK_STATE_TABLE_TYPE: u16 = 4;
 pub const L2_USER_IDS_BY_PUBLIC_KEY_TABLE_TYPE: u16 = 5;
+// table type 6 is unused, it held the token registry before the registry became a merkle tree
+pub const L1_FORCED_WITHDRAWALS_TABLE_TYPE: u16 = 7;
+// table type 8 is unused, it held the withdrawal owners before they were kept in the withdrawal
+// records
+pub const INDEXED_MERKLE_LEAVES_TABLE_TYPE: u16 = 9;
+pub const INDEXED_MERKLE_SORTED_KEYS_TABLE_TYPE: u16 = 10;
+pub const L1_DEPOSIT_IDS_BY_PUBLIC_KEY_TABLE_TYPE: u16 = 11;
+pub const L1_WITHDRAWAL_IDS_BY_DESTINATION_TABLE_TYPE: u16 = 12;
+pub const L1_WITHDRAWAL_DESTINATIONS_BY_ID_TABLE_TYPE: u16 = 13;
+pub const SCHEMA_VERSION_TABLE_TYPE: u16 = 14;
+pub const L1_WITHDRAWAL_RECORDS_BY_ID_TABLE_TYPE: u16 = 15;
 
 pub const GLOBAL_USER_TREE_ID: u8 = 1;
 pub const L1_DEPOSIT_TREE_ID: u8 = 2;
 pub const L1_WITHDRAWAL_TREE_ID: u8 = 3;
+pub const USER_PUBLIC_KEY_TREE_ID: u8 = 4;
+pub const TOKEN_REGISTRY_TREE_ID: u8 = 5;
+pub const TOKEN_BALANCE_TREE_ID: u8 = 6;
+
+// compressed public key (33)
+pub const L1_DEPOSIT_OWNER_SIZE: usize = 33;
+// address type (1) + hash160 (20)
+pub const L1_WITHDRAWAL_DESTINATION_SIZE: usize = 21;
+// address type (1) + hash160 (20) + value (8) + owner user id (8)
+pub const L1_WITHDRAWAL_RECORD_SIZE: usize = 37;
+
+// declares the store aliases together with the keys of the tables they use and registers every
+// key in the table registry, so a store can't be added without registering its tables. aliases
+// built on another store list no keys, their tables are registered by the store they wrap
+macro_rules! city_stores {
+    ($vis:vis $registry:ident; $([$($table:ty),* $(,)?] $store:item)+) => {
+        $($store)+
+
+        // every table of the store, two tables with the same table type fail to compile
+        kvq_table_registry!($vis $registry = [$($($table,)*)+]);
+    };
+}
+
+city_stores! {
+    pub CITY_STORE_TABLES;
+
+    [KVQMerkleNodeKey<TREE_TABLE_TYPE>]
+    pub type CityTreeStore<S, const TREE_ID: u8, const HEIGHT: u8> = KVQFixedConfigMerkleTreeModel<
+        TREE_ID,
+        HEIGHT,
+        0,
+        0,
+        TREE_TABLE_TYPE,
+        false,
+        S,
+        KVQStandardAdapter<S, KVQMerkleNodeKey<TREE_TABLE_TYPE>, CityHash>,
+        CityHash,
+        CityHasher,
+    >;
 
-pub type CityTreeStore<S, const TREE_ID: u8, const HEIGHT: u8> = KVQFixedConfigMerkleTreeModel<
-    TREE_ID,
-    HEIGHT,
-    0,
-    0,
-    TREE_TABLE_TYPE,
-    false,
-    S,
-    KVQStandardAdapter<S, KVQMerkleNodeKey<TREE_TABLE_TYPE>, CityHash>,
-    CityHash,
-    CityHasher,
->;
-
-pub type GlobalUserTreeStore<S> = CityTreeStore<S, GLOBAL_USER_TREE_ID, GLOBAL_USER_TREE_HEIGHT>;
-pub type L1DepositTreeStore<S> = CityTreeStore<S, L1_DEPOSIT_TREE_ID, L1_DEPOSIT_TREE_HEIGHT>;
-pub type L1WithdrawalTreeStore<S> =
-    CityTreeStore<S, L1_WITHDRAWAL_TREE_ID, L1_WITHDRAWAL_TREE_HEIGHT>;
-
-pub type L1DepositsStore<S> = L1DepositsModel<
-    L1_DEPOSITS_BY_ID_TABLE_TYPE,
-    L1_DEPOSITS_BY_TXID_TABLE_TYPE,
-    S,
-    KVQStandardAdapter<S, L1DepositKeyByDepositIdCore<L1_DEPOSITS_BY_ID_TABLE_TYPE>, CityL1Deposit>,
-    KVQStandardAdapter<
+    []
+    pub type GlobalUserTreeStore<S> =
+        CityTreeStore<S, GLOBAL_USER_TREE_ID, GLOBAL_USER_TREE_HEIGHT>;
+    []
+    pub type L1DepositTreeStore<S> = CityTreeStore<S, L1_DEPOSIT_TREE_ID, L1_DEPOSIT_TREE_HEIGHT>;
+    []
+    pub type L1WithdrawalTreeStore<S> =
+        CityTreeStore<S, L1_WITHDRAWAL_TREE_ID, L1_WITHDRAWAL_TREE_HEIGHT>;
+    // the roots of the token trees are committed in the token state leaf of the user tree
+    []
+    pub type TokenRegistryTreeStore<S> =
+        CityTreeStore<S, TOKEN_REGISTRY_TREE_ID, TOKEN_REGISTRY_TREE_HEIGHT>;
+    []
+    pub type TokenBalanceTreeStore<S> =
+        CityTreeStore<S, TOKEN_BALANCE_TREE_ID, TOKEN_BALANCE_TREE_HEIGHT>;
+
+    [
+        IndexedMerkleLeafKeyCore<INDEXED_MERKLE_LEAVES_TABLE_TYPE>,
+        IndexedMerkleSortedKeyCore<INDEXED_MERKLE_SORTED_KEYS_TABLE_TYPE>,
+    ]
+    pub type CityIndexedTreeStore<S, const TREE_ID: u8, const HEIGHT: u8> = IndexedMerkleTreeModel<
+        TREE_ID,
+        HEIGHT,
+        INDEXED_MERKLE_LEAVES_TABLE_TYPE,
+        INDEXED_MERKLE_SORTED_KEYS_TABLE_TYPE,
         S,
+        KVQStandardAdapter<
+            S,
+            IndexedMerkleLeafKeyCore<INDEXED_MERKLE_LEAVES_TABLE_TYPE>,
+            IndexedMerkleLeaf<F>,
+        >,
+        KVQStandardAdapter<
+            S,
+            IndexedMerkleSortedKeyCore<INDEXED_MERKLE_SORTED_KEYS_TABLE_TYPE>,
+            IndexedMerkleKeyEntry,
+        >,
+        KVQStandardAdapter<S, KVQMerkleNodeKey<TREE_TABLE_TYPE>, CityHash>,
+        CityTreeStore<S, TREE_ID, HEIGHT>,
+    >;
+
+    // the public keys of all registered users, used to prove that a public key is not registered
+    // yet
+    []
+    pub type UserPublicKeyTreeStore<S> =
+        CityIndexedTreeStore<S, USER_PUBLIC_KEY_TREE_ID, USER_PUBLIC_KEY_TREE_HEIGHT>;
+
+    [
+        L1DepositKeyByDepositIdCore<L1_DEPOSITS_BY_ID_TABLE_TYPE>,
         L1DepositKeyByTransactionIdCore<L1_DEPOSITS_BY_TXID_TABLE_TYPE>,
-        CityL1Deposit,
-    >,
->;
-
-pub type L2BlockStateStore<S> = L2BlockStatesModel<
-    L2_BLOCK_STATE_TABLE_TYPE,
-    S,
-    KVQStandardAdapter<S, L2BlockStateKeyCore<L2_BLOCK_STATE_TABLE_TYPE>, CityL2BlockState>,
->;
-
-pub type L2UserIdsStore<S> = L2UserIdsModel<
-    L2_USER_IDS_BY_PUBLIC_KEY_TABLE_TYPE,
-    S,
-    KVQStandardAdapter<S, L2UserIdKeyByPubicKeyIdCore<L2_USER_IDS_BY_PUBLIC_KEY_TABLE_TYPE>, u64>,
->;
+    ]
+    pub type L1DepositsStore<S> = L1DepositsModel<
+        L1_DEPOSITS_BY_ID_TABLE_TYPE,
+        L1_DEPOSITS_BY_TXID_TABLE_TYPE,
+        S,
+        KVQStandardAdapter<
+            S,
+            L1DepositKeyByDepositIdCore<L1_DEPOSITS_BY_ID_TABLE_TYPE>,
+            CityL1Deposit,
+        >,
+        KVQStandardAdapter<
+            S,
+            L1DepositKeyByTransactionIdCore<L1_DEPOSITS_BY_TXID_TABLE_TYPE>,
+            CityL1Deposit,
+        >,
+    >;
+
+    [L2BlockStateKeyCore<L2_BLOCK_STATE_TABLE_TYPE>]
+    pub type L2BlockStateStore<S> = L2BlockStatesModel<
+        L2_BLOCK_STATE_TABLE_TYPE,
+        S,
+        KVQStandardAdapter<S, L2BlockStateKeyCore<L2_BLOCK_STATE_TABLE_TYPE>, CityL2BlockState>,
+    >;
+
+    [L2UserIdKeyByPubicKeyIdCore<L2_USER_IDS_BY_PUBLIC_KEY_TABLE_TYPE>]
+    pub type L2UserIdsStore<S> = L2UserIdsModel<
+        L2_USER_IDS_BY_PUBLIC_KEY_TABLE_TYPE,
+        S,
+        KVQStandardAdapter<
+            S,
+            L2UserIdKeyByPubicKeyIdCore<L2_USER_IDS_BY_PUBLIC_KEY_TABLE_TYPE>,
+            u64,
+        >,
+    >;
+
+    [L1ForcedWithdrawalKeyCore<L1_FORCED_WITHDRAWALS_TABLE_TYPE>]
+    pub type L1ForcedWithdrawalsStore<S> = L1ForcedWithdrawalsModel<
+        L1_FORCED_WITHDRAWALS_TABLE_TYPE,
+        S,
+        KVQStandardAdapter<
+            S,
+            L1ForcedWithdrawalKeyCore<L1_FORCED_WITHDRAWALS_TABLE_TYPE>,
+            CityForcedWithdrawalRequestStatus,
+        >,
+    >;
+
+    [L1IdsByOwnerKeyCore<L1_DEPOSIT_IDS_BY_PUBLIC_KEY_TABLE_TYPE, L1_DEPOSIT_OWNER_SIZE>]
+    pub type L1DepositIdsByPublicKeyStore<S> = L1IdsByOwnerModel<
+        L1_DEPOSIT_IDS_BY_PUBLIC_KEY_TABLE_TYPE,
+        L1_DEPOSIT_OWNER_SIZE,
+        S,
+        KVQStandardAdapter<
+            S,
+            L1IdsByOwnerKeyCore<L1_DEPOSIT_IDS_BY_PUBLIC_KEY_TABLE_TYPE, L1_DEPOSIT_OWNER_SIZE>,
+            u64,
+        >,
+    >;
+
+    [
+        L1IdsByOwnerKeyCore<
+            L1_WITHDRAWAL_IDS_BY_DESTINATION_TABLE_TYPE,
+            L1_WITHDRAWAL_DESTINATION_SIZE,
+        >,
+    ]
+    pub type L1WithdrawalIdsByDestinationStore<S> = L1IdsByOwnerModel<
+        L1_WITHDRAWAL_IDS_BY_DESTINATION_TABLE_TYPE,
+        L1_WITHDRAWAL_DESTINATION_SIZE,
+        S,
+        KVQStandardAdapter<
+            S,
+            L1IdsByOwnerKeyCore<
+                L1_WITHDRAWAL_IDS_BY_DESTINATION_TABLE_TYPE,
+                L1_WITHDRAWAL_DESTINATION_SIZE,
+            >,
+            u64,
+        >,
+    >;
+
+    // withdrawal ids are reused once the queue is empty, so the destination index is checked
+    // against the destination of each withdrawal id at the queried checkpoint
+    [L1OwnerByIdKeyCore<L1_WITHDRAWAL_DESTINATIONS_BY_ID_TABLE_TYPE>]
+    pub type L1WithdrawalDestinationsStore<S> = L1OwnerByIdModel<
+        L1_WITHDRAWAL_DESTINATIONS_BY_ID_TABLE_TYPE,
+        L1_WITHDRAWAL_DESTINATION_SIZE,
+        S,
+        KVQStandardAdapter<
+            S,
+            L1OwnerByIdKeyCore<L1_WITHDRAWAL_DESTINATIONS_BY_ID_TABLE_TYPE>,
+            [u8; L1_WITHDRAWAL_DESTINATION_SIZE],
+        >,
+    >;
+
+    // the withdrawal tree leaf is the hash of the withdrawal and its owner, so the preimage of
+    // each leaf is kept to prove withdrawals being processed or cancelled
+    [L1OwnerByIdKeyCore<L1_WITHDRAWAL_RECORDS_BY_ID_TABLE_TYPE>]
+    pub type L1WithdrawalRecordsStore<S> = L1OwnerByIdModel<
+        L1_WITHDRAWAL_RECORDS_BY_ID_TABLE_TYPE,
+        L1_WITHDRAWAL_RECORD_SIZE,
+        S,
+        KVQStandardAdapter<
+            S,
+            L1OwnerByIdKeyCore<L1_WITHDRAWAL_RECORDS_BY_ID_TABLE_TYPE>,
+            [u8; L1_WITHDRAWAL_RECORD_SIZE],
+        >,
+    >;
+
+    // the schema version record is read and written as raw bytes, so it has a key but no store
+    [SchemaVersionKeyCore<SCHEMA_VERSION_TABLE_TYPE>]
+    pub type SchemaVersionKey = SchemaVersionKeyCore<SCHEMA_VERSION_TABLE_TYPE>;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Debug;
+
+    use kvq::{
+        table::{check_table_collisions, KVQTable},
+        traits::KVQSerializable,
+    };
+
+    use super::*;
+
+    // the derived layouts must match the handwritten encodings of existing stores
+    fn check_key<K: KVQSerializable + KVQTable + Debug>(key: K, fields: &[&[u8]]) {
+        let mut expected = K::TABLE_ID.to_be_bytes().to_vec();
+        for field in fields {
+            expected.extend_from_slice(field);
+        }
+        let bytes = key.to_bytes().unwrap();
+        assert_eq!(bytes, expected, "{}", K::TABLE_NAME);
+        assert_eq!(bytes.len(), K::KEY_SIZE);
+        assert_eq!(K::from_bytes(&bytes).unwrap(), key);
+        assert!(K::from_bytes(&bytes[1..]).is_err());
+    }
+
+    #[test]
+    fn table_registry_has_no_collisions() {
+        check_table_collisions(CITY_STORE_TABLES).unwrap();
+    }
+
+    #[test]
+    fn table_keys_round_trip() {
+        let hash = CityHash::from_values(1, 2, 3, 4);
+        check_key(
+            KVQMerkleNodeKey::<TREE_TABLE_TYPE>::new(3, 4, 5, 6, 7, 8),
+            &[
+                &[3],
+                &4u64.to_be_bytes(),
+                &5u32.to_be_bytes(),
+                &[6],
+                &7u64.to_be_bytes(),
+                &8u64.to_be_bytes(),
+            ],
+        );
+        check_key(
+            L1DepositKeyByDepositIdCore::<L1_DEPOSITS_BY_ID_TABLE_TYPE>::new(9, 10),
+            &[&10u64.to_be_bytes(), &9u64.to_be_bytes()],
+        );
+        check_key(
+            L1DepositKeyByTransactionIdCore::<L1_DEPOSITS_BY_TXID_TABLE_TYPE>([7u8; 32]),
+            &[&[7u8; 32]],
+        );
+        check_key(
+            L2BlockStateKeyCore::<L2_BLOCK_STATE_TABLE_TYPE>(11),
+            &[&11u64.to_be_bytes()],
+        );
+        check_key(
+            L2UserIdKeyByPubicKeyIdCore::<L2_USER_IDS_BY_PUBLIC_KEY_TABLE_TYPE> {
+                public_key: hash,
+                user_id: 12,
+            },
+            &[&hash.to_le_bytes(), &12u64.to_be_bytes()],
+        );
+        check_key(
+            L1ForcedWithdrawalKeyCore::<L1_FORCED_WITHDRAWALS_TABLE_TYPE>::new(14, 15),
+            &[&14u64.to_be_bytes(), &15u64.to_be_bytes()],
+        );
+        check_key(
+            IndexedMerkleLeafKeyCore::<INDEXED_MERKLE_LEAVES_TABLE_TYPE> {
+                tree_id: USER_PUBLIC_KEY_TREE_ID,
+                index: 17,
+                checkpoint_id: 18,
+            },
+            &[
+                &[USER_PUBLIC_KEY_TREE_ID],
+                &17u64.to_be_bytes(),
+                &18u64.to_be_bytes(),
+            ],
+        );
+        let sorted_key = IndexedMerkleSortedKeyCore::<INDEXED_MERKLE_SORTED_KEYS_TABLE_TYPE>::new(
+            USER_PUBLIC_KEY_TREE_ID,
+            hash,
+        );
+        check_key(sorted_key, &[&[USER_PUBLIC_KEY_TREE_ID], &sorted_key.key]);
+        check_key(
+            L1IdsByOwnerKeyCore::<L1_DEPOSIT_IDS_BY_PUBLIC_KEY_TABLE_TYPE, L1_DEPOSIT_OWNER_SIZE> {
+                owner: [2u8; L1_DEPOSIT_OWNER_SIZE],
+                id: 19,
+                checkpoint_id: 20,
+            },
+            &[
+                &[2u8; L1_DEPOSIT_OWNER_SIZE],
+                &19u64.to_be_bytes(),
+                &20u64.to_be_bytes(),
+            ],
+        );
+        check_key(
+            L1OwnerByIdKeyCore::<L1_WITHDRAWAL_DESTINATIONS_BY_ID_TABLE_TYPE> {
+                id: 21,
+                checkpoint_id: 22,
+            },
+            &[&21u64.to_be_bytes(), &22u64.to_be_bytes()],
+        );
+        check_key(SchemaVersionKeyCore::<SCHEMA_VERSION_TABLE_TYPE>, &[]);
+        check_key(
+            L1OwnerByIdKeyCore::<L1_WITHDRAWAL_RECORDS_BY_ID_TABLE_TYPE> {
+                id: 25,
+                checkpoint_id: 26,
+            },
+            &[&25u64.to_be_bytes(), &26u64.to_be_bytes()],
+        );
+
+        let entry = IndexedMerkleKeyEntry {
+            index: 23,
+            checkpoint_id: 24,
+        };
+        let bytes = entry.to_bytes().unwrap();
+        assert_eq!(bytes, [23u64.to_be_bytes(), 24u64.to_be_bytes()].concat());
+        assert_eq!(IndexedMerkleKeyEntry::from_bytes(&bytes).unwrap(), entry);
+    }
+}
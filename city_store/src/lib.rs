@@ -1,3 +1,4 @@
 pub mod config;
+pub mod migrations;
 pub mod models;
 pub mod store;
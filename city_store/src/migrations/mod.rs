@@ -0,0 +1,374 @@
+use city_rollup_common::api::data::store::CityL2BlockState;
+use kvq::traits::{KVQBinaryStore, KVQBinaryStoreReader, KVQPair, KVQSerializable};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::{CITY_STORE_TABLES, L2_BLOCK_STATE_TABLE_TYPE},
+    store::city::base::CityStore,
+};
+
+pub mod v1;
+
+// the version of stores written before the schema version record was added
+pub const LEGACY_SCHEMA_VERSION: u32 = 0;
+// bump this and register a migration from the previous version whenever a key layout or value
+// encoding of a table changes
+pub const CITY_STORE_SCHEMA_VERSION: u32 = 1;
+
+const MIGRATION_PAGE_SIZE: usize = 1024;
+
+type KVQBinaryPair = KVQPair<Vec<u8>, Vec<u8>>;
+
+pub struct CityStoreMigration<S> {
+    pub from_version: u32,
+    pub description: &'static str,
+    // rewrites the records of from_version into the layout of from_version + 1 and returns the
+    // number of records written
+    pub migrate: fn(&mut S) -> anyhow::Result<usize>,
+    // checks the store after migrate, before from_version + 1 is recorded
+    pub verify: fn(&S) -> anyhow::Result<()>,
+}
+
+impl<S> CityStoreMigration<S> {
+    pub fn verify(&self, store: &S) -> anyhow::Result<()> {
+        (self.verify)(store).map_err(|err| {
+            anyhow::anyhow!(
+                "verifying migration from schema version {} ({}) failed: {}",
+                self.from_version,
+                self.description,
+                err
+            )
+        })
+    }
+}
+
+pub struct CityStoreMigrations<S> {
+    target_version: u32,
+    migrations: Vec<CityStoreMigration<S>>,
+}
+
+impl<S> CityStoreMigrations<S> {
+    pub fn new(target_version: u32) -> Self {
+        Self {
+            target_version,
+            migrations: Vec::new(),
+        }
+    }
+    pub fn register(mut self, migration: CityStoreMigration<S>) -> anyhow::Result<Self> {
+        if migration.from_version >= self.target_version {
+            anyhow::bail!(
+                "migration from schema version {} does not lead to target version {}",
+                migration.from_version,
+                self.target_version
+            );
+        }
+        if self
+            .migrations
+            .iter()
+            .any(|m| m.from_version == migration.from_version)
+        {
+            anyhow::bail!(
+                "a migration from schema version {} is already registered",
+                migration.from_version
+            );
+        }
+        self.migrations.push(migration);
+        Ok(self)
+    }
+    pub fn target_version(&self) -> u32 {
+        self.target_version
+    }
+    pub fn get(&self, from_version: u32) -> anyhow::Result<&CityStoreMigration<S>> {
+        self.migrations
+            .iter()
+            .find(|m| m.from_version == from_version)
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "no migration registered from schema version {}",
+                    from_version
+                )
+            })
+    }
+    // the migrations which upgrade a store at from_version to the target version, in order
+    pub fn plan(&self, from_version: u32) -> anyhow::Result<Vec<&CityStoreMigration<S>>> {
+        if from_version > self.target_version {
+            anyhow::bail!(
+                "store has schema version {} which is newer than the supported version {}",
+                from_version,
+                self.target_version
+            );
+        }
+        (from_version..self.target_version)
+            .map(|version| self.get(version))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CityMigrationStep {
+    pub from_version: u32,
+    pub description: String,
+    pub records: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CityMigrationReport {
+    pub from_version: u32,
+    pub to_version: u32,
+    pub steps: Vec<CityMigrationStep>,
+}
+
+pub fn table_prefix(table_type: u16) -> [u8; 2] {
+    table_type.to_be_bytes()
+}
+
+pub fn for_each_record<S: KVQBinaryStoreReader>(
+    store: &S,
+    prefix: &[u8],
+    mut f: impl FnMut(&KVQBinaryPair) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    let mut cursor = None;
+    loop {
+        let page = store.get_prefix_page(prefix, cursor.as_ref(), MIGRATION_PAGE_SIZE)?;
+        for pair in page.items.iter() {
+            f(pair)?;
+        }
+        match page.cursor {
+            Some(next) => cursor = Some(next),
+            None => return Ok(()),
+        }
+    }
+}
+
+// replaces the records under prefix for which f returns a new pair, deleting the old key if it
+// changed. new keys must not sort after the old key under the same prefix, or they are visited
+// again
+pub fn rewrite_records<S: KVQBinaryStore>(
+    store: &mut S,
+    prefix: &[u8],
+    mut f: impl FnMut(&KVQBinaryPair) -> anyhow::Result<Option<KVQBinaryPair>>,
+) -> anyhow::Result<usize> {
+    let mut records = 0;
+    let mut cursor = None;
+    loop {
+        let page = store.get_prefix_page(prefix, cursor.as_ref(), MIGRATION_PAGE_SIZE)?;
+        for pair in page.items.iter() {
+            if let Some(new_pair) = f(pair)? {
+                if new_pair.key != pair.key {
+                    store.delete(&pair.key)?;
+                }
+                store.set(new_pair.key, new_pair.value)?;
+                records += 1;
+            }
+        }
+        match page.cursor {
+            Some(next) => cursor = Some(next),
+            None => return Ok(records),
+        }
+    }
+}
+
+fn verify_v1_layout<S: KVQBinaryStoreReader>(store: &S) -> anyhow::Result<()> {
+    for_each_record(store, &[], |pair| {
+        let table_type = match pair.key.get(0..2) {
+            Some(prefix) => u16::from_be_bytes([prefix[0], prefix[1]]),
+            None => anyhow::bail!("record key {} has no table type", hex::encode(&pair.key)),
+        };
+        // version 1 is the current layout, so every record belongs to a registered table
+        if !CITY_STORE_TABLES.iter().any(|table| table.id == table_type) {
+            anyhow::bail!(
+                "record key {} has unknown table type {}",
+                hex::encode(&pair.key),
+                table_type
+            );
+        }
+        Ok(())
+    })?;
+    for_each_record(store, &table_prefix(L2_BLOCK_STATE_TABLE_TYPE), |pair| {
+        CityL2BlockState::from_bytes(&pair.value)?;
+        Ok(())
+    })
+}
+
+// the migrations from every released schema version to CITY_STORE_SCHEMA_VERSION
+pub fn city_store_migrations<S: KVQBinaryStore>() -> CityStoreMigrations<S> {
+    CityStoreMigrations::new(CITY_STORE_SCHEMA_VERSION)
+        .register(CityStoreMigration {
+            from_version: LEGACY_SCHEMA_VERSION,
+            description: "reserve user 0, commit withdrawal owners and backfill the indexes",
+            migrate: v1::migrate_v0_to_v1::<S>,
+            verify: v1::verify_v1::<S>,
+        })
+        .unwrap()
+}
+
+// verify_schema for read only stores, which the migrations can't be built for
+pub fn verify_city_store<S: KVQBinaryStoreReader>(store: &S) -> anyhow::Result<()> {
+    CityStore::check_schema_version(store)?;
+    v1::verify_v1(store)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use kvq::memory::simple::KVQSimpleMemoryBackingStore;
+    use kvq_store_redb::{KVQReDBTableDefinition, KVQReDBTableProvider};
+    use redb::{backends::InMemoryBackend, Database, TableDefinition};
+
+    use super::*;
+    use crate::models::l2_block_state::data::L2BlockStateKeyCore;
+
+    const KV: KVQReDBTableDefinition = TableDefinition::new("KV");
+
+    pub(super) fn fixture_db() -> KVQReDBTableProvider {
+        let db = Database::builder()
+            .create_with_backend(InMemoryBackend::new())
+            .unwrap();
+        KVQReDBTableProvider::new(Arc::new(db), KV)
+    }
+
+    fn block_state(checkpoint_id: u64) -> CityL2BlockState {
+        CityL2BlockState {
+            checkpoint_id,
+            next_deposit_id: checkpoint_id * 2,
+            next_user_id: checkpoint_id * 3,
+            end_balance: checkpoint_id * 1000,
+            ..Default::default()
+        }
+    }
+
+    // a synthetic version 0 layout which stored block states as json
+    fn write_json_block_states<S: KVQBinaryStore>(store: &mut S) -> anyhow::Result<()> {
+        for checkpoint_id in 0..5 {
+            let key = L2BlockStateKeyCore::<L2_BLOCK_STATE_TABLE_TYPE>(checkpoint_id).to_bytes()?;
+            store.set(key, serde_json::to_vec(&block_state(checkpoint_id))?)?;
+        }
+        Ok(())
+    }
+
+    fn json_block_state_migrations<S: KVQBinaryStore>(
+        verify: fn(&S) -> anyhow::Result<()>,
+    ) -> CityStoreMigrations<S> {
+        CityStoreMigrations::new(1)
+            .register(CityStoreMigration {
+                from_version: 0,
+                description: "encode block states as bytes",
+                migrate: |store| {
+                    rewrite_records(store, &table_prefix(L2_BLOCK_STATE_TABLE_TYPE), |pair| {
+                        let state: CityL2BlockState = serde_json::from_slice(&pair.value)?;
+                        Ok(Some(KVQPair {
+                            key: pair.key.clone(),
+                            value: state.to_bytes()?,
+                        }))
+                    })
+                },
+                verify,
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn migrate_synthetic_older_layout() {
+        let db = fixture_db();
+        db.write(|store| write_json_block_states(store)).unwrap();
+        db.read(|store| {
+            assert_eq!(CityStore::get_schema_version(&store)?, Some(0));
+            assert!(CityStore::get_latest_block_state(&store).is_err());
+            Ok(())
+        })
+        .unwrap();
+
+        let report = db
+            .write(|store| {
+                CityStore::migrate(store, &json_block_state_migrations(verify_v1_layout::<_>))
+            })
+            .unwrap();
+        assert_eq!(report.from_version, 0);
+        assert_eq!(report.to_version, 1);
+        assert_eq!(report.steps.len(), 1);
+        assert_eq!(report.steps[0].records, 5);
+
+        db.read(|store| {
+            assert_eq!(CityStore::get_schema_version(&store)?, Some(1));
+            CityStore::verify_schema(&store, &json_block_state_migrations(verify_v1_layout::<_>))?;
+            for checkpoint_id in 0..5 {
+                assert_eq!(
+                    CityStore::get_block_state(&store, checkpoint_id)?,
+                    block_state(checkpoint_id)
+                );
+            }
+            Ok(())
+        })
+        .unwrap();
+
+        // migrating an up to date store does nothing
+        let report = db
+            .write(|store| {
+                CityStore::migrate(store, &json_block_state_migrations(verify_v1_layout::<_>))
+            })
+            .unwrap();
+        assert!(report.steps.is_empty());
+    }
+
+    #[test]
+    fn failed_verify_leaves_store_untouched() {
+        let db = fixture_db();
+        db.write(|store| write_json_block_states(store)).unwrap();
+        let result = db.write(|store| {
+            CityStore::migrate(
+                store,
+                &json_block_state_migrations(|_| anyhow::bail!("bad block state")),
+            )
+        });
+        assert!(result.is_err());
+        db.read(|store| {
+            assert_eq!(CityStore::get_schema_version(&store)?, Some(0));
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn new_store_gets_current_version() {
+        let db = fixture_db();
+        db.write(|store| CityStore::ensure_schema_version(store))
+            .unwrap();
+        db.read(|store| {
+            assert_eq!(
+                CityStore::get_schema_version(&store)?,
+                Some(CITY_STORE_SCHEMA_VERSION)
+            );
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn unknown_versions_are_rejected() {
+        let migrations = city_store_migrations::<KVQSimpleMemoryBackingStore>();
+        assert!(migrations.plan(CITY_STORE_SCHEMA_VERSION + 1).is_err());
+        assert!(migrations
+            .plan(CITY_STORE_SCHEMA_VERSION)
+            .unwrap()
+            .is_empty());
+
+        let gap = CityStoreMigrations::<KVQSimpleMemoryBackingStore>::new(3)
+            .register(CityStoreMigration {
+                from_version: 0,
+                description: "noop",
+                migrate: |_| Ok(0),
+                verify: |_| Ok(()),
+            })
+            .unwrap();
+        assert!(gap.plan(0).is_err());
+        assert!(gap
+            .register(CityStoreMigration {
+                from_version: 0,
+                description: "duplicate",
+                migrate: |_| Ok(0),
+                verify: |_| Ok(()),
+            })
+            .is_err());
+    }
+}
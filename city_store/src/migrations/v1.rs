@@ -0,0 +1,381 @@
+use city_common::config::rollup_constants::TOKEN_STATE_USER_ID;
+use city_rollup_common::api::data::store::{CityL1Deposit, CityL1Withdrawal, CityL2BlockState};
+use kvq::traits::{KVQBinaryStore, KVQBinaryStoreReader, KVQSerializable};
+
+use super::{for_each_record, table_prefix, verify_v1_layout};
+use crate::{
+    config::{
+        CityHash, GlobalUserTreeStore, L1DepositIdsByPublicKeyStore, L1WithdrawalTreeStore,
+        L2UserIdsStore, UserPublicKeyTreeStore, L1_DEPOSITS_BY_ID_TABLE_TYPE,
+        L2_BLOCK_STATE_TABLE_TYPE,
+    },
+    models::{
+        indexed_merkle::model::IndexedMerkleTreeModelReaderCore,
+        kvq_merkle::model::{
+            KVQFixedConfigMerkleTreeModelCore, KVQFixedConfigMerkleTreeModelReaderCore,
+        },
+        l1_index::model::{L1IdsByOwnerModelCore, L1IdsByOwnerModelReaderCore},
+        user::model::L2UserIdsModelCore,
+    },
+    store::city::{base::CityStore, token::get_token_state_leaf},
+};
+
+// version 0 withdrawal leaves don't say who added them. there was no way to cancel a withdrawal
+// in version 0, so they are given to the token state user, which has no public key and can't sign
+// a cancellation, and they stay processable
+pub const LEGACY_WITHDRAWAL_OWNER_USER_ID: u64 = TOKEN_STATE_USER_ID;
+
+fn get_latest_block_state<S: KVQBinaryStoreReader>(
+    store: &S,
+) -> anyhow::Result<Option<CityL2BlockState>> {
+    if store
+        .get_prefix_page(&table_prefix(L2_BLOCK_STATE_TABLE_TYPE), None, 1)?
+        .items
+        .is_empty()
+    {
+        return Ok(None);
+    }
+    Ok(Some(CityStore::get_latest_block_state(store)?))
+}
+
+// version 0 registered users from id 0, so a user registered there is moved to the next free user
+// id before its leaves are given to the token state. users look up their new id by public key
+fn relocate_token_state_user<S: KVQBinaryStore>(
+    store: &mut S,
+    block_state: &mut CityL2BlockState,
+) -> anyhow::Result<usize> {
+    let checkpoint_id = block_state.checkpoint_id;
+    let leaf_id = TOKEN_STATE_USER_ID * 2;
+    let left = GlobalUserTreeStore::get_leaf_value_fc(store, checkpoint_id, leaf_id)?;
+    let public_key = GlobalUserTreeStore::get_leaf_value_fc(store, checkpoint_id, leaf_id + 1)?;
+    if left == CityHash::ZERO && public_key == CityHash::ZERO {
+        return Ok(0);
+    }
+    let user_id = block_state.next_user_id;
+    if user_id == TOKEN_STATE_USER_ID {
+        anyhow::bail!(
+            "user {} is registered but the next user id is {}",
+            TOKEN_STATE_USER_ID,
+            user_id
+        );
+    }
+    GlobalUserTreeStore::set_leaf_fc(store, checkpoint_id, user_id * 2, left)?;
+    GlobalUserTreeStore::set_leaf_fc(store, checkpoint_id, user_id * 2 + 1, public_key)?;
+    GlobalUserTreeStore::set_leaf_fc(store, checkpoint_id, leaf_id + 1, CityHash::ZERO)?;
+    if public_key != CityHash::ZERO {
+        L2UserIdsStore::delete_user_id_public_key_pair(store, TOKEN_STATE_USER_ID, public_key)?;
+        L2UserIdsStore::set_user_id_public_key_pair(store, user_id, public_key)?;
+    }
+    block_state.next_user_id += 1;
+    CityStore::set_block_state(store, block_state)?;
+    Ok(1)
+}
+
+// version 0 stores have no token state leaf, withdrawal leaves holding only the withdrawal hash,
+// no public key tree and no deposit or withdrawal indexes. the trees are rewritten at the latest
+// checkpoint only, earlier checkpoints keep the version 0 leaves and should be pruned
+pub fn migrate_v0_to_v1<S: KVQBinaryStore>(store: &mut S) -> anyhow::Result<usize> {
+    let mut block_state = match get_latest_block_state(store)? {
+        Some(block_state) => block_state,
+        None => anyhow::bail!("the store has records but no block state"),
+    };
+    let checkpoint_id = block_state.checkpoint_id;
+    let mut records = relocate_token_state_user(store, &mut block_state)?;
+    CityStore::init_token_state(store, checkpoint_id)?;
+    records += 1;
+
+    for withdrawal_id in block_state.next_process_withdrawal_id..block_state.next_add_withdrawal_id
+    {
+        let leaf = L1WithdrawalTreeStore::get_leaf_value_fc(store, checkpoint_id, withdrawal_id)?;
+        if leaf == CityHash::ZERO {
+            continue;
+        }
+        let withdrawal = CityL1Withdrawal::from_hash(withdrawal_id, leaf);
+        if CityHash::from(&withdrawal) != leaf {
+            anyhow::bail!(
+                "the leaf of withdrawal {} is not a withdrawal hash",
+                withdrawal_id
+            );
+        }
+        CityStore::set_withdrawal(
+            store,
+            checkpoint_id,
+            &withdrawal,
+            LEGACY_WITHDRAWAL_OWNER_USER_ID,
+        )?;
+        records += 1;
+    }
+
+    // added in user id order so the public key tree doesn't depend on the store's key order
+    for user_id in 0..block_state.next_user_id {
+        if user_id == TOKEN_STATE_USER_ID {
+            continue;
+        }
+        let public_key =
+            GlobalUserTreeStore::get_leaf_value_fc(store, checkpoint_id, user_id * 2 + 1)?;
+        if public_key != CityHash::ZERO
+            && UserPublicKeyTreeStore::<S>::get_key_index(store, checkpoint_id, public_key)?
+                .is_none()
+        {
+            CityStore::insert_user_public_key(store, checkpoint_id, public_key)?;
+            records += 1;
+        }
+    }
+
+    let mut deposits = Vec::new();
+    for_each_record(store, &table_prefix(L1_DEPOSITS_BY_ID_TABLE_TYPE), |pair| {
+        deposits.push(CityL1Deposit::from_bytes(&pair.value)?);
+        Ok(())
+    })?;
+    for deposit in deposits.iter() {
+        L1DepositIdsByPublicKeyStore::add_id_for_owner(
+            store,
+            deposit.checkpoint_id,
+            deposit.public_key.0,
+            deposit.deposit_id,
+        )?;
+    }
+    Ok(records + deposits.len())
+}
+
+// checks the state written at the latest checkpoint, stores without a block state have none
+pub fn verify_v1<S: KVQBinaryStoreReader>(store: &S) -> anyhow::Result<()> {
+    verify_v1_layout(store)?;
+    let block_state = match get_latest_block_state(store)? {
+        Some(block_state) => block_state,
+        None => return Ok(()),
+    };
+    let checkpoint_id = block_state.checkpoint_id;
+
+    let token_state_leaf = get_token_state_leaf(
+        CityStore::get_token_registry_root(store, checkpoint_id)?,
+        CityStore::get_token_balance_root(store, checkpoint_id)?,
+    );
+    let leaf_id = TOKEN_STATE_USER_ID * 2;
+    if GlobalUserTreeStore::get_leaf_value_fc(store, checkpoint_id, leaf_id)? != token_state_leaf {
+        anyhow::bail!(
+            "user {} does not hold the token state at checkpoint {}",
+            TOKEN_STATE_USER_ID,
+            checkpoint_id
+        );
+    }
+    if GlobalUserTreeStore::get_leaf_value_fc(store, checkpoint_id, leaf_id + 1)? != CityHash::ZERO
+    {
+        anyhow::bail!(
+            "user {} is registered at checkpoint {}",
+            TOKEN_STATE_USER_ID,
+            checkpoint_id
+        );
+    }
+
+    // fails for pending withdrawals without a record or with a record not matching their leaf
+    for withdrawal_id in block_state.next_process_withdrawal_id..block_state.next_add_withdrawal_id
+    {
+        CityStore::get_pending_withdrawal_with_owner(store, checkpoint_id, withdrawal_id)?;
+    }
+
+    for user_id in 0..block_state.next_user_id {
+        let public_key =
+            GlobalUserTreeStore::get_leaf_value_fc(store, checkpoint_id, user_id * 2 + 1)?;
+        if public_key != CityHash::ZERO
+            && UserPublicKeyTreeStore::<S>::get_key_index(store, checkpoint_id, public_key)?
+                .is_none()
+        {
+            anyhow::bail!(
+                "the public key of user {} is not in the public key tree",
+                user_id
+            );
+        }
+    }
+
+    for_each_record(store, &table_prefix(L1_DEPOSITS_BY_ID_TABLE_TYPE), |pair| {
+        let deposit = CityL1Deposit::from_bytes(&pair.value)?;
+        let deposit_ids = L1DepositIdsByPublicKeyStore::get_ids_for_owner(
+            store,
+            deposit.checkpoint_id,
+            deposit.public_key.0,
+        )?;
+        if !deposit_ids.contains(&deposit.deposit_id) {
+            anyhow::bail!(
+                "deposit {} is not indexed by its public key",
+                deposit.deposit_id
+            );
+        }
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use city_common::binaryhelpers::bytes::CompressedPublicKey;
+    use city_crypto::hash::base_types::{hash160::Hash160, hash256::Hash256};
+    use city_rollup_common::link::data::BTCAddress160;
+
+    use super::*;
+    use crate::{
+        config::L1DepositsStore,
+        migrations::{city_store_migrations, tests::fixture_db, verify_city_store},
+        models::l1_deposits::model::L1DepositsModelCore,
+    };
+
+    fn public_key(seed: u64) -> CityHash {
+        CityHash::from_values(seed, 2, 3, 4)
+    }
+
+    fn withdrawal(withdrawal_id: u64) -> CityL1Withdrawal {
+        let destination = BTCAddress160::new_p2pkh(Hash160([withdrawal_id as u8; 20]));
+        CityL1Withdrawal {
+            withdrawal_id,
+            address: destination.address,
+            address_type: destination.address_type.into(),
+            value: 1000 + withdrawal_id,
+        }
+    }
+
+    // writes a store the way version 0 did: users registered from id 0, withdrawal leaves holding
+    // the withdrawal hash and no token state, public key tree or l1 indexes
+    fn write_v0_store<S: KVQBinaryStore>(store: &mut S) -> anyhow::Result<()> {
+        CityStore::set_block_state(store, &CityL2BlockState::default())?;
+
+        // users 1 and 2 share a public key
+        for (user_id, seed) in [(0, 1), (1, 2), (2, 2)] {
+            L2UserIdsStore::set_user_id_public_key_pair(store, user_id, public_key(seed))?;
+            GlobalUserTreeStore::set_leaf_fc(store, 1, user_id * 2 + 1, public_key(seed))?;
+        }
+        CityStore::increment_user_balance(store, 1, 0, 500, None)?;
+        L1DepositsStore::set_deposit(
+            store,
+            CityL1Deposit {
+                deposit_id: 0,
+                checkpoint_id: 1,
+                value: 500,
+                txid: Hash256([1u8; 32]),
+                public_key: CompressedPublicKey([2u8; 33]),
+            },
+        )?;
+        CityStore::set_block_state(
+            store,
+            &CityL2BlockState {
+                checkpoint_id: 1,
+                next_deposit_id: 1,
+                next_user_id: 3,
+                ..Default::default()
+            },
+        )?;
+
+        // withdrawal 0 is processed at checkpoint 2, 1 and 2 are pending
+        for withdrawal_id in 0..3 {
+            L1WithdrawalTreeStore::set_leaf_fc(
+                store,
+                2,
+                withdrawal_id,
+                CityHash::from(&withdrawal(withdrawal_id)),
+            )?;
+        }
+        L1WithdrawalTreeStore::set_leaf_fc(store, 2, 0, CityHash::ZERO)?;
+        CityStore::set_block_state(
+            store,
+            &CityL2BlockState {
+                checkpoint_id: 2,
+                next_add_withdrawal_id: 3,
+                next_process_withdrawal_id: 1,
+                next_deposit_id: 1,
+                next_user_id: 3,
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn migrate_v0_store() {
+        let db = fixture_db();
+        db.write(|store| write_v0_store(store)).unwrap();
+        db.read(|store| {
+            assert!(verify_v1(&store).is_err());
+            Ok(())
+        })
+        .unwrap();
+
+        let report = db
+            .write(|store| CityStore::migrate(store, &city_store_migrations()))
+            .unwrap();
+        assert_eq!(report.steps.len(), 1);
+        // user 0, the token state, 2 withdrawals, 2 public keys and 1 deposit
+        assert_eq!(report.steps[0].records, 7);
+
+        db.read(|store| {
+            verify_city_store(&store)?;
+            assert_eq!(CityStore::get_latest_block_state(&store)?.next_user_id, 4);
+
+            // user 0 moved to the next free user id and its leaves hold the token state
+            assert_eq!(
+                CityStore::get_user_ids_for_public_key(&store, public_key(1))?,
+                vec![3]
+            );
+            let user = CityStore::get_user_by_id(&store, 2, 3)?;
+            assert_eq!((user.balance, user.public_key), (500, public_key(1)));
+            assert_eq!(
+                GlobalUserTreeStore::get_leaf_value_fc(&store, 2, 0)?,
+                get_token_state_leaf(
+                    CityStore::get_token_registry_root(&store, 2)?,
+                    CityStore::get_token_balance_root(&store, 2)?,
+                )
+            );
+            assert_eq!(
+                CityStore::get_user_by_id(&store, 2, 0)?.public_key,
+                CityHash::ZERO
+            );
+
+            assert_eq!(
+                CityStore::get_pending_withdrawal_with_owner(&store, 2, 0)?,
+                None
+            );
+            for withdrawal_id in 1..3 {
+                let withdrawal = withdrawal(withdrawal_id);
+                assert_eq!(
+                    CityStore::get_pending_withdrawal_with_owner(&store, 2, withdrawal_id)?,
+                    Some((withdrawal, LEGACY_WITHDRAWAL_OWNER_USER_ID))
+                );
+                assert_eq!(
+                    CityStore::get_withdrawal_ids_for_destination(
+                        &store,
+                        2,
+                        BTCAddress160::new_p2pkh(withdrawal.address)
+                    )?,
+                    vec![withdrawal_id]
+                );
+            }
+
+            for seed in 1..3 {
+                assert!(
+                    CityStore::get_public_key_membership_proof(&store, 2, public_key(seed)).is_ok()
+                );
+            }
+            assert!(
+                CityStore::get_public_key_non_membership_proof(&store, 2, public_key(3)).is_ok()
+            );
+            assert_eq!(
+                CityStore::get_deposit_ids_for_public_key(
+                    &store,
+                    2,
+                    CompressedPublicKey([2u8; 33])
+                )?,
+                vec![0]
+            );
+            Ok(())
+        })
+        .unwrap();
+
+        // the migrated store keeps working and migrating it again does nothing
+        db.write(|store| {
+            CityStore::register_user(store, 3, 4, public_key(3))?;
+            assert!(CityStore::register_user(store, 3, 0, public_key(4)).is_err());
+            Ok(())
+        })
+        .unwrap();
+        let report = db
+            .write(|store| CityStore::migrate(store, &city_store_migrations()))
+            .unwrap();
+        assert!(report.steps.is_empty());
+    }
+}
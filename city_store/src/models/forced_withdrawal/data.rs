@@ -0,0 +1,29 @@
+use kvq::traits::KVQSerializable;
+use serde::{Deserialize, Serialize};
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    KVQSerializable,
+)]
+#[kvq_table(id = TABLE_TYPE)]
+pub struct L1ForcedWithdrawalKeyCore<const TABLE_TYPE: u16> {
+    pub observed_checkpoint_id: u64,
+    pub index: u64,
+}
+impl<const TABLE_TYPE: u16> L1ForcedWithdrawalKeyCore<TABLE_TYPE> {
+    pub fn new(observed_checkpoint_id: u64, index: u64) -> Self {
+        Self {
+            observed_checkpoint_id,
+            index,
+        }
+    }
+}
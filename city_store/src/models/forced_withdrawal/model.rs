@@ -0,0 +1,87 @@
+use city_rollup_common::api::data::store::CityForcedWithdrawalRequestStatus;
+use kvq::traits::{KVQBinaryStore, KVQBinaryStoreReader, KVQStoreAdapter, KVQStoreAdapterReader};
+
+use super::data::L1ForcedWithdrawalKeyCore;
+
+pub trait L1ForcedWithdrawalsModelReaderCore<
+    const L1_FORCED_WITHDRAWALS_TABLE_TYPE: u16,
+    S: KVQBinaryStoreReader,
+    KVA: KVQStoreAdapterReader<
+        S,
+        L1ForcedWithdrawalKeyCore<L1_FORCED_WITHDRAWALS_TABLE_TYPE>,
+        CityForcedWithdrawalRequestStatus,
+    >,
+>
+{
+    fn get_forced_withdrawal_request(
+        store: &S,
+        observed_checkpoint_id: u64,
+        index: u64,
+    ) -> anyhow::Result<Option<CityForcedWithdrawalRequestStatus>> {
+        KVA::get_exact_if_exists(
+            store,
+            &L1ForcedWithdrawalKeyCore::new(observed_checkpoint_id, index),
+        )
+    }
+    // requests observed in the same block are stored with consecutive indexes starting at 0
+    fn get_forced_withdrawal_requests_for_checkpoint(
+        store: &S,
+        observed_checkpoint_id: u64,
+    ) -> anyhow::Result<Vec<CityForcedWithdrawalRequestStatus>> {
+        let mut result = Vec::new();
+        while let Some(status) =
+            Self::get_forced_withdrawal_request(store, observed_checkpoint_id, result.len() as u64)?
+        {
+            result.push(status);
+        }
+        Ok(result)
+    }
+}
+pub trait L1ForcedWithdrawalsModelCore<
+    const L1_FORCED_WITHDRAWALS_TABLE_TYPE: u16,
+    S: KVQBinaryStore,
+    KVA: KVQStoreAdapter<
+        S,
+        L1ForcedWithdrawalKeyCore<L1_FORCED_WITHDRAWALS_TABLE_TYPE>,
+        CityForcedWithdrawalRequestStatus,
+    >,
+>: L1ForcedWithdrawalsModelReaderCore<L1_FORCED_WITHDRAWALS_TABLE_TYPE, S, KVA>
+{
+    fn set_forced_withdrawal_request(
+        store: &mut S,
+        status: CityForcedWithdrawalRequestStatus,
+    ) -> anyhow::Result<()> {
+        let key = L1ForcedWithdrawalKeyCore::new(status.observed_checkpoint_id, status.index);
+        KVA::set(store, key, status)?;
+        Ok(())
+    }
+}
+pub struct L1ForcedWithdrawalsModel<const L1_FORCED_WITHDRAWALS_TABLE_TYPE: u16, S, KVA> {
+    _store: S,
+    _kva: KVA,
+}
+
+impl<
+        const L1_FORCED_WITHDRAWALS_TABLE_TYPE: u16,
+        S: KVQBinaryStoreReader,
+        KVA: KVQStoreAdapterReader<
+            S,
+            L1ForcedWithdrawalKeyCore<L1_FORCED_WITHDRAWALS_TABLE_TYPE>,
+            CityForcedWithdrawalRequestStatus,
+        >,
+    > L1ForcedWithdrawalsModelReaderCore<L1_FORCED_WITHDRAWALS_TABLE_TYPE, S, KVA>
+    for L1ForcedWithdrawalsModel<L1_FORCED_WITHDRAWALS_TABLE_TYPE, S, KVA>
+{
+}
+impl<
+        const L1_FORCED_WITHDRAWALS_TABLE_TYPE: u16,
+        S: KVQBinaryStore,
+        KVA: KVQStoreAdapter<
+            S,
+            L1ForcedWithdrawalKeyCore<L1_FORCED_WITHDRAWALS_TABLE_TYPE>,
+            CityForcedWithdrawalRequestStatus,
+        >,
+    > L1ForcedWithdrawalsModelCore<L1_FORCED_WITHDRAWALS_TABLE_TYPE, S, KVA>
+    for L1ForcedWithdrawalsModel<L1_FORCED_WITHDRAWALS_TABLE_TYPE, S, KVA>
+{
+}
@@ -0,0 +1,83 @@
+use kvq::traits::KVQSerializable;
+use plonky2::field::types::PrimeField64;
+use serde::{Deserialize, Serialize};
+
+use crate::config::CityHash;
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    KVQSerializable,
+)]
+#[kvq_table(id = TABLE_TYPE)]
+pub struct IndexedMerkleLeafKeyCore<const TABLE_TYPE: u16> {
+    pub tree_id: u8,
+    pub index: u64,
+    pub checkpoint_id: u64,
+}
+
+// the key is stored big endian with element 3 first, so the byte order of the table matches
+// compare_indexed_merkle_keys
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    KVQSerializable,
+)]
+#[kvq_table(id = TABLE_TYPE)]
+pub struct IndexedMerkleSortedKeyCore<const TABLE_TYPE: u16> {
+    pub tree_id: u8,
+    pub key: [u8; 32],
+}
+
+impl<const TABLE_TYPE: u16> IndexedMerkleSortedKeyCore<TABLE_TYPE> {
+    pub fn new(tree_id: u8, key: CityHash) -> Self {
+        let mut bytes = [0u8; 32];
+        for i in 0..4 {
+            bytes[i * 8..(i + 1) * 8]
+                .copy_from_slice(&key.0.elements[3 - i].to_canonical_u64().to_be_bytes());
+        }
+        IndexedMerkleSortedKeyCore {
+            tree_id,
+            key: bytes,
+        }
+    }
+    // the largest key which sorts before this one, None for the zero key
+    pub fn predecessor(&self) -> Option<Self> {
+        let mut key = self.key;
+        for byte in key.iter_mut().rev() {
+            if *byte == 0 {
+                *byte = 0xff;
+            } else {
+                *byte -= 1;
+                return Some(IndexedMerkleSortedKeyCore {
+                    tree_id: self.tree_id,
+                    key,
+                });
+            }
+        }
+        None
+    }
+}
+
+// the leaf index of a key and the checkpoint it was inserted at
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, KVQSerializable)]
+pub struct IndexedMerkleKeyEntry {
+    pub index: u64,
+    pub checkpoint_id: u64,
+}
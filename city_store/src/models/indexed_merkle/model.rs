@@ -0,0 +1,484 @@
+use std::marker::PhantomData;
+
+use city_crypto::hash::merkle::indexed::{
+    IndexedMerkleInsertProof, IndexedMerkleLeaf, IndexedMerkleMembershipProof,
+    IndexedMerkleNonMembershipProof,
+};
+use kvq::traits::{KVQBinaryStore, KVQBinaryStoreReader, KVQStoreAdapter, KVQStoreAdapterReader};
+
+use super::data::{IndexedMerkleKeyEntry, IndexedMerkleLeafKeyCore, IndexedMerkleSortedKeyCore};
+use crate::{
+    config::{CityHash, CityHasher, F, TREE_TABLE_TYPE},
+    models::kvq_merkle::{
+        key::KVQMerkleNodeKey,
+        model::{
+            KVQFixedConfigMerkleTreeModelCore, KVQFixedConfigMerkleTreeModelReaderCore,
+            CHECKPOINT_ID_FUZZY_SIZE, PRUNE_PAGE_SIZE,
+        },
+    },
+};
+
+// the leaf index and the checkpoint id of a leaf key
+const LEAF_INDEX_FUZZY_SIZE: usize = 16;
+const SORTED_KEY_FUZZY_SIZE: usize = 32;
+
+/// An indexed merkle tree, the tree itself is stored by `TREE` and the leaf preimages and the
+/// sorted keys are kept in two additional tables to find low leaves.
+///
+/// Keys can only be inserted at or after the latest checkpoint which modified the tree.
+pub trait IndexedMerkleTreeModelReaderCore<
+    const TREE_ID: u8,
+    const TREE_HEIGHT: u8,
+    const LEAF_TABLE_TYPE: u16,
+    const SORTED_KEY_TABLE_TYPE: u16,
+    S: KVQBinaryStoreReader,
+    LEAFKVA: KVQStoreAdapterReader<S, IndexedMerkleLeafKeyCore<LEAF_TABLE_TYPE>, IndexedMerkleLeaf<F>>,
+    KEYKVA: KVQStoreAdapterReader<
+        S,
+        IndexedMerkleSortedKeyCore<SORTED_KEY_TABLE_TYPE>,
+        IndexedMerkleKeyEntry,
+    >,
+    TREEKVA: KVQStoreAdapterReader<S, KVQMerkleNodeKey<TREE_TABLE_TYPE>, CityHash>,
+    TREE: KVQFixedConfigMerkleTreeModelReaderCore<
+        TREE_ID,
+        TREE_HEIGHT,
+        0,
+        0,
+        TREE_TABLE_TYPE,
+        false,
+        S,
+        TREEKVA,
+        CityHash,
+        CityHasher,
+    >,
+>
+{
+    fn get_root(store: &S, checkpoint_id: u64) -> anyhow::Result<CityHash> {
+        TREE::get_root_fc(store, checkpoint_id)
+    }
+    fn get_leaf_preimage(
+        store: &S,
+        checkpoint_id: u64,
+        index: u64,
+    ) -> anyhow::Result<Option<IndexedMerkleLeaf<F>>> {
+        LEAFKVA::get_leq(
+            store,
+            &IndexedMerkleLeafKeyCore {
+                tree_id: TREE_ID,
+                index,
+                checkpoint_id,
+            },
+            CHECKPOINT_ID_FUZZY_SIZE,
+        )
+    }
+    // the leaf preimages at checkpoint_id in index order, starting with the sentinel. leaves are
+    // only appended, so the first missing index is the end of the tree
+    fn get_leaves(store: &S, checkpoint_id: u64) -> anyhow::Result<Vec<IndexedMerkleLeaf<F>>> {
+        let mut leaves = Vec::new();
+        while let Some(leaf) = Self::get_leaf_preimage(store, checkpoint_id, leaves.len() as u64)? {
+            leaves.push(leaf);
+        }
+        Ok(leaves)
+    }
+    // the key of the leaf with the highest index, None if the sentinel leaf has not been written
+    fn get_last_leaf_key(
+        store: &S,
+    ) -> anyhow::Result<Option<IndexedMerkleLeafKeyCore<LEAF_TABLE_TYPE>>> {
+        Ok(LEAFKVA::get_leq_kv(
+            store,
+            &IndexedMerkleLeafKeyCore {
+                tree_id: TREE_ID,
+                index: u64::MAX,
+                checkpoint_id: u64::MAX,
+            },
+            LEAF_INDEX_FUZZY_SIZE,
+        )?
+        .map(|kv| kv.key))
+    }
+    fn get_key_index(store: &S, checkpoint_id: u64, key: CityHash) -> anyhow::Result<Option<u64>> {
+        Ok(
+            KEYKVA::get_exact_if_exists(store, &IndexedMerkleSortedKeyCore::new(TREE_ID, key))?
+                .filter(|entry| entry.checkpoint_id <= checkpoint_id)
+                .map(|entry| entry.index),
+        )
+    }
+    // the index of the leaf with the largest key smaller than key, keys inserted after
+    // checkpoint_id are skipped
+    fn get_low_leaf_index(store: &S, checkpoint_id: u64, key: CityHash) -> anyhow::Result<u64> {
+        let mut cursor = IndexedMerkleSortedKeyCore::new(TREE_ID, key).predecessor();
+        while let Some(current) = cursor {
+            match KEYKVA::get_leq_kv(store, &current, SORTED_KEY_FUZZY_SIZE)? {
+                Some(kv) if kv.value.checkpoint_id <= checkpoint_id => return Ok(kv.value.index),
+                Some(kv) => cursor = kv.key.predecessor(),
+                None => break,
+            }
+        }
+        // the sentinel
+        Ok(0)
+    }
+    fn get_membership_proof(
+        store: &S,
+        checkpoint_id: u64,
+        key: CityHash,
+    ) -> anyhow::Result<IndexedMerkleMembershipProof<F>> {
+        let index = Self::get_key_index(store, checkpoint_id, key)?.ok_or_else(|| {
+            anyhow::anyhow!(
+                "key {} is not in indexed merkle tree {} at checkpoint {}",
+                key,
+                TREE_ID,
+                checkpoint_id
+            )
+        })?;
+        let leaf = Self::get_leaf_preimage(store, checkpoint_id, index)?
+            .ok_or_else(|| anyhow::anyhow!("missing preimage of indexed merkle leaf {}", index))?;
+        Ok(IndexedMerkleMembershipProof {
+            leaf,
+            proof: TREE::get_leaf_fc(store, checkpoint_id, index)?,
+        })
+    }
+    fn get_non_membership_proof(
+        store: &S,
+        checkpoint_id: u64,
+        key: CityHash,
+    ) -> anyhow::Result<IndexedMerkleNonMembershipProof<F>> {
+        if key == CityHash::ZERO {
+            anyhow::bail!("the zero key is reserved for the sentinel leaf");
+        }
+        if Self::get_key_index(store, checkpoint_id, key)?.is_some() {
+            anyhow::bail!(
+                "key {} is in indexed merkle tree {} at checkpoint {}",
+                key,
+                TREE_ID,
+                checkpoint_id
+            );
+        }
+        let index = Self::get_low_leaf_index(store, checkpoint_id, key)?;
+        let low_leaf = Self::get_leaf_preimage(store, checkpoint_id, index)?.ok_or_else(|| {
+            anyhow::anyhow!(
+                "indexed merkle tree {} is empty at checkpoint {}",
+                TREE_ID,
+                checkpoint_id
+            )
+        })?;
+        Ok(IndexedMerkleNonMembershipProof {
+            key,
+            low_leaf,
+            low_leaf_proof: TREE::get_leaf_fc(store, checkpoint_id, index)?,
+        })
+    }
+}
+
+pub trait IndexedMerkleTreeModelCore<
+    const TREE_ID: u8,
+    const TREE_HEIGHT: u8,
+    const LEAF_TABLE_TYPE: u16,
+    const SORTED_KEY_TABLE_TYPE: u16,
+    S: KVQBinaryStore,
+    LEAFKVA: KVQStoreAdapter<S, IndexedMerkleLeafKeyCore<LEAF_TABLE_TYPE>, IndexedMerkleLeaf<F>>,
+    KEYKVA: KVQStoreAdapter<S, IndexedMerkleSortedKeyCore<SORTED_KEY_TABLE_TYPE>, IndexedMerkleKeyEntry>,
+    TREEKVA: KVQStoreAdapter<S, KVQMerkleNodeKey<TREE_TABLE_TYPE>, CityHash>,
+    TREE: KVQFixedConfigMerkleTreeModelCore<
+        TREE_ID,
+        TREE_HEIGHT,
+        0,
+        0,
+        TREE_TABLE_TYPE,
+        false,
+        S,
+        TREEKVA,
+        CityHash,
+        CityHasher,
+    >,
+>:
+    IndexedMerkleTreeModelReaderCore<
+    TREE_ID,
+    TREE_HEIGHT,
+    LEAF_TABLE_TYPE,
+    SORTED_KEY_TABLE_TYPE,
+    S,
+    LEAFKVA,
+    KEYKVA,
+    TREEKVA,
+    TREE,
+>
+{
+    fn set_leaf_preimage(
+        store: &mut S,
+        checkpoint_id: u64,
+        index: u64,
+        leaf: &IndexedMerkleLeaf<F>,
+    ) -> anyhow::Result<()> {
+        LEAFKVA::set_ref(
+            store,
+            &IndexedMerkleLeafKeyCore {
+                tree_id: TREE_ID,
+                index,
+                checkpoint_id,
+            },
+            leaf,
+        )
+    }
+    fn insert(
+        store: &mut S,
+        checkpoint_id: u64,
+        key: CityHash,
+    ) -> anyhow::Result<IndexedMerkleInsertProof<F>> {
+        if key == CityHash::ZERO {
+            anyhow::bail!("the zero key is reserved for the sentinel leaf");
+        }
+        let new_index = match Self::get_last_leaf_key(store)? {
+            Some(last) if last.checkpoint_id > checkpoint_id => anyhow::bail!(
+                "cannot insert into indexed merkle tree {} at checkpoint {}, it was already modified at checkpoint {}",
+                TREE_ID,
+                checkpoint_id,
+                last.checkpoint_id
+            ),
+            Some(last) => last.index + 1,
+            // the first insert also writes the sentinel leaf, the insert proof starts from the tree
+            // which only holds the sentinel
+            None => {
+                let sentinel = IndexedMerkleLeaf::sentinel();
+                Self::set_leaf_preimage(store, checkpoint_id, 0, &sentinel)?;
+                TREE::set_leaf_fc(store, checkpoint_id, 0, sentinel.hash::<CityHasher>())?;
+                1
+            }
+        };
+        if TREE_HEIGHT < 64 && new_index >= (1u64 << TREE_HEIGHT) {
+            anyhow::bail!("indexed merkle tree {} is full", TREE_ID);
+        }
+        if Self::get_key_index(store, checkpoint_id, key)?.is_some() {
+            anyhow::bail!("key {} is already in indexed merkle tree {}", key, TREE_ID);
+        }
+
+        let low_index = Self::get_low_leaf_index(store, checkpoint_id, key)?;
+        let low_leaf =
+            Self::get_leaf_preimage(store, checkpoint_id, low_index)?.ok_or_else(|| {
+                anyhow::anyhow!("missing preimage of indexed merkle leaf {}", low_index)
+            })?;
+        let updated_low_leaf = IndexedMerkleLeaf {
+            key: low_leaf.key,
+            next_key: key,
+            next_index: new_index,
+        };
+        let new_leaf = IndexedMerkleLeaf {
+            key,
+            next_key: low_leaf.next_key,
+            next_index: low_leaf.next_index,
+        };
+
+        let low_leaf_delta = TREE::set_leaf_fc(
+            store,
+            checkpoint_id,
+            low_index,
+            updated_low_leaf.hash::<CityHasher>(),
+        )?;
+        let new_leaf_delta = TREE::set_leaf_fc(
+            store,
+            checkpoint_id,
+            new_index,
+            new_leaf.hash::<CityHasher>(),
+        )?;
+        Self::set_leaf_preimage(store, checkpoint_id, low_index, &updated_low_leaf)?;
+        Self::set_leaf_preimage(store, checkpoint_id, new_index, &new_leaf)?;
+        KEYKVA::set(
+            store,
+            IndexedMerkleSortedKeyCore::new(TREE_ID, key),
+            IndexedMerkleKeyEntry {
+                index: new_index,
+                checkpoint_id,
+            },
+        )?;
+
+        Ok(IndexedMerkleInsertProof {
+            key,
+            low_leaf,
+            low_leaf_delta,
+            new_leaf_delta,
+        })
+    }
+    // writes leaves read with get_leaves into an empty tree, every key is recorded as inserted at
+    // checkpoint_id
+    fn set_leaves(
+        store: &mut S,
+        checkpoint_id: u64,
+        leaves: &[IndexedMerkleLeaf<F>],
+    ) -> anyhow::Result<()> {
+        if Self::get_last_leaf_key(store)?.is_some() {
+            anyhow::bail!("indexed merkle tree {} is not empty", TREE_ID);
+        }
+        for (index, leaf) in leaves.iter().enumerate() {
+            let index = index as u64;
+            Self::set_leaf_preimage(store, checkpoint_id, index, leaf)?;
+            TREE::set_leaf_fc(store, checkpoint_id, index, leaf.hash::<CityHasher>())?;
+            // the sentinel's zero key is not in the sorted keys
+            if index != 0 {
+                KEYKVA::set(
+                    store,
+                    IndexedMerkleSortedKeyCore::new(TREE_ID, leaf.key),
+                    IndexedMerkleKeyEntry {
+                        index,
+                        checkpoint_id,
+                    },
+                )?;
+            }
+        }
+        Ok(())
+    }
+    // deletes the tree nodes and leaf preimages which are shadowed at min_checkpoint_id by a newer
+    // version, a key has a single sorted key entry so those are kept
+    fn prune_checkpoints_before(store: &mut S, min_checkpoint_id: u64) -> anyhow::Result<usize> {
+        let mut deleted = TREE::prune_checkpoints_before_fc(store, min_checkpoint_id)?;
+        let leaf_key = |index, checkpoint_id| IndexedMerkleLeafKeyCore::<LEAF_TABLE_TYPE> {
+            tree_id: TREE_ID,
+            index,
+            checkpoint_id,
+        };
+        let (start, end) = (leaf_key(0, 0), leaf_key(u64::MAX, u64::MAX));
+        let mut previous: Option<IndexedMerkleLeafKeyCore<LEAF_TABLE_TYPE>> = None;
+        let mut cursor = None;
+        loop {
+            let page =
+                LEAFKVA::get_range_page(store, &start, &end, cursor.as_ref(), PRUNE_PAGE_SIZE)?;
+            let mut stale_keys = Vec::new();
+            for kv in page.items.iter() {
+                if let Some(previous) = previous {
+                    if previous.checkpoint_id < min_checkpoint_id
+                        && kv.key.index == previous.index
+                        && kv.key.checkpoint_id <= min_checkpoint_id
+                    {
+                        stale_keys.push(previous);
+                    }
+                }
+                previous = Some(kv.key);
+            }
+            if !stale_keys.is_empty() {
+                LEAFKVA::delete_many(store, &stale_keys)?;
+                deleted += stale_keys.len();
+            }
+            match page.cursor {
+                Some(next) => cursor = Some(next),
+                None => return Ok(deleted),
+            }
+        }
+    }
+}
+
+pub struct IndexedMerkleTreeModel<
+    const TREE_ID: u8,
+    const TREE_HEIGHT: u8,
+    const LEAF_TABLE_TYPE: u16,
+    const SORTED_KEY_TABLE_TYPE: u16,
+    S,
+    LEAFKVA,
+    KEYKVA,
+    TREEKVA,
+    TREE,
+> {
+    _s: PhantomData<S>,
+    _leafkva: PhantomData<LEAFKVA>,
+    _keykva: PhantomData<KEYKVA>,
+    _treekva: PhantomData<TREEKVA>,
+    _tree: PhantomData<TREE>,
+}
+
+impl<
+        const TREE_ID: u8,
+        const TREE_HEIGHT: u8,
+        const LEAF_TABLE_TYPE: u16,
+        const SORTED_KEY_TABLE_TYPE: u16,
+        S: KVQBinaryStoreReader,
+        LEAFKVA: KVQStoreAdapterReader<S, IndexedMerkleLeafKeyCore<LEAF_TABLE_TYPE>, IndexedMerkleLeaf<F>>,
+        KEYKVA: KVQStoreAdapterReader<
+            S,
+            IndexedMerkleSortedKeyCore<SORTED_KEY_TABLE_TYPE>,
+            IndexedMerkleKeyEntry,
+        >,
+        TREEKVA: KVQStoreAdapterReader<S, KVQMerkleNodeKey<TREE_TABLE_TYPE>, CityHash>,
+        TREE: KVQFixedConfigMerkleTreeModelReaderCore<
+            TREE_ID,
+            TREE_HEIGHT,
+            0,
+            0,
+            TREE_TABLE_TYPE,
+            false,
+            S,
+            TREEKVA,
+            CityHash,
+            CityHasher,
+        >,
+    >
+    IndexedMerkleTreeModelReaderCore<
+        TREE_ID,
+        TREE_HEIGHT,
+        LEAF_TABLE_TYPE,
+        SORTED_KEY_TABLE_TYPE,
+        S,
+        LEAFKVA,
+        KEYKVA,
+        TREEKVA,
+        TREE,
+    >
+    for IndexedMerkleTreeModel<
+        TREE_ID,
+        TREE_HEIGHT,
+        LEAF_TABLE_TYPE,
+        SORTED_KEY_TABLE_TYPE,
+        S,
+        LEAFKVA,
+        KEYKVA,
+        TREEKVA,
+        TREE,
+    >
+{
+}
+
+impl<
+        const TREE_ID: u8,
+        const TREE_HEIGHT: u8,
+        const LEAF_TABLE_TYPE: u16,
+        const SORTED_KEY_TABLE_TYPE: u16,
+        S: KVQBinaryStore,
+        LEAFKVA: KVQStoreAdapter<S, IndexedMerkleLeafKeyCore<LEAF_TABLE_TYPE>, IndexedMerkleLeaf<F>>,
+        KEYKVA: KVQStoreAdapter<
+            S,
+            IndexedMerkleSortedKeyCore<SORTED_KEY_TABLE_TYPE>,
+            IndexedMerkleKeyEntry,
+        >,
+        TREEKVA: KVQStoreAdapter<S, KVQMerkleNodeKey<TREE_TABLE_TYPE>, CityHash>,
+        TREE: KVQFixedConfigMerkleTreeModelCore<
+            TREE_ID,
+            TREE_HEIGHT,
+            0,
+            0,
+            TREE_TABLE_TYPE,
+            false,
+            S,
+            TREEKVA,
+            CityHash,
+            CityHasher,
+        >,
+    >
+    IndexedMerkleTreeModelCore<
+        TREE_ID,
+        TREE_HEIGHT,
+        LEAF_TABLE_TYPE,
+        SORTED_KEY_TABLE_TYPE,
+        S,
+        LEAFKVA,
+        KEYKVA,
+        TREEKVA,
+        TREE,
+    >
+    for IndexedMerkleTreeModel<
+        TREE_ID,
+        TREE_HEIGHT,
+        LEAF_TABLE_TYPE,
+        SORTED_KEY_TABLE_TYPE,
+        S,
+        LEAFKVA,
+        KEYKVA,
+        TREEKVA,
+        TREE,
+    >
+{
+}
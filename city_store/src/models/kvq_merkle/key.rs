@@ -55,7 +55,8 @@ impl KVQTreeIdentifier {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, KVQSerializable)]
+#[kvq_table(id = TABLE_TYPE)]
 pub struct KVQMerkleNodeKey<const TABLE_TYPE: u16> {
     pub tree_id: u8,
     pub primary_id: u64,
@@ -121,31 +122,6 @@ impl<const TABLE_TYPE: u16> KVQMerkleNodeKey<TABLE_TYPE> {
         }
     }
 }
-impl<const TABLE_TYPE: u16> KVQSerializable for KVQMerkleNodeKey<TABLE_TYPE> {
-    fn to_bytes(&self) -> anyhow::Result<Vec<u8>> {
-        let mut result: Vec<u8> = Vec::with_capacity(32);
-        result.push(((TABLE_TYPE & 0xFF00) >> 8) as u8); // 1
-        result.push((TABLE_TYPE & 0xFF) as u8); // 2
-        result.push(self.tree_id); // 3
-        result.extend_from_slice(&self.primary_id.to_be_bytes()); // 11
-        result.extend_from_slice(&self.secondary_id.to_be_bytes()); // 15
-        result.push(self.level); // 16
-        result.extend_from_slice(&self.index.to_be_bytes()); // 24
-        result.extend_from_slice(&self.checkpoint_id.to_be_bytes()); // 32
-        Ok(result)
-    }
-
-    fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
-        Ok(Self {
-            tree_id: bytes[2],
-            primary_id: u64::from_be_bytes(bytes[3..11].try_into().unwrap()),
-            secondary_id: u32::from_be_bytes(bytes[11..15].try_into().unwrap()),
-            level: bytes[15],
-            index: u64::from_be_bytes(bytes[16..24].try_into().unwrap()),
-            checkpoint_id: u64::from_be_bytes(bytes[24..32].try_into().unwrap()),
-        })
-    }
-}
 impl<const TABLE_TYPE: u16> KVQMerkleNodeKey<TABLE_TYPE> {
     pub fn new(
         tree_id: u8,
@@ -1,6 +1,9 @@
 use super::key::KVQMerkleNodeKey;
+use super::key::KVQTreeIdentifier;
 use city_crypto::hash::merkle::core::DeltaMerkleProofCore;
 use city_crypto::hash::merkle::core::MerkleProofCore;
+use city_crypto::hash::merkle::multi_proof::get_merkle_multi_proof_sibling_positions;
+use city_crypto::hash::merkle::multi_proof::MerkleMultiProofCore;
 use city_crypto::hash::traits::hasher::MerkleZeroHasherWithMarkedLeaf;
 use kvq::traits::KVQBinaryStore;
 use kvq::traits::KVQBinaryStoreReader;
@@ -11,6 +14,7 @@ use kvq::traits::KVQStoreAdapterReader;
 use std::marker::PhantomData;
 
 pub const CHECKPOINT_ID_FUZZY_SIZE: usize = 8;
+pub const PRUNE_PAGE_SIZE: usize = 1024;
 
 pub trait KVQMerkleTreeModelReaderCore<
     const TABLE_TYPE: u16,
@@ -89,6 +93,81 @@ pub trait KVQMerkleTreeModelReaderCore<
             index: key.index,
         })
     }
+    // returns the value of every leaf which has been written at or before checkpoint_id
+    fn get_leaves_at_checkpoint(
+        store: &S,
+        identifier: &KVQTreeIdentifier,
+        tree_height: u8,
+        checkpoint_id: u64,
+    ) -> anyhow::Result<Vec<KVQPair<u64, Hash>>> {
+        let leaf_level_end_key = KVQMerkleNodeKey::<TABLE_TYPE> {
+            tree_id: identifier.tree_id,
+            primary_id: identifier.primary_id,
+            secondary_id: identifier.secondary_id,
+            level: tree_height,
+            index: u64::MAX,
+            checkpoint_id: u64::MAX,
+        };
+        let versions =
+            KVA::get_fuzzy_range_leq_kv(store, &leaf_level_end_key, CHECKPOINT_ID_FUZZY_SIZE + 8)?;
+        let mut leaves: Vec<KVQPair<u64, Hash>> = Vec::new();
+        // versions are sorted by (index, checkpoint_id), so the last visible version wins
+        for version in versions
+            .into_iter()
+            .filter(|v| v.key.checkpoint_id <= checkpoint_id)
+        {
+            match leaves.last_mut() {
+                Some(last) if last.key == version.key.index => last.value = version.value,
+                _ => leaves.push(KVQPair {
+                    key: version.key.index,
+                    value: version.value,
+                }),
+            }
+        }
+        Ok(leaves)
+    }
+    fn get_multi_proof(
+        store: &S,
+        identifier: &KVQTreeIdentifier,
+        tree_height: u8,
+        checkpoint_id: u64,
+        indices: &[u64],
+    ) -> anyhow::Result<MerkleMultiProofCore<Hash>> {
+        let mut indices = indices.to_vec();
+        indices.sort_unstable();
+        indices.dedup();
+        if indices.is_empty() {
+            anyhow::bail!("a multi proof needs at least one leaf");
+        }
+        let new_key = |level: u8, index: u64| KVQMerkleNodeKey::<TABLE_TYPE> {
+            tree_id: identifier.tree_id,
+            primary_id: identifier.primary_id,
+            secondary_id: identifier.secondary_id,
+            level,
+            index,
+            checkpoint_id,
+        };
+        // leaves, then siblings, then the root
+        let keys = indices
+            .iter()
+            .map(|index| new_key(tree_height, *index))
+            .chain(
+                get_merkle_multi_proof_sibling_positions(tree_height, &indices)
+                    .into_iter()
+                    .map(|position| new_key(position.level, position.index)),
+            )
+            .chain(std::iter::once(new_key(0, 0)))
+            .collect::<Vec<_>>();
+        let nodes = Self::get_nodes(store, tree_height as usize, &keys)?;
+        let root_ind = nodes.len() - 1;
+        Ok(MerkleMultiProofCore {
+            root: nodes[root_ind],
+            height: tree_height,
+            values: nodes[0..indices.len()].to_vec(),
+            siblings: nodes[indices.len()..root_ind].to_vec(),
+            indices,
+        })
+    }
 }
 pub trait KVQMerkleTreeModelCore<
     const TABLE_TYPE: u16,
@@ -190,6 +269,56 @@ pub trait KVQMerkleTreeModelCore<
             index: key.index,
         })
     }
+    // deletes every node version which is shadowed at min_checkpoint_id by a newer version of the
+    // same node, so get_leq lookups for checkpoints >= min_checkpoint_id are unaffected
+    fn prune_checkpoints_before(
+        store: &mut S,
+        identifier: &KVQTreeIdentifier,
+        tree_height: u8,
+        min_checkpoint_id: u64,
+    ) -> anyhow::Result<usize> {
+        let mut deleted = 0;
+        for level in 0..=tree_height {
+            let level_key = |index, checkpoint_id| KVQMerkleNodeKey::<TABLE_TYPE> {
+                tree_id: identifier.tree_id,
+                primary_id: identifier.primary_id,
+                secondary_id: identifier.secondary_id,
+                level,
+                index,
+                checkpoint_id,
+            };
+            let (start, end) = (level_key(0, 0), level_key(u64::MAX, u64::MAX));
+            // walk every version of every node on this level in (index, checkpoint_id) order, one
+            // page at a time
+            let mut previous: Option<KVQMerkleNodeKey<TABLE_TYPE>> = None;
+            let mut cursor = None;
+            loop {
+                let page =
+                    KVA::get_range_page(store, &start, &end, cursor.as_ref(), PRUNE_PAGE_SIZE)?;
+                let mut stale_keys = Vec::new();
+                for kv in page.items.iter() {
+                    if let Some(previous) = previous {
+                        if previous.checkpoint_id < min_checkpoint_id
+                            && kv.key.index == previous.index
+                            && kv.key.checkpoint_id <= min_checkpoint_id
+                        {
+                            stale_keys.push(previous);
+                        }
+                    }
+                    previous = Some(kv.key);
+                }
+                if !stale_keys.is_empty() {
+                    KVA::delete_many(store, &stale_keys)?;
+                    deleted += stale_keys.len();
+                }
+                match page.cursor {
+                    Some(next) => cursor = Some(next),
+                    None => break,
+                }
+            }
+        }
+        Ok(deleted)
+    }
 }
 pub trait KVQFixedConfigMerkleTreeModelReaderCore<
     const TREE_ID: u8,
@@ -268,6 +397,30 @@ pub trait KVQFixedConfigMerkleTreeModelReaderCore<
             &Self::new_node_key_fc(checkpoint_id, 0, 0),
         )
     }
+    fn get_leaves_at_checkpoint_fc(
+        store: &S,
+        checkpoint_id: u64,
+    ) -> anyhow::Result<Vec<KVQPair<u64, Hash>>> {
+        Self::get_leaves_at_checkpoint(
+            store,
+            &KVQTreeIdentifier::new(TREE_ID, PRIMARY_ID, SECONDARY_ID),
+            TREE_HEIGHT,
+            checkpoint_id,
+        )
+    }
+    fn get_multi_proof_fc(
+        store: &S,
+        checkpoint_id: u64,
+        indices: &[u64],
+    ) -> anyhow::Result<MerkleMultiProofCore<Hash>> {
+        Self::get_multi_proof(
+            store,
+            &KVQTreeIdentifier::new(TREE_ID, PRIMARY_ID, SECONDARY_ID),
+            TREE_HEIGHT,
+            checkpoint_id,
+            indices,
+        )
+    }
 }
 pub trait KVQFixedConfigMerkleTreeModelCore<
     const TREE_ID: u8,
@@ -303,6 +456,14 @@ pub trait KVQFixedConfigMerkleTreeModelCore<
     ) -> anyhow::Result<DeltaMerkleProofCore<Hash>> {
         Self::set_leaf(store, &Self::new_leaf_key_fc(checkpoint_id, index), value)
     }
+    fn prune_checkpoints_before_fc(store: &mut S, min_checkpoint_id: u64) -> anyhow::Result<usize> {
+        Self::prune_checkpoints_before(
+            store,
+            &KVQTreeIdentifier::new(TREE_ID, PRIMARY_ID, SECONDARY_ID),
+            TREE_HEIGHT,
+            min_checkpoint_id,
+        )
+    }
 }
 
 pub struct KVQMerkleTreeModel<
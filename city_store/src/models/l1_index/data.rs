@@ -0,0 +1,31 @@
+use kvq::traits::KVQSerializable;
+use serde::{Deserialize, Serialize};
+
+// an index entry which records that `id` belongs to `owner` since `checkpoint_id`, the owner is
+// stored before the id so all ids of an owner are next to each other in the table
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, KVQSerializable)]
+#[kvq_table(id = TABLE_TYPE)]
+pub struct L1IdsByOwnerKeyCore<const TABLE_TYPE: u16, const OWNER_SIZE: usize> {
+    pub owner: [u8; OWNER_SIZE],
+    pub id: u64,
+    pub checkpoint_id: u64,
+}
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    KVQSerializable,
+)]
+#[kvq_table(id = TABLE_TYPE)]
+pub struct L1OwnerByIdKeyCore<const TABLE_TYPE: u16> {
+    pub id: u64,
+    pub checkpoint_id: u64,
+}
@@ -0,0 +1,190 @@
+use std::collections::BTreeSet;
+
+use kvq::traits::{KVQBinaryStore, KVQBinaryStoreReader, KVQStoreAdapter, KVQStoreAdapterReader};
+
+use crate::models::kvq_merkle::model::{CHECKPOINT_ID_FUZZY_SIZE, PRUNE_PAGE_SIZE};
+
+use super::data::{L1IdsByOwnerKeyCore, L1OwnerByIdKeyCore};
+
+// id (8) + checkpoint_id (8)
+pub const OWNER_INDEX_FUZZY_SIZE: usize = 16;
+
+pub trait L1IdsByOwnerModelReaderCore<
+    const TABLE_TYPE: u16,
+    const OWNER_SIZE: usize,
+    S: KVQBinaryStoreReader,
+    KVA: KVQStoreAdapterReader<S, L1IdsByOwnerKeyCore<TABLE_TYPE, OWNER_SIZE>, u64>,
+>
+{
+    // the ids indexed for the owner at or before checkpoint_id, in ascending order
+    fn get_ids_for_owner(
+        store: &S,
+        checkpoint_id: u64,
+        owner: [u8; OWNER_SIZE],
+    ) -> anyhow::Result<Vec<u64>> {
+        let ids = KVA::get_fuzzy_range_leq_kv(
+            store,
+            &L1IdsByOwnerKeyCore {
+                owner,
+                id: u64::MAX,
+                checkpoint_id: u64::MAX,
+            },
+            OWNER_INDEX_FUZZY_SIZE,
+        )?
+        .into_iter()
+        .filter(|kv| kv.key.checkpoint_id <= checkpoint_id)
+        .map(|kv| kv.value)
+        .collect::<BTreeSet<_>>();
+        Ok(ids.into_iter().collect())
+    }
+}
+
+pub trait L1IdsByOwnerModelCore<
+    const TABLE_TYPE: u16,
+    const OWNER_SIZE: usize,
+    S: KVQBinaryStore,
+    KVA: KVQStoreAdapter<S, L1IdsByOwnerKeyCore<TABLE_TYPE, OWNER_SIZE>, u64>,
+>: L1IdsByOwnerModelReaderCore<TABLE_TYPE, OWNER_SIZE, S, KVA>
+{
+    fn add_id_for_owner(
+        store: &mut S,
+        checkpoint_id: u64,
+        owner: [u8; OWNER_SIZE],
+        id: u64,
+    ) -> anyhow::Result<()> {
+        KVA::set(
+            store,
+            L1IdsByOwnerKeyCore {
+                owner,
+                id,
+                checkpoint_id,
+            },
+            id,
+        )
+    }
+}
+
+pub struct L1IdsByOwnerModel<const TABLE_TYPE: u16, const OWNER_SIZE: usize, S, KVA> {
+    _store: S,
+    _kva: KVA,
+}
+
+impl<
+        const TABLE_TYPE: u16,
+        const OWNER_SIZE: usize,
+        S: KVQBinaryStoreReader,
+        KVA: KVQStoreAdapterReader<S, L1IdsByOwnerKeyCore<TABLE_TYPE, OWNER_SIZE>, u64>,
+    > L1IdsByOwnerModelReaderCore<TABLE_TYPE, OWNER_SIZE, S, KVA>
+    for L1IdsByOwnerModel<TABLE_TYPE, OWNER_SIZE, S, KVA>
+{
+}
+impl<
+        const TABLE_TYPE: u16,
+        const OWNER_SIZE: usize,
+        S: KVQBinaryStore,
+        KVA: KVQStoreAdapter<S, L1IdsByOwnerKeyCore<TABLE_TYPE, OWNER_SIZE>, u64>,
+    > L1IdsByOwnerModelCore<TABLE_TYPE, OWNER_SIZE, S, KVA>
+    for L1IdsByOwnerModel<TABLE_TYPE, OWNER_SIZE, S, KVA>
+{
+}
+
+// the owner of an id at a checkpoint, needed when ids are reused so stale entries in the owner
+// index can be skipped
+pub trait L1OwnerByIdModelReaderCore<
+    const TABLE_TYPE: u16,
+    const OWNER_SIZE: usize,
+    S: KVQBinaryStoreReader,
+    KVA: KVQStoreAdapterReader<S, L1OwnerByIdKeyCore<TABLE_TYPE>, [u8; OWNER_SIZE]>,
+>
+{
+    fn get_owner_by_id(
+        store: &S,
+        checkpoint_id: u64,
+        id: u64,
+    ) -> anyhow::Result<Option<[u8; OWNER_SIZE]>> {
+        KVA::get_leq(
+            store,
+            &L1OwnerByIdKeyCore { id, checkpoint_id },
+            CHECKPOINT_ID_FUZZY_SIZE,
+        )
+    }
+}
+
+pub trait L1OwnerByIdModelCore<
+    const TABLE_TYPE: u16,
+    const OWNER_SIZE: usize,
+    S: KVQBinaryStore,
+    KVA: KVQStoreAdapter<S, L1OwnerByIdKeyCore<TABLE_TYPE>, [u8; OWNER_SIZE]>,
+>: L1OwnerByIdModelReaderCore<TABLE_TYPE, OWNER_SIZE, S, KVA>
+{
+    fn set_owner_by_id(
+        store: &mut S,
+        checkpoint_id: u64,
+        id: u64,
+        owner: [u8; OWNER_SIZE],
+    ) -> anyhow::Result<()> {
+        KVA::set(store, L1OwnerByIdKeyCore { id, checkpoint_id }, owner)
+    }
+    // deletes every entry which is shadowed at min_checkpoint_id by a newer entry for the same id,
+    // so get_owner_by_id lookups for checkpoints >= min_checkpoint_id are unaffected
+    fn prune_checkpoints_before(store: &mut S, min_checkpoint_id: u64) -> anyhow::Result<usize> {
+        let start = L1OwnerByIdKeyCore::<TABLE_TYPE> {
+            id: 0,
+            checkpoint_id: 0,
+        };
+        let end = L1OwnerByIdKeyCore::<TABLE_TYPE> {
+            id: u64::MAX,
+            checkpoint_id: u64::MAX,
+        };
+        let mut deleted = 0;
+        let mut previous: Option<L1OwnerByIdKeyCore<TABLE_TYPE>> = None;
+        let mut cursor = None;
+        loop {
+            let page = KVA::get_range_page(store, &start, &end, cursor.as_ref(), PRUNE_PAGE_SIZE)?;
+            let mut stale_keys = Vec::new();
+            for kv in page.items.iter() {
+                if let Some(previous) = previous {
+                    if previous.checkpoint_id < min_checkpoint_id
+                        && kv.key.id == previous.id
+                        && kv.key.checkpoint_id <= min_checkpoint_id
+                    {
+                        stale_keys.push(previous);
+                    }
+                }
+                previous = Some(kv.key);
+            }
+            if !stale_keys.is_empty() {
+                KVA::delete_many(store, &stale_keys)?;
+                deleted += stale_keys.len();
+            }
+            match page.cursor {
+                Some(next) => cursor = Some(next),
+                None => return Ok(deleted),
+            }
+        }
+    }
+}
+
+pub struct L1OwnerByIdModel<const TABLE_TYPE: u16, const OWNER_SIZE: usize, S, KVA> {
+    _store: S,
+    _kva: KVA,
+}
+
+impl<
+        const TABLE_TYPE: u16,
+        const OWNER_SIZE: usize,
+        S: KVQBinaryStoreReader,
+        KVA: KVQStoreAdapterReader<S, L1OwnerByIdKeyCore<TABLE_TYPE>, [u8; OWNER_SIZE]>,
+    > L1OwnerByIdModelReaderCore<TABLE_TYPE, OWNER_SIZE, S, KVA>
+    for L1OwnerByIdModel<TABLE_TYPE, OWNER_SIZE, S, KVA>
+{
+}
+impl<
+        const TABLE_TYPE: u16,
+        const OWNER_SIZE: usize,
+        S: KVQBinaryStore,
+        KVA: KVQStoreAdapter<S, L1OwnerByIdKeyCore<TABLE_TYPE>, [u8; OWNER_SIZE]>,
+    > L1OwnerByIdModelCore<TABLE_TYPE, OWNER_SIZE, S, KVA>
+    for L1OwnerByIdModel<TABLE_TYPE, OWNER_SIZE, S, KVA>
+{
+}
@@ -1,7 +1,7 @@
 use city_rollup_common::api::data::store::CityL2BlockState;
 use kvq::traits::{KVQBinaryStore, KVQBinaryStoreReader, KVQStoreAdapter, KVQStoreAdapterReader};
 
-use crate::models::kvq_merkle::model::CHECKPOINT_ID_FUZZY_SIZE;
+use crate::models::kvq_merkle::model::{CHECKPOINT_ID_FUZZY_SIZE, PRUNE_PAGE_SIZE};
 
 use super::data::L2BlockStateKeyCore;
 
@@ -77,6 +77,28 @@ pub trait L2BlockStatesModelCore<
 
         Ok(())
     }
+    fn prune_block_states_before(store: &mut S, min_checkpoint_id: u64) -> anyhow::Result<usize> {
+        let mut deleted = 0;
+        let mut cursor = None;
+        loop {
+            let page = KVA::get_range_page(
+                store,
+                &L2BlockStateKeyCore(0),
+                &L2BlockStateKeyCore(min_checkpoint_id),
+                cursor.as_ref(),
+                PRUNE_PAGE_SIZE,
+            )?;
+            let stale_keys = page.items.iter().map(|kv| kv.key).collect::<Vec<_>>();
+            if !stale_keys.is_empty() {
+                KVA::delete_many(store, &stale_keys)?;
+                deleted += stale_keys.len();
+            }
+            match page.cursor {
+                Some(next) => cursor = Some(next),
+                None => return Ok(deleted),
+            }
+        }
+    }
 }
 pub struct L2BlockStatesModel<const L2_BLOCK_STATE_TABLE_TYPE: u16, S, KVA> {
     _store: S,
@@ -1,4 +1,8 @@
+pub mod forced_withdrawal;
+pub mod indexed_merkle;
 pub mod kvq_merkle;
 pub mod l1_deposits;
+pub mod l1_index;
 pub mod l2_block_state;
+pub mod schema;
 pub mod user;
\ No newline at end of file
@@ -0,0 +1,6 @@
+use kvq::traits::KVQSerializable;
+
+// the key of the store wide schema version record, which is just the table id
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, KVQSerializable)]
+#[kvq_table(id = TABLE_TYPE)]
+pub struct SchemaVersionKeyCore<const TABLE_TYPE: u16>;
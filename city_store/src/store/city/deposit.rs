@@ -1,3 +1,4 @@
+use city_common::binaryhelpers::bytes::CompressedPublicKey;
 use city_crypto::hash::base_types::hash256::Hash256;
 use city_rollup_common::{
     api::data::{block::requested_actions::CityAddDepositRequest, store::CityL1Deposit},
@@ -7,14 +8,15 @@ use kvq::traits::{KVQBinaryStore, KVQBinaryStoreReader};
 
 use crate::{
     config::{
-        CityDeltaMerkleProof, CityHash, CityHasher, CityMerkleProof, L1DepositTreeStore,
-        L1DepositsStore, F,
+        CityDeltaMerkleProof, CityHash, CityHasher, CityMerkleProof, L1DepositIdsByPublicKeyStore,
+        L1DepositTreeStore, L1DepositsStore, F,
     },
     models::{
         kvq_merkle::model::{
             KVQFixedConfigMerkleTreeModelCore, KVQFixedConfigMerkleTreeModelReaderCore,
         },
         l1_deposits::model::{L1DepositsModelCore, L1DepositsModelReaderCore},
+        l1_index::model::{L1IdsByOwnerModelCore, L1IdsByOwnerModelReaderCore},
     },
 };
 
@@ -64,6 +66,13 @@ impl<S: KVQBinaryStoreReader> CityStore<S> {
     ) -> anyhow::Result<CityMerkleProof> {
         L1DepositTreeStore::get_leaf_fc(store, checkpoint_id, deposit_id)
     }
+    pub fn get_deposit_ids_for_public_key(
+        store: &S,
+        checkpoint_id: u64,
+        public_key: CompressedPublicKey,
+    ) -> anyhow::Result<Vec<u64>> {
+        L1DepositIdsByPublicKeyStore::get_ids_for_owner(store, checkpoint_id, public_key.0)
+    }
 }
 
 impl<S: KVQBinaryStore> CityStore<S> {
@@ -80,6 +89,12 @@ impl<S: KVQBinaryStore> CityStore<S> {
         .get_hash::<CityHasher>();
 
         L1DepositsStore::set_deposit_ref(store, deposit)?;
+        L1DepositIdsByPublicKeyStore::add_id_for_owner(
+            store,
+            checkpoint_id,
+            deposit.public_key.0,
+            deposit.deposit_id,
+        )?;
         L1DepositTreeStore::set_leaf_fc(store, checkpoint_id, deposit.deposit_id, deposit_hash)
     }
     pub fn add_deposit_from_request(
@@ -103,6 +118,12 @@ impl<S: KVQBinaryStore> CityStore<S> {
         .get_hash::<CityHasher>();
 
         L1DepositsStore::set_deposit(store, deposit)?;
+        L1DepositIdsByPublicKeyStore::add_id_for_owner(
+            store,
+            checkpoint_id,
+            deposit.public_key.0,
+            deposit.deposit_id,
+        )?;
         L1DepositTreeStore::set_leaf_fc(store, checkpoint_id, deposit.deposit_id, deposit_hash)
     }
     pub fn mark_deposit_as_claimed(
@@ -113,3 +134,42 @@ impl<S: KVQBinaryStore> CityStore<S> {
         L1DepositTreeStore::set_leaf_fc(store, checkpoint_id, deposit_id, CityHash::ZERO)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use kvq::memory::simple::KVQSimpleMemoryBackingStore;
+
+    use super::*;
+
+    type S = KVQSimpleMemoryBackingStore;
+
+    #[test]
+    fn deposit_ids_are_indexed_by_public_key() {
+        let mut store = S::new();
+        let alice = CompressedPublicKey([2u8; 33]);
+        let bob = CompressedPublicKey([3u8; 33]);
+        let requests = [(1, alice), (1, bob), (2, alice), (3, alice)];
+        for (deposit_id, (checkpoint_id, public_key)) in requests.into_iter().enumerate() {
+            let req =
+                CityAddDepositRequest::new(1000, Hash256([deposit_id as u8; 32]), public_key.0);
+            CityStore::add_deposit_from_request(&mut store, checkpoint_id, deposit_id as u64, &req)
+                .unwrap();
+        }
+
+        assert_eq!(
+            CityStore::get_deposit_ids_for_public_key(&store, 1, alice).unwrap(),
+            vec![0]
+        );
+        assert_eq!(
+            CityStore::get_deposit_ids_for_public_key(&store, 3, alice).unwrap(),
+            vec![0, 2, 3]
+        );
+        assert_eq!(
+            CityStore::get_deposit_ids_for_public_key(&store, 3, bob).unwrap(),
+            vec![1]
+        );
+        assert!(CityStore::get_deposit_ids_for_public_key(&store, 0, alice)
+            .unwrap()
+            .is_empty());
+    }
+}
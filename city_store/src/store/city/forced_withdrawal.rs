@@ -0,0 +1,130 @@
+use city_rollup_common::{
+    api::data::store::CityForcedWithdrawalRequestStatus,
+    introspection::rollup::forced_withdrawal::CityForcedWithdrawalRequest,
+};
+use kvq::traits::{KVQBinaryStore, KVQBinaryStoreReader};
+
+use crate::{
+    config::L1ForcedWithdrawalsStore,
+    models::forced_withdrawal::model::{
+        L1ForcedWithdrawalsModelCore, L1ForcedWithdrawalsModelReaderCore,
+    },
+};
+
+use super::base::CityStore;
+
+impl<S: KVQBinaryStoreReader> CityStore<S> {
+    pub fn get_forced_withdrawal_requests(
+        store: &S,
+        observed_checkpoint_id: u64,
+    ) -> anyhow::Result<Vec<CityForcedWithdrawalRequestStatus>> {
+        L1ForcedWithdrawalsStore::<S>::get_forced_withdrawal_requests_for_checkpoint(
+            store,
+            observed_checkpoint_id,
+        )
+    }
+    // a request can only be spent by the block whose deposit address it pays, so only the requests
+    // observed for checkpoint_id which have not been included yet are pending
+    pub fn get_pending_forced_withdrawal_requests(
+        store: &S,
+        checkpoint_id: u64,
+    ) -> anyhow::Result<Vec<CityForcedWithdrawalRequestStatus>> {
+        Ok(Self::get_forced_withdrawal_requests(store, checkpoint_id)?
+            .into_iter()
+            .filter(|status| !status.is_included())
+            .collect())
+    }
+}
+
+impl<S: KVQBinaryStore> CityStore<S> {
+    pub fn add_forced_withdrawal_requests(
+        store: &mut S,
+        checkpoint_id: u64,
+        requests: &[CityForcedWithdrawalRequest],
+    ) -> anyhow::Result<Vec<CityForcedWithdrawalRequestStatus>> {
+        let start_index = Self::get_forced_withdrawal_requests(store, checkpoint_id)?.len() as u64;
+        let mut result = Vec::with_capacity(requests.len());
+        for (i, request) in requests.iter().enumerate() {
+            let status = CityForcedWithdrawalRequestStatus {
+                request: *request,
+                observed_checkpoint_id: checkpoint_id,
+                index: start_index + i as u64,
+                deadline_checkpoint_id: checkpoint_id,
+                included_checkpoint_id: 0,
+            };
+            L1ForcedWithdrawalsStore::set_forced_withdrawal_request(store, status)?;
+            result.push(status);
+        }
+        Ok(result)
+    }
+    pub fn mark_forced_withdrawal_request_as_included(
+        store: &mut S,
+        checkpoint_id: u64,
+        status: &CityForcedWithdrawalRequestStatus,
+    ) -> anyhow::Result<()> {
+        if status.is_included() {
+            anyhow::bail!(
+                "forced withdrawal request {} was already included in checkpoint {}",
+                status.request.txid.to_hex_string(),
+                status.included_checkpoint_id
+            );
+        }
+        L1ForcedWithdrawalsStore::set_forced_withdrawal_request(
+            store,
+            CityForcedWithdrawalRequestStatus {
+                included_checkpoint_id: checkpoint_id,
+                ..*status
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use city_crypto::hash::base_types::{hash160::Hash160, hash256::Hash256};
+    use kvq::memory::simple::KVQSimpleMemoryBackingStore;
+
+    use super::*;
+
+    type S = KVQSimpleMemoryBackingStore;
+
+    fn request(id: u8) -> CityForcedWithdrawalRequest {
+        CityForcedWithdrawalRequest {
+            txid: Hash256([id; 32]),
+            user_id: id as u64,
+            nonce: 0,
+            value: 1000,
+            destination_type: 0,
+            destination: Hash160([id; 20]),
+        }
+    }
+
+    #[test]
+    fn requests_are_only_pending_for_their_block() {
+        let mut store = S::new();
+        let added =
+            CityStore::add_forced_withdrawal_requests(&mut store, 2, &[request(1), request(2)])
+                .unwrap();
+        assert_eq!(added[1].index, 1);
+        assert_eq!(added[1].deadline_checkpoint_id, 2);
+
+        assert_eq!(
+            CityStore::get_pending_forced_withdrawal_requests(&store, 2).unwrap(),
+            added
+        );
+        assert!(CityStore::get_pending_forced_withdrawal_requests(&store, 3)
+            .unwrap()
+            .is_empty());
+
+        CityStore::mark_forced_withdrawal_request_as_included(&mut store, 2, &added[0]).unwrap();
+        assert_eq!(
+            CityStore::get_pending_forced_withdrawal_requests(&store, 2).unwrap(),
+            vec![added[1]]
+        );
+        let included = CityStore::get_forced_withdrawal_requests(&store, 2).unwrap();
+        assert!(
+            CityStore::mark_forced_withdrawal_request_as_included(&mut store, 2, &included[0])
+                .is_err()
+        );
+    }
+}
@@ -1,7 +1,12 @@
 pub mod base;
 pub mod deposit;
+pub mod forced_withdrawal;
 pub mod l2_state;
+pub mod prune;
 pub mod requests;
 pub mod root;
+pub mod schema;
+pub mod snapshot;
+pub mod token;
 pub mod user;
 pub mod withdrawal;
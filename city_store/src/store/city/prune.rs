@@ -0,0 +1,343 @@
+use kvq::traits::KVQBinaryStore;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::{
+        GlobalUserTreeStore, L1DepositTreeStore, L1WithdrawalDestinationsStore,
+        L1WithdrawalRecordsStore, L1WithdrawalTreeStore, L2BlockStateStore, TokenBalanceTreeStore,
+        TokenRegistryTreeStore, UserPublicKeyTreeStore,
+    },
+    models::{
+        indexed_merkle::model::IndexedMerkleTreeModelCore,
+        kvq_merkle::model::KVQFixedConfigMerkleTreeModelCore,
+        l1_index::model::L1OwnerByIdModelCore, l2_block_state::model::L2BlockStatesModelCore,
+    },
+};
+
+use super::base::CityStore;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CityPruneStats {
+    pub user_tree_nodes: usize,
+    pub deposit_tree_nodes: usize,
+    pub withdrawal_tree_nodes: usize,
+    pub token_registry_tree_nodes: usize,
+    pub token_balance_tree_nodes: usize,
+    // the tree nodes and the leaf preimages
+    pub user_public_key_tree_nodes: usize,
+    pub withdrawal_records: usize,
+    pub withdrawal_destinations: usize,
+    pub block_states: usize,
+}
+
+impl<S: KVQBinaryStore> CityStore<S> {
+    // drops the history needed to read checkpoints before min_checkpoint_id, reads of checkpoints
+    // >= min_checkpoint_id return the same results as before pruning
+    pub fn prune_checkpoints_before(
+        store: &mut S,
+        min_checkpoint_id: u64,
+    ) -> anyhow::Result<CityPruneStats> {
+        Ok(CityPruneStats {
+            user_tree_nodes: GlobalUserTreeStore::prune_checkpoints_before_fc(
+                store,
+                min_checkpoint_id,
+            )?,
+            deposit_tree_nodes: L1DepositTreeStore::prune_checkpoints_before_fc(
+                store,
+                min_checkpoint_id,
+            )?,
+            withdrawal_tree_nodes: L1WithdrawalTreeStore::prune_checkpoints_before_fc(
+                store,
+                min_checkpoint_id,
+            )?,
+            token_registry_tree_nodes: TokenRegistryTreeStore::prune_checkpoints_before_fc(
+                store,
+                min_checkpoint_id,
+            )?,
+            token_balance_tree_nodes: TokenBalanceTreeStore::prune_checkpoints_before_fc(
+                store,
+                min_checkpoint_id,
+            )?,
+            user_public_key_tree_nodes: UserPublicKeyTreeStore::prune_checkpoints_before(
+                store,
+                min_checkpoint_id,
+            )?,
+            withdrawal_records: L1WithdrawalRecordsStore::prune_checkpoints_before(
+                store,
+                min_checkpoint_id,
+            )?,
+            withdrawal_destinations: L1WithdrawalDestinationsStore::prune_checkpoints_before(
+                store,
+                min_checkpoint_id,
+            )?,
+            block_states: L2BlockStateStore::prune_block_states_before(store, min_checkpoint_id)?,
+        })
+    }
+    pub fn prune_keep_last_checkpoints(
+        store: &mut S,
+        count: u64,
+    ) -> anyhow::Result<CityPruneStats> {
+        if count == 0 {
+            anyhow::bail!("cannot prune all checkpoints, keep at least one");
+        }
+        let latest_checkpoint_id = Self::get_latest_block_state(store)?.checkpoint_id;
+        Self::prune_checkpoints_before(store, (latest_checkpoint_id + 1).saturating_sub(count))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use city_common::config::rollup_constants::GLOBAL_USER_TREE_HEIGHT;
+    use city_crypto::hash::{base_types::hash160::Hash160, merkle::indexed::IndexedMerkleLeaf};
+    use city_rollup_common::api::data::store::{CityL1Withdrawal, CityL2BlockState};
+    use kvq::{memory::simple::KVQSimpleMemoryBackingStore, traits::KVQBinaryStoreReader};
+    use kvq_store_redb::{KVQReDBTableDefinition, KVQReDBTableProvider};
+    use redb::{backends::InMemoryBackend, Database, TableDefinition};
+
+    use super::*;
+    use crate::{
+        config::{CityHash, CityMerkleProof, F},
+        models::{
+            indexed_merkle::model::IndexedMerkleTreeModelReaderCore,
+            kvq_merkle::model::{KVQFixedConfigMerkleTreeModelReaderCore, PRUNE_PAGE_SIZE},
+        },
+    };
+
+    type S = KVQSimpleMemoryBackingStore;
+
+    const LAST_CHECKPOINT_ID: u64 = 6;
+
+    fn build_store() -> S {
+        let mut store = S::new();
+        write_checkpoints(&mut store);
+        store
+    }
+
+    fn write_checkpoints<T: KVQBinaryStore>(store: &mut T) {
+        for checkpoint_id in 1..=LAST_CHECKPOINT_ID {
+            for index in 0..4u64 {
+                // rewrite some leaves every checkpoint and leave others untouched for a while
+                if (index + checkpoint_id) % 2 == 0 || index == 0 {
+                    GlobalUserTreeStore::set_leaf_fc(
+                        store,
+                        checkpoint_id,
+                        index,
+                        CityHash::from_values(checkpoint_id, index, 1, 0),
+                    )
+                    .unwrap();
+                }
+            }
+            L1DepositTreeStore::set_leaf_fc(
+                store,
+                checkpoint_id,
+                checkpoint_id - 1,
+                CityHash::from_values(checkpoint_id, 2, 0, 0),
+            )
+            .unwrap();
+            if checkpoint_id % 3 != 1 {
+                let withdrawal = CityL1Withdrawal {
+                    withdrawal_id: 0,
+                    address: Hash160([checkpoint_id as u8; 20]),
+                    address_type: 0,
+                    value: checkpoint_id * 100,
+                };
+                CityStore::set_withdrawal(store, checkpoint_id, &withdrawal, 1).unwrap();
+            }
+            // each insert also rewrites the leaf of the previous key
+            CityStore::insert_user_public_key(
+                store,
+                checkpoint_id,
+                CityHash::from_values(checkpoint_id, 9, 9, 9),
+            )
+            .unwrap();
+            TokenRegistryTreeStore::set_leaf_fc(
+                store,
+                checkpoint_id,
+                1,
+                CityHash::from_values(checkpoint_id, 1, 0, 0),
+            )
+            .unwrap();
+            TokenBalanceTreeStore::set_leaf_fc(
+                store,
+                checkpoint_id,
+                (checkpoint_id << 31) + 1,
+                CityHash::from_values(checkpoint_id, 1, 0, 0),
+            )
+            .unwrap();
+            CityStore::set_block_state(
+                store,
+                &CityL2BlockState {
+                    checkpoint_id,
+                    next_user_id: checkpoint_id * 10,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        }
+    }
+
+    fn snapshot<T: KVQBinaryStoreReader>(
+        store: &T,
+        checkpoint_id: u64,
+    ) -> (
+        Vec<CityMerkleProof>,
+        Vec<CityHash>,
+        Vec<IndexedMerkleLeaf<F>>,
+        Option<(CityL1Withdrawal, u64)>,
+    ) {
+        let mut proofs = Vec::new();
+        for index in 0..4u64 {
+            proofs.push(GlobalUserTreeStore::get_leaf_fc(store, checkpoint_id, index).unwrap());
+        }
+        for index in 0..LAST_CHECKPOINT_ID {
+            proofs.push(L1DepositTreeStore::get_leaf_fc(store, checkpoint_id, index).unwrap());
+        }
+        proofs.push(L1WithdrawalTreeStore::get_leaf_fc(store, checkpoint_id, 0).unwrap());
+        proofs.push(TokenRegistryTreeStore::get_leaf_fc(store, checkpoint_id, 1).unwrap());
+        for user_id in 1..=LAST_CHECKPOINT_ID {
+            proofs.push(
+                TokenBalanceTreeStore::get_leaf_fc(store, checkpoint_id, (user_id << 31) + 1)
+                    .unwrap(),
+            );
+        }
+        let roots = vec![
+            CityStore::get_user_tree_root(store, checkpoint_id).unwrap(),
+            CityStore::get_deposit_tree_root(store, checkpoint_id).unwrap(),
+            CityStore::get_withdrawal_tree_root(store, checkpoint_id).unwrap(),
+            CityStore::get_token_registry_root(store, checkpoint_id).unwrap(),
+            CityStore::get_token_balance_root(store, checkpoint_id).unwrap(),
+            CityStore::get_user_public_key_tree_root(store, checkpoint_id).unwrap(),
+        ];
+        let public_key_leaves = UserPublicKeyTreeStore::get_leaves(store, checkpoint_id).unwrap();
+        let pending_withdrawal =
+            CityStore::get_pending_withdrawal_with_owner(store, checkpoint_id, 0).unwrap();
+        (proofs, roots, public_key_leaves, pending_withdrawal)
+    }
+
+    #[test]
+    fn prune_keeps_retained_checkpoints() {
+        let min_checkpoint_id = 4;
+        let mut store = build_store();
+        let before = (min_checkpoint_id..=LAST_CHECKPOINT_ID + 1)
+            .map(|checkpoint_id| snapshot(&store, checkpoint_id))
+            .collect::<Vec<_>>();
+
+        let stats = CityStore::prune_checkpoints_before(&mut store, min_checkpoint_id).unwrap();
+        assert!(stats.user_tree_nodes > 0);
+        assert!(stats.deposit_tree_nodes > 0);
+        assert!(stats.token_registry_tree_nodes > 0);
+        assert!(stats.token_balance_tree_nodes > 0);
+        assert!(stats.user_public_key_tree_nodes > 0);
+        // withdrawal 0 is written at checkpoints 2, 3, 5 and 6, only the version at 2 is shadowed
+        assert_eq!(stats.withdrawal_records, 1);
+        assert_eq!(stats.withdrawal_destinations, 1);
+        assert_eq!(stats.block_states, (min_checkpoint_id - 1) as usize);
+
+        let after = (min_checkpoint_id..=LAST_CHECKPOINT_ID + 1)
+            .map(|checkpoint_id| snapshot(&store, checkpoint_id))
+            .collect::<Vec<_>>();
+        assert_eq!(before, after);
+
+        for checkpoint_id in 1..min_checkpoint_id {
+            assert!(CityStore::get_block_state(&store, checkpoint_id).is_err());
+        }
+        for checkpoint_id in min_checkpoint_id..=LAST_CHECKPOINT_ID {
+            assert_eq!(
+                CityStore::get_block_state(&store, checkpoint_id)
+                    .unwrap()
+                    .next_user_id,
+                checkpoint_id * 10
+            );
+        }
+
+        // pruning again is a no-op
+        let stats = CityStore::prune_checkpoints_before(&mut store, min_checkpoint_id).unwrap();
+        assert_eq!(stats, CityPruneStats::default());
+    }
+
+    #[test]
+    fn prune_keep_last_checkpoints() {
+        let mut store = build_store();
+        let before = snapshot(&store, LAST_CHECKPOINT_ID - 1);
+
+        CityStore::prune_keep_last_checkpoints(&mut store, 2).unwrap();
+        assert_eq!(before, snapshot(&store, LAST_CHECKPOINT_ID - 1));
+        assert!(CityStore::get_block_state(&store, LAST_CHECKPOINT_ID - 2).is_err());
+        assert_eq!(
+            CityStore::get_latest_block_state(&store)
+                .unwrap()
+                .checkpoint_id,
+            LAST_CHECKPOINT_ID
+        );
+        assert!(CityStore::prune_keep_last_checkpoints(&mut store, 0).is_err());
+    }
+
+    #[test]
+    fn prune_keeps_retained_checkpoints_in_redb() {
+        const KV: KVQReDBTableDefinition = TableDefinition::new("KV");
+        let db = Database::builder()
+            .create_with_backend(InMemoryBackend::new())
+            .unwrap();
+        let db = KVQReDBTableProvider::new(Arc::new(db), KV);
+        let min_checkpoint_id = 4;
+        let snapshots = |db: &KVQReDBTableProvider| {
+            db.read(|store| {
+                Ok((min_checkpoint_id..=LAST_CHECKPOINT_ID + 1)
+                    .map(|checkpoint_id| snapshot(&store, checkpoint_id))
+                    .collect::<Vec<_>>())
+            })
+            .unwrap()
+        };
+
+        db.write(|store| {
+            write_checkpoints(store);
+            Ok(())
+        })
+        .unwrap();
+        let before = snapshots(&db);
+        let stats = db
+            .write(|store| CityStore::prune_checkpoints_before(store, min_checkpoint_id))
+            .unwrap();
+        assert!(stats.user_tree_nodes > 0);
+        assert_eq!(stats.block_states, (min_checkpoint_id - 1) as usize);
+        assert_eq!(before, snapshots(&db));
+    }
+
+    #[test]
+    fn prune_pages_through_long_histories() {
+        // more versions of each node than fit in one page
+        let last_checkpoint_id = PRUNE_PAGE_SIZE as u64 + 100;
+        let mut store = S::new();
+        for checkpoint_id in 1..=last_checkpoint_id {
+            GlobalUserTreeStore::set_leaf_fc(
+                &mut store,
+                checkpoint_id,
+                0,
+                CityHash::from_values(checkpoint_id, 0, 0, 0),
+            )
+            .unwrap();
+            CityStore::set_block_state(
+                &mut store,
+                &CityL2BlockState {
+                    checkpoint_id,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        }
+        let before = GlobalUserTreeStore::get_leaf_fc(&store, last_checkpoint_id, 0).unwrap();
+
+        let stats = CityStore::prune_checkpoints_before(&mut store, last_checkpoint_id).unwrap();
+        assert_eq!(
+            stats.user_tree_nodes,
+            (last_checkpoint_id as usize - 1) * (GLOBAL_USER_TREE_HEIGHT as usize + 1)
+        );
+        assert_eq!(stats.block_states, last_checkpoint_id as usize - 1);
+        assert_eq!(
+            before,
+            GlobalUserTreeStore::get_leaf_fc(&store, last_checkpoint_id, 0).unwrap()
+        );
+    }
+}
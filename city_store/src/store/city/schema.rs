@@ -0,0 +1,109 @@
+use kvq::traits::{KVQBinaryStore, KVQBinaryStoreReader, KVQSerializable};
+
+use crate::{
+    config::SchemaVersionKey,
+    migrations::{
+        CityMigrationReport, CityMigrationStep, CityStoreMigrations, CITY_STORE_SCHEMA_VERSION,
+        LEGACY_SCHEMA_VERSION,
+    },
+    models::schema::data::SchemaVersionKeyCore,
+};
+
+use super::base::CityStore;
+
+fn schema_version_key() -> anyhow::Result<Vec<u8>> {
+    let key: SchemaVersionKey = SchemaVersionKeyCore;
+    key.to_bytes()
+}
+
+impl<S: KVQBinaryStoreReader> CityStore<S> {
+    // None for an empty store, which can be initialized with any schema version
+    pub fn get_schema_version(store: &S) -> anyhow::Result<Option<u32>> {
+        match store.get_exact_if_exists(&schema_version_key()?)? {
+            Some(bytes) => {
+                if bytes.len() != 4 {
+                    anyhow::bail!(
+                        "expected 4 bytes for the schema version, got {} bytes",
+                        bytes.len()
+                    );
+                }
+                Ok(Some(u32::from_be_bytes(bytes.try_into().unwrap())))
+            }
+            // stores written before the schema version record was added have records but no version
+            None if store.get_prefix_page(&[], None, 1)?.items.is_empty() => Ok(None),
+            None => Ok(Some(LEGACY_SCHEMA_VERSION)),
+        }
+    }
+    pub fn check_schema_version(store: &S) -> anyhow::Result<()> {
+        match Self::get_schema_version(store)? {
+            Some(version) if version != CITY_STORE_SCHEMA_VERSION => anyhow::bail!(
+                "store has schema version {}, expected {}, run city-rollup-cli migrate to upgrade it",
+                version,
+                CITY_STORE_SCHEMA_VERSION
+            ),
+            _ => Ok(()),
+        }
+    }
+    // checks that the store is at the target version and runs the checks of the last migration
+    pub fn verify_schema(store: &S, migrations: &CityStoreMigrations<S>) -> anyhow::Result<()> {
+        let target_version = migrations.target_version();
+        match Self::get_schema_version(store)? {
+            None => Ok(()),
+            Some(version) if version != target_version => anyhow::bail!(
+                "store has schema version {}, expected {}",
+                version,
+                target_version
+            ),
+            Some(version) => match version.checked_sub(1) {
+                Some(from_version) => migrations.get(from_version)?.verify(store),
+                None => Ok(()),
+            },
+        }
+    }
+}
+
+impl<S: KVQBinaryStore> CityStore<S> {
+    pub fn set_schema_version(store: &mut S, version: u32) -> anyhow::Result<()> {
+        store.set(schema_version_key()?, version.to_be_bytes().to_vec())
+    }
+    // records the current schema version in new stores and refuses stores with any other version
+    pub fn ensure_schema_version(store: &mut S) -> anyhow::Result<()> {
+        Self::check_schema_version(store)?;
+        if Self::get_schema_version(store)?.is_none() {
+            Self::set_schema_version(store, CITY_STORE_SCHEMA_VERSION)?;
+        }
+        Ok(())
+    }
+    // upgrades the store to the target version of migrations, each migration is verified before
+    // its version is recorded. on error the store may be partially migrated, so callers should run
+    // this inside a transaction and discard it if it fails
+    pub fn migrate(
+        store: &mut S,
+        migrations: &CityStoreMigrations<S>,
+    ) -> anyhow::Result<CityMigrationReport> {
+        let target_version = migrations.target_version();
+        let from_version = match Self::get_schema_version(store)? {
+            Some(version) => version,
+            None => {
+                Self::set_schema_version(store, target_version)?;
+                target_version
+            }
+        };
+        let mut steps = Vec::new();
+        for migration in migrations.plan(from_version)? {
+            let records = (migration.migrate)(store)?;
+            migration.verify(store)?;
+            Self::set_schema_version(store, migration.from_version + 1)?;
+            steps.push(CityMigrationStep {
+                from_version: migration.from_version,
+                description: migration.description.to_string(),
+                records,
+            });
+        }
+        Ok(CityMigrationReport {
+            from_version,
+            to_version: target_version,
+            steps,
+        })
+    }
+}
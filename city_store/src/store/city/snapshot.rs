@@ -0,0 +1,503 @@
+use city_common::config::rollup_constants::TOKEN_STATE_USER_ID;
+use city_crypto::hash::{core::sha256::CoreSha256Hasher, merkle::indexed::IndexedMerkleLeaf};
+use city_rollup_common::api::data::store::{CityL1Withdrawal, CityL2BlockState};
+use kvq::traits::{KVQBinaryStore, KVQBinaryStoreReader, KVQPair};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::{
+        CityHash, GlobalUserTreeStore, L1DepositTreeStore, L1WithdrawalTreeStore,
+        TokenBalanceTreeStore, TokenRegistryTreeStore, UserPublicKeyTreeStore, F,
+    },
+    models::{
+        indexed_merkle::model::{IndexedMerkleTreeModelCore, IndexedMerkleTreeModelReaderCore},
+        kvq_merkle::model::{
+            KVQFixedConfigMerkleTreeModelCore, KVQFixedConfigMerkleTreeModelReaderCore,
+        },
+    },
+};
+
+use super::{base::CityStore, token::get_token_state_leaf};
+
+pub const CITY_SNAPSHOT_FILE_MAGIC: [u8; 4] = *b"CRSS";
+pub const CITY_SNAPSHOT_FORMAT_VERSION: u32 = 3;
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+struct CityStateSnapshotHeader {
+    version: u32,
+    checkpoint_id: u64,
+    city_root: CityHash,
+    payload_length: u64,
+    payload_sha256: String,
+}
+
+/// A pending withdrawal and the user who added it, the preimage of its withdrawal tree leaf.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Debug)]
+pub struct CityWithdrawalRecordSnapshot {
+    pub withdrawal: CityL1Withdrawal,
+    pub owner_user_id: u64,
+}
+
+/// The user, user public key, deposit, withdrawal and token tree leaves, the records of the
+/// pending withdrawals and the block state of a single checkpoint.
+///
+/// Importing a snapshot only restores that checkpoint, earlier checkpoints and the deposit and user
+/// id lookup tables are not part of the snapshot.
+#[derive(Serialize, Deserialize)]
+pub struct CityStateSnapshot {
+    pub checkpoint_id: u64,
+    pub block_state: CityL2BlockState,
+    pub user_tree_root: CityHash,
+    pub deposit_tree_root: CityHash,
+    pub withdrawal_tree_root: CityHash,
+    pub token_registry_tree_root: CityHash,
+    pub token_balance_tree_root: CityHash,
+    pub user_public_key_tree_root: CityHash,
+    pub city_root: CityHash,
+    pub user_leaves: Vec<KVQPair<u64, CityHash>>,
+    pub deposit_leaves: Vec<KVQPair<u64, CityHash>>,
+    pub withdrawal_leaves: Vec<KVQPair<u64, CityHash>>,
+    pub token_registry_leaves: Vec<KVQPair<u64, CityHash>>,
+    pub token_balance_leaves: Vec<KVQPair<u64, CityHash>>,
+    pub withdrawal_records: Vec<CityWithdrawalRecordSnapshot>,
+    // the leaf preimages in index order, starting with the sentinel
+    pub user_public_key_leaves: Vec<IndexedMerkleLeaf<F>>,
+}
+
+impl CityStateSnapshot {
+    pub fn to_file_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        let payload = serde_json::to_vec(self)?;
+        let header = serde_json::to_vec(&CityStateSnapshotHeader {
+            version: CITY_SNAPSHOT_FORMAT_VERSION,
+            checkpoint_id: self.checkpoint_id,
+            city_root: self.city_root,
+            payload_length: payload.len() as u64,
+            payload_sha256: CoreSha256Hasher::hash_bytes(&payload).to_hex_string(),
+        })?;
+
+        let mut bytes = Vec::with_capacity(8 + header.len() + payload.len());
+        bytes.extend_from_slice(&CITY_SNAPSHOT_FILE_MAGIC);
+        bytes.extend_from_slice(&(header.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&header);
+        bytes.extend_from_slice(&payload);
+        Ok(bytes)
+    }
+    pub fn from_file_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        if bytes.len() < 8 || bytes[0..4] != CITY_SNAPSHOT_FILE_MAGIC {
+            anyhow::bail!("not a city state snapshot");
+        }
+        let header_length = u32::from_le_bytes(bytes[4..8].try_into().unwrap()) as usize;
+        if bytes.len() < 8 + header_length {
+            anyhow::bail!("city state snapshot is truncated");
+        }
+        let header: CityStateSnapshotHeader = serde_json::from_slice(&bytes[8..8 + header_length])?;
+        let payload = &bytes[8 + header_length..];
+
+        if header.version != CITY_SNAPSHOT_FORMAT_VERSION {
+            anyhow::bail!(
+                "city state snapshot has format version {}, expected {}",
+                header.version,
+                CITY_SNAPSHOT_FORMAT_VERSION
+            );
+        }
+        if header.payload_length != payload.len() as u64
+            || header.payload_sha256 != CoreSha256Hasher::hash_bytes(payload).to_hex_string()
+        {
+            anyhow::bail!("city state snapshot failed its payload integrity check");
+        }
+        let snapshot: CityStateSnapshot = serde_json::from_slice(payload)?;
+        if snapshot.checkpoint_id != header.checkpoint_id
+            || snapshot.block_state.checkpoint_id != header.checkpoint_id
+            || snapshot.city_root != header.city_root
+        {
+            anyhow::bail!("city state snapshot does not match its header");
+        }
+        Ok(snapshot)
+    }
+}
+
+impl<S: KVQBinaryStoreReader> CityStore<S> {
+    pub fn export_snapshot(store: &S, checkpoint_id: u64) -> anyhow::Result<CityStateSnapshot> {
+        let withdrawal_leaves =
+            L1WithdrawalTreeStore::get_leaves_at_checkpoint_fc(store, checkpoint_id)?;
+        let mut withdrawal_records = Vec::new();
+        for leaf in withdrawal_leaves.iter() {
+            if let Some((withdrawal, owner_user_id)) =
+                Self::get_pending_withdrawal_with_owner(store, checkpoint_id, leaf.key)?
+            {
+                withdrawal_records.push(CityWithdrawalRecordSnapshot {
+                    withdrawal,
+                    owner_user_id,
+                });
+            }
+        }
+        Ok(CityStateSnapshot {
+            checkpoint_id,
+            block_state: Self::get_block_state(store, checkpoint_id)?,
+            user_tree_root: Self::get_user_tree_root(store, checkpoint_id)?,
+            deposit_tree_root: Self::get_deposit_tree_root(store, checkpoint_id)?,
+            withdrawal_tree_root: Self::get_withdrawal_tree_root(store, checkpoint_id)?,
+            token_registry_tree_root: Self::get_token_registry_root(store, checkpoint_id)?,
+            token_balance_tree_root: Self::get_token_balance_root(store, checkpoint_id)?,
+            user_public_key_tree_root: Self::get_user_public_key_tree_root(store, checkpoint_id)?,
+            city_root: Self::get_city_root(store, checkpoint_id)?,
+            user_leaves: GlobalUserTreeStore::get_leaves_at_checkpoint_fc(store, checkpoint_id)?,
+            deposit_leaves: L1DepositTreeStore::get_leaves_at_checkpoint_fc(store, checkpoint_id)?,
+            withdrawal_leaves,
+            token_registry_leaves: TokenRegistryTreeStore::get_leaves_at_checkpoint_fc(
+                store,
+                checkpoint_id,
+            )?,
+            token_balance_leaves: TokenBalanceTreeStore::get_leaves_at_checkpoint_fc(
+                store,
+                checkpoint_id,
+            )?,
+            withdrawal_records,
+            user_public_key_leaves: UserPublicKeyTreeStore::get_leaves(store, checkpoint_id)?,
+        })
+    }
+}
+
+impl<S: KVQBinaryStore> CityStore<S> {
+    // writes the snapshot to the store and returns the recomputed city root, the store should be
+    // empty as existing nodes at later checkpoints would shadow the imported ones
+    pub fn import_snapshot(
+        store: &mut S,
+        snapshot: &CityStateSnapshot,
+    ) -> anyhow::Result<CityHash> {
+        Self::ensure_schema_version(store)?;
+        let checkpoint_id = snapshot.checkpoint_id;
+        for leaf in snapshot.user_leaves.iter() {
+            GlobalUserTreeStore::set_leaf_fc(store, checkpoint_id, leaf.key, leaf.value)?;
+        }
+        for leaf in snapshot.deposit_leaves.iter() {
+            L1DepositTreeStore::set_leaf_fc(store, checkpoint_id, leaf.key, leaf.value)?;
+        }
+        for leaf in snapshot.withdrawal_leaves.iter() {
+            L1WithdrawalTreeStore::set_leaf_fc(store, checkpoint_id, leaf.key, leaf.value)?;
+        }
+        for leaf in snapshot.token_registry_leaves.iter() {
+            TokenRegistryTreeStore::set_leaf_fc(store, checkpoint_id, leaf.key, leaf.value)?;
+        }
+        for leaf in snapshot.token_balance_leaves.iter() {
+            TokenBalanceTreeStore::set_leaf_fc(store, checkpoint_id, leaf.key, leaf.value)?;
+        }
+        UserPublicKeyTreeStore::set_leaves(store, checkpoint_id, &snapshot.user_public_key_leaves)?;
+        for record in snapshot.withdrawal_records.iter() {
+            Self::set_withdrawal_record(
+                store,
+                checkpoint_id,
+                &record.withdrawal,
+                record.owner_user_id,
+            )?;
+        }
+        Self::set_block_state(store, &snapshot.block_state)?;
+
+        // every pending withdrawal needs the record matching its leaf to be processed or cancelled
+        for leaf in snapshot.withdrawal_leaves.iter() {
+            Self::get_pending_withdrawal_with_owner(store, checkpoint_id, leaf.key)?;
+        }
+        for record in snapshot.withdrawal_records.iter() {
+            let withdrawal_id = record.withdrawal.withdrawal_id;
+            if Self::get_pending_withdrawal_with_owner(store, checkpoint_id, withdrawal_id)?
+                != Some((record.withdrawal, record.owner_user_id))
+            {
+                anyhow::bail!(
+                    "withdrawal {} is not pending in the snapshot",
+                    withdrawal_id
+                );
+            }
+        }
+
+        let roots = [
+            (
+                "user tree",
+                Self::get_user_tree_root(store, checkpoint_id)?,
+                snapshot.user_tree_root,
+            ),
+            (
+                "deposit tree",
+                Self::get_deposit_tree_root(store, checkpoint_id)?,
+                snapshot.deposit_tree_root,
+            ),
+            (
+                "withdrawal tree",
+                Self::get_withdrawal_tree_root(store, checkpoint_id)?,
+                snapshot.withdrawal_tree_root,
+            ),
+            (
+                "token registry tree",
+                Self::get_token_registry_root(store, checkpoint_id)?,
+                snapshot.token_registry_tree_root,
+            ),
+            (
+                "token balance tree",
+                Self::get_token_balance_root(store, checkpoint_id)?,
+                snapshot.token_balance_tree_root,
+            ),
+            (
+                "user public key tree",
+                Self::get_user_public_key_tree_root(store, checkpoint_id)?,
+                snapshot.user_public_key_tree_root,
+            ),
+            (
+                "token state",
+                get_token_state_leaf(
+                    snapshot.token_registry_tree_root,
+                    snapshot.token_balance_tree_root,
+                ),
+                Self::get_user_tree_leaf(store, checkpoint_id, TOKEN_STATE_USER_ID * 2)?,
+            ),
+            (
+                "city",
+                Self::get_city_root(store, checkpoint_id)?,
+                snapshot.city_root,
+            ),
+        ];
+        for (name, recomputed, expected) in roots.iter() {
+            if recomputed != expected {
+                anyhow::bail!(
+                    "recomputed {} root {} does not match the snapshot's {}",
+                    name,
+                    recomputed.to_string(),
+                    expected.to_string()
+                );
+            }
+        }
+        Ok(snapshot.city_root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use city_crypto::hash::base_types::hash160::Hash160;
+    use city_rollup_common::{
+        api::data::block::requested_actions::CityAddWithdrawalRequest, link::data::BTCAddress160,
+        qworker::job_id::QProvingJobDataID,
+    };
+    use kvq::memory::simple::KVQSimpleMemoryBackingStore;
+
+    use super::*;
+    use crate::migrations::{CITY_STORE_SCHEMA_VERSION, LEGACY_SCHEMA_VERSION};
+
+    type S = KVQSimpleMemoryBackingStore;
+
+    fn build_store() -> S {
+        build_store_until(3)
+    }
+
+    fn public_key(checkpoint_id: u64) -> CityHash {
+        CityHash::from_values(checkpoint_id, 9, 9, 9)
+    }
+
+    // user 1 adds withdrawal checkpoint_id - 1 at each checkpoint
+    fn build_store_until(last_checkpoint_id: u64) -> S {
+        let mut store = S::new();
+        for checkpoint_id in 1..=last_checkpoint_id {
+            // user 0 holds the token state leaf
+            for index in 2..checkpoint_id * 2 + 2 {
+                GlobalUserTreeStore::set_leaf_fc(
+                    &mut store,
+                    checkpoint_id,
+                    index,
+                    CityHash::from_values(checkpoint_id, index, 1, 0),
+                )
+                .unwrap();
+            }
+            L1DepositTreeStore::set_leaf_fc(
+                &mut store,
+                checkpoint_id,
+                checkpoint_id,
+                CityHash::from_values(checkpoint_id, 2, 0, 0),
+            )
+            .unwrap();
+            let withdrawal_id = checkpoint_id - 1;
+            let req = CityAddWithdrawalRequest::new(
+                1,
+                1000 + checkpoint_id,
+                0,
+                checkpoint_id,
+                0,
+                Hash160([checkpoint_id as u8; 20]),
+                QProvingJobDataID::withdrawal_signature_proof(
+                    0,
+                    checkpoint_id,
+                    withdrawal_id as u32,
+                ),
+            );
+            CityStore::add_withdrawal_to_tree_from_request(
+                &mut store,
+                checkpoint_id,
+                withdrawal_id,
+                &req,
+            )
+            .unwrap();
+            TokenBalanceTreeStore::set_leaf_fc(
+                &mut store,
+                checkpoint_id,
+                (checkpoint_id << 31) + 1,
+                CityHash::from_values(checkpoint_id, 1, 0, 0),
+            )
+            .unwrap();
+            TokenRegistryTreeStore::set_leaf_fc(
+                &mut store,
+                checkpoint_id,
+                1,
+                CityHash::from_values(checkpoint_id * 4, 1, 0, 0),
+            )
+            .unwrap();
+            CityStore::insert_user_public_key(&mut store, checkpoint_id, public_key(checkpoint_id))
+                .unwrap();
+            CityStore::init_token_state(&mut store, checkpoint_id).unwrap();
+            CityStore::set_block_state(
+                &mut store,
+                &CityL2BlockState {
+                    checkpoint_id,
+                    next_user_id: checkpoint_id * 2,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        }
+        store
+    }
+
+    #[test]
+    fn snapshot_roundtrip() {
+        let store = build_store();
+        let checkpoint_id = 2;
+        let snapshot = CityStore::export_snapshot(&store, checkpoint_id).unwrap();
+        assert_eq!(snapshot.user_leaves.len(), 5);
+        assert_eq!(snapshot.deposit_leaves.len(), 2);
+        assert_eq!(snapshot.withdrawal_leaves.len(), 2);
+        assert_eq!(snapshot.withdrawal_records.len(), 2);
+        assert_eq!(snapshot.user_public_key_leaves.len(), 3);
+        assert_eq!(snapshot.token_registry_leaves.len(), 1);
+        assert_eq!(snapshot.token_balance_leaves.len(), 2);
+
+        let bytes = snapshot.to_file_bytes().unwrap();
+        let decoded = CityStateSnapshot::from_file_bytes(&bytes).unwrap();
+
+        let mut imported = S::new();
+        let city_root = CityStore::import_snapshot(&mut imported, &decoded).unwrap();
+        assert_eq!(
+            city_root,
+            CityStore::get_city_root(&store, checkpoint_id).unwrap()
+        );
+        assert_eq!(
+            CityStore::get_city_root(&imported, checkpoint_id).unwrap(),
+            city_root
+        );
+        for index in 0..6u64 {
+            assert_eq!(
+                GlobalUserTreeStore::get_leaf_fc(&imported, checkpoint_id, index).unwrap(),
+                GlobalUserTreeStore::get_leaf_fc(&store, checkpoint_id, index).unwrap()
+            );
+        }
+        assert_eq!(
+            CityStore::get_token_balance(&imported, checkpoint_id, 1, 1).unwrap(),
+            CityStore::get_token_balance(&store, checkpoint_id, 1, 1).unwrap()
+        );
+        assert!(CityStore::get_public_key_membership_proof(
+            &imported,
+            checkpoint_id,
+            public_key(1)
+        )
+        .is_ok());
+        assert!(CityStore::get_public_key_non_membership_proof(
+            &imported,
+            checkpoint_id,
+            public_key(3)
+        )
+        .is_ok());
+        // inserting the next key gives the same tree as in the exported store
+        CityStore::insert_user_public_key(&mut imported, checkpoint_id + 1, public_key(3)).unwrap();
+        assert_eq!(
+            CityStore::get_user_public_key_tree_root(&imported, checkpoint_id + 1).unwrap(),
+            CityStore::get_user_public_key_tree_root(&store, checkpoint_id + 1).unwrap()
+        );
+        assert_eq!(
+            CityStore::get_latest_block_state(&imported)
+                .unwrap()
+                .next_user_id,
+            4
+        );
+        assert_eq!(
+            CityStore::get_schema_version(&imported).unwrap(),
+            Some(CITY_STORE_SCHEMA_VERSION)
+        );
+
+        let mut legacy = S::new();
+        CityStore::set_schema_version(&mut legacy, LEGACY_SCHEMA_VERSION).unwrap();
+        assert!(CityStore::import_snapshot(&mut legacy, &decoded).is_err());
+    }
+
+    #[test]
+    fn snapshot_keeps_pending_withdrawals_processable() {
+        let checkpoint_id = 2;
+        let mut store = build_store_until(checkpoint_id);
+        let snapshot = CityStore::export_snapshot(&store, checkpoint_id).unwrap();
+        let mut imported = S::new();
+        CityStore::import_snapshot(&mut imported, &snapshot).unwrap();
+
+        for withdrawal_id in 0..2u64 {
+            let pending =
+                CityStore::get_pending_withdrawal_with_owner(&store, checkpoint_id, withdrawal_id)
+                    .unwrap();
+            assert!(pending.is_some());
+            assert_eq!(
+                CityStore::get_pending_withdrawal_with_owner(
+                    &imported,
+                    checkpoint_id,
+                    withdrawal_id
+                )
+                .unwrap(),
+                pending
+            );
+        }
+        // withdrawal 1 was added at checkpoint 2
+        let destination = BTCAddress160::new_p2pkh(Hash160([2u8; 20]));
+        assert_eq!(
+            CityStore::get_withdrawal_ids_for_destination(&imported, checkpoint_id, destination)
+                .unwrap(),
+            vec![1]
+        );
+
+        // processing the next withdrawal in the next block gives the same withdrawal tree
+        for store in [&mut store, &mut imported] {
+            let (withdrawal, owner) =
+                CityStore::get_pending_withdrawal_with_owner(store, checkpoint_id + 1, 0)
+                    .unwrap()
+                    .unwrap();
+            assert_eq!((withdrawal.value, owner), (1001, 1));
+            CityStore::mark_withdrawal_as_completed(store, checkpoint_id + 1, 0).unwrap();
+        }
+        assert_eq!(
+            CityStore::get_withdrawal_tree_root(&imported, checkpoint_id + 1).unwrap(),
+            CityStore::get_withdrawal_tree_root(&store, checkpoint_id + 1).unwrap()
+        );
+        assert!(
+            CityStore::get_pending_withdrawal_with_owner(&imported, checkpoint_id + 1, 0)
+                .unwrap()
+                .is_none()
+        );
+
+        // a pending withdrawal without its record is rejected
+        let mut snapshot = CityStore::export_snapshot(&store, checkpoint_id).unwrap();
+        snapshot.withdrawal_records.pop();
+        assert!(CityStore::import_snapshot(&mut S::new(), &snapshot).is_err());
+    }
+
+    #[test]
+    fn snapshot_rejects_corrupted_file() {
+        let store = build_store();
+        let mut bytes = CityStore::export_snapshot(&store, 3)
+            .unwrap()
+            .to_file_bytes()
+            .unwrap();
+        let last = bytes.len() - 2;
+        bytes[last] ^= 1;
+        assert!(CityStateSnapshot::from_file_bytes(&bytes).is_err());
+        assert!(CityStateSnapshot::from_file_bytes(&bytes[0..6]).is_err());
+    }
+}
@@ -0,0 +1,462 @@
+use city_common::config::rollup_constants::{TOKEN_REGISTRY_TREE_HEIGHT, TOKEN_STATE_USER_ID};
+use city_crypto::hash::{
+    qhashout::QHashOut,
+    traits::hasher::{MerkleHasher, PoseidonHasher},
+};
+use city_rollup_common::{
+    api::data::store::{CityTokenBalance, CityTokenInfo},
+    qworker::job_witnesses::op::CRTokenStateTransitionInput,
+};
+use kvq::traits::{KVQBinaryStore, KVQBinaryStoreReader};
+use plonky2::field::types::{Field, Field64, PrimeField64};
+
+use crate::{
+    config::{
+        CityDeltaMerkleProof, CityHash, GlobalUserTreeStore, TokenBalanceTreeStore,
+        TokenRegistryTreeStore, F,
+    },
+    models::kvq_merkle::model::{
+        KVQFixedConfigMerkleTreeModelCore, KVQFixedConfigMerkleTreeModelReaderCore,
+    },
+};
+
+use super::base::CityStore;
+
+// registry leaves are [supply, 1, 0, 0] and balance leaves are [balance, 1, 0, 0], the second
+// element marks a registered token/an opened account so a zero supply/balance is distinguishable from a missing leaf
+fn token_leaf(amount: u64) -> CityHash {
+    QHashOut::from_values(amount, 1, 0, 0)
+}
+fn token_leaf_amount(leaf: CityHash) -> Option<u64> {
+    if leaf.0.elements[1] == F::ONE {
+        Some(leaf.0.elements[0].to_canonical_u64())
+    } else {
+        None
+    }
+}
+fn checked_token_add(amount: u64, delta: u64) -> anyhow::Result<u64> {
+    match amount.checked_add(delta) {
+        Some(result) if result < F::ORDER => Ok(result),
+        _ => anyhow::bail!("Token amount overflow"),
+    }
+}
+
+pub fn get_token_balance_index(user_id: u64, token_id: u64) -> anyhow::Result<u64> {
+    let max_id = 1u64 << TOKEN_REGISTRY_TREE_HEIGHT;
+    if user_id >= max_id || token_id >= max_id {
+        anyhow::bail!(
+            "user id {} or token id {} does not fit in the token balance tree",
+            user_id,
+            token_id
+        );
+    }
+    Ok((user_id << TOKEN_REGISTRY_TREE_HEIGHT) + token_id)
+}
+pub fn get_token_state_leaf(
+    token_registry_root: CityHash,
+    token_balance_root: CityHash,
+) -> CityHash {
+    PoseidonHasher::two_to_one(&token_registry_root, &token_balance_root)
+}
+
+impl<S: KVQBinaryStoreReader> CityStore<S> {
+    pub fn get_token_registry_root(store: &S, checkpoint_id: u64) -> anyhow::Result<CityHash> {
+        TokenRegistryTreeStore::<S>::get_root_fc(store, checkpoint_id)
+    }
+    pub fn get_token_balance_root(store: &S, checkpoint_id: u64) -> anyhow::Result<CityHash> {
+        TokenBalanceTreeStore::<S>::get_root_fc(store, checkpoint_id)
+    }
+    pub fn get_token_info_if_exists(
+        store: &S,
+        checkpoint_id: u64,
+        token_id: u64,
+    ) -> anyhow::Result<Option<CityTokenInfo>> {
+        if token_id >= (1u64 << TOKEN_REGISTRY_TREE_HEIGHT) {
+            return Ok(None);
+        }
+        let leaf = TokenRegistryTreeStore::<S>::get_leaf_value_fc(store, checkpoint_id, token_id)?;
+        Ok(token_leaf_amount(leaf).map(|supply| CityTokenInfo {
+            token_id,
+            issuer_user_id: token_id,
+            supply,
+        }))
+    }
+    pub fn get_token_info(
+        store: &S,
+        checkpoint_id: u64,
+        token_id: u64,
+    ) -> anyhow::Result<CityTokenInfo> {
+        match Self::get_token_info_if_exists(store, checkpoint_id, token_id)? {
+            Some(info) => Ok(info),
+            None => anyhow::bail!("Token {} does not exist", token_id),
+        }
+    }
+    pub fn get_token_balance_if_exists(
+        store: &S,
+        checkpoint_id: u64,
+        user_id: u64,
+        token_id: u64,
+    ) -> anyhow::Result<Option<CityTokenBalance>> {
+        let index = get_token_balance_index(user_id, token_id)?;
+        let leaf = TokenBalanceTreeStore::<S>::get_leaf_value_fc(store, checkpoint_id, index)?;
+        Ok(token_leaf_amount(leaf).map(|balance| CityTokenBalance {
+            user_id,
+            token_id,
+            balance,
+        }))
+    }
+    pub fn get_token_balance(
+        store: &S,
+        checkpoint_id: u64,
+        user_id: u64,
+        token_id: u64,
+    ) -> anyhow::Result<CityTokenBalance> {
+        match Self::get_token_balance_if_exists(store, checkpoint_id, user_id, token_id)? {
+            Some(balance) => Ok(balance),
+            None => anyhow::bail!(
+                "User {} does not hold an account for token {}",
+                user_id,
+                token_id
+            ),
+        }
+    }
+    fn ensure_token_signer(
+        store: &S,
+        checkpoint_id: u64,
+        user_id: u64,
+        nonce: u64,
+    ) -> anyhow::Result<()> {
+        let user = Self::get_user_by_id(store, checkpoint_id, user_id)?;
+        if user.public_key == QHashOut::ZERO {
+            anyhow::bail!("User {} is not registered", user_id);
+        }
+        if nonce <= user.nonce {
+            anyhow::bail!("Invalid nonce");
+        } else if nonce > F::ORDER {
+            anyhow::bail!("Nonce is too large");
+        }
+        Ok(())
+    }
+}
+
+impl<S: KVQBinaryStore> CityStore<S> {
+    // writes the commitment to the empty token trees, called once for the genesis checkpoint
+    pub fn init_token_state(
+        store: &mut S,
+        checkpoint_id: u64,
+    ) -> anyhow::Result<CityDeltaMerkleProof> {
+        Self::update_token_state_leaf(store, checkpoint_id)
+    }
+    fn update_token_state_leaf(
+        store: &mut S,
+        checkpoint_id: u64,
+    ) -> anyhow::Result<CityDeltaMerkleProof> {
+        let leaf = get_token_state_leaf(
+            Self::get_token_registry_root(store, checkpoint_id)?,
+            Self::get_token_balance_root(store, checkpoint_id)?,
+        );
+        GlobalUserTreeStore::set_leaf_fc(store, checkpoint_id, TOKEN_STATE_USER_ID * 2, leaf)
+    }
+    // the checks are done by the callers before anything is written
+    fn apply_token_state_transition(
+        store: &mut S,
+        checkpoint_id: u64,
+        signer_user_id: u64,
+        nonce: u64,
+        token_id: u64,
+        new_supply: u64,
+        new_balances: &[(u64, u64)],
+    ) -> anyhow::Result<CRTokenStateTransitionInput<F>> {
+        let user_tree_delta_merkle_proof =
+            Self::increment_user_balance(store, checkpoint_id, signer_user_id, 0, Some(nonce))?;
+        let token_registry_delta_merkle_proof = TokenRegistryTreeStore::set_leaf_fc(
+            store,
+            checkpoint_id,
+            token_id,
+            token_leaf(new_supply),
+        )?;
+        let token_balance_delta_merkle_proofs = new_balances
+            .iter()
+            .map(|(user_id, balance)| {
+                TokenBalanceTreeStore::set_leaf_fc(
+                    store,
+                    checkpoint_id,
+                    get_token_balance_index(*user_id, token_id)?,
+                    token_leaf(*balance),
+                )
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let token_state_user_tree_delta_merkle_proof =
+            Self::update_token_state_leaf(store, checkpoint_id)?;
+        Ok(CRTokenStateTransitionInput {
+            user_tree_delta_merkle_proof,
+            token_state_user_tree_delta_merkle_proof,
+            token_registry_delta_merkle_proof,
+            token_balance_delta_merkle_proofs,
+        })
+    }
+    pub fn open_token_account(
+        store: &mut S,
+        checkpoint_id: u64,
+        user_id: u64,
+        token_id: u64,
+        nonce: u64,
+    ) -> anyhow::Result<CRTokenStateTransitionInput<F>> {
+        if token_id == TOKEN_STATE_USER_ID {
+            anyhow::bail!("Token id {} is reserved", token_id);
+        }
+        Self::ensure_token_signer(store, checkpoint_id, user_id, nonce)?;
+        if Self::get_token_balance_if_exists(store, checkpoint_id, user_id, token_id)?.is_some() {
+            anyhow::bail!(
+                "User {} already holds an account for token {}",
+                user_id,
+                token_id
+            );
+        }
+
+        // opening an account with token_id == user_id issues a new token
+        let token = Self::get_token_info_if_exists(store, checkpoint_id, token_id)?;
+        let supply = if token_id == user_id {
+            if token.is_some() {
+                anyhow::bail!("Token {} is already registered", token_id);
+            }
+            0
+        } else {
+            match token {
+                Some(token) => token.supply,
+                None => anyhow::bail!("Token {} does not exist", token_id),
+            }
+        };
+        Self::apply_token_state_transition(
+            store,
+            checkpoint_id,
+            user_id,
+            nonce,
+            token_id,
+            supply,
+            &[(user_id, 0)],
+        )
+    }
+    pub fn mint_token(
+        store: &mut S,
+        checkpoint_id: u64,
+        issuer_user_id: u64,
+        to: u64,
+        amount: u64,
+        nonce: u64,
+    ) -> anyhow::Result<CRTokenStateTransitionInput<F>> {
+        if amount == 0 {
+            anyhow::bail!("Mint amount must be greater than 0");
+        }
+        Self::ensure_token_signer(store, checkpoint_id, issuer_user_id, nonce)?;
+        let token_id = issuer_user_id;
+        let token = Self::get_token_info(store, checkpoint_id, token_id)?;
+        let receiver = Self::get_token_balance(store, checkpoint_id, to, token_id)?;
+        let new_supply = checked_token_add(token.supply, amount)?;
+        let new_balance = checked_token_add(receiver.balance, amount)?;
+
+        Self::apply_token_state_transition(
+            store,
+            checkpoint_id,
+            issuer_user_id,
+            nonce,
+            token_id,
+            new_supply,
+            &[(to, new_balance)],
+        )
+    }
+    pub fn send_token(
+        store: &mut S,
+        checkpoint_id: u64,
+        from: u64,
+        token_id: u64,
+        to: u64,
+        amount: u64,
+        nonce: u64,
+    ) -> anyhow::Result<CRTokenStateTransitionInput<F>> {
+        if from == to {
+            anyhow::bail!("Cannot send tokens to yourself");
+        }
+        if amount == 0 {
+            anyhow::bail!("Send amount must be greater than 0");
+        }
+        Self::ensure_token_signer(store, checkpoint_id, from, nonce)?;
+        let token = Self::get_token_info(store, checkpoint_id, token_id)?;
+        let sender = Self::get_token_balance(store, checkpoint_id, from, token_id)?;
+        let receiver = Self::get_token_balance(store, checkpoint_id, to, token_id)?;
+        if amount > sender.balance {
+            anyhow::bail!("Insufficient token balance");
+        }
+        let new_receiver_balance = checked_token_add(receiver.balance, amount)?;
+
+        Self::apply_token_state_transition(
+            store,
+            checkpoint_id,
+            from,
+            nonce,
+            token_id,
+            token.supply,
+            &[(from, sender.balance - amount), (to, new_receiver_balance)],
+        )
+    }
+    pub fn burn_token(
+        store: &mut S,
+        checkpoint_id: u64,
+        holder_user_id: u64,
+        token_id: u64,
+        amount: u64,
+        nonce: u64,
+    ) -> anyhow::Result<CRTokenStateTransitionInput<F>> {
+        if amount == 0 {
+            anyhow::bail!("Burn amount must be greater than 0");
+        }
+        Self::ensure_token_signer(store, checkpoint_id, holder_user_id, nonce)?;
+        let token = Self::get_token_info(store, checkpoint_id, token_id)?;
+        let holder = Self::get_token_balance(store, checkpoint_id, holder_user_id, token_id)?;
+        if amount > holder.balance {
+            anyhow::bail!("Insufficient token balance");
+        }
+        // the supply is the sum of all balances, so it can not be smaller than the holder's balance
+        Self::apply_token_state_transition(
+            store,
+            checkpoint_id,
+            holder_user_id,
+            nonce,
+            token_id,
+            token.supply - amount,
+            &[(holder_user_id, holder.balance - amount)],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use kvq::memory::simple::KVQSimpleMemoryBackingStore;
+
+    use super::*;
+    use crate::config::CityHasher;
+
+    type S = KVQSimpleMemoryBackingStore;
+
+    fn register(store: &mut S, checkpoint_id: u64, user_id: u64) {
+        CityStore::register_user(
+            store,
+            checkpoint_id,
+            user_id,
+            QHashOut::from_values(user_id, 7, 7, 7),
+        )
+        .unwrap();
+    }
+
+    fn assert_valid_transition(
+        store: &S,
+        checkpoint_id: u64,
+        input: &CRTokenStateTransitionInput<F>,
+    ) {
+        let token_state = &input.token_state_user_tree_delta_merkle_proof;
+        assert!(input.user_tree_delta_merkle_proof.verify::<CityHasher>());
+        assert!(token_state.verify::<CityHasher>());
+        assert!(input
+            .token_registry_delta_merkle_proof
+            .verify::<CityHasher>());
+        assert_eq!(token_state.index, TOKEN_STATE_USER_ID * 2);
+        assert_eq!(
+            input.user_tree_delta_merkle_proof.new_root,
+            token_state.old_root
+        );
+
+        let balance_proofs = &input.token_balance_delta_merkle_proofs;
+        assert!(balance_proofs.iter().all(|p| p.verify::<CityHasher>()));
+        for pair in balance_proofs.windows(2) {
+            assert_eq!(pair[0].new_root, pair[1].old_root);
+        }
+        assert_eq!(
+            token_state.old_value,
+            get_token_state_leaf(
+                input.token_registry_delta_merkle_proof.old_root,
+                balance_proofs[0].old_root
+            )
+        );
+        assert_eq!(
+            token_state.new_value,
+            get_token_state_leaf(
+                input.token_registry_delta_merkle_proof.new_root,
+                balance_proofs.last().unwrap().new_root
+            )
+        );
+        assert_eq!(
+            token_state.new_root,
+            CityStore::get_user_tree_root(store, checkpoint_id).unwrap()
+        );
+    }
+
+    #[test]
+    fn token_operations_update_the_committed_token_state() {
+        let mut store = S::new();
+        CityStore::init_token_state(&mut store, 0).unwrap();
+        for user_id in 1..=3 {
+            register(&mut store, 1, user_id);
+        }
+        assert!(CityStore::register_user(
+            &mut store,
+            1,
+            TOKEN_STATE_USER_ID,
+            QHashOut::from_values(1, 2, 3, 4)
+        )
+        .is_err());
+
+        // user 1 issues token 1, users 2 and 3 open accounts for it
+        let input = CityStore::open_token_account(&mut store, 1, 1, 1, 1).unwrap();
+        assert_valid_transition(&store, 1, &input);
+        assert_eq!(
+            input.token_registry_delta_merkle_proof.old_value,
+            QHashOut::ZERO
+        );
+        for user_id in 2..=3 {
+            let input = CityStore::open_token_account(&mut store, 1, user_id, 1, 1).unwrap();
+            assert_valid_transition(&store, 1, &input);
+            // a holder account leaves the registry unchanged
+            assert_eq!(
+                input.token_registry_delta_merkle_proof.old_value,
+                input.token_registry_delta_merkle_proof.new_value
+            );
+        }
+        assert!(CityStore::open_token_account(&mut store, 1, 2, 1, 2).is_err());
+        assert!(CityStore::open_token_account(&mut store, 1, 2, 3, 2).is_err());
+        assert!(CityStore::open_token_account(&mut store, 1, 1, 1, 2).is_err());
+
+        let input = CityStore::mint_token(&mut store, 2, 1, 2, 100, 2).unwrap();
+        assert_valid_transition(&store, 2, &input);
+        let input = CityStore::send_token(&mut store, 2, 2, 1, 3, 40, 2).unwrap();
+        assert_valid_transition(&store, 2, &input);
+        let input = CityStore::burn_token(&mut store, 2, 3, 1, 15, 2).unwrap();
+        assert_valid_transition(&store, 2, &input);
+
+        assert!(CityStore::send_token(&mut store, 2, 2, 1, 3, 61, 3).is_err());
+        assert!(CityStore::send_token(&mut store, 2, 2, 1, 2, 1, 3).is_err());
+        assert!(CityStore::burn_token(&mut store, 2, 3, 1, 1, 2).is_err());
+        assert!(CityStore::mint_token(&mut store, 2, 2, 3, 1, 3).is_err());
+
+        assert_eq!(CityStore::get_token_info(&store, 2, 1).unwrap().supply, 85);
+        assert_eq!(CityStore::get_token_info(&store, 1, 1).unwrap().supply, 0);
+        let balances = (1..=3)
+            .map(|user_id| {
+                CityStore::get_token_balance(&store, 2, user_id, 1)
+                    .unwrap()
+                    .balance
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(balances, vec![0, 60, 25]);
+        assert!(CityStore::get_token_balance(&store, 0, 2, 1).is_err());
+        assert!(CityStore::get_token_info(&store, 2, 2).is_err());
+
+        // the city root commits to the token trees through the token state leaf
+        assert_eq!(
+            CityStore::get_user_tree_leaf(&store, 2, TOKEN_STATE_USER_ID * 2).unwrap(),
+            get_token_state_leaf(
+                CityStore::get_token_registry_root(&store, 2).unwrap(),
+                CityStore::get_token_balance_root(&store, 2).unwrap()
+            )
+        );
+    }
+}
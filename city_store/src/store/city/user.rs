@@ -1,4 +1,10 @@
-use city_crypto::hash::qhashout::QHashOut;
+use city_common::config::rollup_constants::TOKEN_STATE_USER_ID;
+use city_crypto::hash::{
+    merkle::indexed::{
+        IndexedMerkleInsertProof, IndexedMerkleMembershipProof, IndexedMerkleNonMembershipProof,
+    },
+    qhashout::QHashOut,
+};
 use city_rollup_common::api::data::store::CityUserState;
 use kvq::traits::{KVQBinaryStore, KVQBinaryStoreReader};
 use plonky2::{
@@ -8,9 +14,11 @@ use plonky2::{
 
 use crate::{
     config::{
-        CityDeltaMerkleProof, CityHash, CityMerkleProof, GlobalUserTreeStore, L2UserIdsStore, F,
+        CityDeltaMerkleProof, CityHash, CityMerkleMultiProof, CityMerkleProof, GlobalUserTreeStore,
+        L2UserIdsStore, UserPublicKeyTreeStore, F,
     },
     models::{
+        indexed_merkle::model::{IndexedMerkleTreeModelCore, IndexedMerkleTreeModelReaderCore},
         kvq_merkle::model::{
             KVQFixedConfigMerkleTreeModelCore, KVQFixedConfigMerkleTreeModelReaderCore,
         },
@@ -58,6 +66,33 @@ impl<S: KVQBinaryStoreReader> CityStore<S> {
     ) -> anyhow::Result<CityMerkleProof> {
         GlobalUserTreeStore::<S>::get_leaf_fc(store, checkpoint_id, leaf_id)
     }
+    pub fn get_user_tree_multi_proof(
+        store: &S,
+        checkpoint_id: u64,
+        leaf_ids: &[u64],
+    ) -> anyhow::Result<CityMerkleMultiProof> {
+        GlobalUserTreeStore::<S>::get_multi_proof_fc(store, checkpoint_id, leaf_ids)
+    }
+    pub fn get_user_public_key_tree_root(
+        store: &S,
+        checkpoint_id: u64,
+    ) -> anyhow::Result<CityHash> {
+        UserPublicKeyTreeStore::<S>::get_root(store, checkpoint_id)
+    }
+    pub fn get_public_key_membership_proof(
+        store: &S,
+        checkpoint_id: u64,
+        public_key: CityHash,
+    ) -> anyhow::Result<IndexedMerkleMembershipProof<F>> {
+        UserPublicKeyTreeStore::<S>::get_membership_proof(store, checkpoint_id, public_key)
+    }
+    pub fn get_public_key_non_membership_proof(
+        store: &S,
+        checkpoint_id: u64,
+        public_key: CityHash,
+    ) -> anyhow::Result<IndexedMerkleNonMembershipProof<F>> {
+        UserPublicKeyTreeStore::<S>::get_non_membership_proof(store, checkpoint_id, public_key)
+    }
 }
 
 impl<S: KVQBinaryStore> CityStore<S> {
@@ -100,10 +135,37 @@ impl<S: KVQBinaryStore> CityStore<S> {
         user_id: u64,
         public_key: CityHash,
     ) -> anyhow::Result<CityDeltaMerkleProof> {
+        if user_id == TOKEN_STATE_USER_ID {
+            anyhow::bail!("User id {} is reserved for the token state", user_id);
+        }
         let leaf_id = user_id * 2;
         L2UserIdsStore::set_user_id_public_key_pair(store, user_id, public_key)?;
+        Self::add_used_public_key(store, checkpoint_id, public_key)?;
         GlobalUserTreeStore::set_leaf_fc(store, checkpoint_id, leaf_id + 1, public_key)
     }
+    // the public key tree holds every key which has been used by a user. users may share a public
+    // key, so it is added by the first of them, and rotated keys stay in the tree so they are never
+    // proven unused. the zero key can not be inserted (or signed with), so it is left out
+    fn add_used_public_key(
+        store: &mut S,
+        checkpoint_id: u64,
+        public_key: CityHash,
+    ) -> anyhow::Result<()> {
+        if public_key != CityHash::ZERO
+            && UserPublicKeyTreeStore::<S>::get_key_index(store, checkpoint_id, public_key)?
+                .is_none()
+        {
+            Self::insert_user_public_key(store, checkpoint_id, public_key)?;
+        }
+        Ok(())
+    }
+    pub fn insert_user_public_key(
+        store: &mut S,
+        checkpoint_id: u64,
+        public_key: CityHash,
+    ) -> anyhow::Result<IndexedMerkleInsertProof<F>> {
+        UserPublicKeyTreeStore::insert(store, checkpoint_id, public_key)
+    }
     pub fn decrement_user_balance(
         store: &mut S,
         checkpoint_id: u64,
@@ -178,4 +240,266 @@ impl<S: KVQBinaryStore> CityStore<S> {
 
         GlobalUserTreeStore::set_leaf_fc(store, checkpoint_id, leaf_id, new_user_leaf)
     }
+    pub fn change_user_public_key(
+        store: &mut S,
+        checkpoint_id: u64,
+        user_id: u64,
+        new_public_key: CityHash,
+        nonce: u64,
+    ) -> anyhow::Result<(CityDeltaMerkleProof, CityDeltaMerkleProof)> {
+        let leaf_id = user_id * 2;
+        let old_public_key =
+            GlobalUserTreeStore::get_leaf_value_fc(store, checkpoint_id, leaf_id + 1)?;
+        if old_public_key == QHashOut::ZERO {
+            anyhow::bail!("User {} is not registered", user_id);
+        }
+        if new_public_key == QHashOut::ZERO {
+            anyhow::bail!("New public key cannot be zero");
+        }
+
+        // the nonce is stored in the left leaf, so it is bumped first (balance stays the same)
+        let nonce_proof =
+            Self::increment_user_balance(store, checkpoint_id, user_id, 0, Some(nonce))?;
+        let public_key_proof =
+            GlobalUserTreeStore::set_leaf_fc(store, checkpoint_id, leaf_id + 1, new_public_key)?;
+
+        L2UserIdsStore::delete_user_id_public_key_pair(store, user_id, old_public_key)?;
+        L2UserIdsStore::set_user_id_public_key_pair(store, user_id, new_public_key)?;
+        Self::add_used_public_key(store, checkpoint_id, new_public_key)?;
+
+        Ok((nonce_proof, public_key_proof))
+    }
+    pub fn credit_sequencer_fee(
+        store: &mut S,
+        checkpoint_id: u64,
+        fee_recipient_user_id: u64,
+        fee: u64,
+    ) -> anyhow::Result<CityDeltaMerkleProof> {
+        Self::increment_user_balance(store, checkpoint_id, fee_recipient_user_id, fee, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use kvq::memory::simple::KVQSimpleMemoryBackingStore;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    use city_common::config::rollup_constants::USER_PUBLIC_KEY_TREE_HEIGHT;
+    use city_crypto::hash::merkle::indexed::IndexedMerkleTree;
+
+    use super::*;
+    use crate::config::CityHasher;
+
+    type S = KVQSimpleMemoryBackingStore;
+
+    #[test]
+    fn user_tree_multi_proof_matches_single_proofs() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0x43);
+        let mut store = S::new();
+        for checkpoint_id in 1..=3u64 {
+            for _ in 0..24 {
+                // cluster most leaves so some siblings are shared, scatter the rest
+                let index = if rng.gen_bool(0.75) {
+                    rng.gen_range(0..64u64)
+                } else {
+                    rng.gen_range(0..(1u64 << 32))
+                };
+                let value = QHashOut::from_values(rng.gen(), rng.gen(), rng.gen(), rng.gen());
+                GlobalUserTreeStore::<S>::set_leaf_fc(&mut store, checkpoint_id, index, value)
+                    .unwrap();
+            }
+        }
+
+        for _ in 0..32 {
+            let checkpoint_id = rng.gen_range(1..=3u64);
+            let leaf_ids = (0..rng.gen_range(1..12))
+                .map(|_| {
+                    if rng.gen_bool(0.75) {
+                        rng.gen_range(0..64u64)
+                    } else {
+                        rng.gen_range(0..(1u64 << 32))
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            let multi_proof =
+                CityStore::get_user_tree_multi_proof(&store, checkpoint_id, &leaf_ids).unwrap();
+            assert!(multi_proof.verify::<CityHasher>());
+
+            let single_proofs = multi_proof
+                .indices
+                .iter()
+                .map(|index| {
+                    CityStore::get_user_tree_leaf_merkle_proof(&store, checkpoint_id, *index)
+                        .unwrap()
+                })
+                .collect::<Vec<_>>();
+            assert!(single_proofs.iter().all(|p| p.verify::<CityHasher>()));
+            assert_eq!(
+                multi_proof.to_merkle_proofs::<CityHasher>().unwrap(),
+                single_proofs
+            );
+            assert!(
+                multi_proof.siblings.len()
+                    <= single_proofs
+                        .iter()
+                        .map(|p| p.siblings.len())
+                        .sum::<usize>()
+            );
+
+            // a changed leaf fails both the multi proof and the single proof of that leaf
+            let tampered_index = rng.gen_range(0..multi_proof.indices.len());
+            let tampered_value = QHashOut::from_values(rng.gen(), rng.gen(), 0, 1);
+            let mut tampered_multi_proof = multi_proof.clone();
+            tampered_multi_proof.values[tampered_index] = tampered_value;
+            let mut tampered_single_proof = single_proofs[tampered_index].clone();
+            tampered_single_proof.value = tampered_value;
+            assert!(!tampered_multi_proof.verify::<CityHasher>());
+            assert!(!tampered_single_proof.verify::<CityHasher>());
+        }
+    }
+
+    #[test]
+    fn public_key_tree_matches_in_memory_tree() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0x44);
+        let mut store = S::new();
+        let mut tree = IndexedMerkleTree::<F, CityHasher>::new(USER_PUBLIC_KEY_TREE_HEIGHT);
+        let mut inserted = Vec::new();
+        for checkpoint_id in 1..=3u64 {
+            for _ in 0..8 {
+                // small values so some keys only differ in the less significant elements
+                let public_key = QHashOut::from_values(
+                    rng.gen_range(0..4),
+                    rng.gen(),
+                    rng.gen_range(0..4),
+                    rng.gen_range(0..4),
+                );
+                let proof =
+                    CityStore::insert_user_public_key(&mut store, checkpoint_id, public_key)
+                        .unwrap();
+                assert!(proof.verify::<CityHasher>());
+                assert_eq!(proof, tree.insert(public_key).unwrap());
+                inserted.push((checkpoint_id, public_key));
+            }
+            assert_eq!(
+                CityStore::get_user_public_key_tree_root(&store, checkpoint_id).unwrap(),
+                tree.root()
+            );
+        }
+        let (_, duplicate) = inserted[3];
+        assert!(CityStore::insert_user_public_key(&mut store, 3, duplicate).is_err());
+        assert!(CityStore::insert_user_public_key(
+            &mut store,
+            2,
+            QHashOut::from_values(1, 2, 3, 4)
+        )
+        .is_err());
+
+        for (inserted_at, public_key) in inserted.iter() {
+            for checkpoint_id in 1..=3u64 {
+                if checkpoint_id >= *inserted_at {
+                    let proof = CityStore::get_public_key_membership_proof(
+                        &store,
+                        checkpoint_id,
+                        *public_key,
+                    )
+                    .unwrap();
+                    assert!(proof.verify::<CityHasher>());
+                    assert!(CityStore::get_public_key_non_membership_proof(
+                        &store,
+                        checkpoint_id,
+                        *public_key
+                    )
+                    .is_err());
+                } else {
+                    // the key was inserted later, so it is absent from this checkpoint
+                    let proof = CityStore::get_public_key_non_membership_proof(
+                        &store,
+                        checkpoint_id,
+                        *public_key,
+                    )
+                    .unwrap();
+                    assert!(proof.verify::<CityHasher>());
+                    assert_eq!(
+                        proof.low_leaf_proof.root,
+                        CityStore::get_user_public_key_tree_root(&store, checkpoint_id).unwrap()
+                    );
+                }
+            }
+        }
+
+        for _ in 0..16 {
+            let public_key = QHashOut::from_values(rng.gen(), rng.gen(), rng.gen(), rng.gen());
+            let proof =
+                CityStore::get_public_key_non_membership_proof(&store, 3, public_key).unwrap();
+            assert!(proof.verify::<CityHasher>());
+            assert_eq!(proof, tree.get_non_membership_proof(&public_key).unwrap());
+        }
+    }
+
+    #[test]
+    fn registered_public_keys_are_in_the_public_key_tree() {
+        let mut store = S::new();
+        let shared_key = QHashOut::from_values(7, 7, 7, 7);
+        CityStore::register_user(&mut store, 1, 1, shared_key).unwrap();
+        CityStore::register_user(&mut store, 1, 2, shared_key).unwrap();
+        CityStore::register_user(&mut store, 2, 3, QHashOut::from_values(3, 3, 3, 3)).unwrap();
+        CityStore::register_user(&mut store, 2, 4, QHashOut::ZERO).unwrap();
+
+        let mut tree = IndexedMerkleTree::<F, CityHasher>::new(USER_PUBLIC_KEY_TREE_HEIGHT);
+        tree.insert(shared_key).unwrap();
+        assert_eq!(
+            CityStore::get_user_public_key_tree_root(&store, 1).unwrap(),
+            tree.root()
+        );
+        tree.insert(QHashOut::from_values(3, 3, 3, 3)).unwrap();
+        assert_eq!(
+            CityStore::get_user_public_key_tree_root(&store, 2).unwrap(),
+            tree.root()
+        );
+        assert!(
+            CityStore::get_public_key_membership_proof(&store, 2, shared_key)
+                .unwrap()
+                .verify::<CityHasher>()
+        );
+    }
+
+    #[test]
+    fn rotated_public_keys_are_in_the_public_key_tree() {
+        let mut store = S::new();
+        let old_key = QHashOut::from_values(7, 7, 7, 7);
+        let new_key = QHashOut::from_values(8, 8, 8, 8);
+        CityStore::register_user(&mut store, 1, 1, old_key).unwrap();
+        CityStore::change_user_public_key(&mut store, 2, 1, new_key, 1).unwrap();
+        // rotating back to a key which is already in the tree leaves the tree unchanged
+        CityStore::change_user_public_key(&mut store, 3, 1, old_key, 2).unwrap();
+
+        let mut tree = IndexedMerkleTree::<F, CityHasher>::new(USER_PUBLIC_KEY_TREE_HEIGHT);
+        tree.insert(old_key).unwrap();
+        assert!(CityStore::get_public_key_non_membership_proof(&store, 1, new_key).is_ok());
+        tree.insert(new_key).unwrap();
+        for checkpoint_id in 2..=3 {
+            assert_eq!(
+                CityStore::get_user_public_key_tree_root(&store, checkpoint_id).unwrap(),
+                tree.root()
+            );
+            for public_key in [old_key, new_key] {
+                assert!(CityStore::get_public_key_membership_proof(
+                    &store,
+                    checkpoint_id,
+                    public_key
+                )
+                .unwrap()
+                .verify::<CityHasher>());
+            }
+        }
+        assert_eq!(
+            CityStore::get_user_ids_for_public_key(&store, old_key).unwrap(),
+            vec![1]
+        );
+        assert!(CityStore::get_user_ids_for_public_key(&store, new_key)
+            .unwrap()
+            .is_empty());
+    }
 }
@@ -1,28 +1,115 @@
-use city_rollup_common::api::data::{
-    block::requested_actions::CityAddWithdrawalRequest, store::CityL1Withdrawal,
+use city_crypto::hash::base_types::hash160::Hash160;
+use city_rollup_common::{
+    api::data::{block::requested_actions::CityAddWithdrawalRequest, store::CityL1Withdrawal},
+    link::data::BTCAddress160,
 };
 use kvq::traits::{KVQBinaryStore, KVQBinaryStoreReader};
 
 use crate::{
-    config::{CityDeltaMerkleProof, CityHash, CityMerkleProof, L1WithdrawalTreeStore},
-    models::kvq_merkle::model::{
-        KVQFixedConfigMerkleTreeModelCore, KVQFixedConfigMerkleTreeModelReaderCore,
+    config::{
+        CityDeltaMerkleProof, CityHash, CityMerkleProof, L1WithdrawalDestinationsStore,
+        L1WithdrawalIdsByDestinationStore, L1WithdrawalRecordsStore, L1WithdrawalTreeStore,
+        L1_WITHDRAWAL_DESTINATION_SIZE, L1_WITHDRAWAL_RECORD_SIZE,
+    },
+    models::{
+        kvq_merkle::model::{
+            KVQFixedConfigMerkleTreeModelCore, KVQFixedConfigMerkleTreeModelReaderCore,
+        },
+        l1_index::model::{
+            L1IdsByOwnerModelCore, L1IdsByOwnerModelReaderCore, L1OwnerByIdModelCore,
+            L1OwnerByIdModelReaderCore,
+        },
     },
 };
 
 use super::base::CityStore;
 
+fn withdrawal_destination_bytes(
+    address_type: u8,
+    address: &Hash160,
+) -> [u8; L1_WITHDRAWAL_DESTINATION_SIZE] {
+    let mut result = [0u8; L1_WITHDRAWAL_DESTINATION_SIZE];
+    result[0] = address_type;
+    result[1..].copy_from_slice(&address.0);
+    result
+}
+
+fn withdrawal_record_bytes(
+    withdrawal: &CityL1Withdrawal,
+    owner_user_id: u64,
+) -> [u8; L1_WITHDRAWAL_RECORD_SIZE] {
+    let mut result = [0u8; L1_WITHDRAWAL_RECORD_SIZE];
+    result[0..21].copy_from_slice(&withdrawal_destination_bytes(
+        withdrawal.address_type,
+        &withdrawal.address,
+    ));
+    result[21..29].copy_from_slice(&withdrawal.value.to_le_bytes());
+    result[29..37].copy_from_slice(&owner_user_id.to_le_bytes());
+    result
+}
+
+fn withdrawal_from_record_bytes(
+    withdrawal_id: u64,
+    bytes: &[u8; L1_WITHDRAWAL_RECORD_SIZE],
+) -> (CityL1Withdrawal, u64) {
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&bytes[1..21]);
+    let withdrawal = CityL1Withdrawal {
+        withdrawal_id,
+        address: Hash160(address),
+        address_type: bytes[0],
+        value: u64::from_le_bytes(bytes[21..29].try_into().unwrap()),
+    };
+    let owner_user_id = u64::from_le_bytes(bytes[29..37].try_into().unwrap());
+    (withdrawal, owner_user_id)
+}
+
 impl<S: KVQBinaryStoreReader> CityStore<S> {
     pub fn get_withdrawal_tree_root(store: &S, checkpoint_id: u64) -> anyhow::Result<CityHash> {
         L1WithdrawalTreeStore::<S>::get_root_fc(store, checkpoint_id)
     }
+    // the pending withdrawal and the user who added it, None if the withdrawal was processed or
+    // cancelled
+    pub fn get_pending_withdrawal_with_owner(
+        store: &S,
+        checkpoint_id: u64,
+        withdrawal_id: u64,
+    ) -> anyhow::Result<Option<(CityL1Withdrawal, u64)>> {
+        let leaf = L1WithdrawalTreeStore::get_leaf_value_fc(store, checkpoint_id, withdrawal_id)?;
+        Self::get_withdrawal_with_owner_for_leaf(store, checkpoint_id, withdrawal_id, leaf)
+    }
+    fn get_withdrawal_with_owner_for_leaf(
+        store: &S,
+        checkpoint_id: u64,
+        withdrawal_id: u64,
+        leaf: CityHash,
+    ) -> anyhow::Result<Option<(CityL1Withdrawal, u64)>> {
+        if leaf == CityHash::ZERO {
+            return Ok(None);
+        }
+        let record =
+            L1WithdrawalRecordsStore::get_owner_by_id(store, checkpoint_id, withdrawal_id)?
+                .ok_or_else(|| anyhow::anyhow!("withdrawal {} has no record", withdrawal_id))?;
+        let (withdrawal, owner_user_id) = withdrawal_from_record_bytes(withdrawal_id, &record);
+        if withdrawal.get_leaf_hash(owner_user_id) != leaf {
+            anyhow::bail!(
+                "the record of withdrawal {} does not match its leaf",
+                withdrawal_id
+            );
+        }
+        Ok(Some((withdrawal, owner_user_id)))
+    }
     pub fn get_withdrawal_by_id(
         store: &S,
         checkpoint_id: u64,
         withdrawal_id: u64,
     ) -> anyhow::Result<CityL1Withdrawal> {
-        let leaf = L1WithdrawalTreeStore::get_leaf_value_fc(store, checkpoint_id, withdrawal_id)?;
-        Ok(CityL1Withdrawal::from_hash(withdrawal_id, leaf))
+        Ok(
+            match Self::get_pending_withdrawal_with_owner(store, checkpoint_id, withdrawal_id)? {
+                Some((withdrawal, _)) => withdrawal,
+                None => CityL1Withdrawal::from_hash(withdrawal_id, CityHash::ZERO),
+            },
+        )
     }
     pub fn get_withdrawals_by_id(
         store: &S,
@@ -31,18 +118,37 @@ impl<S: KVQBinaryStoreReader> CityStore<S> {
     ) -> anyhow::Result<Vec<CityL1Withdrawal>> {
         let leaves =
             L1WithdrawalTreeStore::get_leaf_values_fc(store, checkpoint_id, withdrawal_ids)?;
-        Ok(leaves
+        leaves
             .iter()
             .zip(withdrawal_ids)
-            .map(|(leaf, withdrawal_id)| CityL1Withdrawal::from_hash(*withdrawal_id, *leaf))
-            .collect::<Vec<_>>())
+            .map(|(leaf, withdrawal_id)| {
+                Ok(
+                    match Self::get_withdrawal_with_owner_for_leaf(
+                        store,
+                        checkpoint_id,
+                        *withdrawal_id,
+                        *leaf,
+                    )? {
+                        Some((withdrawal, _)) => withdrawal,
+                        None => CityL1Withdrawal::from_hash(*withdrawal_id, CityHash::ZERO),
+                    },
+                )
+            })
+            .collect()
     }
+    // the hash of the withdrawal's L1 output which the user signs, zero if the withdrawal is not
+    // pending
     pub fn get_withdrawal_hash(
         store: &S,
         checkpoint_id: u64,
         withdrawal_id: u64,
     ) -> anyhow::Result<CityHash> {
-        L1WithdrawalTreeStore::get_leaf_value_fc(store, checkpoint_id, withdrawal_id)
+        Ok(
+            match Self::get_pending_withdrawal_with_owner(store, checkpoint_id, withdrawal_id)? {
+                Some((withdrawal, _)) => CityHash::from(&withdrawal),
+                None => CityHash::ZERO,
+            },
+        )
     }
     pub fn get_withdrawal_leaf_merkle_proof(
         store: &S,
@@ -51,21 +157,75 @@ impl<S: KVQBinaryStoreReader> CityStore<S> {
     ) -> anyhow::Result<CityMerkleProof> {
         L1WithdrawalTreeStore::get_leaf_fc(store, checkpoint_id, withdrawal_id)
     }
+    pub fn get_withdrawal_ids_for_destination(
+        store: &S,
+        checkpoint_id: u64,
+        destination: BTCAddress160,
+    ) -> anyhow::Result<Vec<u64>> {
+        let destination_bytes =
+            withdrawal_destination_bytes(destination.address_type.into(), &destination.address);
+        let candidates = L1WithdrawalIdsByDestinationStore::get_ids_for_owner(
+            store,
+            checkpoint_id,
+            destination_bytes,
+        )?;
+        let mut result = Vec::with_capacity(candidates.len());
+        for withdrawal_id in candidates {
+            let current_destination = L1WithdrawalDestinationsStore::get_owner_by_id(
+                store,
+                checkpoint_id,
+                withdrawal_id,
+            )?;
+            if current_destination == Some(destination_bytes) {
+                result.push(withdrawal_id);
+            }
+        }
+        Ok(result)
+    }
 }
 
 impl<S: KVQBinaryStore> CityStore<S> {
+    // the preimage of the withdrawal's leaf and its entry in the destination index, without the
+    // leaf itself
+    pub fn set_withdrawal_record(
+        store: &mut S,
+        checkpoint_id: u64,
+        withdrawal: &CityL1Withdrawal,
+        owner_user_id: u64,
+    ) -> anyhow::Result<()> {
+        let destination_bytes =
+            withdrawal_destination_bytes(withdrawal.address_type, &withdrawal.address);
+        L1WithdrawalIdsByDestinationStore::add_id_for_owner(
+            store,
+            checkpoint_id,
+            destination_bytes,
+            withdrawal.withdrawal_id,
+        )?;
+        L1WithdrawalDestinationsStore::set_owner_by_id(
+            store,
+            checkpoint_id,
+            withdrawal.withdrawal_id,
+            destination_bytes,
+        )?;
+        L1WithdrawalRecordsStore::set_owner_by_id(
+            store,
+            checkpoint_id,
+            withdrawal.withdrawal_id,
+            withdrawal_record_bytes(withdrawal, owner_user_id),
+        )
+    }
     pub fn set_withdrawal(
         store: &mut S,
         checkpoint_id: u64,
         withdrawal: &CityL1Withdrawal,
+        owner_user_id: u64,
     ) -> anyhow::Result<CityDeltaMerkleProof> {
-        let withdrawal_hash: CityHash = withdrawal.into();
-
+        Self::set_withdrawal_record(store, checkpoint_id, withdrawal, owner_user_id)?;
         L1WithdrawalTreeStore::set_leaf_fc(
             store,
             checkpoint_id,
             withdrawal.withdrawal_id,
-            withdrawal_hash,
+            withdrawal.get_leaf_hash(owner_user_id),
         )
     }
     pub fn add_withdrawal_to_tree_from_request(
@@ -80,7 +240,7 @@ impl<S: KVQBinaryStore> CityStore<S> {
             address_type: req.destination_type,
             value: req.value,
         };
-        Self::set_withdrawal(store, checkpoint_id, &withdrawal)
+        Self::set_withdrawal(store, checkpoint_id, &withdrawal, req.user_id)
     }
     pub fn mark_withdrawal_as_completed(
         store: &mut S,
@@ -89,4 +249,111 @@ impl<S: KVQBinaryStore> CityStore<S> {
     ) -> anyhow::Result<CityDeltaMerkleProof> {
         L1WithdrawalTreeStore::set_leaf_fc(store, checkpoint_id, withdrawal_id, CityHash::ZERO)
     }
+    pub fn cancel_withdrawal(
+        store: &mut S,
+        checkpoint_id: u64,
+        withdrawal_id: u64,
+    ) -> anyhow::Result<CityDeltaMerkleProof> {
+        let leaf = L1WithdrawalTreeStore::get_leaf_value_fc(store, checkpoint_id, withdrawal_id)?;
+        if leaf == CityHash::ZERO {
+            anyhow::bail!("withdrawal {} is not pending", withdrawal_id);
+        }
+        L1WithdrawalTreeStore::set_leaf_fc(store, checkpoint_id, withdrawal_id, CityHash::ZERO)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use city_rollup_common::qworker::job_id::QProvingJobDataID;
+    use kvq::memory::simple::KVQSimpleMemoryBackingStore;
+
+    use super::*;
+
+    type S = KVQSimpleMemoryBackingStore;
+
+    fn add_withdrawal(store: &mut S, checkpoint_id: u64, withdrawal_id: u64, to: BTCAddress160) {
+        let req = CityAddWithdrawalRequest::new(
+            1,
+            1000,
+            0,
+            withdrawal_id,
+            to.address_type.into(),
+            to.address,
+            QProvingJobDataID::withdrawal_signature_proof(0, checkpoint_id, withdrawal_id as u32),
+        );
+        CityStore::add_withdrawal_to_tree_from_request(store, checkpoint_id, withdrawal_id, &req)
+            .unwrap();
+    }
+
+    #[test]
+    fn withdrawal_ids_are_indexed_by_destination() {
+        let mut store = S::new();
+        let alice = BTCAddress160::new_p2pkh(Hash160([1u8; 20]));
+        let bob = BTCAddress160::new_p2sh(Hash160([1u8; 20]));
+
+        add_withdrawal(&mut store, 1, 0, alice);
+        add_withdrawal(&mut store, 1, 1, bob);
+        add_withdrawal(&mut store, 2, 2, alice);
+        for withdrawal_id in 0..3 {
+            CityStore::mark_withdrawal_as_completed(&mut store, 3, withdrawal_id).unwrap();
+        }
+        // the queue is empty, so withdrawal id 0 is given to bob
+        add_withdrawal(&mut store, 4, 0, bob);
+
+        assert_eq!(
+            CityStore::get_withdrawal_ids_for_destination(&store, 2, alice).unwrap(),
+            vec![0, 2]
+        );
+        assert_eq!(
+            CityStore::get_withdrawal_ids_for_destination(&store, 2, bob).unwrap(),
+            vec![1]
+        );
+        assert_eq!(
+            CityStore::get_withdrawal_ids_for_destination(&store, 4, alice).unwrap(),
+            vec![2]
+        );
+        assert_eq!(
+            CityStore::get_withdrawal_ids_for_destination(&store, 4, bob).unwrap(),
+            vec![0, 1]
+        );
+    }
+
+    #[test]
+    fn withdrawal_leaves_commit_to_their_owner() {
+        let mut store = S::new();
+        let alice = BTCAddress160::new_p2pkh(Hash160([1u8; 20]));
+        add_withdrawal(&mut store, 1, 0, alice);
+
+        let (withdrawal, owner) = CityStore::get_pending_withdrawal_with_owner(&store, 1, 0)
+            .unwrap()
+            .unwrap();
+        assert_eq!(owner, 1);
+        assert_eq!(withdrawal.value, 1000);
+        assert_eq!(withdrawal.address, alice.address);
+        let withdrawal_hash = CityStore::get_withdrawal_hash(&store, 1, 0).unwrap();
+        assert_eq!(withdrawal_hash, CityHash::from(&withdrawal));
+        assert_eq!(
+            CityStore::get_withdrawal_leaf_merkle_proof(&store, 1, 0)
+                .unwrap()
+                .value,
+            CityL1Withdrawal::leaf_hash_from_withdrawal_hash(withdrawal_hash, 1)
+        );
+        assert_ne!(
+            withdrawal.get_leaf_hash::<crate::config::F>(1),
+            withdrawal.get_leaf_hash::<crate::config::F>(2)
+        );
+
+        CityStore::cancel_withdrawal(&mut store, 2, 0).unwrap();
+        assert!(CityStore::get_pending_withdrawal_with_owner(&store, 2, 0)
+            .unwrap()
+            .is_none());
+        assert_eq!(
+            CityStore::get_withdrawal_hash(&store, 2, 0).unwrap(),
+            CityHash::ZERO
+        );
+        assert_eq!(
+            CityStore::get_withdrawal_by_id(&store, 1, 0).unwrap(),
+            withdrawal
+        );
+    }
 }
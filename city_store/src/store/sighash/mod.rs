@@ -2,7 +2,8 @@ use city_crypto::hash::{merkle::core::MerkleProofCore, qhashout::QHashOut};
 use city_rollup_common::{
     config::sighash_wrapper_config::{
         SIGHASH_CIRCUIT_FINGERPRINTS, SIGHASH_CIRCUIT_MAX_DEPOSITS,
-        SIGHASH_CIRCUIT_MAX_WITHDRAWALS, SIGHASH_CIRCUIT_WHITELIST_TREE_HEIGHT,
+        SIGHASH_CIRCUIT_MAX_FORCED_WITHDRAWAL_REQUESTS, SIGHASH_CIRCUIT_MAX_WITHDRAWALS,
+        SIGHASH_CIRCUIT_WHITELIST_TREE_HEIGHT,
     },
     introspection::rollup::introspection::{
         BlockSpendCoreConfig, SigHashGadgetId, SigHashGadgetIdWithIndex,
@@ -27,6 +28,7 @@ pub struct SigHashMerkleTree {
     pub tree_height: usize,
     pub max_deposits: usize,
     pub max_withdrawals: usize,
+    pub max_forced_withdrawal_requests: usize,
     pub root: QHashOut<F>,
 }
 type SigHashTreeStore = KVQFixedConfigMerkleTreeModel<
@@ -43,12 +45,46 @@ type SigHashTreeStore = KVQFixedConfigMerkleTreeModel<
 >;
 impl SigHashMerkleTree {
     pub fn new() -> Self {
+        Self::new_with_fingerprints(
+            SIGHASH_CIRCUIT_MAX_DEPOSITS,
+            SIGHASH_CIRCUIT_MAX_WITHDRAWALS,
+            SIGHASH_CIRCUIT_MAX_FORCED_WITHDRAWAL_REQUESTS,
+            &SIGHASH_CIRCUIT_FINGERPRINTS,
+        )
+        .unwrap()
+    }
+    // fingerprints are indexed in the order of generate_permutations/generate_id_permutations
+    pub fn new_with_fingerprints(
+        max_deposits: usize,
+        max_withdrawals: usize,
+        max_forced_withdrawal_requests: usize,
+        fingerprints: &[QHashOut<F>],
+    ) -> anyhow::Result<Self> {
         let tree_height = SIGHASH_CIRCUIT_WHITELIST_TREE_HEIGHT as usize;
-        let max_deposits = SIGHASH_CIRCUIT_MAX_DEPOSITS;
-        let max_withdrawals = SIGHASH_CIRCUIT_MAX_WITHDRAWALS;
+        let ids = BlockSpendCoreConfig::standard_p2sh_p2pkh().generate_id_permutations(
+            max_deposits,
+            max_withdrawals,
+            max_forced_withdrawal_requests,
+        );
+        if ids.len() != fingerprints.len() {
+            anyhow::bail!(
+                "expected {} sighash circuit fingerprints for max_deposits={}, max_withdrawals={}, max_forced_withdrawal_requests={}, got {}",
+                ids.len(),
+                max_deposits,
+                max_withdrawals,
+                max_forced_withdrawal_requests,
+                fingerprints.len()
+            );
+        }
+        if ids.len() > (1usize << tree_height) {
+            anyhow::bail!(
+                "{} sighash circuits do not fit in a whitelist tree of height {}",
+                ids.len(),
+                tree_height
+            );
+        }
         let mut store = KVQSimpleMemoryBackingStore::new();
-        let mut sorted_ids_with_index = BlockSpendCoreConfig::standard_p2sh_p2pkh()
-            .generate_id_permutations(max_deposits, max_withdrawals)
+        let mut sorted_ids_with_index = ids
             .into_iter()
             .enumerate()
             .map(|(i, x)| SigHashGadgetIdWithIndex {
@@ -57,29 +93,24 @@ impl SigHashMerkleTree {
             })
             .collect::<Vec<_>>();
         sorted_ids_with_index.sort_by(|a, b| a.gadget_id.cmp(&b.gadget_id));
-        sorted_ids_with_index.iter().enumerate().for_each(|(i, x)| {
-            SigHashTreeStore::set_leaf_fc(
-                &mut store,
-                0,
-                i as u64,
-                SIGHASH_CIRCUIT_FINGERPRINTS[x.index],
-            )
-            .unwrap();
-        });
+        for (i, x) in sorted_ids_with_index.iter().enumerate() {
+            SigHashTreeStore::set_leaf_fc(&mut store, 0, i as u64, fingerprints[x.index])?;
+        }
         let sorted_ids = sorted_ids_with_index
             .into_iter()
             .map(|x| x.gadget_id)
             .collect::<Vec<_>>();
-        let root = SigHashTreeStore::get_root_fc(&store, 0).unwrap();
+        let root = SigHashTreeStore::get_root_fc(&store, 0)?;
 
-        Self {
+        Ok(Self {
             store,
             sorted_ids,
             tree_height,
             max_deposits,
             max_withdrawals,
+            max_forced_withdrawal_requests,
             root,
-        }
+        })
     }
     pub fn get_proof_for_id_ref(
         &self,
@@ -0,0 +1,268 @@
+use std::sync::Arc;
+use std::sync::RwLock;
+
+use async_trait::async_trait;
+
+use crate::traits::KVQAsyncBinaryStoreReader;
+use crate::traits::KVQAsyncBinaryStoreWriter;
+use crate::traits::KVQBinaryStore;
+use crate::traits::KVQBinaryStoreReader;
+use crate::traits::KVQBinaryStoreWriter;
+use crate::traits::KVQPair;
+
+/// A source of synchronous store handles which can be used from tokio's blocking thread pool.
+///
+/// Backends which need a transaction per access (redb) open one in each call, in memory stores
+/// are shared behind a lock.
+pub trait KVQBlockingStoreProvider: Send + Sync + 'static {
+    fn with_reader<T>(
+        &self,
+        f: impl FnOnce(&dyn KVQBinaryStoreReader) -> anyhow::Result<T>,
+    ) -> anyhow::Result<T>;
+    fn with_writer<T>(
+        &self,
+        f: impl FnOnce(&mut dyn KVQBinaryStoreWriter) -> anyhow::Result<T>,
+    ) -> anyhow::Result<T>;
+}
+
+impl<S: KVQBinaryStore + Send + Sync + 'static> KVQBlockingStoreProvider for RwLock<S> {
+    fn with_reader<T>(
+        &self,
+        f: impl FnOnce(&dyn KVQBinaryStoreReader) -> anyhow::Result<T>,
+    ) -> anyhow::Result<T> {
+        let store = self
+            .read()
+            .map_err(|_| anyhow::anyhow!("store lock is poisoned"))?;
+        f(&*store)
+    }
+
+    fn with_writer<T>(
+        &self,
+        f: impl FnOnce(&mut dyn KVQBinaryStoreWriter) -> anyhow::Result<T>,
+    ) -> anyhow::Result<T> {
+        let mut store = self
+            .write()
+            .map_err(|_| anyhow::anyhow!("store lock is poisoned"))?;
+        f(&mut *store)
+    }
+}
+
+/// Implements the async store traits by running every call of a synchronous store on tokio's
+/// blocking thread pool, so slow reads never stall the async worker threads.
+pub struct KVQBlockingPoolStore<P> {
+    provider: Arc<P>,
+}
+
+impl<P> Clone for KVQBlockingPoolStore<P> {
+    fn clone(&self) -> Self {
+        Self {
+            provider: self.provider.clone(),
+        }
+    }
+}
+
+impl<P: KVQBlockingStoreProvider> KVQBlockingPoolStore<P> {
+    pub fn new(provider: P) -> Self {
+        Self::from_arc(Arc::new(provider))
+    }
+
+    pub fn from_arc(provider: Arc<P>) -> Self {
+        Self { provider }
+    }
+
+    pub fn provider(&self) -> &Arc<P> {
+        &self.provider
+    }
+
+    pub async fn run<T, F>(&self, f: F) -> anyhow::Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&P) -> anyhow::Result<T> + Send + 'static,
+    {
+        let provider = self.provider.clone();
+        tokio::task::spawn_blocking(move || f(&provider)).await?
+    }
+
+    pub async fn read<T, F>(&self, f: F) -> anyhow::Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&dyn KVQBinaryStoreReader) -> anyhow::Result<T> + Send + 'static,
+    {
+        self.run(move |provider| provider.with_reader(f)).await
+    }
+
+    pub async fn write<T, F>(&self, f: F) -> anyhow::Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut dyn KVQBinaryStoreWriter) -> anyhow::Result<T> + Send + 'static,
+    {
+        self.run(move |provider| provider.with_writer(f)).await
+    }
+}
+
+#[async_trait]
+impl<P: KVQBlockingStoreProvider> KVQAsyncBinaryStoreReader for KVQBlockingPoolStore<P> {
+    async fn get_exact_if_exists(&self, key: &Vec<u8>) -> anyhow::Result<Option<Vec<u8>>> {
+        let key = key.clone();
+        self.read(move |s| s.get_exact_if_exists(&key)).await
+    }
+
+    async fn get_exact(&self, key: &Vec<u8>) -> anyhow::Result<Vec<u8>> {
+        let key = key.clone();
+        self.read(move |s| s.get_exact(&key)).await
+    }
+
+    async fn get_many_exact(&self, keys: &[Vec<u8>]) -> anyhow::Result<Vec<Vec<u8>>> {
+        let keys = keys.to_vec();
+        self.read(move |s| s.get_many_exact(&keys)).await
+    }
+
+    async fn get_leq(&self, key: &Vec<u8>, fuzzy_bytes: usize) -> anyhow::Result<Option<Vec<u8>>> {
+        let key = key.clone();
+        self.read(move |s| s.get_leq(&key, fuzzy_bytes)).await
+    }
+
+    async fn get_fuzzy_range_leq_kv(
+        &self,
+        key: &Vec<u8>,
+        fuzzy_bytes: usize,
+    ) -> anyhow::Result<Vec<KVQPair<Vec<u8>, Vec<u8>>>> {
+        let key = key.clone();
+        self.read(move |s| s.get_fuzzy_range_leq_kv(&key, fuzzy_bytes))
+            .await
+    }
+
+    async fn get_leq_kv(
+        &self,
+        key: &Vec<u8>,
+        fuzzy_bytes: usize,
+    ) -> anyhow::Result<Option<KVQPair<Vec<u8>, Vec<u8>>>> {
+        let key = key.clone();
+        self.read(move |s| s.get_leq_kv(&key, fuzzy_bytes)).await
+    }
+
+    async fn get_many_leq(
+        &self,
+        keys: &[Vec<u8>],
+        fuzzy_bytes: usize,
+    ) -> anyhow::Result<Vec<Option<Vec<u8>>>> {
+        let keys = keys.to_vec();
+        self.read(move |s| s.get_many_leq(&keys, fuzzy_bytes)).await
+    }
+
+    async fn get_many_leq_kv(
+        &self,
+        keys: &[Vec<u8>],
+        fuzzy_bytes: usize,
+    ) -> anyhow::Result<Vec<Option<KVQPair<Vec<u8>, Vec<u8>>>>> {
+        let keys = keys.to_vec();
+        self.read(move |s| s.get_many_leq_kv(&keys, fuzzy_bytes))
+            .await
+    }
+}
+
+#[async_trait]
+impl<P: KVQBlockingStoreProvider> KVQAsyncBinaryStoreWriter for KVQBlockingPoolStore<P> {
+    async fn set(&mut self, key: Vec<u8>, value: Vec<u8>) -> anyhow::Result<()> {
+        self.write(move |s| s.set(key, value)).await
+    }
+
+    async fn set_ref(&mut self, key: &Vec<u8>, value: &Vec<u8>) -> anyhow::Result<()> {
+        let (key, value) = (key.clone(), value.clone());
+        self.write(move |s| s.set(key, value)).await
+    }
+
+    async fn set_many_ref<'a>(
+        &mut self,
+        items: &[KVQPair<&'a Vec<u8>, &'a Vec<u8>>],
+    ) -> anyhow::Result<()> {
+        let items = items
+            .iter()
+            .map(|kv| KVQPair {
+                key: kv.key.clone(),
+                value: kv.value.clone(),
+            })
+            .collect::<Vec<_>>();
+        self.write(move |s| s.set_many_vec(items)).await
+    }
+
+    async fn set_many_vec(&mut self, items: Vec<KVQPair<Vec<u8>, Vec<u8>>>) -> anyhow::Result<()> {
+        self.write(move |s| s.set_many_vec(items)).await
+    }
+
+    async fn set_many_split_ref(
+        &mut self,
+        keys: &[Vec<u8>],
+        values: &[Vec<u8>],
+    ) -> anyhow::Result<()> {
+        let (keys, values) = (keys.to_vec(), values.to_vec());
+        self.write(move |s| s.set_many_split_ref(&keys, &values))
+            .await
+    }
+
+    async fn delete(&mut self, key: &Vec<u8>) -> anyhow::Result<bool> {
+        let key = key.clone();
+        self.write(move |s| s.delete(&key)).await
+    }
+
+    async fn delete_many(&mut self, keys: &[Vec<u8>]) -> anyhow::Result<Vec<bool>> {
+        let keys = keys.to_vec();
+        self.write(move |s| s.delete_many(&keys)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::standard_async::KVQAsyncStandardAdapter;
+    use crate::memory::simple::KVQSimpleMemoryBackingStore;
+    use crate::traits::KVQAsyncStoreAdapter;
+    use crate::traits::KVQAsyncStoreAdapterReader;
+
+    type TestStore = KVQBlockingPoolStore<RwLock<KVQSimpleMemoryBackingStore>>;
+    type TestAdapter = KVQAsyncStandardAdapter<TestStore, Vec<u8>, Vec<u8>>;
+
+    #[tokio::test]
+    async fn blocking_pool_store_matches_sync_store() {
+        let mut store = TestStore::new(RwLock::new(KVQSimpleMemoryBackingStore::new()));
+        TestAdapter::set(&mut store, vec![1, 0, 1], vec![10])
+            .await
+            .unwrap();
+        TestAdapter::set(&mut store, vec![1, 0, 3], vec![30])
+            .await
+            .unwrap();
+        TestAdapter::set(&mut store, vec![2, 0, 0], vec![40])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            TestAdapter::get_leq(&store, &vec![1, 0, 2], 1)
+                .await
+                .unwrap(),
+            Some(vec![10])
+        );
+        assert_eq!(
+            TestAdapter::get_leq(&store, &vec![1, 0, 0], 1)
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            TestAdapter::get_fuzzy_range_leq_kv(&store, &vec![1, 0, 0xff], 1)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|kv| kv.value)
+                .collect::<Vec<_>>(),
+            vec![vec![10], vec![30]]
+        );
+        assert!(TestAdapter::delete(&mut store, &vec![1, 0, 1])
+            .await
+            .unwrap());
+
+        // the async store shares its state with the synchronous one
+        let sync_store = store.provider().read().unwrap();
+        assert_eq!(sync_store.get_leq(&vec![1, 0, 2], 1).unwrap(), None);
+        assert_eq!(sync_store.get_exact(&vec![2, 0, 0]).unwrap(), vec![40]);
+    }
+}
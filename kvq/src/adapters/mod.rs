@@ -1 +1,3 @@
+pub mod blocking;
 pub mod standard;
+pub mod standard_async;
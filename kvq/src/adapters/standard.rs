@@ -2,7 +2,9 @@ use std::marker::PhantomData;
 
 use crate::traits::KVQBinaryStore;
 use crate::traits::KVQBinaryStoreReader;
+use crate::traits::KVQPage;
 use crate::traits::KVQPair;
+use crate::traits::KVQRange;
 use crate::traits::KVQSerializable;
 use crate::traits::KVQStoreAdapter;
 use crate::traits::KVQStoreAdapterReader;
@@ -121,6 +123,103 @@ impl<S: KVQBinaryStoreReader, K: KVQSerializable, V: KVQSerializable> KVQStoreAd
             })
             .collect()
     }
+
+    fn get_range_page(
+        s: &S,
+        start: &K,
+        end: &K,
+        cursor: Option<&Vec<u8>>,
+        page_size: usize,
+    ) -> anyhow::Result<KVQPage<K, V>> {
+        let range = KVQRange::new(start.to_bytes()?, Some(end.to_bytes()?));
+        decode_page(s.get_range_page(&range, cursor, page_size)?)
+    }
+
+    fn get_range_page_at_checkpoint(
+        s: &S,
+        start: &K,
+        end: &K,
+        checkpoint_id: u64,
+        cursor: Option<&Vec<u8>>,
+        page_size: usize,
+    ) -> anyhow::Result<KVQPage<K, V>> {
+        if page_size == 0 {
+            anyhow::bail!("page size must be greater than 0");
+        }
+        let start = split_checkpoint_id(&start.to_bytes()?)?.0.to_vec();
+        let end = split_checkpoint_id(&end.to_bytes()?)?.0.to_vec();
+        let range = KVQRange::new(
+            with_checkpoint_id(&start, 0),
+            Some(with_checkpoint_id(&end, 0)),
+        );
+        // skip every version of the key at the cursor
+        let mut raw_cursor = match cursor {
+            Some(cursor) => Some(with_checkpoint_id(split_checkpoint_id(cursor)?.0, u64::MAX)),
+            None => None,
+        };
+
+        // the versions of a key are adjacent and sorted by checkpoint id, so the last version at or
+        // before checkpoint_id is kept once the scan moves on to the next key
+        let mut items: Vec<KVQPair<Vec<u8>, Vec<u8>>> = Vec::new();
+        let mut current_key: Option<Vec<u8>> = None;
+        let mut current_version: Option<KVQPair<Vec<u8>, Vec<u8>>> = None;
+        loop {
+            let page = s.get_range_page(&range, raw_cursor.as_ref(), page_size)?;
+            for kv in page.items {
+                let (key, version) = split_checkpoint_id(&kv.key)?;
+                if current_key.as_deref() != Some(key) {
+                    items.extend(current_version.take());
+                    current_key = Some(key.to_vec());
+                }
+                if version <= checkpoint_id {
+                    current_version = Some(kv);
+                }
+            }
+            if items.len() > page_size {
+                break;
+            }
+            if page.cursor.is_none() {
+                items.extend(current_version.take());
+                break;
+            }
+            raw_cursor = page.cursor;
+        }
+        decode_page(KVQPage::from_lookahead(items, page_size)?)
+    }
+}
+
+fn decode_page<K: KVQSerializable, V: KVQSerializable>(
+    page: KVQPage<Vec<u8>, Vec<u8>>,
+) -> anyhow::Result<KVQPage<K, V>> {
+    Ok(KVQPage {
+        items: page
+            .items
+            .iter()
+            .map(|kv| {
+                Ok(KVQPair {
+                    key: K::from_bytes(&kv.key)?,
+                    value: V::from_bytes(&kv.value)?,
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?,
+        cursor: page.cursor,
+    })
+}
+
+// splits a key into the part before its big endian checkpoint id and the checkpoint id
+fn split_checkpoint_id(key: &[u8]) -> anyhow::Result<(&[u8], u64)> {
+    if key.len() < 8 {
+        anyhow::bail!("key {} has no checkpoint id", hex::encode(key));
+    }
+    let (key, checkpoint_id) = key.split_at(key.len() - 8);
+    Ok((key, u64::from_be_bytes(checkpoint_id.try_into().unwrap())))
+}
+
+fn with_checkpoint_id(key: &[u8], checkpoint_id: u64) -> Vec<u8> {
+    let mut result = Vec::with_capacity(key.len() + 8);
+    result.extend_from_slice(key);
+    result.extend_from_slice(&checkpoint_id.to_be_bytes());
+    result
 }
 
 impl<S: KVQBinaryStore, K: KVQSerializable, V: KVQSerializable> KVQStoreAdapter<S, K, V>
@@ -0,0 +1,174 @@
+use std::marker::PhantomData;
+
+use async_trait::async_trait;
+
+use crate::traits::KVQAsyncBinaryStore;
+use crate::traits::KVQAsyncBinaryStoreReader;
+use crate::traits::KVQAsyncStoreAdapter;
+use crate::traits::KVQAsyncStoreAdapterReader;
+use crate::traits::KVQPair;
+use crate::traits::KVQSerializable;
+
+pub struct KVQAsyncStandardAdapter<S, K: KVQSerializable, V: KVQSerializable> {
+    _s: PhantomData<S>,
+    _k: PhantomData<K>,
+    _v: PhantomData<V>,
+}
+
+fn keys_to_bytes<K: KVQSerializable>(keys: &[K]) -> anyhow::Result<Vec<Vec<u8>>> {
+    keys.iter().map(|k| k.to_bytes()).collect()
+}
+
+fn kv_from_bytes<K: KVQSerializable, V: KVQSerializable>(
+    kv: &KVQPair<Vec<u8>, Vec<u8>>,
+) -> anyhow::Result<KVQPair<K, V>> {
+    Ok(KVQPair {
+        key: K::from_bytes(&kv.key)?,
+        value: V::from_bytes(&kv.value)?,
+    })
+}
+
+fn kvs_to_bytes<'a, K: KVQSerializable + 'a, V: KVQSerializable + 'a>(
+    items: impl Iterator<Item = (&'a K, &'a V)>,
+) -> anyhow::Result<Vec<KVQPair<Vec<u8>, Vec<u8>>>> {
+    items
+        .map(|(key, value)| {
+            Ok(KVQPair {
+                key: key.to_bytes()?,
+                value: value.to_bytes()?,
+            })
+        })
+        .collect()
+}
+
+#[async_trait]
+impl<
+        S: KVQAsyncBinaryStoreReader,
+        K: KVQSerializable + Send + Sync,
+        V: KVQSerializable + Send + Sync,
+    > KVQAsyncStoreAdapterReader<S, K, V> for KVQAsyncStandardAdapter<S, K, V>
+{
+    async fn get_exact_if_exists(s: &S, key: &K) -> anyhow::Result<Option<V>> {
+        let key = key.to_bytes()?;
+        match s.get_exact_if_exists(&key).await? {
+            Some(v) => Ok(Some(V::from_bytes(&v)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn get_exact(s: &S, key: &K) -> anyhow::Result<V> {
+        let key = key.to_bytes()?;
+        V::from_bytes(&s.get_exact(&key).await?)
+    }
+
+    async fn get_many_exact(s: &S, keys: &[K]) -> anyhow::Result<Vec<V>> {
+        let keys_bytes = keys_to_bytes(keys)?;
+        s.get_many_exact(&keys_bytes)
+            .await?
+            .iter()
+            .map(|v| V::from_bytes(v))
+            .collect()
+    }
+
+    async fn get_fuzzy_range_leq_kv(
+        s: &S,
+        key: &K,
+        fuzzy_bytes: usize,
+    ) -> anyhow::Result<Vec<KVQPair<K, V>>> {
+        let key = key.to_bytes()?;
+        s.get_fuzzy_range_leq_kv(&key, fuzzy_bytes)
+            .await?
+            .iter()
+            .map(kv_from_bytes)
+            .collect()
+    }
+
+    async fn get_leq(s: &S, key: &K, fuzzy_bytes: usize) -> anyhow::Result<Option<V>> {
+        let key = key.to_bytes()?;
+        match s.get_leq(&key, fuzzy_bytes).await? {
+            Some(v) => Ok(Some(V::from_bytes(&v)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn get_leq_kv(
+        s: &S,
+        key: &K,
+        fuzzy_bytes: usize,
+    ) -> anyhow::Result<Option<KVQPair<K, V>>> {
+        let key = key.to_bytes()?;
+        s.get_leq_kv(&key, fuzzy_bytes)
+            .await?
+            .as_ref()
+            .map(kv_from_bytes)
+            .transpose()
+    }
+
+    async fn get_many_leq(s: &S, keys: &[K], fuzzy_bytes: usize) -> anyhow::Result<Vec<Option<V>>> {
+        let keys_bytes = keys_to_bytes(keys)?;
+        s.get_many_leq(&keys_bytes, fuzzy_bytes)
+            .await?
+            .iter()
+            .map(|r| r.as_ref().map(|v| V::from_bytes(v)).transpose())
+            .collect()
+    }
+
+    async fn get_many_leq_kv(
+        s: &S,
+        keys: &[K],
+        fuzzy_bytes: usize,
+    ) -> anyhow::Result<Vec<Option<KVQPair<K, V>>>> {
+        let keys_bytes = keys_to_bytes(keys)?;
+        s.get_many_leq_kv(&keys_bytes, fuzzy_bytes)
+            .await?
+            .iter()
+            .map(|r| r.as_ref().map(kv_from_bytes).transpose())
+            .collect()
+    }
+}
+
+#[async_trait]
+impl<
+        S: KVQAsyncBinaryStore,
+        K: KVQSerializable + Send + Sync,
+        V: KVQSerializable + Send + Sync,
+    > KVQAsyncStoreAdapter<S, K, V> for KVQAsyncStandardAdapter<S, K, V>
+{
+    async fn set(s: &mut S, key: K, value: V) -> anyhow::Result<()> {
+        let (key, value) = (key.to_bytes()?, value.to_bytes()?);
+        s.set(key, value).await
+    }
+
+    async fn set_ref(s: &mut S, key: &K, value: &V) -> anyhow::Result<()> {
+        let (key, value) = (key.to_bytes()?, value.to_bytes()?);
+        s.set(key, value).await
+    }
+
+    async fn set_many_ref<'a>(s: &mut S, items: &[KVQPair<&'a K, &'a V>]) -> anyhow::Result<()> {
+        let pairs = kvs_to_bytes(items.iter().map(|kv| (kv.key, kv.value)))?;
+        s.set_many_vec(pairs).await
+    }
+
+    async fn set_many_split_ref(s: &mut S, keys: &[K], values: &[V]) -> anyhow::Result<()> {
+        if keys.len() != values.len() {
+            return Err(anyhow::anyhow!("Keys and values must have the same length"));
+        }
+        let pairs = kvs_to_bytes(keys.iter().zip(values.iter()))?;
+        s.set_many_vec(pairs).await
+    }
+
+    async fn set_many(s: &mut S, items: &[KVQPair<K, V>]) -> anyhow::Result<()> {
+        let pairs = kvs_to_bytes(items.iter().map(|kv| (&kv.key, &kv.value)))?;
+        s.set_many_vec(pairs).await
+    }
+
+    async fn delete(s: &mut S, key: &K) -> anyhow::Result<bool> {
+        let key = key.to_bytes()?;
+        s.delete(&key).await
+    }
+
+    async fn delete_many(s: &mut S, keys: &[K]) -> anyhow::Result<Vec<bool>> {
+        let keys_bytes = keys_to_bytes(keys)?;
+        s.delete_many(&keys_bytes).await
+    }
+}
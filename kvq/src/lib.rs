@@ -1,4 +1,8 @@
+// lets the derived impls refer to ::kvq inside this crate
+extern crate self as kvq;
+
 pub mod adapters;
 pub mod base_types;
 pub mod memory;
+pub mod table;
 pub mod traits;
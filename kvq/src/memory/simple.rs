@@ -3,7 +3,9 @@ use std::ops::Bound::Included;
 
 use crate::traits::KVQBinaryStoreReader;
 use crate::traits::KVQBinaryStoreWriter;
+use crate::traits::KVQPage;
 use crate::traits::KVQPair;
+use crate::traits::KVQRange;
 
 pub struct KVQSimpleMemoryBackingStore {
     map: BTreeMap<Vec<u8>, Vec<u8>>,
@@ -202,6 +204,27 @@ impl KVQBinaryStoreReader for KVQSimpleMemoryBackingStore {
             })
             .collect::<Vec<_>>())
     }
+
+    fn get_range_page(
+        &self,
+        range: &KVQRange,
+        cursor: Option<&Vec<u8>>,
+        page_size: usize,
+    ) -> anyhow::Result<KVQPage<Vec<u8>, Vec<u8>>> {
+        let items = match range.bounds_after(cursor) {
+            Some(bounds) => self
+                .map
+                .range::<[u8], _>(bounds)
+                .take(page_size.saturating_add(1))
+                .map(|(k, v)| KVQPair {
+                    key: k.to_owned(),
+                    value: v.to_owned(),
+                })
+                .collect::<Vec<_>>(),
+            None => Vec::new(),
+        };
+        KVQPage::from_lookahead(items, page_size)
+    }
 }
 
 impl KVQBinaryStoreWriter for KVQSimpleMemoryBackingStore {
@@ -0,0 +1,181 @@
+use plonky2::hash::hash_types::{HashOut, RichField};
+
+use crate::traits::KVQSerializable;
+
+// a fixed size field of a key deriving KVQSerializable, integers are big endian so keys sort by
+// their fields in declaration order
+pub trait KVQKeyField: Sized {
+    const SIZE: usize;
+    fn write_key_bytes(&self, result: &mut Vec<u8>);
+    // bytes has exactly SIZE bytes
+    fn read_key_bytes(bytes: &[u8]) -> anyhow::Result<Self>;
+}
+
+macro_rules! impl_kvq_key_field_be {
+    ($($typ:ty),+ $(,)?) => {
+        $(
+            impl KVQKeyField for $typ {
+                const SIZE: usize = std::mem::size_of::<$typ>();
+                fn write_key_bytes(&self, result: &mut Vec<u8>) {
+                    result.extend_from_slice(&self.to_be_bytes());
+                }
+                fn read_key_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+                    Ok(<$typ>::from_be_bytes(bytes.try_into()?))
+                }
+            }
+        )+
+    };
+}
+
+impl_kvq_key_field_be!(u8, u16, u32, u64, u128);
+
+impl<const SIZE: usize> KVQKeyField for [u8; SIZE] {
+    const SIZE: usize = SIZE;
+    fn write_key_bytes(&self, result: &mut Vec<u8>) {
+        result.extend_from_slice(self);
+    }
+    fn read_key_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        Ok(bytes.try_into()?)
+    }
+}
+
+// same little endian layout as the KVQSerializable impl
+impl<F: RichField> KVQKeyField for HashOut<F> {
+    const SIZE: usize = 32;
+    fn write_key_bytes(&self, result: &mut Vec<u8>) {
+        for element in self.elements.iter() {
+            result.extend_from_slice(&element.to_canonical_u64().to_le_bytes());
+        }
+    }
+    fn read_key_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        <HashOut<F> as KVQSerializable>::from_bytes(bytes)
+    }
+}
+
+// implemented by keys with #[kvq_table(id = ...)], every key of the table starts with the big
+// endian table id
+pub trait KVQTable {
+    const TABLE_ID: u16;
+    const TABLE_NAME: &'static str;
+    const KEY_SIZE: usize;
+    const INFO: KVQTableInfo = KVQTableInfo {
+        name: Self::TABLE_NAME,
+        id: Self::TABLE_ID,
+        key_size: Self::KEY_SIZE,
+    };
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KVQTableInfo {
+    pub name: &'static str,
+    pub id: u16,
+    pub key_size: usize,
+}
+
+// the first two tables which share an id. table ids are the two byte prefix of every key, so
+// tables with distinct ids never have a key which is a prefix of a key of another table
+pub const fn find_table_collision(tables: &[KVQTableInfo]) -> Option<(usize, usize)> {
+    let mut i = 0;
+    while i < tables.len() {
+        let mut j = i + 1;
+        while j < tables.len() {
+            if tables[i].id == tables[j].id {
+                return Some((i, j));
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    None
+}
+
+pub fn check_table_collisions(tables: &[KVQTableInfo]) -> anyhow::Result<()> {
+    match find_table_collision(tables) {
+        Some((i, j)) => anyhow::bail!(
+            "tables {} and {} share the table id {}",
+            tables[i].name,
+            tables[j].name,
+            tables[i].id
+        ),
+        None => Ok(()),
+    }
+}
+
+// declares a registry of tables and fails to compile if two of them share an id
+#[macro_export]
+macro_rules! kvq_table_registry {
+    ($vis:vis $name:ident = [$($table:ty),+ $(,)?]) => {
+        $vis const $name: &[$crate::table::KVQTableInfo] =
+            &[$(<$table as $crate::table::KVQTable>::INFO),+];
+        const _: () = assert!(
+            $crate::table::find_table_collision($name).is_none(),
+            concat!("two tables in ", stringify!($name), " share a table id")
+        );
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::KVQSerializable;
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, KVQSerializable)]
+    #[kvq_table(id = TABLE_TYPE)]
+    struct TestKey<const TABLE_TYPE: u16, const N: usize> {
+        owner: [u8; N],
+        id: u64,
+        level: u8,
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, KVQSerializable)]
+    #[kvq_table(id = 0x0102)]
+    struct TestTupleKey(u32);
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, KVQSerializable)]
+    struct TestValue {
+        index: u64,
+        checkpoint_id: u64,
+    }
+
+    kvq_table_registry!(TEST_TABLES = [TestKey<3, 4>, TestKey<4, 4>, TestTupleKey]);
+
+    #[test]
+    fn derived_keys_round_trip() {
+        let key = TestKey::<3, 4> {
+            owner: [9, 8, 7, 6],
+            id: 0x0102030405060708,
+            level: 5,
+        };
+        let bytes = key.to_bytes().unwrap();
+        assert_eq!(bytes, vec![0, 3, 9, 8, 7, 6, 1, 2, 3, 4, 5, 6, 7, 8, 5]);
+        assert_eq!(bytes.len(), <TestKey<3, 4> as KVQTable>::KEY_SIZE);
+        assert_eq!(TestKey::<3, 4>::from_bytes(&bytes).unwrap(), key);
+        assert!(TestKey::<3, 4>::from_bytes(&bytes[1..]).is_err());
+        assert!(TestKey::<4, 4>::from_bytes(&bytes).is_err());
+
+        let key = TestTupleKey(0xaabbccdd);
+        let bytes = key.to_bytes().unwrap();
+        assert_eq!(bytes, vec![1, 2, 0xaa, 0xbb, 0xcc, 0xdd]);
+        assert_eq!(TestTupleKey::from_bytes(&bytes).unwrap(), key);
+
+        let value = TestValue {
+            index: 1,
+            checkpoint_id: 2,
+        };
+        let bytes = value.to_bytes().unwrap();
+        assert_eq!(bytes, vec![0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 2]);
+        assert_eq!(TestValue::from_bytes(&bytes).unwrap(), value);
+    }
+
+    #[test]
+    fn table_collisions_are_detected() {
+        check_table_collisions(TEST_TABLES).unwrap();
+        let colliding = [
+            <TestKey<3, 4> as KVQTable>::INFO,
+            <TestTupleKey as KVQTable>::INFO,
+            <TestKey<0x0102, 1> as KVQTable>::INFO,
+        ];
+        assert_eq!(find_table_collision(&colliding), Some((1, 2)));
+        assert!(check_table_collisions(&colliding).is_err());
+    }
+}
@@ -1,3 +1,7 @@
+use std::ops::Bound;
+
+use async_trait::async_trait;
+pub use kvq_derive::KVQSerializable;
 use serde::Deserialize;
 use serde::Serialize;
 
@@ -36,6 +40,88 @@ impl<'de, K: Deserialize<'de>, V: Deserialize<'de>> Deserialize<'de> for KVQPair
     }
 }
 
+/// A half open key range `[start, end)`, the range has no upper bound if `end` is None.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct KVQRange {
+    pub start: Vec<u8>,
+    pub end: Option<Vec<u8>>,
+}
+
+impl KVQRange {
+    pub fn new(start: Vec<u8>, end: Option<Vec<u8>>) -> Self {
+        Self { start, end }
+    }
+
+    // every key which starts with prefix
+    pub fn prefix(prefix: &[u8]) -> Self {
+        let mut end = prefix.to_vec();
+        while let Some(last) = end.pop() {
+            if last != 0xff {
+                end.push(last + 1);
+                return Self::new(prefix.to_vec(), Some(end));
+            }
+        }
+        Self::new(prefix.to_vec(), None)
+    }
+
+    pub fn contains(&self, key: &[u8]) -> bool {
+        key >= self.start.as_slice() && self.end.as_ref().map_or(true, |end| key < end.as_slice())
+    }
+
+    // the bounds of the keys after cursor in this range, None if there are no such keys
+    pub fn bounds_after(&self, cursor: Option<&Vec<u8>>) -> Option<(Bound<&[u8]>, Bound<&[u8]>)> {
+        let lower = match cursor {
+            Some(cursor) if cursor.as_slice() >= self.start.as_slice() => {
+                Bound::Excluded(cursor.as_slice())
+            }
+            _ => Bound::Included(self.start.as_slice()),
+        };
+        let upper = match &self.end {
+            Some(end) => Bound::Excluded(end.as_slice()),
+            None => Bound::Unbounded,
+        };
+        let is_empty = match (lower, upper) {
+            (Bound::Included(l), Bound::Excluded(u)) => l >= u,
+            (Bound::Excluded(l), Bound::Excluded(u)) => l >= u,
+            _ => false,
+        };
+        if is_empty {
+            None
+        } else {
+            Some((lower, upper))
+        }
+    }
+}
+
+/// A page of a range scan in key order. Passing `cursor` back to the scan returns the next page,
+/// it is None once the range is exhausted.
+pub struct KVQPage<K, V> {
+    pub items: Vec<KVQPair<K, V>>,
+    pub cursor: Option<Vec<u8>>,
+}
+
+impl<V> KVQPage<Vec<u8>, V> {
+    // backends read one item more than the page size to know if another page follows
+    pub fn from_lookahead(
+        mut items: Vec<KVQPair<Vec<u8>, V>>,
+        page_size: usize,
+    ) -> anyhow::Result<Self> {
+        if page_size == 0 {
+            anyhow::bail!("page size must be greater than 0");
+        }
+        if items.len() > page_size {
+            items.truncate(page_size);
+            let cursor = items.last().map(|kv| kv.key.clone());
+            Ok(Self { items, cursor })
+        } else {
+            Ok(Self {
+                items,
+                cursor: None,
+            })
+        }
+    }
+}
+
 pub trait KVQSerializable: Clone + PartialEq {
     fn to_bytes(&self) -> anyhow::Result<Vec<u8>>;
     fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self>;
@@ -89,6 +175,26 @@ pub trait KVQStoreAdapterReader<S, K: KVQSerializable, V: KVQSerializable> {
         let results = Self::get_many_leq_kv(s, keys, fuzzy_bytes)?;
         unwrap_kv_vec_result(results)
     }
+
+    // pairs with keys in [start, end)
+    fn get_range_page(
+        s: &S,
+        start: &K,
+        end: &K,
+        cursor: Option<&Vec<u8>>,
+        page_size: usize,
+    ) -> anyhow::Result<KVQPage<K, V>>;
+
+    // for keys ending with a big endian checkpoint id, returns the latest version at or before
+    // checkpoint_id of each key in [start, end), the checkpoint ids of start and end are ignored
+    fn get_range_page_at_checkpoint(
+        s: &S,
+        start: &K,
+        end: &K,
+        checkpoint_id: u64,
+        cursor: Option<&Vec<u8>>,
+        page_size: usize,
+    ) -> anyhow::Result<KVQPage<K, V>>;
 }
 
 pub trait KVQStoreAdapter<S, K: KVQSerializable, V: KVQSerializable>:
@@ -178,6 +284,23 @@ pub trait KVQBinaryStoreReader {
     ) -> anyhow::Result<Vec<KVQPair<Vec<u8>, Vec<u8>>>> {
         unwrap_kv_vec_result(self.get_many_leq_kv(keys, fuzzy_bytes)?)
     }
+
+    // up to page_size pairs of the range with keys after cursor, in key order
+    fn get_range_page(
+        &self,
+        range: &KVQRange,
+        cursor: Option<&Vec<u8>>,
+        page_size: usize,
+    ) -> anyhow::Result<KVQPage<Vec<u8>, Vec<u8>>>;
+
+    fn get_prefix_page(
+        &self,
+        prefix: &[u8],
+        cursor: Option<&Vec<u8>>,
+        page_size: usize,
+    ) -> anyhow::Result<KVQPage<Vec<u8>, Vec<u8>>> {
+        self.get_range_page(&KVQRange::prefix(prefix), cursor, page_size)
+    }
 }
 
 pub trait KVQBinaryStoreWriter {
@@ -197,3 +320,106 @@ pub trait KVQBinaryStoreWriter {
 pub trait KVQBinaryStore: KVQBinaryStoreReader + KVQBinaryStoreWriter {}
 
 impl<T: KVQBinaryStoreReader + KVQBinaryStoreWriter> KVQBinaryStore for T {}
+
+// async variants of the store traits, for callers running inside a tokio runtime which should not
+// block their worker threads on disk reads
+#[async_trait]
+pub trait KVQAsyncBinaryStoreReader: Send + Sync {
+    async fn get_exact_if_exists(&self, key: &Vec<u8>) -> anyhow::Result<Option<Vec<u8>>>;
+    async fn get_exact(&self, key: &Vec<u8>) -> anyhow::Result<Vec<u8>>;
+    async fn get_many_exact(&self, keys: &[Vec<u8>]) -> anyhow::Result<Vec<Vec<u8>>>;
+
+    async fn get_leq(&self, key: &Vec<u8>, fuzzy_bytes: usize) -> anyhow::Result<Option<Vec<u8>>>;
+    async fn get_fuzzy_range_leq_kv(
+        &self,
+        key: &Vec<u8>,
+        fuzzy_bytes: usize,
+    ) -> anyhow::Result<Vec<KVQPair<Vec<u8>, Vec<u8>>>>;
+    async fn get_leq_kv(
+        &self,
+        key: &Vec<u8>,
+        fuzzy_bytes: usize,
+    ) -> anyhow::Result<Option<KVQPair<Vec<u8>, Vec<u8>>>>;
+
+    async fn get_many_leq(
+        &self,
+        keys: &[Vec<u8>],
+        fuzzy_bytes: usize,
+    ) -> anyhow::Result<Vec<Option<Vec<u8>>>>;
+    async fn get_many_leq_kv(
+        &self,
+        keys: &[Vec<u8>],
+        fuzzy_bytes: usize,
+    ) -> anyhow::Result<Vec<Option<KVQPair<Vec<u8>, Vec<u8>>>>>;
+}
+
+#[async_trait]
+pub trait KVQAsyncBinaryStoreWriter: Send + Sync {
+    async fn set(&mut self, key: Vec<u8>, value: Vec<u8>) -> anyhow::Result<()>;
+    async fn set_ref(&mut self, key: &Vec<u8>, value: &Vec<u8>) -> anyhow::Result<()>;
+    async fn set_many_ref<'a>(
+        &mut self,
+        items: &[KVQPair<&'a Vec<u8>, &'a Vec<u8>>],
+    ) -> anyhow::Result<()>;
+    async fn set_many_vec(&mut self, items: Vec<KVQPair<Vec<u8>, Vec<u8>>>) -> anyhow::Result<()>;
+    async fn set_many_split_ref(
+        &mut self,
+        keys: &[Vec<u8>],
+        values: &[Vec<u8>],
+    ) -> anyhow::Result<()>;
+
+    async fn delete(&mut self, key: &Vec<u8>) -> anyhow::Result<bool>;
+    async fn delete_many(&mut self, keys: &[Vec<u8>]) -> anyhow::Result<Vec<bool>>;
+}
+
+pub trait KVQAsyncBinaryStore: KVQAsyncBinaryStoreReader + KVQAsyncBinaryStoreWriter {}
+
+impl<T: KVQAsyncBinaryStoreReader + KVQAsyncBinaryStoreWriter> KVQAsyncBinaryStore for T {}
+
+#[async_trait]
+pub trait KVQAsyncStoreAdapterReader<
+    S: Sync,
+    K: KVQSerializable + Send + Sync,
+    V: KVQSerializable + Send + Sync,
+>
+{
+    async fn get_exact_if_exists(s: &S, key: &K) -> anyhow::Result<Option<V>>;
+    async fn get_exact(s: &S, key: &K) -> anyhow::Result<V>;
+    async fn get_many_exact(s: &S, keys: &[K]) -> anyhow::Result<Vec<V>>;
+
+    async fn get_fuzzy_range_leq_kv(
+        s: &S,
+        key: &K,
+        fuzzy_bytes: usize,
+    ) -> anyhow::Result<Vec<KVQPair<K, V>>>;
+    async fn get_leq(s: &S, key: &K, fuzzy_bytes: usize) -> anyhow::Result<Option<V>>;
+    async fn get_leq_kv(
+        s: &S,
+        key: &K,
+        fuzzy_bytes: usize,
+    ) -> anyhow::Result<Option<KVQPair<K, V>>>;
+
+    async fn get_many_leq(s: &S, keys: &[K], fuzzy_bytes: usize) -> anyhow::Result<Vec<Option<V>>>;
+    async fn get_many_leq_kv(
+        s: &S,
+        keys: &[K],
+        fuzzy_bytes: usize,
+    ) -> anyhow::Result<Vec<Option<KVQPair<K, V>>>>;
+}
+
+#[async_trait]
+pub trait KVQAsyncStoreAdapter<
+    S: Send + Sync,
+    K: KVQSerializable + Send + Sync,
+    V: KVQSerializable + Send + Sync,
+>: KVQAsyncStoreAdapterReader<S, K, V>
+{
+    async fn set(s: &mut S, key: K, value: V) -> anyhow::Result<()>;
+    async fn set_ref(s: &mut S, key: &K, value: &V) -> anyhow::Result<()>;
+    async fn set_many_ref<'a>(s: &mut S, items: &[KVQPair<&'a K, &'a V>]) -> anyhow::Result<()>;
+    async fn set_many_split_ref(s: &mut S, keys: &[K], values: &[V]) -> anyhow::Result<()>;
+    async fn set_many(s: &mut S, items: &[KVQPair<K, V>]) -> anyhow::Result<()>;
+
+    async fn delete(s: &mut S, key: &K) -> anyhow::Result<bool>;
+    async fn delete_many(s: &mut S, keys: &[K]) -> anyhow::Result<Vec<bool>>;
+}
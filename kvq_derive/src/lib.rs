@@ -0,0 +1,150 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    parse::ParseStream, parse_macro_input, Data, DeriveInput, Expr, Fields, Ident, Index, Token,
+};
+
+// #[derive(KVQSerializable)] encodes the fields of a struct in declaration order with
+// kvq::table::KVQKeyField. with #[kvq_table(id = ...)] the key starts with the big endian table id
+// and the struct implements kvq::table::KVQTable, so it can be added to a table registry
+#[proc_macro_derive(KVQSerializable, attributes(kvq_table))]
+pub fn derive_kvq_serializable(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_kvq_serializable(input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+fn parse_table_id(input: &DeriveInput) -> syn::Result<Option<Expr>> {
+    let mut table_id = None;
+    for attr in input.attrs.iter().filter(|a| a.path.is_ident("kvq_table")) {
+        if table_id.is_some() {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "duplicate kvq_table attribute",
+            ));
+        }
+        table_id = Some(attr.parse_args_with(|stream: ParseStream| {
+            let name: Ident = stream.parse()?;
+            if name != "id" {
+                return Err(syn::Error::new_spanned(name, "expected `id = ...`"));
+            }
+            stream.parse::<Token![=]>()?;
+            stream.parse::<Expr>()
+        })?);
+    }
+    Ok(table_id)
+}
+
+fn expand_kvq_serializable(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let name_str = name.to_string();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let table_id = parse_table_id(&input)?;
+
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "KVQSerializable can only be derived for structs",
+            ))
+        }
+    };
+    let types = fields.iter().map(|f| &f.ty).collect::<Vec<_>>();
+    let vars = (0..types.len())
+        .map(|i| format_ident!("field_{}", i))
+        .collect::<Vec<_>>();
+    let accessors = fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| match &f.ident {
+            Some(ident) => quote!(self.#ident),
+            None => {
+                let index = Index::from(i);
+                quote!(self.#index)
+            }
+        })
+        .collect::<Vec<_>>();
+    let construct = match fields {
+        Fields::Named(_) => {
+            let idents = fields.iter().map(|f| &f.ident);
+            quote!(Self { #(#idents: #vars),* })
+        }
+        Fields::Unnamed(_) => quote!(Self(#(#vars),*)),
+        Fields::Unit => quote!(Self),
+    };
+
+    let prefix_size = if table_id.is_some() { 2usize } else { 0usize };
+    let size = quote!(#prefix_size #(+ <#types as ::kvq::table::KVQKeyField>::SIZE)*);
+    let (write_prefix, check_prefix) = if table_id.is_some() {
+        (
+            quote! {
+                result.extend_from_slice(
+                    &<Self as ::kvq::table::KVQTable>::TABLE_ID.to_be_bytes(),
+                );
+            },
+            quote! {
+                let table_id = u16::from_be_bytes([bytes[0], bytes[1]]);
+                if table_id != <Self as ::kvq::table::KVQTable>::TABLE_ID {
+                    ::anyhow::bail!(
+                        "expected table id {} for deserializing {}, got {}",
+                        <Self as ::kvq::table::KVQTable>::TABLE_ID,
+                        #name_str,
+                        table_id
+                    );
+                }
+            },
+        )
+    } else {
+        (quote!(), quote!())
+    };
+
+    let serializable = quote! {
+        impl #impl_generics ::kvq::traits::KVQSerializable for #name #ty_generics #where_clause {
+            fn to_bytes(&self) -> ::anyhow::Result<::std::vec::Vec<u8>> {
+                let mut result = ::std::vec::Vec::with_capacity(#size);
+                #write_prefix
+                #(::kvq::table::KVQKeyField::write_key_bytes(&#accessors, &mut result);)*
+                Ok(result)
+            }
+
+            #[allow(unused_assignments, unused_mut, unused_variables)]
+            fn from_bytes(bytes: &[u8]) -> ::anyhow::Result<Self> {
+                let size = #size;
+                if bytes.len() != size {
+                    ::anyhow::bail!(
+                        "expected {} bytes for deserializing {}, got {} bytes",
+                        size,
+                        #name_str,
+                        bytes.len()
+                    );
+                }
+                #check_prefix
+                let mut offset = #prefix_size;
+                #(
+                    let #vars = <#types as ::kvq::table::KVQKeyField>::read_key_bytes(
+                        &bytes[offset..(offset + <#types as ::kvq::table::KVQKeyField>::SIZE)],
+                    )?;
+                    offset += <#types as ::kvq::table::KVQKeyField>::SIZE;
+                )*
+                Ok(#construct)
+            }
+        }
+    };
+    let table = match table_id {
+        Some(table_id) => quote! {
+            impl #impl_generics ::kvq::table::KVQTable for #name #ty_generics #where_clause {
+                const TABLE_ID: u16 = #table_id;
+                const TABLE_NAME: &'static str = #name_str;
+                const KEY_SIZE: usize = #size;
+            }
+        },
+        None => quote!(),
+    };
+    Ok(quote! {
+        #serializable
+        #table
+    })
+}
@@ -1,8 +1,19 @@
+use std::ops::Bound;
+use std::ops::RangeBounds;
+use std::sync::Arc;
+
+use kvq::adapters::blocking::KVQBlockingPoolStore;
+use kvq::adapters::blocking::KVQBlockingStoreProvider;
 use kvq::traits::KVQBinaryStoreReader;
 use kvq::traits::KVQBinaryStoreWriter;
+use kvq::traits::KVQPage;
 use kvq::traits::KVQPair;
+use kvq::traits::KVQRange;
+use redb::Database;
+use redb::ReadOnlyTable;
 use redb::ReadableTable;
 use redb::Table;
+use redb::TableDefinition;
 
 pub struct KVQReDBStore<T> {
     kv: T,
@@ -157,6 +168,42 @@ where
             })
             .collect::<Result<Vec<_>, _>>()
     }
+
+    fn get_range_page(
+        &self,
+        range: &KVQRange,
+        cursor: Option<&Vec<u8>>,
+        page_size: usize,
+    ) -> anyhow::Result<KVQPage<Vec<u8>, Vec<u8>>> {
+        let items = match range.bounds_after(cursor) {
+            Some((lower, upper)) => self
+                .kv
+                .range(KeyRange(lower, upper))?
+                .take(page_size.saturating_add(1))
+                .map(|x| {
+                    let x = x?;
+                    Ok(KVQPair {
+                        key: x.0.value().to_vec(),
+                        value: x.1.value().to_vec(),
+                    })
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?,
+            None => Vec::new(),
+        };
+        KVQPage::from_lookahead(items, page_size)
+    }
+}
+
+// redb needs a range over &[u8], a tuple of bounds would also be a range over [u8]
+struct KeyRange<'a>(Bound<&'a [u8]>, Bound<&'a [u8]>);
+
+impl<'a> RangeBounds<&'a [u8]> for KeyRange<'a> {
+    fn start_bound(&self) -> Bound<&&'a [u8]> {
+        self.0.as_ref()
+    }
+    fn end_bound(&self) -> Bound<&&'a [u8]> {
+        self.1.as_ref()
+    }
 }
 
 impl<'db, 'txn> KVQBinaryStoreWriter
@@ -216,3 +263,249 @@ impl<'db, 'txn> KVQBinaryStoreWriter
         Ok(())
     }
 }
+
+pub type KVQReDBTableDefinition = TableDefinition<'static, &'static [u8], &'static [u8]>;
+
+/// Opens a fresh transaction on one table for every access, so it can be shared between threads.
+#[derive(Clone)]
+pub struct KVQReDBTableProvider {
+    db: Arc<Database>,
+    table: KVQReDBTableDefinition,
+}
+
+impl KVQReDBTableProvider {
+    pub fn new(db: Arc<Database>, table: KVQReDBTableDefinition) -> Self {
+        Self { db, table }
+    }
+
+    pub fn read<T>(
+        &self,
+        f: impl FnOnce(KVQReDBStore<ReadOnlyTable<&'static [u8], &'static [u8]>>) -> anyhow::Result<T>,
+    ) -> anyhow::Result<T> {
+        let rxn = self.db.begin_read()?;
+        let table = rxn.open_table(self.table)?;
+        f(KVQReDBStore::new(table))
+    }
+
+    // the transaction is only committed if f succeeds
+    pub fn write<T>(
+        &self,
+        f: impl FnOnce(
+            &mut KVQReDBStore<Table<'_, '_, &'static [u8], &'static [u8]>>,
+        ) -> anyhow::Result<T>,
+    ) -> anyhow::Result<T> {
+        let wxn = self.db.begin_write()?;
+        let result = {
+            let table = wxn.open_table(self.table)?;
+            let mut store = KVQReDBStore::new(table);
+            f(&mut store)?
+        };
+        wxn.commit()?;
+        Ok(result)
+    }
+}
+
+impl KVQBlockingStoreProvider for KVQReDBTableProvider {
+    fn with_reader<T>(
+        &self,
+        f: impl FnOnce(&dyn KVQBinaryStoreReader) -> anyhow::Result<T>,
+    ) -> anyhow::Result<T> {
+        self.read(|store| f(&store))
+    }
+
+    fn with_writer<T>(
+        &self,
+        f: impl FnOnce(&mut dyn KVQBinaryStoreWriter) -> anyhow::Result<T>,
+    ) -> anyhow::Result<T> {
+        self.write(|store| f(store))
+    }
+}
+
+pub type KVQReDBBlockingStore = KVQBlockingPoolStore<KVQReDBTableProvider>;
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use kvq::adapters::standard::KVQStandardAdapter;
+    use kvq::memory::simple::KVQSimpleMemoryBackingStore;
+    use kvq::traits::KVQBinaryStore;
+    use kvq::traits::KVQStoreAdapterReader;
+    use redb::backends::InMemoryBackend;
+
+    use super::*;
+
+    const TEST_TABLE: KVQReDBTableDefinition = TableDefinition::new("test");
+
+    // table (1) | id (2) | checkpoint_id (8)
+    fn versioned_key(table: u8, id: u16, checkpoint_id: u64) -> Vec<u8> {
+        let mut key = vec![table];
+        key.extend_from_slice(&id.to_be_bytes());
+        key.extend_from_slice(&checkpoint_id.to_be_bytes());
+        key
+    }
+
+    fn test_pairs() -> BTreeMap<Vec<u8>, Vec<u8>> {
+        let mut pairs = BTreeMap::new();
+        for table in [0u8, 1, 2, 0xff] {
+            for id in 0..20u16 {
+                for checkpoint_id in (id as u64 % 3 + 1)..=4 {
+                    pairs.insert(
+                        versioned_key(table, id, checkpoint_id),
+                        vec![table, id as u8, checkpoint_id as u8],
+                    );
+                }
+            }
+        }
+        pairs
+    }
+
+    fn collect_range<S: KVQBinaryStoreReader>(
+        store: &S,
+        range: &KVQRange,
+        page_size: usize,
+    ) -> Vec<Vec<u8>> {
+        let mut keys = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = store
+                .get_range_page(range, cursor.as_ref(), page_size)
+                .unwrap();
+            assert!(page.items.len() <= page_size);
+            for kv in page.items.iter() {
+                assert!(range.contains(&kv.key));
+                keys.push(kv.key.clone());
+            }
+            match page.cursor {
+                Some(next) => {
+                    assert_eq!(page.items.len(), page_size);
+                    cursor = Some(next);
+                }
+                None => return keys,
+            }
+        }
+    }
+
+    fn check_range_pages<S: KVQBinaryStore>(store: &mut S) {
+        let pairs = test_pairs();
+        for (key, value) in pairs.iter() {
+            store.set_ref(key, value).unwrap();
+        }
+
+        let ranges = [
+            KVQRange::new(vec![], None),
+            KVQRange::prefix(&[1]),
+            KVQRange::prefix(&[0xff]),
+            KVQRange::prefix(&[0, 0, 7]),
+            KVQRange::new(versioned_key(0, 5, 2), Some(versioned_key(2, 3, 0))),
+            KVQRange::new(versioned_key(1, 0, 0), Some(versioned_key(1, 0, 0))),
+            KVQRange::prefix(&[3]),
+        ];
+        for range in ranges.iter() {
+            let expected = pairs
+                .keys()
+                .filter(|key| range.contains(key))
+                .cloned()
+                .collect::<Vec<_>>();
+            for page_size in [1, 3, 7, 1000] {
+                assert_eq!(collect_range(store, range, page_size), expected);
+            }
+        }
+
+        let page = store
+            .get_prefix_page(&[2], Some(&versioned_key(2, 19, 2)), 10)
+            .unwrap();
+        assert_eq!(
+            page.items.iter().map(|kv| &kv.key).collect::<Vec<_>>(),
+            vec![&versioned_key(2, 19, 3), &versioned_key(2, 19, 4)]
+        );
+        assert!(page.cursor.is_none());
+        assert!(store.get_prefix_page(&[2], None, 0).is_err());
+    }
+
+    fn check_checkpoint_pages<S: KVQBinaryStore>(store: &mut S) {
+        for (key, value) in test_pairs().iter() {
+            store.set_ref(key, value).unwrap();
+        }
+
+        for checkpoint_id in 0..=5u64 {
+            let expected = (0..20u16)
+                .filter(|id| *id as u64 % 3 < checkpoint_id)
+                .map(|id| vec![1, id as u8, checkpoint_id.min(4) as u8])
+                .collect::<Vec<_>>();
+            for page_size in [1, 4, 1000] {
+                let mut values = Vec::new();
+                let mut cursor = None;
+                loop {
+                    let page =
+                        KVQStandardAdapter::<S, Vec<u8>, Vec<u8>>::get_range_page_at_checkpoint(
+                            store,
+                            &versioned_key(1, 0, 0),
+                            &versioned_key(2, 0, 0),
+                            checkpoint_id,
+                            cursor.as_ref(),
+                            page_size,
+                        )
+                        .unwrap();
+                    assert!(page.items.len() <= page_size);
+                    values.extend(page.items.into_iter().map(|kv| kv.value));
+                    cursor = page.cursor;
+                    if cursor.is_none() {
+                        break;
+                    }
+                }
+                assert_eq!(values, expected);
+            }
+        }
+    }
+
+    fn test_provider() -> KVQReDBTableProvider {
+        let db = Database::builder()
+            .create_with_backend(InMemoryBackend::new())
+            .unwrap();
+        KVQReDBTableProvider::new(Arc::new(db), TEST_TABLE)
+    }
+
+    #[test]
+    fn memory_store_range_pages() {
+        check_range_pages(&mut KVQSimpleMemoryBackingStore::new());
+        check_checkpoint_pages(&mut KVQSimpleMemoryBackingStore::new());
+    }
+
+    #[test]
+    fn redb_store_range_pages() {
+        test_provider()
+            .write(|store| {
+                check_range_pages(store);
+                Ok(())
+            })
+            .unwrap();
+        test_provider()
+            .write(|store| {
+                check_checkpoint_pages(store);
+                Ok(())
+            })
+            .unwrap();
+
+        // read transactions page through committed data the same way
+        let provider = test_provider();
+        provider
+            .write(|store| {
+                for (key, value) in test_pairs().iter() {
+                    store.set_ref(key, value)?;
+                }
+                Ok(())
+            })
+            .unwrap();
+        let expected = test_pairs()
+            .into_keys()
+            .filter(|key| key[0] == 2)
+            .collect::<Vec<_>>();
+        provider
+            .read(|store| {
+                assert_eq!(collect_range(&store, &KVQRange::prefix(&[2]), 6), expected);
+                Ok(())
+            })
+            .unwrap();
+    }
+}
@@ -0,0 +1,512 @@
+use std::ops::Bound;
+
+use kvq::traits::KVQBinaryStoreReader;
+use kvq::traits::KVQBinaryStoreWriter;
+use kvq::traits::KVQPage;
+use kvq::traits::KVQPair;
+use kvq::traits::KVQRange;
+use r2d2_redis::RedisConnectionManager;
+
+/// A KVQ binary store in redis.
+///
+/// Every key is a member of a sorted set with score 0, which redis orders by the raw bytes of its
+/// members, so fuzzy lookups are ZREVRANGEBYLEX/ZRANGEBYLEX queries. The values are kept in a
+/// hash next to it. Both live under `namespace`, so several stores can share one redis instance.
+#[derive(Clone)]
+pub struct KVQRedisStore {
+    pool: r2d2::Pool<RedisConnectionManager>,
+    keys_key: String,
+    values_key: String,
+}
+
+// an inclusive lexicographic range bound
+fn lex_bound(key: &[u8]) -> Vec<u8> {
+    let mut bound = Vec::with_capacity(key.len() + 1);
+    bound.push(b'[');
+    bound.extend_from_slice(key);
+    bound
+}
+
+fn lex_range_bound(bound: Bound<&[u8]>, unbounded: &[u8]) -> Vec<u8> {
+    match bound {
+        Bound::Included(key) => lex_bound(key),
+        Bound::Excluded(key) => {
+            let mut result = Vec::with_capacity(key.len() + 1);
+            result.push(b'(');
+            result.extend_from_slice(key);
+            result
+        }
+        Bound::Unbounded => unbounded.to_vec(),
+    }
+}
+
+fn fuzzy_base_key(key: &[u8], fuzzy_bytes: usize) -> anyhow::Result<Vec<u8>> {
+    let key_len = key.len();
+    if fuzzy_bytes > key_len {
+        return Err(anyhow::anyhow!(
+            "Fuzzy bytes must be less than or equal to key length"
+        ));
+    }
+    let mut base_key = key.to_vec();
+    for i in 0..fuzzy_bytes {
+        base_key[key_len - i - 1] = 0;
+    }
+    Ok(base_key)
+}
+
+impl KVQRedisStore {
+    pub fn new(uri: &str, namespace: &str) -> anyhow::Result<Self> {
+        let manager = RedisConnectionManager::new(uri)?;
+        let pool = r2d2::Pool::builder().build(manager)?;
+        Ok(Self::from_pool(pool, namespace))
+    }
+
+    pub fn from_pool(pool: r2d2::Pool<RedisConnectionManager>, namespace: &str) -> Self {
+        Self {
+            pool,
+            keys_key: format!("{}:kvq_keys", namespace),
+            values_key: format!("{}:kvq_values", namespace),
+        }
+    }
+
+    pub fn get_connection(&self) -> anyhow::Result<r2d2::PooledConnection<RedisConnectionManager>> {
+        Ok(self.pool.get()?)
+    }
+
+    // removes every key of this store's namespace
+    pub fn clear(&self) -> anyhow::Result<()> {
+        let mut conn = self.get_connection()?;
+        redis::cmd("DEL")
+            .arg(&self.keys_key)
+            .arg(&self.values_key)
+            .query::<()>(&mut *conn)?;
+        Ok(())
+    }
+
+    fn get_values(&self, keys: &[Vec<u8>]) -> anyhow::Result<Vec<Option<Vec<u8>>>> {
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut conn = self.get_connection()?;
+        let mut hmget = redis::cmd("HMGET");
+        hmget.arg(&self.values_key);
+        for key in keys {
+            hmget.arg(key.as_slice());
+        }
+        Ok(hmget.query::<Vec<Option<Vec<u8>>>>(&mut *conn)?)
+    }
+}
+
+impl KVQBinaryStoreReader for KVQRedisStore {
+    fn get_exact(&self, key: &Vec<u8>) -> anyhow::Result<Vec<u8>> {
+        match self.get_exact_if_exists(key)? {
+            Some(v) => Ok(v),
+            None => anyhow::bail!("Key {} not found", hex::encode(key)),
+        }
+    }
+
+    fn get_many_exact(&self, keys: &[Vec<u8>]) -> anyhow::Result<Vec<Vec<u8>>> {
+        self.get_values(keys)?
+            .into_iter()
+            .zip(keys)
+            .map(|(value, key)| {
+                value.ok_or_else(|| anyhow::anyhow!("Key {} not found", hex::encode(key)))
+            })
+            .collect()
+    }
+
+    fn get_leq(&self, key: &Vec<u8>, fuzzy_bytes: usize) -> anyhow::Result<Option<Vec<u8>>> {
+        Ok(self.get_leq_kv(key, fuzzy_bytes)?.map(|kv| kv.value))
+    }
+
+    fn get_leq_kv(
+        &self,
+        key: &Vec<u8>,
+        fuzzy_bytes: usize,
+    ) -> anyhow::Result<Option<KVQPair<Vec<u8>, Vec<u8>>>> {
+        Ok(self
+            .get_many_leq_kv(std::slice::from_ref(key), fuzzy_bytes)?
+            .pop()
+            .flatten())
+    }
+
+    fn get_many_leq(
+        &self,
+        keys: &[Vec<u8>],
+        fuzzy_bytes: usize,
+    ) -> anyhow::Result<Vec<Option<Vec<u8>>>> {
+        Ok(self
+            .get_many_leq_kv(keys, fuzzy_bytes)?
+            .into_iter()
+            .map(|kv| kv.map(|kv| kv.value))
+            .collect())
+    }
+
+    // one round trip finds the largest key in each range, a second one reads their values
+    fn get_many_leq_kv(
+        &self,
+        keys: &[Vec<u8>],
+        fuzzy_bytes: usize,
+    ) -> anyhow::Result<Vec<Option<KVQPair<Vec<u8>, Vec<u8>>>>> {
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut pipe = redis::pipe();
+        for key in keys {
+            pipe.cmd("ZREVRANGEBYLEX")
+                .arg(&self.keys_key)
+                .arg(lex_bound(key))
+                .arg(lex_bound(&fuzzy_base_key(key, fuzzy_bytes)?))
+                .arg("LIMIT")
+                .arg(0)
+                .arg(1);
+        }
+        let found_keys = {
+            let mut conn = self.get_connection()?;
+            pipe.query::<Vec<Vec<Vec<u8>>>>(&mut *conn)?
+                .into_iter()
+                .map(|mut members| members.pop())
+                .collect::<Vec<_>>()
+        };
+
+        let existing_keys = found_keys.iter().flatten().cloned().collect::<Vec<_>>();
+        let mut values = self.get_values(&existing_keys)?.into_iter();
+        found_keys
+            .into_iter()
+            .map(|found_key| match found_key {
+                Some(found_key) => {
+                    let value = values.next().flatten().ok_or_else(|| {
+                        anyhow::anyhow!("Key {} has no value", hex::encode(&found_key))
+                    })?;
+                    Ok(Some(KVQPair {
+                        key: found_key,
+                        value,
+                    }))
+                }
+                None => Ok(None),
+            })
+            .collect()
+    }
+
+    fn get_exact_if_exists(&self, key: &Vec<u8>) -> anyhow::Result<Option<Vec<u8>>> {
+        let mut conn = self.get_connection()?;
+        Ok(redis::cmd("HGET")
+            .arg(&self.values_key)
+            .arg(key.as_slice())
+            .query::<Option<Vec<u8>>>(&mut *conn)?)
+    }
+
+    fn get_fuzzy_range_leq_kv(
+        &self,
+        key: &Vec<u8>,
+        fuzzy_bytes: usize,
+    ) -> anyhow::Result<Vec<KVQPair<Vec<u8>, Vec<u8>>>> {
+        let base_key = fuzzy_base_key(key, fuzzy_bytes)?;
+        let found_keys = {
+            let mut conn = self.get_connection()?;
+            redis::cmd("ZRANGEBYLEX")
+                .arg(&self.keys_key)
+                .arg(lex_bound(&base_key))
+                .arg(lex_bound(key))
+                .query::<Vec<Vec<u8>>>(&mut *conn)?
+        };
+        let values = self.get_many_exact(&found_keys)?;
+        Ok(found_keys
+            .into_iter()
+            .zip(values)
+            .map(|(key, value)| KVQPair { key, value })
+            .collect())
+    }
+
+    fn get_range_page(
+        &self,
+        range: &KVQRange,
+        cursor: Option<&Vec<u8>>,
+        page_size: usize,
+    ) -> anyhow::Result<KVQPage<Vec<u8>, Vec<u8>>> {
+        let Some((lower, upper)) = range.bounds_after(cursor) else {
+            return KVQPage::from_lookahead(Vec::new(), page_size);
+        };
+        let found_keys = {
+            let mut conn = self.get_connection()?;
+            redis::cmd("ZRANGEBYLEX")
+                .arg(&self.keys_key)
+                .arg(lex_range_bound(lower, b"-"))
+                .arg(lex_range_bound(upper, b"+"))
+                .arg("LIMIT")
+                .arg(0)
+                .arg(page_size.saturating_add(1))
+                .query::<Vec<Vec<u8>>>(&mut *conn)?
+        };
+        let values = self.get_many_exact(&found_keys)?;
+        KVQPage::from_lookahead(
+            found_keys
+                .into_iter()
+                .zip(values)
+                .map(|(key, value)| KVQPair { key, value })
+                .collect(),
+            page_size,
+        )
+    }
+}
+
+impl KVQBinaryStoreWriter for KVQRedisStore {
+    fn set(&mut self, key: Vec<u8>, value: Vec<u8>) -> anyhow::Result<()> {
+        self.set_ref(&key, &value)
+    }
+
+    fn set_ref(&mut self, key: &Vec<u8>, value: &Vec<u8>) -> anyhow::Result<()> {
+        self.set_many_ref(&[KVQPair { key, value }])
+    }
+
+    // the sorted set and the hash are updated in a single MULTI/EXEC transaction
+    fn set_many_ref(&mut self, items: &[KVQPair<&'_ Vec<u8>, &'_ Vec<u8>>]) -> anyhow::Result<()> {
+        if items.is_empty() {
+            return Ok(());
+        }
+        let mut zadd = redis::cmd("ZADD");
+        zadd.arg(&self.keys_key);
+        let mut hset = redis::cmd("HSET");
+        hset.arg(&self.values_key);
+        for item in items {
+            zadd.arg(0).arg(item.key.as_slice());
+            hset.arg(item.key.as_slice()).arg(item.value.as_slice());
+        }
+
+        let mut conn = self.get_connection()?;
+        redis::pipe()
+            .atomic()
+            .add_command(zadd)
+            .ignore()
+            .add_command(hset)
+            .ignore()
+            .query::<()>(&mut *conn)?;
+        Ok(())
+    }
+
+    fn set_many_vec(&mut self, items: Vec<KVQPair<Vec<u8>, Vec<u8>>>) -> anyhow::Result<()> {
+        self.set_many_ref(
+            items
+                .iter()
+                .map(|x| KVQPair {
+                    key: &x.key,
+                    value: &x.value,
+                })
+                .collect::<Vec<_>>()
+                .as_slice(),
+        )
+    }
+
+    fn delete(&mut self, key: &Vec<u8>) -> anyhow::Result<bool> {
+        Ok(self.delete_many(std::slice::from_ref(key))?[0])
+    }
+
+    fn delete_many(&mut self, keys: &[Vec<u8>]) -> anyhow::Result<Vec<bool>> {
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        for key in keys {
+            pipe.cmd("HDEL").arg(&self.values_key).arg(key.as_slice());
+        }
+        let mut zrem = redis::cmd("ZREM");
+        zrem.arg(&self.keys_key);
+        for key in keys {
+            zrem.arg(key.as_slice());
+        }
+        pipe.add_command(zrem).ignore();
+
+        let mut conn = self.get_connection()?;
+        let deleted = pipe.query::<Vec<i64>>(&mut *conn)?;
+        Ok(deleted.into_iter().map(|x| x > 0).collect())
+    }
+
+    fn set_many_split_ref(&mut self, keys: &[Vec<u8>], values: &[Vec<u8>]) -> anyhow::Result<()> {
+        if keys.len() != values.len() {
+            return Err(anyhow::anyhow!(
+                "Keys and values must be of the same length"
+            ));
+        }
+        self.set_many_ref(
+            keys.iter()
+                .zip(values)
+                .map(|(key, value)| KVQPair { key, value })
+                .collect::<Vec<_>>()
+                .as_slice(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        net::{TcpListener, TcpStream},
+        process::{Child, Command, Stdio},
+        thread::sleep,
+        time::Duration,
+    };
+
+    use kvq::memory::simple::KVQSimpleMemoryBackingStore;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    // a throwaway redis-server without persistence, killed when dropped
+    struct LocalRedisServer {
+        child: Child,
+        port: u16,
+    }
+
+    impl LocalRedisServer {
+        fn spawn() -> Option<Self> {
+            let port = TcpListener::bind("127.0.0.1:0")
+                .ok()?
+                .local_addr()
+                .ok()?
+                .port();
+            let child = Command::new("redis-server")
+                .args([
+                    "--port",
+                    &port.to_string(),
+                    "--save",
+                    "",
+                    "--appendonly",
+                    "no",
+                ])
+                .stdout(Stdio::null())
+                .spawn()
+                .ok()?;
+            let server = Self { child, port };
+            for _ in 0..50 {
+                if TcpStream::connect(("127.0.0.1", port)).is_ok() {
+                    return Some(server);
+                }
+                sleep(Duration::from_millis(100));
+            }
+            None
+        }
+        fn uri(&self) -> String {
+            format!("redis://127.0.0.1:{}", self.port)
+        }
+    }
+
+    impl Drop for LocalRedisServer {
+        fn drop(&mut self) {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+
+    // the redis tests are ignored by default, run them with `cargo test -- --ignored`
+    fn spawn_store(namespace: &str) -> (LocalRedisServer, KVQRedisStore) {
+        let server = LocalRedisServer::spawn().expect("failed to start redis-server");
+        let store = KVQRedisStore::new(&server.uri(), namespace).unwrap();
+        (server, store)
+    }
+
+    // table (1) | id (1) | checkpoint (2)
+    fn random_key(rng: &mut ChaCha8Rng) -> Vec<u8> {
+        vec![
+            rng.gen_range(0..2),
+            rng.gen_range(0..4),
+            0,
+            rng.gen_range(0..8),
+        ]
+    }
+
+    #[test]
+    #[ignore = "requires redis-server"]
+    fn redis_store_matches_memory_store() {
+        let (_server, mut store) = spawn_store("test");
+        let mut reference = KVQSimpleMemoryBackingStore::new();
+        let mut rng = ChaCha8Rng::seed_from_u64(0x45);
+
+        for _ in 0..8 {
+            let items = (0..rng.gen_range(1..16))
+                .map(|_| KVQPair {
+                    key: random_key(&mut rng),
+                    value: vec![rng.gen(), rng.gen()],
+                })
+                .collect::<Vec<_>>();
+            store
+                .set_many_vec(
+                    items
+                        .iter()
+                        .map(|x| KVQPair {
+                            key: x.key.clone(),
+                            value: x.value.clone(),
+                        })
+                        .collect(),
+                )
+                .unwrap();
+            reference.set_many_vec(items).unwrap();
+
+            let deleted = (0..rng.gen_range(0..4))
+                .map(|_| random_key(&mut rng))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                store.delete_many(&deleted).unwrap(),
+                reference.delete_many(&deleted).unwrap()
+            );
+
+            let queries = (0..32).map(|_| random_key(&mut rng)).collect::<Vec<_>>();
+            for fuzzy_bytes in [0, 1, 2] {
+                let results = store.get_many_leq_kv(&queries, fuzzy_bytes).unwrap();
+                let expected = reference.get_many_leq_kv(&queries, fuzzy_bytes).unwrap();
+                assert_eq!(results.len(), expected.len());
+                for (result, expected) in results.iter().zip(expected.iter()) {
+                    assert_eq!(
+                        result.as_ref().map(|kv| (&kv.key, &kv.value)),
+                        expected.as_ref().map(|kv| (&kv.key, &kv.value))
+                    );
+                }
+                for query in queries.iter() {
+                    assert_eq!(
+                        store.get_leq(query, fuzzy_bytes).unwrap(),
+                        reference.get_leq(query, fuzzy_bytes).unwrap()
+                    );
+                    let range = store.get_fuzzy_range_leq_kv(query, fuzzy_bytes).unwrap();
+                    let expected_range = reference
+                        .get_fuzzy_range_leq_kv(query, fuzzy_bytes)
+                        .unwrap();
+                    assert_eq!(
+                        range
+                            .iter()
+                            .map(|kv| (&kv.key, &kv.value))
+                            .collect::<Vec<_>>(),
+                        expected_range
+                            .iter()
+                            .map(|kv| (&kv.key, &kv.value))
+                            .collect::<Vec<_>>()
+                    );
+                }
+            }
+            for query in queries.iter() {
+                assert_eq!(
+                    store.get_exact_if_exists(query).unwrap(),
+                    reference.get_exact_if_exists(query).unwrap()
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[ignore = "requires redis-server"]
+    fn redis_store_namespaces_are_separate() {
+        let (server, mut store) = spawn_store("a");
+        let mut other = KVQRedisStore::new(&server.uri(), "b").unwrap();
+        store.set(vec![1, 2, 3], vec![4]).unwrap();
+        other.set(vec![1, 2, 2], vec![5]).unwrap();
+
+        assert_eq!(store.get_leq(&vec![1, 2, 3], 1).unwrap(), Some(vec![4]));
+        assert_eq!(other.get_leq(&vec![1, 2, 3], 1).unwrap(), Some(vec![5]));
+        assert!(store.get_exact(&vec![1, 2, 2]).is_err());
+
+        store.clear().unwrap();
+        assert_eq!(store.get_leq(&vec![1, 2, 3], 1).unwrap(), None);
+        assert!(other.delete(&vec![1, 2, 2]).unwrap());
+        assert!(!other.delete(&vec![1, 2, 2]).unwrap());
+    }
+}
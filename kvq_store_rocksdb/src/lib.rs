@@ -1,10 +1,15 @@
+use std::ops::Bound;
 use std::path::Path;
 use std::sync::Arc;
 
 use kvq::traits::KVQBinaryStoreReader;
 use kvq::traits::KVQBinaryStoreWriter;
+use kvq::traits::KVQPage;
 use kvq::traits::KVQPair;
+use kvq::traits::KVQRange;
+use rocksdb::Direction;
 use rocksdb::ErrorKind;
+use rocksdb::IteratorMode;
 use rocksdb::TransactionDB;
 
 #[derive(Clone)]
@@ -168,6 +173,44 @@ impl KVQBinaryStoreReader for KVQRocksDBStore {
             })
             .collect::<Result<Vec<_>, _>>()
     }
+
+    fn get_range_page(
+        &self,
+        range: &KVQRange,
+        cursor: Option<&Vec<u8>>,
+        page_size: usize,
+    ) -> anyhow::Result<KVQPage<Vec<u8>, Vec<u8>>> {
+        let Some((lower, upper)) = range.bounds_after(cursor) else {
+            return KVQPage::from_lookahead(Vec::new(), page_size);
+        };
+        let start = match lower {
+            Bound::Included(key) | Bound::Excluded(key) => key,
+            Bound::Unbounded => &[],
+        };
+        let mut items = Vec::new();
+        for x in self
+            .db
+            .iterator(IteratorMode::From(start, Direction::Forward))
+        {
+            let (key, value) = x?;
+            if lower == Bound::Excluded(key.as_ref()) {
+                continue;
+            }
+            if let Bound::Excluded(end) = upper {
+                if key.as_ref() >= end {
+                    break;
+                }
+            }
+            items.push(KVQPair {
+                key: key.to_vec(),
+                value: value.to_vec(),
+            });
+            if items.len() > page_size {
+                break;
+            }
+        }
+        KVQPage::from_lookahead(items, page_size)
+    }
 }
 
 impl KVQBinaryStoreWriter for KVQRocksDBStore {